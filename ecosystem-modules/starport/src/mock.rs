@@ -132,6 +132,7 @@ impl module_currencies::Config for Runtime {
 	type EVMBridge = ();
 	type SweepOrigin = EnsureSignedBy<One, AccountId>;
 	type OnDust = ();
+	type OnTransfer = ();
 }
 
 pub struct MockCashModule;
@@ -44,14 +44,17 @@ use sp_runtime::{
 		storage_lock::{StorageLock, Time},
 		Duration,
 	},
-	traits::{CheckedDiv, Saturating, Zero},
+	traits::{CheckedDiv, One, Saturating, Zero},
 	transaction_validity::{
 		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
 	},
 	DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
 };
 use sp_std::prelude::*;
-use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, EmergencyShutdown, PriceProvider, Rate};
+use support::{
+	AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, EmergencyShutdown, PayCleanupBounty, Price,
+	PriceProvider, Rate, Ratio,
+};
 
 mod mock;
 mod tests;
@@ -124,6 +127,59 @@ impl<AccountId, BlockNumber> CollateralAuctionItem<AccountId, BlockNumber> {
 	}
 }
 
+/// The way a collateral auction is settled.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum CollateralAuctionSettlement {
+	/// Dealt with the winner bidder.
+	Bid,
+	/// Taken by DEX.
+	DEX,
+	/// Cancelled, the collateral is confiscated to offset the target.
+	Cancelled,
+	/// Ended without bid, and DEX did not take it.
+	Unsold,
+	/// Ended below the reserve price, the collateral is reverted to CDP
+	/// treasury and the bid is refunded.
+	Reverted,
+}
+
+/// Analytics of collateral auction, which is versioned to allow the layout
+/// to evolve without breaking the decoders of external risk models.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum CollateralAuctionAnalytics<BlockNumber> {
+	/// Version 1 analytics at the creation of collateral auction.
+	CreatedV1 {
+		/// Collateral type for sale.
+		currency_id: CurrencyId,
+		/// Collateral amount for sale.
+		amount: Balance,
+		/// Target sales amount.
+		target: Balance,
+		/// The oracle price of collateral in stable currency.
+		oracle_price: Option<Price>,
+	},
+	/// Version 1 analytics at the settlement of collateral auction.
+	SettledV1 {
+		/// Collateral type for sale.
+		currency_id: CurrencyId,
+		/// The settled collateral amount.
+		amount: Balance,
+		/// The stable currency received for the settled collateral.
+		proceeds: Balance,
+		/// How the collateral auction is settled.
+		settlement: CollateralAuctionSettlement,
+		/// The oracle price of collateral in stable currency at creation.
+		creation_oracle_price: Option<Price>,
+		/// The oracle price of collateral in stable currency at settlement.
+		oracle_price: Option<Price>,
+		/// The realized discount of proceeds to the oracle value of the settled
+		/// collateral, `None` if there's no proceeds.
+		realized_discount: Option<Ratio>,
+		/// The number of blocks from creation to settlement.
+		time_to_settle: BlockNumber,
+	},
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -174,12 +230,29 @@ pub mod module {
 		/// Emergency shutdown.
 		type EmergencyShutdown: EmergencyShutdown;
 
+		/// The origin which may update the reserve price ratios.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
 		/// The default parital path list for DEX to directly take auction,
 		/// Note: the path is parital, the whole swap path is collateral currency id concat
 		/// the partial path. And the list is sorted, DEX try to take auction by order.
 		#[pallet::constant]
 		type DefaultSwapParitalPathList: Get<Vec<Vec<CurrencyId>>>;
 
+		/// Pay the bounty to the caller for each closed auction
+		type CleanupBounty: PayCleanupBounty<Self::AccountId>;
+
+		/// The minimum weight that should remain in the block after the ended
+		/// collateral auctions are settled in `on_idle`
+		#[pallet::constant]
+		type MinimumWeightRemainInBlock: Get<Weight>;
+
+		/// The weight reserved in `on_initialize` to settle the ended
+		/// collateral auctions, so that they're settled in full blocks where
+		/// `on_idle` gets no weight. Zero disables the reservation
+		#[pallet::constant]
+		type MinimumSettlementWeight: Get<Weight>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -198,6 +271,13 @@ pub mod module {
 		InvalidBidPrice,
 		/// Invalid input amount
 		InvalidAmount,
+		/// The reserve price ratio must be greater than zero and not greater
+		/// than one
+		InvalidReservePriceRatio,
+		/// None of the auctions has records to close
+		NothingToClose,
+		/// The auction has ended and is waiting to be settled
+		AuctionPendingSettlement,
 	}
 
 	#[pallet::event]
@@ -214,6 +294,23 @@ pub mod module {
 		/// Dex take collateral auction. \[auction_id, collateral_type,
 		/// collateral_amount, turnover\]
 		DEXTakeCollateralAuction(AuctionId, CurrencyId, Balance, Balance),
+		/// Analytics of collateral auction for external risk models.
+		/// \[auction_id, analytics\]
+		CollateralAuctionAnalytics(AuctionId, CollateralAuctionAnalytics<T::BlockNumber>),
+		/// The reserve price ratio of specific collateral type updated.
+		/// \[collateral_type, new_ratio\]
+		ReservePriceRatioUpdated(CurrencyId, Option<Ratio>),
+		/// Collateral auction ended below the reserve price, the collateral is
+		/// reverted to CDP treasury. \[auction_id, collateral_type,
+		/// collateral_amount, bidder, refund_amount\]
+		CollateralAuctionReverted(AuctionId, CurrencyId, Balance, T::AccountId, Balance),
+		/// The refund below the existential deposit of the refund recipient
+		/// is kept by CDP treasury as dust. \[refund_recipient, currency_id,
+		/// amount\]
+		DustCollected(T::AccountId, CurrencyId, Balance),
+		/// The records of settled auctions are closed. \[who, closed_count,
+		/// bounty\]
+		AuctionsClosed(T::AccountId, u32, Balance),
 	}
 
 	/// Mapping from auction id to collateral auction info
@@ -239,11 +336,62 @@ pub mod module {
 	#[pallet::getter(fn total_target_in_auction)]
 	pub type TotalTargetInAuction<T: Config> = StorageValue<_, Balance, ValueQuery>;
 
+	/// The oracle price of collateral in stable currency at the creation of
+	/// active collateral auctions
+	///
+	/// AuctionCreationPrices: map AuctionId => Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn auction_creation_prices)]
+	pub type AuctionCreationPrices<T: Config> = StorageMap<_, Twox64Concat, AuctionId, Price, OptionQuery>;
+
+	/// The minimum acceptable final price of collateral auctions of specific
+	/// collateral type, as a ratio of the oracle price at settlement. The
+	/// auctions ending below it are not settled.
+	///
+	/// ReservePriceRatios: map CurrencyId => Option<Ratio>
+	#[pallet::storage]
+	#[pallet::getter(fn reserve_price_ratios)]
+	pub type ReservePriceRatios<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Ratio, OptionQuery>;
+
+	/// The accumulated refunds of specific currency kept by CDP treasury as
+	/// dust, since they're below the existential deposit of the refund
+	/// recipients.
+	///
+	/// DustCollected: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn dust_collected)]
+	pub type DustCollected<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The collateral auctions ended and waiting to be settled in `on_idle`,
+	/// with the winner of the auction.
+	///
+	/// EndedCollateralAuctions: map AuctionId => Option<Option<(AccountId,
+	/// Balance)>>
+	#[pallet::storage]
+	#[pallet::getter(fn ended_collateral_auctions)]
+	pub type EndedCollateralAuctions<T: Config> =
+		StorageMap<_, Twox64Concat, AuctionId, Option<(T::AccountId, Balance)>, OptionQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Settle the ended collateral auctions with the reserved weight.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			let minimum_settlement_weight = T::MinimumSettlementWeight::get();
+			if minimum_settlement_weight.is_zero() {
+				return Zero::zero();
+			}
+			Self::settle_ended_auctions(minimum_settlement_weight)
+		}
+
+		/// Settle the ended collateral auctions with the weight left in the
+		/// block, the ones exceeding it are settled in the following blocks.
+		fn on_idle(_now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::settle_ended_auctions(remaining_weight.saturating_sub(T::MinimumWeightRemainInBlock::get()))
+		}
+
 		/// Start offchain worker in order to submit unsigned tx to cancel
 		/// active auction after system shutdown.
 		fn offchain_worker(now: T::BlockNumber) {
@@ -285,6 +433,51 @@ pub mod module {
 			Self::deposit_event(Event::CancelAuction(id));
 			Ok(())
 		}
+
+		/// Update the reserve price ratio of specific collateral type, `None`
+		/// means to settle the auctions at any price.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `ratio`: the minimum acceptable final price as a ratio of the
+		///   oracle price
+		#[pallet::weight((T::WeightInfo::set_reserve_price_ratio(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_reserve_price_ratio(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			ratio: Option<Ratio>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if let Some(ratio) = ratio {
+				ensure!(
+					!ratio.is_zero() && ratio <= Ratio::one(),
+					Error::<T>::InvalidReservePriceRatio
+				);
+			}
+			ReservePriceRatios::<T>::mutate_exists(currency_id, |maybe_ratio| *maybe_ratio = ratio);
+			Self::deposit_event(Event::ReservePriceRatioUpdated(currency_id, ratio));
+			Ok(())
+		}
+
+		/// Close the records left by the settled auctions, the caller receives
+		/// `CleanupBounty` from the treasury for each closed auction.
+		///
+		/// The dispatch origin of this call must be `Signed` by the transactor.
+		///
+		/// - `ids`: the ids of the settled auctions
+		#[pallet::weight(T::WeightInfo::close_auctions(ids.len() as u32))]
+		#[transactional]
+		pub fn close_auctions(origin: OriginFor<T>, ids: Vec<AuctionId>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let closed_count = ids.into_iter().filter(|id| Self::close_auction_records(*id)).count() as u32;
+			ensure!(!closed_count.is_zero(), Error::<T>::NothingToClose);
+
+			let bounty = T::CleanupBounty::pay_cleanup_bounty(&who, closed_count);
+			Self::deposit_event(Event::AuctionsClosed(who, closed_count, bounty));
+			Ok(())
+		}
 	}
 
 	#[pallet::validate_unsigned]
@@ -296,6 +489,11 @@ pub mod module {
 					return InvalidTransaction::Call.into();
 				}
 
+				// the ended collateral auction will be settled, shouldn't cancel
+				if EndedCollateralAuctions::<T>::contains_key(auction_id) {
+					return InvalidTransaction::Stale.into();
+				}
+
 				if let Some(collateral_auction) = Self::collateral_auctions(auction_id) {
 					if let Some((_, bid_price)) = Self::get_last_bid(*auction_id) {
 						// if collateral auction is in reverse stage, shouldn't cancel
@@ -321,6 +519,15 @@ pub mod module {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Remove the records left by the auction if it's settled, returns
+	/// whether any record is removed.
+	fn close_auction_records(auction_id: AuctionId) -> bool {
+		if T::Auction::auction_info(auction_id).is_some() || Self::collateral_auctions(auction_id).is_some() {
+			return false;
+		}
+		AuctionCreationPrices::<T>::take(auction_id).is_some()
+	}
+
 	fn get_last_bid(auction_id: AuctionId) -> Option<(T::AccountId, Balance)> {
 		T::Auction::auction_info(auction_id).and_then(|auction_info| auction_info.bid)
 	}
@@ -373,6 +580,15 @@ impl<T: Config> Pallet<T> {
 		while let Some((collateral_auction_id, _)) = iterator.next() {
 			iteration_count += 1;
 
+			// the ended collateral auction will be settled, should skip it.
+			if EndedCollateralAuctions::<T>::contains_key(collateral_auction_id) {
+				if iteration_count == max_iterations {
+					finished = false;
+					break;
+				}
+				continue;
+			}
+
 			if let (Some(collateral_auction), Some((_, last_bid_price))) = (
 				Self::collateral_auctions(collateral_auction_id),
 				Self::get_last_bid(collateral_auction_id),
@@ -436,8 +652,16 @@ impl<T: Config> Pallet<T> {
 		};
 		let refund_collateral_amount = collateral_auction.amount.saturating_sub(confiscate_collateral_amount);
 
+		Self::deposit_settlement_analytics(
+			id,
+			&collateral_auction,
+			confiscate_collateral_amount,
+			Zero::zero(),
+			CollateralAuctionSettlement::Cancelled,
+		);
+
 		// refund remain collateral to refund recipient from CDP treasury
-		T::CDPTreasury::withdraw_collateral(
+		Self::refund_collateral(
 			&collateral_auction.refund_recipient,
 			collateral_auction.currency_id,
 			refund_collateral_amount,
@@ -464,6 +688,35 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Whether `amount` of `currency_id` is too small for `who` to receive,
+	/// that the balance of `who` would be below the existential deposit.
+	fn is_dust(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> bool {
+		T::Currency::total_balance(currency_id, who).saturating_add(amount) < T::Currency::minimum_balance(currency_id)
+	}
+
+	/// Keep `amount` of `currency_id` refunded to `who` in CDP treasury as
+	/// dust.
+	fn collect_dust(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) {
+		DustCollected::<T>::mutate(currency_id, |dust| *dust = dust.saturating_add(amount));
+		Self::deposit_event(Event::DustCollected(who.clone(), currency_id, amount));
+	}
+
+	/// Refund `amount` collateral to the refund recipient from CDP treasury,
+	/// the collateral is kept by CDP treasury as dust if the refund recipient
+	/// can't receive it.
+	fn refund_collateral(refund_recipient: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+
+		if Self::is_dust(refund_recipient, currency_id, amount) {
+			Self::collect_dust(refund_recipient, currency_id, amount);
+			Ok(())
+		} else {
+			T::CDPTreasury::withdraw_collateral(refund_recipient, currency_id, amount)
+		}
+	}
+
 	/// Return `true` if price increment rate is greater than or equal to
 	/// minimum.
 	///
@@ -562,8 +815,8 @@ impl<T: Config> Pallet<T> {
 					let refund_collateral_amount = collateral_auction.amount.saturating_sub(new_collateral_amount);
 
 					if !refund_collateral_amount.is_zero() {
-						T::CDPTreasury::withdraw_collateral(
-							&(collateral_auction.refund_recipient),
+						Self::refund_collateral(
+							&collateral_auction.refund_recipient,
 							collateral_auction.currency_id,
 							refund_collateral_amount,
 						)?;
@@ -583,6 +836,47 @@ impl<T: Config> Pallet<T> {
 		)
 	}
 
+	/// Settle the ended collateral auctions within `available_weight`, return
+	/// the weight used. The auctions exceeding it are kept in
+	/// `EndedCollateralAuctions` for the following blocks.
+	pub fn settle_ended_auctions(available_weight: Weight) -> Weight {
+		let settle_weight = T::WeightInfo::settle_collateral_auction();
+		let mut weight_used: Weight = T::DbWeight::get().reads(1);
+		if weight_used.saturating_add(settle_weight) > available_weight {
+			return Zero::zero();
+		}
+
+		// collect the ended auctions first, they're removed by the settlements
+		let mut ended_auctions: Vec<(AuctionId, Option<(T::AccountId, Balance)>)> = vec![];
+		for (id, winner) in EndedCollateralAuctions::<T>::iter() {
+			if weight_used.saturating_add(settle_weight) > available_weight {
+				break;
+			}
+			weight_used = weight_used.saturating_add(settle_weight);
+			ended_auctions.push((id, winner));
+		}
+
+		for (id, winner) in ended_auctions {
+			Self::settle_ended_auction(id, winner);
+		}
+		weight_used
+	}
+
+	fn settle_ended_auction(id: AuctionId, winner: Option<(T::AccountId, Balance)>) {
+		EndedCollateralAuctions::<T>::remove(id);
+		if let Some(collateral_auction) = <CollateralAuctions<T>>::take(id) {
+			Self::collateral_auction_end_handler(id, collateral_auction, winner.clone());
+		} else {
+			// the settlement analytics which takes the creation price isn't deposited
+			AuctionCreationPrices::<T>::remove(id);
+		}
+
+		if let Some((bidder, _)) = &winner {
+			// decrease account ref of winner
+			frame_system::Pallet::<T>::dec_consumers(bidder);
+		}
+	}
+
 	fn collateral_auction_end_handler(
 		auction_id: AuctionId,
 		collateral_auction: CollateralAuctionItem<T::AccountId, T::BlockNumber>,
@@ -594,6 +888,7 @@ impl<T: Config> Pallet<T> {
 			(None, Zero::zero())
 		};
 		let mut should_deal = maybe_bidder.is_some();
+		let mut taken_by_dex = false;
 
 		// if bid_price doesn't reach target, DEX will try trading with DEX to get better result.
 		if !collateral_auction.in_reverse_stage(bid_price) {
@@ -622,6 +917,7 @@ impl<T: Config> Pallet<T> {
 						) {
 							// swap successfully, will not deal.
 							should_deal = false;
+							taken_by_dex = true;
 
 							// refund stable currency to the last bidder, it shouldn't fail and affect the
 							// process. but even it failed, just the winner did not get the bid price. it
@@ -648,11 +944,25 @@ impl<T: Config> Pallet<T> {
 								// it shouldn't fail and affect the process.
 								// but even it failed, just the winner did not get the refund amount. it can be
 								// fixed by treasury council.
-								let res = T::CDPTreasury::issue_debit(
+								let res = if Self::is_dust(
 									&collateral_auction.refund_recipient,
+									T::GetStableCurrencyId::get(),
 									refund_amount,
-									false,
-								);
+								) {
+									// the extra stable currency is kept in the surplus pool
+									Self::collect_dust(
+										&collateral_auction.refund_recipient,
+										T::GetStableCurrencyId::get(),
+										refund_amount,
+									);
+									Ok(())
+								} else {
+									T::CDPTreasury::issue_debit(
+										&collateral_auction.refund_recipient,
+										refund_amount,
+										false,
+									)
+								};
 								if let Err(e) = res {
 									log::warn!(
 										target: "auction-manager",
@@ -664,6 +974,13 @@ impl<T: Config> Pallet<T> {
 								}
 							}
 
+							Self::deposit_settlement_analytics(
+								auction_id,
+								&collateral_auction,
+								collateral_auction.amount,
+								stable_amount,
+								CollateralAuctionSettlement::DEX,
+							);
 							Self::deposit_event(Event::DEXTakeCollateralAuction(
 								auction_id,
 								collateral_auction.currency_id,
@@ -681,30 +998,80 @@ impl<T: Config> Pallet<T> {
 
 		if should_deal {
 			let bidder = maybe_bidder.expect("guaranteed by previous check");
+			let payment_amount = collateral_auction.payment_amount(bid_price);
+
+			if Self::below_reserve_price(&collateral_auction, payment_amount) {
+				// the collateral is kept by CDP treasury for an alternative route, refund the
+				// payment to the bidder. it shouldn't fail and affect the process. but even it
+				// failed, just the bidder did not get the refund. it can be fixed by treasury
+				// council.
+				let res = T::CDPTreasury::issue_debit(&bidder, payment_amount, false);
+				if let Err(e) = res {
+					log::warn!(
+						target: "auction-manager",
+						"issue_debit: failed to issue stable {:?} to {:?}: {:?}. \
+						This is unexpected but should be safe",
+						payment_amount, bidder, e
+					);
+					debug_assert!(false);
+				}
 
-			// transfer collateral to winner from CDP treasury, it shouldn't fail and affect
-			// the process. but even it failed, just the winner did not get the amount. it
-			// can be fixed by treasury council.
-			let res =
-				T::CDPTreasury::withdraw_collateral(&bidder, collateral_auction.currency_id, collateral_auction.amount);
-			if let Err(e) = res {
-				log::warn!(
-					target: "auction-manager",
-					"withdraw_collateral: failed to withdraw {:?} {:?} from CDP treasury to {:?}: {:?}. \
-					This is unexpected but should be safe",
-					collateral_auction.amount, collateral_auction.currency_id, bidder, e
+				Self::deposit_settlement_analytics(
+					auction_id,
+					&collateral_auction,
+					Zero::zero(),
+					Zero::zero(),
+					CollateralAuctionSettlement::Reverted,
 				);
-				debug_assert!(false);
-			}
+				Self::deposit_event(Event::CollateralAuctionReverted(
+					auction_id,
+					collateral_auction.currency_id,
+					collateral_auction.amount,
+					bidder,
+					payment_amount,
+				));
+			} else {
+				// transfer collateral to winner from CDP treasury, it shouldn't fail and affect
+				// the process. but even it failed, just the winner did not get the amount. it
+				// can be fixed by treasury council.
+				let res = T::CDPTreasury::withdraw_collateral(
+					&bidder,
+					collateral_auction.currency_id,
+					collateral_auction.amount,
+				);
+				if let Err(e) = res {
+					log::warn!(
+						target: "auction-manager",
+						"withdraw_collateral: failed to withdraw {:?} {:?} from CDP treasury to {:?}: {:?}. \
+						This is unexpected but should be safe",
+						collateral_auction.amount, collateral_auction.currency_id, bidder, e
+					);
+					debug_assert!(false);
+				}
 
-			let payment_amount = collateral_auction.payment_amount(bid_price);
-			Self::deposit_event(Event::CollateralAuctionDealt(
+				Self::deposit_settlement_analytics(
+					auction_id,
+					&collateral_auction,
+					collateral_auction.amount,
+					payment_amount,
+					CollateralAuctionSettlement::Bid,
+				);
+				Self::deposit_event(Event::CollateralAuctionDealt(
+					auction_id,
+					collateral_auction.currency_id,
+					collateral_auction.amount,
+					bidder,
+					payment_amount,
+				));
+			}
+		} else if !taken_by_dex {
+			Self::deposit_settlement_analytics(
 				auction_id,
-				collateral_auction.currency_id,
-				collateral_auction.amount,
-				bidder,
-				payment_amount,
-			));
+				&collateral_auction,
+				Zero::zero(),
+				Zero::zero(),
+				CollateralAuctionSettlement::Unsold,
+			);
 		}
 
 		// decrement recipient account reference
@@ -717,6 +1084,59 @@ impl<T: Config> Pallet<T> {
 		TotalTargetInAuction::<T>::mutate(|balance| *balance = balance.saturating_sub(collateral_auction.target));
 	}
 
+	/// Return whether the final price of `payment_amount` for the collateral
+	/// of `collateral_auction` is below its reserve price. The reserve price
+	/// isn't enforced if the oracle price is unavailable.
+	fn below_reserve_price(
+		collateral_auction: &CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+		payment_amount: Balance,
+	) -> bool {
+		Self::reserve_price_ratios(collateral_auction.currency_id)
+			.and_then(|ratio| {
+				T::PriceSource::get_relative_price(collateral_auction.currency_id, T::GetStableCurrencyId::get())
+					.map(|price| price.saturating_mul(ratio))
+			})
+			.map_or(false, |reserve_price| {
+				reserve_price.saturating_mul_int(collateral_auction.amount) > payment_amount
+			})
+	}
+
+	/// Realize the surplus of the proceeds and deposit the settlement
+	/// analytics of collateral auction, the realized discount is `1 - proceeds
+	/// / (amount * oracle_price)`.
+	fn deposit_settlement_analytics(
+		auction_id: AuctionId,
+		collateral_auction: &CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+		amount: Balance,
+		proceeds: Balance,
+		settlement: CollateralAuctionSettlement,
+	) {
+		T::CDPTreasury::on_collateral_proceeds(collateral_auction.currency_id, collateral_auction.target, proceeds);
+
+		let creation_oracle_price = AuctionCreationPrices::<T>::take(auction_id);
+		let oracle_price =
+			T::PriceSource::get_relative_price(collateral_auction.currency_id, T::GetStableCurrencyId::get());
+		let realized_discount = oracle_price
+			.filter(|_| !proceeds.is_zero())
+			.and_then(|price| Ratio::checked_from_rational(proceeds, price.saturating_mul_int(amount)))
+			.map(|ratio| Ratio::one().saturating_sub(ratio));
+		let time_to_settle = <frame_system::Pallet<T>>::block_number().saturating_sub(collateral_auction.start_time);
+
+		Self::deposit_event(Event::CollateralAuctionAnalytics(
+			auction_id,
+			CollateralAuctionAnalytics::SettledV1 {
+				currency_id: collateral_auction.currency_id,
+				amount,
+				proceeds,
+				settlement,
+				creation_oracle_price,
+				oracle_price,
+				realized_discount,
+				time_to_settle,
+			},
+		));
+	}
+
 	/// increment `new_bidder` reference and decrement `last_bidder`
 	/// reference if any
 	fn swap_bidders(new_bidder: &T::AccountId, last_bidder: Option<&T::AccountId>) {
@@ -759,15 +1179,10 @@ impl<T: Config> AuctionHandler<T::AccountId, Balance, T::BlockNumber, AuctionId>
 		}
 	}
 
+	/// Queue the ended auction to be settled in `on_idle`, so that the
+	/// auctions ending in the same block can't overweight it.
 	fn on_auction_ended(id: AuctionId, winner: Option<(T::AccountId, Balance)>) {
-		if let Some(collateral_auction) = <CollateralAuctions<T>>::take(id) {
-			Self::collateral_auction_end_handler(id, collateral_auction, winner.clone());
-		}
-
-		if let Some((bidder, _)) = &winner {
-			// decrease account ref of winner
-			frame_system::Pallet::<T>::dec_consumers(bidder);
-		}
+		EndedCollateralAuctions::<T>::insert(id, winner);
 	}
 }
 
@@ -826,11 +1241,28 @@ impl<T: Config> AuctionManager<T::AccountId> for Pallet<T> {
 			);
 		}
 
+		let oracle_price = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get());
+		if let Some(price) = oracle_price {
+			AuctionCreationPrices::<T>::insert(auction_id, price);
+		}
+		Self::deposit_event(Event::CollateralAuctionAnalytics(
+			auction_id,
+			CollateralAuctionAnalytics::CreatedV1 {
+				currency_id,
+				amount,
+				target,
+				oracle_price,
+			},
+		));
 		Self::deposit_event(Event::NewCollateralAuction(auction_id, currency_id, amount, target));
 		Ok(())
 	}
 
 	fn cancel_auction(id: Self::AuctionId) -> DispatchResult {
+		ensure!(
+			!EndedCollateralAuctions::<T>::contains_key(id),
+			Error::<T>::AuctionPendingSettlement
+		);
 		let collateral_auction = <CollateralAuctions<T>>::take(id).ok_or(Error::<T>::AuctionNotExists)?;
 		Self::cancel_collateral_auction(id, collateral_auction)?;
 		T::Auction::remove_auction(id);
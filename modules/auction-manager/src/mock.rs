@@ -36,6 +36,7 @@ use sp_runtime::{
 };
 use sp_std::cell::RefCell;
 pub use support::Price;
+use support::TreasuryCleanupBounty;
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -45,6 +46,7 @@ pub type Amount = i64;
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const CAROL: AccountId = 3;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
 pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
 pub const BTC: CurrencyId = CurrencyId::Token(TokenSymbol::RENBTC);
 pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
@@ -83,9 +85,16 @@ impl frame_system::Config for Runtime {
 	type OnSetCode = ();
 }
 
+thread_local! {
+	pub static BTC_EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+}
+
 parameter_type_with_key! {
-	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
-		Default::default()
+	pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
+		match *currency_id {
+			BTC => BTC_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow()),
+			_ => Default::default(),
+		}
 	};
 }
 
@@ -116,20 +125,46 @@ ord_parameter_types! {
 parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 10;
+	pub const OffsetPeriod: BlockNumber = 1;
 	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"aca/cdpt");
 	pub TreasuryAccount: AccountId = PalletId(*b"aca/hztr").into_account();
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![];
 }
 
 impl cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Tokens;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = AuctionManagerModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DEXModule;
+	type Swap = ();
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = TreasuryAccount;
+	type PriceSource = MockPriceSource;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = ();
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = ();
+	type MinimumIdleWorkWeight = ();
+	type EmergencyShutdown = MockEmergencyShutdown;
 	type WeightInfo = ();
 }
 
@@ -157,6 +192,7 @@ parameter_types! {
 	pub const DEXPalletId: PalletId = PalletId(*b"aca/dexm");
 	pub const GetExchangeFee: (u32, u32) = (0, 100);
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 10;
 	pub EnabledTradingPairs: Vec<TradingPair> = vec![
 		TradingPair::from_currency_ids(AUSD, BTC).unwrap(),
 		TradingPair::from_currency_ids(DOT, BTC).unwrap(),
@@ -169,11 +205,15 @@ impl module_dex::Config for Runtime {
 	type Currency = Tokens;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = ();
 	type DEXIncentives = ();
 	type WeightInfo = ();
 	type ListingOrigin = EnsureSignedBy<One, AccountId>;
+	type StableAsset = ();
+	type ProtocolFeeReceiver = TreasuryAccount;
+	type OnProtocolFee = ();
 }
 
 thread_local! {
@@ -196,6 +236,9 @@ parameter_types! {
 	pub const AuctionTimeToClose: u64 = 100;
 	pub const AuctionDurationSoftCap: u64 = 2000;
 	pub const UnsignedPriority: u64 = 1 << 20;
+	pub const CleanupBounty: Balance = 10;
+	pub const MinimumWeightRemainInBlock: Weight = 0;
+	pub static MinimumSettlementWeight: Weight = 0;
 	pub DefaultSwapParitalPathList: Vec<Vec<CurrencyId>> = vec![
 		vec![AUSD],
 		vec![DOT, AUSD],
@@ -215,7 +258,11 @@ impl Config for Runtime {
 	type PriceSource = MockPriceSource;
 	type UnsignedPriority = UnsignedPriority;
 	type EmergencyShutdown = MockEmergencyShutdown;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type CleanupBounty = TreasuryCleanupBounty<Tokens, GetNativeCurrencyId, TreasuryAccount, CleanupBounty>;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumSettlementWeight = MinimumSettlementWeight;
 	type WeightInfo = ();
 }
 
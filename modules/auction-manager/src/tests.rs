@@ -25,7 +25,7 @@ use frame_support::{assert_noop, assert_ok};
 use mock::{Call as MockCall, Event, *};
 use sp_core::offchain::{testing, DbExternalities, OffchainDbExt, OffchainWorkerExt, StorageKind, TransactionPoolExt};
 use sp_io::offchain;
-use sp_runtime::traits::One;
+use sp_runtime::traits::{BadOrigin, One};
 
 fn run_to_block_offchain(n: u64) {
 	while System::block_number() < n {
@@ -201,6 +201,7 @@ fn collateral_auction_end_handler_without_bid() {
 
 		assert!(AuctionManagerModule::collateral_auctions(0).is_some());
 		AuctionManagerModule::on_auction_ended(0, None);
+		AuctionManagerModule::on_idle(1, u64::MAX);
 		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
 			0, BTC, 100, 500,
 		)));
@@ -252,6 +253,7 @@ fn collateral_auction_end_handler_without_bid_and_swap_by_alternative_path() {
 		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1000);
 
 		AuctionManagerModule::on_auction_ended(0, None);
+		AuctionManagerModule::on_idle(1, u64::MAX);
 		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
 			0, BTC, 100, 333,
 		)));
@@ -282,6 +284,7 @@ fn collateral_auction_end_handler_in_reverse_stage() {
 
 		assert!(AuctionManagerModule::collateral_auctions(0).is_some());
 		AuctionManagerModule::on_auction_ended(0, Some((BOB, 400)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
 		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionDealt(
 			0, BTC, 50, BOB, 200,
 		)));
@@ -301,6 +304,102 @@ fn collateral_auction_end_handler_in_reverse_stage() {
 	});
 }
 
+#[test]
+fn refund_dust_collected_by_cdp_treasury() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		BTC_EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 100);
+		let refund_recipient: AccountId = 4;
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(
+			&refund_recipient,
+			BTC,
+			100,
+			200
+		));
+
+		// the refund in reverse stage is below the existential deposit
+		assert!(AuctionManagerModule::collateral_auction_bid_handler(2, 0, (BOB, 400), None).is_ok());
+		System::assert_has_event(Event::AuctionManagerModule(crate::Event::DustCollected(
+			refund_recipient,
+			BTC,
+			50,
+		)));
+		assert_eq!(AuctionManagerModule::dust_collected(BTC), 50);
+		assert_eq!(Tokens::free_balance(BTC, &refund_recipient), 0);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 50);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 200);
+	});
+}
+
+#[test]
+fn ended_collateral_auctions_settled_on_idle_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 20));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100));
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 20);
+
+		// the ended auctions are queued to be settled
+		AuctionManagerModule::on_auction_ended(0, None);
+		AuctionManagerModule::on_auction_ended(1, None);
+		assert_eq!(AuctionManagerModule::ended_collateral_auctions(0), Some(None));
+		assert_eq!(AuctionManagerModule::ended_collateral_auctions(1), Some(None));
+		assert!(AuctionManagerModule::collateral_auctions(0).is_some());
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 20);
+
+		// the ended auctions can't be cancelled
+		mock_shutdown();
+		assert_noop!(
+			AuctionManagerModule::cancel(Origin::none(), 0),
+			Error::<Runtime>::AuctionPendingSettlement
+		);
+
+		// one auction is settled with the weight for it, the other one is carried over
+		let settle_weight = <() as WeightInfo>::settle_collateral_auction();
+		assert_eq!(AuctionManagerModule::on_idle(1, settle_weight), settle_weight);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 10);
+		assert_eq!(EndedCollateralAuctions::<Runtime>::iter().count(), 1);
+
+		// nothing is settled without enough weight
+		assert_eq!(AuctionManagerModule::on_idle(2, settle_weight - 1), 0);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 10);
+
+		assert_eq!(AuctionManagerModule::on_idle(3, settle_weight), settle_weight);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 0);
+		assert_eq!(EndedCollateralAuctions::<Runtime>::iter().count(), 0);
+		assert_eq!(AuctionManagerModule::collateral_auctions(0), None);
+		assert_eq!(AuctionManagerModule::collateral_auctions(1), None);
+	});
+}
+
+#[test]
+fn ended_collateral_auctions_settled_on_initialize_with_reserved_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 20));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100));
+		AuctionManagerModule::on_auction_ended(0, None);
+		AuctionManagerModule::on_auction_ended(1, None);
+
+		// nothing is reserved by default
+		assert_eq!(AuctionManagerModule::on_initialize(2), 0);
+		assert_eq!(EndedCollateralAuctions::<Runtime>::iter().count(), 2);
+
+		// the reserved weight settles the ended auctions in full blocks
+		let settle_weight = <() as WeightInfo>::settle_collateral_auction();
+		MinimumSettlementWeight::set(settle_weight);
+		assert_eq!(AuctionManagerModule::on_initialize(3), settle_weight);
+		assert_eq!(EndedCollateralAuctions::<Runtime>::iter().count(), 1);
+		assert_eq!(AuctionManagerModule::on_initialize(4), settle_weight);
+		assert_eq!(EndedCollateralAuctions::<Runtime>::iter().count(), 0);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 0);
+	});
+}
+
 #[test]
 fn collateral_auction_end_handler_by_dealing_which_target_not_zero() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -320,6 +419,7 @@ fn collateral_auction_end_handler_by_dealing_which_target_not_zero() {
 
 		assert!(AuctionManagerModule::collateral_auctions(0).is_some());
 		AuctionManagerModule::on_auction_ended(0, Some((BOB, 100)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
 		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionDealt(
 			0, BTC, 100, BOB, 100,
 		)));
@@ -338,6 +438,195 @@ fn collateral_auction_end_handler_by_dealing_which_target_not_zero() {
 	});
 }
 
+#[test]
+fn collateral_auction_analytics_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_integer(2)));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200));
+		System::assert_has_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAnalytics(
+			0,
+			CollateralAuctionAnalytics::CreatedV1 {
+				currency_id: BTC,
+				amount: 100,
+				target: 200,
+				oracle_price: Some(Price::saturating_from_integer(2)),
+			},
+		)));
+		assert_eq!(
+			AuctionManagerModule::auction_creation_prices(0),
+			Some(Price::saturating_from_integer(2))
+		);
+
+		assert!(AuctionManagerModule::collateral_auction_bid_handler(1, 0, (BOB, 150), None).is_ok());
+		System::set_block_number(5);
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(3, 2)));
+		AuctionManagerModule::on_auction_ended(0, Some((BOB, 150)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
+		System::assert_has_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAnalytics(
+			0,
+			CollateralAuctionAnalytics::SettledV1 {
+				currency_id: BTC,
+				amount: 100,
+				proceeds: 150,
+				settlement: CollateralAuctionSettlement::Bid,
+				creation_oracle_price: Some(Price::saturating_from_integer(2)),
+				oracle_price: Some(Price::saturating_from_rational(3, 2)),
+				realized_discount: Some(Ratio::zero()),
+				time_to_settle: 4,
+			},
+		)));
+		assert_eq!(AuctionManagerModule::auction_creation_prices(0), None);
+
+		// realized discount to the oracle value of collateral
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200));
+		assert!(AuctionManagerModule::collateral_auction_bid_handler(5, 1, (BOB, 120), None).is_ok());
+		AuctionManagerModule::on_auction_ended(1, Some((BOB, 120)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
+		System::assert_has_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAnalytics(
+			1,
+			CollateralAuctionAnalytics::SettledV1 {
+				currency_id: BTC,
+				amount: 100,
+				proceeds: 120,
+				settlement: CollateralAuctionSettlement::Bid,
+				creation_oracle_price: Some(Price::saturating_from_rational(3, 2)),
+				oracle_price: Some(Price::saturating_from_rational(3, 2)),
+				realized_discount: Some(Ratio::saturating_from_rational(20, 100)),
+				time_to_settle: 0,
+			},
+		)));
+	});
+}
+
+#[test]
+fn set_reserve_price_ratio_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			AuctionManagerModule::set_reserve_price_ratio(
+				Origin::signed(5),
+				BTC,
+				Some(Ratio::saturating_from_rational(80, 100))
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			AuctionManagerModule::set_reserve_price_ratio(Origin::signed(1), BTC, Some(Ratio::zero())),
+			Error::<Runtime>::InvalidReservePriceRatio
+		);
+		assert_noop!(
+			AuctionManagerModule::set_reserve_price_ratio(
+				Origin::signed(1),
+				BTC,
+				Some(Ratio::saturating_from_rational(110, 100))
+			),
+			Error::<Runtime>::InvalidReservePriceRatio
+		);
+
+		assert_ok!(AuctionManagerModule::set_reserve_price_ratio(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(80, 100))
+		));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::ReservePriceRatioUpdated(
+			BTC,
+			Some(Ratio::saturating_from_rational(80, 100)),
+		)));
+		assert_eq!(
+			AuctionManagerModule::reserve_price_ratios(BTC),
+			Some(Ratio::saturating_from_rational(80, 100))
+		);
+
+		assert_ok!(AuctionManagerModule::set_reserve_price_ratio(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		assert_eq!(AuctionManagerModule::reserve_price_ratios(BTC), None);
+	});
+}
+
+#[test]
+fn close_auctions_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MockPriceSource::set_relative_price(Some(Price::one()));
+		assert_ok!(Tokens::deposit(ACA, &TreasuryAccount::get(), 1000));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 10, 100));
+		AuctionCreationPrices::<Runtime>::insert(5, Price::one());
+
+		// the active auction and the unknown auction have nothing to close
+		assert_noop!(
+			AuctionManagerModule::close_auctions(Origin::signed(BOB), vec![0, 6]),
+			Error::<Runtime>::NothingToClose
+		);
+
+		assert_ok!(AuctionManagerModule::close_auctions(Origin::signed(BOB), vec![0, 5, 5]));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::AuctionsClosed(BOB, 1, 10)));
+		assert_eq!(AuctionManagerModule::auction_creation_prices(5), None);
+		assert_eq!(AuctionManagerModule::auction_creation_prices(0), Some(Price::one()));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 10);
+		assert_eq!(Tokens::free_balance(ACA, &TreasuryAccount::get()), 990);
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_below_reserve_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_integer(2)));
+		assert_ok!(AuctionManagerModule::set_reserve_price_ratio(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(80, 100))
+		));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&CAROL, BTC, 100));
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200));
+		assert!(AuctionManagerModule::collateral_auction_bid_handler(1, 0, (BOB, 150), None).is_ok());
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 850);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 150);
+
+		// the final price 1.5 is below the reserve price 2 * 80%
+		AuctionManagerModule::on_auction_ended(0, Some((BOB, 150)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
+		System::assert_has_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAnalytics(
+			0,
+			CollateralAuctionAnalytics::SettledV1 {
+				currency_id: BTC,
+				amount: 0,
+				proceeds: 0,
+				settlement: CollateralAuctionSettlement::Reverted,
+				creation_oracle_price: Some(Price::saturating_from_integer(2)),
+				oracle_price: Some(Price::saturating_from_integer(2)),
+				realized_discount: None,
+				time_to_settle: 0,
+			},
+		)));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionReverted(
+			0, BTC, 100, BOB, 150,
+		)));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(BTC, &BOB), 1000);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 150);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(BTC), 0);
+		assert_eq!(AuctionManagerModule::total_target_in_auction(), 0);
+
+		// settled at the reserve price
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, BTC, 100, 200));
+		assert!(AuctionManagerModule::collateral_auction_bid_handler(1, 1, (BOB, 160), None).is_ok());
+		AuctionManagerModule::on_auction_ended(1, Some((BOB, 160)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionDealt(
+			1, BTC, 100, BOB, 160,
+		)));
+		assert_eq!(Tokens::free_balance(BTC, &BOB), 1100);
+	});
+}
+
 #[test]
 fn collateral_auction_end_handler_by_dex_which_target_not_zero() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -370,6 +659,7 @@ fn collateral_auction_end_handler_by_dex_which_target_not_zero() {
 
 		assert!(AuctionManagerModule::collateral_auctions(0).is_some());
 		AuctionManagerModule::on_auction_ended(0, Some((BOB, 20)));
+		AuctionManagerModule::on_idle(1, u64::MAX);
 		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
 			0, BTC, 100, 500,
 		)));
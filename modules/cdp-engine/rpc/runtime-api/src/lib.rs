@@ -0,0 +1,50 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Runtime API definition for cdp engine module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use primitives::CurrencyId;
+use sp_runtime::FixedU128;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait CdpEngineApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The recorded checkpoints of the debit exchange rate of the
+		/// collateral type, in ascending order of block number.
+		fn debit_exchange_rate_checkpoints(currency_id: CurrencyId) -> Vec<(BlockNumber, FixedU128)>;
+
+		/// The interest accrued by the current debit of the CDP between the
+		/// checkpoints at `from` and `to`, `None` if either checkpoint is not
+		/// found.
+		fn accrued_interest(currency_id: CurrencyId, who: AccountId, from: BlockNumber, to: BlockNumber) -> Option<Balance>;
+
+		/// The extra interest rate per sec of the collateral type computed by
+		/// its interest rate curve at the current utilization, `None` if the
+		/// curve is not set.
+		fn curve_interest_rate_per_sec(currency_id: CurrencyId) -> Option<FixedU128>;
+	}
+}
@@ -23,25 +23,37 @@
 //! The core module of Honzon protocol. CDP engine is responsible for handle
 //! internal processes about CDPs, including liquidation, settlement and risk
 //! management.
+//!
+//! The debit of a collateral type is denominated in the stable currency by
+//! default. Governance can denominate it in other stable asset enabled as a
+//! debt denomination of CDP treasury instead, e.g. a EUR-pegged asset, so the
+//! CDPs of the collateral type mint and repay the denomination, their prices
+//! and risk params are in the denomination, and their bad debt and interest
+//! go to the debit pool and surplus pool of the denomination. These CDPs are
+//! liquidated by swapping the collateral to the denomination by DEX, or by
+//! decreasing price sale paid in the denomination if the swap fails, and the
+//! features trading the collateral with the stable currency, the extra
+//! collaterals, leverage and stop-loss orders, are not supported for them.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 #![allow(clippy::upper_case_acronyms)]
 
-use frame_support::{log, pallet_prelude::*, traits::UnixTime, transactional};
+use frame_support::{log, pallet_prelude::*, traits::UnixTime, transactional, BoundedVec};
 use frame_system::{
 	offchain::{SendTransactionTypes, SubmitTransaction},
 	pallet_prelude::*,
 };
 use loans::Position;
-use orml_traits::Change;
+use orml_traits::{Change, Happened, MultiCurrency};
 use orml_utilities::OffchainErr;
-use primitives::{Amount, Balance, CurrencyId};
+use primitives::{Amount, Balance, CircuitBreakerId, CircuitBreakerReason, CurrencyId, RevenueSource};
 use rand_chacha::{
 	rand_core::{RngCore, SeedableRng},
 	ChaChaRng,
 };
 use scale_info::TypeInfo;
+use sp_core::U256;
 use sp_runtime::{
 	offchain::{
 		storage::StorageValueRef,
@@ -50,13 +62,16 @@ use sp_runtime::{
 	},
 	traits::{Bounded, Convert, One, Saturating, StaticLookup, UniqueSaturatedInto, Zero},
 	transaction_validity::{
-		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity,
+		ValidTransaction,
 	},
 	DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
 };
 use sp_std::prelude::*;
 use support::{
-	CDPTreasury, CDPTreasuryExtended, EmergencyShutdown, ExchangeRate, Price, PriceProvider, Rate, Ratio, RiskManager,
+	average_cumulative_price, CDPTreasury, CDPTreasuryExtended, CircuitBreakerRecorder, CircuitBreakerUnpause,
+	DEXManager, DEXPriceCumulative, DebtDenominations, EmergencyShutdown, ExchangeRate, Price, PriceProvider, Rate,
+	Ratio, RiskManager,
 };
 
 mod debit_exchange_rate_convertor;
@@ -71,8 +86,13 @@ pub use weights::WeightInfo;
 pub const OFFCHAIN_WORKER_DATA: &[u8] = b"acala/cdp-engine/data/";
 pub const OFFCHAIN_WORKER_LOCK: &[u8] = b"acala/cdp-engine/lock/";
 pub const OFFCHAIN_WORKER_MAX_ITERATIONS: &[u8] = b"acala/cdp-engine/max-iterations/";
+pub const OFFCHAIN_WORKER_LIQUIDATION_CURSOR: &[u8] = b"acala/cdp-engine/liquidation-cursor/";
+pub const OFFCHAIN_WORKER_MAX_LIQUIDATIONS: &[u8] = b"acala/cdp-engine/max-liquidations/";
 pub const LOCK_DURATION: u64 = 100;
 pub const DEFAULT_MAX_ITERATIONS: u32 = 1000;
+pub const DEFAULT_MAX_LIQUIDATIONS: u32 = 1000;
+pub const RISK_BUCKETS_PER_DOUBLING: u32 = 16;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
 
 pub type LoansOf<T> = loans::Pallet<T>;
 
@@ -109,6 +129,69 @@ type ChangeOptionRate = Change<Option<Rate>>;
 type ChangeOptionRatio = Change<Option<Ratio>>;
 type ChangeBalance = Change<Balance>;
 
+/// The changes of risk management params waiting for the timelock
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct RiskParamsChanges<BlockNumber> {
+	/// The change of extra interest rate per sec
+	pub interest_rate_per_sec: Change<Option<Rate>>,
+	/// The change of liquidation ratio
+	pub liquidation_ratio: Change<Option<Ratio>>,
+	/// The change of liquidation penalty
+	pub liquidation_penalty: Change<Option<Rate>>,
+	/// The change of required collateral ratio
+	pub required_collateral_ratio: Change<Option<Ratio>>,
+	/// The change of maximum total debit value
+	pub maximum_total_debit_value: Change<Balance>,
+	/// The change of interest rate curve
+	pub interest_rate_curve: Change<Option<InterestRateCurve>>,
+	/// The change of soft cap of total debit value
+	pub soft_debit_cap: Change<Option<SoftDebitCap>>,
+	/// The change of minimum debit value
+	pub minimum_debit_value: Change<Option<Balance>>,
+	/// The change of liquidation grace window
+	pub liquidation_grace: Change<Option<LiquidationGrace<BlockNumber>>>,
+}
+
+impl<BlockNumber> Default for RiskParamsChanges<BlockNumber> {
+	fn default() -> Self {
+		Self {
+			interest_rate_per_sec: Change::NoChange,
+			liquidation_ratio: Change::NoChange,
+			liquidation_penalty: Change::NoChange,
+			required_collateral_ratio: Change::NoChange,
+			maximum_total_debit_value: Change::NoChange,
+			interest_rate_curve: Change::NoChange,
+			soft_debit_cap: Change::NoChange,
+			minimum_debit_value: Change::NoChange,
+			liquidation_grace: Change::NoChange,
+		}
+	}
+}
+
+impl<BlockNumber> RiskParamsChanges<BlockNumber> {
+	/// Merge the later changes into these, the later new values replace
+	/// these.
+	pub fn merge(self, later: Self) -> Self {
+		fn merge<T>(earlier: Change<T>, later: Change<T>) -> Change<T> {
+			match later {
+				Change::NoChange => earlier,
+				new_value => new_value,
+			}
+		}
+		Self {
+			interest_rate_per_sec: merge(self.interest_rate_per_sec, later.interest_rate_per_sec),
+			liquidation_ratio: merge(self.liquidation_ratio, later.liquidation_ratio),
+			liquidation_penalty: merge(self.liquidation_penalty, later.liquidation_penalty),
+			required_collateral_ratio: merge(self.required_collateral_ratio, later.required_collateral_ratio),
+			maximum_total_debit_value: merge(self.maximum_total_debit_value, later.maximum_total_debit_value),
+			interest_rate_curve: merge(self.interest_rate_curve, later.interest_rate_curve),
+			soft_debit_cap: merge(self.soft_debit_cap, later.soft_debit_cap),
+			minimum_debit_value: merge(self.minimum_debit_value, later.minimum_debit_value),
+			liquidation_grace: merge(self.liquidation_grace, later.liquidation_grace),
+		}
+	}
+}
+
 /// Liquidation strategy available
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
 pub enum LiquidationStrategy {
@@ -117,6 +200,12 @@ pub enum LiquidationStrategy {
 	Auction { auction_count: u32 },
 	/// Liquidation CDP's collateral by swap with DEX
 	Exchange,
+	/// Liquidation CDP's collateral by create a sale whose price decreases
+	/// over time
+	DecreasingPriceSale { sale_id: u32 },
+	/// Liquidation CDP's collateral by swap through the stable asset pools by
+	/// the aggregated swap paths
+	StableAssetSwap,
 }
 
 /// Status of CDP
@@ -127,6 +216,86 @@ pub enum CDPStatus {
 	ChecksFailed(DispatchError),
 }
 
+/// The stop-loss order of CDP
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct StopLossOrder {
+	/// The order is triggered when the oracle price of the collateral is at
+	/// or below the trigger price
+	pub trigger_price: Price,
+	/// The amount of collateral sold to repay the debit when triggered
+	pub collateral_amount: Balance,
+}
+
+/// The interest rate curve of collateral type against the utilization of its
+/// hard cap of total debit value
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct InterestRateCurve {
+	/// The extra interest rate per sec when the utilization is zero
+	pub base_rate: Rate,
+	/// The increase of the extra interest rate per sec when the utilization
+	/// is full
+	pub slope: Rate,
+}
+
+/// The soft cap of the total debit value of collateral type, crossing it
+/// raises the interest rate by the multiplier
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct SoftDebitCap {
+	/// The soft cap of the total debit value
+	pub soft_cap: Balance,
+	/// The multiplier of the interest rate per sec when the total debit value
+	/// is above the soft cap
+	pub interest_rate_multiplier: Ratio,
+}
+
+/// The level of the total debit value of collateral type against its caps
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum DebitCapLevel {
+	/// The total debit value is at or below the soft cap, or the soft cap is
+	/// not set
+	BelowSoftCap,
+	/// The total debit value is above the soft cap
+	AboveSoftCap,
+	/// The total debit value has reached the hard cap
+	ReachedHardCap,
+}
+
+impl Default for DebitCapLevel {
+	fn default() -> Self {
+		DebitCapLevel::BelowSoftCap
+	}
+}
+
+/// The grace window before liquidating the CDPs of collateral type
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct LiquidationGrace<BlockNumber> {
+	/// The number of blocks to wait after the CDP first crosses the
+	/// liquidation ratio
+	pub grace_period: BlockNumber,
+	/// The CDP whose collateral ratio is below it will be liquidated without
+	/// waiting for the grace window
+	pub hard_liquidation_ratio: Ratio,
+}
+
+/// The valuation of collateral type when its oracle price is unavailable
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum OracleFailurePolicy<BlockNumber> {
+	/// No valuation, the CDPs cannot be adjusted or liquidated
+	Freeze,
+	/// Use the last oracle price if it's not older than `max_age` blocks
+	LastGoodPrice { max_age: BlockNumber },
+	/// Use the time-weighted average DEX price of the collateral and its debt
+	/// denomination over the last complete `window` blocks, reduced by the
+	/// haircut
+	DexTwap { window: u32, haircut: Ratio },
+}
+
+impl<BlockNumber> Default for OracleFailurePolicy<BlockNumber> {
+	fn default() -> Self {
+		OracleFailurePolicy::Freeze
+	}
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -139,6 +308,10 @@ pub mod module {
 		/// always do this.
 		type UpdateOrigin: EnsureOrigin<Self::Origin>;
 
+		/// The origin which may pause or resume the issuance of new debit for
+		/// specific collateral type in emergency.
+		type EmergencyOrigin: EnsureOrigin<Self::Origin>;
+
 		/// The list of valid collateral currency types
 		#[pallet::constant]
 		type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
@@ -155,7 +328,8 @@ pub mod module {
 		#[pallet::constant]
 		type DefaultLiquidationPenalty: Get<Rate>;
 
-		/// The minimum debit value to avoid debit dust
+		/// The default minimum debit value to avoid debit dust, overridden by
+		/// the minimum debit value of the collateral type if set
 		#[pallet::constant]
 		type MinimumDebitValue: Get<Balance>;
 
@@ -168,7 +342,8 @@ pub mod module {
 		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
 
 		/// The CDP treasury to maintain bad debts and surplus generated by CDPs
-		type CDPTreasury: CDPTreasuryExtended<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+		type CDPTreasury: CDPTreasuryExtended<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>
+			+ DebtDenominations<Self::AccountId>;
 
 		/// The price source of all types of currencies related to CDP
 		type PriceSource: PriceProvider<CurrencyId>;
@@ -180,6 +355,12 @@ pub mod module {
 		#[pallet::constant]
 		type UnsignedPriority: Get<TransactionPriority>;
 
+		/// The number of blocks the unsigned transactions are valid for, the
+		/// positions are revalidated and resubmitted by the offchain worker
+		/// after that.
+		#[pallet::constant]
+		type UnsignedLongevity: Get<TransactionLongevity>;
+
 		/// Emergency shutdown.
 		type EmergencyShutdown: EmergencyShutdown;
 
@@ -195,6 +376,62 @@ pub mod module {
 		#[pallet::constant]
 		type DefaultSwapParitalPathList: Get<Vec<Vec<CurrencyId>>>;
 
+		/// The DEX to swap between the stable currency and the collateral
+		/// when expanding or shrinking the CDP.
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The DEX cumulative prices to average the collateral price over a
+		/// window under the `DexTwap` oracle failure policy.
+		type DEXPriceCumulative: DEXPriceCumulative<CurrencyId, Self::BlockNumber>;
+
+		/// The interval in blocks to record the checkpoints of debit exchange
+		/// rates
+		#[pallet::constant]
+		type DebitExchangeRateCheckpointInterval: Get<Self::BlockNumber>;
+
+		/// The maximum number of debit exchange rate checkpoints kept for each
+		/// collateral type, the oldest checkpoint is dropped when exceeded
+		#[pallet::constant]
+		type MaxDebitExchangeRateCheckpoints: Get<u32>;
+
+		/// The interval in blocks to recompute the extra interest rates of
+		/// the collateral types with the interest rate curve
+		#[pallet::constant]
+		type InterestRateCurvePeriod: Get<Self::BlockNumber>;
+
+		/// The hook called when the CDP is liquidated, with the owner, the
+		/// collateral type and the liquidation penalty
+		type OnLiquidation: Happened<(Self::AccountId, CurrencyId, Balance)>;
+
+		/// The fee in stable currency paid from the position when its
+		/// stop-loss order is executed, kept by the CDP treasury
+		#[pallet::constant]
+		type StopLossExecutionFee: Get<Balance>;
+
+		/// The recorder of the pause and resumption of the issuance of new
+		/// debit
+		type CircuitBreakerRecorder: CircuitBreakerRecorder;
+
+		/// The delay before the risk management params updated by the
+		/// non-root `UpdateOrigin` take effect, zero means immediately
+		#[pallet::constant]
+		type RiskParamsUpdateDelay: Get<Self::BlockNumber>;
+
+		/// The fixed bounty in stable currency paid out of the surplus of cdp
+		/// treasury to the keeper who submits the signed liquidation, the
+		/// bounty is capped by the liquidation penalty
+		#[pallet::constant]
+		type LiquidationKeeperBounty: Get<Balance>;
+
+		/// The share of the value of the seized collateral paid to the keeper
+		/// in addition to the fixed bounty
+		#[pallet::constant]
+		type LiquidationKeeperBountyShare: Get<Ratio>;
+
+		/// The cap of the total liquidation bounties paid in a block
+		#[pallet::constant]
+		type MaxLiquidationBountyPerBlock: Get<Balance>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -226,6 +463,46 @@ pub mod module {
 		MustAfterShutdown,
 		/// Failed to swap debit by default path list
 		SwapDebitFailed,
+		/// The expiry of parameters is not after the current block
+		InvalidExpiry,
+		/// The issuance of new debit is paused
+		DebitIssuancePaused,
+		/// The issuance of new debit for the collateral type is paused
+		CollateralMintPaused,
+		/// The debit value of the CDP exceeds the cap per account
+		ExceedMaxDebitPerAccount,
+		/// The total debit value relative to the total collateral value of
+		/// the system exceeds the cap
+		ExceedSystemLeverageCap,
+		/// The stop-loss order is invalid
+		InvalidStopLossOrder,
+		/// The stop-loss order does not exist
+		StopLossOrderNotExists,
+		/// The oracle price has not reached the trigger price of the stop-loss
+		/// order
+		StopLossNotTriggered,
+		/// The stable currency raised by the stop-loss order cannot cover the
+		/// execution fee
+		StopLossBelowExecutionFee,
+		/// The soft cap of total debit value is above the hard cap
+		SoftCapAboveHardCap,
+		/// The unsafe CDP is still in the grace window before liquidation
+		InLiquidationGracePeriod,
+		/// There is no pending update of risk management params
+		NoPendingRiskParams,
+		/// The currency is not a debt denomination of CDP treasury
+		InvalidDebtDenomination,
+		/// The collateral type still has debit, its debt denomination can't
+		/// be changed
+		DebtDenominationInUse,
+		/// The operation is not supported for the collateral type denominated
+		/// in other stable asset
+		NotSupportedForDebtDenomination,
+		/// The swap path doesn't end with the stable currency
+		InvalidSwapPath,
+		/// The extra interest rate of the collateral type is updated by its
+		/// interest rate curve
+		InterestRateCurveEnabled,
 	}
 
 	#[pallet::event]
@@ -258,6 +535,79 @@ pub mod module {
 		/// The global interest rate per sec for all types of collateral
 		/// updated. \[new_global_interest_rate_per_sec\]
 		GlobalInterestRatePerSecUpdated(Rate),
+		/// The expiry of risk management params for specific collateral type
+		/// updated. \[collateral_type, expire_at\]
+		CollateralParamsExpiryUpdated(CurrencyId, Option<T::BlockNumber>),
+		/// The risk management params for specific collateral type expired
+		/// and reverted to default. \[collateral_type, default_params\]
+		CollateralParamsExpired(CurrencyId, RiskManagementParams),
+		/// The issuance of new debit is paused or resumed. \[paused\]
+		DebitIssuancePausedUpdated(bool),
+		/// The issuance of new debit for specific collateral type is paused
+		/// or resumed. \[collateral_type, paused\]
+		CollateralMintPausedUpdated(CurrencyId, bool),
+		/// The cap of the debit value per account for specific collateral
+		/// type updated. \[collateral_type, new_max_debit_per_account\]
+		MaxDebitPerAccountUpdated(CurrencyId, Option<Balance>),
+		/// The cap of the system leverage updated. \[new_max_system_leverage\]
+		MaxSystemLeverageUpdated(Option<Ratio>),
+		/// The stop-loss order of CDP placed. \[collateral_type, owner,
+		/// trigger_price, collateral_amount\]
+		StopLossOrderPlaced(CurrencyId, T::AccountId, Price, Balance),
+		/// The stop-loss order of CDP cancelled. \[collateral_type, owner\]
+		StopLossOrderCancelled(CurrencyId, T::AccountId),
+		/// The stop-loss order of CDP executed. \[collateral_type, owner,
+		/// sold_collateral_amount, repaid_debit_value, execution_fee\]
+		StopLossExecuted(CurrencyId, T::AccountId, Balance, Balance, Balance),
+		/// The interest of specific collateral type is accumulated into the
+		/// debit exchange rate and issued to the surplus pool.
+		/// \[collateral_type, interest_value, new_debit_exchange_rate\]
+		InterestAccumulated(CurrencyId, Balance, ExchangeRate),
+		/// The CDP is expanded by swapping the stable currency issued to the
+		/// collateral by DEX. \[collateral_type, owner,
+		/// increase_collateral_amount, increase_debit_value\]
+		PositionExpanded(CurrencyId, T::AccountId, Balance, Balance),
+		/// The CDP is shrunk by swapping the collateral to the stable currency
+		/// by DEX to repay the debit. \[collateral_type, owner,
+		/// decrease_collateral_amount, decrease_debit_value\]
+		PositionShrunk(CurrencyId, T::AccountId, Balance, Balance),
+		/// The interest rate curve for specific collateral type updated.
+		/// \[collateral_type, new_interest_rate_curve\]
+		InterestRateCurveUpdated(CurrencyId, Option<InterestRateCurve>),
+		/// The soft cap of total debit value for specific collateral type
+		/// updated. \[collateral_type, new_soft_debit_cap\]
+		SoftDebitCapUpdated(CurrencyId, Option<SoftDebitCap>),
+		/// The total debit value of specific collateral type crossed the soft
+		/// cap or the hard cap. \[collateral_type, new_debit_cap_level\]
+		DebitCapLevelChanged(CurrencyId, DebitCapLevel),
+		/// The minimum debit value for specific collateral type updated.
+		/// \[collateral_type, new_minimum_debit_value\]
+		MinimumDebitValueUpdated(CurrencyId, Option<Balance>),
+		/// The liquidation grace window for specific collateral type updated.
+		/// \[collateral_type, new_liquidation_grace\]
+		LiquidationGraceUpdated(CurrencyId, Option<LiquidationGrace<T::BlockNumber>>),
+		/// The CDP crossed the liquidation ratio, it will be liquidated after
+		/// the deadline unless topped up. \[collateral_type, owner, deadline\]
+		UnsafeCdp(CurrencyId, T::AccountId, T::BlockNumber),
+		/// The oracle failure policy for specific collateral type updated.
+		/// \[collateral_type, new_oracle_failure_policy\]
+		OracleFailurePolicyUpdated(CurrencyId, OracleFailurePolicy<T::BlockNumber>),
+		/// The update of risk management params for specific collateral type
+		/// is scheduled. \[collateral_type, effective_at\]
+		RiskParamsUpdateScheduled(CurrencyId, T::BlockNumber),
+		/// The pending update of risk management params for specific
+		/// collateral type is cancelled. \[collateral_type\]
+		PendingRiskParamsCancelled(CurrencyId),
+		/// The pending update of risk management params for specific
+		/// collateral type is rejected, as the params are invalid when it
+		/// takes effect. \[collateral_type\]
+		PendingRiskParamsRejected(CurrencyId),
+		/// The bounty for the liquidation paid to the keeper. \[keeper,
+		/// collateral_type, owner, bounty\]
+		LiquidationBountyPaid(T::AccountId, CurrencyId, T::AccountId, Balance),
+		/// The debt denomination for specific collateral type updated.
+		/// \[collateral_type, new_debt_denomination\]
+		DebtDenominationUpdated(CurrencyId, Option<CurrencyId>),
 	}
 
 	/// Mapping from collateral type to its exchange rate of debit units and
@@ -282,6 +632,17 @@ pub mod module {
 	#[pallet::getter(fn collateral_params)]
 	pub type CollateralParams<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, RiskManagementParams, ValueQuery>;
 
+	/// Mapping from collateral type to the expiry of its risk management
+	/// params, at which the params revert to the default, it's removed when
+	/// the params are updated
+	///
+	/// CollateralParamsExpiries: CurrencyId => Option<(BlockNumber,
+	/// RiskManagementParams)>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_params_expiries)]
+	pub type CollateralParamsExpiries<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, (T::BlockNumber, RiskManagementParams), OptionQuery>;
+
 	/// Timestamp in seconds of the last interest accumulation
 	///
 	/// LastAccumulationSecs: u64
@@ -289,6 +650,236 @@ pub mod module {
 	#[pallet::getter(fn last_accumulation_secs)]
 	pub type LastAccumulationSecs<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+	/// Whether the issuance of new debit is paused, CDPs can't increase
+	/// debit while it's paused.
+	///
+	/// DebitIssuancePaused: bool
+	#[pallet::storage]
+	#[pallet::getter(fn debit_issuance_paused)]
+	pub type DebitIssuancePaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Whether the issuance of new debit for specific collateral type is
+	/// paused, repayments, withdrawals and liquidations are still allowed.
+	///
+	/// CollateralMintPaused: map CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_mint_paused)]
+	pub type CollateralMintPaused<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, bool, ValueQuery>;
+
+	/// The cap of the debit value of a single CDP of specific collateral
+	/// type, `None` means no cap.
+	///
+	/// MaxDebitPerAccount: map CurrencyId => Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn max_debit_per_account)]
+	pub type MaxDebitPerAccount<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, OptionQuery>;
+
+	/// The cap of the total debit value relative to the total collateral
+	/// value at oracle prices of all types of collateral, CDPs can't increase
+	/// debit beyond it.
+	///
+	/// MaxSystemLeverage: Option<Ratio>
+	#[pallet::storage]
+	#[pallet::getter(fn max_system_leverage)]
+	pub type MaxSystemLeverage<T: Config> = StorageValue<_, Ratio, OptionQuery>;
+
+	/// The system leverage taken at the beginning of the block if the cap of
+	/// the system leverage is set, the debit increases in the block are
+	/// checked against it.
+	///
+	/// SystemLeverageSnapshot: Option<Ratio>
+	#[pallet::storage]
+	#[pallet::getter(fn system_leverage_snapshot)]
+	pub type SystemLeverageSnapshot<T: Config> = StorageValue<_, Ratio, OptionQuery>;
+
+	/// Mapping from collateral type to the checkpoints of its debit exchange
+	/// rate recorded at the interval, in ascending order of block number
+	///
+	/// DebitExchangeRateCheckpoints: CurrencyId => Vec<(BlockNumber,
+	/// ExchangeRate)>
+	#[pallet::storage]
+	#[pallet::getter(fn debit_exchange_rate_checkpoints)]
+	pub type DebitExchangeRateCheckpoints<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		CurrencyId,
+		BoundedVec<(T::BlockNumber, ExchangeRate), T::MaxDebitExchangeRateCheckpoints>,
+		ValueQuery,
+	>;
+
+	/// Mapping from CDP to the snapshots of its debit taken before each update
+	/// of the debit, together with the debit exchange rate at that time, in
+	/// ascending order of block number. At most
+	/// `MaxDebitExchangeRateCheckpoints` snapshots are kept.
+	///
+	/// DebitSnapshots: double_map CurrencyId, AccountId => Vec<(BlockNumber,
+	/// ExchangeRate, Balance)>
+	#[pallet::storage]
+	#[pallet::getter(fn debit_snapshots)]
+	pub type DebitSnapshots<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CurrencyId,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<(T::BlockNumber, ExchangeRate, Balance), T::MaxDebitExchangeRateCheckpoints>,
+		ValueQuery,
+	>;
+
+	/// The stop-loss orders of CDPs
+	///
+	/// StopLossOrders: double_map CurrencyId, AccountId => Option<StopLossOrder>
+	#[pallet::storage]
+	#[pallet::getter(fn stop_loss_orders)]
+	pub type StopLossOrders<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, StopLossOrder, OptionQuery>;
+
+	/// Mapping from collateral type to its interest rate curve, the extra
+	/// interest rate per sec of the collateral type is recomputed by the curve
+	/// at the period
+	///
+	/// InterestRateCurves: map CurrencyId => Option<InterestRateCurve>
+	#[pallet::storage]
+	#[pallet::getter(fn interest_rate_curves)]
+	pub type InterestRateCurves<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, InterestRateCurve, OptionQuery>;
+
+	/// Mapping from collateral type to the extra interest rate per sec
+	/// computed by its interest rate curve at the last period, it overrides
+	/// the extra interest rate per sec of the collateral params while the
+	/// curve is set
+	///
+	/// CurveInterestRates: map CurrencyId => Option<Rate>
+	#[pallet::storage]
+	#[pallet::getter(fn curve_interest_rates)]
+	pub type CurveInterestRates<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Rate, OptionQuery>;
+
+	/// Mapping from collateral type to the soft cap of its total debit value
+	///
+	/// SoftDebitCaps: map CurrencyId => Option<SoftDebitCap>
+	#[pallet::storage]
+	#[pallet::getter(fn soft_debit_caps)]
+	pub type SoftDebitCaps<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, SoftDebitCap, OptionQuery>;
+
+	/// Mapping from collateral type to the level of its total debit value
+	/// against the caps, updated at the beginning of every block
+	///
+	/// DebitCapLevels: map CurrencyId => DebitCapLevel
+	#[pallet::storage]
+	#[pallet::getter(fn debit_cap_levels)]
+	pub type DebitCapLevels<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, DebitCapLevel, ValueQuery>;
+
+	/// Mapping from collateral type to the minimum debit value of its CDPs,
+	/// overriding the `MinimumDebitValue`
+	///
+	/// MinimumDebitValues: map CurrencyId => Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn minimum_debit_values)]
+	pub type MinimumDebitValues<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, OptionQuery>;
+
+	/// Mapping from collateral type to the grace window before liquidating
+	/// its CDPs
+	///
+	/// LiquidationGraces: map CurrencyId => Option<LiquidationGrace>
+	#[pallet::storage]
+	#[pallet::getter(fn liquidation_graces)]
+	pub type LiquidationGraces<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, LiquidationGrace<T::BlockNumber>, OptionQuery>;
+
+	/// The deadline block of the grace window of the unsafe CDPs, after which
+	/// they can be liquidated
+	///
+	/// UnsafeCdpDeadlines: double_map CurrencyId, AccountId => Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn unsafe_cdp_deadlines)]
+	pub type UnsafeCdpDeadlines<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	/// The risk bucket of the CDP with debit in the liquidation index. The
+	/// bucket is the logarithm of the ratio of collateral amount to debit
+	/// amount, so it doesn't move with the prices or the debit exchange rate.
+	///
+	/// CdpRiskBuckets: double_map CurrencyId, AccountId => Option<u32>
+	#[pallet::storage]
+	#[pallet::getter(fn cdp_risk_buckets)]
+	pub type CdpRiskBuckets<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, u32, OptionQuery>;
+
+	/// The liquidation index of the CDPs of all collateral types by risk
+	/// bucket
+	///
+	/// LiquidationIndex: double_map (CurrencyId, u32), AccountId => Option<()>
+	#[pallet::storage]
+	pub type LiquidationIndex<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, (CurrencyId, u32), Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	/// The number of CDPs in the risk bucket of the collateral type
+	///
+	/// RiskBucketSizes: double_map CurrencyId, u32 => u32
+	#[pallet::storage]
+	#[pallet::getter(fn risk_bucket_sizes)]
+	pub type RiskBucketSizes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, u32, u32, ValueQuery>;
+
+	/// Mapping from collateral type to the valuation policy when its oracle
+	/// price is unavailable
+	///
+	/// OracleFailurePolicies: map CurrencyId => OracleFailurePolicy
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_failure_policies)]
+	pub type OracleFailurePolicies<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, OracleFailurePolicy<T::BlockNumber>, ValueQuery>;
+
+	/// Mapping from collateral type to its last oracle price and the block
+	/// number it's recorded, only recorded under the `LastGoodPrice` policy
+	///
+	/// LastGoodPrices: map CurrencyId => Option<(Price, BlockNumber)>
+	#[pallet::storage]
+	#[pallet::getter(fn last_good_prices)]
+	pub type LastGoodPrices<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, (Price, T::BlockNumber), OptionQuery>;
+
+	/// Mapping from collateral type to its average DEX price, only sampled
+	/// under the `DexTwap` policy
+	///
+	/// DexTwapPrices: map CurrencyId => Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn dex_twap_prices)]
+	pub type DexTwapPrices<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
+
+	/// Mapping from collateral type to the DEX cumulative price observed at
+	/// the start of the current window, only under the `DexTwap` policy
+	///
+	/// DexTwapObservations: map CurrencyId => Option<(U256, BlockNumber)>
+	#[pallet::storage]
+	#[pallet::getter(fn dex_twap_observations)]
+	pub type DexTwapObservations<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, (U256, T::BlockNumber), OptionQuery>;
+
+	/// Mapping from collateral type to the pending changes of its risk
+	/// management params and the block number at which they take effect
+	///
+	/// PendingRiskParams: map CurrencyId => Option<(BlockNumber,
+	/// RiskParamsChanges)>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_risk_params)]
+	pub type PendingRiskParams<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, (T::BlockNumber, RiskParamsChanges<T::BlockNumber>), OptionQuery>;
+
+	/// The block number and the total liquidation bounties paid to the
+	/// keepers in it.
+	///
+	/// LiquidationBountiesPaid: (BlockNumber, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn liquidation_bounties_paid)]
+	pub type LiquidationBountiesPaid<T: Config> = StorageValue<_, (T::BlockNumber, Balance), ValueQuery>;
+
+	/// The stable asset the debit of specific collateral type is denominated
+	/// in, the stable currency if it's not set.
+	///
+	/// CollateralDebtDenominations: map CurrencyId => Option<CurrencyId>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_debt_denominations)]
+	pub type CollateralDebtDenominations<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, CurrencyId, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig {
 		#[allow(clippy::type_complexity)]
@@ -361,10 +952,18 @@ pub mod module {
 				now_as_secs,
 				Self::last_accumulation_secs(),
 			))
+			.saturating_add(Self::expire_collateral_params(now))
+			.saturating_add(Self::apply_pending_risk_params(now))
+			.saturating_add(Self::record_debit_exchange_rate_checkpoints(now))
+			.saturating_add(Self::recompute_interest_rates(now))
+			.saturating_add(Self::update_debit_cap_levels())
+			.saturating_add(Self::update_system_leverage_snapshot())
+			.saturating_add(Self::record_fallback_prices(now))
 		}
 
 		/// Runs after every block. Start offchain worker to check CDP and
-		/// submit unsigned tx to trigger liquidation or settlement.
+		/// submit unsigned tx to trigger liquidation or settlement. The
+		/// liquidations of the riskiest CDPs are submitted first.
 		fn offchain_worker(now: T::BlockNumber) {
 			if let Err(e) = Self::_offchain_worker() {
 				log::info!(
@@ -393,21 +992,33 @@ pub mod module {
 	impl<T: Config> Pallet<T> {
 		/// Liquidate unsafe CDP
 		///
-		/// The dispatch origin of this call must be _None_.
+		/// The dispatch origin of this call must be _None_ or _Signed_, the
+		/// signed keeper is paid the liquidation bounty out of the surplus of
+		/// cdp treasury.
 		///
 		/// - `currency_id`: CDP's collateral type.
 		/// - `who`: CDP's owner.
-		#[pallet::weight(<T as Config>::WeightInfo::liquidate_by_auction(<T as Config>::CDPTreasury::max_auction()))]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::liquidate_by_auction(<T as Config>::CDPTreasury::max_auction())
+				.max(Pallet::<T>::liquidate_by_dex_weight())
+				.saturating_add(Pallet::<T>::liquidation_bounty_weight())
+		)]
 		#[transactional]
 		pub fn liquidate(
 			origin: OriginFor<T>,
 			currency_id: CurrencyId,
 			who: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResultWithPostInfo {
-			ensure_none(origin)?;
+			let keeper = match ensure_signed(origin.clone()) {
+				Ok(keeper) => Some(keeper),
+				Err(_) => {
+					ensure_none(origin)?;
+					None
+				}
+			};
 			let who = T::Lookup::lookup(who)?;
 			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
-			let consumed_weight: Weight = Self::liquidate_unsafe_cdp(who, currency_id)?;
+			let consumed_weight: Weight = Self::do_liquidate_unsafe_cdp(who, currency_id, keeper)?;
 			Ok(Some(consumed_weight).into())
 		}
 
@@ -423,12 +1034,12 @@ pub mod module {
 			origin: OriginFor<T>,
 			currency_id: CurrencyId,
 			who: <T::Lookup as StaticLookup>::Source,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			ensure_none(origin)?;
 			let who = T::Lookup::lookup(who)?;
 			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
-			Self::settle_cdp_has_debit(who, currency_id)?;
-			Ok(())
+			let consumed_weight: Weight = Self::settle_cdp_has_debit(who, currency_id)?;
+			Ok(Some(consumed_weight).into())
 		}
 
 		/// Update global parameters related to risk management of CDP
@@ -446,7 +1057,8 @@ pub mod module {
 		}
 
 		/// Update parameters related to risk management of CDP under specific
-		/// collateral type
+		/// collateral type. The update takes effect after
+		/// `RiskParamsUpdateDelay`, merged into the pending one.
 		///
 		/// The dispatch origin of this call must be `UpdateOrigin`.
 		///
@@ -470,108 +1082,572 @@ pub mod module {
 			liquidation_penalty: ChangeOptionRate,
 			required_collateral_ratio: ChangeOptionRatio,
 			maximum_total_debit_value: ChangeBalance,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					interest_rate_per_sec,
+					liquidation_ratio,
+					liquidation_penalty,
+					required_collateral_ratio,
+					maximum_total_debit_value,
+					..Default::default()
+				},
+			)
+		}
+
+		/// Cancel the pending update of risk management params under specific
+		/// collateral type.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		#[pallet::weight((<T as Config>::WeightInfo::cancel_pending_risk_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn cancel_pending_risk_params(origin: OriginFor<T>, currency_id: CurrencyId) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			PendingRiskParams::<T>::take(currency_id).ok_or(Error::<T>::NoPendingRiskParams)?;
+			Self::deposit_event(Event::PendingRiskParamsCancelled(currency_id));
+			Ok(())
+		}
+
+		/// Set the expiry of risk management params under specific collateral
+		/// type, at which the params revert to the predefined default. Used to
+		/// make temporary emergency params not linger forever.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `expiry`: the block number at which the params expire and the default params to revert
+		///   to, `None` means removing the expiry.
+		#[pallet::weight((<T as Config>::WeightInfo::set_collateral_params_expiry(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_collateral_params_expiry(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			expiry: Option<(T::BlockNumber, RiskManagementParams)>,
 		) -> DispatchResult {
 			T::UpdateOrigin::ensure_origin(origin)?;
 			ensure!(
 				T::CollateralCurrencyIds::get().contains(&currency_id),
 				Error::<T>::InvalidCollateralType,
 			);
-
-			let mut collateral_params = Self::collateral_params(currency_id);
-			if let Change::NewValue(update) = interest_rate_per_sec {
-				collateral_params.interest_rate_per_sec = update;
-				Self::deposit_event(Event::InterestRatePerSecUpdated(currency_id, update));
-			}
-			if let Change::NewValue(update) = liquidation_ratio {
-				collateral_params.liquidation_ratio = update;
-				Self::deposit_event(Event::LiquidationRatioUpdated(currency_id, update));
-			}
-			if let Change::NewValue(update) = liquidation_penalty {
-				collateral_params.liquidation_penalty = update;
-				Self::deposit_event(Event::LiquidationPenaltyUpdated(currency_id, update));
-			}
-			if let Change::NewValue(update) = required_collateral_ratio {
-				collateral_params.required_collateral_ratio = update;
-				Self::deposit_event(Event::RequiredCollateralRatioUpdated(currency_id, update));
-			}
-			if let Change::NewValue(val) = maximum_total_debit_value {
-				collateral_params.maximum_total_debit_value = val;
-				Self::deposit_event(Event::MaximumTotalDebitValueUpdated(currency_id, val));
+			if let Some((expire_at, _)) = expiry {
+				ensure!(
+					expire_at > <frame_system::Pallet<T>>::block_number(),
+					Error::<T>::InvalidExpiry
+				);
 			}
-			CollateralParams::<T>::insert(currency_id, collateral_params);
+
+			let expire_at = expiry.as_ref().map(|(expire_at, _)| *expire_at);
+			CollateralParamsExpiries::<T>::mutate_exists(currency_id, |maybe_expiry| *maybe_expiry = expiry);
+			Self::deposit_event(Event::CollateralParamsExpiryUpdated(currency_id, expire_at));
 			Ok(())
 		}
-	}
-
-	#[pallet::validate_unsigned]
-	impl<T: Config> ValidateUnsigned for Pallet<T> {
-		type Call = Call<T>;
 
-		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-			match call {
-				Call::liquidate { currency_id, who } => {
-					let account = T::Lookup::lookup(who.clone())?;
-					let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &account);
-					if !matches!(
-						Self::check_cdp_status(*currency_id, collateral, debit),
-						CDPStatus::Unsafe
-					) || T::EmergencyShutdown::is_shutdown()
-					{
-						return InvalidTransaction::Stale.into();
-					}
+		/// Pause or resume the issuance of new debit for all types of
+		/// collateral.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `paused`: whether to pause the issuance of new debit.
+		#[pallet::weight((<T as Config>::WeightInfo::set_debit_issuance_paused(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_debit_issuance_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if paused {
+				Self::pause_debit_issuance(CircuitBreakerReason::Governance);
+			} else {
+				Self::resume_debit_issuance();
+			}
+			Ok(())
+		}
 
-					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
-						.priority(T::UnsignedPriority::get())
-						.and_provides((<frame_system::Pallet<T>>::block_number(), currency_id, who))
-						.longevity(64_u64)
-						.propagate(true)
-						.build()
-				}
-				Call::settle { currency_id, who } => {
-					let account = T::Lookup::lookup(who.clone())?;
-					let Position { debit, .. } = <LoansOf<T>>::positions(currency_id, account);
-					if debit.is_zero() || !T::EmergencyShutdown::is_shutdown() {
-						return InvalidTransaction::Stale.into();
-					}
+		/// Pause or resume the issuance of new debit for specific collateral
+		/// type.
+		///
+		/// The dispatch origin of this call must be `EmergencyOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `paused`: whether to pause the issuance of new debit.
+		#[pallet::weight((<T as Config>::WeightInfo::set_collateral_mint_paused(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_collateral_mint_paused(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			paused: bool,
+		) -> DispatchResult {
+			T::EmergencyOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
 
-					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
-						.priority(T::UnsignedPriority::get())
-						.and_provides((currency_id, who))
-						.longevity(64_u64)
-						.propagate(true)
-						.build()
-				}
-				_ => InvalidTransaction::Call.into(),
+			if paused {
+				Self::pause_collateral_mint(currency_id);
+			} else {
+				Self::resume_collateral_mint(currency_id);
 			}
+			Ok(())
 		}
-	}
-}
-
-impl<T: Config> Pallet<T> {
-	fn accumulate_interest(now_secs: u64, last_accumulation_secs: u64) -> u32 {
-		let mut count: u32 = 0;
 
-		if !T::EmergencyShutdown::is_shutdown() && !now_secs.is_zero() {
-			let interval_secs = now_secs.saturating_sub(last_accumulation_secs);
+		/// Update the cap of the debit value of a single CDP of specific
+		/// collateral type, so that a single account can't take a dominant
+		/// share of the debit of the collateral type.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `max_debit_per_account`: the cap of the debit value, `None` means
+		///   no cap.
+		#[pallet::weight((<T as Config>::WeightInfo::set_max_debit_per_account(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_max_debit_per_account(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			max_debit_per_account: Option<Balance>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
 
-			for currency_id in T::CollateralCurrencyIds::get() {
-				let rate_to_accumulate =
-					Self::compound_interest_rate(Self::get_interest_rate_per_sec(currency_id), interval_secs);
-				let total_debits = <LoansOf<T>>::total_positions(currency_id).debit;
+			MaxDebitPerAccount::<T>::set(currency_id, max_debit_per_account);
+			Self::deposit_event(Event::MaxDebitPerAccountUpdated(currency_id, max_debit_per_account));
+			Ok(())
+		}
 
-				if !rate_to_accumulate.is_zero() && !total_debits.is_zero() {
+		/// Update the cap of the total debit value relative to the total
+		/// collateral value of all types of collateral, `None` means no cap.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `max_system_leverage`: the cap of the system leverage.
+		#[pallet::weight((<T as Config>::WeightInfo::set_max_system_leverage(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_max_system_leverage(origin: OriginFor<T>, max_system_leverage: Option<Ratio>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			MaxSystemLeverage::<T>::set(max_system_leverage);
+			Self::deposit_event(Event::MaxSystemLeverageUpdated(max_system_leverage));
+			Ok(())
+		}
+
+		/// Place the stop-loss order of the caller's CDP, which replaces the
+		/// existing one. When the oracle price of the collateral is at or
+		/// below the trigger price, the collateral of the order is sold by
+		/// DEX to repay the debit and pay the execution fee.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `trigger_price`: the oracle price to trigger the order.
+		/// - `collateral_amount`: the amount of collateral to sell.
+		#[pallet::weight(<T as Config>::WeightInfo::place_stop_loss_order())]
+		#[transactional]
+		pub fn place_stop_loss_order(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			trigger_price: Price,
+			#[pallet::compact] collateral_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+			Self::ensure_stable_denominated(currency_id)?;
+			ensure!(
+				!trigger_price.is_zero() && !collateral_amount.is_zero(),
+				Error::<T>::InvalidStopLossOrder
+			);
+			ensure!(
+				!<LoansOf<T>>::positions(currency_id, &who).debit.is_zero(),
+				Error::<T>::NoDebitValue
+			);
+
+			StopLossOrders::<T>::insert(
+				currency_id,
+				&who,
+				StopLossOrder {
+					trigger_price,
+					collateral_amount,
+				},
+			);
+			Self::deposit_event(Event::StopLossOrderPlaced(
+				currency_id,
+				who,
+				trigger_price,
+				collateral_amount,
+			));
+			Ok(())
+		}
+
+		/// Cancel the stop-loss order of the caller's CDP.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_stop_loss_order())]
+		#[transactional]
+		pub fn cancel_stop_loss_order(origin: OriginFor<T>, currency_id: CurrencyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			StopLossOrders::<T>::take(currency_id, &who).ok_or(Error::<T>::StopLossOrderNotExists)?;
+			Self::deposit_event(Event::StopLossOrderCancelled(currency_id, who));
+			Ok(())
+		}
+
+		/// Execute the triggered stop-loss order of CDP
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `who`: CDP's owner.
+		#[pallet::weight(<T as Config>::WeightInfo::execute_stop_loss())]
+		#[transactional]
+		pub fn execute_stop_loss(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			Self::execute_stop_loss_order(who, currency_id)?;
+			Ok(())
+		}
+
+		/// Update the extra interest rate of specific collateral type by the
+		/// annual rate, which is converted to the interest rate per sec
+		/// compounding to it in a year. The update is scheduled the same as
+		/// `set_collateral_params`. It can't be updated while the interest
+		/// rate curve of the collateral type is set.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `annual_interest_rate`: extra annual interest rate, `None` means removing it.
+		#[pallet::weight((<T as Config>::WeightInfo::set_annual_interest_rate(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_annual_interest_rate(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			annual_interest_rate: Option<Rate>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let pending_curve = Self::pending_risk_params(currency_id).map(|(_, changes)| changes.interest_rate_curve);
+			let curve_enabled = match pending_curve {
+				Some(Change::NewValue(curve)) => curve.is_some(),
+				_ => InterestRateCurves::<T>::contains_key(currency_id),
+			};
+			ensure!(!curve_enabled, Error::<T>::InterestRateCurveEnabled);
+
+			let interest_rate_per_sec = annual_interest_rate.map(Self::annual_to_per_sec_rate);
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					interest_rate_per_sec: Change::NewValue(interest_rate_per_sec),
+					..Default::default()
+				},
+			)
+		}
+
+		/// Update the interest rate curve of specific collateral type, the
+		/// extra interest rate per sec is recomputed by the curve when the
+		/// update takes effect and then at every `InterestRateCurvePeriod`.
+		/// The update is scheduled the same as `set_collateral_params`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `interest_rate_curve`: interest rate curve, `None` means removing it and restoring the
+		///   extra interest rate per sec of the collateral params.
+		#[pallet::weight((<T as Config>::WeightInfo::set_interest_rate_curve(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_interest_rate_curve(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			interest_rate_curve: Option<InterestRateCurve>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					interest_rate_curve: Change::NewValue(interest_rate_curve),
+					..Default::default()
+				},
+			)
+		}
+
+		/// Update the soft cap of total debit value of specific collateral
+		/// type, the interest rate per sec is multiplied by the multiplier
+		/// when the total debit value is above the soft cap. The update is
+		/// scheduled the same as `set_collateral_params`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `soft_debit_cap`: soft cap and interest rate multiplier, `None` means removing it.
+		#[pallet::weight((<T as Config>::WeightInfo::set_soft_debit_cap(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_soft_debit_cap(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			soft_debit_cap: Option<SoftDebitCap>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					soft_debit_cap: Change::NewValue(soft_debit_cap),
+					..Default::default()
+				},
+			)
+		}
+
+		/// Update the minimum debit value of the CDPs of specific collateral
+		/// type, the CDP cannot be adjusted to leave the debit value between
+		/// zero and the minimum. The update is scheduled the same as
+		/// `set_collateral_params`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `minimum_debit_value`: minimum debit value, `None` means using the default
+		///   `MinimumDebitValue`.
+		#[pallet::weight((<T as Config>::WeightInfo::set_minimum_debit_value(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_minimum_debit_value(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			minimum_debit_value: Option<Balance>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					minimum_debit_value: Change::NewValue(minimum_debit_value),
+					..Default::default()
+				},
+			)
+		}
+
+		/// Update the grace window before liquidating the CDPs of specific
+		/// collateral type. When a CDP first crosses the liquidation ratio, an
+		/// `UnsafeCdp` event is emitted and it can only be liquidated after the
+		/// grace period, unless its collateral ratio is below the hard
+		/// liquidation ratio. The update is scheduled the same as
+		/// `set_collateral_params`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `liquidation_grace`: grace window, `None` means liquidating the unsafe CDPs
+		///   immediately.
+		#[pallet::weight((<T as Config>::WeightInfo::set_liquidation_grace(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_liquidation_grace(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			liquidation_grace: Option<LiquidationGrace<T::BlockNumber>>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::schedule_risk_params_changes(
+				currency_id,
+				RiskParamsChanges {
+					liquidation_grace: Change::NewValue(liquidation_grace),
+					..Default::default()
+				},
+			)
+		}
+
+		/// Update the valuation policy of specific collateral type when its
+		/// oracle price is unavailable, the recorded fallback prices are
+		/// reset.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `policy`: oracle failure policy.
+		#[pallet::weight((<T as Config>::WeightInfo::set_oracle_failure_policy(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_oracle_failure_policy(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			policy: OracleFailurePolicy<T::BlockNumber>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+
+			OracleFailurePolicies::<T>::insert(currency_id, policy);
+			LastGoodPrices::<T>::remove(currency_id);
+			DexTwapPrices::<T>::remove(currency_id);
+			DexTwapObservations::<T>::remove(currency_id);
+			Self::deposit_event(Event::OracleFailurePolicyUpdated(currency_id, policy));
+			Ok(())
+		}
+
+		/// Update the stable asset the debit of specific collateral type is
+		/// denominated in. It can only be changed while the collateral type
+		/// has no debit.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `denomination`: the debt denomination of CDP treasury, `None` means the stable
+		///   currency.
+		#[pallet::weight((<T as Config>::WeightInfo::set_debt_denomination(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_debt_denomination(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			denomination: Option<CurrencyId>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+			let denomination = denomination.filter(|denomination| *denomination != T::GetStableCurrencyId::get());
+			if let Some(denomination) = denomination {
+				ensure!(
+					<T as Config>::CDPTreasury::is_debt_denomination(denomination),
+					Error::<T>::InvalidDebtDenomination
+				);
+			}
+			ensure!(
+				<LoansOf<T>>::total_positions(currency_id).debit.is_zero(),
+				Error::<T>::DebtDenominationInUse
+			);
+
+			// the debt denomination can't be disabled while collateral types are
+			// denominated in it
+			let old_denomination = Self::collateral_debt_denominations(currency_id);
+			if old_denomination != denomination {
+				if let Some(denomination) = denomination {
+					<T as Config>::CDPTreasury::inc_denomination_consumers(denomination)?;
+				}
+				if let Some(old_denomination) = old_denomination {
+					<T as Config>::CDPTreasury::dec_denomination_consumers(old_denomination);
+				}
+			}
+			CollateralDebtDenominations::<T>::set(currency_id, denomination);
+			Self::deposit_event(Event::DebtDenominationUpdated(currency_id, denomination));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// The unsigned transactions of a position provide the same tag, so
+		/// only one of them stays in the pool for the position and the
+		/// duplicates submitted by the offchain workers are rejected unless
+		/// they have higher priority.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::liquidate { currency_id, who } => {
+					let account = T::Lookup::lookup(who.clone())?;
+					let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &account);
+					let collateral = Self::vault_collateral(*currency_id, &account, collateral);
+					if !matches!(
+						Self::check_cdp_status(*currency_id, collateral, debit),
+						CDPStatus::Unsafe
+					) || Self::in_liquidation_grace(*currency_id, &account, collateral, debit)
+						|| T::EmergencyShutdown::is_shutdown()
+					{
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+						.priority(Self::liquidation_priority(*currency_id, collateral, debit))
+						.and_provides(("liquidate", currency_id, who))
+						.longevity(T::UnsignedLongevity::get())
+						.propagate(true)
+						.build()
+				}
+				Call::settle { currency_id, who } => {
+					let account = T::Lookup::lookup(who.clone())?;
+					let Position { debit, .. } = <LoansOf<T>>::positions(currency_id, account);
+					if debit.is_zero() || !T::EmergencyShutdown::is_shutdown() {
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides(("settle", currency_id, who))
+						.longevity(T::UnsignedLongevity::get())
+						.propagate(true)
+						.build()
+				}
+				Call::execute_stop_loss { currency_id, who } => {
+					let account = T::Lookup::lookup(who.clone())?;
+					let Position { debit, .. } = <LoansOf<T>>::positions(currency_id, &account);
+					// the stale order of the CDP without debit is valid to be cleared
+					let stale_order = debit.is_zero() && StopLossOrders::<T>::contains_key(currency_id, &account);
+					if !(stale_order || Self::is_stop_loss_triggered(*currency_id, &account))
+						|| T::EmergencyShutdown::is_shutdown()
+					{
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides(("execute_stop_loss", currency_id, who))
+						.longevity(T::UnsignedLongevity::get())
+						.propagate(true)
+						.build()
+				}
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn accumulate_interest(now_secs: u64, last_accumulation_secs: u64) -> u32 {
+		let mut count: u32 = 0;
+
+		if !T::EmergencyShutdown::is_shutdown() && !now_secs.is_zero() {
+			let interval_secs = now_secs.saturating_sub(last_accumulation_secs);
+
+			for currency_id in T::CollateralCurrencyIds::get() {
+				let rate_to_accumulate =
+					Self::compound_interest_rate(Self::get_interest_rate_per_sec(currency_id), interval_secs);
+				let total_debits = <LoansOf<T>>::total_positions(currency_id).debit;
+
+				if !rate_to_accumulate.is_zero() && !total_debits.is_zero() {
 					let debit_exchange_rate = Self::get_debit_exchange_rate(currency_id);
 					let debit_exchange_rate_increment = debit_exchange_rate.saturating_mul(rate_to_accumulate);
 					let issued_stable_coin_balance = debit_exchange_rate_increment.saturating_mul_int(total_debits);
 
-					// issue stablecoin to surplus pool
-					let res = <T as Config>::CDPTreasury::on_system_surplus(issued_stable_coin_balance);
+					// issue stablecoin to surplus pool of the debt denomination
+					let debt_denomination = Self::debt_denomination_of(currency_id);
+					let res =
+						<T as Config>::CDPTreasury::on_system_surplus_of(debt_denomination, issued_stable_coin_balance);
 					match res {
 						Ok(_) => {
+							// the revenue is accounted in the stable currency
+							if debt_denomination == T::GetStableCurrencyId::get() {
+								<T as Config>::CDPTreasury::on_revenue(
+									RevenueSource::StabilityFee,
+									issued_stable_coin_balance,
+								);
+								<T as Config>::CDPTreasury::on_collateral_surplus(
+									currency_id,
+									issued_stable_coin_balance,
+								);
+							}
 							// update exchange rate when issue success
 							let new_debit_exchange_rate =
 								debit_exchange_rate.saturating_add(debit_exchange_rate_increment);
 							DebitExchangeRate::<T>::insert(currency_id, new_debit_exchange_rate);
+							Self::deposit_event(Event::InterestAccumulated(
+								currency_id,
+								issued_stable_coin_balance,
+								new_debit_exchange_rate,
+							));
 						}
 						Err(e) => {
 							log::warn!(
@@ -592,6 +1668,143 @@ impl<T: Config> Pallet<T> {
 		count
 	}
 
+	/// The risk bucket of the ratio of `collateral` to `debit`, the logarithm
+	/// of the ratio in `RISK_BUCKETS_PER_DOUBLING` steps per doubling. Returns
+	/// `None` for the CDP without debit which is never liquidated.
+	pub fn risk_bucket(collateral: Balance, debit: Balance) -> Option<u32> {
+		if debit.is_zero() {
+			return None;
+		}
+		let ratio = Ratio::checked_from_rational(collateral, debit).unwrap_or_else(Ratio::max_value);
+		Some(Self::risk_bucket_of(ratio))
+	}
+
+	fn risk_bucket_of(ratio: Ratio) -> u32 {
+		let inner = ratio.into_inner();
+		if inner.is_zero() {
+			return Zero::zero();
+		}
+		let exponent = 127 - inner.leading_zeros();
+		let mantissa = if exponent >= 4 {
+			(inner >> (exponent - 4)) & 0xF
+		} else {
+			(inner << (4 - exponent)) & 0xF
+		} as u32;
+		// reserve the bucket zero for the CDPs without collateral
+		exponent * RISK_BUCKETS_PER_DOUBLING + mantissa + 1
+	}
+
+	/// Move the CDP to the risk bucket of its updated position in the
+	/// liquidation index, the CDP without debit is removed from the index.
+	fn update_liquidation_index(who: &T::AccountId, currency_id: CurrencyId) {
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+		let new_bucket = Self::risk_bucket(collateral, debit);
+		let old_bucket = Self::cdp_risk_buckets(currency_id, who);
+		if new_bucket == old_bucket {
+			return;
+		}
+
+		if let Some(bucket) = old_bucket {
+			LiquidationIndex::<T>::remove((currency_id, bucket), who);
+			RiskBucketSizes::<T>::mutate_exists(currency_id, bucket, |maybe_size| {
+				*maybe_size = maybe_size
+					.map(|size| size.saturating_sub(1))
+					.filter(|size| !size.is_zero());
+			});
+		}
+		match new_bucket {
+			Some(bucket) => {
+				LiquidationIndex::<T>::insert((currency_id, bucket), who, ());
+				RiskBucketSizes::<T>::mutate(currency_id, bucket, |size| *size = size.saturating_add(1));
+				CdpRiskBuckets::<T>::insert(currency_id, who, bucket);
+			}
+			None => CdpRiskBuckets::<T>::remove(currency_id, who),
+		}
+	}
+
+	/// The risk buckets of all collateral types which may hold unsafe CDPs,
+	/// the riskiest first. The CDP is unsafe if its ratio of collateral
+	/// amount to debit amount is below `liquidation_ratio * debit_exchange_rate
+	/// / price`, the buckets are ranked by how far they're below the bucket of
+	/// it.
+	fn liquidation_candidate_buckets() -> Vec<(CurrencyId, u32)> {
+		let mut candidate_buckets = T::CollateralCurrencyIds::get()
+			.into_iter()
+			.filter_map(|currency_id| {
+				let threshold = Self::get_liquidation_ratio(currency_id)
+					.saturating_mul(Self::get_debit_exchange_rate(currency_id))
+					.checked_div(&Self::get_collateral_price(currency_id)?)?;
+				Some((currency_id, Self::risk_bucket_of(threshold)))
+			})
+			.flat_map(|(currency_id, threshold_bucket)| {
+				RiskBucketSizes::<T>::iter_prefix(currency_id)
+					.filter(move |(bucket, _)| *bucket <= threshold_bucket)
+					.map(move |(bucket, _)| (threshold_bucket - bucket, currency_id, bucket))
+			})
+			.collect::<Vec<_>>();
+		candidate_buckets.sort_by(|a, b| b.cmp(a));
+		candidate_buckets
+			.into_iter()
+			.map(|(_, currency_id, bucket)| (currency_id, bucket))
+			.collect()
+	}
+
+	/// Walk the liquidation index from the riskiest buckets, and submit the
+	/// liquidations of the unsafe CDPs. At most `max_iterations` CDPs of the
+	/// index are visited and at most `max_liquidations` liquidations are
+	/// submitted, the walk continues from where it stops in the following
+	/// blocks. Returns the number of submitted liquidations.
+	fn submit_liquidations_by_risk(max_iterations: u32, max_liquidations: u32) -> u32 {
+		let mut cursor_record = StorageValueRef::persistent(OFFCHAIN_WORKER_LIQUIDATION_CURSOR);
+		let cursor = cursor_record.get::<(CurrencyId, u32, Vec<u8>)>().unwrap_or_default();
+		let candidate_buckets = Self::liquidation_candidate_buckets();
+
+		// continue from the cursor if its bucket is still a candidate, otherwise start
+		// from the riskiest bucket
+		let (start, mut start_key) = cursor
+			.and_then(|(currency_id, bucket, key)| {
+				candidate_buckets
+					.iter()
+					.position(|candidate| *candidate == (currency_id, bucket))
+					.map(|position| (position, Some(key)))
+			})
+			.unwrap_or((0, None));
+
+		let mut iteration_count: u32 = 0;
+		let mut submitted_count: u32 = 0;
+		for (currency_id, bucket) in candidate_buckets.into_iter().skip(start) {
+			let mut iterator = match start_key.take() {
+				Some(key) => LiquidationIndex::<T>::iter_prefix_from((currency_id, bucket), key),
+				None => LiquidationIndex::<T>::iter_prefix((currency_id, bucket)),
+			};
+
+			#[allow(clippy::while_let_on_iterator)]
+			while let Some((who, _)) = iterator.next() {
+				let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
+				let collateral = Self::vault_collateral(currency_id, &who, collateral);
+				// the CDPs in the grace window wait for the deadline
+				if matches!(
+					Self::check_cdp_status(currency_id, collateral, debit),
+					CDPStatus::Unsafe
+				) && !Self::in_liquidation_grace(currency_id, &who, collateral, debit)
+				{
+					submitted_count += 1;
+					Self::submit_unsigned_liquidation_tx(currency_id, who);
+				}
+
+				iteration_count += 1;
+				if iteration_count >= max_iterations || submitted_count >= max_liquidations {
+					cursor_record.set(&(currency_id, bucket, iterator.last_raw_key()));
+					return submitted_count;
+				}
+			}
+		}
+
+		// the walk is finished, start from the riskiest bucket in the next block
+		cursor_record.clear();
+		submitted_count
+	}
+
 	fn submit_unsigned_liquidation_tx(currency_id: CurrencyId, who: T::AccountId) {
 		let who = T::Lookup::unlookup(who);
 		let call = Call::<T>::liquidate {
@@ -622,6 +1835,21 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	fn submit_unsigned_stop_loss_tx(currency_id: CurrencyId, who: T::AccountId) {
+		let who = T::Lookup::unlookup(who);
+		let call = Call::<T>::execute_stop_loss {
+			currency_id,
+			who: who.clone(),
+		};
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "cdp-engine offchain worker",
+				"submit unsigned stop-loss tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed!",
+				who, currency_id,
+			);
+		}
+	}
+
 	fn _offchain_worker() -> Result<(), OffchainErr> {
 		let collateral_currency_ids = T::CollateralCurrencyIds::get();
 		if collateral_currency_ids.len().is_zero() {
@@ -658,9 +1886,22 @@ impl<T: Config> Pallet<T> {
 			.unwrap_or(Some(DEFAULT_MAX_ITERATIONS))
 			.unwrap_or(DEFAULT_MAX_ITERATIONS);
 
+		// get the max liquidations config
+		let max_liquidations = StorageValueRef::persistent(OFFCHAIN_WORKER_MAX_LIQUIDATIONS)
+			.get::<u32>()
+			.unwrap_or(Some(DEFAULT_MAX_LIQUIDATIONS))
+			.unwrap_or(DEFAULT_MAX_LIQUIDATIONS);
+
 		let currency_id = collateral_currency_ids[collateral_position as usize];
 		let is_shutdown = T::EmergencyShutdown::is_shutdown();
 
+		// liquidate unsafe CDPs before emergency shutdown occurs, the riskiest first
+		let mut submitted_count = if is_shutdown {
+			0
+		} else {
+			Self::submit_liquidations_by_risk(max_iterations, max_liquidations)
+		};
+
 		// If start key is Some(value) continue iterating from that point in storage otherwise start
 		// iterating from the beginning of <loans::Positons<T>>
 		let mut map_iterator = match start_key.clone() {
@@ -674,13 +1915,28 @@ impl<T: Config> Pallet<T> {
 
 		#[allow(clippy::while_let_on_iterator)]
 		while let Some((who, Position { collateral, debit })) = map_iterator.next() {
+			let collateral = Self::vault_collateral(currency_id, &who, collateral);
 			if !is_shutdown
 				&& matches!(
 					Self::check_cdp_status(currency_id, collateral, debit),
 					CDPStatus::Unsafe
 				) {
-				// liquidate unsafe CDPs before emergency shutdown occurs
-				Self::submit_unsigned_liquidation_tx(currency_id, who);
+				// the CDPs in the liquidation index are liquidated by risk, only the ones opened
+				// before the index are liquidated here
+				if submitted_count < max_liquidations
+					&& !CdpRiskBuckets::<T>::contains_key(currency_id, &who)
+					&& !Self::in_liquidation_grace(currency_id, &who, collateral, debit)
+				{
+					submitted_count += 1;
+					Self::submit_unsigned_liquidation_tx(currency_id, who);
+				}
+			} else if !is_shutdown
+				&& (Self::is_stop_loss_triggered(currency_id, &who)
+					|| (debit.is_zero() && StopLossOrders::<T>::contains_key(currency_id, &who)))
+			{
+				// execute the triggered stop-loss orders of safe CDPs, and clear the stale
+				// orders of CDPs without debit
+				Self::submit_unsigned_stop_loss_tx(currency_id, who);
 			} else if is_shutdown && !debit.is_zero() {
 				// settle CDPs with debit after emergency shutdown occurs.
 				Self::submit_unsigned_settlement_tx(currency_id, who);
@@ -727,9 +1983,428 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Revert the expired risk management params to their default, returns
+	/// the consumed weight.
+	fn expire_collateral_params(now: T::BlockNumber) -> Weight {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		let mut expired_count: u64 = 0;
+		for currency_id in collateral_currency_ids.iter() {
+			if let Some((expire_at, default_params)) = Self::collateral_params_expiries(currency_id) {
+				if now >= expire_at {
+					CollateralParams::<T>::insert(currency_id, default_params.clone());
+					CollateralParamsExpiries::<T>::remove(currency_id);
+					Self::deposit_event(Event::CollateralParamsExpired(*currency_id, default_params));
+					expired_count += 1;
+				}
+			}
+		}
+
+		T::DbWeight::get().reads_writes(collateral_currency_ids.len() as u64, expired_count.saturating_mul(2))
+	}
+
+	/// Schedule the changes of risk management params of the collateral type
+	/// after `RiskParamsUpdateDelay`, merged into the pending ones. No origin
+	/// bypasses the timelock.
+	fn schedule_risk_params_changes(
+		currency_id: CurrencyId,
+		changes: RiskParamsChanges<T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(
+			T::CollateralCurrencyIds::get().contains(&currency_id),
+			Error::<T>::InvalidCollateralType,
+		);
+
+		let changes = match Self::pending_risk_params(currency_id) {
+			Some((_, pending)) => pending.merge(changes),
+			None => changes,
+		};
+		Self::check_risk_params_changes(currency_id, &changes)?;
+
+		let delay = T::RiskParamsUpdateDelay::get();
+		if delay.is_zero() {
+			PendingRiskParams::<T>::remove(currency_id);
+			Self::apply_risk_params_changes(currency_id, changes);
+		} else {
+			let effective_at = <frame_system::Pallet<T>>::block_number().saturating_add(delay);
+			PendingRiskParams::<T>::insert(currency_id, (effective_at, changes));
+			Self::deposit_event(Event::RiskParamsUpdateScheduled(currency_id, effective_at));
+		}
+		Ok(())
+	}
+
+	/// Check the risk management params of the collateral type are valid
+	/// after the changes.
+	fn check_risk_params_changes(
+		currency_id: CurrencyId,
+		changes: &RiskParamsChanges<T::BlockNumber>,
+	) -> DispatchResult {
+		let maximum_total_debit_value = match changes.maximum_total_debit_value {
+			Change::NewValue(val) => val,
+			Change::NoChange => Self::maximum_total_debit_value(currency_id),
+		};
+		let soft_debit_cap = match changes.soft_debit_cap {
+			Change::NewValue(cap) => cap,
+			Change::NoChange => Self::soft_debit_caps(currency_id),
+		};
+		if let Some(cap) = soft_debit_cap {
+			ensure!(
+				cap.soft_cap <= maximum_total_debit_value,
+				Error::<T>::SoftCapAboveHardCap
+			);
+		}
+		Ok(())
+	}
+
+	/// Apply the pending changes of risk management params whose timelock
+	/// has passed. The changes are validated again against the params when
+	/// they take effect, and rejected if invalid.
+	fn apply_pending_risk_params(now: T::BlockNumber) -> Weight {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		let mut applied_count: u64 = 0;
+		for currency_id in collateral_currency_ids.iter() {
+			if let Some((effective_at, changes)) = Self::pending_risk_params(currency_id) {
+				if now >= effective_at {
+					PendingRiskParams::<T>::remove(currency_id);
+					if Self::check_risk_params_changes(*currency_id, &changes).is_ok() {
+						Self::apply_risk_params_changes(*currency_id, changes);
+					} else {
+						Self::deposit_event(Event::PendingRiskParamsRejected(*currency_id));
+					}
+					applied_count += 1;
+				}
+			}
+		}
+
+		T::DbWeight::get().reads_writes(
+			(collateral_currency_ids.len() as u64).saturating_add(applied_count.saturating_mul(4)),
+			applied_count.saturating_mul(7),
+		)
+	}
+
+	/// Apply the pending changes of risk management params of the collateral
+	/// type without waiting for the timelock, only for previewing a proposal
+	/// in a transaction that is rolled back.
+	pub fn apply_pending_risk_params_now(currency_id: CurrencyId) {
+		if let Some((_, changes)) = PendingRiskParams::<T>::take(currency_id) {
+			if Self::check_risk_params_changes(currency_id, &changes).is_ok() {
+				Self::apply_risk_params_changes(currency_id, changes);
+			}
+		}
+	}
+
+	fn apply_risk_params_changes(currency_id: CurrencyId, changes: RiskParamsChanges<T::BlockNumber>) {
+		let mut collateral_params = Self::collateral_params(currency_id);
+		if let Change::NewValue(update) = changes.interest_rate_per_sec {
+			collateral_params.interest_rate_per_sec = update;
+			Self::deposit_event(Event::InterestRatePerSecUpdated(currency_id, update));
+		}
+		if let Change::NewValue(update) = changes.liquidation_ratio {
+			collateral_params.liquidation_ratio = update;
+			Self::deposit_event(Event::LiquidationRatioUpdated(currency_id, update));
+		}
+		if let Change::NewValue(update) = changes.liquidation_penalty {
+			collateral_params.liquidation_penalty = update;
+			Self::deposit_event(Event::LiquidationPenaltyUpdated(currency_id, update));
+		}
+		if let Change::NewValue(update) = changes.required_collateral_ratio {
+			collateral_params.required_collateral_ratio = update;
+			Self::deposit_event(Event::RequiredCollateralRatioUpdated(currency_id, update));
+		}
+		if let Change::NewValue(val) = changes.maximum_total_debit_value {
+			collateral_params.maximum_total_debit_value = val;
+			Self::deposit_event(Event::MaximumTotalDebitValueUpdated(currency_id, val));
+		}
+		CollateralParams::<T>::insert(currency_id, collateral_params);
+
+		// the pending expiry was set for the replaced params
+		if CollateralParamsExpiries::<T>::take(currency_id).is_some() {
+			Self::deposit_event(Event::CollateralParamsExpiryUpdated(currency_id, None));
+		}
+
+		if let Change::NewValue(interest_rate_curve) = changes.interest_rate_curve {
+			InterestRateCurves::<T>::set(currency_id, interest_rate_curve);
+			Self::deposit_event(Event::InterestRateCurveUpdated(currency_id, interest_rate_curve));
+			if interest_rate_curve.is_some() {
+				Self::update_interest_rate_by_curve(currency_id);
+			} else if CurveInterestRates::<T>::take(currency_id).is_some() {
+				Self::deposit_event(Event::InterestRatePerSecUpdated(
+					currency_id,
+					Self::collateral_params(currency_id).interest_rate_per_sec,
+				));
+			}
+		}
+		if let Change::NewValue(soft_debit_cap) = changes.soft_debit_cap {
+			SoftDebitCaps::<T>::set(currency_id, soft_debit_cap);
+			Self::deposit_event(Event::SoftDebitCapUpdated(currency_id, soft_debit_cap));
+		}
+		if let Change::NewValue(minimum_debit_value) = changes.minimum_debit_value {
+			MinimumDebitValues::<T>::set(currency_id, minimum_debit_value);
+			Self::deposit_event(Event::MinimumDebitValueUpdated(currency_id, minimum_debit_value));
+		}
+		if let Change::NewValue(liquidation_grace) = changes.liquidation_grace {
+			LiquidationGraces::<T>::set(currency_id, liquidation_grace);
+			Self::deposit_event(Event::LiquidationGraceUpdated(currency_id, liquidation_grace));
+		}
+	}
+
+	/// Record the checkpoints of the debit exchange rates of all collateral
+	/// types at the interval.
+	fn record_debit_exchange_rate_checkpoints(now: T::BlockNumber) -> Weight {
+		let interval = T::DebitExchangeRateCheckpointInterval::get();
+		if interval.is_zero() || !(now % interval).is_zero() {
+			return 0;
+		}
+
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		for currency_id in collateral_currency_ids.iter() {
+			let debit_exchange_rate = Self::get_debit_exchange_rate(*currency_id);
+			DebitExchangeRateCheckpoints::<T>::mutate(currency_id, |checkpoints| {
+				if !checkpoints.is_empty() && checkpoints.len() as u32 >= T::MaxDebitExchangeRateCheckpoints::get() {
+					checkpoints.remove(0);
+				}
+				let _ = checkpoints.try_push((now, debit_exchange_rate));
+			});
+		}
+
+		let count = collateral_currency_ids.len() as u64;
+		T::DbWeight::get().reads_writes(count.saturating_mul(2), count)
+	}
+
+	/// Recompute the extra interest rates of the collateral types with the
+	/// interest rate curve at the period.
+	fn recompute_interest_rates(now: T::BlockNumber) -> Weight {
+		let period = T::InterestRateCurvePeriod::get();
+		if period.is_zero() || !(now % period).is_zero() {
+			return 0;
+		}
+
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		for currency_id in collateral_currency_ids.iter() {
+			Self::update_interest_rate_by_curve(*currency_id);
+		}
+
+		let count = collateral_currency_ids.len() as u64;
+		T::DbWeight::get().reads_writes(count.saturating_mul(4), count)
+	}
+
+	/// Update the extra interest rate per sec of the collateral type computed
+	/// by its interest rate curve, if the curve is set and the rate is
+	/// changed. The extra interest rate per sec of the collateral params set
+	/// by governance is kept.
+	fn update_interest_rate_by_curve(currency_id: CurrencyId) {
+		if let Some(interest_rate_per_sec) = Self::curve_interest_rate_per_sec(currency_id) {
+			if Self::curve_interest_rates(currency_id) != Some(interest_rate_per_sec) {
+				CurveInterestRates::<T>::insert(currency_id, interest_rate_per_sec);
+				Self::deposit_event(Event::InterestRatePerSecUpdated(
+					currency_id,
+					Some(interest_rate_per_sec),
+				));
+			}
+		}
+	}
+
+	/// Get the utilization of the hard cap of total debit value of the
+	/// collateral type, which is full if the hard cap is zero.
+	pub fn utilization(currency_id: CurrencyId) -> Ratio {
+		let total_debit_value = Self::get_debit_value(currency_id, <LoansOf<T>>::total_positions(currency_id).debit);
+		Ratio::checked_from_rational(total_debit_value, Self::maximum_total_debit_value(currency_id))
+			.unwrap_or_else(Ratio::one)
+			.min(Ratio::one())
+	}
+
+	/// Get the extra interest rate per sec of the collateral type computed by
+	/// its interest rate curve at the current utilization, `None` if the curve
+	/// is not set.
+	pub fn curve_interest_rate_per_sec(currency_id: CurrencyId) -> Option<Rate> {
+		Self::interest_rate_curves(currency_id).map(|curve| {
+			curve
+				.base_rate
+				.saturating_add(curve.slope.saturating_mul(Self::utilization(currency_id)))
+		})
+	}
+
+	/// Update the levels of the total debit values of all collateral types
+	/// against their caps.
+	fn update_debit_cap_levels() -> Weight {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		let mut changed_count: u64 = 0;
+		for currency_id in collateral_currency_ids.iter() {
+			let level = Self::get_debit_cap_level(*currency_id);
+			if level != Self::debit_cap_levels(currency_id) {
+				DebitCapLevels::<T>::insert(currency_id, level);
+				Self::deposit_event(Event::DebitCapLevelChanged(*currency_id, level));
+				changed_count += 1;
+			}
+		}
+
+		T::DbWeight::get().reads_writes((collateral_currency_ids.len() as u64).saturating_mul(5), changed_count)
+	}
+
+	/// Snapshot the system leverage if its cap is set, so the debit increases
+	/// don't value all types of collateral.
+	fn update_system_leverage_snapshot() -> Weight {
+		if Self::max_system_leverage().is_none() {
+			SystemLeverageSnapshot::<T>::kill();
+			return T::DbWeight::get().reads_writes(1, 1);
+		}
+
+		SystemLeverageSnapshot::<T>::set(Self::system_leverage());
+		let collateral_count = T::CollateralCurrencyIds::get().len() as u64;
+		T::DbWeight::get().reads_writes(collateral_count.saturating_mul(5).saturating_add(1), 1)
+	}
+
+	/// Record the fallback prices of the collateral types required by their
+	/// oracle failure policies.
+	fn record_fallback_prices(now: T::BlockNumber) -> Weight {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		let mut recorded_count: u64 = 0;
+		for currency_id in collateral_currency_ids.iter() {
+			let debt_denomination = Self::debt_denomination_of(*currency_id);
+			match Self::oracle_failure_policies(currency_id) {
+				OracleFailurePolicy::Freeze => {}
+				OracleFailurePolicy::LastGoodPrice { .. } => {
+					if let Some(price) = T::PriceSource::get_relative_price(*currency_id, debt_denomination) {
+						LastGoodPrices::<T>::insert(currency_id, (price, now));
+						recorded_count += 1;
+					}
+				}
+				OracleFailurePolicy::DexTwap { window, .. } => {
+					if let Some(observation) =
+						T::DEXPriceCumulative::get_cumulative_price(*currency_id, debt_denomination)
+					{
+						let last_observation = Self::dex_twap_observations(currency_id);
+						// average the cumulative prices once per window, so the price can only be
+						// moved by holding the DEX price over the whole window
+						if last_observation.map_or(true, |(_, last_observed)| {
+							now.saturating_sub(last_observed) >= window.max(1).into()
+						}) {
+							if let Some(twap_price) = last_observation
+								.and_then(|last_observation| average_cumulative_price(last_observation, observation))
+							{
+								DexTwapPrices::<T>::insert(currency_id, twap_price);
+							}
+							DexTwapObservations::<T>::insert(currency_id, observation);
+							recorded_count += 1;
+						}
+					}
+				}
+			}
+		}
+
+		T::DbWeight::get().reads_writes((collateral_currency_ids.len() as u64).saturating_mul(3), recorded_count)
+	}
+
+	/// Get the stable asset the debit of the collateral type is denominated
+	/// in.
+	pub fn debt_denomination_of(currency_id: CurrencyId) -> CurrencyId {
+		Self::collateral_debt_denominations(currency_id).unwrap_or_else(T::GetStableCurrencyId::get)
+	}
+
+	/// Ensure the debit of the collateral type is denominated in the stable
+	/// currency.
+	fn ensure_stable_denominated(currency_id: CurrencyId) -> DispatchResult {
+		ensure!(
+			Self::collateral_debt_denominations(currency_id).is_none(),
+			Error::<T>::NotSupportedForDebtDenomination
+		);
+		Ok(())
+	}
+
+	/// Get the price of the collateral type in its debt denomination, falls
+	/// back to its oracle failure policy if the oracle price is unavailable.
+	pub fn get_collateral_price(currency_id: CurrencyId) -> Option<Price> {
+		T::PriceSource::get_relative_price(currency_id, Self::debt_denomination_of(currency_id)).or_else(|| {
+			match Self::oracle_failure_policies(currency_id) {
+				OracleFailurePolicy::Freeze => None,
+				OracleFailurePolicy::LastGoodPrice { max_age } => {
+					Self::last_good_prices(currency_id).and_then(|(price, recorded_at)| {
+						if <frame_system::Pallet<T>>::block_number().saturating_sub(recorded_at) <= max_age {
+							Some(price)
+						} else {
+							None
+						}
+					})
+				}
+				OracleFailurePolicy::DexTwap { haircut, .. } => Self::dex_twap_prices(currency_id)
+					.map(|twap_price| twap_price.saturating_mul(Ratio::one().saturating_sub(haircut))),
+			}
+		})
+	}
+
+	/// Get the level of the current total debit value of the collateral type
+	/// against its caps.
+	pub fn get_debit_cap_level(currency_id: CurrencyId) -> DebitCapLevel {
+		let total_debit_value = Self::get_debit_value(currency_id, <LoansOf<T>>::total_positions(currency_id).debit);
+		if !total_debit_value.is_zero() && total_debit_value >= Self::maximum_total_debit_value(currency_id) {
+			DebitCapLevel::ReachedHardCap
+		} else if Self::soft_debit_caps(currency_id).map_or(false, |cap| total_debit_value > cap.soft_cap) {
+			DebitCapLevel::AboveSoftCap
+		} else {
+			DebitCapLevel::BelowSoftCap
+		}
+	}
+
+	/// Get the interest accrued by the debit of the CDP between the debit
+	/// exchange rate checkpoints at `from` and `to`, `None` if either
+	/// checkpoint is not found or the debit snapshots since `from` are
+	/// dropped.
+	pub fn accrued_interest(
+		currency_id: CurrencyId,
+		who: &T::AccountId,
+		from: T::BlockNumber,
+		to: T::BlockNumber,
+	) -> Option<Balance> {
+		let checkpoints = Self::debit_exchange_rate_checkpoints(currency_id);
+		let checkpoint_at = |at: T::BlockNumber| {
+			checkpoints
+				.iter()
+				.find(|(block_number, _)| *block_number == at)
+				.map(|(_, debit_exchange_rate)| *debit_exchange_rate)
+		};
+		let from_debit_exchange_rate = checkpoint_at(from)?;
+		let to_debit_exchange_rate = checkpoint_at(to)?;
+
+		let snapshots = Self::debit_snapshots(currency_id, who);
+		if snapshots.len() as u32 >= T::MaxDebitExchangeRateCheckpoints::get()
+			&& snapshots
+				.first()
+				.map_or(false, |(block_number, _, _)| *block_number > from)
+		{
+			return None;
+		}
+
+		// accrue the interest by the debit in effect between every two updates of
+		// the debit
+		let mut interest: Balance = Zero::zero();
+		let mut debit_exchange_rate = from_debit_exchange_rate;
+		for (_, snapshot_debit_exchange_rate, debit) in snapshots
+			.iter()
+			.filter(|(block_number, _, _)| *block_number > from && *block_number <= to)
+		{
+			interest = interest.saturating_add(
+				snapshot_debit_exchange_rate
+					.saturating_sub(debit_exchange_rate)
+					.saturating_mul_int(*debit),
+			);
+			debit_exchange_rate = *snapshot_debit_exchange_rate;
+		}
+		let debit = snapshots
+			.iter()
+			.find(|(block_number, _, _)| *block_number > to)
+			.map(|(_, _, debit)| *debit)
+			.unwrap_or_else(|| <LoansOf<T>>::positions(currency_id, who).debit);
+
+		Some(
+			interest.saturating_add(
+				to_debit_exchange_rate
+					.saturating_sub(debit_exchange_rate)
+					.saturating_mul_int(debit),
+			),
+		)
+	}
+
 	pub fn check_cdp_status(currency_id: CurrencyId, collateral_amount: Balance, debit_amount: Balance) -> CDPStatus {
-		let stable_currency_id = T::GetStableCurrencyId::get();
-		if let Some(feed_price) = T::PriceSource::get_relative_price(currency_id, stable_currency_id) {
+		if let Some(feed_price) = Self::get_collateral_price(currency_id) {
 			let collateral_ratio =
 				Self::calculate_collateral_ratio(currency_id, collateral_amount, debit_amount, feed_price);
 			if collateral_ratio < Self::get_liquidation_ratio(currency_id) {
@@ -742,19 +2417,104 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// The priority of the unsigned liquidation of the CDP, the base priority
+	/// plus the shortfall of its collateral ratio below the liquidation ratio
+	/// in basis points, so that the riskier CDPs are liquidated first.
+	pub fn liquidation_priority(
+		currency_id: CurrencyId,
+		collateral_amount: Balance,
+		debit_amount: Balance,
+	) -> TransactionPriority {
+		let shortfall = Self::get_collateral_price(currency_id).map_or_else(Ratio::zero, |feed_price| {
+			Self::get_liquidation_ratio(currency_id).saturating_sub(Self::calculate_collateral_ratio(
+				currency_id,
+				collateral_amount,
+				debit_amount,
+				feed_price,
+			))
+		});
+		T::UnsignedPriority::get().saturating_add(shortfall.saturating_mul_int(10_000))
+	}
+
+	/// The number of the CDPs of `currency_id` that are unsafe under the
+	/// current price and risk parameters, counting their extra collaterals.
+	/// It iterates all the positions of the collateral type, only for the
+	/// runtime api.
+	pub fn unsafe_cdp_count(currency_id: CurrencyId) -> u32 {
+		<loans::Positions<T>>::iter_prefix(currency_id)
+			.filter(|(who, Position { collateral, debit })| {
+				!debit.is_zero()
+					&& Self::check_cdp_status(
+						currency_id,
+						Self::vault_collateral(currency_id, who, *collateral),
+						*debit,
+					) == CDPStatus::Unsafe
+			})
+			.count() as u32
+	}
+
 	pub fn maximum_total_debit_value(currency_id: CurrencyId) -> Balance {
 		Self::collateral_params(currency_id).maximum_total_debit_value
 	}
 
+	pub fn get_minimum_debit_value(currency_id: CurrencyId) -> Balance {
+		Self::minimum_debit_values(currency_id).unwrap_or_else(T::MinimumDebitValue::get)
+	}
+
+	/// Check whether the collateral ratio of the CDP is below the hard
+	/// liquidation ratio of the grace window.
+	fn below_hard_liquidation_ratio(
+		currency_id: CurrencyId,
+		collateral_amount: Balance,
+		debit_amount: Balance,
+		liquidation_grace: &LiquidationGrace<T::BlockNumber>,
+	) -> bool {
+		Self::get_collateral_price(currency_id).map_or(false, |feed_price| {
+			Self::calculate_collateral_ratio(currency_id, collateral_amount, debit_amount, feed_price)
+				< liquidation_grace.hard_liquidation_ratio
+		})
+	}
+
+	/// Check whether the unsafe CDP is waiting for the deadline of its grace
+	/// window.
+	pub fn in_liquidation_grace(
+		currency_id: CurrencyId,
+		who: &T::AccountId,
+		collateral_amount: Balance,
+		debit_amount: Balance,
+	) -> bool {
+		match (
+			Self::liquidation_graces(currency_id),
+			Self::unsafe_cdp_deadlines(currency_id, who),
+		) {
+			(Some(liquidation_grace), Some(deadline)) => {
+				<frame_system::Pallet<T>>::block_number() < deadline
+					&& !Self::below_hard_liquidation_ratio(
+						currency_id,
+						collateral_amount,
+						debit_amount,
+						&liquidation_grace,
+					)
+			}
+			_ => false,
+		}
+	}
+
 	pub fn required_collateral_ratio(currency_id: CurrencyId) -> Option<Ratio> {
 		Self::collateral_params(currency_id).required_collateral_ratio
 	}
 
 	pub fn get_interest_rate_per_sec(currency_id: CurrencyId) -> Rate {
-		Self::collateral_params(currency_id)
-			.interest_rate_per_sec
+		// the rate computed by the interest rate curve overrides the rate of the collateral params
+		let interest_rate_per_sec = Self::curve_interest_rates(currency_id)
+			.or_else(|| Self::collateral_params(currency_id).interest_rate_per_sec)
 			.unwrap_or_default()
-			.saturating_add(Self::global_interest_rate_per_sec())
+			.saturating_add(Self::global_interest_rate_per_sec());
+
+		match (Self::debit_cap_levels(currency_id), Self::soft_debit_caps(currency_id)) {
+			(DebitCapLevel::BelowSoftCap, _) | (_, None) => interest_rate_per_sec,
+			(_, Some(cap)) => interest_rate_per_sec.saturating_mul(cap.interest_rate_multiplier),
+		}
 	}
 
 	pub fn compound_interest_rate(rate_per_sec: Rate, secs: u64) -> Rate {
@@ -764,6 +2524,30 @@ impl<T: Config> Pallet<T> {
 			.saturating_sub(Rate::one())
 	}
 
+	/// Get the annual interest rate of specific collateral type compounded
+	/// from its interest rate per sec.
+	pub fn get_annual_interest_rate(currency_id: CurrencyId) -> Rate {
+		Self::compound_interest_rate(Self::get_interest_rate_per_sec(currency_id), SECONDS_PER_YEAR)
+	}
+
+	/// Convert the annual rate to the max interest rate per sec which doesn't
+	/// compound above it in a year.
+	pub fn annual_to_per_sec_rate(annual_rate: Rate) -> Rate {
+		// the compound rate is never below the simple rate, so the simple rate is
+		// the upper bound
+		let mut low: u128 = 0;
+		let mut high: u128 = annual_rate.into_inner() / u128::from(SECONDS_PER_YEAR);
+		while low < high {
+			let mid = high - (high - low) / 2;
+			if Self::compound_interest_rate(Rate::from_inner(mid), SECONDS_PER_YEAR) <= annual_rate {
+				low = mid;
+			} else {
+				high = mid - 1;
+			}
+		}
+		Rate::from_inner(low)
+	}
+
 	pub fn get_liquidation_ratio(currency_id: CurrencyId) -> Ratio {
 		Self::collateral_params(currency_id)
 			.liquidation_ratio
@@ -784,6 +2568,50 @@ impl<T: Config> Pallet<T> {
 		crate::DebitExchangeRateConvertor::<T>::convert((currency_id, debit_balance))
 	}
 
+	/// Convert the debit value to the debit balance by the debit exchange
+	/// rate of the collateral type.
+	pub fn convert_to_debit_balance(currency_id: CurrencyId, debit_value: Balance) -> Balance {
+		Self::get_debit_exchange_rate(currency_id)
+			.reciprocal()
+			.map_or(Zero::zero(), |rate| rate.saturating_mul_int(debit_value))
+	}
+
+	/// Get the total debit value relative to the total collateral value at
+	/// oracle prices of all types of collateral, `None` if the total
+	/// collateral value is zero. The collateral types without feed price of
+	/// the collateral or the debt denomination are left out.
+	pub fn system_leverage() -> Option<Ratio> {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let (total_debit_value, total_collateral_value) = T::CollateralCurrencyIds::get().into_iter().fold(
+			(Balance::zero(), Balance::zero()),
+			|(debit_value, collateral_value), currency_id| {
+				// value the debit of other debt denominations in the stable currency
+				let debt_denomination = Self::debt_denomination_of(currency_id);
+				let denomination_price = if debt_denomination == stable_currency_id {
+					Some(Price::one())
+				} else {
+					T::PriceSource::get_relative_price(debt_denomination, stable_currency_id)
+				};
+				match (
+					T::PriceSource::get_relative_price(currency_id, stable_currency_id),
+					denomination_price,
+				) {
+					(Some(price), Some(denomination_price)) => {
+						let Position { collateral, debit } = <LoansOf<T>>::total_positions(currency_id);
+						(
+							debit_value.saturating_add(
+								denomination_price.saturating_mul_int(Self::get_debit_value(currency_id, debit)),
+							),
+							collateral_value.saturating_add(price.saturating_mul_int(collateral)),
+						)
+					}
+					_ => (debit_value, collateral_value),
+				}
+			},
+		);
+		Ratio::checked_from_rational(total_debit_value, total_collateral_value)
+	}
+
 	pub fn calculate_collateral_ratio(
 		currency_id: CurrencyId,
 		collateral_balance: Balance,
@@ -796,38 +2624,199 @@ impl<T: Config> Pallet<T> {
 		Ratio::checked_from_rational(locked_collateral_value, debit_value).unwrap_or_else(Ratio::max_value)
 	}
 
-	pub fn adjust_position(
+	pub fn adjust_position(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+		debit_adjustment: Amount,
+	) -> DispatchResult {
+		ensure!(
+			T::CollateralCurrencyIds::get().contains(&currency_id),
+			Error::<T>::InvalidCollateralType,
+		);
+		<LoansOf<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)?;
+		Self::clear_stop_loss_if_no_debit(who, currency_id);
+		Ok(())
+	}
+
+	/// Adjust the extra collateral of `extra_currency_id` backing the CDP of
+	/// `currency_id`.
+	pub fn adjust_extra_collateral(
 		who: &T::AccountId,
 		currency_id: CurrencyId,
+		extra_currency_id: CurrencyId,
 		collateral_adjustment: Amount,
-		debit_adjustment: Amount,
 	) -> DispatchResult {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
 		ensure!(
-			T::CollateralCurrencyIds::get().contains(&currency_id),
+			collateral_currency_ids.contains(&currency_id) && collateral_currency_ids.contains(&extra_currency_id),
 			Error::<T>::InvalidCollateralType,
 		);
-		<LoansOf<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)?;
-		Ok(())
+		<LoansOf<T>>::adjust_extra_collateral(who, currency_id, extra_currency_id, collateral_adjustment)
+	}
+
+	/// Get the maximum debit value that can be additionally issued to the
+	/// CDP of `who`, derived from the checks of the debit issuance: the
+	/// pauses, the system leverage cap, the collateral ratios, the minimum
+	/// debit value, the hard cap and the cap per account of the collateral
+	/// type.
+	pub fn max_additional_debit_value(currency_id: CurrencyId, who: &T::AccountId) -> Balance {
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+		let total_debit = <LoansOf<T>>::total_positions(currency_id).debit;
+		let is_issuable = |debit_increase: Balance| -> bool {
+			let new_debit = debit.saturating_add(debit_increase);
+			Self::check_debit_per_account(currency_id, who, new_debit).is_ok()
+				&& Self::check_debit_cap(currency_id, total_debit.saturating_add(debit_increase)).is_ok()
+				&& Self::check_vault_valid(currency_id, who, collateral, new_debit, true).is_ok()
+		};
+
+		// the minimum debit value is the only lower bound, the other checks are
+		// upper bounds, so the issuable increases are a range
+		let minimum_debit_value = Self::get_minimum_debit_value(currency_id);
+		let mut low = Self::convert_to_debit_balance(currency_id, minimum_debit_value)
+			.saturating_sub(debit)
+			.max(One::one());
+		// round up the debit balance of the minimum debit value
+		while Self::get_debit_value(currency_id, debit.saturating_add(low)) < minimum_debit_value
+			&& low < Balance::max_value()
+		{
+			low = low.saturating_add(One::one());
+		}
+		if !is_issuable(low) {
+			return Zero::zero();
+		}
+
+		// binary search the max issuable increase under the hard cap
+		let mut high = Self::convert_to_debit_balance(currency_id, Self::maximum_total_debit_value(currency_id))
+			.saturating_sub(total_debit)
+			.max(low);
+		while low < high {
+			let mid = high - (high - low) / 2;
+			if is_issuable(mid) {
+				low = mid;
+			} else {
+				high = mid - 1;
+			}
+		}
+		Self::get_debit_value(currency_id, low)
+	}
+
+	// the CDP topped up out of the unsafe status ends its grace window
+	fn end_grace_if_not_unsafe(who: &T::AccountId, currency_id: CurrencyId) {
+		if UnsafeCdpDeadlines::<T>::contains_key(currency_id, who) {
+			let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+			if !matches!(
+				Self::check_cdp_status(currency_id, Self::vault_collateral(currency_id, who, collateral), debit),
+				CDPStatus::Unsafe
+			) {
+				UnsafeCdpDeadlines::<T>::remove(currency_id, who);
+			}
+		}
+	}
+
+	// the stop-loss order of the CDP whose debit is repaid in full is stale,
+	// clear it so it won't be triggered after the CDP borrows again
+	fn clear_stop_loss_if_no_debit(who: &T::AccountId, currency_id: CurrencyId) {
+		if StopLossOrders::<T>::contains_key(currency_id, who)
+			&& <LoansOf<T>>::positions(currency_id, who).debit.is_zero()
+		{
+			StopLossOrders::<T>::remove(currency_id, who);
+			Self::deposit_event(Event::StopLossOrderCancelled(currency_id, who.clone()));
+		}
+	}
+
+	/// Get the collateral of the CDP including its extra collaterals, the
+	/// extra collaterals are converted to the amount of `currency_id` at the
+	/// oracle prices, and reduced by the ratio of the liquidation ratio of
+	/// `currency_id` to their own if it's riskier. The extra collateral
+	/// without price, or of the collateral type closed to debit, is not
+	/// counted.
+	pub fn vault_collateral(currency_id: CurrencyId, who: &T::AccountId, collateral: Balance) -> Balance {
+		let extra_collaterals = <LoansOf<T>>::extra_collaterals(currency_id, who);
+		if extra_collaterals.is_empty() {
+			return collateral;
+		}
+
+		let liquidation_ratio = Self::get_liquidation_ratio(currency_id);
+		extra_collaterals
+			.into_iter()
+			.fold(collateral, |total, (extra_currency_id, amount)| {
+				if Self::maximum_total_debit_value(extra_currency_id).is_zero() {
+					return total;
+				}
+				let haircut = liquidation_ratio
+					.checked_div(&Self::get_liquidation_ratio(extra_currency_id))
+					.map_or_else(Ratio::zero, |haircut| haircut.min(Ratio::one()));
+				let extra_amount = T::PriceSource::get_relative_price(extra_currency_id, currency_id)
+					.map_or_else(Zero::zero, |price| price.saturating_mul_int(amount));
+				total.saturating_add(haircut.saturating_mul_int(extra_amount))
+			})
 	}
 
-	// settle cdp has debit when emergency shutdown
-	pub fn settle_cdp_has_debit(who: T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+	/// Get the value of the collateral in the stable currency at the oracle
+	/// price, zero if the price is unavailable.
+	fn get_collateral_value(currency_id: CurrencyId, amount: Balance) -> Balance {
+		Self::get_collateral_price(currency_id).map_or(Zero::zero(), |price| price.saturating_mul_int(amount))
+	}
+
+	// settle cdp has debit when emergency shutdown, return the consumed weight
+	#[transactional]
+	pub fn settle_cdp_has_debit(who: T::AccountId, currency_id: CurrencyId) -> Result<Weight, DispatchError> {
 		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
 		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
 
 		// confiscate collateral in cdp to cdp treasury
 		// and decrease CDP's debit to zero
-		let settle_price: Price = T::PriceSource::get_relative_price(T::GetStableCurrencyId::get(), currency_id)
-			.ok_or(Error::<T>::InvalidFeedPrice)?;
+		let settle_price: Price =
+			T::PriceSource::get_relative_price(Self::debt_denomination_of(currency_id), currency_id)
+				.ok_or(Error::<T>::InvalidFeedPrice)?;
 		let bad_debt_value = Self::get_debit_value(currency_id, debit);
 		let confiscate_collateral_amount =
 			sp_std::cmp::min(settle_price.saturating_mul_int(bad_debt_value), collateral);
 
+		// the bad debt not covered by the collateral is covered by the extra
+		// collaterals, otherwise they are withdrawn by the owner once the debit is
+		// settled
+		let mut uncovered_debt_value = settle_price.reciprocal().map_or_else(Zero::zero, |reciprocal_price| {
+			reciprocal_price.saturating_mul_int(
+				settle_price
+					.saturating_mul_int(bad_debt_value)
+					.saturating_sub(confiscate_collateral_amount),
+			)
+		});
+		let mut extra_count: u64 = 0;
+		for (extra_currency_id, amount) in <LoansOf<T>>::extra_collaterals(currency_id, &who) {
+			if uncovered_debt_value.is_zero() {
+				break;
+			}
+			let extra_price =
+				T::PriceSource::get_relative_price(extra_currency_id, Self::debt_denomination_of(currency_id))
+					.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let confiscate_extra_amount = extra_price
+				.reciprocal()
+				.map_or(amount, |reciprocal_price| {
+					reciprocal_price.saturating_mul_int(uncovered_debt_value)
+				})
+				.min(amount);
+			<LoansOf<T>>::confiscate_extra_collateral(&who, currency_id, extra_currency_id, confiscate_extra_amount)?;
+			uncovered_debt_value =
+				uncovered_debt_value.saturating_sub(extra_price.saturating_mul_int(confiscate_extra_amount));
+			extra_count += 1;
+		}
+
 		// confiscate collateral and all debit
 		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, confiscate_collateral_amount, debit)?;
+		Self::clear_stop_loss_if_no_debit(&who, currency_id);
 
 		Self::deposit_event(Event::SettleCDPInDebit(currency_id, who));
-		Ok(())
+		// no collateral is transferred to cdp treasury if there's nothing to confiscate
+		let extra_weight =
+			T::DbWeight::get().reads_writes(extra_count.saturating_mul(6), extra_count.saturating_mul(6));
+		if confiscate_collateral_amount.is_zero() {
+			Ok(T::WeightInfo::settle_without_collateral().saturating_add(extra_weight))
+		} else {
+			Ok(T::WeightInfo::settle().saturating_add(extra_weight))
+		}
 	}
 
 	// close cdp has debit by swap collateral to exact debit
@@ -838,15 +2827,24 @@ impl<T: Config> Pallet<T> {
 		max_collateral_amount: Balance,
 		maybe_path: Option<&[CurrencyId]>,
 	) -> DispatchResult {
+		Self::ensure_stable_denominated(currency_id)?;
 		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
 		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
 		ensure!(
-			matches!(Self::check_cdp_status(currency_id, collateral, debit), CDPStatus::Safe),
+			matches!(
+				Self::check_cdp_status(
+					currency_id,
+					Self::vault_collateral(currency_id, &who, collateral),
+					debit
+				),
+				CDPStatus::Safe
+			),
 			Error::<T>::MustBeSafe
 		);
 
 		// confiscate all collateral and debit of unsafe cdp to cdp treasury
 		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, collateral, debit)?;
+		Self::clear_stop_loss_if_no_debit(&who, currency_id);
 
 		// swap exact stable with DEX in limit of price impact
 		let debit_value = Self::get_debit_value(currency_id, debit);
@@ -855,6 +2853,11 @@ impl<T: Config> Pallet<T> {
 		// if specify swap path
 		let actual_supply_collateral = (|| -> Result<Balance, DispatchError> {
 			if let Some(path) = maybe_path {
+				// the target of the swap is repaid as the stable currency
+				ensure!(
+					path.last() == Some(&T::GetStableCurrencyId::get()),
+					Error::<T>::InvalidSwapPath
+				);
 				<T as Config>::CDPTreasury::swap_collateral_to_exact_stable(
 					currency_id,
 					collateral_supply,
@@ -907,25 +2910,348 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Increase the debit of the CDP by `increase_debit_value`, swap the
+	/// stable currency issued to the collateral by DEX and deposit it into
+	/// the CDP, to lever up the CDP in a single step.
+	#[transactional]
+	pub fn expand_position_collateral(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		increase_debit_value: Balance,
+		min_increase_collateral: Balance,
+	) -> DispatchResult {
+		ensure!(
+			T::CollateralCurrencyIds::get().contains(&currency_id),
+			Error::<T>::InvalidCollateralType,
+		);
+		Self::ensure_stable_denominated(currency_id)?;
+		let loans_module_account = <LoansOf<T>>::account_id();
+		let increase_debit = Self::convert_to_debit_balance(currency_id, increase_debit_value);
+		let increase_debit_value = Self::get_debit_value(currency_id, increase_debit);
+
+		// issue the stable currency of the debit to the loans module account in advance
+		<T as Config>::CDPTreasury::issue_debit(&loans_module_account, increase_debit_value, true)?;
+
+		// swap the stable currency to the collateral kept by the loans module account
+		let mut swap_path = <T as Config>::CDPTreasury::get_collateral_swap_path(currency_id);
+		swap_path.reverse();
+		let increase_collateral = T::DEX::swap_with_exact_supply(
+			&loans_module_account,
+			&swap_path,
+			increase_debit_value,
+			min_increase_collateral,
+		)?;
+
+		<LoansOf<T>>::update_loan(
+			who,
+			currency_id,
+			<LoansOf<T>>::amount_try_from_balance(increase_collateral)?,
+			<LoansOf<T>>::amount_try_from_balance(increase_debit)?,
+		)?;
+
+		// ensure the CDP and the debit cap pass the risk check
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+		Self::check_vault_valid(currency_id, who, collateral, debit, true)?;
+		Self::check_debit_cap(currency_id, <LoansOf<T>>::total_positions(currency_id).debit)?;
+
+		Self::deposit_event(Event::PositionExpanded(
+			currency_id,
+			who.clone(),
+			increase_collateral,
+			increase_debit_value,
+		));
+		Ok(())
+	}
+
+	/// Swap `decrease_collateral` of the collateral of the CDP to the stable
+	/// currency by DEX to repay the debit, to deleverage the CDP in a single
+	/// step. The stable currency left after the debit is repaid in full is
+	/// refunded to the CDP owner.
+	#[transactional]
+	pub fn shrink_position_debit(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		decrease_collateral: Balance,
+		min_decrease_debit_value: Balance,
+	) -> DispatchResult {
+		Self::ensure_stable_denominated(currency_id)?;
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
+		let decrease_collateral = decrease_collateral.min(collateral);
+		let loans_module_account = <LoansOf<T>>::account_id();
+
+		// swap the collateral kept by the loans module account to the stable currency
+		let swap_path = <T as Config>::CDPTreasury::get_collateral_swap_path(currency_id);
+		let stable_amount = T::DEX::swap_with_exact_supply(
+			&loans_module_account,
+			&swap_path,
+			decrease_collateral,
+			min_decrease_debit_value,
+		)?;
+
+		let debit_value = Self::get_debit_value(currency_id, debit);
+		let (decrease_debit, decrease_debit_value) = if stable_amount >= debit_value {
+			(debit, debit_value)
+		} else {
+			let decrease_debit = Self::convert_to_debit_balance(currency_id, stable_amount).min(debit);
+			(decrease_debit, Self::get_debit_value(currency_id, decrease_debit))
+		};
+
+		// repay the debit and refund the stable currency left to the CDP owner
+		<T as Config>::CDPTreasury::burn_debit(&loans_module_account, decrease_debit_value)?;
+		<T as loans::Config>::Currency::transfer(
+			T::GetStableCurrencyId::get(),
+			&loans_module_account,
+			who,
+			stable_amount.saturating_sub(decrease_debit_value),
+		)?;
+
+		<LoansOf<T>>::update_loan(
+			who,
+			currency_id,
+			<LoansOf<T>>::amount_try_from_balance(decrease_collateral)?.saturating_neg(),
+			<LoansOf<T>>::amount_try_from_balance(decrease_debit)?.saturating_neg(),
+		)?;
+
+		// ensure the CDP left passes the risk check
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, who);
+		Self::check_vault_valid(currency_id, who, collateral, debit, false)?;
+
+		Self::deposit_event(Event::PositionShrunk(
+			currency_id,
+			who.clone(),
+			decrease_collateral,
+			decrease_debit_value,
+		));
+		Ok(())
+	}
+
+	/// Whether the stop-loss order of the CDP exists and the oracle price of
+	/// the collateral is at or below its trigger price.
+	pub fn is_stop_loss_triggered(currency_id: CurrencyId, who: &T::AccountId) -> bool {
+		Self::stop_loss_orders(currency_id, who).map_or(false, |order| {
+			T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+				.map_or(false, |price| price <= order.trigger_price)
+		})
+	}
+
+	// execute the triggered stop-loss order of cdp, sell the collateral of the
+	// order by DEX to repay the debit, and the execution fee is paid from the
+	// stable currency raised. The stale order of the cdp without debit is cleared.
+	pub fn execute_stop_loss_order(who: T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+		ensure!(
+			StopLossOrders::<T>::contains_key(currency_id, &who),
+			Error::<T>::StopLossOrderNotExists
+		);
+		if <LoansOf<T>>::positions(currency_id, &who).debit.is_zero() {
+			Self::clear_stop_loss_if_no_debit(&who, currency_id);
+			return Ok(());
+		}
+
+		Self::ensure_stable_denominated(currency_id)?;
+		let order = Self::stop_loss_orders(currency_id, &who).ok_or(Error::<T>::StopLossOrderNotExists)?;
+		let price = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+		ensure!(price <= order.trigger_price, Error::<T>::StopLossNotTriggered);
+
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
+
+		// confiscate the collateral of the order to cdp treasury
+		let collateral_supply = order.collateral_amount.min(collateral);
+		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, collateral_supply, Zero::zero())?;
+
+		// calculate the supply limit by slippage limit for the price of oracle
+		let execution_fee = T::StopLossExecutionFee::get();
+		let debit_value = Self::get_debit_value(currency_id, debit);
+		let target_stable_amount = debit_value.saturating_add(execution_fee);
+		let min_price = price.saturating_mul(Ratio::one().saturating_sub(T::MaxSwapSlippageCompareToOracle::get()));
+		let swap_path = <T as Config>::CDPTreasury::get_collateral_swap_path(currency_id);
+		let max_supply_amount = min_price.reciprocal().map_or(collateral_supply, |reciprocal_price| {
+			reciprocal_price.saturating_mul_int(target_stable_amount)
+		});
+
+		// swap to the exact stable for the debit and the execution fee if the collateral
+		// is enough, otherwise swap the collateral of the order up to the supply limit.
+		let supply_limit = max_supply_amount.min(collateral_supply);
+		let (sold_collateral_amount, stable_amount) = match <T as Config>::CDPTreasury::swap_collateral_to_exact_stable(
+			currency_id,
+			supply_limit,
+			target_stable_amount,
+			&swap_path,
+			false,
+		) {
+			Ok(sold_collateral_amount) => (sold_collateral_amount, target_stable_amount),
+			Err(_) => {
+				let stable_amount = <T as Config>::CDPTreasury::swap_exact_collateral_to_stable(
+					currency_id,
+					supply_limit,
+					min_price.saturating_mul_int(supply_limit),
+					&swap_path,
+					false,
+				)?;
+				(supply_limit, stable_amount)
+			}
+		};
+		ensure!(stable_amount > execution_fee, Error::<T>::StopLossBelowExecutionFee);
+
+		// refund the collateral left to CDP owner
+		<T as Config>::CDPTreasury::withdraw_collateral(
+			&who,
+			currency_id,
+			collateral_supply.saturating_sub(sold_collateral_amount),
+		)?;
+
+		// decrease the debit repaid by the stable currency raised. If the debit left
+		// would be dust below the minimum debit value, repay it in full out of the
+		// execution fee as far as the stable currency raised covers it.
+		let mut execution_fee = execution_fee;
+		let mut repaid_debit_value = stable_amount.saturating_sub(execution_fee).min(debit_value);
+		let remain_debit_value = debit_value.saturating_sub(repaid_debit_value);
+		if !remain_debit_value.is_zero()
+			&& remain_debit_value < Self::get_minimum_debit_value(currency_id)
+			&& stable_amount > debit_value
+		{
+			repaid_debit_value = debit_value;
+			execution_fee = stable_amount.saturating_sub(debit_value);
+		}
+		let debit_decrease = if repaid_debit_value == debit_value {
+			debit
+		} else {
+			Self::convert_to_debit_balance(currency_id, repaid_debit_value).min(debit)
+		};
+		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, Zero::zero(), debit_decrease)?;
+		<T as Config>::CDPTreasury::on_collateral_surplus(currency_id, execution_fee);
+
+		// refund the stable currency raised beyond the debit and the execution fee to CDP owner
+		let excess_stable_amount = stable_amount
+			.saturating_sub(execution_fee)
+			.saturating_sub(repaid_debit_value);
+		if !excess_stable_amount.is_zero() {
+			<T as Config>::CDPTreasury::issue_debit(&who, excess_stable_amount, false)?;
+		}
+
+		StopLossOrders::<T>::remove(currency_id, &who);
+		Self::deposit_event(Event::StopLossExecuted(
+			currency_id,
+			who,
+			sold_collateral_amount,
+			repaid_debit_value,
+			execution_fee,
+		));
+		Ok(())
+	}
+
 	// liquidate unsafe cdp
 	pub fn liquidate_unsafe_cdp(who: T::AccountId, currency_id: CurrencyId) -> Result<Weight, DispatchError> {
+		Self::do_liquidate_unsafe_cdp(who, currency_id, None)
+	}
+
+	fn do_liquidate_unsafe_cdp(
+		who: T::AccountId,
+		currency_id: CurrencyId,
+		keeper: Option<T::AccountId>,
+	) -> Result<Weight, DispatchError> {
 		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
+		let vault_collateral = Self::vault_collateral(currency_id, &who, collateral);
 
 		// ensure the cdp is unsafe
 		ensure!(
 			matches!(
-				Self::check_cdp_status(currency_id, collateral, debit),
+				Self::check_cdp_status(currency_id, vault_collateral, debit),
 				CDPStatus::Unsafe
 			),
 			Error::<T>::MustBeUnsafe
 		);
 
-		// confiscate all collateral and debit of unsafe cdp to cdp treasury
+		// give the CDP a grace window to top up when it first crosses the liquidation
+		// ratio, unless it is below the hard liquidation ratio
+		if let Some(liquidation_grace) = Self::liquidation_graces(currency_id) {
+			if !Self::below_hard_liquidation_ratio(currency_id, vault_collateral, debit, &liquidation_grace) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				match Self::unsafe_cdp_deadlines(currency_id, &who) {
+					// the deadline expired a whole grace period ago is left by an earlier unsafe
+					// status which the price recovered from, it doesn't shorten the new window
+					Some(deadline) if now < deadline.saturating_add(liquidation_grace.grace_period) => {
+						ensure!(now >= deadline, Error::<T>::InLiquidationGracePeriod)
+					}
+					_ => {
+						let deadline = now.saturating_add(liquidation_grace.grace_period);
+						UnsafeCdpDeadlines::<T>::insert(currency_id, &who, deadline);
+						Self::deposit_event(Event::UnsafeCdp(currency_id, who, deadline));
+						return Ok(T::WeightInfo::liquidate_in_grace_period());
+					}
+				}
+			}
+		}
+		UnsafeCdpDeadlines::<T>::remove(currency_id, &who);
+
+		// confiscate all collateral, extra collaterals and debit of unsafe cdp to cdp
+		// treasury
+		let extra_collaterals = <LoansOf<T>>::confiscate_extra_collaterals(&who, currency_id)?;
 		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, collateral, debit)?;
+		Self::clear_stop_loss_if_no_debit(&who, currency_id);
 
+		let debt_denomination = Self::debt_denomination_of(currency_id);
 		let bad_debt_value = Self::get_debit_value(currency_id, debit);
-		let target_stable_amount = Self::get_liquidation_penalty(currency_id).saturating_mul_acc_int(bad_debt_value);
+		let total_target_stable_amount =
+			Self::get_liquidation_penalty(currency_id).saturating_mul_acc_int(bad_debt_value);
+
+		// split the target by the value of the collaterals, the extra collaterals are
+		// sold by collateral auctions for their shares of the target
+		let total_collateral_value = extra_collaterals.iter().fold(
+			Self::get_collateral_value(currency_id, collateral),
+			|total, (extra_currency_id, amount)| {
+				total.saturating_add(Self::get_collateral_value(*extra_currency_id, *amount))
+			},
+		);
+		// the liquidation penalty is recorded as the surplus of the collateral when
+		// realized, in proportion to the target raised
+		let liquidation_penalty = total_target_stable_amount.saturating_sub(bad_debt_value);
+		let stable_denominated = debt_denomination == T::GetStableCurrencyId::get();
+		let penalty_of = |target: Balance| -> Balance {
+			Ratio::checked_from_rational(target, total_target_stable_amount)
+				.unwrap_or_default()
+				.saturating_mul_int(liquidation_penalty)
+		};
+		let on_unrealized_surplus = |currency_id: CurrencyId, target: Balance| {
+			if stable_denominated {
+				<T as Config>::CDPTreasury::on_unrealized_collateral_surplus(currency_id, penalty_of(target), target);
+			}
+		};
+		let on_realized_surplus = |currency_id: CurrencyId, target: Balance| {
+			if stable_denominated {
+				<T as Config>::CDPTreasury::on_collateral_surplus(currency_id, penalty_of(target));
+			}
+		};
+
+		let mut target_stable_amount = total_target_stable_amount;
+		let mut extra_auction_count: u32 = 0;
+		for (extra_currency_id, amount) in extra_collaterals.iter() {
+			let extra_target_stable_amount = Ratio::checked_from_rational(
+				Self::get_collateral_value(*extra_currency_id, *amount),
+				total_collateral_value,
+			)
+			.unwrap_or_default()
+			.saturating_mul_int(total_target_stable_amount)
+			.min(target_stable_amount);
+			target_stable_amount = target_stable_amount.saturating_sub(extra_target_stable_amount);
+			on_unrealized_surplus(*extra_currency_id, extra_target_stable_amount);
+			extra_auction_count =
+				extra_auction_count.saturating_add(<T as Config>::CDPTreasury::create_collateral_auctions(
+					*extra_currency_id,
+					*amount,
+					extra_target_stable_amount,
+					who.clone(),
+					true,
+				)?);
+		}
+
 		let liquidation_strategy = (|| -> Result<LiquidationStrategy, DispatchError> {
+			// the CDP backed by the extra collaterals only is liquidated by their auctions
+			if collateral.is_zero() && !extra_collaterals.is_empty() {
+				return Ok(LiquidationStrategy::Auction { auction_count: 0 });
+			}
+
 			let default_swap_parital_path_list: Vec<Vec<CurrencyId>> = T::DefaultSwapParitalPathList::get();
 
 			// calculate the supply limit by slippage limit for the price of oracle,
@@ -934,12 +3260,44 @@ impl<T: Config> Pallet<T> {
 				.reciprocal()
 				.unwrap_or_else(Ratio::max_value)
 				.saturating_mul_int(
-					T::PriceSource::get_relative_price(T::GetStableCurrencyId::get(), currency_id)
+					T::PriceSource::get_relative_price(debt_denomination, currency_id)
+						.or_else(|| Self::get_collateral_price(currency_id).and_then(|price| price.reciprocal()))
 						.expect("the oracle price should be avalible because liquidation are triggered by it.")
 						.saturating_mul_int(target_stable_amount),
 				);
 			let collateral_supply = collateral.min(max_supply_limit);
 
+			// the collateral of other debt denominations is swapped to the denomination
+			// by DEX directly, or sold by decreasing price sale paid in the denomination
+			// if the swap fails, since collateral auctions raise the stable currency only.
+			if !stable_denominated {
+				if let Ok(actual_supply_collateral) = <T as Config>::CDPTreasury::swap_collateral_to_exact_stable(
+					currency_id,
+					collateral_supply,
+					target_stable_amount,
+					&[currency_id, debt_denomination],
+					false,
+				) {
+					// refund remain collateral to CDP owner
+					let refund_collateral_amount = collateral
+						.checked_sub(actual_supply_collateral)
+						.expect("swap succecced means collateral >= actual_supply_collateral; qed");
+
+					<T as Config>::CDPTreasury::withdraw_collateral(&who, currency_id, refund_collateral_amount)?;
+
+					return Ok(LiquidationStrategy::Exchange);
+				}
+
+				let sale_id = <T as Config>::CDPTreasury::create_decreasing_price_sale_of(
+					debt_denomination,
+					currency_id,
+					collateral,
+					target_stable_amount,
+					who.clone(),
+				)?;
+				return Ok(LiquidationStrategy::DecreasingPriceSale { sale_id });
+			}
+
 			// iterator default_swap_parital_path_list to try swap until swap succeed.
 			for partial_path in default_swap_parital_path_list {
 				let partial_path_len = partial_path.len();
@@ -962,13 +3320,60 @@ impl<T: Config> Pallet<T> {
 							.expect("swap succecced means collateral >= actual_supply_collateral; qed");
 
 						<T as Config>::CDPTreasury::withdraw_collateral(&who, currency_id, refund_collateral_amount)?;
+						on_realized_surplus(currency_id, target_stable_amount);
 
 						return Ok(LiquidationStrategy::Exchange);
 					}
 				}
 			}
 
-			// if cannot liquidate by swap, create collateral auctions by cdp treasury
+			// the pegged collateral is swapped through the deep stable asset pools by the
+			// aggregated swap paths before falling back to auctions.
+			if let Ok(actual_supply_collateral) =
+				<T as Config>::CDPTreasury::swap_collateral_to_stable_by_aggregated_paths(
+					currency_id,
+					collateral_supply,
+					target_stable_amount,
+				) {
+				// refund remain collateral to CDP owner
+				let refund_collateral_amount = collateral
+					.checked_sub(actual_supply_collateral)
+					.expect("swap succecced means collateral >= actual_supply_collateral; qed");
+
+				<T as Config>::CDPTreasury::withdraw_collateral(&who, currency_id, refund_collateral_amount)?;
+				on_realized_surplus(currency_id, target_stable_amount);
+
+				return Ok(LiquidationStrategy::StableAssetSwap);
+			}
+
+			// the auction-exempt collateral is swapped by cdp treasury with its max
+			// slippage instead of auctions.
+			if <T as Config>::CDPTreasury::is_auction_exempt(currency_id) {
+				on_unrealized_surplus(currency_id, target_stable_amount);
+				<T as Config>::CDPTreasury::create_collateral_auctions(
+					currency_id,
+					collateral,
+					target_stable_amount,
+					who.clone(),
+					true,
+				)?;
+				return Ok(LiquidationStrategy::Exchange);
+			}
+
+			// if cannot liquidate by swap, sell collateral by decreasing price sale if it's
+			// enabled for the collateral type.
+			if let Ok(sale_id) = <T as Config>::CDPTreasury::create_decreasing_price_sale(
+				currency_id,
+				collateral,
+				target_stable_amount,
+				who.clone(),
+			) {
+				on_unrealized_surplus(currency_id, target_stable_amount);
+				return Ok(LiquidationStrategy::DecreasingPriceSale { sale_id });
+			}
+
+			// otherwise create collateral auctions by cdp treasury
+			on_unrealized_surplus(currency_id, target_stable_amount);
 			let created_auctions = <T as Config>::CDPTreasury::create_collateral_auctions(
 				currency_id,
 				collateral,
@@ -982,6 +3387,35 @@ impl<T: Config> Pallet<T> {
 			})
 		})()?;
 
+		// the revenue is accounted in the stable currency
+		if stable_denominated {
+			<T as Config>::CDPTreasury::on_revenue(RevenueSource::LiquidationPenalty, liquidation_penalty);
+		}
+		T::OnLiquidation::happened(&(who.clone(), currency_id, liquidation_penalty));
+
+		// the bounty is paid out of the stable surplus, only the penalty in stable
+		// currency can cover it
+		let bounty_weight = match keeper {
+			Some(keeper) if stable_denominated => {
+				if let Err(e) = Self::pay_liquidation_bounty(
+					&keeper,
+					currency_id,
+					&who,
+					total_collateral_value,
+					liquidation_penalty,
+				) {
+					log::warn!(
+						target: "cdp-engine",
+						"pay_liquidation_bounty: failed to pay the bounty to {:?}: {:?}. \
+						This is unexpected but should be safe",
+						keeper, e
+					);
+				}
+				Self::liquidation_bounty_weight()
+			}
+			_ => 0,
+		};
+
 		Self::deposit_event(Event::LiquidateUnsafeCDP(
 			currency_id,
 			who,
@@ -989,10 +3423,102 @@ impl<T: Config> Pallet<T> {
 			bad_debt_value,
 			liquidation_strategy.clone(),
 		));
+		let extra_weight = if extra_collaterals.is_empty() {
+			0
+		} else {
+			T::WeightInfo::liquidate_by_auction(extra_auction_count)
+		};
 		match liquidation_strategy {
 			LiquidationStrategy::Auction { auction_count } => Ok(T::WeightInfo::liquidate_by_auction(auction_count)),
-			LiquidationStrategy::Exchange => Ok(T::WeightInfo::liquidate_by_dex()),
+			LiquidationStrategy::Exchange | LiquidationStrategy::StableAssetSwap => Ok(Self::liquidate_by_dex_weight()),
+			LiquidationStrategy::DecreasingPriceSale { .. } => Ok(T::WeightInfo::liquidate_by_auction(1)),
+		}
+		.map(|weight| weight.saturating_add(extra_weight).saturating_add(bounty_weight))
+	}
+
+	/// The weight of paying the liquidation bounty.
+	fn liquidation_bounty_weight() -> Weight {
+		T::DbWeight::get().reads_writes(4, 3)
+	}
+
+	/// The weight of liquidating the CDP by DEX, the swaps by the paths of
+	/// `DefaultSwapParitalPathList` are tried in order.
+	fn liquidate_by_dex_weight() -> Weight {
+		T::DefaultSwapParitalPathList::get()
+			.iter()
+			.fold(T::WeightInfo::liquidate_by_dex(), |weight, partial_path| {
+				weight.saturating_add(<T as Config>::CDPTreasury::swap_collateral_to_stable_weight(
+					partial_path.len().saturating_add(1) as u32,
+				))
+			})
+	}
+
+	/// Pay the fixed bounty plus the share of the value of the seized
+	/// collateral to the keeper out of the surplus of cdp treasury, capped by
+	/// the liquidation penalty and the bounties left for the block.
+	#[transactional]
+	fn pay_liquidation_bounty(
+		keeper: &T::AccountId,
+		currency_id: CurrencyId,
+		owner: &T::AccountId,
+		seized_collateral_value: Balance,
+		liquidation_penalty: Balance,
+	) -> DispatchResult {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let (block_number, paid) = Self::liquidation_bounties_paid();
+		let paid = if block_number == now { paid } else { Zero::zero() };
+		let bounty = T::LiquidationKeeperBounty::get()
+			.saturating_add(T::LiquidationKeeperBountyShare::get().saturating_mul_int(seized_collateral_value))
+			.min(liquidation_penalty)
+			.min(T::MaxLiquidationBountyPerBlock::get().saturating_sub(paid))
+			.min(<T as Config>::CDPTreasury::get_surplus_pool());
+		if bounty.is_zero() {
+			return Ok(());
 		}
+
+		<T as Config>::CDPTreasury::withdraw_surplus(keeper, bounty)?;
+		LiquidationBountiesPaid::<T>::put((now, paid.saturating_add(bounty)));
+		Self::deposit_event(Event::LiquidationBountyPaid(
+			keeper.clone(),
+			currency_id,
+			owner.clone(),
+			bounty,
+		));
+		Ok(())
+	}
+
+	fn pause_debit_issuance(reason: CircuitBreakerReason) {
+		if !Self::debit_issuance_paused() {
+			DebitIssuancePaused::<T>::put(true);
+			T::CircuitBreakerRecorder::on_paused(CircuitBreakerId::DebitIssuance, reason);
+			Self::deposit_event(Event::DebitIssuancePausedUpdated(true));
+		}
+	}
+
+	fn resume_debit_issuance() {
+		if Self::debit_issuance_paused() {
+			DebitIssuancePaused::<T>::put(false);
+			T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::DebitIssuance);
+			Self::deposit_event(Event::DebitIssuancePausedUpdated(false));
+		}
+	}
+
+	fn pause_collateral_mint(currency_id: CurrencyId) {
+		if !Self::collateral_mint_paused(currency_id) {
+			CollateralMintPaused::<T>::insert(currency_id, true);
+			T::CircuitBreakerRecorder::on_paused(
+				CircuitBreakerId::CollateralMint(currency_id),
+				CircuitBreakerReason::Governance,
+			);
+		}
+		Self::deposit_event(Event::CollateralMintPausedUpdated(currency_id, true));
+	}
+
+	fn resume_collateral_mint(currency_id: CurrencyId) {
+		if CollateralMintPaused::<T>::take(currency_id) {
+			T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::CollateralMint(currency_id));
+		}
+		Self::deposit_event(Event::CollateralMintPausedUpdated(currency_id, false));
 	}
 }
 
@@ -1009,8 +3535,7 @@ impl<T: Config> RiskManager<T::AccountId, CurrencyId, Balance, Balance> for Pall
 	) -> DispatchResult {
 		if !debit_balance.is_zero() {
 			let debit_value = Self::get_debit_value(currency_id, debit_balance);
-			let feed_price = <T as Config>::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
-				.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let feed_price = Self::get_collateral_price(currency_id).ok_or(Error::<T>::InvalidFeedPrice)?;
 			let collateral_ratio =
 				Self::calculate_collateral_ratio(currency_id, collateral_balance, debit_balance, feed_price);
 
@@ -1032,7 +3557,7 @@ impl<T: Config> RiskManager<T::AccountId, CurrencyId, Balance, Balance> for Pall
 
 			// check the minimum_debit_value
 			ensure!(
-				debit_value >= T::MinimumDebitValue::get(),
+				debit_value >= Self::get_minimum_debit_value(currency_id),
 				Error::<T>::RemainDebitValueTooSmall,
 			);
 		}
@@ -1040,14 +3565,125 @@ impl<T: Config> RiskManager<T::AccountId, CurrencyId, Balance, Balance> for Pall
 		Ok(())
 	}
 
+	fn check_vault_valid(
+		currency_id: CurrencyId,
+		who: &T::AccountId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		check_required_ratio: bool,
+	) -> DispatchResult {
+		// the extra collaterals are valued in the stable currency
+		if !<LoansOf<T>>::extra_collaterals(currency_id, who).is_empty() {
+			Self::ensure_stable_denominated(currency_id)?;
+		}
+
+		Self::check_position_valid(
+			currency_id,
+			Self::vault_collateral(currency_id, who, collateral_balance),
+			debit_balance,
+			check_required_ratio,
+		)
+	}
+
 	fn check_debit_cap(currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		ensure!(!Self::debit_issuance_paused(), Error::<T>::DebitIssuancePaused);
+		ensure!(
+			!Self::collateral_mint_paused(currency_id),
+			Error::<T>::CollateralMintPaused
+		);
+
 		let hard_cap = Self::maximum_total_debit_value(currency_id);
 		let total_debit_value = Self::get_debit_value(currency_id, total_debit_balance);
 
 		ensure!(total_debit_value <= hard_cap, Error::<T>::ExceedDebitValueHardCap,);
 
+		if let Some(max_system_leverage) = Self::max_system_leverage() {
+			ensure!(
+				Self::system_leverage_snapshot().map_or(true, |leverage| leverage <= max_system_leverage),
+				Error::<T>::ExceedSystemLeverageCap
+			);
+		}
+
+		Ok(())
+	}
+
+	// the debit value of the CDP can't exceed the cap per account of the
+	// collateral type
+	fn check_debit_per_account(currency_id: CurrencyId, who: &T::AccountId, debit_balance: Balance) -> DispatchResult {
+		if let Some(max_debit_per_account) = Self::max_debit_per_account(currency_id) {
+			ensure!(
+				Self::get_debit_value(currency_id, debit_balance) <= max_debit_per_account,
+				Error::<T>::ExceedMaxDebitPerAccount
+			);
+		}
 		Ok(())
 	}
+
+	fn get_debt_denomination(currency_id: CurrencyId) -> Option<CurrencyId> {
+		Self::collateral_debt_denominations(currency_id)
+	}
+}
+
+/// Pause the issuance of new debit when the bad debt overflows, used as the
+/// `OnBadDebtOverflow` handler of CDP treasury.
+pub struct PauseDebitIssuance<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Happened<Balance> for PauseDebitIssuance<T> {
+	fn happened(_debit_pool: &Balance) {
+		Pallet::<T>::pause_debit_issuance(CircuitBreakerReason::BadDebtOverflow);
+	}
+}
+
+/// Snapshot the debit of the CDP before it's updated, used as the
+/// `OnUpdateDebit` handler of loans.
+pub struct RecordDebitSnapshot<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Happened<(T::AccountId, CurrencyId, Balance)> for RecordDebitSnapshot<T> {
+	fn happened((who, currency_id, debit): &(T::AccountId, CurrencyId, Balance)) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let debit_exchange_rate = Pallet::<T>::get_debit_exchange_rate(*currency_id);
+		DebitSnapshots::<T>::mutate(currency_id, who, |snapshots| {
+			// no interest accrues between the updates in the same block, keep the first snapshot
+			if snapshots
+				.last()
+				.map_or(false, |(block_number, _, _)| *block_number == now)
+			{
+				return;
+			}
+			if !snapshots.is_empty() && snapshots.len() as u32 >= T::MaxDebitExchangeRateCheckpoints::get() {
+				snapshots.remove(0);
+			}
+			let _ = snapshots.try_push((now, debit_exchange_rate, *debit));
+		});
+	}
+}
+
+/// Move the CDP in the liquidation index and end its grace window if it's no
+/// longer unsafe after its position is updated by any path, used as the
+/// `OnPositionUpdated` handler of loans.
+pub struct OnPositionUpdated<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Happened<(T::AccountId, CurrencyId)> for OnPositionUpdated<T> {
+	fn happened((who, currency_id): &(T::AccountId, CurrencyId)) {
+		Pallet::<T>::update_liquidation_index(who, *currency_id);
+		Pallet::<T>::end_grace_if_not_unsafe(who, *currency_id);
+	}
+}
+
+impl<T: Config> CircuitBreakerUnpause for Pallet<T> {
+	fn unpause(id: &CircuitBreakerId) -> bool {
+		match id {
+			CircuitBreakerId::DebitIssuance => {
+				Self::resume_debit_issuance();
+				true
+			}
+			CircuitBreakerId::CollateralMint(currency_id) => {
+				Self::resume_collateral_mint(*currency_id);
+				true
+			}
+			_ => false,
+		}
+	}
 }
 
 /// Pick a new PRN, in the range [0, `max`) (exclusive).
@@ -28,14 +28,14 @@ use frame_support::{
 };
 use frame_system::EnsureSignedBy;
 use orml_traits::parameter_type_with_key;
-use primitives::{Moment, TokenSymbol, TradingPair};
+use primitives::{Moment, StableAssetPoolId, TokenSymbol, TradingPair};
 use sp_core::H256;
 use sp_runtime::{
 	testing::{Header, TestXt},
 	traits::{AccountIdConversion, IdentityLookup, One as OneT},
 };
 use sp_std::cell::RefCell;
-use support::{AuctionManager, EmergencyShutdown};
+use support::{AggregatedSwapAdapter, AuctionManager, EmergencyShutdown, StableAssetPool};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -44,6 +44,7 @@ pub type AuctionId = u32;
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const CAROL: AccountId = 3;
+pub const STABLE_ASSET_POOL: AccountId = 20;
 pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
 pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
 pub const BTC: CurrencyId = CurrencyId::Token(TokenSymbol::RENBTC);
@@ -143,10 +144,13 @@ impl loans::Config for Runtime {
 	type CDPTreasury = CDPTreasuryModule;
 	type PalletId = LoansPalletId;
 	type OnUpdateLoan = ();
+	type OnUpdateDebit = RecordDebitSnapshot<Runtime>;
+	type OnPositionUpdated = OnPositionUpdated<Runtime>;
 }
 
 thread_local! {
 	static RELATIVE_PRICE: RefCell<Option<Price>> = RefCell::new(Some(Price::one()));
+	static DOT_PRICE: RefCell<Option<Price>> = RefCell::new(None);
 }
 
 pub struct MockPriceSource;
@@ -154,12 +158,23 @@ impl MockPriceSource {
 	pub fn set_relative_price(price: Option<Price>) {
 		RELATIVE_PRICE.with(|v| *v.borrow_mut() = price);
 	}
+
+	pub fn set_dot_price(price: Option<Price>) {
+		DOT_PRICE.with(|v| *v.borrow_mut() = price);
+	}
 }
 impl PriceProvider<CurrencyId> for MockPriceSource {
 	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
 		match (base, quote) {
 			(AUSD, BTC) => RELATIVE_PRICE.with(|v| *v.borrow_mut()),
 			(BTC, AUSD) => RELATIVE_PRICE.with(|v| *v.borrow_mut()),
+			(LDOT, BTC) => RELATIVE_PRICE.with(|v| *v.borrow_mut()),
+			(BTC, LDOT) => RELATIVE_PRICE.with(|v| *v.borrow_mut()),
+			(DOT, AUSD) => DOT_PRICE.with(|v| *v.borrow_mut()),
+			(DOT, BTC) => DOT_PRICE
+				.with(|v| *v.borrow_mut())
+				.zip(RELATIVE_PRICE.with(|v| *v.borrow_mut()))
+				.and_then(|(dot_price, btc_price)| dot_price.checked_div(&btc_price)),
 			_ => None,
 		}
 	}
@@ -197,9 +212,64 @@ impl AuctionManager<AccountId> for MockAuctionManager {
 	}
 }
 
+/// Stable asset pool 0 swaps BTC for AUSD at 1:1
+pub struct MockStableAsset;
+impl StableAssetPool<AccountId, CurrencyId, Balance> for MockStableAsset {
+	fn get_pool_assets(pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>> {
+		match pool_id {
+			0 => Some(vec![BTC, AUSD]),
+			_ => None,
+		}
+	}
+
+	fn mint(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amounts: Vec<Balance>,
+		_min_mint_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn redeem_proportion(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amount: Balance,
+		_min_redeem_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn swap(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		i: u32,
+		j: u32,
+		dx: Balance,
+		min_dy: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let assets = Self::get_pool_assets(pool_id).ok_or(DispatchError::Other("invalid pool"))?;
+		let dy = Self::get_swap_output_amount(pool_id, i, j, dx).ok_or(DispatchError::Other("invalid swap"))?;
+		ensure!(dy >= min_dy, DispatchError::Other("swap output too small"));
+		<Currencies as MultiCurrency<AccountId>>::transfer(assets[i as usize], who, &STABLE_ASSET_POOL, dx)?;
+		<Currencies as MultiCurrency<AccountId>>::transfer(assets[j as usize], &STABLE_ASSET_POOL, who, dy)?;
+		Ok(dy)
+	}
+
+	fn get_swap_output_amount(pool_id: StableAssetPoolId, i: u32, j: u32, dx: Balance) -> Option<Balance> {
+		let assets = Self::get_pool_assets(pool_id)?;
+		assets.get(i as usize)?;
+		assets.get(j as usize)?;
+		Some(dx)
+	}
+}
+
 parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 10;
+	pub const OffsetPeriod: BlockNumber = 1;
 	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"aca/cdpt");
 	pub TreasuryAccount: AccountId = PalletId(*b"aca/hztr").into_account();
 }
@@ -208,12 +278,31 @@ impl cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = MockAuctionManager;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = DEXModule;
+	type Swap = AggregatedSwapAdapter<DEXModule, MockStableAsset>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = TreasuryAccount;
+	type PriceSource = MockPriceSource;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = ();
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = ();
+	type MinimumIdleWorkWeight = ();
+	type EmergencyShutdown = MockEmergencyShutdown;
 	type WeightInfo = ();
 }
 
@@ -221,6 +310,7 @@ parameter_types! {
 	pub const DEXPalletId: PalletId = PalletId(*b"aca/dexm");
 	pub const GetExchangeFee: (u32, u32) = (0, 100);
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 10;
 	pub EnabledTradingPairs: Vec<TradingPair> = vec![
 		TradingPair::from_currency_ids(AUSD, BTC).unwrap(),
 		TradingPair::from_currency_ids(AUSD, DOT).unwrap(),
@@ -235,11 +325,15 @@ impl dex::Config for Runtime {
 	type Currency = Currencies;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = ();
 	type DEXIncentives = ();
 	type WeightInfo = ();
 	type ListingOrigin = EnsureSignedBy<One, AccountId>;
+	type StableAsset = ();
+	type ProtocolFeeReceiver = TreasuryAccount;
+	type OnProtocolFee = ();
 }
 
 parameter_types! {
@@ -278,11 +372,21 @@ parameter_types! {
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(50, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
+	pub const UnsignedLongevity: u64 = 64;
 	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![LDOT];
 	pub DefaultSwapParitalPathList: Vec<Vec<CurrencyId>> = vec![
 		vec![AUSD],
 		vec![ACA, AUSD],
 	];
+	pub const DebitExchangeRateCheckpointInterval: BlockNumber = 10;
+	pub const MaxDebitExchangeRateCheckpoints: u32 = 3;
+	pub const InterestRateCurvePeriod: BlockNumber = 10;
+	pub const StopLossExecutionFee: Balance = 2;
+	pub static RiskParamsUpdateDelay: BlockNumber = 0;
+	pub const LiquidationKeeperBounty: Balance = 2;
+	pub LiquidationKeeperBountyShare: Ratio = Ratio::saturating_from_rational(1, 10);
+	pub const MaxLiquidationBountyPerBlock: Balance = 50;
 }
 
 impl Config for Runtime {
@@ -296,11 +400,25 @@ impl Config for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type UnsignedPriority = UnsignedPriority;
+	type UnsignedLongevity = UnsignedLongevity;
 	type EmergencyShutdown = MockEmergencyShutdown;
 	type UnixTime = Timestamp;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type DEX = DEXModule;
+	type DEXPriceCumulative = DEXModule;
+	type DebitExchangeRateCheckpointInterval = DebitExchangeRateCheckpointInterval;
+	type MaxDebitExchangeRateCheckpoints = MaxDebitExchangeRateCheckpoints;
+	type InterestRateCurvePeriod = InterestRateCurvePeriod;
+	type OnLiquidation = ();
+	type StopLossExecutionFee = StopLossExecutionFee;
+	type CircuitBreakerRecorder = ();
+	type RiskParamsUpdateDelay = RiskParamsUpdateDelay;
+	type LiquidationKeeperBounty = LiquidationKeeperBounty;
+	type LiquidationKeeperBountyShare = LiquidationKeeperBountyShare;
+	type MaxLiquidationBountyPerBlock = MaxLiquidationBountyPerBlock;
 	type WeightInfo = ();
 }
 
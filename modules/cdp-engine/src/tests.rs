@@ -28,9 +28,10 @@ use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPo
 use sp_io::offchain;
 use sp_runtime::{
 	offchain::{DbExternalities, StorageKind},
-	traits::BadOrigin,
+	traits::{BadOrigin, ValidateUnsigned},
+	transaction_validity::{TransactionSource, ValidTransaction},
 };
-use support::DEXManager;
+use support::{DEXManager, SwapPath};
 
 pub const INIT_TIMESTAMP: u64 = 30_000;
 pub const BLOCK_TIME: u64 = 1000;
@@ -79,6 +80,50 @@ fn check_cdp_status_work() {
 	});
 }
 
+#[test]
+fn unsafe_cdp_count_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 100));
+		assert_eq!(CDPEngineModule::unsafe_cdp_count(BTC), 0);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(CDPEngineModule::unsafe_cdp_count(BTC), 1);
+		assert_eq!(CDPEngineModule::unsafe_cdp_count(DOT), 0);
+
+		// the extra collaterals are counted
+		MockPriceSource::set_dot_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			DOT,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_extra_collateral(&ALICE, BTC, DOT, 100));
+		assert_eq!(CDPEngineModule::unsafe_cdp_count(BTC), 0);
+	});
+}
+
 #[test]
 fn get_debit_exchange_rate_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -253,108 +298,350 @@ fn set_collateral_params_work() {
 }
 
 #[test]
-fn calculate_collateral_ratio_work() {
+fn set_collateral_params_expiry_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let default_params = RiskManagementParams {
+			maximum_total_debit_value: 10000,
+			interest_rate_per_sec: Some(Rate::saturating_from_rational(1, 100000)),
+			liquidation_ratio: Some(Ratio::saturating_from_rational(3, 2)),
+			liquidation_penalty: Some(Rate::saturating_from_rational(2, 10)),
+			required_collateral_ratio: Some(Ratio::saturating_from_rational(9, 5)),
+		};
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_expiry(Origin::signed(5), BTC, Some((3, default_params.clone()))),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_expiry(Origin::signed(1), LDOT, Some((3, default_params.clone()))),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params_expiry(Origin::signed(1), BTC, Some((1, default_params.clone()))),
+			Error::<Runtime>::InvalidExpiry
+		);
+
+		// temporary emergency params
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
-			Change::NewValue(10000),
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 1000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(0),
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params_expiry(
+			Origin::signed(1),
+			BTC,
+			Some((3, default_params.clone()))
 		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CollateralParamsExpiryUpdated(
+			BTC,
+			Some(3),
+		)));
 		assert_eq!(
-			CDPEngineModule::calculate_collateral_ratio(BTC, 100, 500, Price::saturating_from_rational(1, 1)),
-			Ratio::saturating_from_rational(100, 50)
+			CDPEngineModule::collateral_params_expiries(BTC),
+			Some((3, default_params.clone()))
 		);
+
+		System::set_block_number(2);
+		Timestamp::set_timestamp(2 * BLOCK_TIME + INIT_TIMESTAMP);
+		CDPEngineModule::on_initialize(2);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).maximum_total_debit_value, 0);
+
+		System::set_block_number(3);
+		Timestamp::set_timestamp(3 * BLOCK_TIME + INIT_TIMESTAMP);
+		CDPEngineModule::on_initialize(3);
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::CollateralParamsExpired(
+			BTC,
+			default_params.clone(),
+		)));
+		assert_eq!(CDPEngineModule::collateral_params(BTC), default_params);
+		assert_eq!(CDPEngineModule::collateral_params_expiries(BTC), None);
+
+		assert_ok!(CDPEngineModule::set_collateral_params_expiry(
+			Origin::signed(1),
+			BTC,
+			Some((5, default_params.clone()))
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params_expiry(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CollateralParamsExpiryUpdated(
+			BTC, None,
+		)));
+		assert_eq!(CDPEngineModule::collateral_params_expiries(BTC), None);
+
+		// updating the params removes the pending expiry
+		assert_ok!(CDPEngineModule::set_collateral_params_expiry(
+			Origin::signed(1),
+			BTC,
+			Some((5, default_params))
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(20000),
+		));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::CollateralParamsExpiryUpdated(
+			BTC, None,
+		)));
+		assert_eq!(CDPEngineModule::collateral_params_expiries(BTC), None);
+
+		System::set_block_number(5);
+		Timestamp::set_timestamp(5 * BLOCK_TIME + INIT_TIMESTAMP);
+		CDPEngineModule::on_initialize(5);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).maximum_total_debit_value, 20000);
 	});
 }
 
 #[test]
-fn check_debit_cap_work() {
+fn set_collateral_params_with_timelock_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		RiskParamsUpdateDelay::set(10);
+		let changes = RiskParamsChanges {
+			interest_rate_per_sec: Change::NoChange,
+			liquidation_ratio: Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+			liquidation_penalty: Change::NoChange,
+			required_collateral_ratio: Change::NoChange,
+			maximum_total_debit_value: Change::NewValue(10000),
+			..Default::default()
+		};
+
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+			Change::NoChange,
+			Change::NoChange,
 			Change::NewValue(10000),
 		));
-		assert_ok!(CDPEngineModule::check_debit_cap(BTC, 100000));
-		assert_noop!(
-			CDPEngineModule::check_debit_cap(BTC, 100010),
-			Error::<Runtime>::ExceedDebitValueHardCap,
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::RiskParamsUpdateScheduled(BTC, 11)));
+		assert_eq!(CDPEngineModule::pending_risk_params(BTC), Some((11, changes.clone())));
+		assert_eq!(CDPEngineModule::collateral_params(BTC).liquidation_ratio, None);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).maximum_total_debit_value, 0);
+
+		CDPEngineModule::on_initialize(10);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).liquidation_ratio, None);
+
+		CDPEngineModule::on_initialize(11);
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::LiquidationRatioUpdated(
+			BTC,
+			Some(Ratio::saturating_from_rational(2, 1)),
+		)));
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).liquidation_ratio,
+			Some(Ratio::saturating_from_rational(2, 1))
 		);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).maximum_total_debit_value, 10000);
+		assert_eq!(CDPEngineModule::pending_risk_params(BTC), None);
 	});
 }
 
 #[test]
-fn check_position_valid_failed_when_invalid_feed_price() {
+fn cancel_pending_risk_params_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		RiskParamsUpdateDelay::set(10);
+		assert_noop!(
+			CDPEngineModule::cancel_pending_risk_params(Origin::signed(1), BTC),
+			Error::<Runtime>::NoPendingRiskParams
+		);
+
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(1, 1))),
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
 			Change::NewValue(10000),
 		));
+		assert!(CDPEngineModule::pending_risk_params(BTC).is_some());
 
-		MockPriceSource::set_relative_price(None);
 		assert_noop!(
-			CDPEngineModule::check_position_valid(BTC, 100, 500, true),
-			Error::<Runtime>::InvalidFeedPrice
+			CDPEngineModule::cancel_pending_risk_params(Origin::signed(5), BTC),
+			BadOrigin
 		);
+		assert_ok!(CDPEngineModule::cancel_pending_risk_params(Origin::signed(1), BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::PendingRiskParamsCancelled(BTC)));
+		assert_eq!(CDPEngineModule::pending_risk_params(BTC), None);
 
-		MockPriceSource::set_relative_price(Some(Price::one()));
-		assert_ok!(CDPEngineModule::check_position_valid(BTC, 100, 500, true));
+		CDPEngineModule::on_initialize(11);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).maximum_total_debit_value, 0);
 	});
 }
 
 #[test]
-fn check_position_valid_failed_when_remain_debit_value_too_small() {
+fn risk_params_setters_are_scheduled_with_timelock() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		RiskParamsUpdateDelay::set(10);
+		let curve = InterestRateCurve {
+			base_rate: Rate::zero(),
+			slope: Rate::saturating_from_rational(1, 100000),
+		};
+		let soft_debit_cap = SoftDebitCap {
+			soft_cap: 5000,
+			interest_rate_multiplier: Ratio::saturating_from_integer(2),
+		};
+		let liquidation_grace = LiquidationGrace {
+			grace_period: 10,
+			hard_liquidation_ratio: Ratio::saturating_from_rational(3, 2),
+		};
+
+		// the soft cap is checked against the pending hard cap
+		assert_noop!(
+			CDPEngineModule::set_soft_debit_cap(Origin::signed(1), BTC, Some(soft_debit_cap)),
+			Error::<Runtime>::SoftCapAboveHardCap
+		);
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(1, 1))),
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
 			Change::NewValue(10000),
 		));
+		assert_ok!(CDPEngineModule::set_soft_debit_cap(
+			Origin::signed(1),
+			BTC,
+			Some(soft_debit_cap)
+		));
+		assert_ok!(CDPEngineModule::set_minimum_debit_value(
+			Origin::signed(1),
+			BTC,
+			Some(20)
+		));
+		System::set_block_number(2);
+		assert_ok!(CDPEngineModule::set_liquidation_grace(
+			Origin::signed(1),
+			BTC,
+			Some(liquidation_grace)
+		));
+		assert_ok!(CDPEngineModule::set_interest_rate_curve(
+			Origin::signed(1),
+			BTC,
+			Some(curve)
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::RiskParamsUpdateScheduled(BTC, 12)));
+
+		// the pending changes are merged
+		assert_eq!(
+			CDPEngineModule::pending_risk_params(BTC),
+			Some((
+				12,
+				RiskParamsChanges {
+					maximum_total_debit_value: Change::NewValue(10000),
+					interest_rate_curve: Change::NewValue(Some(curve)),
+					soft_debit_cap: Change::NewValue(Some(soft_debit_cap)),
+					minimum_debit_value: Change::NewValue(Some(20)),
+					liquidation_grace: Change::NewValue(Some(liquidation_grace)),
+					..Default::default()
+				}
+			))
+		);
 		assert_noop!(
-			CDPEngineModule::check_position_valid(BTC, 2, 10, true),
-			Error::<Runtime>::RemainDebitValueTooSmall,
+			CDPEngineModule::set_annual_interest_rate(
+				Origin::signed(1),
+				BTC,
+				Some(Rate::saturating_from_rational(1, 10))
+			),
+			Error::<Runtime>::InterestRateCurveEnabled
+		);
+		assert_eq!(CDPEngineModule::maximum_total_debit_value(BTC), 0);
+		assert_eq!(CDPEngineModule::soft_debit_caps(BTC), None);
+		assert_eq!(CDPEngineModule::interest_rate_curves(BTC), None);
+
+		CDPEngineModule::on_initialize(11);
+		assert_eq!(CDPEngineModule::soft_debit_caps(BTC), None);
+
+		CDPEngineModule::on_initialize(12);
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::SoftDebitCapUpdated(
+			BTC,
+			Some(soft_debit_cap),
+		)));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::MinimumDebitValueUpdated(
+			BTC,
+			Some(20),
+		)));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::LiquidationGraceUpdated(
+			BTC,
+			Some(liquidation_grace),
+		)));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::InterestRateCurveUpdated(
+			BTC,
+			Some(curve),
+		)));
+		assert_eq!(CDPEngineModule::maximum_total_debit_value(BTC), 10000);
+		assert_eq!(CDPEngineModule::soft_debit_caps(BTC), Some(soft_debit_cap));
+		assert_eq!(CDPEngineModule::minimum_debit_values(BTC), Some(20));
+		assert_eq!(CDPEngineModule::liquidation_graces(BTC), Some(liquidation_grace));
+		assert_eq!(CDPEngineModule::interest_rate_curves(BTC), Some(curve));
+		assert_eq!(CDPEngineModule::pending_risk_params(BTC), None);
+	});
+}
+
+#[test]
+fn pending_risk_params_are_validated_when_applied() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let soft_debit_cap = SoftDebitCap {
+			soft_cap: 5000,
+			interest_rate_multiplier: Ratio::saturating_from_integer(2),
+		};
+		PendingRiskParams::<Runtime>::insert(
+			BTC,
+			(
+				11,
+				RiskParamsChanges {
+					liquidation_ratio: Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+					soft_debit_cap: Change::NewValue(Some(soft_debit_cap)),
+					..Default::default()
+				},
+			),
 		);
+
+		// the soft cap is above the hard cap when the changes take effect
+		CDPEngineModule::on_initialize(11);
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::PendingRiskParamsRejected(BTC)));
+		assert_eq!(CDPEngineModule::pending_risk_params(BTC), None);
+		assert_eq!(CDPEngineModule::soft_debit_caps(BTC), None);
+		assert_eq!(CDPEngineModule::collateral_params(BTC).liquidation_ratio, None);
 	});
 }
 
 #[test]
-fn check_position_valid_ratio_below_liquidate_ratio() {
+fn calculate_collateral_ratio_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
 			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(10, 2))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
 			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_noop!(
-			CDPEngineModule::check_position_valid(BTC, 91, 500, true),
-			Error::<Runtime>::BelowLiquidationRatio,
+		assert_eq!(
+			CDPEngineModule::calculate_collateral_ratio(BTC, 100, 500, Price::saturating_from_rational(1, 1)),
+			Ratio::saturating_from_rational(100, 50)
 		);
 	});
 }
 
 #[test]
-fn check_position_valid_ratio_below_required_ratio() {
+fn check_debit_cap_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
@@ -365,17 +652,18 @@ fn check_position_valid_ratio_below_required_ratio() {
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_ok!(CDPEngineModule::check_position_valid(BTC, 89, 500, false));
+		assert_ok!(CDPEngineModule::check_debit_cap(BTC, 100000));
 		assert_noop!(
-			CDPEngineModule::check_position_valid(BTC, 89, 500, true),
-			Error::<Runtime>::BelowRequiredCollateralRatio
+			CDPEngineModule::check_debit_cap(BTC, 100010),
+			Error::<Runtime>::ExceedDebitValueHardCap,
 		);
 	});
 }
 
 #[test]
-fn adjust_position_work() {
+fn debit_issuance_paused_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
@@ -386,30 +674,41 @@ fn adjust_position_work() {
 			Change::NewValue(10000),
 		));
 		assert_noop!(
-			CDPEngineModule::adjust_position(&ALICE, ACA, 100, 500),
-			Error::<Runtime>::InvalidCollateralType,
+			CDPEngineModule::set_debit_issuance_paused(Origin::signed(5), true),
+			BadOrigin
 		);
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 1000);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 0);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
-		assert!(!CDPEngineModule::adjust_position(&ALICE, BTC, 0, 200).is_ok());
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -200));
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 30);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 300);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+
+		// paused by the bad debt overflow handler
+		PauseDebitIssuance::<Runtime>::happened(&1000);
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitIssuancePausedUpdated(true)));
+		assert!(CDPEngineModule::debit_issuance_paused());
+		assert_noop!(
+			CDPEngineModule::check_debit_cap(BTC, 100),
+			Error::<Runtime>::DebitIssuancePaused,
+		);
+
+		assert_ok!(CDPEngineModule::set_debit_issuance_paused(Origin::signed(1), false));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitIssuancePausedUpdated(false)));
+		assert!(!CDPEngineModule::debit_issuance_paused());
+		assert_ok!(CDPEngineModule::check_debit_cap(BTC, 100));
+
+		// unpaused by the circuit breaker
+		PauseDebitIssuance::<Runtime>::happened(&1000);
+		assert!(!CDPEngineModule::unpause(&CircuitBreakerId::Transaction(
+			vec![],
+			vec![]
+		)));
+		assert!(CDPEngineModule::debit_issuance_paused());
+		assert!(CDPEngineModule::unpause(&CircuitBreakerId::DebitIssuance));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitIssuancePausedUpdated(false)));
+		assert!(!CDPEngineModule::debit_issuance_paused());
 	});
 }
 
 #[test]
-fn remain_debit_value_too_small_check() {
+fn max_debit_per_account_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
@@ -419,14 +718,64 @@ fn remain_debit_value_too_small_check() {
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
+		assert_noop!(
+			CDPEngineModule::set_max_debit_per_account(Origin::signed(5), BTC, Some(60)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_max_debit_per_account(Origin::signed(1), LDOT, Some(60)),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
 		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
-		assert!(!CDPEngineModule::adjust_position(&ALICE, BTC, 0, -490).is_ok());
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, -100, -500));
+		assert_ok!(CDPEngineModule::set_max_debit_per_account(
+			Origin::signed(1),
+			BTC,
+			Some(60)
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MaxDebitPerAccountUpdated(
+			BTC,
+			Some(60),
+		)));
+		assert_eq!(CDPEngineModule::max_debit_per_account(BTC), Some(60));
+
+		// the debit value of the CDP is capped
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, 101),
+			Error::<Runtime>::ExceedMaxDebitPerAccount
+		);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 100));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 600);
+
+		// other accounts are capped separately
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 600));
+
+		// the CDP exceeding the lowered cap can still repay and withdraw
+		assert_ok!(CDPEngineModule::set_max_debit_per_account(
+			Origin::signed(1),
+			BTC,
+			Some(10)
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, -10, -100));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+
+		// the cap is checked by the loans, so transferring the loan can't bypass it
+		assert_noop!(
+			LoansModule::transfer_loan(&ALICE, &CAROL, BTC),
+			Error::<Runtime>::ExceedMaxDebitPerAccount
+		);
+
+		assert_ok!(CDPEngineModule::set_max_debit_per_account(Origin::signed(1), BTC, None));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MaxDebitPerAccountUpdated(
+			BTC, None,
+		)));
+		assert_eq!(CDPEngineModule::max_debit_per_account(BTC), None);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 100));
 	});
 }
 
 #[test]
-fn liquidate_unsafe_cdp_by_collateral_auction() {
+fn collateral_mint_paused_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 		assert_ok!(CDPEngineModule::set_collateral_params(
@@ -438,48 +787,68 @@ fn liquidate_unsafe_cdp_by_collateral_auction() {
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
 		assert_noop!(
-			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
-			Error::<Runtime>::MustBeUnsafe,
+			CDPEngineModule::set_collateral_mint_paused(Origin::signed(5), BTC, true),
+			BadOrigin
 		);
-		assert_ok!(CDPEngineModule::set_collateral_params(
+		assert_noop!(
+			CDPEngineModule::set_collateral_mint_paused(Origin::signed(1), LDOT, true),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::set_collateral_mint_paused(
 			Origin::signed(1),
 			BTC,
-			Change::NoChange,
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
-			Change::NoChange,
-			Change::NoChange,
-			Change::NoChange,
+			true
 		));
-		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
-		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
-			BTC,
-			ALICE,
-			100,
-			50,
-			LiquidationStrategy::Auction { auction_count: 1 },
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CollateralMintPausedUpdated(
+			BTC, true,
 		)));
-		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+		assert!(CDPEngineModule::collateral_mint_paused(BTC));
+		assert_noop!(
+			CDPEngineModule::check_debit_cap(BTC, 100),
+			Error::<Runtime>::CollateralMintPaused,
+		);
+		assert_ok!(CDPEngineModule::check_debit_cap(DOT, 100));
 
-		mock_shutdown();
+		// can't increase debit, but can repay and withdraw collateral
 		assert_noop!(
-			CDPEngineModule::liquidate(Origin::none(), BTC, ALICE),
-			Error::<Runtime>::AlreadyShutdown
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, 100),
+			Error::<Runtime>::CollateralMintPaused,
 		);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -100));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, -10, 0));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 400);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 90);
+
+		assert_ok!(CDPEngineModule::set_collateral_mint_paused(
+			Origin::signed(1),
+			BTC,
+			false
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CollateralMintPausedUpdated(
+			BTC, false,
+		)));
+		assert!(!CDPEngineModule::collateral_mint_paused(BTC));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 50));
+
+		// unpaused by the circuit breaker
+		assert_ok!(CDPEngineModule::set_collateral_mint_paused(
+			Origin::signed(1),
+			BTC,
+			true
+		));
+		assert!(CDPEngineModule::unpause(&CircuitBreakerId::CollateralMint(BTC)));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CollateralMintPausedUpdated(
+			BTC, false,
+		)));
+		assert!(!CDPEngineModule::collateral_mint_paused(BTC));
 	});
 }
 
 #[test]
-fn liquidate_unsafe_cdp_by_collateral_auction_when_limited_by_slippage() {
+fn max_system_leverage_work() {
 	ExtBuilder::default().build().execute_with(|| {
 		System::set_block_number(1);
 		assert_ok!(CDPEngineModule::set_collateral_params(
@@ -491,257 +860,1689 @@ fn liquidate_unsafe_cdp_by_collateral_auction_when_limited_by_slippage() {
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_ok!(DEXModule::add_liquidity(
-			Origin::signed(CAROL),
-			BTC,
-			AUSD,
-			100,
-			121,
-			0,
-			false
+		assert_eq!(CDPEngineModule::system_leverage(), None);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 200, 500));
+		assert_eq!(
+			CDPEngineModule::system_leverage(),
+			Some(Ratio::saturating_from_rational(50, 200))
+		);
+
+		assert_noop!(
+			CDPEngineModule::set_max_system_leverage(Origin::signed(5), Some(Ratio::saturating_from_rational(2, 10))),
+			BadOrigin
+		);
+		assert_ok!(CDPEngineModule::set_max_system_leverage(
+			Origin::signed(1),
+			Some(Ratio::saturating_from_rational(2, 10))
 		));
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MaxSystemLeverageUpdated(Some(
+			Ratio::saturating_from_rational(2, 10),
+		))));
+		assert_eq!(
+			CDPEngineModule::max_system_leverage(),
+			Some(Ratio::saturating_from_rational(2, 10))
+		);
 
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		// the debit increases are checked against the snapshot taken at the
+		// beginning of the block
+		assert_eq!(CDPEngineModule::system_leverage_snapshot(), None);
+		CDPEngineModule::update_system_leverage_snapshot();
+		assert_eq!(
+			CDPEngineModule::system_leverage_snapshot(),
+			Some(Ratio::saturating_from_rational(50, 200))
+		);
+
+		// the debit can't increase beyond the cap
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, 100),
+			Error::<Runtime>::ExceedSystemLeverageCap
+		);
+		// the debit can still decrease
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -100));
+
+		assert_ok!(CDPEngineModule::set_max_system_leverage(
+			Origin::signed(1),
+			Some(Ratio::saturating_from_rational(3, 10))
+		));
+		CDPEngineModule::update_system_leverage_snapshot();
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 200));
+		assert_eq!(
+			CDPEngineModule::system_leverage(),
+			Some(Ratio::saturating_from_rational(60, 200))
+		);
+
+		assert_ok!(CDPEngineModule::set_max_system_leverage(Origin::signed(1), None));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MaxSystemLeverageUpdated(None)));
+		CDPEngineModule::update_system_leverage_snapshot();
+		assert_eq!(CDPEngineModule::system_leverage_snapshot(), None);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 100));
+	});
+}
+
+#[test]
+fn system_leverage_leave_out_collateral_without_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		for currency_id in vec![BTC, DOT] {
+			assert_ok!(CDPEngineModule::set_collateral_params(
+				Origin::signed(1),
+				currency_id,
+				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NewValue(10000),
+			));
+		}
+		MockPriceSource::set_dot_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 200, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, DOT, 200, 1000));
+		assert_eq!(
+			CDPEngineModule::system_leverage(),
+			Some(Ratio::saturating_from_rational(150, 400))
+		);
+
+		// the debit of DOT is not valued without the price of DOT
+		MockPriceSource::set_dot_price(None);
+		assert_eq!(
+			CDPEngineModule::system_leverage(),
+			Some(Ratio::saturating_from_rational(50, 200))
+		);
+	});
+}
 
+#[test]
+fn check_position_valid_failed_when_invalid_feed_price() {
+	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NoChange,
-			Change::NewValue(Some(Ratio::max_value())),
-			Change::NoChange,
-			Change::NoChange,
-			Change::NoChange,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(1, 1))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(10000),
 		));
 
-		// pool is enough, but slippage limit the swap
-		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(1, 2)));
-		assert_eq!(DEXModule::get_swap_supply_amount(&[BTC, AUSD], 60), Some(99));
-		assert_eq!(DEXModule::get_swap_target_amount(&[BTC, AUSD], 100), Some(60));
-		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
-		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
-			BTC,
-			ALICE,
-			100,
-			50,
-			LiquidationStrategy::Auction { auction_count: 1 },
-		)));
+		MockPriceSource::set_relative_price(None);
+		assert_noop!(
+			CDPEngineModule::check_position_valid(BTC, 100, 500, true),
+			Error::<Runtime>::InvalidFeedPrice
+		);
 
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
-		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
-		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+		MockPriceSource::set_relative_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::check_position_valid(BTC, 100, 500, true));
 	});
 }
 
 #[test]
-fn liquidate_unsafe_cdp_by_swap() {
+fn check_position_valid_failed_when_remain_debit_value_too_small() {
 	ExtBuilder::default().build().execute_with(|| {
-		System::set_block_number(1);
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
 			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(1, 1))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			CDPEngineModule::check_position_valid(BTC, 2, 10, true),
+			Error::<Runtime>::RemainDebitValueTooSmall,
+		);
+	});
+}
+
+#[test]
+fn check_position_valid_ratio_below_liquidate_ratio() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(10, 2))),
 			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_ok!(DEXModule::add_liquidity(
-			Origin::signed(CAROL),
+		assert_noop!(
+			CDPEngineModule::check_position_valid(BTC, 91, 500, true),
+			Error::<Runtime>::BelowLiquidationRatio,
+		);
+	});
+}
+
+#[test]
+fn check_position_valid_ratio_below_required_ratio() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
 			BTC,
-			AUSD,
-			100,
-			121,
-			0,
-			false
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
 		));
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
+		assert_ok!(CDPEngineModule::check_position_valid(BTC, 89, 500, false));
+		assert_noop!(
+			CDPEngineModule::check_position_valid(BTC, 89, 500, true),
+			Error::<Runtime>::BelowRequiredCollateralRatio
+		);
+	});
+}
 
+#[test]
+fn adjust_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, ACA, 100, 500),
+			Error::<Runtime>::InvalidCollateralType,
+		);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 1000);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
 		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
 		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
 		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
 		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert!(!CDPEngineModule::adjust_position(&ALICE, BTC, 0, 200).is_ok());
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -200));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 30);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 300);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+	});
+}
+
+#[test]
+fn remain_debit_value_too_small_check() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert!(!CDPEngineModule::adjust_position(&ALICE, BTC, 0, -490).is_ok());
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, -100, -500));
+	});
+}
 
+#[test]
+fn liquidate_unsafe_cdp_by_decreasing_price_sale() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPTreasuryModule::set_collateral_sale_params(
+			Origin::signed(1),
+			BTC,
+			Some(cdp_treasury::DecreasingPriceSaleParams {
+				start_price_ratio: Ratio::saturating_from_rational(12, 10),
+				decay_per_block: Ratio::saturating_from_rational(1, 100),
+				floor_price_ratio: Ratio::saturating_from_rational(5, 10),
+				min_take_amount: 0,
+			})
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
 			Change::NoChange,
-			Change::NewValue(Some(Ratio::max_value())),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
 			Change::NoChange,
 			Change::NoChange,
 			Change::NoChange,
 		));
-
 		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
 		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
 			BTC,
 			ALICE,
 			100,
 			50,
-			LiquidationStrategy::Exchange,
+			LiquidationStrategy::DecreasingPriceSale { sale_id: 0 },
 		)));
+		let sale = CDPTreasuryModule::collateral_sales(0).unwrap();
+		assert_eq!((sale.currency_id, sale.amount, sale.target), (BTC, 100, 60));
+		assert_eq!(sale.refund_recipient, ALICE);
+		assert_eq!(CDPTreasuryModule::total_collateral_in_sale(BTC), 100);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
 
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (199, 61));
+#[test]
+fn minimum_debit_value_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPEngineModule::set_minimum_debit_value(Origin::signed(5), BTC, Some(20)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_minimum_debit_value(Origin::signed(1), LDOT, Some(20)),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_eq!(CDPEngineModule::get_minimum_debit_value(BTC), 2);
+
+		assert_ok!(CDPEngineModule::set_minimum_debit_value(
+			Origin::signed(1),
+			BTC,
+			Some(20)
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MinimumDebitValueUpdated(
+			BTC,
+			Some(20),
+		)));
+		assert_eq!(CDPEngineModule::get_minimum_debit_value(BTC), 20);
+		assert_eq!(CDPEngineModule::get_minimum_debit_value(DOT), 2);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 100, 100),
+			Error::<Runtime>::RemainDebitValueTooSmall
+		);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, -400),
+			Error::<Runtime>::RemainDebitValueTooSmall
+		);
+
+		// the liquidation clears the debit below the minimum
+		assert_ok!(CDPEngineModule::set_minimum_debit_value(
+			Origin::signed(1),
+			BTC,
+			Some(100)
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+
+		assert_ok!(CDPEngineModule::set_minimum_debit_value(Origin::signed(1), BTC, None));
+		assert_eq!(CDPEngineModule::get_minimum_debit_value(BTC), 2);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_collateral_auction() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert_noop!(
+			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
+			Error::<Runtime>::MustBeUnsafe,
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Auction { auction_count: 1 },
+		)));
 		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 901);
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::LiquidationPenalty), 10);
+		// the surplus is recorded when the auction raises the penalty
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 0);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (10, 60));
+		CDPTreasuryModule::on_collateral_proceeds(BTC, 60, 55);
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 5);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (0, 0));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
 		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
 		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+
+		mock_shutdown();
+		assert_noop!(
+			CDPEngineModule::liquidate(Origin::none(), BTC, ALICE),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_stable_asset_swap() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(Currencies::deposit(AUSD, &STABLE_ASSET_POOL, 1000));
+		assert_ok!(CDPTreasuryModule::set_aggregated_swap_paths(
+			Origin::signed(1),
+			BTC,
+			Some(vec![SwapPath::Taiga(0, 0, 1)])
+		));
+
+		// swap the collateral for the target with the penalty through the stable asset
+		// pool, and refund the remain
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::StableAssetSwap,
+		)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 61);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 939);
+		assert_eq!(Currencies::free_balance(BTC, &STABLE_ASSET_POOL), 61);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn liquidate_by_keeper_pays_bounty() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 500, 2500));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_noop!(CDPEngineModule::liquidate(Origin::root(), BTC, ALICE), BadOrigin);
+
+		// the fixed bounty plus 10% of the collateral value is paid out of the
+		// surplus, capped by the penalty
+		assert_ok!(CDPTreasuryModule::on_system_surplus(100));
+		assert_ok!(CDPEngineModule::liquidate(Origin::signed(CAROL), BTC, ALICE));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::LiquidationBountyPaid(
+			CAROL, BTC, ALICE, 10,
+		)));
+		assert_eq!(Currencies::free_balance(AUSD, &CAROL), 10010);
+		assert_eq!(CDPEngineModule::liquidation_bounties_paid(), (1, 10));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 90);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+
+		// capped by the bounties left for the block
+		assert_ok!(CDPEngineModule::liquidate(Origin::signed(CAROL), BTC, BOB));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::LiquidationBountyPaid(
+			CAROL, BTC, BOB, 40,
+		)));
+		assert_eq!(Currencies::free_balance(AUSD, &CAROL), 10050);
+		assert_eq!(CDPEngineModule::liquidation_bounties_paid(), (1, 50));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 50);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 300);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_with_extra_collaterals() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		MockPriceSource::set_dot_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_noop!(
+			CDPEngineModule::adjust_extra_collateral(&ALICE, BTC, ACA, 100),
+			Error::<Runtime>::InvalidCollateralType,
+		);
+		assert_ok!(CDPEngineModule::adjust_extra_collateral(&ALICE, BTC, DOT, 100));
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 900);
+
+		// the extra collateral of the type closed to debit is not counted
+		assert_eq!(CDPEngineModule::vault_collateral(BTC, &ALICE, 100), 100);
+
+		// the riskier extra collateral is reduced by the ratio of the liquidation
+		// ratios
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			DOT,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(10000),
+		));
+		assert_eq!(CDPEngineModule::vault_collateral(BTC, &ALICE, 100), 150);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			DOT,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(CDPEngineModule::vault_collateral(BTC, &ALICE, 100), 200);
+
+		// the extra collateral keeps the cdp safe
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_noop!(
+			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
+			Error::<Runtime>::MustBeUnsafe,
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(5, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Auction { auction_count: 1 },
+		)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::LiquidationPenalty), 10);
+		assert_eq!(CDPTreasuryModule::total_collaterals(DOT), 100);
+		assert_eq!(LoansModule::extra_collaterals(BTC, &ALICE), vec![]);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 900);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn liquidation_grace_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let liquidation_grace = LiquidationGrace {
+			grace_period: 10,
+			hard_liquidation_ratio: Ratio::saturating_from_rational(3, 2),
+		};
+		assert_noop!(
+			CDPEngineModule::set_liquidation_grace(Origin::signed(5), BTC, Some(liquidation_grace)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_liquidation_grace(Origin::signed(1), LDOT, Some(liquidation_grace)),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_ok!(CDPEngineModule::set_liquidation_grace(
+			Origin::signed(1),
+			BTC,
+			Some(liquidation_grace)
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidationGraceUpdated(
+			BTC,
+			Some(liquidation_grace),
+		)));
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		// the first liquidation records the deadline of the grace window
+		assert_eq!(
+			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
+			Ok(<() as WeightInfo>::liquidate_in_grace_period())
+		);
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::UnsafeCdp(BTC, ALICE, 11)));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, ALICE), Some(11));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert!(CDPEngineModule::in_liquidation_grace(BTC, &ALICE, 100, 500));
+
+		System::set_block_number(5);
+		assert_noop!(
+			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
+			Error::<Runtime>::InLiquidationGracePeriod
+		);
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(BOB, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::UnsafeCdp(BTC, BOB, 15)));
+
+		// topping up to safe ends the grace window
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 0));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, ALICE), None);
+
+		// falling below the hard liquidation ratio skips the grace window
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(1, 3)));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+
+		// liquidate after the deadline of the grace window
+		MockPriceSource::set_relative_price(Some(Price::one()));
+		System::set_block_number(15);
+		assert!(!CDPEngineModule::in_liquidation_grace(BTC, &BOB, 100, 500));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(BOB, BTC));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, BOB), None);
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 0);
+	});
+}
+
+#[test]
+fn unsafe_cdp_deadline_cleared_on_every_position_update() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_liquidation_grace(
+			Origin::signed(1),
+			BTC,
+			Some(LiquidationGrace {
+				grace_period: 10,
+				hard_liquidation_ratio: Ratio::saturating_from_rational(3, 2),
+			})
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(BOB, BTC));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, ALICE), Some(11));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, BOB), Some(11));
+
+		// updating the position by loans directly also ends the grace window
+		assert_ok!(LoansModule::confiscate_collateral_and_debit(&ALICE, BTC, 0, 500));
+		assert_eq!(CDPEngineModule::unsafe_cdp_deadlines(BTC, ALICE), None);
+
+		// the deadline left by the unsafe status which the price recovered from doesn't
+		// shorten the grace window of the next one
+		System::set_block_number(21);
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(BOB, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::UnsafeCdp(BTC, BOB, 31)));
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 100);
+	});
+}
+
+#[test]
+fn oracle_failure_policy_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let policy = OracleFailurePolicy::LastGoodPrice { max_age: 10 };
+		assert_noop!(
+			CDPEngineModule::set_oracle_failure_policy(Origin::signed(5), BTC, policy),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_oracle_failure_policy(Origin::signed(1), LDOT, policy),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
+		// freeze by default
+		assert_eq!(
+			CDPEngineModule::oracle_failure_policies(BTC),
+			OracleFailurePolicy::Freeze
+		);
+		MockPriceSource::set_relative_price(None);
+		CDPEngineModule::on_initialize(1);
+		assert_eq!(CDPEngineModule::get_collateral_price(BTC), None);
+
+		// use the last good price within the max age
+		assert_ok!(CDPEngineModule::set_oracle_failure_policy(
+			Origin::signed(1),
+			BTC,
+			policy
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::OracleFailurePolicyUpdated(
+			BTC, policy,
+		)));
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(1, 2)));
+		CDPEngineModule::on_initialize(1);
+		assert_eq!(
+			CDPEngineModule::last_good_prices(BTC),
+			Some((Price::saturating_from_rational(1, 2), 1))
+		);
+		MockPriceSource::set_relative_price(None);
+		assert_eq!(
+			CDPEngineModule::get_collateral_price(BTC),
+			Some(Price::saturating_from_rational(1, 2))
+		);
+		System::set_block_number(12);
+		assert_eq!(CDPEngineModule::get_collateral_price(BTC), None);
+		assert_eq!(
+			CDPEngineModule::check_cdp_status(BTC, 100, 500),
+			CDPStatus::ChecksFailed(Error::<Runtime>::InvalidFeedPrice.into())
+		);
+
+		// use the average DEX price with the haircut
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			100,
+			200,
+			0,
+			false
+		));
+		assert_ok!(CDPEngineModule::set_oracle_failure_policy(
+			Origin::signed(1),
+			BTC,
+			OracleFailurePolicy::DexTwap {
+				window: 2,
+				haircut: Ratio::saturating_from_rational(1, 10),
+			}
+		));
+		assert_eq!(CDPEngineModule::last_good_prices(BTC), None);
+		CDPEngineModule::on_initialize(12);
+		assert_eq!(CDPEngineModule::dex_twap_observations(BTC), Some((U256::zero(), 12)));
+		assert_eq!(CDPEngineModule::dex_twap_prices(BTC), None);
+		assert_eq!(CDPEngineModule::get_collateral_price(BTC), None);
+
+		// the price is only averaged once the window elapsed
+		System::set_block_number(13);
+		CDPEngineModule::on_initialize(13);
+		assert_eq!(CDPEngineModule::dex_twap_prices(BTC), None);
+
+		System::set_block_number(14);
+		CDPEngineModule::on_initialize(14);
+		assert_eq!(
+			CDPEngineModule::dex_twap_observations(BTC),
+			Some((U256::from(Price::saturating_from_integer(4).into_inner()), 14))
+		);
+		assert_eq!(
+			CDPEngineModule::dex_twap_prices(BTC),
+			Some(Price::saturating_from_integer(2))
+		);
+		assert_eq!(
+			CDPEngineModule::get_collateral_price(BTC),
+			Some(Price::saturating_from_rational(18, 10))
+		);
+		assert_eq!(CDPEngineModule::check_cdp_status(BTC, 100, 500), CDPStatus::Safe);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_collateral_auction_when_limited_by_slippage() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			100,
+			121,
+			0,
+			false
+		));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::max_value())),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		// pool is enough, but slippage limit the swap
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(1, 2)));
+		assert_eq!(DEXModule::get_swap_supply_amount(&[BTC, AUSD], 60), Some(99));
+		assert_eq!(DEXModule::get_swap_target_amount(&[BTC, AUSD], 100), Some(60));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Auction { auction_count: 1 },
+		)));
+
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_swap() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			100,
+			121,
+			0,
+			false
+		));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 121));
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::max_value())),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Exchange,
+		)));
+
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (199, 61));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 901);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn set_debt_denomination_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPEngineModule::set_debt_denomination(Origin::signed(5), BTC, Some(LDOT)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_debt_denomination(Origin::signed(1), LDOT, Some(LDOT)),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::set_debt_denomination(Origin::signed(1), BTC, Some(LDOT)),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			LDOT,
+			true
+		));
+		assert_ok!(CDPEngineModule::set_debt_denomination(
+			Origin::signed(1),
+			BTC,
+			Some(LDOT)
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebtDenominationUpdated(
+			BTC,
+			Some(LDOT),
+		)));
+		assert_eq!(CDPEngineModule::collateral_debt_denominations(BTC), Some(LDOT));
+		assert_eq!(CDPEngineModule::debt_denomination_of(BTC), LDOT);
+		assert_eq!(CDPTreasuryModule::denomination_consumers(LDOT), 1);
+
+		// the debt denomination can't be disabled while collateral types are denominated in it
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(1), LDOT, false),
+			cdp_treasury::Error::<Runtime>::DebtDenominationInUse
+		);
+
+		// the stable currency is the default debt denomination
+		assert_ok!(CDPEngineModule::set_debt_denomination(
+			Origin::signed(1),
+			BTC,
+			Some(AUSD)
+		));
+		assert_eq!(CDPEngineModule::collateral_debt_denominations(BTC), None);
+		assert_eq!(CDPEngineModule::debt_denomination_of(BTC), AUSD);
+		assert_eq!(CDPTreasuryModule::denomination_consumers(LDOT), 0);
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			LDOT,
+			false
+		));
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_noop!(
+			CDPEngineModule::set_debt_denomination(Origin::signed(1), BTC, Some(LDOT)),
+			Error::<Runtime>::DebtDenominationInUse
+		);
+	});
+}
+
+#[test]
+fn debt_denominated_cdp_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			LDOT,
+			true
+		));
+		assert_ok!(CDPEngineModule::set_debt_denomination(
+			Origin::signed(1),
+			BTC,
+			Some(LDOT)
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+
+		// the CDP mints the debt denomination instead of the stable currency
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_eq!(Currencies::free_balance(LDOT, &ALICE), 50);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+
+		// the features trading the collateral with the stable currency are not supported
+		assert_noop!(
+			CDPEngineModule::expand_position_collateral(&ALICE, BTC, 10, 0),
+			Error::<Runtime>::NotSupportedForDebtDenomination
+		);
+		assert_noop!(
+			CDPEngineModule::shrink_position_debit(&ALICE, BTC, 10, 0),
+			Error::<Runtime>::NotSupportedForDebtDenomination
+		);
+
+		// the unsafe CDP is liquidated by swapping the collateral to the denomination
+		assert_ok!(DEXModule::enable_trading_pair(Origin::signed(1), BTC, LDOT));
+		assert_ok!(Currencies::deposit(LDOT, &CAROL, 121));
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			LDOT,
+			100,
+			121,
+			0,
+			false
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::max_value())),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Exchange,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, LDOT), (199, 61));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_eq!(CDPTreasuryModule::denomination_debit_pool(LDOT), 50);
+		assert_eq!(Currencies::free_balance(LDOT, &CDPTreasuryModule::account_id()), 60);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 901);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn debt_denominated_cdp_liquidated_by_sale_if_swap_failed() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			LDOT,
+			true
+		));
+		assert_ok!(CDPEngineModule::set_debt_denomination(
+			Origin::signed(1),
+			BTC,
+			Some(LDOT)
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::max_value())),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		// there's no DEX liquidity of BTC and LDOT, and the sale is not enabled
+		assert_noop!(
+			CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC),
+			cdp_treasury::Error::<Runtime>::CollateralSaleNotEnabled
+		);
+
+		assert_ok!(CDPTreasuryModule::set_collateral_sale_params(
+			Origin::signed(1),
+			BTC,
+			Some(cdp_treasury::DecreasingPriceSaleParams {
+				start_price_ratio: Ratio::saturating_from_rational(12, 10),
+				decay_per_block: Ratio::saturating_from_rational(1, 100),
+				floor_price_ratio: Ratio::saturating_from_rational(5, 10),
+				min_take_amount: 0,
+			})
+		));
+		assert_ok!(CDPEngineModule::liquidate_unsafe_cdp(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			BTC,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::DecreasingPriceSale { sale_id: 0 },
+		)));
+		let sale = CDPTreasuryModule::collateral_sales(0).unwrap();
+		assert_eq!(
+			(sale.currency_id, sale.denomination, sale.amount, sale.target),
+			(BTC, LDOT, 100, 60)
+		);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_eq!(CDPTreasuryModule::denomination_debit_pool(LDOT), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+	});
+}
+
+#[test]
+fn get_interest_rate_per_sec_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(BTC), Rate::zero());
+		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(DOT), Rate::zero());
+
+		assert_ok!(CDPEngineModule::set_global_params(
+			Origin::signed(1),
+			Rate::saturating_from_rational(1, 10000),
+		));
+		assert_eq!(
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			Rate::saturating_from_rational(1, 10000)
+		);
+		assert_eq!(
+			CDPEngineModule::get_interest_rate_per_sec(DOT),
+			Rate::saturating_from_rational(1, 10000)
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_eq!(
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			Rate::saturating_from_rational(12, 100000)
+		);
+		assert_eq!(
+			CDPEngineModule::get_interest_rate_per_sec(DOT),
+			Rate::saturating_from_rational(1, 10000)
+		);
+	});
+}
+
+#[test]
+fn compound_interest_rate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPEngineModule::compound_interest_rate(Rate::zero(), 10), Rate::zero());
+		assert_eq!(
+			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 0),
+			Rate::zero()
+		);
+		assert_eq!(
+			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 1),
+			Rate::saturating_from_rational(1, 10000)
+		);
+		assert_eq!(
+			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 2),
+			Rate::saturating_from_rational(20001, 100000000)
+		);
+
+		// 1% APY
+		assert_eq!(
+			CDPEngineModule::compound_interest_rate(
+				Rate::saturating_from_rational(315_523_000u128, 1_000_000_000_000_000_000u128),
+				6
+			),
+			Rate::saturating_from_rational(1_893_138_000u128, 1_000_000_000_000_000_000u128)
+		);
+		assert_eq!(
+			CDPEngineModule::compound_interest_rate(
+				Rate::saturating_from_rational(315_523_000u128, 1_000_000_000_000_000_000u128),
+				12
+			),
+			Rate::saturating_from_rational(3_786_276_004u128, 1_000_000_000_000_000_000u128)
+		);
+	});
+}
+
+#[test]
+fn accumulate_interest_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			DOT,
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+
+		CDPEngineModule::accumulate_interest(1, 0);
+		assert_eq!(CDPEngineModule::last_accumulation_secs(), 1);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(BTC),
+			ExchangeRate::saturating_from_rational(1, 10)
+		);
+		assert_eq!(CDPEngineModule::debit_exchange_rate(BTC), None);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(1, 10)
+		);
+		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 300));
+
+		CDPEngineModule::accumulate_interest(2, 1);
+		assert_eq!(CDPEngineModule::last_accumulation_secs(), 2);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(BTC),
+			ExchangeRate::saturating_from_rational(101, 1000)
+		);
+		assert_eq!(
+			CDPEngineModule::debit_exchange_rate(BTC),
+			Some(ExchangeRate::saturating_from_rational(101, 1000))
+		);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(1, 10)
+		);
+		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
+
+		mock_shutdown();
+		assert!(<Runtime as Config>::EmergencyShutdown::is_shutdown());
+
+		CDPEngineModule::accumulate_interest(3, 2);
+		assert_eq!(CDPEngineModule::last_accumulation_secs(), 3);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(BTC),
+			ExchangeRate::saturating_from_rational(101, 1000)
+		);
+		assert_eq!(
+			CDPEngineModule::debit_exchange_rate(BTC),
+			Some(ExchangeRate::saturating_from_rational(101, 1000))
+		);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(DOT),
+			ExchangeRate::saturating_from_rational(1, 10)
+		);
+		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
+	});
+}
+
+#[test]
+fn set_annual_interest_rate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPEngineModule::set_annual_interest_rate(
+				Origin::signed(5),
+				BTC,
+				Some(Rate::saturating_from_rational(1, 10))
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_annual_interest_rate(
+				Origin::signed(1),
+				LDOT,
+				Some(Rate::saturating_from_rational(1, 10))
+			),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
+		assert_ok!(CDPEngineModule::set_annual_interest_rate(
+			Origin::signed(1),
+			BTC,
+			Some(Rate::saturating_from_rational(1, 10))
+		));
+		let interest_rate_per_sec = CDPEngineModule::annual_to_per_sec_rate(Rate::saturating_from_rational(1, 10));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::InterestRatePerSecUpdated(
+			BTC,
+			Some(interest_rate_per_sec),
+		)));
+		assert_eq!(
+			CDPEngineModule::collateral_params(BTC).interest_rate_per_sec,
+			Some(interest_rate_per_sec)
+		);
+
+		// the max rate per sec not compounding above the annual rate
+		let annual_interest_rate = CDPEngineModule::get_annual_interest_rate(BTC);
+		assert!(annual_interest_rate <= Rate::saturating_from_rational(1, 10));
+		assert!(annual_interest_rate > Rate::saturating_from_rational(9_999, 100_000));
+		assert!(
+			CDPEngineModule::compound_interest_rate(
+				interest_rate_per_sec.saturating_add(Rate::from_inner(1)),
+				SECONDS_PER_YEAR
+			) > Rate::saturating_from_rational(1, 10)
+		);
+
+		// the accrued interest flows into the surplus pool
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 1000, 5000));
+		CDPEngineModule::accumulate_interest(SECONDS_PER_YEAR, 0);
+		let new_debit_exchange_rate = CDPEngineModule::get_debit_exchange_rate(BTC);
+		let interest_value = new_debit_exchange_rate
+			.saturating_sub(ExchangeRate::saturating_from_rational(1, 10))
+			.saturating_mul_int(5000);
+		assert_eq!(interest_value, 49);
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::InterestAccumulated(
+			BTC,
+			interest_value,
+			new_debit_exchange_rate,
+		)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), interest_value);
+
+		assert_ok!(CDPEngineModule::set_annual_interest_rate(Origin::signed(1), BTC, None));
+		assert_eq!(CDPEngineModule::collateral_params(BTC).interest_rate_per_sec, None);
+
+		// the update is scheduled after the delay
+		RiskParamsUpdateDelay::set(10);
+		assert_ok!(CDPEngineModule::set_annual_interest_rate(
+			Origin::signed(1),
+			BTC,
+			Some(Rate::saturating_from_rational(1, 10))
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::RiskParamsUpdateScheduled(BTC, 11)));
+		assert_eq!(CDPEngineModule::collateral_params(BTC).interest_rate_per_sec, None);
+		RiskParamsUpdateDelay::set(0);
+
+		// the rate can't be updated while the interest rate curve is set
+		assert_ok!(CDPEngineModule::set_interest_rate_curve(
+			Origin::signed(1),
+			BTC,
+			Some(InterestRateCurve {
+				base_rate: Rate::zero(),
+				slope: Rate::saturating_from_rational(1, 100000),
+			})
+		));
+		assert_noop!(
+			CDPEngineModule::set_annual_interest_rate(Origin::signed(1), BTC, None),
+			Error::<Runtime>::InterestRateCurveEnabled
+		);
+	});
+}
+
+#[test]
+fn debit_exchange_rate_checkpoints_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 1000, 5000));
+
+		assert_eq!(CDPEngineModule::record_debit_exchange_rate_checkpoints(5), 0);
+		assert!(CDPEngineModule::debit_exchange_rate_checkpoints(BTC).is_empty());
+
+		CDPEngineModule::record_debit_exchange_rate_checkpoints(10);
+		assert_eq!(
+			CDPEngineModule::debit_exchange_rate_checkpoints(BTC).into_inner(),
+			vec![(10, ExchangeRate::saturating_from_rational(1, 10))]
+		);
+		assert_eq!(
+			CDPEngineModule::debit_exchange_rate_checkpoints(DOT).into_inner(),
+			vec![(10, ExchangeRate::saturating_from_rational(1, 10))]
+		);
+
+		CDPEngineModule::accumulate_interest(2, 1);
+		CDPEngineModule::record_debit_exchange_rate_checkpoints(20);
+		assert_eq!(
+			CDPEngineModule::debit_exchange_rate_checkpoints(BTC).into_inner(),
+			vec![
+				(10, ExchangeRate::saturating_from_rational(1, 10)),
+				(20, ExchangeRate::saturating_from_rational(101, 1000))
+			]
+		);
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 10, 20), Some(5));
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &BOB, 10, 20), Some(0));
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 10, 15), None);
+
+		// the debit updated after the accrual does not change the accrued interest
+		System::set_block_number(25);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 1000, 5000));
+		assert_eq!(
+			CDPEngineModule::debit_snapshots(BTC, &ALICE).last(),
+			Some(&(25, ExchangeRate::saturating_from_rational(101, 1000), 5000))
+		);
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 10, 20), Some(5));
+
+		CDPEngineModule::accumulate_interest(3, 2);
+		CDPEngineModule::record_debit_exchange_rate_checkpoints(30);
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 20, 30), Some(10));
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 10, 30), Some(15));
+
+		// the oldest checkpoint is dropped
+		CDPEngineModule::record_debit_exchange_rate_checkpoints(40);
+		assert_eq!(CDPEngineModule::debit_exchange_rate_checkpoints(BTC).len(), 3);
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 10, 20), None);
+		assert_eq!(CDPEngineModule::accrued_interest(BTC, &ALICE, 20, 40), Some(10));
 	});
 }
 
 #[test]
-fn get_interest_rate_per_sec_work() {
+fn interest_rate_curve_work() {
 	ExtBuilder::default().build().execute_with(|| {
-		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(BTC), Rate::zero());
-		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(DOT), Rate::zero());
-
-		assert_ok!(CDPEngineModule::set_global_params(
-			Origin::signed(1),
-			Rate::saturating_from_rational(1, 10000),
-		));
-		assert_eq!(
-			CDPEngineModule::get_interest_rate_per_sec(BTC),
-			Rate::saturating_from_rational(1, 10000)
+		System::set_block_number(1);
+		let curve = InterestRateCurve {
+			base_rate: Rate::saturating_from_rational(1, 100000),
+			slope: Rate::saturating_from_rational(2, 100000),
+		};
+		assert_noop!(
+			CDPEngineModule::set_interest_rate_curve(Origin::signed(5), BTC, Some(curve)),
+			BadOrigin
 		);
-		assert_eq!(
-			CDPEngineModule::get_interest_rate_per_sec(DOT),
-			Rate::saturating_from_rational(1, 10000)
+		assert_noop!(
+			CDPEngineModule::set_interest_rate_curve(Origin::signed(1), LDOT, Some(curve)),
+			Error::<Runtime>::InvalidCollateralType
 		);
 
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 100000))),
+			Change::NewValue(None),
 			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
 			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 1000, 2500));
+		assert_eq!(
+			CDPEngineModule::utilization(BTC),
+			Ratio::saturating_from_rational(1, 40)
+		);
+		assert_eq!(CDPEngineModule::curve_interest_rate_per_sec(BTC), None);
+
+		assert_ok!(CDPEngineModule::set_interest_rate_curve(
+			Origin::signed(1),
+			BTC,
+			Some(curve)
+		));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::InterestRateCurveUpdated(
+			BTC,
+			Some(curve),
+		)));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::InterestRatePerSecUpdated(
+			BTC,
+			Some(Rate::saturating_from_rational(105, 10000000)),
+		)));
+		assert_eq!(
+			CDPEngineModule::curve_interest_rates(BTC),
+			Some(Rate::saturating_from_rational(105, 10000000))
+		);
 		assert_eq!(
 			CDPEngineModule::get_interest_rate_per_sec(BTC),
-			Rate::saturating_from_rational(12, 100000)
+			Rate::saturating_from_rational(105, 10000000)
 		);
+
+		// the rate set by governance is kept but overridden by the curve
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 1000000))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
 		assert_eq!(
-			CDPEngineModule::get_interest_rate_per_sec(DOT),
-			Rate::saturating_from_rational(1, 10000)
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			Rate::saturating_from_rational(105, 10000000)
 		);
-	});
-}
 
-#[test]
-fn compound_interest_rate_work() {
-	ExtBuilder::default().build().execute_with(|| {
-		assert_eq!(CDPEngineModule::compound_interest_rate(Rate::zero(), 10), Rate::zero());
+		// the rate is recomputed by the utilization at the period
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 2500));
 		assert_eq!(
-			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 0),
-			Rate::zero()
+			CDPEngineModule::utilization(BTC),
+			Ratio::saturating_from_rational(1, 20)
 		);
+		assert_eq!(CDPEngineModule::recompute_interest_rates(5), 0);
 		assert_eq!(
-			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 1),
-			Rate::saturating_from_rational(1, 10000)
+			CDPEngineModule::curve_interest_rates(BTC),
+			Some(Rate::saturating_from_rational(105, 10000000))
 		);
+		CDPEngineModule::recompute_interest_rates(10);
 		assert_eq!(
-			CDPEngineModule::compound_interest_rate(Rate::saturating_from_rational(1, 10000), 2),
-			Rate::saturating_from_rational(20001, 100000000)
+			CDPEngineModule::curve_interest_rates(BTC),
+			Some(Rate::saturating_from_rational(11, 1000000))
 		);
-
-		// 1% APY
 		assert_eq!(
-			CDPEngineModule::compound_interest_rate(
-				Rate::saturating_from_rational(315_523_000u128, 1_000_000_000_000_000_000u128),
-				6
-			),
-			Rate::saturating_from_rational(1_893_138_000u128, 1_000_000_000_000_000_000u128)
+			CDPEngineModule::curve_interest_rate_per_sec(BTC),
+			Some(Rate::saturating_from_rational(11, 1000000))
 		);
 		assert_eq!(
-			CDPEngineModule::compound_interest_rate(
-				Rate::saturating_from_rational(315_523_000u128, 1_000_000_000_000_000_000u128),
-				12
-			),
-			Rate::saturating_from_rational(3_786_276_004u128, 1_000_000_000_000_000_000u128)
+			CDPEngineModule::collateral_params(BTC).interest_rate_per_sec,
+			Some(Rate::saturating_from_rational(1, 1000000))
+		);
+
+		// removing the curve restores the rate set by governance
+		assert_ok!(CDPEngineModule::set_interest_rate_curve(Origin::signed(1), BTC, None));
+		System::assert_has_event(Event::CDPEngineModule(crate::Event::InterestRateCurveUpdated(
+			BTC, None,
+		)));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::InterestRatePerSecUpdated(
+			BTC,
+			Some(Rate::saturating_from_rational(1, 1000000)),
+		)));
+		assert_eq!(CDPEngineModule::curve_interest_rates(BTC), None);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -5000));
+		CDPEngineModule::recompute_interest_rates(20);
+		assert_eq!(
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			Rate::saturating_from_rational(1, 1000000)
 		);
 	});
 }
 
 #[test]
-fn accumulate_interest_work() {
+fn soft_and_hard_debit_caps_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let soft_debit_cap = SoftDebitCap {
+			soft_cap: 200,
+			interest_rate_multiplier: Ratio::saturating_from_integer(2),
+		};
+		assert_noop!(
+			CDPEngineModule::set_soft_debit_cap(Origin::signed(5), BTC, Some(soft_debit_cap)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_soft_debit_cap(Origin::signed(1), LDOT, Some(soft_debit_cap)),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::set_soft_debit_cap(Origin::signed(1), BTC, Some(soft_debit_cap)),
+			Error::<Runtime>::SoftCapAboveHardCap
+		);
+
 		assert_ok!(CDPEngineModule::set_collateral_params(
 			Origin::signed(1),
 			BTC,
-			Change::NewValue(Some(Rate::saturating_from_rational(1, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
 			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 			Change::NewValue(10000),
 		));
-		assert_ok!(CDPEngineModule::set_collateral_params(
+		assert_ok!(CDPEngineModule::set_soft_debit_cap(
 			Origin::signed(1),
-			DOT,
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 100))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
-			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
-			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
-			Change::NewValue(10000),
+			BTC,
+			Some(soft_debit_cap)
 		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::SoftDebitCapUpdated(
+			BTC,
+			Some(soft_debit_cap),
+		)));
+		assert_eq!(CDPEngineModule::soft_debit_caps(BTC), Some(soft_debit_cap));
 
-		CDPEngineModule::accumulate_interest(1, 0);
-		assert_eq!(CDPEngineModule::last_accumulation_secs(), 1);
-		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(BTC),
-			ExchangeRate::saturating_from_rational(1, 10)
-		);
-		assert_eq!(CDPEngineModule::debit_exchange_rate(BTC), None);
-		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(DOT),
-			ExchangeRate::saturating_from_rational(1, 10)
-		);
-		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
-		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 300));
+		let interest_rate_per_sec = CDPEngineModule::get_interest_rate_per_sec(BTC);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 1000, 2500));
+		assert_eq!(CDPEngineModule::get_debit_cap_level(BTC), DebitCapLevel::AboveSoftCap);
+		assert_eq!(CDPEngineModule::debit_cap_levels(BTC), DebitCapLevel::BelowSoftCap);
+		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(BTC), interest_rate_per_sec);
 
-		CDPEngineModule::accumulate_interest(2, 1);
-		assert_eq!(CDPEngineModule::last_accumulation_secs(), 2);
-		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(BTC),
-			ExchangeRate::saturating_from_rational(101, 1000)
-		);
-		assert_eq!(
-			CDPEngineModule::debit_exchange_rate(BTC),
-			Some(ExchangeRate::saturating_from_rational(101, 1000))
-		);
+		// crossing the soft cap raises the interest rate by the multiplier
+		CDPEngineModule::update_debit_cap_levels();
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitCapLevelChanged(
+			BTC,
+			DebitCapLevel::AboveSoftCap,
+		)));
+		assert_eq!(CDPEngineModule::debit_cap_levels(BTC), DebitCapLevel::AboveSoftCap);
 		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(DOT),
-			ExchangeRate::saturating_from_rational(1, 10)
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			interest_rate_per_sec.saturating_mul(Ratio::saturating_from_integer(2))
 		);
-		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
-
-		mock_shutdown();
-		assert!(<Runtime as Config>::EmergencyShutdown::is_shutdown());
 
-		CDPEngineModule::accumulate_interest(3, 2);
-		assert_eq!(CDPEngineModule::last_accumulation_secs(), 3);
-		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(BTC),
-			ExchangeRate::saturating_from_rational(101, 1000)
-		);
-		assert_eq!(
-			CDPEngineModule::debit_exchange_rate(BTC),
-			Some(ExchangeRate::saturating_from_rational(101, 1000))
+		// the hard cap rejects new debit
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(250),
+		));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, BTC, 0, 10),
+			Error::<Runtime>::ExceedDebitValueHardCap
 		);
+		CDPEngineModule::update_debit_cap_levels();
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitCapLevelChanged(
+			BTC,
+			DebitCapLevel::ReachedHardCap,
+		)));
 		assert_eq!(
-			CDPEngineModule::get_debit_exchange_rate(DOT),
-			ExchangeRate::saturating_from_rational(1, 10)
+			CDPEngineModule::get_interest_rate_per_sec(BTC),
+			interest_rate_per_sec.saturating_mul(Ratio::saturating_from_integer(2))
 		);
-		assert_eq!(CDPEngineModule::debit_exchange_rate(DOT), None);
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -1000));
+		CDPEngineModule::update_debit_cap_levels();
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::DebitCapLevelChanged(
+			BTC,
+			DebitCapLevel::BelowSoftCap,
+		)));
+		assert_eq!(CDPEngineModule::get_interest_rate_per_sec(BTC), interest_rate_per_sec);
 	});
 }
 
@@ -770,7 +2571,10 @@ fn settle_cdp_has_debit_work() {
 		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
-		assert_ok!(CDPEngineModule::settle_cdp_has_debit(ALICE, BTC));
+		assert_eq!(
+			CDPEngineModule::settle_cdp_has_debit(ALICE, BTC),
+			Ok(<() as WeightInfo>::settle())
+		);
 		System::assert_last_event(Event::CDPEngineModule(crate::Event::SettleCDPInDebit(BTC, ALICE)));
 		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
@@ -783,6 +2587,45 @@ fn settle_cdp_has_debit_work() {
 	});
 }
 
+#[test]
+fn settle_cdp_has_debit_with_extra_collaterals() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		MockPriceSource::set_dot_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_extra_collateral(&ALICE, BTC, DOT, 100));
+
+		// the collateral covers the bad debt, the extra collateral is kept
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_integer(2)));
+		assert_ok!(CDPEngineModule::settle_cdp_has_debit(ALICE, BTC));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+		assert_eq!(LoansModule::extra_collaterals(BTC, &ALICE), vec![(DOT, 100)]);
+
+		// the bad debt not covered by the collateral is covered by the extra
+		// collateral
+		MockPriceSource::set_relative_price(Some(Price::one()));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_integer(4)));
+		assert_ok!(CDPEngineModule::settle_cdp_has_debit(ALICE, BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::SettleCDPInDebit(BTC, ALICE)));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 200);
+		assert_eq!(CDPTreasuryModule::total_collaterals(DOT), 25);
+		assert_eq!(LoansModule::extra_collaterals(BTC, &ALICE), vec![(DOT, 75)]);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 100);
+	});
+}
+
 #[test]
 fn close_cdp_has_debit_by_dex_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -854,25 +2697,114 @@ fn close_cdp_has_debit_by_dex_work() {
 			Error::<Runtime>::SwapDebitFailed
 		);
 
-		// max collateral amount limit swap
+		// the swap path must end with the stable currency
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			LDOT,
+			true
+		));
+		assert_noop!(
+			CDPEngineModule::close_cdp_has_debit_by_dex(ALICE, BTC, 100, Some(&[BTC, LDOT])),
+			Error::<Runtime>::InvalidSwapPath
+		);
+
+		// max collateral amount limit swap
+		assert_noop!(
+			CDPEngineModule::close_cdp_has_debit_by_dex(ALICE, BTC, 5, Some(&[BTC, AUSD])),
+			dex::Error::<Runtime>::ExcessiveSupplyAmount
+		);
+
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 1000));
+		assert_ok!(CDPEngineModule::close_cdp_has_debit_by_dex(ALICE, BTC, 6, None));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::CloseCDPInDebitByDEX(
+			BTC, ALICE, 6, 94, 50,
+		)));
+
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (106, 950));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 994);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 50);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 50);
+	});
+}
+
+#[test]
+fn expand_and_shrink_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			1000,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 0));
+
+		assert_noop!(
+			CDPEngineModule::expand_position_collateral(&ALICE, ACA, 50, 0),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::expand_position_collateral(&ALICE, BTC, 50, 48),
+			dex::Error::<Runtime>::InsufficientTargetAmount
+		);
+		assert_noop!(
+			CDPEngineModule::expand_position_collateral(&ALICE, BTC, 1000, 0),
+			Error::<Runtime>::BelowRequiredCollateralRatio
+		);
+
+		assert_ok!(CDPEngineModule::expand_position_collateral(&ALICE, BTC, 50, 47));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::PositionExpanded(
+			BTC, ALICE, 47, 50,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (953, 1050));
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 147);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 500);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 0);
+
 		assert_noop!(
-			CDPEngineModule::close_cdp_has_debit_by_dex(ALICE, BTC, 5, Some(&[BTC, AUSD])),
-			dex::Error::<Runtime>::ExcessiveSupplyAmount
+			CDPEngineModule::shrink_position_debit(&BOB, BTC, 10, 0),
+			Error::<Runtime>::NoDebitValue
+		);
+		assert_noop!(
+			CDPEngineModule::shrink_position_debit(&ALICE, BTC, 10, 11),
+			dex::Error::<Runtime>::InsufficientTargetAmount
 		);
 
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (100, 1000));
-		assert_ok!(CDPEngineModule::close_cdp_has_debit_by_dex(ALICE, BTC, 6, None));
-		System::assert_last_event(Event::CDPEngineModule(crate::Event::CloseCDPInDebitByDEX(
-			BTC, ALICE, 6, 94, 50,
-		)));
+		// repay the debit partially
+		assert_ok!(CDPEngineModule::shrink_position_debit(&ALICE, BTC, 10, 10));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::PositionShrunk(BTC, ALICE, 10, 10)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (963, 1040));
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 137);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 400);
 
-		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (106, 950));
-		assert_eq!(Currencies::free_balance(BTC, &ALICE), 994);
-		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		// repay the debit in full and refund the stable currency left
+		assert_ok!(CDPEngineModule::shrink_position_debit(&ALICE, BTC, 100, 0));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::PositionShrunk(
+			BTC, ALICE, 100, 40,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (1063, 943));
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 57);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 37);
 		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
-		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
-		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 50);
-		assert_eq!(CDPTreasuryModule::get_debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 0);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 0);
 	});
 }
 
@@ -942,6 +2874,209 @@ fn close_cdp_has_debit_by_swap_on_alternative_path() {
 	});
 }
 
+#[test]
+fn stop_loss_order_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+
+		assert_noop!(
+			CDPEngineModule::place_stop_loss_order(Origin::signed(ALICE), AUSD, Price::one(), 10),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::place_stop_loss_order(Origin::signed(ALICE), BTC, Price::zero(), 10),
+			Error::<Runtime>::InvalidStopLossOrder
+		);
+		assert_noop!(
+			CDPEngineModule::place_stop_loss_order(Origin::signed(ALICE), BTC, Price::one(), 0),
+			Error::<Runtime>::InvalidStopLossOrder
+		);
+		assert_noop!(
+			CDPEngineModule::cancel_stop_loss_order(Origin::signed(ALICE), BTC),
+			Error::<Runtime>::StopLossOrderNotExists
+		);
+		assert_noop!(
+			CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE),
+			Error::<Runtime>::StopLossOrderNotExists
+		);
+
+		let trigger_price = Price::saturating_from_rational(1, 2);
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			trigger_price,
+			2
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossOrderPlaced(
+			BTC,
+			ALICE,
+			trigger_price,
+			2,
+		)));
+		assert!(!CDPEngineModule::is_stop_loss_triggered(BTC, &ALICE));
+		assert_noop!(
+			CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE),
+			Error::<Runtime>::StopLossNotTriggered
+		);
+
+		assert_ok!(CDPEngineModule::cancel_stop_loss_order(Origin::signed(ALICE), BTC));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossOrderCancelled(BTC, ALICE)));
+		assert_eq!(CDPEngineModule::stop_loss_orders(BTC, ALICE), None);
+
+		// the collateral of the order is not enough to repay all the debit
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::one(),
+			2
+		));
+		assert!(CDPEngineModule::is_stop_loss_triggered(BTC, &ALICE));
+		assert_noop!(
+			CDPEngineModule::execute_stop_loss(Origin::signed(ALICE), BTC, ALICE),
+			BadOrigin
+		);
+		assert_ok!(CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossExecuted(
+			BTC, ALICE, 2, 17, 2,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (102, 981));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 330);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 98);
+		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 19);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 17);
+		assert_eq!(CDPEngineModule::stop_loss_orders(BTC, ALICE), None);
+
+		// repay all the debit and refund the collateral left
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::one(),
+			10
+		));
+		assert_ok!(CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossExecuted(
+			BTC, ALICE, 4, 33, 2,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (106, 946));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 906);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 88);
+		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 54);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 50);
+	});
+}
+
+#[test]
+fn stop_loss_order_repay_dust_debit() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(CAROL),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 180));
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::one(),
+			2
+		));
+
+		// the debit left after paying the full execution fee would be dust,
+		// repay it in full out of the execution fee
+		assert_ok!(CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossExecuted(
+			BTC, ALICE, 2, 18, 1,
+		)));
+		assert_eq!(DEXModule::get_liquidity_pool(BTC, AUSD), (102, 981));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 98);
+		assert_eq!(CDPTreasuryModule::get_surplus_pool(), 19);
+		assert_eq!(CDPTreasuryModule::get_debit_pool(), 18);
+		assert_eq!(CDPEngineModule::stop_loss_orders(BTC, ALICE), None);
+	});
+}
+
+#[test]
+fn stale_stop_loss_order_cleared() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			CDPEngineModule::place_stop_loss_order(Origin::signed(ALICE), BTC, Price::one(), 10),
+			Error::<Runtime>::NoDebitValue
+		);
+
+		// the order is cleared once the debit is repaid in full
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::saturating_from_rational(1, 2),
+			10
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -500));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossOrderCancelled(BTC, ALICE)));
+		assert_eq!(CDPEngineModule::stop_loss_orders(BTC, ALICE), None);
+
+		// the order left by the debit repaid out of the engine is cleared by execution
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 500));
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::saturating_from_rational(1, 2),
+			10
+		));
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 0, -500));
+		assert!(CDPEngineModule::stop_loss_orders(BTC, ALICE).is_some());
+		assert_ok!(CDPEngineModule::execute_stop_loss(Origin::none(), BTC, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::StopLossOrderCancelled(BTC, ALICE)));
+		assert_eq!(CDPEngineModule::stop_loss_orders(BTC, ALICE), None);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+	});
+}
+
 #[test]
 fn offchain_worker_works_cdp() {
 	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
@@ -1098,6 +3233,223 @@ fn offchain_worker_iteration_limit_works() {
 	});
 }
 
+#[test]
+fn offchain_worker_liquidates_riskiest_cdps_first() {
+	let (mut offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		// sets max liquidations value to 1, and iterates BTC positions first
+		offchain.local_storage_set(
+			StorageKind::PERSISTENT,
+			OFFCHAIN_WORKER_MAX_LIQUIDATIONS,
+			&1u32.encode(),
+		);
+		offchain.local_storage_set(
+			StorageKind::PERSISTENT,
+			OFFCHAIN_WORKER_DATA,
+			&(0u32, Option::<Vec<u8>>::None).encode(),
+		);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 550));
+		// make both positions unsafe, bob is riskier
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		run_to_block_offchain(2);
+
+		// only the liquidation of bob is submitted
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.call,
+			MockCall::CDPEngineModule(crate::Call::liquidate {
+				currency_id: BTC,
+				who: BOB
+			})
+		);
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		// alice is kept to liquidate in the next block
+		run_to_block_offchain(3);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.call,
+			MockCall::CDPEngineModule(crate::Call::liquidate {
+				currency_id: BTC,
+				who: ALICE
+			})
+		);
+		assert!(pool_state.write().transactions.pop().is_none());
+	});
+}
+
+#[test]
+fn liquidation_index_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_eq!(CDPEngineModule::risk_bucket(100, 0), None);
+		assert_eq!(CDPEngineModule::risk_bucket(0, 100), Some(0));
+		assert!(CDPEngineModule::risk_bucket(100, 550) < CDPEngineModule::risk_bucket(100, 500));
+		assert_eq!(
+			CDPEngineModule::risk_bucket(200, 1000),
+			CDPEngineModule::risk_bucket(100, 500)
+		);
+
+		// the CDP without debit isn't indexed
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 0));
+		assert_eq!(CDPEngineModule::cdp_risk_buckets(BTC, ALICE), None);
+
+		let bucket = CDPEngineModule::risk_bucket(100, 500).unwrap();
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 500));
+		assert_eq!(CDPEngineModule::cdp_risk_buckets(BTC, ALICE), Some(bucket));
+		assert_eq!(CDPEngineModule::risk_bucket_sizes(BTC, bucket), 2);
+		assert!(LiquidationIndex::<Runtime>::contains_key((BTC, bucket), ALICE));
+
+		// the updated position moves to its new bucket
+		let new_bucket = CDPEngineModule::risk_bucket(100, 250).unwrap();
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -250));
+		assert_eq!(CDPEngineModule::cdp_risk_buckets(BTC, ALICE), Some(new_bucket));
+		assert_eq!(CDPEngineModule::risk_bucket_sizes(BTC, bucket), 1);
+		assert_eq!(CDPEngineModule::risk_bucket_sizes(BTC, new_bucket), 1);
+		assert!(!LiquidationIndex::<Runtime>::contains_key((BTC, bucket), ALICE));
+
+		// the repaid CDP is removed from the index
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 0, -250));
+		assert_eq!(CDPEngineModule::cdp_risk_buckets(BTC, ALICE), None);
+		assert!(!RiskBucketSizes::<Runtime>::contains_key(BTC, new_bucket));
+		assert_eq!(LiquidationIndex::<Runtime>::iter_prefix((BTC, new_bucket)).count(), 0);
+	});
+}
+
+#[test]
+fn validate_unsigned_liquidate_prioritizes_riskier_cdps() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(5, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		let call = crate::Call::liquidate {
+			currency_id: BTC,
+			who: ALICE,
+		};
+
+		// the collateral ratio is 200%, 50% below the liquidation ratio
+		assert_eq!(
+			CDPEngineModule::validate_unsigned(TransactionSource::Local, &call),
+			ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+				.priority(UnsignedPriority::get() + 5_000)
+				.and_provides(("liquidate", BTC, ALICE))
+				.longevity(UnsignedLongevity::get())
+				.propagate(true)
+				.build()
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+
+		// the tag does not change with the block, so the resubmissions of the
+		// offchain workers replace the pending transaction only by priority
+		System::set_block_number(2);
+		assert_eq!(
+			CDPEngineModule::validate_unsigned(TransactionSource::Local, &call),
+			ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+				.priority(UnsignedPriority::get() + 10_000)
+				.and_provides(("liquidate", BTC, ALICE))
+				.longevity(UnsignedLongevity::get())
+				.propagate(true)
+				.build()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_tags_differ_by_call() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(5, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 500));
+		assert_ok!(CDPEngineModule::place_stop_loss_order(
+			Origin::signed(ALICE),
+			BTC,
+			Price::one(),
+			10
+		));
+
+		// the pending stop-loss and liquidation of the same CDP don't replace each other
+		let liquidate = CDPEngineModule::validate_unsigned(
+			TransactionSource::Local,
+			&crate::Call::liquidate {
+				currency_id: BTC,
+				who: ALICE,
+			},
+		)
+		.unwrap();
+		let execute_stop_loss = CDPEngineModule::validate_unsigned(
+			TransactionSource::Local,
+			&crate::Call::execute_stop_loss {
+				currency_id: BTC,
+				who: ALICE,
+			},
+		)
+		.unwrap();
+		assert_ne!(liquidate.provides, execute_stop_loss.provides);
+	});
+}
+
 #[test]
 fn offchain_default_max_iterator_works() {
 	let (mut offchain, _offchain_state) = testing::TestOffchainExt::new();
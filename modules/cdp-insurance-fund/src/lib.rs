@@ -0,0 +1,211 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # CDP Insurance Fund Module
+//!
+//! ## Overview
+//!
+//! CDP insurance fund module accumulates a share of the stability fees into
+//! the insurance fund, until the fund reaches the fund cap. At the beginning
+//! of each block, the bad debit of CDP treasury which is not covered by the
+//! surplus pool is covered by the fund, before the other backstops of the
+//! system are used.
+//!
+//! The fund is kept by the account of the liquidation insurance, so the
+//! premiums and the top-ups of the fund cover the bad debit too. The fee
+//! share and the fund cap are configured by governance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use orml_traits::{Happened, MultiCurrency};
+use primitives::{Balance, CurrencyId, RevenueSource};
+use sp_runtime::{
+	traits::{One, Saturating, Zero},
+	FixedPointNumber,
+};
+use support::{CDPTreasury, Ratio};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to move the stable currency
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// CDP treasury to check the debit pool, pay the share of the
+		/// stability fees out of the surplus and receive the stable currency
+		/// covering the bad debit
+		type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The origin which may update the fee share and the fund cap
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The account keeping the insurance fund, shared with the liquidation
+		/// insurance
+		#[pallet::constant]
+		type InsuranceFundAccount: Get<Self::AccountId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The fee share is greater than one
+		InvalidFeeShare,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The share of the stability fees is updated. \[fee_share\]
+		FeeShareUpdated(Ratio),
+		/// The cap of the insurance fund is updated. \[fund_cap\]
+		FundCapUpdated(Balance),
+		/// The share of the stability fees is accumulated into the fund.
+		/// \[amount\]
+		FeeAccumulated(Balance),
+		/// The bad debit is covered by the fund. \[amount\]
+		BadDebitCovered(Balance),
+	}
+
+	/// The share of the stability fees accumulated into the fund.
+	///
+	/// FeeShare: Ratio
+	#[pallet::storage]
+	#[pallet::getter(fn fee_share)]
+	pub type FeeShare<T: Config> = StorageValue<_, Ratio, ValueQuery>;
+
+	/// The cap of the fund, the stability fees are not accumulated when the
+	/// fund reaches it.
+	///
+	/// FundCap: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn fund_cap)]
+	pub type FundCap<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Cover the bad debit uncovered by the surplus pool with the fund.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			let uncovered_debit = T::CDPTreasury::get_debit_pool().saturating_sub(T::CDPTreasury::get_surplus_pool());
+			let cover_amount = uncovered_debit.min(Self::fund_balance());
+			if cover_amount.is_zero() {
+				return T::WeightInfo::on_initialize();
+			}
+
+			match T::CDPTreasury::deposit_surplus(&T::InsuranceFundAccount::get(), cover_amount) {
+				Ok(_) => Self::deposit_event(Event::BadDebitCovered(cover_amount)),
+				Err(e) => log::warn!(
+					target: "cdp-insurance-fund",
+					"deposit_surplus: failed to cover the bad debit {:?}: {:?}. \
+					This is unexpected but should be safe",
+					cover_amount, e
+				),
+			}
+			T::WeightInfo::on_initialize_with_cover()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Update the share of the stability fees accumulated into the fund.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `fee_share`: the share of the stability fees, no more than one.
+		#[pallet::weight((<T as Config>::WeightInfo::set_fee_share(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_fee_share(origin: OriginFor<T>, fee_share: Ratio) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(fee_share <= Ratio::one(), Error::<T>::InvalidFeeShare);
+			FeeShare::<T>::put(fee_share);
+			Self::deposit_event(Event::FeeShareUpdated(fee_share));
+			Ok(())
+		}
+
+		/// Update the cap of the fund.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `fund_cap`: the cap of the fund.
+		#[pallet::weight((<T as Config>::WeightInfo::set_fund_cap(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_fund_cap(origin: OriginFor<T>, #[pallet::compact] fund_cap: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			FundCap::<T>::put(fund_cap);
+			Self::deposit_event(Event::FundCapUpdated(fund_cap));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the stable currency amount of the fund.
+	pub fn fund_balance() -> Balance {
+		T::Currency::free_balance(T::GetStableCurrencyId::get(), &T::InsuranceFundAccount::get())
+	}
+}
+
+/// Accumulate the share of the stability fees recorded by CDP treasury into
+/// the fund.
+impl<T: Config> Happened<(RevenueSource, Balance)> for Pallet<T> {
+	fn happened((source, amount): &(RevenueSource, Balance)) {
+		if *source != RevenueSource::StabilityFee {
+			return;
+		}
+
+		let fee_amount = Self::fee_share()
+			.saturating_mul_int(*amount)
+			.min(Self::fund_cap().saturating_sub(Self::fund_balance()));
+		if fee_amount.is_zero() {
+			return;
+		}
+
+		match T::CDPTreasury::withdraw_surplus(&T::InsuranceFundAccount::get(), fee_amount) {
+			Ok(_) => Self::deposit_event(Event::FeeAccumulated(fee_amount)),
+			Err(e) => log::warn!(
+				target: "cdp-insurance-fund",
+				"withdraw_surplus: failed to accumulate the stability fee {:?}: {:?}. \
+				This is unexpected but should be safe",
+				fee_amount, e
+			),
+		}
+	}
+}
@@ -0,0 +1,132 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the CDP insurance fund module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn set_fee_share_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CDPInsuranceFundModule::set_fee_share(Origin::signed(BOB), Ratio::saturating_from_rational(1, 10)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPInsuranceFundModule::set_fee_share(Origin::signed(ALICE), Ratio::saturating_from_rational(11, 10)),
+			Error::<Runtime>::InvalidFeeShare
+		);
+
+		assert_ok!(CDPInsuranceFundModule::set_fee_share(
+			Origin::signed(ALICE),
+			Ratio::saturating_from_rational(1, 10)
+		));
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::FeeShareUpdated(
+			Ratio::saturating_from_rational(1, 10),
+		)));
+		assert_eq!(
+			CDPInsuranceFundModule::fee_share(),
+			Ratio::saturating_from_rational(1, 10)
+		);
+	});
+}
+
+#[test]
+fn set_fund_cap_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CDPInsuranceFundModule::set_fund_cap(Origin::signed(BOB), 100),
+			BadOrigin
+		);
+
+		assert_ok!(CDPInsuranceFundModule::set_fund_cap(Origin::signed(ALICE), 100));
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::FundCapUpdated(100)));
+		assert_eq!(CDPInsuranceFundModule::fund_cap(), 100);
+	});
+}
+
+#[test]
+fn accumulate_stability_fee_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPInsuranceFundModule::set_fee_share(
+			Origin::signed(ALICE),
+			Ratio::saturating_from_rational(1, 10)
+		));
+
+		// the fund cap is not set
+		CDPInsuranceFundModule::happened(&(RevenueSource::StabilityFee, 100));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 0);
+
+		assert_ok!(CDPInsuranceFundModule::set_fund_cap(Origin::signed(ALICE), 15));
+		CDPInsuranceFundModule::happened(&(RevenueSource::StabilityFee, 100));
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::FeeAccumulated(10)));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 10);
+		assert_eq!(surplus_pool(), 990);
+
+		// only the stability fees are accumulated
+		CDPInsuranceFundModule::happened(&(RevenueSource::LiquidationPenalty, 100));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 10);
+
+		// capped by the fund cap
+		CDPInsuranceFundModule::happened(&(RevenueSource::StabilityFee, 100));
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::FeeAccumulated(5)));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 15);
+		assert_eq!(surplus_pool(), 985);
+	});
+}
+
+#[test]
+fn cover_bad_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPInsuranceFundModule::set_fee_share(
+			Origin::signed(ALICE),
+			Ratio::one()
+		));
+		assert_ok!(CDPInsuranceFundModule::set_fund_cap(Origin::signed(ALICE), 50));
+		CDPInsuranceFundModule::happened(&(RevenueSource::StabilityFee, 50));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 50);
+		assert_eq!(surplus_pool(), 950);
+
+		// the bad debit is covered by the surplus pool
+		set_debit_pool(950);
+		CDPInsuranceFundModule::on_initialize(2);
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 50);
+
+		set_debit_pool(980);
+		CDPInsuranceFundModule::on_initialize(3);
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::BadDebitCovered(30)));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 20);
+		assert_eq!(surplus_pool(), 980);
+
+		// the debit covered by the surplus pool is not covered again
+		CDPInsuranceFundModule::on_initialize(4);
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 20);
+
+		// capped by the fund
+		set_debit_pool(1100);
+		CDPInsuranceFundModule::on_initialize(5);
+		System::assert_last_event(Event::CDPInsuranceFundModule(crate::Event::BadDebitCovered(20)));
+		assert_eq!(CDPInsuranceFundModule::fund_balance(), 0);
+		assert_eq!(surplus_pool(), 1000);
+	});
+}
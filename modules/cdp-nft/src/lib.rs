@@ -0,0 +1,202 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # CDP NFT Module
+//!
+//! ## Overview
+//!
+//! CDP NFT module represents the ownership of a CDP position by an NFT. When a
+//! position is tokenized, it's moved into a custody account derived from the
+//! token id, and the NFT is minted to the position owner. The NFT is
+//! transferable by the nft module like any other NFT, so the position could be
+//! traded, used in other protocols or held by smart contracts. The holder of
+//! the NFT can redeem it to move the position out of the custody, the NFT is
+//! burned then.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use module_nft::{ClassData, ClassProperty, Properties, TokenData};
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::traits::{AccountIdConversion, Zero};
+use sp_std::vec::Vec;
+use support::EmergencyShutdown;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type ClassIdOf<T> = <T as orml_nft::Config>::ClassId;
+pub type TokenIdOf<T> = <T as orml_nft::Config>::TokenId;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config
+		+ loans::Config
+		+ orml_nft::Config<ClassData = ClassData<Balance>, TokenData = TokenData<Balance>>
+	{
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The CDP NFT module id, the custody accounts of positions are
+		/// derived from it.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Emergency shutdown.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller has no position of the collateral
+		NoPosition,
+		/// The position NFT not exists
+		PositionTokenNotFound,
+		/// The caller is not the owner of the position NFT
+		NoPermission,
+		/// The system has been shutdown
+		AlreadyShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The position is tokenized. \[owner, collateral_type, class_id,
+		/// token_id\]
+		PositionTokenized(T::AccountId, CurrencyId, ClassIdOf<T>, TokenIdOf<T>),
+		/// The position NFT is redeemed. \[owner, collateral_type, class_id,
+		/// token_id\]
+		PositionRedeemed(T::AccountId, CurrencyId, ClassIdOf<T>, TokenIdOf<T>),
+	}
+
+	/// The NFT class of the tokenized positions.
+	///
+	/// PositionClassId: ClassId
+	#[pallet::storage]
+	#[pallet::getter(fn position_class_id)]
+	pub type PositionClassId<T: Config> = StorageValue<_, ClassIdOf<T>, OptionQuery>;
+
+	/// The collateral type of the position held by the custody account of the
+	/// token.
+	///
+	/// TokenizedPositions: map TokenId => Option<CurrencyId>
+	#[pallet::storage]
+	#[pallet::getter(fn tokenized_positions)]
+	pub type TokenizedPositions<T: Config> = StorageMap<_, Twox64Concat, TokenIdOf<T>, CurrencyId, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Move the caller's whole CDP of `currency_id` into custody, and mint
+		/// the NFT representing the ownership of it to the caller.
+		///
+		/// - `currency_id`: collateral currency id.
+		#[pallet::weight(<T as Config>::WeightInfo::tokenize_position())]
+		#[transactional]
+		pub fn tokenize_position(origin: OriginFor<T>, currency_id: CurrencyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			ensure!(
+				!<loans::Pallet<T>>::positions(currency_id, &who).collateral.is_zero(),
+				Error::<T>::NoPosition
+			);
+
+			let class_id = Self::get_or_create_class_id()?;
+			let token_data = TokenData {
+				deposit: Zero::zero(),
+				attributes: Default::default(),
+			};
+			let token_id = orml_nft::Pallet::<T>::mint(&who, class_id, currency_id.encode(), token_data)?;
+			<loans::Pallet<T>>::transfer_loan(&who, &Self::custody_account_id(token_id), currency_id)?;
+			TokenizedPositions::<T>::insert(token_id, currency_id);
+
+			Self::deposit_event(Event::PositionTokenized(who, currency_id, class_id, token_id));
+			Ok(())
+		}
+
+		/// Burn the position NFT held by the caller, and move the position in
+		/// custody to the caller's CDP. The collateral refunded to the custody
+		/// from liquidation is also transferred to the caller.
+		///
+		/// - `token_id`: the token id of the position NFT.
+		#[pallet::weight(<T as Config>::WeightInfo::redeem_position())]
+		#[transactional]
+		pub fn redeem_position(origin: OriginFor<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let class_id = Self::position_class_id().ok_or(Error::<T>::PositionTokenNotFound)?;
+			let currency_id = TokenizedPositions::<T>::take(token_id).ok_or(Error::<T>::PositionTokenNotFound)?;
+			let token_info =
+				orml_nft::Pallet::<T>::tokens(class_id, token_id).ok_or(Error::<T>::PositionTokenNotFound)?;
+			ensure!(token_info.owner == who, Error::<T>::NoPermission);
+
+			let custody_account = Self::custody_account_id(token_id);
+			<loans::Pallet<T>>::transfer_loan(&custody_account, &who, currency_id)?;
+			let refunded = <T as loans::Config>::Currency::free_balance(currency_id, &custody_account);
+			<T as loans::Config>::Currency::transfer(currency_id, &custody_account, &who, refunded)?;
+			orml_nft::Pallet::<T>::burn(&who, (class_id, token_id))?;
+
+			Self::deposit_event(Event::PositionRedeemed(who, currency_id, class_id, token_id));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the position represented by `token_id`.
+	pub fn custody_account_id(token_id: TokenIdOf<T>) -> T::AccountId {
+		<T as Config>::PalletId::get().into_sub_account(token_id)
+	}
+
+	fn get_or_create_class_id() -> Result<ClassIdOf<T>, DispatchError> {
+		if let Some(class_id) = Self::position_class_id() {
+			return Ok(class_id);
+		}
+
+		let class_data = ClassData {
+			deposit: Zero::zero(),
+			// not burnable through the NFT module, the token is only burned by
+			// redeeming the position it represents
+			properties: Properties(ClassProperty::Transferable.into()),
+			attributes: Default::default(),
+		};
+		let class_id = orml_nft::Pallet::<T>::create_class(
+			&<T as Config>::PalletId::get().into_account(),
+			Vec::new(),
+			class_data,
+		)?;
+		PositionClassId::<T>::put(class_id);
+		Ok(class_id)
+	}
+}
@@ -0,0 +1,108 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Unit tests for the cdp nft module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+
+#[test]
+fn tokenize_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CdpNftModule::tokenize_position(Origin::signed(ALICE), BTC),
+			Error::<Runtime>::NoPosition
+		);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CdpNftModule::tokenize_position(Origin::signed(ALICE), BTC));
+		System::assert_last_event(Event::CdpNftModule(crate::Event::PositionTokenized(ALICE, BTC, 0, 0)));
+
+		let custody_account = CdpNftModule::custody_account_id(0);
+		assert_eq!(CdpNftModule::position_class_id(), Some(0));
+		assert_eq!(
+			OrmlNFT::classes(0).unwrap().data.properties,
+			Properties(ClassProperty::Transferable.into())
+		);
+		assert_eq!(CdpNftModule::tokenized_positions(0), Some(BTC));
+		assert_eq!(OrmlNFT::tokens(0, 0).unwrap().owner, ALICE);
+		assert_eq!(LoansModule::positions(BTC, &ALICE).collateral, 0);
+		assert_eq!(LoansModule::positions(BTC, &ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(BTC, &custody_account).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, &custody_account).debit, 50);
+
+		// the class is reused by later positions
+		assert_ok!(LoansModule::adjust_position(&BOB, BTC, 200, 0));
+		assert_ok!(CdpNftModule::tokenize_position(Origin::signed(BOB), BTC));
+		assert_eq!(OrmlNFT::tokens(0, 1).unwrap().owner, BOB);
+		assert_eq!(
+			LoansModule::positions(BTC, &CdpNftModule::custody_account_id(1)).collateral,
+			200
+		);
+	});
+}
+
+#[test]
+fn tokenize_position_fails_after_shutdown() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 50));
+		mock_shutdown();
+		assert_noop!(
+			CdpNftModule::tokenize_position(Origin::signed(ALICE), BTC),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn redeem_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CdpNftModule::redeem_position(Origin::signed(ALICE), 0),
+			Error::<Runtime>::PositionTokenNotFound
+		);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CdpNftModule::tokenize_position(Origin::signed(ALICE), BTC));
+
+		// transfer of the NFT transfers the position
+		assert_ok!(OrmlNFT::transfer(&ALICE, &BOB, (0, 0)));
+		assert_noop!(
+			CdpNftModule::redeem_position(Origin::signed(ALICE), 0),
+			Error::<Runtime>::NoPermission
+		);
+
+		// collateral refunded to the custody is swept to the holder
+		let custody_account = CdpNftModule::custody_account_id(0);
+		assert_ok!(Currencies::transfer(Origin::signed(ALICE), custody_account, BTC, 200));
+
+		assert_ok!(CdpNftModule::redeem_position(Origin::signed(BOB), 0));
+		System::assert_last_event(Event::CdpNftModule(crate::Event::PositionRedeemed(BOB, BTC, 0, 0)));
+		assert_eq!(CdpNftModule::tokenized_positions(0), None);
+		assert!(OrmlNFT::tokens(0, 0).is_none());
+		assert_eq!(LoansModule::positions(BTC, &custody_account).collateral, 0);
+		assert_eq!(LoansModule::positions(BTC, &BOB).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, &BOB).debit, 50);
+		assert_eq!(Currencies::free_balance(BTC, &custody_account), 0);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 1200);
+	});
+}
@@ -0,0 +1,62 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Runtime API definition for cdp treasury module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::{Codec, Decode, Encode};
+use primitives::{CurrencyId, RevenueSource};
+use sp_runtime::{FixedU128, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// The solvency overview of the CDP treasury
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TreasuryHealth<Balance> {
+	/// The surplus pool
+	pub surplus_pool: Balance,
+	/// The debit pool
+	pub debit_pool: Balance,
+	/// The collateral types with the total collateral amount and the amount
+	/// in auction
+	pub collaterals: Vec<(CurrencyId, Balance, Balance)>,
+	/// The ratio of the surplus pool to the debit pool, `None` if the debit
+	/// pool is zero
+	pub reserve_ratio: Option<FixedU128>,
+}
+
+sp_api::decl_runtime_apis! {
+	#[api_version(3)]
+	pub trait CdpTreasuryApi<Balance> where
+		Balance: Codec,
+	{
+		/// The protocol revenue by source of the last `periods` periods, in
+		/// ascending order of the period index.
+		fn revenue_report(periods: u32) -> Vec<(u32, Vec<(RevenueSource, Balance)>)>;
+
+		/// The accumulated surplus generated by each collateral type,
+		/// available since version 2.
+		fn surplus_by_source() -> Vec<(CurrencyId, Balance)>;
+
+		/// The pools, the collaterals and the reserve ratio of the treasury,
+		/// available since version 3.
+		fn treasury_health() -> TreasuryHealth<Balance>;
+	}
+}
@@ -24,20 +24,45 @@
 //! CDPs, and handle excessive surplus or debits timely in order to keep the
 //! system healthy with low risk. It's the only entry for issuing/burning stable
 //! coin for whole system.
+//!
+//! The surplus and collateral held by CDP treasury can be lent as flash
+//! loans, which must be repaid with a fee in the same transaction.
+//!
+//! Besides the stable currency, governance can enable additional debt
+//! denominations. Each of them has separate surplus pool and debit pool,
+//! which are offset at the end of the offset period like the pools of the
+//! stable currency.
+//!
+//! When the debit pool exceeds the surplus pool, governance can choose to
+//! cover it by dilution, issuing native currency and selling it by DEX within
+//! a cap per period until the debit pool is cleared. The dilution stops after
+//! emergency shutdown.
+//!
+//! The end-of-block work, the offset of the pools, the disposal of the
+//! collateral, the yield strategies, the dilution and the surplus handling, is
+//! done in `on_idle` with the weight left in the block, and the work exceeding
+//! it is carried over to the following blocks, so that it can't overweight
+//! the block during liquidation storms. A minimum weight is reserved for it
+//! in `on_initialize`, so that it still makes progress in full blocks.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
-use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_support::{log, pallet_prelude::*, transactional, weights::GetDispatchInfo, PalletId};
 use frame_system::pallet_prelude::*;
-use orml_traits::{MultiCurrency, MultiCurrencyExtended};
-use primitives::{Balance, CurrencyId};
+use orml_traits::{Happened, MultiCurrency, MultiCurrencyExtended};
+use primitives::{Balance, CircuitBreakerId, CircuitBreakerReason, CurrencyId, RevenueSource};
+use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{AccountIdConversion, One, Zero},
-	ArithmeticError, DispatchError, DispatchResult, FixedPointNumber,
+	traits::{AccountIdConversion, Dispatchable, One, Saturating, UniqueSaturatedInto, Zero},
+	ArithmeticError, DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, prelude::*};
+use support::{
+	AggregatedSwap, AuctionManager, CDPTreasury, CDPTreasuryExtended, CircuitBreakerRecorder, CircuitBreakerUnpause,
+	DEXManager, DebtDenominations, EmergencyShutdown, FlashLoan, Price, PriceProvider, Rate, Ratio, SwapPath,
+	TreasuryYieldStrategy,
 };
-use sp_std::convert::TryInto;
-use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, DEXManager, Ratio};
 
 mod mock;
 mod tests;
@@ -46,6 +71,93 @@ pub mod weights;
 pub use module::*;
 pub use weights::WeightInfo;
 
+/// The way to handle the surplus exceeding the surplus buffer
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum SurplusHandling {
+	/// Keep the excess surplus in the surplus pool for surplus auctions
+	Auction,
+	/// Swap the excess surplus to native currency by DEX and burn it
+	BuyBackBurn,
+	/// Transfer the excess surplus to the treasury account
+	Transfer,
+}
+
+impl Default for SurplusHandling {
+	fn default() -> Self {
+		SurplusHandling::Auction
+	}
+}
+
+/// The way to cover the debit pool exceeding the surplus pool
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum DebitHandling {
+	/// Keep the debit pool until it's offset by the surplus pool
+	Keep,
+	/// Issue native currency and swap it to stable currency by DEX to cover
+	/// the debit pool, within the dilution cap of each period
+	Dilution,
+}
+
+impl Default for DebitHandling {
+	fn default() -> Self {
+		DebitHandling::Keep
+	}
+}
+
+/// Parameters of the decreasing price sale of specific collateral type
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct DecreasingPriceSaleParams {
+	/// The ratio of the start price of sale to the oracle price
+	pub start_price_ratio: Ratio,
+	/// The proportion of the start price decreased per block
+	pub decay_per_block: Ratio,
+	/// The ratio of the floor price of sale to the start price
+	pub floor_price_ratio: Ratio,
+	/// The minimum stable amount paid by a take, unless the take finishes
+	/// the sale
+	pub min_take_amount: Balance,
+}
+
+/// Collateral sold at a price decaying over time, anyone can take it
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CollateralSale<AccountId, BlockNumber> {
+	/// Collateral type for sale
+	pub currency_id: CurrencyId,
+	/// The debt denomination the sale is paid in
+	pub denomination: CurrencyId,
+	/// Remaining collateral amount for sale
+	pub amount: Balance,
+	/// Remaining amount of the debt denomination to raise
+	pub target: Balance,
+	/// Receiver of the collateral left when the target is raised
+	pub refund_recipient: AccountId,
+	/// Block number the sale starts at
+	pub start_block: BlockNumber,
+	/// The price of collateral in the debt denomination when the sale
+	/// starts
+	pub start_price: Price,
+	/// The proportion of the start price decreased per block
+	pub decay_per_block: Ratio,
+	/// The price of collateral will not decrease below it
+	pub floor_price: Price,
+	/// The minimum stable amount paid by a take, unless the take finishes
+	/// the sale
+	pub min_take_amount: Balance,
+}
+
+/// Collateral auction lot queued to be created in the following blocks
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct PendingCollateralAuction<AccountId> {
+	/// Receiver of the collateral left when the target is raised
+	pub refund_recipient: AccountId,
+	/// Collateral type for auction
+	pub currency_id: CurrencyId,
+	/// Collateral amount for auction
+	pub amount: Balance,
+	/// Stable amount to raise
+	pub target: Balance,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -65,6 +177,10 @@ pub mod module {
 		#[pallet::constant]
 		type GetStableCurrencyId: Get<CurrencyId>;
 
+		/// Native currency id, bought back and burned with the excess surplus
+		#[pallet::constant]
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
 		/// Auction manager creates auction to handle system surplus and debit
 		type AuctionManagerHandler: AuctionManager<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
 
@@ -72,12 +188,31 @@ pub mod module {
 		/// currency
 		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
 
+		/// Aggregated swap routes through both the DEX and the stable asset
+		/// pools, it's used to swap confiscated collateral assets to stable
+		/// currency when it gets more than the DEX
+		type Swap: AggregatedSwap<Self::AccountId, CurrencyId, Balance>;
+
+		/// The list of valid collateral currency types
+		#[pallet::constant]
+		type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+		/// The stable assets allowed to be enabled as the debt denominations
+		/// besides the stable currency
+		#[pallet::constant]
+		type DebtDenominationCurrencyIds: Get<Vec<CurrencyId>>;
+
 		/// The cap of lots number when create collateral auction on a
 		/// liquidation or to create debit/surplus auction on block end.
 		/// If set to 0, does not work.
 		#[pallet::constant]
 		type MaxAuctionsCount: Get<u32>;
 
+		/// The cap of collateral auctions created in a block, the lots
+		/// exceeding it are queued and created in the following blocks.
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+
 		#[pallet::constant]
 		type TreasuryAccount: Get<Self::AccountId>;
 
@@ -86,6 +221,66 @@ pub mod module {
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
 
+		/// The length in blocks of the period that protocol revenue is
+		/// accounted by.
+		#[pallet::constant]
+		type RevenuePeriod: Get<Self::BlockNumber>;
+
+		/// The length in blocks of the period that the debit pool and the
+		/// surplus pool are offset by, they're offset every block if it's
+		/// zero.
+		#[pallet::constant]
+		type OffsetPeriod: Get<Self::BlockNumber>;
+
+		/// The price source to determine the start price of decreasing price
+		/// sales
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The max slippage allowed when buying back native currency with the
+		/// excess surplus, or selling the native currency issued to cover the
+		/// debit pool by DEX, compared to the oracle price
+		#[pallet::constant]
+		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
+
+		/// Handler for the surplus pool changed, with the new surplus pool
+		type OnSurplusChanged: Happened<Balance>;
+
+		/// Handler for the debit pool changed, with the new debit pool
+		type OnDebitChanged: Happened<Balance>;
+
+		/// Handler for the debit pool exceeding `MaxDebitPool`, with the new
+		/// debit pool
+		type OnBadDebtOverflow: Happened<Balance>;
+
+		/// Record the auction exemptions of the collateral types as the
+		/// circuit breakers
+		type CircuitBreakerRecorder: CircuitBreakerRecorder;
+
+		/// Handler for the protocol revenue recorded, with the source and the
+		/// amount
+		type OnRevenue: Happened<(RevenueSource, Balance)>;
+
+		/// The earning strategy the idle collateral is deposited into, for the
+		/// collateral types with the yield strategy enabled
+		type YieldStrategy: TreasuryYieldStrategy<Self::AccountId, CurrencyId, Balance>;
+
+		/// The call executed by the borrower of a flash loan
+		type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo;
+
+		/// The minimum weight that should remain in the block after the
+		/// end-of-block work of CDP treasury is done in `on_idle`
+		#[pallet::constant]
+		type MinimumWeightRemainInBlock: Get<Weight>;
+
+		/// The weight reserved in `on_initialize` for the end-of-block work of
+		/// CDP treasury, so that it makes progress in full blocks where
+		/// `on_idle` gets no weight. Zero disables the reservation
+		#[pallet::constant]
+		type MinimumIdleWorkWeight: Get<Weight>;
+
+		/// Emergency shutdown
+		type EmergencyShutdown: EmergencyShutdown;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -100,6 +295,42 @@ pub mod module {
 		DebitPoolNotEnough,
 		/// The swap path is invalid
 		InvalidSwapPath,
+		/// The surplus pool would fall below the surplus buffer
+		BelowSurplusBuffer,
+		/// Decreasing price sale is not enabled for the collateral type
+		CollateralSaleNotEnabled,
+		/// The collateral sale does not exist
+		CollateralSaleNotExists,
+		/// The decreasing price sale parameters are invalid
+		InvalidCollateralSaleParams,
+		/// The current sale price is above the price limit
+		SalePriceAboveLimit,
+		/// The take of collateral sale is below the minimum take amount
+		CollateralSaleTakeTooSmall,
+		/// Feed price is invalid
+		InvalidFeedPrice,
+		/// Flash loan is not enabled for the currency
+		FlashLoanNotEnabled,
+		/// A flash loan is already in progress
+		FlashLoanInProgress,
+		/// The amount CDP treasury can lend is not enough
+		FlashLoanLiquidityNotEnough,
+		/// The flash loan and the fee are not repaid
+		FlashLoanNotRepaid,
+		/// The currency is not a collateral type
+		InvalidCollateralType,
+		/// The currency is not an enabled debt denomination
+		InvalidDebtDenomination,
+		/// The debit pool of the debt denomination is not empty, or some
+		/// collateral types are still denominated in it
+		DebtDenominationInUse,
+		/// The sale paid in other debt denomination can't be cancelled to
+		/// collateral auctions
+		CollateralSaleNotCancellable,
+		/// The aggregated swap paths can't get enough stable currency
+		AggregatedSwapNotEnough,
+		/// The dilution period is zero
+		InvalidDilutionParams,
 	}
 
 	#[pallet::event]
@@ -108,6 +339,124 @@ pub mod module {
 		/// The expected amount size for per lot collateral auction of specific
 		/// collateral type updated. \[collateral_type, new_size\]
 		ExpectedCollateralAuctionSizeUpdated(CurrencyId, Balance),
+		/// The surplus buffer size updated. \[new_size\]
+		SurplusBufferSizeUpdated(Balance),
+		/// The decreasing price sale parameters of specific collateral type
+		/// updated. \[collateral_type, new_params\]
+		CollateralSaleParamsUpdated(CurrencyId, Option<DecreasingPriceSaleParams>),
+		/// Collateral sale created. \[sale_id, collateral_type,
+		/// collateral_amount, target, start_price\]
+		NewCollateralSale(u32, CurrencyId, Balance, Balance, Price),
+		/// Collateral sale taken. \[sale_id, buyer, collateral_amount,
+		/// payment_amount\]
+		CollateralSaleTaken(u32, T::AccountId, Balance, Balance),
+		/// Collateral sale finished. \[sale_id, refund_collateral_amount\]
+		CollateralSaleFinished(u32, Balance),
+		/// Collateral sale cancelled, and the remaining collateral is
+		/// liquidated by collateral auctions. \[sale_id, collateral_amount,
+		/// target\]
+		CollateralSaleCancelled(u32, Balance, Balance),
+		/// The way to handle the excess surplus updated. \[new_handling\]
+		SurplusHandlingUpdated(SurplusHandling),
+		/// The excess surplus is swapped to native currency and burned.
+		/// \[surplus_amount, burned_native_amount\]
+		SurplusBoughtBackAndBurned(Balance, Balance),
+		/// The excess surplus is transferred to the treasury account.
+		/// \[surplus_amount\]
+		SurplusTransferredToTreasury(Balance),
+		/// The way to cover the debit pool updated. \[new_handling\]
+		DebitHandlingUpdated(DebitHandling),
+		/// The dilution params updated. \[cap_per_period, period\]
+		DilutionParamsUpdated(Balance, T::BlockNumber),
+		/// Native currency is issued and swapped to stable currency to cover
+		/// the debit pool. \[native_amount, stable_amount\]
+		NativeDilutedToCoverDebit(Balance, Balance),
+		/// The flash loan fee rate of specific currency updated.
+		/// \[currency_id, new_fee_rate\]
+		FlashLoanFeeRateUpdated(CurrencyId, Option<Rate>),
+		/// Flash loan issued. \[borrower, currency_id, amount\]
+		FlashLoanIssued(T::AccountId, CurrencyId, Balance),
+		/// Flash loan repaid. \[borrower, currency_id, amount, fee\]
+		FlashLoanRepaid(T::AccountId, CurrencyId, Balance, Balance),
+		/// The aggregated swap paths of specific collateral type updated.
+		/// \[collateral_type, new_paths\]
+		AggregatedSwapPathsUpdated(CurrencyId, Option<Vec<SwapPath<CurrencyId>>>),
+		/// The swap path of specific collateral type updated.
+		/// \[collateral_type, new_swap_path\]
+		CollateralSwapPathUpdated(CurrencyId, Option<Vec<CurrencyId>>),
+		/// The collateral is swapped to stable currency automatically.
+		/// \[collateral_type, collateral_amount, stable_amount\]
+		CollateralDisposed(CurrencyId, Balance, Balance),
+		/// The threshold of surplus auto extraction updated. \[new_threshold\]
+		SurplusAutoExtractThresholdUpdated(Option<Balance>),
+		/// The surplus exceeding the threshold is extracted to the treasury
+		/// account automatically. \[surplus_amount\]
+		SurplusAutoExtracted(Balance),
+		/// The cap of the debit pool updated. \[new_max_debit_pool\]
+		MaxDebitPoolUpdated(Option<Balance>),
+		/// The debit pool exceeds the cap. \[debit_pool, max_debit_pool\]
+		DebitPoolOverflow(Balance, Balance),
+		/// The collateral auction lot is queued since the auctions created in
+		/// the block reach the cap. \[collateral_type, collateral_amount,
+		/// target\]
+		CollateralAuctionQueued(CurrencyId, Balance, Balance),
+		/// The expected stable value for per lot collateral auction of specific
+		/// collateral type updated. \[collateral_type, new_value\]
+		ExpectedCollateralAuctionValueUpdated(CurrencyId, Option<Balance>),
+		/// The surplus is burned by governance. \[surplus_amount\]
+		SurplusBurned(Balance),
+		/// The surplus is deposited to the surplus pool. \[from, amount\]
+		SurplusDeposited(T::AccountId, Balance),
+		/// The surplus is withdrawn from the surplus pool. \[to, amount\]
+		SurplusWithdrawn(T::AccountId, Balance),
+		/// The stable currency is issued as debit. \[to, amount, backed\]
+		DebitIssued(T::AccountId, Balance, bool),
+		/// The stable currency is burned as debit. \[from, amount\]
+		DebitBurned(T::AccountId, Balance),
+		/// The surplus pool and the debit pool are offset. \[amount\]
+		DebitOffset(Balance),
+		/// The collateral is deposited to the treasury. \[from,
+		/// collateral_type, amount\]
+		CollateralDeposited(T::AccountId, CurrencyId, Balance),
+		/// The collateral is withdrawn from the treasury. \[to,
+		/// collateral_type, amount\]
+		CollateralWithdrawn(T::AccountId, CurrencyId, Balance),
+		/// The yield strategy of specific collateral type is enabled or
+		/// disabled. \[collateral_type, enabled\]
+		YieldStrategyUpdated(CurrencyId, bool),
+		/// The idle collateral is deposited into the yield strategy.
+		/// \[collateral_type, amount\]
+		CollateralDepositedToYieldStrategy(CurrencyId, Balance),
+		/// The collateral is withdrawn from the yield strategy.
+		/// \[collateral_type, amount\]
+		CollateralWithdrawnFromYieldStrategy(CurrencyId, Balance),
+		/// The accrued yield is harvested from the yield strategy.
+		/// \[collateral_type, yield_amount, stable_amount\]
+		YieldHarvested(CurrencyId, Balance, Balance),
+		/// The auction exemption of specific collateral type updated.
+		/// \[collateral_type, new_max_slippage\]
+		AuctionExemptCollateralUpdated(CurrencyId, Option<Ratio>),
+		/// The auction-exempt collateral is swapped by DEX instead of
+		/// auctions. \[collateral_type, collateral_amount, stable_amount\]
+		AuctionExemptCollateralSwapped(CurrencyId, Balance, Balance),
+		/// The stable currency is donated to the surplus pool. \[from,
+		/// amount\]
+		SurplusDonated(T::AccountId, Balance),
+		/// The collateral is donated to the treasury. \[from,
+		/// collateral_type, amount\]
+		CollateralDonated(T::AccountId, CurrencyId, Balance),
+		/// The debt denomination is enabled or disabled. \[denomination,
+		/// enabled\]
+		DebtDenominationUpdated(CurrencyId, bool),
+		/// Debit in the debt denomination is issued. \[denomination, to,
+		/// amount, backed\]
+		DenominationDebitIssued(CurrencyId, T::AccountId, Balance, bool),
+		/// Debit in the debt denomination is burned. \[denomination, from,
+		/// amount\]
+		DenominationDebitBurned(CurrencyId, T::AccountId, Balance),
+		/// The surplus pool and the debit pool of the debt denomination are
+		/// offset. \[denomination, amount\]
+		DenominationDebitOffset(CurrencyId, Balance),
 	}
 
 	/// The expected amount size for per lot collateral auction of specific
@@ -118,6 +467,16 @@ pub mod module {
 	#[pallet::getter(fn expected_collateral_auction_size)]
 	pub type ExpectedCollateralAuctionSize<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
 
+	/// The expected stable currency value for per lot collateral auction of
+	/// specific collateral type. If it's set, the lot size is computed from
+	/// the oracle price when the auction is created, instead of the expected
+	/// collateral auction size.
+	///
+	/// ExpectedCollateralAuctionValue: map CurrencyId => Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn expected_collateral_auction_value)]
+	pub type ExpectedCollateralAuctionValue<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, OptionQuery>;
+
 	/// Current total debit value of system. It's not same as debit in CDP
 	/// engine, it is the bad debt of the system.
 	///
@@ -126,6 +485,267 @@ pub mod module {
 	#[pallet::getter(fn debit_pool)]
 	pub type DebitPool<T: Config> = StorageValue<_, Balance, ValueQuery>;
 
+	/// The surplus kept in the surplus pool as a cushion to absorb bad debts,
+	/// only the surplus exceeding it can be extracted.
+	///
+	/// SurplusBufferSize: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn surplus_buffer_size)]
+	pub type SurplusBufferSize<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	/// The way to handle the surplus exceeding the surplus buffer.
+	///
+	/// SurplusHandlingMode: SurplusHandling
+	#[pallet::storage]
+	#[pallet::getter(fn surplus_handling)]
+	pub type SurplusHandlingMode<T: Config> = StorageValue<_, SurplusHandling, ValueQuery>;
+
+	/// The way to cover the debit pool exceeding the surplus pool.
+	///
+	/// DebitHandlingMode: DebitHandling
+	#[pallet::storage]
+	#[pallet::getter(fn debit_handling)]
+	pub type DebitHandlingMode<T: Config> = StorageValue<_, DebitHandling, ValueQuery>;
+
+	/// The cap of native currency issued to cover the debit pool in a
+	/// dilution period.
+	///
+	/// DilutionCapPerPeriod: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn dilution_cap_per_period)]
+	pub type DilutionCapPerPeriod<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	/// The length in blocks of the dilution period.
+	///
+	/// DilutionPeriod: BlockNumber
+	#[pallet::storage]
+	#[pallet::getter(fn dilution_period)]
+	pub type DilutionPeriod<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The start block of the current dilution period and the native
+	/// currency issued in it. The period is renewed `DilutionPeriod` blocks
+	/// after it starts, the changes of the dilution params don't reset it.
+	///
+	/// NativeDilutedInPeriod: (BlockNumber, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn native_diluted_in_period)]
+	pub type NativeDilutedInPeriod<T: Config> = StorageValue<_, (T::BlockNumber, Balance), ValueQuery>;
+
+	/// The decreasing price sale parameters of specific collateral type, the
+	/// collateral type is liquidated by auctions if it's not set.
+	///
+	/// CollateralSaleParams: map CurrencyId => Option<DecreasingPriceSaleParams>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_sale_params)]
+	pub type CollateralSaleParams<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, DecreasingPriceSaleParams, OptionQuery>;
+
+	/// The id of the next collateral sale.
+	///
+	/// NextCollateralSaleId: u32
+	#[pallet::storage]
+	#[pallet::getter(fn next_collateral_sale_id)]
+	pub type NextCollateralSaleId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The active collateral sales.
+	///
+	/// CollateralSales: map u32 => Option<CollateralSale>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_sales)]
+	pub type CollateralSales<T: Config> =
+		StorageMap<_, Twox64Concat, u32, CollateralSale<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// The total amount of specific collateral type in active sales.
+	///
+	/// TotalCollateralInSale: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_collateral_in_sale)]
+	pub type TotalCollateralInSale<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The protocol revenue from each source in each period.
+	///
+	/// Revenues: double_map PeriodIndex, RevenueSource => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn revenues)]
+	pub type Revenues<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, RevenueSource, Balance, ValueQuery>;
+
+	/// The accumulated surplus generated by each collateral type.
+	///
+	/// SurplusBySource: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn surplus_by_source)]
+	pub type SurplusBySource<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The liquidation penalty not realized yet by the collateral auctions and
+	/// sales of each collateral type, and the total target of them.
+	///
+	/// UnrealizedCollateralSurplus: map CurrencyId => (Balance, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn unrealized_collateral_surplus)]
+	pub type UnrealizedCollateralSurplus<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, (Balance, Balance), ValueQuery>;
+
+	/// The fee rate of flash loans of specific currency, the flash loan of it
+	/// is not enabled if it's not set.
+	///
+	/// FlashLoanFeeRates: map CurrencyId => Option<Rate>
+	#[pallet::storage]
+	#[pallet::getter(fn flash_loan_fee_rates)]
+	pub type FlashLoanFeeRates<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Rate, OptionQuery>;
+
+	/// Whether a flash loan is in progress, to prevent re-entrancy.
+	///
+	/// FlashLoanInProgress: bool
+	#[pallet::storage]
+	#[pallet::getter(fn flash_loan_in_progress)]
+	pub type FlashLoanInProgress<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The aggregated swap paths to swap specific collateral type to stable
+	/// currency, as the alternative to the DEX swap path.
+	///
+	/// AggregatedSwapPaths: map CurrencyId => Option<Vec<SwapPath>>
+	#[pallet::storage]
+	#[pallet::getter(fn aggregated_swap_paths)]
+	pub type AggregatedSwapPaths<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, Vec<SwapPath<CurrencyId>>, OptionQuery>;
+
+	/// The swap path to swap specific collateral type to stable currency. The
+	/// collateral not in auction is swapped automatically in `on_idle` if
+	/// its swap path is set.
+	///
+	/// CollateralSwapPath: map CurrencyId => Option<Vec<CurrencyId>>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_swap_path)]
+	pub type CollateralSwapPath<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Vec<CurrencyId>, OptionQuery>;
+
+	/// The threshold of the surplus pool, the surplus exceeding it is
+	/// extracted to the treasury account automatically in `on_initialize`.
+	///
+	/// SurplusAutoExtractThreshold: Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn surplus_auto_extract_threshold)]
+	pub type SurplusAutoExtractThreshold<T: Config> = StorageValue<_, Balance, OptionQuery>;
+
+	/// The cap of the debit pool, the bad debt overflow handler is triggered
+	/// when the debit pool exceeds it.
+	///
+	/// MaxDebitPool: Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn max_debit_pool)]
+	pub type MaxDebitPool<T: Config> = StorageValue<_, Balance, OptionQuery>;
+
+	/// The collateral auction lots waiting to be created, keyed by the queue
+	/// index.
+	///
+	/// PendingCollateralAuctions: map u32 => Option<PendingCollateralAuction>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_collateral_auctions)]
+	pub type PendingCollateralAuctions<T: Config> =
+		StorageMap<_, Twox64Concat, u32, PendingCollateralAuction<T::AccountId>, OptionQuery>;
+
+	/// The queue index range `[head, tail)` of the pending collateral auction
+	/// lots.
+	///
+	/// PendingCollateralAuctionsRange: (u32, u32)
+	#[pallet::storage]
+	#[pallet::getter(fn pending_collateral_auctions_range)]
+	pub type PendingCollateralAuctionsRange<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+	/// The total amount of specific collateral type in the pending collateral
+	/// auction lots.
+	///
+	/// TotalCollateralPendingAuction: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_collateral_pending_auction)]
+	pub type TotalCollateralPendingAuction<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The number of collateral auctions created in the current block.
+	///
+	/// CreatedAuctionsInBlock: u32
+	#[pallet::storage]
+	#[pallet::getter(fn created_auctions_in_block)]
+	pub type CreatedAuctionsInBlock<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Whether the idle collateral of specific collateral type is deposited
+	/// into the yield strategy.
+	///
+	/// YieldStrategyEnabled: map CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn yield_strategy_enabled)]
+	pub type YieldStrategyEnabled<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, bool, ValueQuery>;
+
+	/// The principal of specific collateral type deposited into the yield
+	/// strategy, the accrued yield excluded.
+	///
+	/// CollateralInYieldStrategy: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_in_yield_strategy)]
+	pub type CollateralInYieldStrategy<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The max slippage compared to the oracle price of the collateral types
+	/// exempt from auctions, the collateral of them is always swapped by DEX
+	/// instead of auctions.
+	///
+	/// AuctionExemptCollaterals: map CurrencyId => Option<Ratio>
+	#[pallet::storage]
+	#[pallet::getter(fn auction_exempt_collaterals)]
+	pub type AuctionExemptCollaterals<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Ratio, OptionQuery>;
+
+	/// The debt denominations enabled besides the stable currency.
+	///
+	/// DebtDenominationEnabled: map CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn debt_denomination_enabled)]
+	pub type DebtDenominationEnabled<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, bool, ValueQuery>;
+
+	/// The number of the debt denominations enabled besides the stable
+	/// currency.
+	///
+	/// DebtDenominationsCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn debt_denominations_count)]
+	pub type DebtDenominationsCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The bad debt of the debt denominations besides the stable currency.
+	///
+	/// DenominationDebitPool: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn denomination_debit_pool)]
+	pub type DenominationDebitPool<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The number of the collateral types denominated in the debt
+	/// denominations besides the stable currency.
+	///
+	/// DenominationConsumers: map CurrencyId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn denomination_consumers)]
+	pub type DenominationConsumers<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, u32, ValueQuery>;
+
+	/// Whether the offset of the debit pools and the surplus pools is due but
+	/// not done yet for the lack of weight, it's carried over to the
+	/// following blocks.
+	///
+	/// OffsetPending: bool
+	#[pallet::storage]
+	#[pallet::getter(fn offset_pending)]
+	pub type OffsetPending<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The raw key of the last collateral type disposed in `on_idle`, the
+	/// following ones are disposed first in the next block.
+	///
+	/// DisposeCollateralsStartKey: Option<Vec<u8>>
+	#[pallet::storage]
+	pub type DisposeCollateralsStartKey<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// The raw key of the last collateral type whose yield strategy is
+	/// managed in `on_idle`, the following ones are managed first in the next
+	/// block.
+	///
+	/// YieldStrategiesStartKey: Option<Vec<u8>>
+	#[pallet::storage]
+	pub type YieldStrategiesStartKey<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig {
 		pub expected_collateral_auction_size: Vec<(CurrencyId, Balance)>,
@@ -151,95 +771,1351 @@ pub mod module {
 		}
 	}
 
-	#[pallet::pallet]
-	pub struct Pallet<T>(_);
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// create the queued collateral auction lots with the cap of the block
+			let created_pending_auctions = Self::create_pending_collateral_auctions();
+			let mut weight = T::WeightInfo::create_pending_collateral_auctions(created_pending_auctions);
+
+			// extract the surplus exceeding the auto extract threshold every block,
+			// not only when there is weight left for `on_idle`
+			weight = weight.saturating_add(T::DbWeight::get().reads(1));
+			if Self::surplus_auto_extract_threshold().is_some() {
+				Self::auto_extract_surplus();
+				weight = weight.saturating_add(T::WeightInfo::auto_extract_surplus());
+			}
+
+			// do the end-of-block work with the reserved weight first, the rest is
+			// done in `on_idle`
+			let minimum_idle_work_weight = T::MinimumIdleWorkWeight::get();
+			if minimum_idle_work_weight.is_zero() {
+				return weight;
+			}
+			weight.saturating_add(Self::do_idle_work(now, minimum_idle_work_weight))
+		}
+
+		/// Handle excessive surplus or debits of system with the weight left
+		/// in the block, the work exceeding it is carried over to the following
+		/// blocks.
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::do_idle_work(
+				now,
+				remaining_weight.saturating_sub(T::MinimumWeightRemainInBlock::get()),
+			)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(T::WeightInfo::extract_surplus_to_treasury())]
+		#[transactional]
+		pub fn extract_surplus_to_treasury(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				amount <= Self::surplus_pool().saturating_sub(Self::surplus_buffer_size()),
+				Error::<T>::BelowSurplusBuffer
+			);
+			T::Currency::transfer(
+				T::GetStableCurrencyId::get(),
+				&Self::account_id(),
+				&T::TreasuryAccount::get(),
+				amount,
+			)?;
+			Self::on_surplus_changed();
+			Ok(())
+		}
+
+		/// Burn the surplus exceeding the surplus buffer directly, retiring
+		/// the stable currency from the total issuance.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `amount`: the surplus amount to burn
+		#[pallet::weight((T::WeightInfo::burn_surplus(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn burn_surplus(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				amount <= Self::surplus_pool().saturating_sub(Self::surplus_buffer_size()),
+				Error::<T>::BelowSurplusBuffer
+			);
+			T::Currency::withdraw(T::GetStableCurrencyId::get(), &Self::account_id(), amount)?;
+			Self::on_surplus_changed();
+			Self::deposit_event(Event::SurplusBurned(amount));
+			Ok(())
+		}
+
+		/// Auction the collateral of CDP treasury, split into lots if
+		/// `splited`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// The weight of `MaxAuctionsCount` lots is charged upfront, and the
+		/// weight of the lots not created is refunded.
+		///
+		/// - `currency_id`: collateral type
+		/// - `amount`: the amount of collateral to auction
+		/// - `target`: the target amount of stable currency to raise
+		/// - `splited`: whether to split the collateral into lots
+		#[pallet::weight(T::WeightInfo::auction_collateral(T::MaxAuctionsCount::get()))]
+		#[transactional]
+		pub fn auction_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] amount: Balance,
+			#[pallet::compact] target: Balance,
+			splited: bool,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let created_auctions = <Self as CDPTreasuryExtended<T::AccountId>>::create_collateral_auctions(
+				currency_id,
+				amount,
+				target,
+				Self::account_id(),
+				splited,
+			)?;
+			Ok(Some(T::WeightInfo::auction_collateral(created_auctions)).into())
+		}
+
+		/// Update parameters related to collateral auction under specific
+		/// collateral type
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `amount`: expected size of per lot collateral auction
+		#[pallet::weight((T::WeightInfo::set_expected_collateral_auction_size(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_expected_collateral_auction_size(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] size: Balance,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ExpectedCollateralAuctionSize::<T>::insert(currency_id, size);
+			Self::deposit_event(Event::ExpectedCollateralAuctionSizeUpdated(currency_id, size));
+			Ok(())
+		}
+
+		/// Update the expected stable currency value of per lot collateral
+		/// auction under specific collateral type, `None` means the expected
+		/// collateral auction size is used.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `value`: expected stable value of per lot collateral auction
+		#[pallet::weight((T::WeightInfo::set_expected_collateral_auction_value(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_expected_collateral_auction_value(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			value: Option<Balance>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ExpectedCollateralAuctionValue::<T>::mutate_exists(currency_id, |maybe_value| *maybe_value = value);
+			Self::deposit_event(Event::ExpectedCollateralAuctionValueUpdated(currency_id, value));
+			Ok(())
+		}
+
+		/// Update the surplus buffer size, the surplus below which is kept to
+		/// absorb bad debts.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `size`: the surplus buffer size
+		#[pallet::weight((T::WeightInfo::set_surplus_buffer_size(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_surplus_buffer_size(origin: OriginFor<T>, #[pallet::compact] size: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			SurplusBufferSize::<T>::put(size);
+			Self::deposit_event(Event::SurplusBufferSizeUpdated(size));
+			Ok(())
+		}
+
+		/// Update the decreasing price sale parameters of specific collateral
+		/// type, `None` means to liquidate it by auctions.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `params`: the decreasing price sale parameters
+		#[pallet::weight((T::WeightInfo::set_collateral_sale_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_collateral_sale_params(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			params: Option<DecreasingPriceSaleParams>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if let Some(params) = params {
+				ensure!(
+					!params.start_price_ratio.is_zero() && params.floor_price_ratio <= Ratio::one(),
+					Error::<T>::InvalidCollateralSaleParams
+				);
+			}
+			CollateralSaleParams::<T>::mutate_exists(currency_id, |maybe_params| *maybe_params = params);
+			Self::deposit_event(Event::CollateralSaleParamsUpdated(currency_id, params));
+			Ok(())
+		}
+
+		/// Update the way to handle the surplus exceeding the surplus buffer.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `handling`: the way to handle the excess surplus
+		#[pallet::weight((T::WeightInfo::set_surplus_handling(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_surplus_handling(origin: OriginFor<T>, handling: SurplusHandling) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			SurplusHandlingMode::<T>::put(handling);
+			Self::deposit_event(Event::SurplusHandlingUpdated(handling));
+			Ok(())
+		}
+
+		/// Update the way to cover the debit pool exceeding the surplus pool.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `handling`: the way to cover the debit pool
+		#[pallet::weight((T::WeightInfo::set_debit_handling(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_debit_handling(origin: OriginFor<T>, handling: DebitHandling) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			DebitHandlingMode::<T>::put(handling);
+			Self::deposit_event(Event::DebitHandlingUpdated(handling));
+			Ok(())
+		}
+
+		/// Update the cap of native currency issued to cover the debit pool
+		/// in each dilution period.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `cap_per_period`: the cap of native currency issued in a period
+		/// - `period`: the length in blocks of the dilution period
+		#[pallet::weight((T::WeightInfo::set_dilution_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_dilution_params(
+			origin: OriginFor<T>,
+			#[pallet::compact] cap_per_period: Balance,
+			period: T::BlockNumber,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(!period.is_zero(), Error::<T>::InvalidDilutionParams);
+			DilutionCapPerPeriod::<T>::put(cap_per_period);
+			DilutionPeriod::<T>::put(period);
+			Self::deposit_event(Event::DilutionParamsUpdated(cap_per_period, period));
+			Ok(())
+		}
+
+		/// Buy collateral from the collateral sale at the current sale price.
+		///
+		/// - `sale_id`: the id of the collateral sale
+		/// - `amount`: the collateral amount to buy, it's capped by the
+		///   remaining collateral and target of the sale
+		/// - `max_price`: the highest acceptable price of collateral in the
+		///   debt denomination of the sale
+		#[pallet::weight(T::WeightInfo::take_collateral_sale())]
+		#[transactional]
+		pub fn take_collateral_sale(
+			origin: OriginFor<T>,
+			#[pallet::compact] sale_id: u32,
+			#[pallet::compact] amount: Balance,
+			max_price: Price,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_take_collateral_sale(&who, sale_id, amount, max_price)
+		}
+
+		/// Cancel the collateral sale, the remaining collateral is liquidated
+		/// by collateral auctions to raise the remaining target. The sale paid
+		/// in other debt denomination can't be cancelled, since collateral
+		/// auctions raise the stable currency only.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `sale_id`: the id of the collateral sale
+		#[pallet::weight((T::WeightInfo::cancel_collateral_sale(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn cancel_collateral_sale(origin: OriginFor<T>, #[pallet::compact] sale_id: u32) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let sale = CollateralSales::<T>::take(sale_id).ok_or(Error::<T>::CollateralSaleNotExists)?;
+			ensure!(
+				sale.denomination == T::GetStableCurrencyId::get(),
+				Error::<T>::CollateralSaleNotCancellable
+			);
+			TotalCollateralInSale::<T>::mutate(sale.currency_id, |balance| {
+				*balance = balance.saturating_sub(sale.amount)
+			});
+			<Self as CDPTreasuryExtended<T::AccountId>>::create_collateral_auctions(
+				sale.currency_id,
+				sale.amount,
+				sale.target,
+				sale.refund_recipient,
+				true,
+			)?;
+			Self::deposit_event(Event::CollateralSaleCancelled(sale_id, sale.amount, sale.target));
+			Ok(())
+		}
+
+		/// Update the flash loan fee rate of specific currency, `None` means
+		/// to disable the flash loan of it.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: the currency to lend
+		/// - `fee_rate`: the fee rate charged on the loan amount
+		#[pallet::weight((T::WeightInfo::set_flash_loan_fee_rate(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_flash_loan_fee_rate(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			fee_rate: Option<Rate>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			FlashLoanFeeRates::<T>::mutate_exists(currency_id, |maybe_rate| *maybe_rate = fee_rate);
+			Self::deposit_event(Event::FlashLoanFeeRateUpdated(currency_id, fee_rate));
+			Ok(())
+		}
+
+		/// Update the aggregated swap paths of specific collateral type,
+		/// `None` means to swap it by the DEX only.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `paths`: the aggregated swap paths from the collateral to stable
+		///   currency
+		#[pallet::weight((T::WeightInfo::set_aggregated_swap_paths(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_aggregated_swap_paths(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			paths: Option<Vec<SwapPath<CurrencyId>>>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if let Some(paths) = &paths {
+				ensure!(
+					T::Swap::get_swap_currencies(paths) == Some((currency_id, T::GetStableCurrencyId::get())),
+					Error::<T>::InvalidSwapPath
+				);
+			}
+			AggregatedSwapPaths::<T>::mutate_exists(currency_id, |maybe_paths| *maybe_paths = paths.clone());
+			Self::deposit_event(Event::AggregatedSwapPathsUpdated(currency_id, paths));
+			Ok(())
+		}
+
+		/// Update the swap path of specific collateral type, `None` means to
+		/// swap it by the direct path and stop disposing it automatically.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `swap_path`: the DEX swap path from the collateral to stable
+		///   currency
+		#[pallet::weight((T::WeightInfo::set_collateral_swap_path(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_collateral_swap_path(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			swap_path: Option<Vec<CurrencyId>>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if let Some(path) = &swap_path {
+				ensure!(
+					path.len() >= 2
+						&& path.first() == Some(&currency_id)
+						&& path.last() == Some(&T::GetStableCurrencyId::get()),
+					Error::<T>::InvalidSwapPath
+				);
+			}
+			CollateralSwapPath::<T>::mutate_exists(currency_id, |maybe_path| *maybe_path = swap_path.clone());
+			Self::deposit_event(Event::CollateralSwapPathUpdated(currency_id, swap_path));
+			Ok(())
+		}
+
+		/// Update the threshold of the surplus pool, the surplus exceeding it
+		/// is extracted to the treasury account in `on_initialize`. `None`
+		/// means to stop the auto extraction.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `threshold`: the surplus auto extract threshold
+		#[pallet::weight((T::WeightInfo::set_surplus_auto_extract_threshold(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_surplus_auto_extract_threshold(origin: OriginFor<T>, threshold: Option<Balance>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			SurplusAutoExtractThreshold::<T>::set(threshold);
+			Self::deposit_event(Event::SurplusAutoExtractThresholdUpdated(threshold));
+			Ok(())
+		}
+
+		/// Update the cap of the debit pool, `None` means no cap.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `max_debit_pool`: the cap of the debit pool
+		#[pallet::weight((T::WeightInfo::set_max_debit_pool(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_max_debit_pool(origin: OriginFor<T>, max_debit_pool: Option<Balance>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			MaxDebitPool::<T>::set(max_debit_pool);
+			Self::deposit_event(Event::MaxDebitPoolUpdated(max_debit_pool));
+			Ok(())
+		}
+
+		/// Enable or disable depositing the idle collateral of specific
+		/// collateral type into the yield strategy. When disabled, the
+		/// accrued yield is harvested and all the collateral is withdrawn
+		/// from the yield strategy.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `enabled`: whether the yield strategy is enabled
+		#[pallet::weight((T::WeightInfo::set_yield_strategy_enabled(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_yield_strategy_enabled(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if enabled {
+				YieldStrategyEnabled::<T>::insert(currency_id, true);
+			} else {
+				YieldStrategyEnabled::<T>::remove(currency_id);
+				Self::harvest_yield(currency_id)?;
+				Self::withdraw_from_yield_strategy(currency_id, Self::collateral_in_yield_strategy(currency_id))?;
+			}
+			Self::deposit_event(Event::YieldStrategyUpdated(currency_id, enabled));
+			Ok(())
+		}
+
+		/// Update the auction exemption of specific collateral type, the
+		/// collateral of the auction-exempt collateral type is always swapped
+		/// by DEX instead of auctions. `None` means to auction it.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type
+		/// - `max_slippage`: the max slippage of the swap compared to the
+		///   oracle price
+		#[pallet::weight((T::WeightInfo::set_auction_exempt_collateral(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_auction_exempt_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			max_slippage: Option<Ratio>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::do_set_auction_exempt_collateral(currency_id, max_slippage);
+			Ok(())
+		}
+
+		/// Donate stable currency to the surplus pool.
+		///
+		/// - `amount`: the stable currency amount to donate
+		#[pallet::weight(T::WeightInfo::deposit_surplus_from())]
+		#[transactional]
+		pub fn deposit_surplus_from(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::account_id(), amount)?;
+			Self::on_surplus_changed();
+			Self::deposit_event(Event::SurplusDonated(who, amount));
+			Ok(())
+		}
+
+		/// Donate collateral to the treasury, only the collateral types are
+		/// accepted.
+		///
+		/// - `currency_id`: collateral type
+		/// - `amount`: the collateral amount to donate
+		#[pallet::weight(T::WeightInfo::donate_collateral())]
+		#[transactional]
+		pub fn donate_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType
+			);
+			T::Currency::transfer(currency_id, &who, &Self::account_id(), amount)?;
+			Self::deposit_event(Event::CollateralDonated(who, currency_id, amount));
+			Ok(())
+		}
+
+		/// Borrow the surplus or collateral of CDP treasury, and dispatch
+		/// `call` as the caller. The loan and the fee must be repaid by the
+		/// caller when `call` finishes, otherwise the whole call fails.
+		///
+		/// - `currency_id`: the currency to borrow
+		/// - `amount`: the amount to borrow
+		/// - `call`: the call to dispatch with the loan
+		#[pallet::weight((
+			T::WeightInfo::flash_loan().saturating_add(call.get_dispatch_info().weight),
+			DispatchClass::Normal
+		))]
+		#[transactional]
+		pub fn flash_loan(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] amount: Balance,
+			call: Box<<T as Config>::Call>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			<Self as FlashLoan<T::AccountId, CurrencyId, Balance>>::flash_loan(&who, currency_id, amount, || {
+				call.dispatch(frame_system::RawOrigin::Signed(who.clone()).into())
+					.map(|_| ())
+					.map_err(|e| e.error)
+			})
+		}
+
+		/// Enable or disable the debt denomination besides the stable
+		/// currency. It can't be disabled while its debit pool is not empty
+		/// or any collateral type is denominated in it.
+		/// Only the stable assets in `DebtDenominationCurrencyIds` can be
+		/// enabled, and they must not be collateral types, since the surplus
+		/// pool of the denomination is the free balance of CDP treasury.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `denomination`: the currency the debt is denominated in
+		/// - `enabled`: whether the debt denomination is enabled
+		#[pallet::weight((T::WeightInfo::set_debt_denomination_enabled(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_debt_denomination_enabled(
+			origin: OriginFor<T>,
+			denomination: CurrencyId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				denomination != T::GetStableCurrencyId::get(),
+				Error::<T>::InvalidDebtDenomination
+			);
+			if enabled {
+				ensure!(
+					T::DebtDenominationCurrencyIds::get().contains(&denomination),
+					Error::<T>::InvalidDebtDenomination
+				);
+				if !DebtDenominationEnabled::<T>::contains_key(denomination) {
+					DebtDenominationEnabled::<T>::insert(denomination, true);
+					DebtDenominationsCount::<T>::mutate(|count| *count = count.saturating_add(1));
+				}
+			} else {
+				ensure!(
+					Self::denomination_debit_pool(denomination).is_zero()
+						&& Self::denomination_consumers(denomination).is_zero(),
+					Error::<T>::DebtDenominationInUse
+				);
+				if DebtDenominationEnabled::<T>::contains_key(denomination) {
+					DebtDenominationEnabled::<T>::remove(denomination);
+					DebtDenominationsCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+				}
+			}
+			Self::deposit_event(Event::DebtDenominationUpdated(denomination, enabled));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of cdp treasury module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Get current total surplus of system.
+	pub fn surplus_pool() -> Balance {
+		T::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id())
+	}
+
+	/// Get total collateral amount of cdp treasury module, including the
+	/// collateral in the yield strategy.
+	pub fn total_collaterals(currency_id: CurrencyId) -> Balance {
+		T::Currency::free_balance(currency_id, &Self::account_id())
+			.saturating_add(Self::collateral_in_yield_strategy(currency_id))
+	}
+
+	/// Get collateral amount not in auction or sale
+	pub fn total_collaterals_not_in_auction(currency_id: CurrencyId) -> Balance {
+		Self::total_collaterals(currency_id).saturating_sub(Self::collateral_in_auction_or_sale(currency_id))
+	}
+
+	/// Get collateral amount in auction, pending auction or sale
+	fn collateral_in_auction_or_sale(currency_id: CurrencyId) -> Balance {
+		T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id)
+			.saturating_add(Self::total_collateral_pending_auction(currency_id))
+			.saturating_add(Self::total_collateral_in_sale(currency_id))
+	}
+
+	/// Get the expected lot size of collateral auction. If the expected
+	/// auction value is set, it's computed from the current oracle price,
+	/// otherwise it's the expected collateral auction size.
+	pub fn collateral_auction_lot_size(currency_id: CurrencyId) -> Balance {
+		Self::expected_collateral_auction_value(currency_id)
+			.and_then(|value| {
+				T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+					.and_then(|price| price.reciprocal())
+					.map(|reciprocal_price| reciprocal_price.saturating_mul_int(value))
+			})
+			.unwrap_or_else(|| Self::expected_collateral_auction_size(currency_id))
+	}
+
+	/// Create the collateral auction lot if the auctions created in the block
+	/// don't reach the cap and no lot is queued before, otherwise queue it.
+	fn create_or_queue_collateral_auction(
+		refund_recipient: &T::AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		target: Balance,
+	) -> DispatchResult {
+		let (head, tail) = Self::pending_collateral_auctions_range();
+		let created_auctions = Self::created_auctions_in_block();
+		if head == tail && created_auctions < T::MaxAuctionsPerBlock::get() {
+			T::AuctionManagerHandler::new_collateral_auction(refund_recipient, currency_id, amount, target)?;
+			CreatedAuctionsInBlock::<T>::put(created_auctions.saturating_add(1));
+		} else {
+			let new_tail = tail.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			TotalCollateralPendingAuction::<T>::try_mutate(currency_id, |total| -> DispatchResult {
+				*total = total.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+			PendingCollateralAuctions::<T>::insert(
+				tail,
+				PendingCollateralAuction {
+					refund_recipient: refund_recipient.clone(),
+					currency_id,
+					amount,
+					target,
+				},
+			);
+			PendingCollateralAuctionsRange::<T>::put((head, new_tail));
+			Self::deposit_event(Event::CollateralAuctionQueued(currency_id, amount, target));
+		}
+		Ok(())
+	}
+
+	/// Create the queued collateral auction lots in order, at most
+	/// `MaxAuctionsPerBlock` lots, return the number of attempted lots. The
+	/// lot failed to be created is re-queued and stops the creation in the
+	/// block.
+	pub fn create_pending_collateral_auctions() -> u32 {
+		let (mut head, mut tail) = Self::pending_collateral_auctions_range();
+		let mut created_auctions: u32 = 0;
+		let mut attempts: u32 = 0;
+		while head < tail && created_auctions < T::MaxAuctionsPerBlock::get() {
+			let maybe_lot = PendingCollateralAuctions::<T>::take(head);
+			head = head.saturating_add(1);
+			if let Some(lot) = maybe_lot {
+				attempts = attempts.saturating_add(1);
+				match T::AuctionManagerHandler::new_collateral_auction(
+					&lot.refund_recipient,
+					lot.currency_id,
+					lot.amount,
+					lot.target,
+				) {
+					Ok(_) => {
+						TotalCollateralPendingAuction::<T>::mutate(lot.currency_id, |total| {
+							*total = total.saturating_sub(lot.amount)
+						});
+						created_auctions = created_auctions.saturating_add(1);
+					}
+					Err(e) => {
+						// re-queue the lot and retry it in the following blocks, the collateral
+						// stays reserved for it
+						log::warn!(
+							target: "cdp-treasury",
+							"create_pending_collateral_auctions: Attempt to create queued collateral auction {:?} failed: {:?}, re-queue it",
+							lot, e
+						);
+						PendingCollateralAuctions::<T>::insert(tail, lot);
+						tail = tail.saturating_add(1);
+						break;
+					}
+				}
+			}
+		}
+		PendingCollateralAuctionsRange::<T>::put((head, tail));
+		CreatedAuctionsInBlock::<T>::put(created_auctions);
+		attempts
+	}
+
+	/// Do the end-of-block work within `available_weight`, return the weight
+	/// used. The offset is done first since it absorbs the bad debit, then the
+	/// collateral disposal and the yield strategies resume from where the last
+	/// block stopped, and the surplus handling goes last.
+	pub fn do_idle_work(now: T::BlockNumber, available_weight: Weight) -> Weight {
+		let offset_period = T::OffsetPeriod::get();
+		let offset_due = offset_period.is_zero() || (now % offset_period).is_zero();
+		let mut weight_used: Weight = T::DbWeight::get().reads(1);
+		if weight_used > available_weight {
+			// the due offset is carried over to the following blocks
+			if offset_due {
+				OffsetPending::<T>::put(true);
+				return T::DbWeight::get().writes(1);
+			}
+			return Zero::zero();
+		}
+
+		// offset the same amount between debit pool and surplus pool at the end of
+		// the offset period
+		if offset_due || Self::offset_pending() {
+			let offset_weight = T::WeightInfo::offset_denominations(Self::debt_denominations_count());
+			weight_used = weight_used.saturating_add(T::DbWeight::get().reads(1));
+			if weight_used.saturating_add(offset_weight) > available_weight {
+				OffsetPending::<T>::put(true);
+				return weight_used.saturating_add(T::DbWeight::get().writes(1));
+			}
+			Self::offset_surplus_and_debit();
+			Self::offset_denominations();
+			OffsetPending::<T>::kill();
+			weight_used = weight_used.saturating_add(offset_weight);
+		}
+
+		// swap the collateral with swap path set to stable currency
+		weight_used =
+			weight_used.saturating_add(Self::dispose_collaterals(available_weight.saturating_sub(weight_used)));
+		// harvest the yield and deposit the idle collateral into the yield
+		// strategies
+		weight_used = weight_used.saturating_add(Self::manage_yield_strategies(
+			available_weight.saturating_sub(weight_used),
+		));
+
+		// cover the debit pool exceeding the surplus pool by native currency
+		if Self::debit_handling() == DebitHandling::Dilution {
+			let dilute_to_cover_debit_weight = T::WeightInfo::dilute_to_cover_debit();
+			if weight_used.saturating_add(dilute_to_cover_debit_weight) > available_weight {
+				return weight_used;
+			}
+			Self::dilute_to_cover_debit(now);
+			weight_used = weight_used.saturating_add(dilute_to_cover_debit_weight);
+		}
+
+		// handle the surplus exceeding the surplus buffer
+		if Self::surplus_handling() != SurplusHandling::Auction {
+			let handle_excess_surplus_weight = T::WeightInfo::handle_excess_surplus();
+			if weight_used.saturating_add(handle_excess_surplus_weight) > available_weight {
+				return weight_used;
+			}
+			Self::handle_excess_surplus();
+			weight_used = weight_used.saturating_add(handle_excess_surplus_weight);
+		}
+
+		weight_used
+	}
+
+	/// Get the current price of the collateral sale, it decreases linearly
+	/// from the start price until it reaches the floor price.
+	pub fn collateral_sale_price(sale: &CollateralSale<T::AccountId, T::BlockNumber>) -> Price {
+		let elapsed_blocks: u128 = frame_system::Pallet::<T>::block_number()
+			.saturating_sub(sale.start_block)
+			.unique_saturated_into();
+		let decay = sale
+			.decay_per_block
+			.saturating_mul(Ratio::saturating_from_integer(elapsed_blocks));
+		sale.start_price
+			.saturating_mul(Ratio::one().saturating_sub(decay))
+			.max(sale.floor_price)
+	}
+
+	/// Handle the surplus exceeding the surplus buffer by the surplus
+	/// handling mode, unless the debit pool is outstanding.
+	pub fn handle_excess_surplus() {
+		let excess_surplus = Self::surplus_pool().saturating_sub(Self::surplus_buffer_size());
+		let handling = Self::surplus_handling();
+		// the surplus is kept to offset the outstanding debit at the end of the
+		// offset period
+		if excess_surplus.is_zero() || handling == SurplusHandling::Auction || !Self::debit_pool().is_zero() {
+			return;
+		}
+
+		let res = match handling {
+			SurplusHandling::Auction => Ok(()),
+			SurplusHandling::BuyBackBurn => Self::buy_back_and_burn(excess_surplus),
+			SurplusHandling::Transfer => T::Currency::transfer(
+				T::GetStableCurrencyId::get(),
+				&Self::account_id(),
+				&T::TreasuryAccount::get(),
+				excess_surplus,
+			)
+			.map(|_| Self::deposit_event(Event::SurplusTransferredToTreasury(excess_surplus))),
+		};
+		match res {
+			Ok(_) => Self::on_surplus_changed(),
+			Err(e) => log::warn!(
+				target: "cdp-treasury",
+				"handle_excess_surplus: Attempt to handle excess surplus {:?} failed: {:?}, this is unexpected but should be safe",
+				excess_surplus, e
+			),
+		}
+	}
+
+	/// Extract the surplus exceeding the auto extract threshold to the
+	/// treasury account.
+	pub fn auto_extract_surplus() {
+		let threshold = match Self::surplus_auto_extract_threshold() {
+			Some(threshold) => threshold,
+			None => return,
+		};
+		let excess_surplus = Self::surplus_pool().saturating_sub(threshold);
+		if excess_surplus.is_zero() {
+			return;
+		}
+
+		match T::Currency::transfer(
+			T::GetStableCurrencyId::get(),
+			&Self::account_id(),
+			&T::TreasuryAccount::get(),
+			excess_surplus,
+		) {
+			Ok(_) => {
+				Self::deposit_event(Event::SurplusAutoExtracted(excess_surplus));
+				Self::on_surplus_changed();
+			}
+			Err(e) => log::warn!(
+				target: "cdp-treasury",
+				"auto_extract_surplus: Attempt to extract surplus {:?} failed: {:?}, this is unexpected but should be safe",
+				excess_surplus, e
+			),
+		}
+	}
+
+	/// Swap the collateral not in auction of the collateral types with swap
+	/// path set to stable currency, at most the expected collateral auction
+	/// size of each type per block. The collateral types are disposed in
+	/// order from `DisposeCollateralsStartKey` within `weight_limit`, return
+	/// the weight used.
+	pub fn dispose_collaterals(weight_limit: Weight) -> Weight {
+		let iterator = match DisposeCollateralsStartKey::<T>::get() {
+			Some(key) => CollateralSwapPath::<T>::iter_from(key),
+			None => CollateralSwapPath::<T>::iter(),
+		};
+
+		let mut weight_used: Weight = Zero::zero();
+		let mut last_disposed = None;
+		let mut finished = true;
+		for (currency_id, swap_path) in iterator {
+			let dispose_weight = T::WeightInfo::dispose_collaterals(1)
+				.saturating_add(T::WeightInfo::swap_exact_collateral_to_stable(swap_path.len() as u32));
+			if weight_used.saturating_add(dispose_weight) > weight_limit {
+				finished = false;
+				break;
+			}
+			weight_used = weight_used.saturating_add(dispose_weight);
+			last_disposed = Some(currency_id);
+
+			let mut amount = Self::total_collaterals_not_in_auction(currency_id);
+			let lot_size = Self::collateral_auction_lot_size(currency_id);
+			if !lot_size.is_zero() {
+				amount = amount.min(lot_size);
+			}
+			if amount.is_zero() {
+				continue;
+			}
+
+			if let Err(e) = Self::dispose_collateral(currency_id, amount, &swap_path) {
+				log::warn!(
+					target: "cdp-treasury",
+					"dispose_collaterals: Attempt to swap collateral {:?} {:?} failed: {:?}, this is unexpected but should be safe",
+					amount, currency_id, e
+				);
+			}
+		}
+
+		match (finished, last_disposed) {
+			(true, _) => DisposeCollateralsStartKey::<T>::kill(),
+			(false, Some(currency_id)) => {
+				DisposeCollateralsStartKey::<T>::put(CollateralSwapPath::<T>::hashed_key_for(currency_id))
+			}
+			// nothing is disposed, resume from the same key
+			(false, None) => {}
+		}
+		weight_used
+	}
+
+	/// Swap `amount` collateral to stable currency by `swap_path`, return the
+	/// stable amount.
+	#[transactional]
+	fn dispose_collateral(
+		currency_id: CurrencyId,
+		amount: Balance,
+		swap_path: &[CurrencyId],
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		// calculate the min target limit by slippage limit for the price of oracle
+		let min_target_amount = Ratio::one()
+			.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+			.saturating_mul_int(
+				T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+					.ok_or(Error::<T>::InvalidFeedPrice)?
+					.saturating_mul_int(amount),
+			);
+		let stable_amount = <Self as CDPTreasuryExtended<T::AccountId>>::swap_exact_collateral_to_stable(
+			currency_id,
+			amount,
+			min_target_amount,
+			swap_path,
+			false,
+		)?;
+
+		Self::deposit_event(Event::CollateralDisposed(currency_id, amount, stable_amount));
+		Ok(stable_amount)
+	}
+
+	/// Harvest the accrued yield and deposit the idle collateral into the
+	/// yield strategies of the enabled collateral types. The collateral types
+	/// are managed in order from `YieldStrategiesStartKey` within
+	/// `weight_limit`, return the weight used.
+	pub fn manage_yield_strategies(weight_limit: Weight) -> Weight {
+		let manage_weight = T::WeightInfo::manage_yield_strategies(1);
+		let iterator = match YieldStrategiesStartKey::<T>::get() {
+			Some(key) => YieldStrategyEnabled::<T>::iter_from(key),
+			None => YieldStrategyEnabled::<T>::iter(),
+		};
+
+		let mut weight_used: Weight = Zero::zero();
+		let mut last_managed = None;
+		let mut finished = true;
+		for (currency_id, _) in iterator {
+			if weight_used.saturating_add(manage_weight) > weight_limit {
+				finished = false;
+				break;
+			}
+			weight_used = weight_used.saturating_add(manage_weight);
+			last_managed = Some(currency_id);
+
+			if let Err(e) = Self::harvest_yield(currency_id) {
+				log::warn!(
+					target: "cdp-treasury",
+					"manage_yield_strategies: Attempt to harvest the yield of {:?} failed: {:?}, this is unexpected but should be safe",
+					currency_id, e
+				);
+			}
+
+			let idle_amount = T::Currency::free_balance(currency_id, &Self::account_id())
+				.saturating_sub(Self::collateral_in_auction_or_sale(currency_id));
+			if let Err(e) = Self::deposit_to_yield_strategy(currency_id, idle_amount) {
+				log::warn!(
+					target: "cdp-treasury",
+					"manage_yield_strategies: Attempt to deposit {:?} {:?} into the yield strategy failed: {:?}, this is unexpected but should be safe",
+					idle_amount, currency_id, e
+				);
+			}
+		}
+
+		match (finished, last_managed) {
+			(true, _) => YieldStrategiesStartKey::<T>::kill(),
+			(false, Some(currency_id)) => {
+				YieldStrategiesStartKey::<T>::put(YieldStrategyEnabled::<T>::hashed_key_for(currency_id))
+			}
+			// nothing is managed, resume from the same key
+			(false, None) => {}
+		}
+		weight_used
+	}
+
+	/// Withdraw the accrued yield of the collateral type from the yield
+	/// strategy. It's swapped to stable currency and accrues to the surplus
+	/// pool if the swap path of the collateral type is set, otherwise it's
+	/// kept as the collateral of CDP treasury.
+	#[transactional]
+	fn harvest_yield(currency_id: CurrencyId) -> DispatchResult {
+		let principal = Self::collateral_in_yield_strategy(currency_id);
+		let yield_amount = T::YieldStrategy::balance(&Self::account_id(), currency_id).saturating_sub(principal);
+		if yield_amount.is_zero() {
+			return Ok(());
+		}
+
+		T::YieldStrategy::withdraw(&Self::account_id(), currency_id, yield_amount)?;
+		let stable_amount = match Self::collateral_swap_path(currency_id) {
+			Some(swap_path) => {
+				let stable_amount = Self::dispose_collateral(currency_id, yield_amount, &swap_path)?;
+				<Self as CDPTreasury<T::AccountId>>::on_revenue(RevenueSource::YieldStrategy, stable_amount);
+				<Self as CDPTreasury<T::AccountId>>::on_collateral_surplus(currency_id, stable_amount);
+				stable_amount
+			}
+			None => Zero::zero(),
+		};
+
+		Self::deposit_event(Event::YieldHarvested(currency_id, yield_amount, stable_amount));
+		Ok(())
+	}
+
+	#[transactional]
+	fn deposit_to_yield_strategy(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+
+		// only the amount that actually left CDP treasury is recorded as the
+		// principal, the no-op strategy deposits nothing
+		let free_balance = T::Currency::free_balance(currency_id, &Self::account_id());
+		T::YieldStrategy::deposit(&Self::account_id(), currency_id, amount)?;
+		let deposited = free_balance.saturating_sub(T::Currency::free_balance(currency_id, &Self::account_id()));
+		if deposited.is_zero() {
+			return Ok(());
+		}
+
+		CollateralInYieldStrategy::<T>::try_mutate(currency_id, |principal| -> DispatchResult {
+			*principal = principal.checked_add(deposited).ok_or(ArithmeticError::Overflow)?;
+			Ok(())
+		})?;
+		Self::deposit_event(Event::CollateralDepositedToYieldStrategy(currency_id, deposited));
+		Ok(())
+	}
+
+	#[transactional]
+	fn withdraw_from_yield_strategy(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+
+		T::YieldStrategy::withdraw(&Self::account_id(), currency_id, amount)?;
+		CollateralInYieldStrategy::<T>::mutate(currency_id, |principal| *principal = principal.saturating_sub(amount));
+		Self::deposit_event(Event::CollateralWithdrawnFromYieldStrategy(currency_id, amount));
+		Ok(())
+	}
+
+	/// Swap the auction-exempt collateral by DEX to raise `target` instead of
+	/// auctions, with the price bounded by `max_slippage` compared to the
+	/// oracle price. The collateral left is refunded to `refund_receiver`.
+	#[transactional]
+	fn swap_auction_exempt_collateral(
+		currency_id: CurrencyId,
+		amount: Balance,
+		target: Balance,
+		refund_receiver: &T::AccountId,
+		max_slippage: Ratio,
+	) -> DispatchResult {
+		let min_price = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+			.ok_or(Error::<T>::InvalidFeedPrice)?
+			.saturating_mul(Ratio::one().saturating_sub(max_slippage));
+		let swap_path = <Self as CDPTreasuryExtended<T::AccountId>>::get_collateral_swap_path(currency_id);
+		let max_supply_amount = min_price
+			.reciprocal()
+			.map_or(amount, |reciprocal_price| reciprocal_price.saturating_mul_int(target));
+
+		let (supply_amount, stable_amount) = if max_supply_amount <= amount {
+			// the target can be raised, refund the collateral left
+			let supply_amount = <Self as CDPTreasuryExtended<T::AccountId>>::swap_collateral_to_exact_stable(
+				currency_id,
+				max_supply_amount,
+				target,
+				&swap_path,
+				false,
+			)?;
+			let refund_amount = amount.saturating_sub(supply_amount);
+			if !refund_amount.is_zero() && *refund_receiver != Self::account_id() {
+				<Self as CDPTreasury<T::AccountId>>::withdraw_collateral(refund_receiver, currency_id, refund_amount)?;
+			}
+			(supply_amount, target)
+		} else {
+			let stable_amount = <Self as CDPTreasuryExtended<T::AccountId>>::swap_exact_collateral_to_stable(
+				currency_id,
+				amount,
+				min_price.saturating_mul_int(amount),
+				&swap_path,
+				false,
+			)?;
+			(amount, stable_amount)
+		};
+		<Self as CDPTreasury<T::AccountId>>::on_collateral_proceeds(currency_id, target, stable_amount);
+
+		Self::deposit_event(Event::AuctionExemptCollateralSwapped(
+			currency_id,
+			supply_amount,
+			stable_amount,
+		));
+		Ok(())
+	}
+
+	/// Withdraw the shortfall from the yield strategy if the free collateral
+	/// of CDP treasury is less than `amount`.
+	fn ensure_liquid_collateral(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		let shortfall = amount
+			.saturating_sub(T::Currency::free_balance(currency_id, &Self::account_id()))
+			.min(Self::collateral_in_yield_strategy(currency_id));
+		Self::withdraw_from_yield_strategy(currency_id, shortfall)
+	}
+
+	/// Swap `amount` surplus to native currency by DEX and burn it.
+	#[transactional]
+	fn buy_back_and_burn(amount: Balance) -> DispatchResult {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let native_currency_id = T::GetNativeCurrencyId::get();
+
+		// calculate the min target limit by slippage limit for the price of oracle
+		let min_target_amount = Ratio::one()
+			.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+			.saturating_mul_int(
+				T::PriceSource::get_relative_price(stable_currency_id, native_currency_id)
+					.ok_or(Error::<T>::InvalidFeedPrice)?
+					.saturating_mul_int(amount),
+			);
+		let native_amount = T::DEX::swap_with_exact_supply(
+			&Self::account_id(),
+			&[stable_currency_id, native_currency_id],
+			amount,
+			min_target_amount,
+		)?;
+		T::Currency::withdraw(native_currency_id, &Self::account_id(), native_amount)?;
+
+		Self::deposit_event(Event::SurplusBoughtBackAndBurned(amount, native_amount));
+		Ok(())
+	}
+
+	/// Issue native currency within the cap of the current dilution period,
+	/// and swap it to stable currency by DEX to cover the debit pool
+	/// exceeding the surplus pool. No native currency is issued after
+	/// emergency shutdown.
+	pub fn dilute_to_cover_debit(now: T::BlockNumber) {
+		let uncovered_debit = Self::debit_pool().saturating_sub(Self::surplus_pool());
+		let period = Self::dilution_period();
+		if uncovered_debit.is_zero() || period.is_zero() || T::EmergencyShutdown::is_shutdown() {
+			return;
+		}
+
+		// the cap is renewed when the current period ends, the native currency issued
+		// in it is kept when the dilution params change
+		let (last_period_start, diluted) = Self::native_diluted_in_period();
+		let (period_start, diluted) = if now >= last_period_start.saturating_add(period) {
+			(now, Zero::zero())
+		} else {
+			(last_period_start, diluted)
+		};
+		let remaining_cap = Self::dilution_cap_per_period().saturating_sub(diluted);
+		if remaining_cap.is_zero() {
+			return;
+		}
+
+		let native_amount = T::DEX::get_swap_supply_amount(
+			&[T::GetNativeCurrencyId::get(), T::GetStableCurrencyId::get()],
+			uncovered_debit,
+		)
+		.unwrap_or(remaining_cap)
+		.min(remaining_cap);
+		match Self::issue_and_sell_native(native_amount) {
+			Ok(_) => {
+				NativeDilutedInPeriod::<T>::put((period_start, diluted.saturating_add(native_amount)));
+				Self::on_surplus_changed();
+				Self::offset_surplus_and_debit();
+			}
+			Err(e) => log::warn!(
+				target: "cdp-treasury",
+				"dilute_to_cover_debit: Attempt to issue and sell native currency {:?} failed: {:?}, this is unexpected but should be safe",
+				native_amount, e
+			),
+		}
+	}
+
+	#[transactional]
+	fn issue_and_sell_native(native_amount: Balance) -> sp_std::result::Result<Balance, DispatchError> {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let native_currency_id = T::GetNativeCurrencyId::get();
+
+		// calculate the min target limit by slippage limit for the price of oracle
+		let min_target_amount = Ratio::one()
+			.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+			.saturating_mul_int(
+				T::PriceSource::get_relative_price(native_currency_id, stable_currency_id)
+					.ok_or(Error::<T>::InvalidFeedPrice)?
+					.saturating_mul_int(native_amount),
+			);
+		T::Currency::deposit(native_currency_id, &Self::account_id(), native_amount)?;
+		let stable_amount = T::DEX::swap_with_exact_supply(
+			&Self::account_id(),
+			&[native_currency_id, stable_currency_id],
+			native_amount,
+			min_target_amount,
+		)?;
+
+		Self::deposit_event(Event::NativeDilutedToCoverDebit(native_amount, stable_amount));
+		Ok(stable_amount)
+	}
+
+	fn do_take_collateral_sale(who: &T::AccountId, sale_id: u32, amount: Balance, max_price: Price) -> DispatchResult {
+		CollateralSales::<T>::try_mutate_exists(sale_id, |maybe_sale| -> DispatchResult {
+			let mut sale = maybe_sale.take().ok_or(Error::<T>::CollateralSaleNotExists)?;
+			let price = Self::collateral_sale_price(&sale);
+			ensure!(price <= max_price, Error::<T>::SalePriceAboveLimit);
+
+			let mut collateral_amount = amount.min(sale.amount);
+			let mut payment_amount = price.saturating_mul_int(collateral_amount);
+			if payment_amount > sale.target {
+				// only sell the collateral enough to raise the remaining target
+				payment_amount = sale.target;
+				collateral_amount = price
+					.reciprocal()
+					.map(|reciprocal| reciprocal.saturating_mul_int(payment_amount))
+					.unwrap_or(collateral_amount)
+					.min(collateral_amount);
+			}
+			// dust takes are rejected, except the take finishing the sale
+			ensure!(
+				!collateral_amount.is_zero()
+					&& (payment_amount >= sale.min_take_amount
+						|| collateral_amount == sale.amount
+						|| payment_amount == sale.target),
+				Error::<T>::CollateralSaleTakeTooSmall
+			);
+
+			// the proceeds of other debt denomination go to its surplus pool, the
+			// collateral proceeds are accounted in the stable currency only
+			let stable_denominated = sale.denomination == T::GetStableCurrencyId::get();
+			if stable_denominated {
+				<Self as CDPTreasury<T::AccountId>>::deposit_surplus(who, payment_amount)?;
+			} else {
+				T::Currency::transfer(sale.denomination, who, &Self::account_id(), payment_amount)?;
+			}
+			<Self as CDPTreasury<T::AccountId>>::withdraw_collateral(who, sale.currency_id, collateral_amount)?;
+			if stable_denominated {
+				<Self as CDPTreasury<T::AccountId>>::on_collateral_proceeds(
+					sale.currency_id,
+					payment_amount,
+					payment_amount,
+				);
+			}
+			sale.amount = sale.amount.saturating_sub(collateral_amount);
+			sale.target = sale.target.saturating_sub(payment_amount);
+			TotalCollateralInSale::<T>::mutate(sale.currency_id, |balance| {
+				*balance = balance.saturating_sub(collateral_amount)
+			});
+			Self::deposit_event(Event::CollateralSaleTaken(
+				sale_id,
+				who.clone(),
+				collateral_amount,
+				payment_amount,
+			));
+
+			if sale.amount.is_zero() || sale.target.is_zero() {
+				// the penalty share of the target not raised is never realized
+				if stable_denominated {
+					<Self as CDPTreasury<T::AccountId>>::on_collateral_proceeds(
+						sale.currency_id,
+						sale.target,
+						Zero::zero(),
+					);
+				}
+				// refund the remaining collateral when the target is raised
+				if !sale.amount.is_zero() {
+					<Self as CDPTreasury<T::AccountId>>::withdraw_collateral(
+						&sale.refund_recipient,
+						sale.currency_id,
+						sale.amount,
+					)?;
+					TotalCollateralInSale::<T>::mutate(sale.currency_id, |balance| {
+						*balance = balance.saturating_sub(sale.amount)
+					});
+				}
+				Self::deposit_event(Event::CollateralSaleFinished(sale_id, sale.amount));
+			} else {
+				*maybe_sale = Some(sale);
+			}
+			Ok(())
+		})
+	}
+
+	/// Get the index of current revenue period.
+	pub fn current_revenue_period() -> u32 {
+		let now = frame_system::Pallet::<T>::block_number();
+		(now / T::RevenuePeriod::get()).unique_saturated_into()
+	}
 
-	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
-		/// Handle excessive surplus or debits of system when block end
-		fn on_finalize(_now: T::BlockNumber) {
-			// offset the same amount between debit pool and surplus pool
-			Self::offset_surplus_and_debit();
+	/// Get the protocol revenue by source of the last `periods` periods,
+	/// including current period, in ascending order of period index.
+	pub fn revenue_report(periods: u32) -> Vec<(u32, Vec<(RevenueSource, Balance)>)> {
+		if periods.is_zero() {
+			return vec![];
 		}
+
+		let current_period = Self::current_revenue_period();
+		let first_period = current_period.saturating_sub(periods.saturating_sub(1));
+		(first_period..=current_period)
+			.map(|period| {
+				let mut revenues: Vec<(RevenueSource, Balance)> = Revenues::<T>::iter_prefix(period).collect();
+				revenues.sort_by_key(|(source, _)| *source);
+				(period, revenues)
+			})
+			.collect()
 	}
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		#[pallet::weight(T::WeightInfo::extract_surplus_to_treasury())]
-		#[transactional]
-		pub fn extract_surplus_to_treasury(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
-			T::UpdateOrigin::ensure_origin(origin)?;
-			T::Currency::transfer(
-				T::GetStableCurrencyId::get(),
-				&Self::account_id(),
-				&T::TreasuryAccount::get(),
-				amount,
-			)?;
-			Ok(())
-		}
+	/// Get the ratio of the surplus pool to the debit pool, `None` if the
+	/// debit pool is zero.
+	pub fn reserve_ratio() -> Option<Ratio> {
+		Ratio::checked_from_rational(Self::surplus_pool(), Self::debit_pool())
+	}
 
-		#[pallet::weight(T::WeightInfo::auction_collateral(T::MaxAuctionsCount::get()))]
-		#[transactional]
-		pub fn auction_collateral(
-			origin: OriginFor<T>,
-			currency_id: CurrencyId,
-			#[pallet::compact] amount: Balance,
-			#[pallet::compact] target: Balance,
-			splited: bool,
-		) -> DispatchResultWithPostInfo {
-			T::UpdateOrigin::ensure_origin(origin)?;
-			let created_auctions = <Self as CDPTreasuryExtended<T::AccountId>>::create_collateral_auctions(
-				currency_id,
-				amount,
-				target,
-				Self::account_id(),
-				splited,
-			)?;
-			Ok(Some(T::WeightInfo::auction_collateral(created_auctions)).into())
-		}
+	/// Get the total amount and the amount in auction of the collateral
+	/// types.
+	pub fn collateral_report(currency_ids: Vec<CurrencyId>) -> Vec<(CurrencyId, Balance, Balance)> {
+		currency_ids
+			.into_iter()
+			.map(|currency_id| {
+				(
+					currency_id,
+					Self::total_collaterals(currency_id),
+					T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id),
+				)
+			})
+			.collect()
+	}
 
-		/// Update parameters related to collateral auction under specific
-		/// collateral type
-		///
-		/// The dispatch origin of this call must be `UpdateOrigin`.
-		///
-		/// - `currency_id`: collateral type
-		/// - `amount`: expected size of per lot collateral auction
-		#[pallet::weight((T::WeightInfo::set_expected_collateral_auction_size(), DispatchClass::Operational))]
-		#[transactional]
-		pub fn set_expected_collateral_auction_size(
-			origin: OriginFor<T>,
-			currency_id: CurrencyId,
-			#[pallet::compact] size: Balance,
-		) -> DispatchResult {
-			T::UpdateOrigin::ensure_origin(origin)?;
-			ExpectedCollateralAuctionSize::<T>::insert(currency_id, size);
-			Self::deposit_event(Event::ExpectedCollateralAuctionSizeUpdated(currency_id, size));
-			Ok(())
+	/// Get the accumulated surplus generated by each collateral type.
+	pub fn surplus_report() -> Vec<(CurrencyId, Balance)> {
+		let mut surplus: Vec<(CurrencyId, Balance)> = SurplusBySource::<T>::iter().collect();
+		surplus.sort_by_key(|(currency_id, _)| *currency_id);
+		surplus
+	}
+
+	fn do_set_auction_exempt_collateral(currency_id: CurrencyId, max_slippage: Option<Ratio>) {
+		let previous = AuctionExemptCollaterals::<T>::mutate_exists(currency_id, |maybe_slippage| {
+			sp_std::mem::replace(maybe_slippage, max_slippage)
+		});
+		match (previous, max_slippage) {
+			(None, Some(_)) => T::CircuitBreakerRecorder::on_paused(
+				CircuitBreakerId::CollateralAuction(currency_id),
+				CircuitBreakerReason::Governance,
+			),
+			(Some(_), None) => T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::CollateralAuction(currency_id)),
+			_ => {}
 		}
+		Self::deposit_event(Event::AuctionExemptCollateralUpdated(currency_id, max_slippage));
 	}
-}
 
-impl<T: Config> Pallet<T> {
-	/// Get account of cdp treasury module.
-	pub fn account_id() -> T::AccountId {
-		T::PalletId::get().into_account()
+	fn on_surplus_changed() {
+		T::OnSurplusChanged::happened(&Self::surplus_pool());
 	}
 
-	/// Get current total surplus of system.
-	pub fn surplus_pool() -> Balance {
-		T::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id())
+	fn on_debit_changed() {
+		T::OnDebitChanged::happened(&Self::debit_pool());
 	}
 
-	/// Get total collateral amount of cdp treasury module.
-	pub fn total_collaterals(currency_id: CurrencyId) -> Balance {
-		T::Currency::free_balance(currency_id, &Self::account_id())
+	fn increase_debit_pool(amount: Balance) -> Result<Balance, DispatchError> {
+		let debit_pool = DebitPool::<T>::try_mutate(|debit_pool| -> Result<Balance, DispatchError> {
+			*debit_pool = debit_pool.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+			Ok(*debit_pool)
+		})?;
+		Self::on_debit_changed();
+		Ok(debit_pool)
 	}
 
-	/// Get collateral amount not in auction
-	pub fn total_collaterals_not_in_auction(currency_id: CurrencyId) -> Balance {
-		T::Currency::free_balance(currency_id, &Self::account_id())
-			.saturating_sub(T::AuctionManagerHandler::get_total_collateral_in_auction(currency_id))
+	/// Trigger the circuit breaker if the bad debt exceeds the cap.
+	fn check_debit_pool_overflow(debit_pool: Balance) {
+		if let Some(max_debit_pool) = Self::max_debit_pool() {
+			if debit_pool > max_debit_pool {
+				Self::deposit_event(Event::DebitPoolOverflow(debit_pool, max_debit_pool));
+				T::OnBadDebtOverflow::happened(&debit_pool);
+			}
+		}
 	}
 
 	fn offset_surplus_and_debit() {
@@ -255,6 +2131,9 @@ impl<T: Config> Pallet<T> {
 							.checked_sub(offset_amount)
 							.expect("offset = min(debit, surplus); qed")
 					});
+					Self::on_surplus_changed();
+					Self::on_debit_changed();
+					Self::deposit_event(Event::DebitOffset(offset_amount));
 				}
 				Err(e) => {
 					log::warn!(
@@ -266,6 +2145,38 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 	}
+
+	/// Offset the debit pool of each enabled debt denomination by its
+	/// surplus pool.
+	pub fn offset_denominations() {
+		for (denomination, _) in DebtDenominationEnabled::<T>::iter() {
+			let offset_amount = sp_std::cmp::min(
+				Self::denomination_debit_pool(denomination),
+				Self::get_surplus_pool_of(denomination),
+			);
+			if offset_amount.is_zero() {
+				continue;
+			}
+
+			match T::Currency::withdraw(denomination, &Self::account_id(), offset_amount) {
+				Ok(_) => {
+					DenominationDebitPool::<T>::mutate(denomination, |debit| {
+						*debit = debit
+							.checked_sub(offset_amount)
+							.expect("offset = min(debit, surplus); qed")
+					});
+					Self::deposit_event(Event::DenominationDebitOffset(denomination, offset_amount));
+				}
+				Err(e) => {
+					log::warn!(
+						target: "cdp-treasury",
+						"offset_denominations: Attempt to burn surplus {:?} of {:?} failed: {:?}, this is unexpected but should be safe",
+						offset_amount, denomination, e
+					);
+				}
+			}
+		}
+	}
 }
 
 impl<T: Config> CDPTreasury<T::AccountId> for Pallet<T> {
@@ -290,46 +2201,324 @@ impl<T: Config> CDPTreasury<T::AccountId> for Pallet<T> {
 	}
 
 	fn on_system_debit(amount: Self::Balance) -> DispatchResult {
-		DebitPool::<T>::try_mutate(|debit_pool| -> DispatchResult {
-			*debit_pool = debit_pool.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
-			Ok(())
-		})
+		let debit_pool = Self::increase_debit_pool(amount)?;
+		Self::check_debit_pool_overflow(debit_pool);
+		Ok(())
 	}
 
 	fn on_system_surplus(amount: Self::Balance) -> DispatchResult {
-		Self::issue_debit(&Self::account_id(), amount, true)
+		Self::issue_debit(&Self::account_id(), amount, true)?;
+		Self::on_surplus_changed();
+		Ok(())
+	}
+
+	fn on_revenue(source: RevenueSource, amount: Self::Balance) {
+		if !amount.is_zero() {
+			Revenues::<T>::mutate(Self::current_revenue_period(), source, |revenue| {
+				*revenue = revenue.saturating_add(amount)
+			});
+			T::OnRevenue::happened(&(source, amount));
+		}
+	}
+
+	fn on_collateral_surplus(currency_id: Self::CurrencyId, amount: Self::Balance) {
+		if !amount.is_zero() {
+			SurplusBySource::<T>::mutate(currency_id, |surplus| *surplus = surplus.saturating_add(amount));
+		}
+	}
+
+	fn on_unrealized_collateral_surplus(currency_id: Self::CurrencyId, penalty: Self::Balance, target: Self::Balance) {
+		if !target.is_zero() {
+			UnrealizedCollateralSurplus::<T>::mutate(currency_id, |(unrealized, total_target)| {
+				*unrealized = unrealized.saturating_add(penalty.min(target));
+				*total_target = total_target.saturating_add(target);
+			});
+		}
+	}
+
+	fn on_collateral_proceeds(currency_id: Self::CurrencyId, target: Self::Balance, proceeds: Self::Balance) {
+		if target.is_zero() {
+			return;
+		}
+
+		// the penalty is the share of the target raised last, the ended auctions or
+		// sales take the penalty share of their target out of the unrealized
+		let realized = UnrealizedCollateralSurplus::<T>::mutate_exists(currency_id, |maybe_unrealized| {
+			let (unrealized, total_target) = maybe_unrealized.unwrap_or_default();
+			let target = target.min(total_target);
+			let penalty = Ratio::checked_from_rational(target, total_target)
+				.unwrap_or_default()
+				.saturating_mul_int(unrealized)
+				.min(unrealized);
+			let remaining_target = total_target.saturating_sub(target);
+			*maybe_unrealized = if remaining_target.is_zero() {
+				None
+			} else {
+				Some((unrealized.saturating_sub(penalty), remaining_target))
+			};
+			proceeds.saturating_sub(target.saturating_sub(penalty)).min(penalty)
+		});
+		<Self as CDPTreasury<T::AccountId>>::on_collateral_surplus(currency_id, realized);
 	}
 
+	#[transactional]
 	fn issue_debit(who: &T::AccountId, debit: Self::Balance, backed: bool) -> DispatchResult {
 		// increase system debit if the debit is unbacked
-		if !backed {
-			Self::on_system_debit(debit)?;
-		}
+		let maybe_debit_pool = if !backed {
+			Some(Self::increase_debit_pool(debit)?)
+		} else {
+			None
+		};
 		T::Currency::deposit(T::GetStableCurrencyId::get(), who, debit)?;
 
+		// only check the cap once the debit has actually been issued, a failed issuance
+		// must not trip the circuit breaker
+		if let Some(debit_pool) = maybe_debit_pool {
+			Self::check_debit_pool_overflow(debit_pool);
+		}
+		Self::deposit_event(Event::DebitIssued(who.clone(), debit, backed));
 		Ok(())
 	}
 
 	fn burn_debit(who: &T::AccountId, debit: Self::Balance) -> DispatchResult {
-		T::Currency::withdraw(T::GetStableCurrencyId::get(), who, debit)
+		T::Currency::withdraw(T::GetStableCurrencyId::get(), who, debit)?;
+		Self::deposit_event(Event::DebitBurned(who.clone(), debit));
+		Ok(())
 	}
 
 	fn deposit_surplus(from: &T::AccountId, surplus: Self::Balance) -> DispatchResult {
-		T::Currency::transfer(T::GetStableCurrencyId::get(), from, &Self::account_id(), surplus)
+		T::Currency::transfer(T::GetStableCurrencyId::get(), from, &Self::account_id(), surplus)?;
+		Self::on_surplus_changed();
+		Self::deposit_event(Event::SurplusDeposited(from.clone(), surplus));
+		Ok(())
+	}
+
+	fn withdraw_surplus(to: &T::AccountId, surplus: Self::Balance) -> DispatchResult {
+		T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), to, surplus)?;
+		Self::on_surplus_changed();
+		Self::deposit_event(Event::SurplusWithdrawn(to.clone(), surplus));
+		Ok(())
 	}
 
 	fn deposit_collateral(from: &T::AccountId, currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult {
-		T::Currency::transfer(currency_id, from, &Self::account_id(), amount)
+		T::Currency::transfer(currency_id, from, &Self::account_id(), amount)?;
+		Self::deposit_event(Event::CollateralDeposited(from.clone(), currency_id, amount));
+		Ok(())
 	}
 
 	fn withdraw_collateral(to: &T::AccountId, currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult {
-		T::Currency::transfer(currency_id, &Self::account_id(), to, amount)
+		Self::ensure_liquid_collateral(currency_id, amount)?;
+		T::Currency::transfer(currency_id, &Self::account_id(), to, amount)?;
+		Self::deposit_event(Event::CollateralWithdrawn(to.clone(), currency_id, amount));
+		Ok(())
+	}
+}
+
+impl<T: Config> DebtDenominations<T::AccountId> for Pallet<T> {
+	fn is_debt_denomination(denomination: Self::CurrencyId) -> bool {
+		denomination == T::GetStableCurrencyId::get() || Self::debt_denomination_enabled(denomination)
+	}
+
+	fn get_surplus_pool_of(denomination: Self::CurrencyId) -> Self::Balance {
+		T::Currency::free_balance(denomination, &Self::account_id())
+	}
+
+	fn get_debit_pool_of(denomination: Self::CurrencyId) -> Self::Balance {
+		if denomination == T::GetStableCurrencyId::get() {
+			Self::debit_pool()
+		} else {
+			Self::denomination_debit_pool(denomination)
+		}
+	}
+
+	fn on_system_debit_of(denomination: Self::CurrencyId, amount: Self::Balance) -> DispatchResult {
+		if denomination == T::GetStableCurrencyId::get() {
+			return Self::on_system_debit(amount);
+		}
+
+		ensure!(
+			Self::debt_denomination_enabled(denomination),
+			Error::<T>::InvalidDebtDenomination
+		);
+		DenominationDebitPool::<T>::try_mutate(denomination, |debit_pool| -> DispatchResult {
+			*debit_pool = debit_pool.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+			Ok(())
+		})
+	}
+
+	fn on_system_surplus_of(denomination: Self::CurrencyId, amount: Self::Balance) -> DispatchResult {
+		if denomination == T::GetStableCurrencyId::get() {
+			return Self::on_system_surplus(amount);
+		}
+
+		Self::issue_debit_of(denomination, &Self::account_id(), amount, true)
+	}
+
+	#[transactional]
+	fn issue_debit_of(
+		denomination: Self::CurrencyId,
+		who: &T::AccountId,
+		debit: Self::Balance,
+		backed: bool,
+	) -> DispatchResult {
+		if denomination == T::GetStableCurrencyId::get() {
+			return Self::issue_debit(who, debit, backed);
+		}
+
+		ensure!(
+			Self::debt_denomination_enabled(denomination),
+			Error::<T>::InvalidDebtDenomination
+		);
+		// increase system debit of the denomination if the debit is unbacked
+		if !backed {
+			Self::on_system_debit_of(denomination, debit)?;
+		}
+		T::Currency::deposit(denomination, who, debit)?;
+
+		Self::deposit_event(Event::DenominationDebitIssued(denomination, who.clone(), debit, backed));
+		Ok(())
+	}
+
+	fn burn_debit_of(denomination: Self::CurrencyId, who: &T::AccountId, debit: Self::Balance) -> DispatchResult {
+		if denomination == T::GetStableCurrencyId::get() {
+			return Self::burn_debit(who, debit);
+		}
+
+		ensure!(
+			Self::debt_denomination_enabled(denomination),
+			Error::<T>::InvalidDebtDenomination
+		);
+		T::Currency::withdraw(denomination, who, debit)?;
+		Self::deposit_event(Event::DenominationDebitBurned(denomination, who.clone(), debit));
+		Ok(())
+	}
+
+	fn inc_denomination_consumers(denomination: Self::CurrencyId) -> DispatchResult {
+		if denomination == T::GetStableCurrencyId::get() {
+			return Ok(());
+		}
+
+		ensure!(
+			Self::debt_denomination_enabled(denomination),
+			Error::<T>::InvalidDebtDenomination
+		);
+		DenominationConsumers::<T>::mutate(denomination, |consumers| *consumers = consumers.saturating_add(1));
+		Ok(())
+	}
+
+	fn dec_denomination_consumers(denomination: Self::CurrencyId) {
+		if denomination != T::GetStableCurrencyId::get() {
+			DenominationConsumers::<T>::mutate(denomination, |consumers| *consumers = consumers.saturating_sub(1));
+		}
+	}
+
+	fn create_decreasing_price_sale_of(
+		denomination: Self::CurrencyId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+		refund_receiver: T::AccountId,
+	) -> Result<u32, DispatchError> {
+		ensure!(
+			Self::is_debt_denomination(denomination),
+			Error::<T>::InvalidDebtDenomination
+		);
+		ensure!(
+			Self::total_collaterals_not_in_auction(currency_id) >= amount,
+			Error::<T>::CollateralNotEnough,
+		);
+		let params = Self::collateral_sale_params(currency_id).ok_or(Error::<T>::CollateralSaleNotEnabled)?;
+		// keep the collateral for sale liquid
+		Self::ensure_liquid_collateral(
+			currency_id,
+			Self::collateral_in_auction_or_sale(currency_id).saturating_add(amount),
+		)?;
+		let start_price = T::PriceSource::get_relative_price(currency_id, denomination)
+			.ok_or(Error::<T>::InvalidFeedPrice)?
+			.saturating_mul(params.start_price_ratio);
+
+		let sale_id = NextCollateralSaleId::<T>::try_mutate(|id| -> Result<u32, DispatchError> {
+			let current_id = *id;
+			*id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			Ok(current_id)
+		})?;
+		CollateralSales::<T>::insert(
+			sale_id,
+			CollateralSale {
+				currency_id,
+				denomination,
+				amount,
+				target,
+				refund_recipient: refund_receiver,
+				start_block: frame_system::Pallet::<T>::block_number(),
+				start_price,
+				decay_per_block: params.decay_per_block,
+				floor_price: start_price.saturating_mul(params.floor_price_ratio),
+				min_take_amount: params.min_take_amount,
+			},
+		);
+		TotalCollateralInSale::<T>::mutate(currency_id, |balance| *balance = balance.saturating_add(amount));
+		Self::deposit_event(Event::NewCollateralSale(
+			sale_id,
+			currency_id,
+			amount,
+			target,
+			start_price,
+		));
+		Ok(sale_id)
+	}
+}
+
+impl<T: Config> FlashLoan<T::AccountId, CurrencyId, Balance> for Pallet<T> {
+	fn flash_loan_fee(currency_id: CurrencyId, amount: Balance) -> Option<Balance> {
+		Self::flash_loan_fee_rates(currency_id).map(|rate| rate.saturating_mul_int(amount))
+	}
+
+	#[transactional]
+	fn flash_loan(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		f: impl FnOnce() -> DispatchResult,
+	) -> DispatchResult {
+		ensure!(!Self::flash_loan_in_progress(), Error::<T>::FlashLoanInProgress);
+		let fee = Self::flash_loan_fee(currency_id, amount).ok_or(Error::<T>::FlashLoanNotEnabled)?;
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let available = if currency_id == stable_currency_id {
+			Self::surplus_pool()
+		} else {
+			Self::total_collaterals_not_in_auction(currency_id)
+		};
+		ensure!(amount <= available, Error::<T>::FlashLoanLiquidityNotEnough);
+
+		FlashLoanInProgress::<T>::put(true);
+		if currency_id != stable_currency_id {
+			Self::ensure_liquid_collateral(currency_id, amount)?;
+		}
+		T::Currency::transfer(currency_id, &Self::account_id(), who, amount)?;
+		Self::deposit_event(Event::FlashLoanIssued(who.clone(), currency_id, amount));
+
+		f()?;
+
+		let repayment = amount.checked_add(fee).ok_or(ArithmeticError::Overflow)?;
+		T::Currency::transfer(currency_id, who, &Self::account_id(), repayment)
+			.map_err(|_| Error::<T>::FlashLoanNotRepaid)?;
+		FlashLoanInProgress::<T>::kill();
+
+		// the fee of stable currency accrues to the surplus pool, the fee of
+		// collateral is kept as the collateral of CDP treasury
+		if currency_id == stable_currency_id {
+			<Self as CDPTreasury<T::AccountId>>::on_revenue(RevenueSource::FlashLoanFee, fee);
+			Self::on_surplus_changed();
+		}
+		Self::deposit_event(Event::FlashLoanRepaid(who.clone(), currency_id, amount, fee));
+		Ok(())
 	}
 }
 
 impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
-	/// Swap exact amount of collateral stable,
-	/// return actual target stable amount
+	/// Swap exact amount of collateral stable, or other debt denomination
+	/// the swap path ends with, return actual target stable amount
 	fn swap_exact_collateral_to_stable(
 		currency_id: CurrencyId,
 		supply_amount: Balance,
@@ -354,15 +2543,37 @@ impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
 		ensure!(
 			swap_path_length >= 2
 				&& swap_path[0] == currency_id
-				&& swap_path[swap_path_length - 1] == T::GetStableCurrencyId::get(),
+				&& Self::is_debt_denomination(swap_path[swap_path_length - 1]),
 			Error::<T>::InvalidSwapPath
 		);
 
-		T::DEX::swap_with_exact_supply(&Self::account_id(), swap_path, supply_amount, min_target_amount)
+		Self::ensure_liquid_collateral(currency_id, supply_amount)?;
+
+		// swap by the aggregated swap paths instead if they get more stable
+		// currency than the DEX swap path, they are not used for other debt
+		// denominations
+		let dex_target_amount = T::DEX::get_swap_target_amount(swap_path, supply_amount);
+		let aggregated_paths = if swap_path[swap_path_length - 1] == T::GetStableCurrencyId::get() {
+			Self::aggregated_swap_paths(currency_id)
+		} else {
+			None
+		};
+		let aggregated_swap = aggregated_paths.and_then(|paths| {
+			T::Swap::get_swap_target_amount(&paths, supply_amount).map(|target_amount| (paths, target_amount))
+		});
+		let target_amount = match aggregated_swap {
+			Some((paths, target_amount)) if dex_target_amount.map_or(true, |amount| target_amount > amount) => {
+				T::Swap::swap_with_exact_supply(&Self::account_id(), &paths, supply_amount, min_target_amount)?
+			}
+			_ => T::DEX::swap_with_exact_supply(&Self::account_id(), swap_path, supply_amount, min_target_amount)?,
+		};
+		Self::on_surplus_changed();
+		Ok(target_amount)
 	}
 
-	/// swap collateral which not in auction to get exact stable,
-	/// return actual supply collateral amount
+	/// swap collateral which not in auction to get exact stable, or other
+	/// debt denomination the swap path ends with, return actual supply
+	/// collateral amount
 	fn swap_collateral_to_exact_stable(
 		currency_id: CurrencyId,
 		max_supply_amount: Balance,
@@ -387,11 +2598,48 @@ impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
 		ensure!(
 			swap_path_length >= 2
 				&& swap_path[0] == currency_id
-				&& swap_path[swap_path_length - 1] == T::GetStableCurrencyId::get(),
+				&& Self::is_debt_denomination(swap_path[swap_path_length - 1]),
 			Error::<T>::InvalidSwapPath
 		);
 
-		T::DEX::swap_with_exact_target(&Self::account_id(), swap_path, target_amount, max_supply_amount)
+		Self::ensure_liquid_collateral(currency_id, max_supply_amount)?;
+		let supply_amount =
+			T::DEX::swap_with_exact_target(&Self::account_id(), swap_path, target_amount, max_supply_amount)?;
+		Self::on_surplus_changed();
+		Ok(supply_amount)
+	}
+
+	fn swap_collateral_to_stable_weight(path_len: u32) -> Weight {
+		T::WeightInfo::swap_exact_collateral_to_stable(path_len)
+			.max(T::WeightInfo::swap_collateral_to_exact_stable(path_len))
+	}
+
+	fn swap_collateral_to_stable_by_aggregated_paths(
+		currency_id: CurrencyId,
+		max_supply_amount: Balance,
+		target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		ensure!(
+			Self::total_collaterals_not_in_auction(currency_id) >= max_supply_amount,
+			Error::<T>::CollateralNotEnough,
+		);
+		let paths = Self::aggregated_swap_paths(currency_id).ok_or(Error::<T>::InvalidSwapPath)?;
+		let max_target_amount = T::Swap::get_swap_target_amount(&paths, max_supply_amount)
+			.filter(|amount| !amount.is_zero() && *amount >= target_amount)
+			.ok_or(Error::<T>::AggregatedSwapNotEnough)?;
+
+		// the target amount of the swap is concave in the supply amount, so the
+		// proportional supply amount gets at least the target amount
+		let supply_amount = Ratio::checked_from_rational(target_amount, max_target_amount)
+			.map_or(max_supply_amount, |ratio| {
+				ratio.saturating_mul_int(max_supply_amount).saturating_add(One::one())
+			})
+			.min(max_supply_amount);
+
+		Self::ensure_liquid_collateral(currency_id, supply_amount)?;
+		T::Swap::swap_with_exact_supply(&Self::account_id(), &paths, supply_amount, target_amount)?;
+		Self::on_surplus_changed();
+		Ok(supply_amount)
 	}
 
 	fn create_collateral_auctions(
@@ -405,10 +2653,29 @@ impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
 			Self::total_collaterals_not_in_auction(currency_id) >= amount,
 			Error::<T>::CollateralNotEnough,
 		);
+		if let Some(max_slippage) = Self::auction_exempt_collaterals(currency_id) {
+			match Self::swap_auction_exempt_collateral(currency_id, amount, target, &refund_receiver, max_slippage) {
+				Ok(_) => return Ok(0),
+				Err(e) => {
+					// fall back to auctions
+					log::warn!(
+						target: "cdp-treasury",
+						"create_collateral_auctions: Attempt to swap the auction-exempt collateral {:?} {:?} failed: {:?}, \
+						fall back to auctions",
+						amount, currency_id, e
+					);
+				}
+			}
+		}
+		// keep the collateral for auctions liquid
+		Self::ensure_liquid_collateral(
+			currency_id,
+			Self::collateral_in_auction_or_sale(currency_id).saturating_add(amount),
+		)?;
 
 		let mut unhandled_collateral_amount = amount;
 		let mut unhandled_target = target;
-		let expected_collateral_auction_size = Self::expected_collateral_auction_size(currency_id);
+		let expected_collateral_auction_size = Self::collateral_auction_lot_size(currency_id);
 		let max_auctions_count: Balance = T::MaxAuctionsCount::get().into();
 		let lots_count = if !splited
 			|| max_auctions_count.is_zero()
@@ -442,12 +2709,7 @@ impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
 				(average_amount_per_lot, average_target_per_lot)
 			};
 
-			T::AuctionManagerHandler::new_collateral_auction(
-				&refund_receiver,
-				currency_id,
-				lot_collateral_amount,
-				lot_target,
-			)?;
+			Self::create_or_queue_collateral_auction(&refund_receiver, currency_id, lot_collateral_amount, lot_target)?;
 
 			unhandled_collateral_amount = unhandled_collateral_amount.saturating_sub(lot_collateral_amount);
 			unhandled_target = unhandled_target.saturating_sub(lot_target);
@@ -456,11 +2718,63 @@ impl<T: Config> CDPTreasuryExtended<T::AccountId> for Pallet<T> {
 		Ok(created_auctions)
 	}
 
+	fn create_decreasing_price_sale(
+		currency_id: CurrencyId,
+		amount: Balance,
+		target: Balance,
+		refund_receiver: T::AccountId,
+	) -> Result<u32, DispatchError> {
+		Self::create_decreasing_price_sale_of(
+			T::GetStableCurrencyId::get(),
+			currency_id,
+			amount,
+			target,
+			refund_receiver,
+		)
+	}
+
+	fn get_collateral_swap_path(currency_id: CurrencyId) -> Vec<CurrencyId> {
+		Self::collateral_swap_path(currency_id).unwrap_or_else(|| vec![currency_id, T::GetStableCurrencyId::get()])
+	}
+
+	fn is_auction_exempt(currency_id: CurrencyId) -> bool {
+		AuctionExemptCollaterals::<T>::contains_key(currency_id)
+	}
+
 	fn max_auction() -> u32 {
 		T::MaxAuctionsCount::get()
 	}
 }
 
+/// Record the protocol fee of DEX swaps received by CDP treasury as protocol
+/// revenue, valued in stable currency at the oracle price.
+impl<T: Config> Happened<(CurrencyId, Balance)> for Pallet<T> {
+	fn happened(&(currency_id, amount): &(CurrencyId, Balance)) {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let stable_amount = if currency_id == stable_currency_id {
+			Self::on_surplus_changed();
+			amount
+		} else {
+			T::PriceSource::get_relative_price(currency_id, stable_currency_id)
+				.map(|price| price.saturating_mul_int(amount))
+				.unwrap_or_default()
+		};
+		<Self as CDPTreasury<T::AccountId>>::on_revenue(RevenueSource::DexProtocolFee, stable_amount);
+	}
+}
+
+impl<T: Config> CircuitBreakerUnpause for Pallet<T> {
+	fn unpause(id: &CircuitBreakerId) -> bool {
+		match id {
+			CircuitBreakerId::CollateralAuction(currency_id) => {
+				Self::do_set_auction_exempt_collateral(*currency_id, None);
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
 #[cfg(feature = "std")]
 impl GenesisConfig {
 	/// Direct implementation of `GenesisBuild::build_storage`.
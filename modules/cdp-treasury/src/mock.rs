@@ -27,10 +27,11 @@ use frame_support::{
 };
 use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 use orml_traits::parameter_type_with_key;
-use primitives::{TokenSymbol, TradingPair};
+use primitives::{StableAssetPoolId, TokenSymbol, TradingPair};
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup};
 use sp_std::cell::RefCell;
+use support::{AggregatedSwapAdapter, StableAssetPool};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -129,10 +130,12 @@ parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub const GetExchangeFee: (u32, u32) = (0, 100);
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 10;
 	pub EnabledTradingPairs: Vec<TradingPair> = vec![
 		TradingPair::from_currency_ids(AUSD, BTC).unwrap(),
 		TradingPair::from_currency_ids(AUSD, DOT).unwrap(),
 		TradingPair::from_currency_ids(BTC, DOT).unwrap(),
+		TradingPair::from_currency_ids(AUSD, ACA).unwrap(),
 	];
 	pub const DEXPalletId: PalletId = PalletId(*b"aca/dexm");
 }
@@ -142,16 +145,34 @@ impl module_dex::Config for Runtime {
 	type Currency = Currencies;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = ();
 	type DEXIncentives = ();
 	type WeightInfo = ();
 	type ListingOrigin = EnsureSignedBy<One, AccountId>;
+	type StableAsset = ();
+	type ProtocolFeeReceiver = TreasuryAccount;
+	type OnProtocolFee = ();
 }
 
 thread_local! {
 	pub static TOTAL_COLLATERAL_AUCTION: RefCell<u32> = RefCell::new(0);
 	pub static TOTAL_COLLATERAL_IN_AUCTION: RefCell<Balance> = RefCell::new(0);
+	pub static AUCTION_CREATION_FAILS: RefCell<bool> = RefCell::new(false);
+	pub static DOT_PRICE: RefCell<Option<Price>> = RefCell::new(None);
+	pub static IS_SHUTDOWN: RefCell<bool> = RefCell::new(false);
+}
+
+pub fn mock_shutdown() {
+	IS_SHUTDOWN.with(|v| *v.borrow_mut() = true)
+}
+
+pub struct MockEmergencyShutdown;
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn is_shutdown() -> bool {
+		IS_SHUTDOWN.with(|v| *v.borrow_mut())
+	}
 }
 
 pub struct MockAuctionManager;
@@ -166,6 +187,9 @@ impl AuctionManager<AccountId> for MockAuctionManager {
 		amount: Self::Balance,
 		_target: Self::Balance,
 	) -> DispatchResult {
+		if AUCTION_CREATION_FAILS.with(|v| *v.borrow()) {
+			return Err(DispatchError::Other("auction creation failed"));
+		}
 		TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut() += 1);
 		TOTAL_COLLATERAL_IN_AUCTION.with(|v| *v.borrow_mut() += amount);
 		Ok(())
@@ -184,30 +208,176 @@ impl AuctionManager<AccountId> for MockAuctionManager {
 	}
 }
 
+pub const STABLE_ASSET_POOL: AccountId = 20;
+
+/// Stable asset pool 0 swaps 1 BTC for 5 AUSD
+pub struct MockStableAsset;
+impl StableAssetPool<AccountId, CurrencyId, Balance> for MockStableAsset {
+	fn get_pool_assets(pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>> {
+		match pool_id {
+			0 => Some(vec![BTC, AUSD]),
+			_ => None,
+		}
+	}
+
+	fn mint(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amounts: Vec<Balance>,
+		_min_mint_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn redeem_proportion(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amount: Balance,
+		_min_redeem_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn swap(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		i: u32,
+		j: u32,
+		dx: Balance,
+		min_dy: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let assets = Self::get_pool_assets(pool_id).ok_or(DispatchError::Other("invalid pool"))?;
+		let dy = Self::get_swap_output_amount(pool_id, i, j, dx).ok_or(DispatchError::Other("invalid swap"))?;
+		ensure!(dy >= min_dy, DispatchError::Other("swap output too small"));
+		<Currencies as MultiCurrency<AccountId>>::transfer(assets[i as usize], who, &STABLE_ASSET_POOL, dx)?;
+		<Currencies as MultiCurrency<AccountId>>::transfer(assets[j as usize], &STABLE_ASSET_POOL, who, dy)?;
+		Ok(dy)
+	}
+
+	fn get_swap_output_amount(pool_id: StableAssetPoolId, i: u32, j: u32, dx: Balance) -> Option<Balance> {
+		let assets = Self::get_pool_assets(pool_id)?;
+		match (assets.get(i as usize)?, assets.get(j as usize)?) {
+			(&BTC, &AUSD) => dx.checked_mul(5),
+			(&AUSD, &BTC) => Some(dx / 5),
+			_ => None,
+		}
+	}
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		match currency_id {
+			AUSD | ACA => Some(Price::one()),
+			BTC => Some(Price::saturating_from_integer(10)),
+			DOT => DOT_PRICE.with(|v| *v.borrow()),
+			_ => None,
+		}
+	}
+}
+
 ord_parameter_types! {
 	pub const One: AccountId = 1;
 	pub const MaxAuctionsCount: u32 = 5;
+	pub const MaxAuctionsPerBlock: u32 = 15;
+	pub const RevenuePeriod: BlockNumber = 10;
 }
 
 parameter_types! {
 	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"aca/cdpt");
 	pub const TreasuryAccount: AccountId = 10;
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(20, 100);
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![DOT];
+	pub static OffsetPeriod: BlockNumber = 1;
+	pub const MinimumWeightRemainInBlock: Weight = 0;
+	pub static MinimumIdleWorkWeight: Weight = 0;
 }
 
 thread_local! {
 	static IS_SHUTDOWN: RefCell<bool> = RefCell::new(false);
+	pub static CHANGED_SURPLUS_POOL: RefCell<Option<Balance>> = RefCell::new(None);
+	pub static CHANGED_DEBIT_POOL: RefCell<Option<Balance>> = RefCell::new(None);
+	pub static OVERFLOWED_DEBIT_POOL: RefCell<Option<Balance>> = RefCell::new(None);
+}
+
+pub struct MockOnSurplusChanged;
+impl Happened<Balance> for MockOnSurplusChanged {
+	fn happened(surplus_pool: &Balance) {
+		CHANGED_SURPLUS_POOL.with(|v| *v.borrow_mut() = Some(*surplus_pool));
+	}
+}
+
+pub struct MockOnDebitChanged;
+impl Happened<Balance> for MockOnDebitChanged {
+	fn happened(debit_pool: &Balance) {
+		CHANGED_DEBIT_POOL.with(|v| *v.borrow_mut() = Some(*debit_pool));
+	}
+}
+
+pub struct MockOnBadDebtOverflow;
+impl Happened<Balance> for MockOnBadDebtOverflow {
+	fn happened(debit_pool: &Balance) {
+		OVERFLOWED_DEBIT_POOL.with(|v| *v.borrow_mut() = Some(*debit_pool));
+	}
+}
+
+pub const YIELD_STRATEGY: AccountId = 30;
+
+thread_local! {
+	pub static YIELD_STRATEGY_NOOP: RefCell<bool> = RefCell::new(false);
+}
+
+/// The yield strategy keeps the deposits in `YIELD_STRATEGY`, the yield
+/// accrues by depositing to it directly. It behaves as the no-op strategy if
+/// `YIELD_STRATEGY_NOOP` is set.
+pub struct MockYieldStrategy;
+impl TreasuryYieldStrategy<AccountId, CurrencyId, Balance> for MockYieldStrategy {
+	fn deposit(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		if YIELD_STRATEGY_NOOP.with(|v| *v.borrow()) {
+			return <() as TreasuryYieldStrategy<AccountId, CurrencyId, Balance>>::deposit(who, currency_id, amount);
+		}
+		<Currencies as MultiCurrency<AccountId>>::transfer(currency_id, who, &YIELD_STRATEGY, amount)
+	}
+
+	fn withdraw(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		<Currencies as MultiCurrency<AccountId>>::transfer(currency_id, &YIELD_STRATEGY, who, amount)
+	}
+
+	fn balance(_who: &AccountId, currency_id: CurrencyId) -> Balance {
+		<Currencies as MultiCurrency<AccountId>>::free_balance(currency_id, &YIELD_STRATEGY)
+	}
 }
 
 impl Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = MockAuctionManager;
 	type UpdateOrigin = EnsureOneOf<AccountId, EnsureRoot<AccountId>, EnsureSignedBy<One, AccountId>>;
 	type DEX = DEXModule;
+	type Swap = AggregatedSwapAdapter<DEXModule, MockStableAsset>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = TreasuryAccount;
+	type PriceSource = MockPriceSource;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = MockOnSurplusChanged;
+	type OnDebitChanged = MockOnDebitChanged;
+	type OnBadDebtOverflow = MockOnBadDebtOverflow;
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = MockYieldStrategy;
+	type Call = Call;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumIdleWorkWeight = MinimumIdleWorkWeight;
+	type EmergencyShutdown = MockEmergencyShutdown;
 	type WeightInfo = ();
 }
 
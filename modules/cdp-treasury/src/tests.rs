@@ -21,7 +21,7 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, weights::GetDispatchInfo};
 use mock::{Event, *};
 use sp_runtime::traits::BadOrigin;
 
@@ -72,44 +72,163 @@ fn on_system_surplus_work() {
 }
 
 #[test]
-fn offset_surplus_and_debit_on_finalize_work() {
+fn offset_surplus_and_debit_on_idle_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 0);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 1000);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
-		CDPTreasuryModule::on_finalize(1);
+		CDPTreasuryModule::on_idle(1, u64::MAX);
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 1000);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 		assert_ok!(CDPTreasuryModule::on_system_debit(300));
 		assert_eq!(CDPTreasuryModule::debit_pool(), 300);
-		CDPTreasuryModule::on_finalize(2);
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitOffset(300)));
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 700);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 700);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 		assert_ok!(CDPTreasuryModule::on_system_debit(800));
 		assert_eq!(CDPTreasuryModule::debit_pool(), 800);
-		CDPTreasuryModule::on_finalize(3);
+		CDPTreasuryModule::on_idle(3, u64::MAX);
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 0);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 100);
 	});
 }
 
+#[test]
+fn offset_surplus_and_debit_at_offset_period_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		OffsetPeriod::set(3);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+
+		// not offset before the end of the offset period
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 300);
+
+		CDPTreasuryModule::on_idle(3, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitOffset(300)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 700);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+
+		// offset every block if the offset period is zero
+		OffsetPeriod::set(0);
+		assert_ok!(CDPTreasuryModule::on_system_debit(100));
+		CDPTreasuryModule::on_idle(4, u64::MAX);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 600);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+	});
+}
+
+#[test]
+fn excess_surplus_kept_while_debit_outstanding() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		OffsetPeriod::set(3);
+		assert_ok!(CDPTreasuryModule::set_surplus_handling(
+			Origin::signed(1),
+			SurplusHandling::Transfer
+		));
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+
+		// the surplus is not transferred before the debit is offset
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 0);
+
+		CDPTreasuryModule::on_idle(3, u64::MAX);
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::DebitOffset(300)));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusTransferredToTreasury(
+			700,
+		)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 700);
+	});
+}
+
+#[test]
+fn offset_carried_over_without_enough_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		OffsetPeriod::set(3);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+
+		// no weight left at the end of the offset period
+		assert_eq!(CDPTreasuryModule::on_idle(3, 0), 0);
+		assert!(CDPTreasuryModule::offset_pending());
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 300);
+
+		// the pending offset is done in the following block
+		let offset_weight = <() as WeightInfo>::offset_denominations(0);
+		assert_eq!(CDPTreasuryModule::on_idle(4, offset_weight), offset_weight);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitOffset(300)));
+		assert!(!CDPTreasuryModule::offset_pending());
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 700);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+	});
+}
+
+#[test]
+fn offset_with_reserved_weight_in_on_initialize() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		OffsetPeriod::set(3);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+
+		// the offset is done with the reserved weight even if on_idle gets no weight
+		let offset_weight = <() as WeightInfo>::offset_denominations(0);
+		MinimumIdleWorkWeight::set(offset_weight);
+		CDPTreasuryModule::on_initialize(3);
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::DebitOffset(300)));
+		assert!(!CDPTreasuryModule::offset_pending());
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 700);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+
+		// the due offset exceeding the reserved weight is carried over
+		assert_ok!(CDPTreasuryModule::on_system_debit(200));
+		MinimumIdleWorkWeight::set(offset_weight - 1);
+		CDPTreasuryModule::on_initialize(6);
+		assert!(CDPTreasuryModule::offset_pending());
+		assert_eq!(CDPTreasuryModule::debit_pool(), 200);
+		assert_eq!(CDPTreasuryModule::on_idle(6, 0), 0);
+		assert!(CDPTreasuryModule::offset_pending());
+
+		assert_eq!(CDPTreasuryModule::on_idle(7, offset_weight), offset_weight);
+		assert!(!CDPTreasuryModule::offset_pending());
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 500);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+	});
+}
+
 #[test]
 fn issue_debit_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 1000);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 
 		assert_ok!(CDPTreasuryModule::issue_debit(&ALICE, 1000, true));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitIssued(ALICE, 1000, true)));
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 2000);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 
 		assert_ok!(CDPTreasuryModule::issue_debit(&ALICE, 1000, false));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitIssued(ALICE, 1000, false)));
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 3000);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 1000);
 	});
@@ -118,9 +237,11 @@ fn issue_debit_work() {
 #[test]
 fn burn_debit_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 1000);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 		assert_ok!(CDPTreasuryModule::burn_debit(&ALICE, 300));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitBurned(ALICE, 300)));
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 700);
 		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
 	});
@@ -129,24 +250,43 @@ fn burn_debit_work() {
 #[test]
 fn deposit_surplus_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 1000);
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 0);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
 		assert_ok!(CDPTreasuryModule::deposit_surplus(&ALICE, 300));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusDeposited(ALICE, 300)));
 		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 700);
 		assert_eq!(Currencies::free_balance(AUSD, &CDPTreasuryModule::account_id()), 300);
 		assert_eq!(CDPTreasuryModule::surplus_pool(), 300);
 	});
 }
 
+#[test]
+fn withdraw_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_surplus(&ALICE, 300));
+		assert!(CDPTreasuryModule::withdraw_surplus(&BOB, 301).is_err());
+		assert_ok!(CDPTreasuryModule::withdraw_surplus(&BOB, 200));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusWithdrawn(BOB, 200)));
+		assert_eq!(Currencies::free_balance(AUSD, &BOB), 1200);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 100);
+	});
+}
+
 #[test]
 fn deposit_collateral_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
 		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 0);
 		assert_eq!(Currencies::free_balance(BTC, &ALICE), 1000);
 		assert!(!CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 10000).is_ok());
 		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 500));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralDeposited(
+			ALICE, BTC, 500,
+		)));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 500);
 		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 500);
 		assert_eq!(Currencies::free_balance(BTC, &ALICE), 500);
@@ -156,12 +296,16 @@ fn deposit_collateral_work() {
 #[test]
 fn withdraw_collateral_work() {
 	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
 		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 500));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 500);
 		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 500);
 		assert_eq!(Currencies::free_balance(BTC, &BOB), 1000);
 		assert!(!CDPTreasuryModule::withdraw_collateral(&BOB, BTC, 501).is_ok());
 		assert_ok!(CDPTreasuryModule::withdraw_collateral(&BOB, BTC, 400));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralWithdrawn(
+			BOB, BTC, 400,
+		)));
 		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
 		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 100);
 		assert_eq!(Currencies::free_balance(BTC, &BOB), 1400);
@@ -391,6 +535,131 @@ fn create_collateral_auctions_work() {
 	});
 }
 
+#[test]
+fn auction_collateral_refund_weight_of_lots_not_created() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Currencies::deposit(BTC, &CDPTreasuryModule::account_id(), 10000));
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(1),
+			BTC,
+			300
+		));
+		assert_noop!(
+			CDPTreasuryModule::auction_collateral(Origin::signed(5), BTC, 1000, 1000, true),
+			BadOrigin
+		);
+
+		let call = crate::Call::<Runtime>::auction_collateral {
+			currency_id: BTC,
+			amount: 1000,
+			target: 1000,
+			splited: true,
+		};
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			<() as WeightInfo>::auction_collateral(MaxAuctionsCount::get())
+		);
+
+		// the weight of the lots created is charged
+		assert_eq!(
+			CDPTreasuryModule::auction_collateral(Origin::signed(1), BTC, 1000, 1000, true)
+				.unwrap()
+				.actual_weight,
+			Some(<() as WeightInfo>::auction_collateral(4))
+		);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 4);
+
+		assert_eq!(
+			CDPTreasuryModule::auction_collateral(Origin::signed(1), BTC, 1000, 1000, false)
+				.unwrap()
+				.actual_weight,
+			Some(<() as WeightInfo>::auction_collateral(1))
+		);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 5);
+	});
+}
+
+#[test]
+fn create_collateral_auctions_queued_over_block_cap() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Currencies::deposit(BTC, &CDPTreasuryModule::account_id(), 10000));
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(1),
+			BTC,
+			100
+		));
+
+		// 15 auctions reach the cap of the block
+		for _ in 0..3 {
+			assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+				BTC, 500, 500, ALICE, true
+			));
+		}
+		assert_eq!(CDPTreasuryModule::created_auctions_in_block(), 15);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 15);
+
+		// the lots exceeding the cap are queued
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			BTC, 1000, 1000, ALICE, true
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralAuctionQueued(
+			BTC, 200, 200,
+		)));
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 15);
+		assert_eq!(CDPTreasuryModule::pending_collateral_auctions_range(), (0, 5));
+		assert_eq!(
+			CDPTreasuryModule::pending_collateral_auctions(0),
+			Some(PendingCollateralAuction {
+				refund_recipient: ALICE,
+				currency_id: BTC,
+				amount: 200,
+				target: 200,
+			})
+		);
+		assert_eq!(CDPTreasuryModule::total_collateral_pending_auction(BTC), 1000);
+		assert_eq!(CDPTreasuryModule::total_collaterals_not_in_auction(BTC), 7500);
+		assert_noop!(
+			CDPTreasuryModule::create_collateral_auctions(BTC, 7501, 1000, ALICE, true),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+
+		// the lot failed to be created is re-queued
+		AUCTION_CREATION_FAILS.with(|v| *v.borrow_mut() = true);
+		assert_eq!(
+			CDPTreasuryModule::on_initialize(2),
+			<() as WeightInfo>::create_pending_collateral_auctions(1)
+		);
+		AUCTION_CREATION_FAILS.with(|v| *v.borrow_mut() = false);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 15);
+		assert_eq!(CDPTreasuryModule::pending_collateral_auctions_range(), (1, 6));
+		assert_eq!(CDPTreasuryModule::pending_collateral_auctions(0), None);
+		assert_eq!(
+			CDPTreasuryModule::pending_collateral_auctions(5),
+			Some(PendingCollateralAuction {
+				refund_recipient: ALICE,
+				currency_id: BTC,
+				amount: 200,
+				target: 200,
+			})
+		);
+		assert_eq!(CDPTreasuryModule::total_collateral_pending_auction(BTC), 1000);
+		assert_eq!(CDPTreasuryModule::created_auctions_in_block(), 0);
+
+		// the queued lots are created in the next block
+		assert_eq!(
+			CDPTreasuryModule::on_initialize(3),
+			<() as WeightInfo>::create_pending_collateral_auctions(5)
+		);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 20);
+		assert_eq!(TOTAL_COLLATERAL_IN_AUCTION.with(|v| *v.borrow_mut()), 2500);
+		assert_eq!(CDPTreasuryModule::pending_collateral_auctions_range(), (6, 6));
+		assert_eq!(CDPTreasuryModule::pending_collateral_auctions(5), None);
+		assert_eq!(CDPTreasuryModule::total_collateral_pending_auction(BTC), 0);
+		assert_eq!(CDPTreasuryModule::created_auctions_in_block(), 5);
+	});
+}
+
 #[test]
 fn set_expected_collateral_auction_size_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -411,6 +680,54 @@ fn set_expected_collateral_auction_size_work() {
 	});
 }
 
+#[test]
+fn expected_collateral_auction_value_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Currencies::deposit(BTC, &CDPTreasuryModule::account_id(), 10000));
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(1),
+			BTC,
+			200
+		));
+		assert_eq!(CDPTreasuryModule::collateral_auction_lot_size(BTC), 200);
+
+		assert_noop!(
+			CDPTreasuryModule::set_expected_collateral_auction_value(Origin::signed(5), BTC, Some(1000)),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_value(
+			Origin::signed(1),
+			BTC,
+			Some(1000)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(
+			crate::Event::ExpectedCollateralAuctionValueUpdated(BTC, Some(1000)),
+		));
+		assert_eq!(CDPTreasuryModule::expected_collateral_auction_value(BTC), Some(1000));
+
+		// the lot size is computed from the price of BTC
+		assert_eq!(CDPTreasuryModule::collateral_auction_lot_size(BTC), 100);
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			BTC, 500, 1000, ALICE, true
+		));
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 5);
+
+		// fallback to the expected collateral auction size
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_value(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		assert_eq!(CDPTreasuryModule::expected_collateral_auction_value(BTC), None);
+		assert_eq!(CDPTreasuryModule::collateral_auction_lot_size(BTC), 200);
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			BTC, 400, 1000, ALICE, true
+		));
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 7);
+	});
+}
+
 #[test]
 fn extract_surplus_to_treasury_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -429,3 +746,1352 @@ fn extract_surplus_to_treasury_work() {
 		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 200);
 	});
 }
+
+#[test]
+fn burn_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::set_surplus_buffer_size(Origin::signed(1), 300));
+		assert_eq!(Currencies::total_issuance(AUSD), 3000);
+
+		assert_noop!(CDPTreasuryModule::burn_surplus(Origin::signed(5), 200), BadOrigin);
+		assert_noop!(
+			CDPTreasuryModule::burn_surplus(Origin::signed(1), 701),
+			Error::<Runtime>::BelowSurplusBuffer
+		);
+
+		assert_ok!(CDPTreasuryModule::burn_surplus(Origin::signed(1), 200));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusBurned(200)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 800);
+		assert_eq!(Currencies::total_issuance(AUSD), 2800);
+	});
+}
+
+#[test]
+fn surplus_buffer_size_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+
+		assert_noop!(
+			CDPTreasuryModule::set_surplus_buffer_size(Origin::signed(5), 300),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_surplus_buffer_size(Origin::signed(1), 300));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusBufferSizeUpdated(300)));
+		assert_eq!(CDPTreasuryModule::surplus_buffer_size(), 300);
+
+		assert_noop!(
+			CDPTreasuryModule::extract_surplus_to_treasury(Origin::signed(1), 800),
+			Error::<Runtime>::BelowSurplusBuffer
+		);
+		assert_ok!(CDPTreasuryModule::extract_surplus_to_treasury(Origin::signed(1), 700));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 300);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 700);
+
+		// the buffer absorbs bad debts
+		assert_ok!(CDPTreasuryModule::on_system_debit(200));
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 100);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+	});
+}
+
+#[test]
+fn handle_excess_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::set_surplus_buffer_size(Origin::signed(1), 300));
+		assert_eq!(CDPTreasuryModule::surplus_handling(), SurplusHandling::Auction);
+		assert_eq!(
+			CDPTreasuryModule::on_initialize(1),
+			<() as WeightInfo>::create_pending_collateral_auctions(0)
+		);
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
+
+		assert_noop!(
+			CDPTreasuryModule::set_surplus_handling(Origin::signed(5), SurplusHandling::Transfer),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_surplus_handling(
+			Origin::signed(1),
+			SurplusHandling::Transfer
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusHandlingUpdated(
+			SurplusHandling::Transfer,
+		)));
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusTransferredToTreasury(
+			700,
+		)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 300);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 700);
+
+		assert_ok!(Currencies::deposit(ACA, &ALICE, 1000));
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			AUSD,
+			ACA,
+			1000,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPTreasuryModule::set_surplus_buffer_size(Origin::signed(1), 200));
+		assert_ok!(CDPTreasuryModule::set_surplus_handling(
+			Origin::signed(1),
+			SurplusHandling::BuyBackBurn
+		));
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusBoughtBackAndBurned(
+			100, 90,
+		)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 200);
+		assert_eq!(DEXModule::get_liquidity_pool(AUSD, ACA), (1100, 910));
+		assert_eq!(Currencies::free_balance(ACA, &CDPTreasuryModule::account_id()), 0);
+		assert_eq!(Currencies::total_issuance(ACA), 910);
+	});
+}
+
+#[test]
+fn dilute_to_cover_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_debit(500));
+		assert_eq!(CDPTreasuryModule::debit_handling(), DebitHandling::Keep);
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 500);
+
+		assert_noop!(
+			CDPTreasuryModule::set_debit_handling(Origin::signed(5), DebitHandling::Dilution),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_debit_handling(
+			Origin::signed(1),
+			DebitHandling::Dilution
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitHandlingUpdated(
+			DebitHandling::Dilution,
+		)));
+
+		assert_noop!(
+			CDPTreasuryModule::set_dilution_params(Origin::signed(5), 50, 10),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_dilution_params(Origin::signed(1), 50, 0),
+			Error::<Runtime>::InvalidDilutionParams
+		);
+		assert_ok!(CDPTreasuryModule::set_dilution_params(Origin::signed(1), 50, 10));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DilutionParamsUpdated(50, 10)));
+		assert_eq!(CDPTreasuryModule::dilution_cap_per_period(), 50);
+		assert_eq!(CDPTreasuryModule::dilution_period(), 10);
+
+		// no liquidity to sell the native currency
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 500);
+		assert_eq!(Currencies::total_issuance(ACA), 0);
+
+		assert_ok!(Currencies::deposit(ACA, &ALICE, 1000));
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			AUSD,
+			ACA,
+			1000,
+			1000,
+			0,
+			false
+		));
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::NativeDilutedToCoverDebit(
+			50, 47,
+		)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 453);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+		assert_eq!(CDPTreasuryModule::native_diluted_in_period(), (0, 50));
+		assert_eq!(DEXModule::get_liquidity_pool(AUSD, ACA), (953, 1050));
+		assert_eq!(Currencies::total_issuance(ACA), 1050);
+
+		// the cap of the period is used up
+		CDPTreasuryModule::on_idle(9, u64::MAX);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 453);
+
+		// the cap is renewed in the next period
+		CDPTreasuryModule::on_idle(10, u64::MAX);
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::NativeDilutedToCoverDebit(
+			50, 43,
+		)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 410);
+		assert_eq!(CDPTreasuryModule::native_diluted_in_period(), (10, 50));
+		assert_eq!(DEXModule::get_liquidity_pool(AUSD, ACA), (910, 1100));
+		assert_eq!(Currencies::total_issuance(ACA), 1100);
+
+		// changing the dilution params keeps the native currency issued in the period
+		assert_ok!(CDPTreasuryModule::set_dilution_params(Origin::signed(1), 50, 5));
+		CDPTreasuryModule::on_idle(12, u64::MAX);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 410);
+		assert_eq!(CDPTreasuryModule::native_diluted_in_period(), (10, 50));
+
+		// no native currency is issued after emergency shutdown
+		mock_shutdown();
+		CDPTreasuryModule::on_idle(15, u64::MAX);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 410);
+		assert_eq!(CDPTreasuryModule::native_diluted_in_period(), (10, 50));
+		assert_eq!(Currencies::total_issuance(ACA), 1100);
+	});
+}
+
+#[test]
+fn auto_extract_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_eq!(CDPTreasuryModule::surplus_auto_extract_threshold(), None);
+		CDPTreasuryModule::on_initialize(1);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1000);
+
+		assert_noop!(
+			CDPTreasuryModule::set_surplus_auto_extract_threshold(Origin::signed(5), Some(400)),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_surplus_auto_extract_threshold(
+			Origin::signed(1),
+			Some(400)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(
+			crate::Event::SurplusAutoExtractThresholdUpdated(Some(400)),
+		));
+		assert_eq!(CDPTreasuryModule::surplus_auto_extract_threshold(), Some(400));
+
+		// extracted in `on_initialize` without the weight left for `on_idle`
+		assert_eq!(
+			CDPTreasuryModule::on_initialize(1),
+			<() as WeightInfo>::create_pending_collateral_auctions(0) + <() as WeightInfo>::auto_extract_surplus()
+		);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusAutoExtracted(600)));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 400);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 600);
+
+		// nothing to extract below the threshold
+		CDPTreasuryModule::on_initialize(2);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 400);
+
+		assert_ok!(CDPTreasuryModule::set_surplus_auto_extract_threshold(
+			Origin::signed(1),
+			None
+		));
+		assert_ok!(CDPTreasuryModule::on_system_surplus(100));
+		CDPTreasuryModule::on_initialize(3);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 500);
+		assert_eq!(Currencies::free_balance(AUSD, &TreasuryAccount::get()), 600);
+	});
+}
+
+#[test]
+fn decreasing_price_sale_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let params = DecreasingPriceSaleParams {
+			start_price_ratio: Ratio::saturating_from_rational(120, 100),
+			decay_per_block: Ratio::saturating_from_rational(1, 100),
+			floor_price_ratio: Ratio::saturating_from_rational(50, 100),
+			min_take_amount: 50,
+		};
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 100));
+		assert_noop!(
+			CDPTreasuryModule::create_decreasing_price_sale(BTC, 100, 500, BOB),
+			Error::<Runtime>::CollateralSaleNotEnabled
+		);
+
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_sale_params(Origin::signed(5), BTC, Some(params)),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_sale_params(
+				Origin::signed(1),
+				BTC,
+				Some(DecreasingPriceSaleParams {
+					floor_price_ratio: Ratio::saturating_from_rational(150, 100),
+					..params
+				})
+			),
+			Error::<Runtime>::InvalidCollateralSaleParams
+		);
+		assert_ok!(CDPTreasuryModule::set_collateral_sale_params(
+			Origin::signed(1),
+			BTC,
+			Some(params)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSaleParamsUpdated(
+			BTC,
+			Some(params),
+		)));
+		assert_eq!(CDPTreasuryModule::collateral_sale_params(BTC), Some(params));
+
+		assert_noop!(
+			CDPTreasuryModule::create_decreasing_price_sale(BTC, 101, 500, BOB),
+			Error::<Runtime>::CollateralNotEnough
+		);
+		assert_eq!(
+			CDPTreasuryModule::create_decreasing_price_sale(BTC, 100, 500, BOB),
+			Ok(0)
+		);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::NewCollateralSale(
+			0,
+			BTC,
+			100,
+			500,
+			Price::saturating_from_integer(12),
+		)));
+		assert_eq!(CDPTreasuryModule::total_collateral_in_sale(BTC), 100);
+		assert_eq!(CDPTreasuryModule::total_collaterals_not_in_auction(BTC), 0);
+
+		// the price decays by 1% of the start price per block
+		System::set_block_number(11);
+		let sale = CDPTreasuryModule::collateral_sales(0).unwrap();
+		assert_eq!(
+			CDPTreasuryModule::collateral_sale_price(&sale),
+			Price::saturating_from_rational(108, 10)
+		);
+
+		assert_noop!(
+			CDPTreasuryModule::take_collateral_sale(Origin::signed(ALICE), 1, 20, Price::saturating_from_integer(11)),
+			Error::<Runtime>::CollateralSaleNotExists
+		);
+		assert_noop!(
+			CDPTreasuryModule::take_collateral_sale(Origin::signed(ALICE), 0, 20, Price::saturating_from_integer(10)),
+			Error::<Runtime>::SalePriceAboveLimit
+		);
+		assert_noop!(
+			CDPTreasuryModule::take_collateral_sale(Origin::signed(ALICE), 0, 0, Price::saturating_from_integer(11)),
+			Error::<Runtime>::CollateralSaleTakeTooSmall
+		);
+		assert_noop!(
+			CDPTreasuryModule::take_collateral_sale(Origin::signed(ALICE), 0, 4, Price::saturating_from_integer(11)),
+			Error::<Runtime>::CollateralSaleTakeTooSmall
+		);
+		assert_ok!(CDPTreasuryModule::take_collateral_sale(
+			Origin::signed(ALICE),
+			0,
+			20,
+			Price::saturating_from_integer(11)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSaleTaken(
+			0, ALICE, 20, 216,
+		)));
+		let sale = CDPTreasuryModule::collateral_sales(0).unwrap();
+		assert_eq!((sale.amount, sale.target), (80, 284));
+		assert_eq!(CDPTreasuryModule::total_collateral_in_sale(BTC), 80);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 216);
+
+		// only sell the collateral enough to raise the remaining target, and refund the rest
+		assert_ok!(CDPTreasuryModule::take_collateral_sale(
+			Origin::signed(ALICE),
+			0,
+			100,
+			Price::saturating_from_integer(11)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSaleFinished(0, 54)));
+		assert_eq!(CDPTreasuryModule::collateral_sales(0), None);
+		assert_eq!(CDPTreasuryModule::total_collateral_in_sale(BTC), 0);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 500);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 500);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 946);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 1054);
+
+		// the price does not decrease below the floor price
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 100));
+		assert_eq!(
+			CDPTreasuryModule::create_decreasing_price_sale(BTC, 100, 500, BOB),
+			Ok(1)
+		);
+		System::set_block_number(200);
+		let sale = CDPTreasuryModule::collateral_sales(1).unwrap();
+		assert_eq!(
+			CDPTreasuryModule::collateral_sale_price(&sale),
+			Price::saturating_from_integer(6)
+		);
+
+		// the cancelled sale is liquidated by collateral auctions
+		assert_noop!(
+			CDPTreasuryModule::cancel_collateral_sale(Origin::signed(5), 1),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::cancel_collateral_sale(Origin::signed(1), 2),
+			Error::<Runtime>::CollateralSaleNotExists
+		);
+		assert_ok!(CDPTreasuryModule::cancel_collateral_sale(Origin::signed(1), 1));
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::CollateralSaleCancelled(
+			1, 100, 500,
+		)));
+		assert_eq!(CDPTreasuryModule::collateral_sales(1), None);
+		assert_eq!(CDPTreasuryModule::total_collateral_in_sale(BTC), 0);
+		assert_eq!(TOTAL_COLLATERAL_IN_AUCTION.with(|v| *v.borrow_mut()), 100);
+	});
+}
+
+#[test]
+fn decreasing_price_sale_of_denomination_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		DOT_PRICE.with(|v| *v.borrow_mut() = Some(Price::saturating_from_integer(2)));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 100));
+		assert_ok!(CDPTreasuryModule::set_collateral_sale_params(
+			Origin::signed(1),
+			BTC,
+			Some(DecreasingPriceSaleParams {
+				start_price_ratio: Ratio::one(),
+				decay_per_block: Ratio::saturating_from_rational(1, 100),
+				floor_price_ratio: Ratio::saturating_from_rational(50, 100),
+				min_take_amount: 0,
+			})
+		));
+		assert_noop!(
+			CDPTreasuryModule::create_decreasing_price_sale_of(DOT, BTC, 100, 200, BOB),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			DOT,
+			true
+		));
+		assert_ok!(CDPTreasuryModule::on_system_debit_of(DOT, 200));
+		assert_eq!(
+			CDPTreasuryModule::create_decreasing_price_sale_of(DOT, BTC, 100, 200, BOB),
+			Ok(0)
+		);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::NewCollateralSale(
+			0,
+			BTC,
+			100,
+			200,
+			Price::saturating_from_integer(5),
+		)));
+		let sale = CDPTreasuryModule::collateral_sales(0).unwrap();
+		assert_eq!(sale.denomination, DOT);
+
+		// the sale paid in the denomination can't be cancelled to collateral auctions
+		assert_noop!(
+			CDPTreasuryModule::cancel_collateral_sale(Origin::signed(1), 0),
+			Error::<Runtime>::CollateralSaleNotCancellable
+		);
+
+		// the proceeds go to the surplus pool of the denomination
+		assert_ok!(CDPTreasuryModule::take_collateral_sale(
+			Origin::signed(ALICE),
+			0,
+			100,
+			Price::saturating_from_integer(5)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSaleFinished(0, 60)));
+		assert_eq!(CDPTreasuryModule::get_surplus_pool_of(DOT), 200);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 800);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 940);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 1060);
+	});
+}
+
+#[test]
+fn revenue_report_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::revenue_report(2), vec![(0, vec![])]);
+
+		System::set_block_number(5);
+		CDPTreasuryModule::on_revenue(RevenueSource::StabilityFee, 100);
+		CDPTreasuryModule::on_revenue(RevenueSource::LiquidationPenalty, 0);
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::StabilityFee), 100);
+
+		System::set_block_number(15);
+		CDPTreasuryModule::on_revenue(RevenueSource::LiquidationPenalty, 50);
+		CDPTreasuryModule::on_revenue(RevenueSource::StabilityFee, 20);
+		CDPTreasuryModule::on_revenue(RevenueSource::StabilityFee, 30);
+
+		assert_eq!(CDPTreasuryModule::current_revenue_period(), 1);
+		assert_eq!(CDPTreasuryModule::revenue_report(0), vec![]);
+		assert_eq!(
+			CDPTreasuryModule::revenue_report(1),
+			vec![(
+				1,
+				vec![
+					(RevenueSource::StabilityFee, 50),
+					(RevenueSource::LiquidationPenalty, 50)
+				]
+			)]
+		);
+		assert_eq!(
+			CDPTreasuryModule::revenue_report(3),
+			vec![
+				(0, vec![(RevenueSource::StabilityFee, 100)]),
+				(
+					1,
+					vec![
+						(RevenueSource::StabilityFee, 50),
+						(RevenueSource::LiquidationPenalty, 50)
+					]
+				),
+			]
+		);
+	});
+}
+
+#[test]
+fn record_dex_protocol_fee_revenue_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		CDPTreasuryModule::happened(&(AUSD, 100));
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::DexProtocolFee), 100);
+
+		// valued in stable currency at the oracle price
+		CDPTreasuryModule::happened(&(BTC, 10));
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::DexProtocolFee), 200);
+
+		// the fee without price is not accounted
+		CDPTreasuryModule::happened(&(DOT, 10));
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::DexProtocolFee), 200);
+	});
+}
+
+#[test]
+fn surplus_report_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::surplus_report(), vec![]);
+
+		CDPTreasuryModule::on_collateral_surplus(BTC, 100);
+		CDPTreasuryModule::on_collateral_surplus(DOT, 0);
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 100);
+		assert_eq!(CDPTreasuryModule::surplus_report(), vec![(BTC, 100)]);
+
+		CDPTreasuryModule::on_collateral_surplus(BTC, 20);
+		CDPTreasuryModule::on_collateral_surplus(DOT, 50);
+		assert_eq!(CDPTreasuryModule::surplus_report(), vec![(DOT, 50), (BTC, 120)]);
+	});
+}
+
+#[test]
+fn realize_collateral_surplus_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		CDPTreasuryModule::on_unrealized_collateral_surplus(BTC, 10, 60);
+		CDPTreasuryModule::on_unrealized_collateral_surplus(BTC, 20, 100);
+		CDPTreasuryModule::on_unrealized_collateral_surplus(DOT, 10, 0);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (30, 160));
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(DOT), (0, 0));
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 0);
+
+		// the penalty share of the target is realized by the proceeds above the rest
+		CDPTreasuryModule::on_collateral_proceeds(BTC, 60, 60);
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 11);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (19, 100));
+
+		CDPTreasuryModule::on_collateral_proceeds(BTC, 50, 45);
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 15);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (10, 50));
+
+		// the penalty share of the target not raised is never realized
+		CDPTreasuryModule::on_collateral_proceeds(BTC, 50, 0);
+		assert_eq!(CDPTreasuryModule::surplus_by_source(BTC), 15);
+		assert_eq!(CDPTreasuryModule::unrealized_collateral_surplus(BTC), (0, 0));
+	});
+}
+
+#[test]
+fn treasury_health_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::reserve_ratio(), None);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(300));
+		assert_eq!(CDPTreasuryModule::reserve_ratio(), None);
+		assert_ok!(CDPTreasuryModule::on_system_debit(200));
+		assert_eq!(
+			CDPTreasuryModule::reserve_ratio(),
+			Some(Ratio::saturating_from_rational(3, 2))
+		);
+
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 500));
+		assert_eq!(
+			CDPTreasuryModule::collateral_report(vec![BTC, DOT]),
+			vec![(BTC, 500, 0), (DOT, 0, 0)]
+		);
+	});
+}
+
+#[test]
+fn surplus_and_debit_changed_hooks_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let changed_surplus_pool = || CHANGED_SURPLUS_POOL.with(|v| *v.borrow());
+		let changed_debit_pool = || CHANGED_DEBIT_POOL.with(|v| *v.borrow());
+		assert_eq!(changed_surplus_pool(), None);
+		assert_eq!(changed_debit_pool(), None);
+
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_eq!(changed_surplus_pool(), Some(1000));
+		assert_ok!(CDPTreasuryModule::deposit_surplus(&ALICE, 300));
+		assert_eq!(changed_surplus_pool(), Some(1300));
+
+		assert_ok!(CDPTreasuryModule::on_system_debit(500));
+		assert_eq!(changed_debit_pool(), Some(500));
+
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(changed_surplus_pool(), Some(800));
+		assert_eq!(changed_debit_pool(), Some(0));
+	});
+}
+
+#[test]
+fn debit_pool_circuit_breaker_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let overflowed_debit_pool = || OVERFLOWED_DEBIT_POOL.with(|v| *v.borrow());
+		assert_ok!(CDPTreasuryModule::on_system_debit(500));
+		assert_eq!(overflowed_debit_pool(), None);
+
+		assert_noop!(
+			CDPTreasuryModule::set_max_debit_pool(Origin::signed(5), Some(1000)),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_max_debit_pool(Origin::signed(1), Some(1000)));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::MaxDebitPoolUpdated(Some(1000))));
+		assert_eq!(CDPTreasuryModule::max_debit_pool(), Some(1000));
+
+		assert_ok!(CDPTreasuryModule::on_system_debit(500));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 1000);
+		assert_eq!(overflowed_debit_pool(), None);
+
+		// the debit is still recorded when it exceeds the cap
+		assert_ok!(CDPTreasuryModule::on_system_debit(1));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitPoolOverflow(1001, 1000)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 1001);
+		assert_eq!(overflowed_debit_pool(), Some(1001));
+
+		// a failed issuance of unbacked debit does not trip the circuit breaker
+		OVERFLOWED_DEBIT_POOL.with(|v| *v.borrow_mut() = None);
+		assert_noop!(
+			CDPTreasuryModule::issue_debit(&ALICE, Balance::max_value() - 1500, false),
+			ArithmeticError::Overflow
+		);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 1001);
+		assert_eq!(overflowed_debit_pool(), None);
+
+		assert_ok!(CDPTreasuryModule::issue_debit(&ALICE, 1, false));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebitIssued(ALICE, 1, false)));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 1002);
+		assert_eq!(overflowed_debit_pool(), Some(1002));
+
+		assert_ok!(CDPTreasuryModule::set_max_debit_pool(Origin::signed(1), None));
+		assert_ok!(CDPTreasuryModule::on_system_debit(1));
+		assert_eq!(overflowed_debit_pool(), Some(1002));
+	});
+}
+
+#[test]
+fn flash_loan_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		let transfer_call = |dest: AccountId, currency_id: CurrencyId, amount: Balance| {
+			Box::new(Call::Currencies(orml_currencies::Call::transfer {
+				dest,
+				currency_id,
+				amount,
+			}))
+		};
+
+		assert_noop!(
+			CDPTreasuryModule::flash_loan(Origin::signed(BOB), AUSD, 500, transfer_call(ALICE, AUSD, 300)),
+			Error::<Runtime>::FlashLoanNotEnabled
+		);
+
+		assert_noop!(
+			CDPTreasuryModule::set_flash_loan_fee_rate(
+				Origin::signed(5),
+				AUSD,
+				Some(Rate::saturating_from_rational(1, 100))
+			),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_flash_loan_fee_rate(
+			Origin::signed(1),
+			AUSD,
+			Some(Rate::saturating_from_rational(1, 100))
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::FlashLoanFeeRateUpdated(
+			AUSD,
+			Some(Rate::saturating_from_rational(1, 100)),
+		)));
+		assert_eq!(CDPTreasuryModule::flash_loan_fee(AUSD, 500), Some(5));
+
+		assert_noop!(
+			CDPTreasuryModule::flash_loan(Origin::signed(BOB), AUSD, 1001, transfer_call(ALICE, AUSD, 300)),
+			Error::<Runtime>::FlashLoanLiquidityNotEnough
+		);
+
+		assert_ok!(CDPTreasuryModule::flash_loan(
+			Origin::signed(BOB),
+			AUSD,
+			500,
+			transfer_call(ALICE, AUSD, 300)
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::FlashLoanRepaid(
+			BOB, AUSD, 500, 5,
+		)));
+		assert_eq!(Currencies::free_balance(AUSD, &BOB), 695);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 1300);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1005);
+		assert_eq!(CDPTreasuryModule::revenues(0, RevenueSource::FlashLoanFee), 5);
+		assert!(!CDPTreasuryModule::flash_loan_in_progress());
+
+		// the loan and the fee must be repaid
+		assert_noop!(
+			CDPTreasuryModule::flash_loan(Origin::signed(BOB), AUSD, 500, transfer_call(ALICE, AUSD, 1195)),
+			Error::<Runtime>::FlashLoanNotRepaid
+		);
+
+		// re-entrancy is not allowed
+		let reentrant_call = Box::new(Call::CDPTreasuryModule(crate::Call::flash_loan {
+			currency_id: AUSD,
+			amount: 100,
+			call: transfer_call(ALICE, AUSD, 100),
+		}));
+		assert_noop!(
+			CDPTreasuryModule::flash_loan(Origin::signed(BOB), AUSD, 500, reentrant_call),
+			Error::<Runtime>::FlashLoanInProgress
+		);
+
+		// the flash loan is always dispatched as a normal call
+		let operational_call = Call::CDPTreasuryModule(crate::Call::flash_loan {
+			currency_id: AUSD,
+			amount: 100,
+			call: Box::new(Call::CDPTreasuryModule(crate::Call::set_flash_loan_fee_rate {
+				currency_id: AUSD,
+				fee_rate: None,
+			})),
+		});
+		assert_eq!(operational_call.get_dispatch_info().class, DispatchClass::Normal);
+
+		// the fee of collateral is kept as the collateral of CDP treasury
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, BTC, 100));
+		assert_ok!(CDPTreasuryModule::set_flash_loan_fee_rate(
+			Origin::signed(1),
+			BTC,
+			Some(Rate::saturating_from_rational(1, 10))
+		));
+		assert_ok!(CDPTreasuryModule::flash_loan(
+			Origin::signed(BOB),
+			BTC,
+			100,
+			transfer_call(ALICE, BTC, 50)
+		));
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 110);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 940);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 1005);
+	});
+}
+
+#[test]
+fn swap_exact_collateral_to_stable_by_aggregated_swap_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(Currencies::deposit(AUSD, &STABLE_ASSET_POOL, 1000));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 200));
+
+		assert_noop!(
+			CDPTreasuryModule::set_aggregated_swap_paths(Origin::signed(5), BTC, Some(vec![SwapPath::Taiga(0, 0, 1)])),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_aggregated_swap_paths(Origin::signed(1), BTC, Some(vec![SwapPath::Taiga(0, 1, 0)])),
+			Error::<Runtime>::InvalidSwapPath
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_aggregated_swap_paths(
+				Origin::signed(1),
+				BTC,
+				Some(vec![SwapPath::Taiga(0, 0, 1), SwapPath::Dex(vec![BTC, AUSD])])
+			),
+			Error::<Runtime>::InvalidSwapPath
+		);
+		assert_ok!(CDPTreasuryModule::set_aggregated_swap_paths(
+			Origin::signed(1),
+			BTC,
+			Some(vec![SwapPath::Taiga(0, 0, 1)])
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AggregatedSwapPathsUpdated(
+			BTC,
+			Some(vec![SwapPath::Taiga(0, 0, 1)]),
+		)));
+
+		// the DEX gets more
+		assert_ok!(CDPTreasuryModule::swap_exact_collateral_to_stable(
+			BTC,
+			10,
+			0,
+			&[BTC, AUSD],
+			false
+		));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 90);
+		assert_eq!(Currencies::free_balance(AUSD, &STABLE_ASSET_POOL), 1000);
+
+		// the stable asset pool gets more
+		assert_ok!(CDPTreasuryModule::swap_exact_collateral_to_stable(
+			BTC,
+			150,
+			700,
+			&[BTC, AUSD],
+			false
+		));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 840);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 40);
+		assert_eq!(Currencies::free_balance(AUSD, &STABLE_ASSET_POOL), 250);
+
+		assert_noop!(
+			CDPTreasuryModule::swap_exact_collateral_to_stable(BTC, 40, 201, &[BTC, AUSD], false),
+			DispatchError::Other("aggregated swap target amount below the limit")
+		);
+
+		assert_ok!(CDPTreasuryModule::set_aggregated_swap_paths(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AggregatedSwapPathsUpdated(
+			BTC, None,
+		)));
+		assert_eq!(CDPTreasuryModule::aggregated_swap_paths(BTC), None);
+	});
+}
+
+#[test]
+fn swap_collateral_to_stable_by_aggregated_paths_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Currencies::deposit(AUSD, &STABLE_ASSET_POOL, 1000));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 200));
+
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable_by_aggregated_paths(BTC, 200, 500),
+			Error::<Runtime>::InvalidSwapPath
+		);
+		assert_ok!(CDPTreasuryModule::set_aggregated_swap_paths(
+			Origin::signed(1),
+			BTC,
+			Some(vec![SwapPath::Taiga(0, 0, 1)])
+		));
+
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable_by_aggregated_paths(BTC, 201, 500),
+			Error::<Runtime>::CollateralNotEnough
+		);
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable_by_aggregated_paths(BTC, 200, 1001),
+			Error::<Runtime>::AggregatedSwapNotEnough
+		);
+
+		// only supply the collateral needed for the target
+		assert_eq!(
+			CDPTreasuryModule::swap_collateral_to_stable_by_aggregated_paths(BTC, 200, 500),
+			Ok(101)
+		);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 99);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 505);
+		assert_eq!(Currencies::free_balance(AUSD, &STABLE_ASSET_POOL), 495);
+	});
+}
+
+#[test]
+fn collateral_swap_path_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(CDPTreasuryModule::get_collateral_swap_path(BTC), vec![BTC, AUSD]);
+
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_swap_path(Origin::signed(5), BTC, Some(vec![BTC, DOT, AUSD])),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_swap_path(Origin::signed(1), BTC, Some(vec![BTC])),
+			Error::<Runtime>::InvalidSwapPath
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_swap_path(Origin::signed(1), BTC, Some(vec![DOT, AUSD])),
+			Error::<Runtime>::InvalidSwapPath
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_collateral_swap_path(Origin::signed(1), BTC, Some(vec![BTC, DOT])),
+			Error::<Runtime>::InvalidSwapPath
+		);
+
+		assert_ok!(CDPTreasuryModule::set_collateral_swap_path(
+			Origin::signed(1),
+			BTC,
+			Some(vec![BTC, DOT, AUSD])
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSwapPathUpdated(
+			BTC,
+			Some(vec![BTC, DOT, AUSD]),
+		)));
+		assert_eq!(CDPTreasuryModule::collateral_swap_path(BTC), Some(vec![BTC, DOT, AUSD]));
+		assert_eq!(CDPTreasuryModule::get_collateral_swap_path(BTC), vec![BTC, DOT, AUSD]);
+
+		assert_ok!(CDPTreasuryModule::set_collateral_swap_path(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralSwapPathUpdated(
+			BTC, None,
+		)));
+		assert_eq!(CDPTreasuryModule::collateral_swap_path(BTC), None);
+		assert_eq!(CDPTreasuryModule::get_collateral_swap_path(BTC), vec![BTC, AUSD]);
+	});
+}
+
+#[test]
+fn dispose_collaterals_on_idle_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 10));
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(1),
+			BTC,
+			5
+		));
+
+		// the collateral is not disposed without swap path set
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 10);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+
+		assert_ok!(CDPTreasuryModule::set_collateral_swap_path(
+			Origin::signed(1),
+			BTC,
+			Some(vec![BTC, AUSD])
+		));
+
+		// dispose at most the expected collateral auction size per block
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralDisposed(BTC, 5, 47)));
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 5);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 47);
+
+		System::set_block_number(2);
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralDisposed(BTC, 5, 43)));
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 90);
+
+		// the swap fails when the slippage compared to oracle price is too large
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 100));
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(1),
+			BTC,
+			0
+		));
+		System::set_block_number(3);
+		CDPTreasuryModule::on_idle(3, u64::MAX);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 90);
+	});
+}
+
+#[test]
+fn dispose_collaterals_carried_over_without_enough_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		OffsetPeriod::set(0);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 5));
+		assert_ok!(CDPTreasuryModule::set_collateral_swap_path(
+			Origin::signed(1),
+			BTC,
+			Some(vec![BTC, AUSD])
+		));
+		assert_ok!(CDPTreasuryModule::set_collateral_swap_path(
+			Origin::signed(1),
+			DOT,
+			Some(vec![DOT, AUSD])
+		));
+		let offset_weight = <() as WeightInfo>::offset_denominations(0);
+		// the swap of the collateral is weighed by the length of its swap path
+		let dispose_weight =
+			<() as WeightInfo>::dispose_collaterals(1) + <() as WeightInfo>::swap_exact_collateral_to_stable(2);
+
+		// only the offset is done without weight for any collateral type
+		assert_eq!(CDPTreasuryModule::on_idle(1, offset_weight), offset_weight);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 5);
+		assert_eq!(DisposeCollateralsStartKey::<Runtime>::get(), None);
+
+		// one collateral type is disposed per block, the other one is carried over
+		assert_eq!(
+			CDPTreasuryModule::on_idle(2, offset_weight + dispose_weight),
+			offset_weight + dispose_weight
+		);
+		assert!(DisposeCollateralsStartKey::<Runtime>::get().is_some());
+		assert_eq!(
+			CDPTreasuryModule::on_idle(3, offset_weight + dispose_weight),
+			offset_weight + dispose_weight
+		);
+		assert_eq!(DisposeCollateralsStartKey::<Runtime>::get(), None);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 47);
+	});
+}
+
+#[test]
+fn yield_strategy_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 100));
+		assert_noop!(
+			CDPTreasuryModule::set_yield_strategy_enabled(Origin::signed(5), BTC, true),
+			BadOrigin
+		);
+
+		assert_ok!(CDPTreasuryModule::set_yield_strategy_enabled(
+			Origin::signed(1),
+			BTC,
+			true
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::YieldStrategyUpdated(BTC, true)));
+		assert!(CDPTreasuryModule::yield_strategy_enabled(BTC));
+
+		// the idle collateral is deposited into the yield strategy in on_idle
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(
+			crate::Event::CollateralDepositedToYieldStrategy(BTC, 100),
+		));
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 100);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 0);
+		assert_eq!(Currencies::free_balance(BTC, &YIELD_STRATEGY), 100);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+
+		// the collateral is withdrawn from the yield strategy when needed
+		assert_ok!(CDPTreasuryModule::withdraw_collateral(&BOB, BTC, 30));
+		System::assert_has_event(Event::CDPTreasuryModule(
+			crate::Event::CollateralWithdrawnFromYieldStrategy(BTC, 30),
+		));
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 70);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 70);
+
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			BTC, 20, 200, ALICE, false
+		));
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 50);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 20);
+
+		// the accrued yield is harvested and the idle collateral is deposited,
+		// the collateral in auction is kept
+		assert_ok!(Currencies::deposit(BTC, &YIELD_STRATEGY, 10));
+		System::set_block_number(2);
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		System::assert_has_event(Event::CDPTreasuryModule(crate::Event::YieldHarvested(BTC, 10, 0)));
+		System::assert_last_event(Event::CDPTreasuryModule(
+			crate::Event::CollateralDepositedToYieldStrategy(BTC, 10),
+		));
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 60);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 20);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 80);
+
+		// all the collateral is withdrawn when the yield strategy is disabled
+		assert_ok!(CDPTreasuryModule::set_yield_strategy_enabled(
+			Origin::signed(1),
+			BTC,
+			false
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::YieldStrategyUpdated(BTC, false)));
+		assert!(!CDPTreasuryModule::yield_strategy_enabled(BTC));
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 0);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 80);
+		assert_eq!(Currencies::free_balance(BTC, &YIELD_STRATEGY), 0);
+
+		System::set_block_number(3);
+		CDPTreasuryModule::on_idle(3, u64::MAX);
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 0);
+	});
+}
+
+#[test]
+fn noop_yield_strategy_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		YIELD_STRATEGY_NOOP.with(|v| *v.borrow_mut() = true);
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 100));
+		assert_ok!(CDPTreasuryModule::set_yield_strategy_enabled(
+			Origin::signed(1),
+			BTC,
+			true
+		));
+
+		// nothing is deposited and no principal is recorded
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		assert_eq!(CDPTreasuryModule::collateral_in_yield_strategy(BTC), 0);
+		assert_eq!(Currencies::free_balance(BTC, &CDPTreasuryModule::account_id()), 100);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+
+		assert_ok!(CDPTreasuryModule::set_yield_strategy_enabled(
+			Origin::signed(1),
+			BTC,
+			false
+		));
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+	});
+}
+
+#[test]
+fn auction_exempt_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(ALICE),
+			BTC,
+			AUSD,
+			100,
+			1000,
+			0,
+			false
+		));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 20));
+		assert!(!CDPTreasuryModule::is_auction_exempt(BTC));
+
+		assert_noop!(
+			CDPTreasuryModule::set_auction_exempt_collateral(Origin::signed(5), BTC, Some(Ratio::zero())),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_auction_exempt_collateral(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::zero())
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AuctionExemptCollateralUpdated(
+			BTC,
+			Some(Ratio::zero()),
+		)));
+		assert!(CDPTreasuryModule::is_auction_exempt(BTC));
+
+		// swap to raise the target and refund the collateral left
+		assert_ok!(CDPTreasuryModule::set_auction_exempt_collateral(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(50, 100))
+		));
+		assert_eq!(
+			CDPTreasuryModule::create_collateral_auctions(BTC, 10, 40, BOB, true),
+			Ok(0)
+		);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AuctionExemptCollateralSwapped(
+			BTC, 5, 40,
+		)));
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 0);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 985);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 10);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 40);
+
+		// swap all the collateral when the target can't be raised
+		assert_eq!(
+			CDPTreasuryModule::create_collateral_auctions(BTC, 10, 200, BOB, true),
+			Ok(0)
+		);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AuctionExemptCollateralSwapped(
+			BTC, 10, 83,
+		)));
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 985);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 0);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 123);
+
+		// fall back to auctions when the swap fails because the slippage compared
+		// to oracle price is too large
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, BTC, 10));
+		assert_ok!(CDPTreasuryModule::set_auction_exempt_collateral(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::zero())
+		));
+		assert_eq!(
+			CDPTreasuryModule::create_collateral_auctions(BTC, 10, 200, BOB, false),
+			Ok(1)
+		);
+		assert_eq!(TOTAL_COLLATERAL_AUCTION.with(|v| *v.borrow_mut()), 1);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 10);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 123);
+
+		assert_ok!(CDPTreasuryModule::set_auction_exempt_collateral(
+			Origin::signed(1),
+			BTC,
+			None
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AuctionExemptCollateralUpdated(
+			BTC, None,
+		)));
+		assert!(!CDPTreasuryModule::is_auction_exempt(BTC));
+
+		// unpaused by the circuit breaker
+		assert_ok!(CDPTreasuryModule::set_auction_exempt_collateral(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::zero())
+		));
+		assert!(!CDPTreasuryModule::unpause(&CircuitBreakerId::DebitIssuance));
+		assert!(CDPTreasuryModule::unpause(&CircuitBreakerId::CollateralAuction(BTC)));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::AuctionExemptCollateralUpdated(
+			BTC, None,
+		)));
+		assert!(!CDPTreasuryModule::is_auction_exempt(BTC));
+	});
+}
+
+#[test]
+fn donation_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPTreasuryModule::deposit_surplus_from(Origin::signed(ALICE), 100));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::SurplusDonated(ALICE, 100)));
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 900);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 100);
+
+		assert_noop!(
+			CDPTreasuryModule::donate_collateral(Origin::signed(ALICE), AUSD, 100),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPTreasuryModule::donate_collateral(Origin::signed(ALICE), ACA, 100),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_ok!(CDPTreasuryModule::donate_collateral(Origin::signed(ALICE), BTC, 100));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::CollateralDonated(
+			ALICE, BTC, 100,
+		)));
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(CDPTreasuryModule::total_collaterals(BTC), 100);
+	});
+}
+
+#[test]
+fn debt_denomination_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(5), DOT, true),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(1), AUSD, true),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+		// only the whitelisted stable assets can be enabled
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(1), ACA, true),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+		assert!(CDPTreasuryModule::is_debt_denomination(AUSD));
+		assert!(!CDPTreasuryModule::is_debt_denomination(DOT));
+		assert_noop!(
+			CDPTreasuryModule::issue_debit_of(DOT, &ALICE, 100, false),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+		assert_noop!(
+			CDPTreasuryModule::inc_denomination_consumers(DOT),
+			Error::<Runtime>::InvalidDebtDenomination
+		);
+
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			DOT,
+			true
+		));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DebtDenominationUpdated(
+			DOT, true,
+		)));
+		assert!(CDPTreasuryModule::is_debt_denomination(DOT));
+		assert_eq!(CDPTreasuryModule::debt_denominations_count(), 1);
+		// enable again doesn't change the count
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			DOT,
+			true
+		));
+		assert_eq!(CDPTreasuryModule::debt_denominations_count(), 1);
+
+		// the denomination can't be disabled while collateral types are denominated in it
+		assert_ok!(CDPTreasuryModule::inc_denomination_consumers(DOT));
+		assert_eq!(CDPTreasuryModule::denomination_consumers(DOT), 1);
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(1), DOT, false),
+			Error::<Runtime>::DebtDenominationInUse
+		);
+		CDPTreasuryModule::dec_denomination_consumers(DOT);
+		assert_eq!(CDPTreasuryModule::denomination_consumers(DOT), 0);
+
+		// the pools of the denomination are separate from the stable currency
+		assert_ok!(CDPTreasuryModule::issue_debit_of(DOT, &ALICE, 300, false));
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DenominationDebitIssued(
+			DOT, ALICE, 300, false,
+		)));
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 1300);
+		assert_eq!(CDPTreasuryModule::get_debit_pool_of(DOT), 300);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_ok!(CDPTreasuryModule::on_system_surplus_of(DOT, 200));
+		assert_eq!(CDPTreasuryModule::get_surplus_pool_of(DOT), 200);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+
+		assert_noop!(
+			CDPTreasuryModule::set_debt_denomination_enabled(Origin::signed(1), DOT, false),
+			Error::<Runtime>::DebtDenominationInUse
+		);
+
+		CDPTreasuryModule::on_idle(1, u64::MAX);
+		System::assert_last_event(Event::CDPTreasuryModule(crate::Event::DenominationDebitOffset(
+			DOT, 200,
+		)));
+		assert_eq!(CDPTreasuryModule::get_surplus_pool_of(DOT), 0);
+		assert_eq!(CDPTreasuryModule::get_debit_pool_of(DOT), 100);
+
+		assert_ok!(CDPTreasuryModule::burn_debit_of(DOT, &ALICE, 300));
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 1000);
+		assert_ok!(CDPTreasuryModule::on_system_surplus_of(DOT, 100));
+		CDPTreasuryModule::on_idle(2, u64::MAX);
+		assert_eq!(CDPTreasuryModule::get_debit_pool_of(DOT), 0);
+
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			DOT,
+			false
+		));
+		assert!(!CDPTreasuryModule::is_debt_denomination(DOT));
+		assert_eq!(CDPTreasuryModule::debt_denominations_count(), 0);
+		assert_ok!(CDPTreasuryModule::set_debt_denomination_enabled(
+			Origin::signed(1),
+			DOT,
+			false
+		));
+		assert_eq!(CDPTreasuryModule::debt_denominations_count(), 0);
+	});
+}
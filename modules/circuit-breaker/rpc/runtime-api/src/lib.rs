@@ -0,0 +1,43 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime API definition for circuit breaker module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use primitives::{CircuitBreakerId, CircuitBreakerReason};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait CircuitBreakerApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// The active circuit breakers, with the reason, the block number at
+		/// which it's paused and whether the pause is confirmed by governance.
+		fn active_breakers() -> Vec<(CircuitBreakerId, CircuitBreakerReason, BlockNumber, bool)>;
+
+		/// The history of the unpaused circuit breakers, with the reason, the
+		/// block numbers at which it's paused and unpaused, in ascending order
+		/// of the block number unpaused.
+		fn breaker_history() -> Vec<(CircuitBreakerId, CircuitBreakerReason, BlockNumber, BlockNumber)>;
+	}
+}
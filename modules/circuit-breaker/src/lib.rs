@@ -0,0 +1,339 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Circuit Breaker Module
+//!
+//! ## Overview
+//!
+//! Circuit breaker module tracks the pause mechanisms of the protocol
+//! uniformly, including the paused transactions of transaction pause, the
+//! pauses of the issuance of new debit of CDP engine, globally or per
+//! collateral type, and the collateral types exempt from auctions by CDP
+//! treasury. It records the reason and the block number of every pause
+//! reported by the owner pallets, and keeps the history of the unpaused
+//! circuit breakers for monitoring.
+//!
+//! Governance can set an auto-unpause condition for a circuit breaker, which
+//! is checked at the beginning of every block while the circuit breaker is
+//! active:
+//! - the price of a currency has been back within the band for a number of
+//!   blocks;
+//! - the pause has not been confirmed by governance within a number of blocks.
+//!
+//! The circuit breaker meeting its condition is unpaused through the owner
+//! pallet. At most `MaxAutoUnpauseChecksPerBlock` active circuit breakers are
+//! checked in a block, the checks continue from the cursor in the following
+//! blocks.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, BoundedVec};
+use frame_system::pallet_prelude::*;
+use primitives::{CircuitBreakerId, CircuitBreakerReason, CurrencyId};
+use sp_runtime::{traits::Saturating, RuntimeDebug};
+use sp_std::prelude::*;
+use support::{CircuitBreakerRecorder, CircuitBreakerUnpause, Price, PriceProvider};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The condition to unpause the circuit breaker automatically
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum AutoUnpauseCondition<BlockNumber> {
+	/// The price of the currency has been within the band for the number of
+	/// blocks
+	PriceWithinBand {
+		currency_id: CurrencyId,
+		lower_bound: Price,
+		upper_bound: Price,
+		blocks: BlockNumber,
+	},
+	/// The pause has not been confirmed by governance within the number of
+	/// blocks
+	ConfirmationAbsent { blocks: BlockNumber },
+}
+
+/// The state of the active circuit breaker
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct BreakerState<BlockNumber> {
+	/// The reason of the pause
+	pub reason: CircuitBreakerReason,
+	/// The block number at which it's paused
+	pub paused_at: BlockNumber,
+	/// Whether the pause is confirmed by governance
+	pub confirmed: bool,
+	/// The block number since which the price has been within the band of
+	/// the auto-unpause condition
+	pub within_band_since: Option<BlockNumber>,
+}
+
+/// The record of the unpaused circuit breaker
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct BreakerRecord<BlockNumber> {
+	pub id: CircuitBreakerId,
+	pub reason: CircuitBreakerReason,
+	pub paused_at: BlockNumber,
+	pub unpaused_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may confirm the pauses and set the auto-unpause
+		/// conditions.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The price source to check the auto-unpause conditions
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The owner pallets to unpause the circuit breakers
+		type Unpause: CircuitBreakerUnpause;
+
+		/// The max number of the records kept in the history, the oldest
+		/// record is dropped when exceeded
+		#[pallet::constant]
+		type MaxHistoryRecords: Get<u32>;
+
+		/// The max number of the active circuit breakers to check the
+		/// auto-unpause conditions in a block
+		#[pallet::constant]
+		type MaxAutoUnpauseChecksPerBlock: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The circuit breaker is not active
+		BreakerNotActive,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The circuit breaker is paused. \[breaker_id, reason\]
+		Paused(CircuitBreakerId, CircuitBreakerReason),
+		/// The circuit breaker is unpaused. \[breaker_id\]
+		Unpaused(CircuitBreakerId),
+		/// The pause of the circuit breaker is confirmed by governance.
+		/// \[breaker_id\]
+		PauseConfirmed(CircuitBreakerId),
+		/// The auto-unpause condition of the circuit breaker updated.
+		/// \[breaker_id, new_condition\]
+		AutoUnpauseConditionUpdated(CircuitBreakerId, Option<AutoUnpauseCondition<T::BlockNumber>>),
+		/// The circuit breaker meets its auto-unpause condition and is
+		/// unpaused. \[breaker_id\]
+		AutoUnpaused(CircuitBreakerId),
+	}
+
+	/// The active circuit breakers.
+	///
+	/// ActiveBreakers: map CircuitBreakerId => Option<BreakerState>
+	#[pallet::storage]
+	#[pallet::getter(fn active_breakers)]
+	pub type ActiveBreakers<T: Config> =
+		StorageMap<_, Twox64Concat, CircuitBreakerId, BreakerState<T::BlockNumber>, OptionQuery>;
+
+	/// The auto-unpause conditions of the circuit breakers.
+	///
+	/// AutoUnpauseConditions: map CircuitBreakerId =>
+	/// Option<AutoUnpauseCondition>
+	#[pallet::storage]
+	#[pallet::getter(fn auto_unpause_conditions)]
+	pub type AutoUnpauseConditions<T: Config> =
+		StorageMap<_, Twox64Concat, CircuitBreakerId, AutoUnpauseCondition<T::BlockNumber>, OptionQuery>;
+
+	/// The history of the unpaused circuit breakers, in ascending order of
+	/// the block number unpaused.
+	///
+	/// BreakerHistory: Vec<BreakerRecord>
+	#[pallet::storage]
+	#[pallet::getter(fn breaker_history)]
+	pub type BreakerHistory<T: Config> =
+		StorageValue<_, BoundedVec<BreakerRecord<T::BlockNumber>, T::MaxHistoryRecords>, ValueQuery>;
+
+	/// The raw storage key of the last active circuit breaker checked, the
+	/// checks of the next block continue from it.
+	///
+	/// AutoUnpauseCheckCursor: Option<Vec<u8>>
+	#[pallet::storage]
+	#[pallet::getter(fn auto_unpause_check_cursor)]
+	pub type AutoUnpauseCheckCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Check the auto-unpause conditions of at most
+		/// `MaxAutoUnpauseChecksPerBlock` active circuit breakers following the
+		/// cursor, and unpause the ones meeting their conditions.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let max_checks = T::MaxAutoUnpauseChecksPerBlock::get();
+			let mut iterator = match Self::auto_unpause_check_cursor() {
+				Some(cursor) => ActiveBreakers::<T>::iter_from(cursor),
+				None => ActiveBreakers::<T>::iter(),
+			};
+			let breakers = iterator.by_ref().take(max_checks as usize).collect::<Vec<_>>();
+			let count = breakers.len() as u32;
+			if count >= max_checks {
+				AutoUnpauseCheckCursor::<T>::put(iterator.last_raw_key());
+			} else {
+				AutoUnpauseCheckCursor::<T>::kill();
+			}
+
+			for (id, state) in breakers {
+				if let Some(condition) = Self::auto_unpause_conditions(&id) {
+					Self::check_auto_unpause(now, id, state, condition);
+				}
+			}
+			T::WeightInfo::on_initialize(count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Confirm the pause of the active circuit breaker, so that it's not
+		/// unpaused by the `ConfirmationAbsent` condition.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `id`: the circuit breaker.
+		#[pallet::weight((T::WeightInfo::confirm_pause(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn confirm_pause(origin: OriginFor<T>, id: CircuitBreakerId) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ActiveBreakers::<T>::try_mutate(&id, |maybe_state| -> DispatchResult {
+				let state = maybe_state.as_mut().ok_or(Error::<T>::BreakerNotActive)?;
+				state.confirmed = true;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::PauseConfirmed(id));
+			Ok(())
+		}
+
+		/// Update the auto-unpause condition of the circuit breaker.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `id`: the circuit breaker.
+		/// - `condition`: the auto-unpause condition, `None` means removing it.
+		#[pallet::weight((T::WeightInfo::set_auto_unpause_condition(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_auto_unpause_condition(
+			origin: OriginFor<T>,
+			id: CircuitBreakerId,
+			condition: Option<AutoUnpauseCondition<T::BlockNumber>>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			AutoUnpauseConditions::<T>::set(&id, condition);
+			ActiveBreakers::<T>::mutate(&id, |maybe_state| {
+				if let Some(state) = maybe_state {
+					state.within_band_since = None;
+				}
+			});
+			Self::deposit_event(Event::AutoUnpauseConditionUpdated(id, condition));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn check_auto_unpause(
+		now: T::BlockNumber,
+		id: CircuitBreakerId,
+		mut state: BreakerState<T::BlockNumber>,
+		condition: AutoUnpauseCondition<T::BlockNumber>,
+	) {
+		let should_unpause = match condition {
+			AutoUnpauseCondition::PriceWithinBand {
+				currency_id,
+				lower_bound,
+				upper_bound,
+				blocks,
+			} => {
+				let within_band = T::PriceSource::get_price(currency_id)
+					.map_or(false, |price| lower_bound <= price && price <= upper_bound);
+				let within_band_since = if within_band {
+					Some(state.within_band_since.unwrap_or(now))
+				} else {
+					None
+				};
+				if within_band_since != state.within_band_since {
+					state.within_band_since = within_band_since;
+					ActiveBreakers::<T>::insert(&id, state);
+				}
+				within_band_since.map_or(false, |since| now.saturating_sub(since) >= blocks)
+			}
+			AutoUnpauseCondition::ConfirmationAbsent { blocks } => {
+				!state.confirmed && now.saturating_sub(state.paused_at) >= blocks
+			}
+		};
+
+		if should_unpause && T::Unpause::unpause(&id) {
+			Self::deposit_event(Event::AutoUnpaused(id));
+		}
+	}
+}
+
+impl<T: Config> CircuitBreakerRecorder for Pallet<T> {
+	fn on_paused(id: CircuitBreakerId, reason: CircuitBreakerReason) {
+		if ActiveBreakers::<T>::contains_key(&id) {
+			return;
+		}
+
+		ActiveBreakers::<T>::insert(
+			&id,
+			BreakerState {
+				reason,
+				paused_at: frame_system::Pallet::<T>::block_number(),
+				confirmed: false,
+				within_band_since: None,
+			},
+		);
+		Self::deposit_event(Event::Paused(id, reason));
+	}
+
+	fn on_unpaused(id: CircuitBreakerId) {
+		if let Some(state) = ActiveBreakers::<T>::take(&id) {
+			BreakerHistory::<T>::mutate(|history| {
+				if !history.is_empty() && history.len() as u32 >= T::MaxHistoryRecords::get() {
+					history.remove(0);
+				}
+				let _ = history.try_push(BreakerRecord {
+					id: id.clone(),
+					reason: state.reason,
+					paused_at: state.paused_at,
+					unpaused_at: frame_system::Pallet::<T>::block_number(),
+				});
+			});
+			Self::deposit_event(Event::Unpaused(id));
+		}
+	}
+}
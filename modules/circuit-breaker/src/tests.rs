@@ -0,0 +1,252 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the circuit breaker module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use sp_runtime::{traits::BadOrigin, FixedPointNumber};
+
+fn transaction_breaker() -> CircuitBreakerId {
+	CircuitBreakerId::Transaction(b"Dex".to_vec(), b"swap_with_exact_supply".to_vec())
+}
+
+#[test]
+fn record_pause_and_unpause_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::BadDebtOverflow);
+		System::assert_last_event(Event::CircuitBreakerModule(crate::Event::Paused(
+			CircuitBreakerId::DebitIssuance,
+			CircuitBreakerReason::BadDebtOverflow,
+		)));
+		let state = BreakerState {
+			reason: CircuitBreakerReason::BadDebtOverflow,
+			paused_at: 1,
+			confirmed: false,
+			within_band_since: None,
+		};
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance),
+			Some(state)
+		);
+
+		// the reason of the first pause is kept
+		System::set_block_number(2);
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance),
+			Some(state)
+		);
+
+		System::set_block_number(5);
+		CircuitBreakerModule::on_unpaused(CircuitBreakerId::DebitIssuance);
+		System::assert_last_event(Event::CircuitBreakerModule(crate::Event::Unpaused(
+			CircuitBreakerId::DebitIssuance,
+		)));
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance),
+			None
+		);
+		assert_eq!(
+			CircuitBreakerModule::breaker_history().into_inner(),
+			vec![BreakerRecord {
+				id: CircuitBreakerId::DebitIssuance,
+				reason: CircuitBreakerReason::BadDebtOverflow,
+				paused_at: 1,
+				unpaused_at: 5,
+			}]
+		);
+
+		// the oldest record is dropped
+		CircuitBreakerModule::on_paused(transaction_breaker(), CircuitBreakerReason::Governance);
+		CircuitBreakerModule::on_unpaused(transaction_breaker());
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+		CircuitBreakerModule::on_unpaused(CircuitBreakerId::DebitIssuance);
+		let history = CircuitBreakerModule::breaker_history();
+		assert_eq!(history.len(), 2);
+		assert_eq!(history[0].id, transaction_breaker());
+		assert_eq!(history[1].reason, CircuitBreakerReason::Governance);
+	});
+}
+
+#[test]
+fn confirm_pause_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CircuitBreakerModule::confirm_pause(Origin::signed(2), CircuitBreakerId::DebitIssuance),
+			BadOrigin
+		);
+		assert_noop!(
+			CircuitBreakerModule::confirm_pause(Origin::signed(1), CircuitBreakerId::DebitIssuance),
+			Error::<Runtime>::BreakerNotActive
+		);
+
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+		assert_ok!(CircuitBreakerModule::confirm_pause(
+			Origin::signed(1),
+			CircuitBreakerId::DebitIssuance
+		));
+		System::assert_last_event(Event::CircuitBreakerModule(crate::Event::PauseConfirmed(
+			CircuitBreakerId::DebitIssuance,
+		)));
+		assert!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance)
+				.unwrap()
+				.confirmed
+		);
+	});
+}
+
+#[test]
+fn auto_unpause_by_confirmation_absent_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let condition = AutoUnpauseCondition::ConfirmationAbsent { blocks: 10 };
+		assert_noop!(
+			CircuitBreakerModule::set_auto_unpause_condition(
+				Origin::signed(2),
+				CircuitBreakerId::DebitIssuance,
+				Some(condition)
+			),
+			BadOrigin
+		);
+		assert_ok!(CircuitBreakerModule::set_auto_unpause_condition(
+			Origin::signed(1),
+			CircuitBreakerId::DebitIssuance,
+			Some(condition)
+		));
+		System::assert_last_event(Event::CircuitBreakerModule(crate::Event::AutoUnpauseConditionUpdated(
+			CircuitBreakerId::DebitIssuance,
+			Some(condition),
+		)));
+		assert_ok!(CircuitBreakerModule::set_auto_unpause_condition(
+			Origin::signed(1),
+			transaction_breaker(),
+			Some(condition)
+		));
+
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::BadDebtOverflow);
+		CircuitBreakerModule::on_paused(transaction_breaker(), CircuitBreakerReason::Governance);
+
+		CircuitBreakerModule::on_initialize(10);
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_some());
+
+		System::set_block_number(11);
+		CircuitBreakerModule::on_initialize(11);
+		System::assert_has_event(Event::CircuitBreakerModule(crate::Event::AutoUnpaused(
+			CircuitBreakerId::DebitIssuance,
+		)));
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_none());
+		assert_eq!(CircuitBreakerModule::breaker_history()[0].unpaused_at, 11);
+		// the circuit breaker not owned by the unpause handler is kept
+		assert!(CircuitBreakerModule::active_breakers(transaction_breaker()).is_some());
+
+		// the confirmed pause is not unpaused
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+		assert_ok!(CircuitBreakerModule::confirm_pause(
+			Origin::signed(1),
+			CircuitBreakerId::DebitIssuance
+		));
+		CircuitBreakerModule::on_initialize(30);
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_some());
+	});
+}
+
+#[test]
+fn auto_unpause_by_price_within_band_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CircuitBreakerModule::set_auto_unpause_condition(
+			Origin::signed(1),
+			CircuitBreakerId::DebitIssuance,
+			Some(AutoUnpauseCondition::PriceWithinBand {
+				currency_id: DOT,
+				lower_bound: Price::saturating_from_integer(9),
+				upper_bound: Price::saturating_from_integer(11),
+				blocks: 5,
+			})
+		));
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+
+		CircuitBreakerModule::on_initialize(2);
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance)
+				.unwrap()
+				.within_band_since,
+			None
+		);
+
+		set_dot_price(Some(Price::saturating_from_integer(10)));
+		CircuitBreakerModule::on_initialize(3);
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance)
+				.unwrap()
+				.within_band_since,
+			Some(3)
+		);
+
+		// the price moving out of the band resets the count
+		set_dot_price(Some(Price::saturating_from_integer(12)));
+		CircuitBreakerModule::on_initialize(5);
+		assert_eq!(
+			CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance)
+				.unwrap()
+				.within_band_since,
+			None
+		);
+
+		set_dot_price(Some(Price::saturating_from_integer(10)));
+		CircuitBreakerModule::on_initialize(6);
+		CircuitBreakerModule::on_initialize(10);
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_some());
+
+		CircuitBreakerModule::on_initialize(11);
+		System::assert_has_event(Event::CircuitBreakerModule(crate::Event::AutoUnpaused(
+			CircuitBreakerId::DebitIssuance,
+		)));
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_none());
+	});
+}
+
+#[test]
+fn auto_unpause_checks_are_bounded_per_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxAutoUnpauseChecksPerBlock::set(1);
+		assert_ok!(CircuitBreakerModule::set_auto_unpause_condition(
+			Origin::signed(1),
+			CircuitBreakerId::DebitIssuance,
+			Some(AutoUnpauseCondition::ConfirmationAbsent { blocks: 0 })
+		));
+		CircuitBreakerModule::on_paused(CircuitBreakerId::DebitIssuance, CircuitBreakerReason::Governance);
+		CircuitBreakerModule::on_paused(transaction_breaker(), CircuitBreakerReason::Governance);
+		assert_eq!(CircuitBreakerModule::auto_unpause_check_cursor(), None);
+
+		// only one circuit breaker is checked in a block
+		CircuitBreakerModule::on_initialize(1);
+		assert!(CircuitBreakerModule::auto_unpause_check_cursor().is_some());
+
+		// the checks continue from the cursor in the next block
+		CircuitBreakerModule::on_initialize(2);
+		assert!(CircuitBreakerModule::active_breakers(CircuitBreakerId::DebitIssuance).is_none());
+		assert!(CircuitBreakerModule::active_breakers(transaction_breaker()).is_some());
+
+		CircuitBreakerModule::on_initialize(3);
+		assert_eq!(CircuitBreakerModule::auto_unpause_check_cursor(), None);
+	});
+}
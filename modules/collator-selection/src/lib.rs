@@ -29,7 +29,8 @@
 //! ## Terminology
 //!
 //! - Collator: A parachain block producer.
-//! - Bond: An amount of `Balance` _reserved_ for candidate registration.
+//! - Bond: An amount of `Balance` _reserved_ for candidate registration. Part of it could be
+//!   posted in LP shares, valued by their fair price after a haircut.
 //! - Invulnerable: An account guaranteed to be in the collator set.
 //!
 //! ## Implementation
@@ -80,24 +81,26 @@ pub mod pallet {
 		pallet_prelude::*,
 		storage::bounded_btree_set::BoundedBTreeSet,
 		traits::{
-			Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, NamedReservableCurrency, ValidatorRegistration,
-			ValidatorSet,
+			BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, Imbalance,
+			NamedReservableCurrency, ValidatorRegistration, ValidatorSet,
 		},
-		BoundedVec, PalletId,
+		transactional, BoundedVec, PalletId,
 	};
 	use frame_support::{
 		sp_runtime::{
 			traits::{AccountIdConversion, CheckedSub, Zero},
-			Permill,
+			FixedPointNumber, Permill, SaturatedConversion,
 		},
 		weights::DispatchClass,
 	};
 	use frame_system::pallet_prelude::*;
 	use frame_system::Config as SystemConfig;
+	use orml_traits::MultiReservableCurrency;
 	use pallet_session::SessionManager;
-	use primitives::ReserveIdentifier;
+	use primitives::{Balance, CurrencyId, ReserveIdentifier};
 	use sp_staking::SessionIndex;
 	use sp_std::{convert::TryInto, ops::Div, vec};
+	use support::{DEXManager, PriceProvider};
 
 	pub const RESERVE_ID: ReserveIdentifier = ReserveIdentifier::CollatorSelection;
 	pub const POINT_PER_BLOCK: u32 = 10;
@@ -158,6 +161,31 @@ pub mod pallet {
 		#[pallet::constant]
 		type CollatorKickThreshold: Get<Permill>;
 
+		/// The multi currency mechanism for the LP shares bond.
+		type MultiCurrency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = BalanceOf<Self>>;
+
+		/// The LP share currency which could be posted as part of the candidacy bond.
+		#[pallet::constant]
+		type LPBondCurrencyId: Get<CurrencyId>;
+
+		/// The native currency id, in which the LP shares bond is valued.
+		#[pallet::constant]
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		/// The haircut applied to the fair value of the LP shares bond.
+		#[pallet::constant]
+		type LPBondHaircut: Get<Permill>;
+
+		/// The max proportion of candidacy bond which could be posted in LP shares.
+		#[pallet::constant]
+		type MaxLPBondProportion: Get<Permill>;
+
+		/// The price source of the LP share, which should provide the fair price.
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// DEX to liquidate the slashed LP shares bond.
+		type DEX: DEXManager<Self::AccountId, CurrencyId, BalanceOf<Self>>;
+
 		/// The weight information of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -209,6 +237,13 @@ pub mod pallet {
 	#[pallet::getter(fn non_candidates)]
 	pub type NonCandidates<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, SessionIndex, ValueQuery>;
 
+	/// The LP shares posted as part of the candidacy bond.
+	///
+	/// LPBonds: map AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn lp_bonds)]
+	pub type LPBonds<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub invulnerables: Vec<T::AccountId>,
@@ -266,6 +301,11 @@ pub mod pallet {
 		CandidateAdded(T::AccountId, BalanceOf<T>),
 		/// A candidate was removed. \[who\]
 		CandidateRemoved(T::AccountId),
+		/// A candidate was added with LP shares bond. \[who, bond, lp_bond\]
+		CandidateAddedWithLPBond(T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A candidate was slashed, the slashed LP shares are liquidated.
+		/// \[who, slashed_bond, slashed_lp_bond\]
+		CandidateSlashed(T::AccountId, BalanceOf<T>, BalanceOf<T>),
 	}
 
 	// Errors inform users that something went wrong.
@@ -284,6 +324,8 @@ pub mod pallet {
 		AlreadyInvulnerable,
 		InvalidProof,
 		MaxInvulnerablesExceeded,
+		InvalidLPBond,
+		ExceedMaxLPBond,
 	}
 
 	#[pallet::hooks]
@@ -324,12 +366,7 @@ pub mod pallet {
 		pub fn register_as_candidate(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			<NonCandidates<T>>::try_mutate_exists(&who, |maybe_index| -> DispatchResult {
-				if let Some(index) = maybe_index.take() {
-					ensure!(T::ValidatorSet::session_index() >= index, Error::<T>::StillLocked);
-				}
-				Ok(())
-			})?;
+			Self::ensure_not_locked(&who)?;
 
 			let deposit = Self::candidacy_bond();
 			let bounded_candidates_len = Self::do_register_candidate(&who, deposit)?;
@@ -338,6 +375,39 @@ pub mod pallet {
 			Ok(Some(T::WeightInfo::register_as_candidate(bounded_candidates_len as u32)).into())
 		}
 
+		/// Register as candidate with part of the candidacy bond posted in LP shares. The LP
+		/// shares are valued by the fair price after `LPBondHaircut`, and could cover at most
+		/// `MaxLPBondProportion` of the candidacy bond, the rest is reserved in native currency.
+		#[pallet::weight(T::WeightInfo::register_as_candidate_with_lp_bond(T::MaxCandidates::get()))]
+		#[transactional]
+		pub fn register_as_candidate_with_lp_bond(
+			origin: OriginFor<T>,
+			#[pallet::compact] lp_amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!lp_amount.is_zero(), Error::<T>::InvalidLPBond);
+
+			Self::ensure_not_locked(&who)?;
+
+			let candidacy_bond = Self::candidacy_bond();
+			let lp_value = Self::lp_bond_value(lp_amount).ok_or(Error::<T>::InvalidLPBond)?;
+			ensure!(
+				lp_value <= T::MaxLPBondProportion::get().mul_floor(candidacy_bond),
+				Error::<T>::ExceedMaxLPBond
+			);
+
+			let deposit = candidacy_bond.saturating_sub(lp_value);
+			let bounded_candidates_len = Self::do_register_candidate(&who, deposit)?;
+			T::MultiCurrency::reserve(T::LPBondCurrencyId::get(), &who, lp_amount)?;
+			<LPBonds<T>>::insert(&who, lp_amount);
+			Self::deposit_event(Event::CandidateAddedWithLPBond(who, deposit, lp_amount));
+
+			Ok(Some(T::WeightInfo::register_as_candidate_with_lp_bond(
+				bounded_candidates_len as u32,
+			))
+			.into())
+		}
+
 		#[pallet::weight(T::WeightInfo::register_candidate(T::MaxCandidates::get()))]
 		pub fn register_candidate(origin: OriginFor<T>, new_candidate: T::AccountId) -> DispatchResultWithPostInfo {
 			T::UpdateOrigin::ensure_origin(origin)?;
@@ -366,12 +436,43 @@ pub mod pallet {
 				if let Some(index) = maybe_index.take() {
 					ensure!(T::ValidatorSet::session_index() >= index, Error::<T>::StillLocked);
 					T::Currency::unreserve_all_named(&RESERVE_ID, &who);
+					let lp_bond = <LPBonds<T>>::take(&who);
+					if !lp_bond.is_zero() {
+						T::MultiCurrency::unreserve(T::LPBondCurrencyId::get(), &who, lp_bond);
+					}
 					Ok(())
 				} else {
 					Err(Error::<T>::NothingToWithdraw.into())
 				}
 			})
 		}
+
+		/// Slash `proportion` of the bond of `who` to the Pot. The slashed LP shares are
+		/// liquidated by removing the liquidity from DEX.
+		#[pallet::weight(T::WeightInfo::slash_candidate())]
+		#[transactional]
+		pub fn slash_candidate(origin: OriginFor<T>, who: T::AccountId, proportion: Permill) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let pot = Self::account_id();
+
+			let bond = T::Currency::reserved_balance_named(&RESERVE_ID, &who);
+			let (imbalance, _) = T::Currency::slash_reserved_named(&RESERVE_ID, &who, proportion.mul_floor(bond));
+			let slashed_bond = imbalance.peek();
+			T::Currency::resolve_creating(&pot, imbalance);
+
+			let slashed_lp_bond = <LPBonds<T>>::mutate_exists(&who, |maybe_lp_bond| {
+				let lp_bond = maybe_lp_bond.unwrap_or_default();
+				let slashed = proportion.mul_floor(lp_bond);
+				*maybe_lp_bond = Some(lp_bond.saturating_sub(slashed)).filter(|remain| !remain.is_zero());
+				slashed
+			});
+			if !slashed_lp_bond.is_zero() {
+				Self::liquidate_lp_bond(&who, &pot, slashed_lp_bond)?;
+			}
+
+			Self::deposit_event(Event::CandidateSlashed(who, slashed_bond, slashed_lp_bond));
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -380,6 +481,48 @@ pub mod pallet {
 			T::PotId::get().into_account()
 		}
 
+		/// The value of LP shares in native currency, which is the fair value after
+		/// `LPBondHaircut`.
+		pub fn lp_bond_value(lp_amount: BalanceOf<T>) -> Option<BalanceOf<T>> {
+			let price = T::PriceSource::get_relative_price(T::LPBondCurrencyId::get(), T::GetNativeCurrencyId::get())?;
+			let value = price.checked_mul_int(lp_amount.saturated_into::<Balance>())?;
+			Some(
+				T::LPBondHaircut::get()
+					.left_from_one()
+					.mul_floor(value)
+					.saturated_into(),
+			)
+		}
+
+		/// Ensure `who` is not locked by leaving or being kicked.
+		fn ensure_not_locked(who: &T::AccountId) -> DispatchResult {
+			<NonCandidates<T>>::try_mutate_exists(who, |maybe_index| -> DispatchResult {
+				if let Some(index) = maybe_index.take() {
+					ensure!(T::ValidatorSet::session_index() >= index, Error::<T>::StillLocked);
+				}
+				Ok(())
+			})
+		}
+
+		/// Move the reserved LP shares of `who` to `pot` and remove the liquidity from DEX.
+		fn liquidate_lp_bond(who: &T::AccountId, pot: &T::AccountId, lp_amount: BalanceOf<T>) -> DispatchResult {
+			let lp_currency_id = T::LPBondCurrencyId::get();
+			let (currency_id_a, currency_id_b) = match lp_currency_id {
+				CurrencyId::DexShare(symbol_0, symbol_1) => (symbol_0.into(), symbol_1.into()),
+				_ => return Err(Error::<T>::InvalidLPBond.into()),
+			};
+			T::MultiCurrency::repatriate_reserved(lp_currency_id, who, pot, lp_amount, BalanceStatus::Free)?;
+			T::DEX::remove_liquidity(
+				pot,
+				currency_id_a,
+				currency_id_b,
+				lp_amount,
+				Zero::zero(),
+				Zero::zero(),
+				false,
+			)
+		}
+
 		/// Removes a candidate if they exist and sends them back their deposit
 		fn try_remove_candidate(who: &T::AccountId) -> Result<usize, DispatchError> {
 			let current_count = <Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
@@ -20,18 +20,24 @@ use super::*;
 use crate as collator_selection;
 use frame_support::{
 	ord_parameter_types, parameter_types,
-	traits::{Everything, FindAuthor, GenesisBuild},
+	traits::{Everything, FindAuthor, GenesisBuild, Nothing},
 	PalletId,
 };
 use frame_system as system;
 use frame_system::EnsureSignedBy;
-use primitives::ReserveIdentifier;
+use orml_traits::parameter_type_with_key;
+use primitives::{CurrencyId, DexShare, ReserveIdentifier, TokenSymbol};
 use sp_core::H256;
 use sp_runtime::{
 	testing::{Header, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup, OpaqueKeys},
-	Permill, RuntimeAppPublic,
+	FixedPointNumber, Permill, RuntimeAppPublic,
 };
+use support::{Price, PriceProvider};
+
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const LP_ACA_AUSD: CurrencyId =
+	CurrencyId::DexShare(DexShare::Token(TokenSymbol::ACA), DexShare::Token(TokenSymbol::AUSD));
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -48,6 +54,7 @@ frame_support::construct_runtime!(
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
 		Aura: pallet_aura::{Pallet, Storage, Config<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		CollatorSelection: collator_selection::{Pallet, Call, Storage, Event<T>},
 		Authorship: pallet_authorship::{Pallet, Call, Storage, Inherent},
 	}
@@ -101,6 +108,38 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> u64 {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type Amount = i64;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = Nothing;
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+		match (base, quote) {
+			(LP_ACA_AUSD, ACA) => Some(Price::saturating_from_integer(2)),
+			_ => None,
+		}
+	}
+
+	fn get_price(_currency_id: CurrencyId) -> Option<Price> {
+		None
+	}
+}
+
 pub struct Author4;
 impl FindAuthor<u64> for Author4 {
 	fn find_author<'a, I>(_digests: I) -> Option<u64>
@@ -198,6 +237,10 @@ parameter_types! {
 	pub const MaxInvulnerables: u32 = 4;
 	pub const KickPenaltySessionLength: u32 = 8;
 	pub const CollatorKickThreshold: Permill = Permill::from_percent(100);
+	pub const LPBondCurrencyId: CurrencyId = LP_ACA_AUSD;
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const LPBondHaircut: Permill = Permill::from_percent(50);
+	pub const MaxLPBondProportion: Permill = Permill::from_percent(50);
 }
 
 impl Config for Test {
@@ -211,6 +254,13 @@ impl Config for Test {
 	type MaxInvulnerables = MaxInvulnerables;
 	type KickPenaltySessionLength = KickPenaltySessionLength;
 	type CollatorKickThreshold = CollatorKickThreshold;
+	type MultiCurrency = Tokens;
+	type LPBondCurrencyId = LPBondCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type LPBondHaircut = LPBondHaircut;
+	type MaxLPBondProportion = MaxLPBondProportion;
+	type PriceSource = MockPriceSource;
+	type DEX = ();
 	type WeightInfo = ();
 }
 
@@ -234,6 +284,9 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	let balances = pallet_balances::GenesisConfig::<Test> {
 		balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (33, 5)],
 	};
+	let tokens = orml_tokens::GenesisConfig::<Test> {
+		balances: vec![(3, LP_ACA_AUSD, 100), (4, LP_ACA_AUSD, 100)],
+	};
 	let collator_selection = collator_selection::GenesisConfig::<Test> {
 		desired_candidates: 2,
 		candidacy_bond: 10,
@@ -241,6 +294,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	};
 	let session = pallet_session::GenesisConfig::<Test> { keys };
 	balances.assimilate_storage(&mut t).unwrap();
+	tokens.assimilate_storage(&mut t).unwrap();
 	// collator selection must be initialized before session.
 	collator_selection.assimilate_storage(&mut t).unwrap();
 	session.assimilate_storage(&mut t).unwrap();
@@ -17,14 +17,15 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate as collator_selection;
-use crate::{mock::*, Error, NonCandidates, RESERVE_ID};
+use crate::{mock::*, Error, Event as CollatorSelectionEvent, NonCandidates, RESERVE_ID};
 use frame_support::{
 	assert_noop, assert_ok,
 	storage::bounded_btree_set::BoundedBTreeSet,
 	traits::{Currency, GenesisBuild, NamedReservableCurrency, OnInitialize},
 };
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
 use pallet_balances::Error as BalancesError;
-use sp_runtime::{testing::UintAuthorityId, traits::BadOrigin};
+use sp_runtime::{testing::UintAuthorityId, traits::BadOrigin, Permill};
 
 #[test]
 fn basic_setup_works() {
@@ -237,6 +238,82 @@ fn register_as_candidate_works() {
 	});
 }
 
+#[test]
+fn register_as_candidate_with_lp_bond_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Session::set_keys(
+			Origin::signed(3),
+			MockSessionKeys {
+				aura: UintAuthorityId(3)
+			},
+			vec![]
+		));
+		assert_ok!(Session::set_keys(
+			Origin::signed(4),
+			MockSessionKeys {
+				aura: UintAuthorityId(4)
+			},
+			vec![]
+		));
+
+		assert_noop!(
+			CollatorSelection::register_as_candidate_with_lp_bond(Origin::signed(3), 0),
+			Error::<Test>::InvalidLPBond
+		);
+		// the value of 6 LP shares after haircut is 6, exceeds half of the candidacy bond.
+		assert_eq!(CollatorSelection::lp_bond_value(6), Some(6));
+		assert_noop!(
+			CollatorSelection::register_as_candidate_with_lp_bond(Origin::signed(3), 6),
+			Error::<Test>::ExceedMaxLPBond
+		);
+
+		assert_ok!(CollatorSelection::register_as_candidate_with_lp_bond(
+			Origin::signed(3),
+			5
+		));
+		System::assert_last_event(Event::CollatorSelection(
+			CollatorSelectionEvent::CandidateAddedWithLPBond(3, 5, 5),
+		));
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
+		assert_eq!(CollatorSelection::candidates().len(), 2);
+		assert_eq!(Balances::free_balance(3), 95);
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &3), 5);
+		assert_eq!(Tokens::reserved_balance(LP_ACA_AUSD, &3), 5);
+		assert_eq!(CollatorSelection::lp_bonds(3), 5);
+
+		// slash 40% of the bond, the slashed LP shares are liquidated by the Pot.
+		let pot = CollatorSelection::account_id();
+		Balances::make_free_balance_be(&pot, 5);
+		assert_noop!(
+			CollatorSelection::slash_candidate(Origin::signed(3), 3, Permill::from_percent(40)),
+			BadOrigin
+		);
+		assert_ok!(CollatorSelection::slash_candidate(
+			Origin::signed(RootAccount::get()),
+			3,
+			Permill::from_percent(40)
+		));
+		System::assert_last_event(Event::CollatorSelection(CollatorSelectionEvent::CandidateSlashed(
+			3, 2, 2,
+		)));
+		assert_eq!(Balances::reserved_balance_named(&RESERVE_ID, &3), 3);
+		assert_eq!(Balances::free_balance(pot), 7);
+		assert_eq!(Tokens::reserved_balance(LP_ACA_AUSD, &3), 3);
+		assert_eq!(Tokens::free_balance(LP_ACA_AUSD, &pot), 2);
+		assert_eq!(CollatorSelection::lp_bonds(3), 3);
+
+		// LP shares bond is returned with the bond.
+		assert_ok!(CollatorSelection::leave_intent(Origin::signed(3)));
+		initialize_to_block(2 * Period::get());
+		assert_ok!(CollatorSelection::withdraw_bond(Origin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 98);
+		assert_eq!(Tokens::reserved_balance(LP_ACA_AUSD, &3), 0);
+		assert_eq!(Tokens::free_balance(LP_ACA_AUSD, &3), 98);
+		assert_eq!(CollatorSelection::lp_bonds(3), 0);
+	});
+}
+
 #[test]
 fn register_candidate_works() {
 	new_test_ext().execute_with(|| {
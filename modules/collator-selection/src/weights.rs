@@ -58,6 +58,8 @@ pub trait WeightInfo {
 	fn new_session() -> Weight;
 	fn start_session(r: u32, c: u32, ) -> Weight;
 	fn end_session(r: u32, c: u32, ) -> Weight;
+	fn register_as_candidate_with_lp_bond(c: u32, ) -> Weight;
+	fn slash_candidate() -> Weight;
 }
 
 /// Weights for module_collator_selection using the Acala node and recommended hardware.
@@ -130,6 +132,17 @@ impl<T: frame_system::Config> WeightInfo for AcalaWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(199 as Weight))
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
 	}
+	fn register_as_candidate_with_lp_bond(c: u32, ) -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add((295_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn slash_candidate() -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -201,4 +214,15 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(199 as Weight))
 			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
 	}
+	fn register_as_candidate_with_lp_bond(c: u32, ) -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add((295_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn slash_candidate() -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
 }
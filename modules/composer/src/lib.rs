@@ -0,0 +1,236 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Composer Module
+//!
+//! ## Overview
+//!
+//! Composer module executes a sequence of whitelisted DeFi steps, such as
+//! swap, add liquidity, stake dex share to incentives and deposit collateral
+//! to CDP, atomically in one extrinsic. The output amount of a step can be
+//! piped as the input amount of the next step, so callers don't have to
+//! simulate the intermediate amounts.
+//!
+//! If any step fails, the whole sequence is reverted.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Amount, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, vec::Vec};
+use support::{DEXIncentives, DEXManager, EmergencyShutdown};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The input amount of a step
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum StepAmount {
+	/// Use the exact amount
+	Exact(Balance),
+	/// Use the output amount of the previous step
+	Piped,
+}
+
+/// The whitelisted DeFi steps
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum Step {
+	/// Swap with exact supply amount by DEX, outputs the target amount.
+	Swap {
+		path: Vec<CurrencyId>,
+		supply_amount: StepAmount,
+		min_target_amount: Balance,
+	},
+	/// Add liquidity to DEX, outputs the increased dex share amount.
+	AddLiquidity {
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		max_amount_a: StepAmount,
+		max_amount_b: Balance,
+		min_share_increment: Balance,
+	},
+	/// Stake dex share to incentives, outputs the staked amount.
+	StakeDexShare {
+		lp_currency_id: CurrencyId,
+		amount: StepAmount,
+	},
+	/// Deposit collateral to CDP, outputs the deposited amount.
+	DepositCollateral {
+		currency_id: CurrencyId,
+		amount: StepAmount,
+	},
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + cdp_engine::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to check the dex share increment
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// DEX to swap and add liquidity
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// Incentives to stake dex share
+		type DEXIncentives: DEXIncentives<Self::AccountId, CurrencyId, Balance>;
+
+		/// The maximum number of steps in a composition
+		#[pallet::constant]
+		type MaxSteps: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// No steps to execute
+		NoSteps,
+		/// Exceed the maximum number of steps
+		TooManySteps,
+		/// The first step can not use the piped amount
+		NoPipedAmount,
+		/// The currency is not a dex share
+		InvalidDexShare,
+		/// The system has been shutdown
+		AlreadyShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The steps are executed. \[who, steps_count, output_amount\]
+		Composed(T::AccountId, u32, Balance),
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Execute the steps atomically, the output amount of each step can be
+		/// piped into the next step.
+		///
+		/// - `steps`: the steps to execute in order.
+		#[pallet::weight(Pallet::<T>::steps_weight(steps))]
+		#[transactional]
+		pub fn compose(origin: OriginFor<T>, steps: Vec<Step>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!steps.is_empty(), Error::<T>::NoSteps);
+			ensure!(steps.len() as u32 <= T::MaxSteps::get(), Error::<T>::TooManySteps);
+
+			let mut output: Option<Balance> = None;
+			for step in steps.iter() {
+				output = Some(Self::do_step(&who, step, output)?);
+			}
+
+			Self::deposit_event(Event::Composed(who, steps.len() as u32, output.unwrap_or_default()));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn steps_weight(steps: &[Step]) -> Weight {
+		steps.iter().fold(Zero::zero(), |weight: Weight, step| {
+			weight.saturating_add(match step {
+				Step::Swap { path, .. } => <T as Config>::WeightInfo::swap(path.len() as u32),
+				Step::AddLiquidity { .. } => <T as Config>::WeightInfo::add_liquidity(),
+				Step::StakeDexShare { .. } => <T as Config>::WeightInfo::stake_dex_share(),
+				Step::DepositCollateral { .. } => <T as Config>::WeightInfo::deposit_collateral(),
+			})
+		})
+	}
+
+	fn input_amount(amount: StepAmount, piped: Option<Balance>) -> Result<Balance, DispatchError> {
+		match amount {
+			StepAmount::Exact(amount) => Ok(amount),
+			StepAmount::Piped => piped.ok_or_else(|| Error::<T>::NoPipedAmount.into()),
+		}
+	}
+
+	fn do_step(who: &T::AccountId, step: &Step, piped: Option<Balance>) -> Result<Balance, DispatchError> {
+		match step {
+			Step::Swap {
+				path,
+				supply_amount,
+				min_target_amount,
+			} => {
+				let supply_amount = Self::input_amount(*supply_amount, piped)?;
+				<T as Config>::DEX::swap_with_exact_supply(who, path, supply_amount, *min_target_amount)
+			}
+			Step::AddLiquidity {
+				currency_id_a,
+				currency_id_b,
+				max_amount_a,
+				max_amount_b,
+				min_share_increment,
+			} => {
+				let max_amount_a = Self::input_amount(*max_amount_a, piped)?;
+				let lp_currency_id = CurrencyId::join_dex_share_currency_id(*currency_id_a, *currency_id_b)
+					.ok_or(Error::<T>::InvalidDexShare)?;
+				let share_before = <T as Config>::Currency::free_balance(lp_currency_id, who);
+				<T as Config>::DEX::add_liquidity(
+					who,
+					*currency_id_a,
+					*currency_id_b,
+					max_amount_a,
+					*max_amount_b,
+					*min_share_increment,
+					false,
+				)?;
+				Ok(<T as Config>::Currency::free_balance(lp_currency_id, who).saturating_sub(share_before))
+			}
+			Step::StakeDexShare { lp_currency_id, amount } => {
+				ensure!(lp_currency_id.is_dex_share_currency_id(), Error::<T>::InvalidDexShare);
+				let amount = Self::input_amount(*amount, piped)?;
+				<T as Config>::DEXIncentives::do_deposit_dex_share(who, *lp_currency_id, amount)?;
+				Ok(amount)
+			}
+			Step::DepositCollateral { currency_id, amount } => {
+				ensure!(
+					!<T as cdp_engine::Config>::EmergencyShutdown::is_shutdown(),
+					Error::<T>::AlreadyShutdown
+				);
+				let amount = Self::input_amount(*amount, piped)?;
+				let collateral_adjustment: Amount = amount.try_into().map_err(|_| ArithmeticError::Overflow)?;
+				<cdp_engine::Pallet<T>>::adjust_position(who, *currency_id, collateral_adjustment, Zero::zero())?;
+				Ok(amount)
+			}
+		}
+	}
+}
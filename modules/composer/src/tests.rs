@@ -0,0 +1,173 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the composer module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiReservableCurrency;
+
+fn ausd_btc_share() -> CurrencyId {
+	CurrencyId::join_dex_share_currency_id(AUSD, BTC).unwrap()
+}
+
+#[test]
+fn compose_swap_add_liquidity_and_stake_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(BOB),
+			AUSD,
+			BTC,
+			1000,
+			1000,
+			0,
+			false
+		));
+
+		assert_ok!(ComposerModule::compose(
+			Origin::signed(ALICE),
+			vec![
+				Step::Swap {
+					path: vec![AUSD, BTC],
+					supply_amount: StepAmount::Exact(100),
+					min_target_amount: 0,
+				},
+				Step::AddLiquidity {
+					currency_id_a: BTC,
+					currency_id_b: AUSD,
+					max_amount_a: StepAmount::Piped,
+					max_amount_b: 1000,
+					min_share_increment: 0,
+				},
+				Step::StakeDexShare {
+					lp_currency_id: ausd_btc_share(),
+					amount: StepAmount::Piped,
+				},
+			]
+		));
+		System::assert_last_event(Event::ComposerModule(crate::Event::Composed(ALICE, 3, 196)));
+
+		// the swapped 90 BTC are all added to the pool with 108 AUSD
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 9792);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 10000);
+		assert_eq!(Tokens::free_balance(ausd_btc_share(), &ALICE), 0);
+		assert_eq!(Tokens::reserved_balance(ausd_btc_share(), &ALICE), 196);
+	});
+}
+
+#[test]
+fn compose_swap_and_deposit_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(BOB),
+			AUSD,
+			BTC,
+			1000,
+			1000,
+			0,
+			false
+		));
+
+		assert_ok!(ComposerModule::compose(
+			Origin::signed(ALICE),
+			vec![
+				Step::Swap {
+					path: vec![AUSD, BTC],
+					supply_amount: StepAmount::Exact(100),
+					min_target_amount: 0,
+				},
+				Step::DepositCollateral {
+					currency_id: BTC,
+					amount: StepAmount::Piped,
+				},
+			]
+		));
+		System::assert_last_event(Event::ComposerModule(crate::Event::Composed(ALICE, 2, 90)));
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 10000);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 90);
+	});
+}
+
+#[test]
+fn compose_fails_atomically() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DEXModule::add_liquidity(
+			Origin::signed(BOB),
+			AUSD,
+			BTC,
+			1000,
+			1000,
+			0,
+			false
+		));
+		let swap = Step::Swap {
+			path: vec![AUSD, BTC],
+			supply_amount: StepAmount::Exact(100),
+			min_target_amount: 0,
+		};
+
+		assert_noop!(
+			ComposerModule::compose(Origin::signed(ALICE), vec![]),
+			Error::<Runtime>::NoSteps
+		);
+		assert_noop!(
+			ComposerModule::compose(Origin::signed(ALICE), vec![swap.clone(); 4]),
+			Error::<Runtime>::TooManySteps
+		);
+		assert_noop!(
+			ComposerModule::compose(
+				Origin::signed(ALICE),
+				vec![Step::DepositCollateral {
+					currency_id: BTC,
+					amount: StepAmount::Piped,
+				}]
+			),
+			Error::<Runtime>::NoPipedAmount
+		);
+		assert_noop!(
+			ComposerModule::compose(
+				Origin::signed(ALICE),
+				vec![
+					swap,
+					Step::StakeDexShare {
+						lp_currency_id: BTC,
+						amount: StepAmount::Piped,
+					},
+				]
+			),
+			Error::<Runtime>::InvalidDexShare
+		);
+
+		mock_shutdown();
+		assert_noop!(
+			ComposerModule::compose(
+				Origin::signed(ALICE),
+				vec![Step::DepositCollateral {
+					currency_id: BTC,
+					amount: StepAmount::Exact(100),
+				}]
+			),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
@@ -50,7 +50,7 @@ use sp_std::{
 	marker, result,
 	vec::Vec,
 };
-use support::{AddressMapping, EVMBridge, InvokeContext};
+use support::{AddressMapping, EVMBridge, InvokeContext, OnTransfer};
 
 mod mock;
 mod tests;
@@ -97,6 +97,9 @@ pub mod module {
 
 		/// Handler to burn or transfer account's dust
 		type OnDust: OnDust<Self::AccountId, CurrencyId, BalanceOf<Self>>;
+
+		/// Handler for the successful transfers
+		type OnTransfer: OnTransfer<Self::AccountId, CurrencyId, BalanceOf<Self>>;
 	}
 
 	#[pallet::error]
@@ -142,7 +145,9 @@ pub mod module {
 		///
 		/// The dispatch origin for this call must be `Signed` by the
 		/// transactor.
-		#[pallet::weight(T::WeightInfo::transfer_non_native_currency())]
+		#[pallet::weight(
+			T::WeightInfo::transfer_non_native_currency().saturating_add(T::OnTransfer::on_transfer_weight())
+		)]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			dest: <T::Lookup as StaticLookup>::Source,
@@ -330,6 +335,7 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
 			_ => T::MultiCurrency::transfer(currency_id, from, to, amount)?,
 		}
 
+		T::OnTransfer::on_transfer(currency_id, from, to, amount);
 		Self::deposit_event(Event::Transferred(currency_id, from.clone(), to.clone(), amount));
 		Ok(())
 	}
@@ -193,6 +193,7 @@ impl Config for Runtime {
 	type EVMBridge = EVMBridge;
 	type SweepOrigin = EnsureSignedBy<CouncilAccount, AccountId>;
 	type OnDust = crate::TransferDust<Runtime, DustAccount>;
+	type OnTransfer = ();
 }
 
 pub type NativeCurrency = Currency<Runtime, GetNativeCurrencyId>;
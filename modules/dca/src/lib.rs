@@ -0,0 +1,465 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # DCA Module
+//!
+//! ## Overview
+//!
+//! DCA (dollar-cost averaging) module lets users create recurring swap
+//! orders, which swap a fixed amount of the supply currency to the target
+//! currency through DEX at every interval until the budget runs out. The
+//! budget and the execution fees of all the executions are escrowed into the
+//! module account when the order is created. The due orders are executed in
+//! `on_idle` with the remaining block weight, the swap of each execution is
+//! protected by the max slippage against the oracle price, and a failed
+//! execution is retried at the next interval. The order can be cancelled by
+//! its owner at any time, and the remaining budget and the unused execution
+//! fees are refunded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{DEXManager, PriceProvider, Ratio};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type OrderId = u64;
+
+/// The recurring swap order.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct DcaOrder<AccountId, BlockNumber> {
+	/// The owner of the order.
+	pub owner: AccountId,
+	/// The currency to supply.
+	pub supply_currency_id: CurrencyId,
+	/// The currency to receive.
+	pub target_currency_id: CurrencyId,
+	/// The supply amount of each execution.
+	pub amount_per_execution: Balance,
+	/// The blocks between two executions.
+	pub interval: BlockNumber,
+	/// The budget of supply currency escrowed and not swapped yet.
+	pub remaining_budget: Balance,
+	/// The max slippage of the swap against the oracle price.
+	pub max_slippage: Ratio,
+	/// The block from which the next execution is due.
+	pub next_execution: BlockNumber,
+	/// The execution fees in native currency escrowed and not paid yet.
+	pub prepaid_fee: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to escrow the budget and the execution fees
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// DEX to swap the supply currency
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The price source to limit the slippage of swaps
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// Native currency id, the execution fees are paid in
+		#[pallet::constant]
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		/// The fee in native currency of each execution, prepaid when the
+		/// order is created
+		#[pallet::constant]
+		type ExecutionFee: Get<Balance>;
+
+		/// The account to receive the execution fees
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The minimum blocks between two executions of an order
+		#[pallet::constant]
+		type MinExecutionInterval: Get<Self::BlockNumber>;
+
+		/// The maximum orders an account can have
+		#[pallet::constant]
+		type MaxOrdersPerAccount: Get<u32>;
+
+		/// The maximum executions in a block
+		#[pallet::constant]
+		type MaxExecutionsPerBlock: Get<u32>;
+
+		/// The minimum weight that should remain before the orders are
+		/// executed in `on_idle`
+		#[pallet::constant]
+		type MinimumWeightRemainInBlock: Get<Weight>;
+
+		/// The DCA module id, keep the budgets and the execution fees
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The supply currency and the target currency are the same
+		InvalidCurrencyPair,
+		/// The amount per execution is zero or the budget is less than it
+		InvalidAmount,
+		/// The interval is less than the minimum execution interval
+		IntervalTooShort,
+		/// The max slippage is greater than one
+		InvalidSlippage,
+		/// The account has reached the maximum orders
+		TooManyOrders,
+		/// The order does not exist
+		OrderNotFound,
+		/// The caller is not the owner of the order
+		NoPermission,
+		/// The oracle price is not available
+		InvalidFeedPrice,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An order is created. \[order_id, owner, supply_currency_id,
+		/// target_currency_id, amount_per_execution, budget\]
+		OrderCreated(OrderId, T::AccountId, CurrencyId, CurrencyId, Balance, Balance),
+		/// An order is executed. \[order_id, owner, supply_amount,
+		/// target_amount\]
+		OrderExecuted(OrderId, T::AccountId, Balance, Balance),
+		/// An execution of an order failed and is retried at the next
+		/// interval. \[order_id, error\]
+		OrderExecutionFailed(OrderId, DispatchError),
+		/// An order has spent all of the budget. \[order_id, owner\]
+		OrderCompleted(OrderId, T::AccountId),
+		/// An order is cancelled. \[order_id, owner, refunded_budget,
+		/// refunded_fee\]
+		OrderCancelled(OrderId, T::AccountId, Balance, Balance),
+	}
+
+	/// The id of the next order.
+	///
+	/// NextOrderId: OrderId
+	#[pallet::storage]
+	#[pallet::getter(fn next_order_id)]
+	pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+	/// The recurring swap orders.
+	///
+	/// Orders: map OrderId => Option<DcaOrder>
+	#[pallet::storage]
+	#[pallet::getter(fn orders)]
+	pub type Orders<T: Config> =
+		StorageMap<_, Twox64Concat, OrderId, DcaOrder<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// The count of the orders of the accounts.
+	///
+	/// OrderCount: map AccountId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn order_count)]
+	pub type OrderCount<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The raw key of the last order scanned in `on_idle`, the next scan
+	/// continues after it.
+	///
+	/// IterationStartKey: Option<Vec<u8>>
+	#[pallet::storage]
+	pub type IterationStartKey<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Execute the due orders with the remaining weight of the block.
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::execute_due_orders(now, remaining_weight)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a recurring swap order, escrow the budget and prepay the
+		/// execution fees. The first execution is due immediately.
+		///
+		/// - `supply_currency_id`: the currency to supply.
+		/// - `target_currency_id`: the currency to receive.
+		/// - `amount_per_execution`: the supply amount of each execution.
+		/// - `interval`: the blocks between two executions.
+		/// - `budget`: the total supply amount of all the executions.
+		/// - `max_slippage`: the max slippage of each swap against the oracle
+		///   price.
+		#[pallet::weight(<T as Config>::WeightInfo::create_order())]
+		#[transactional]
+		pub fn create_order(
+			origin: OriginFor<T>,
+			supply_currency_id: CurrencyId,
+			target_currency_id: CurrencyId,
+			#[pallet::compact] amount_per_execution: Balance,
+			interval: T::BlockNumber,
+			#[pallet::compact] budget: Balance,
+			max_slippage: Ratio,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				supply_currency_id != target_currency_id,
+				Error::<T>::InvalidCurrencyPair
+			);
+			ensure!(
+				!amount_per_execution.is_zero() && budget >= amount_per_execution,
+				Error::<T>::InvalidAmount
+			);
+			ensure!(
+				interval >= T::MinExecutionInterval::get() && !interval.is_zero(),
+				Error::<T>::IntervalTooShort
+			);
+			ensure!(max_slippage <= Ratio::one(), Error::<T>::InvalidSlippage);
+
+			OrderCount::<T>::try_mutate(&who, |count| -> DispatchResult {
+				ensure!(*count < T::MaxOrdersPerAccount::get(), Error::<T>::TooManyOrders);
+				*count = count.saturating_add(1);
+				Ok(())
+			})?;
+
+			// prepay the fees of all the executions, the last execution may supply less
+			// than the amount per execution
+			let executions =
+				budget.saturating_add(amount_per_execution.saturating_sub(One::one())) / amount_per_execution;
+			let prepaid_fee = T::ExecutionFee::get().saturating_mul(executions);
+
+			let module_account = Self::account_id();
+			T::Currency::transfer(supply_currency_id, &who, &module_account, budget)?;
+			T::Currency::transfer(T::GetNativeCurrencyId::get(), &who, &module_account, prepaid_fee)?;
+
+			let order_id = NextOrderId::<T>::try_mutate(|id| -> Result<OrderId, DispatchError> {
+				let current_id = *id;
+				*id = id.checked_add(One::one()).ok_or(ArithmeticError::Overflow)?;
+				Ok(current_id)
+			})?;
+			Orders::<T>::insert(
+				order_id,
+				DcaOrder {
+					owner: who.clone(),
+					supply_currency_id,
+					target_currency_id,
+					amount_per_execution,
+					interval,
+					remaining_budget: budget,
+					max_slippage,
+					next_execution: <frame_system::Pallet<T>>::block_number(),
+					prepaid_fee,
+				},
+			);
+
+			Self::deposit_event(Event::OrderCreated(
+				order_id,
+				who,
+				supply_currency_id,
+				target_currency_id,
+				amount_per_execution,
+				budget,
+			));
+			Ok(())
+		}
+
+		/// Cancel the order, refund the remaining budget and the unused
+		/// execution fees.
+		///
+		/// The dispatch origin of this call must be the owner of the order.
+		///
+		/// - `order_id`: the order to cancel.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
+		#[transactional]
+		pub fn cancel_order(origin: OriginFor<T>, order_id: OrderId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let order = Self::orders(order_id).ok_or(Error::<T>::OrderNotFound)?;
+			ensure!(order.owner == who, Error::<T>::NoPermission);
+
+			Self::close_order(order_id, &order)?;
+			Self::deposit_event(Event::OrderCancelled(
+				order_id,
+				who,
+				order.remaining_budget,
+				order.prepaid_fee,
+			));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of DCA module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Refund the remaining budget and the unused execution fees of the
+	/// order to its owner, and remove the order.
+	fn close_order(order_id: OrderId, order: &DcaOrder<T::AccountId, T::BlockNumber>) -> DispatchResult {
+		let module_account = Self::account_id();
+		T::Currency::transfer(
+			order.supply_currency_id,
+			&module_account,
+			&order.owner,
+			order.remaining_budget,
+		)?;
+		T::Currency::transfer(
+			T::GetNativeCurrencyId::get(),
+			&module_account,
+			&order.owner,
+			order.prepaid_fee,
+		)?;
+
+		Orders::<T>::remove(order_id);
+		OrderCount::<T>::mutate_exists(&order.owner, |maybe_count| {
+			let count = maybe_count.unwrap_or_default().saturating_sub(1);
+			*maybe_count = if count.is_zero() { None } else { Some(count) };
+		});
+		Ok(())
+	}
+
+	/// Scan the orders from the last scanned one and execute the due orders,
+	/// until the remaining weight or the max executions of the block is
+	/// reached. Return the weight consumed.
+	pub fn execute_due_orders(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+		let mut weight_used = T::WeightInfo::on_idle();
+		if remaining_weight <= T::MinimumWeightRemainInBlock::get().saturating_add(weight_used) {
+			return Zero::zero();
+		}
+		let available_weight = remaining_weight.saturating_sub(T::MinimumWeightRemainInBlock::get());
+		let scan_weight = T::DbWeight::get().reads(1);
+		let execute_weight = T::WeightInfo::execute_order();
+
+		let iterator = match IterationStartKey::<T>::get() {
+			Some(key) => Orders::<T>::iter_from(key),
+			None => Orders::<T>::iter(),
+		};
+
+		// collect the due orders first, the orders are mutated by the executions
+		let mut due_orders: Vec<(OrderId, DcaOrder<T::AccountId, T::BlockNumber>)> = vec![];
+		let mut last_scanned = None;
+		let mut finished = true;
+		for (order_id, order) in iterator {
+			weight_used = weight_used.saturating_add(scan_weight);
+			if order.next_execution <= now {
+				weight_used = weight_used.saturating_add(execute_weight);
+				due_orders.push((order_id, order));
+			}
+			last_scanned = Some(order_id);
+
+			if due_orders.len() as u32 >= T::MaxExecutionsPerBlock::get()
+				|| weight_used.saturating_add(scan_weight).saturating_add(execute_weight) > available_weight
+			{
+				finished = false;
+				break;
+			}
+		}
+
+		match (finished, last_scanned) {
+			(false, Some(order_id)) => IterationStartKey::<T>::put(Orders::<T>::hashed_key_for(order_id)),
+			_ => IterationStartKey::<T>::kill(),
+		}
+
+		for (order_id, order) in due_orders {
+			Self::execute_order(now, order_id, order);
+		}
+
+		weight_used
+	}
+
+	/// Execute the order, and reschedule it at the next interval or complete
+	/// it if the budget is spent.
+	fn execute_order(now: T::BlockNumber, order_id: OrderId, mut order: DcaOrder<T::AccountId, T::BlockNumber>) {
+		let supply_amount = order.amount_per_execution.min(order.remaining_budget);
+		let fee = T::ExecutionFee::get().min(order.prepaid_fee);
+
+		match Self::swap_and_pay_fee(&order, supply_amount, fee) {
+			Ok(target_amount) => {
+				order.remaining_budget = order.remaining_budget.saturating_sub(supply_amount);
+				order.prepaid_fee = order.prepaid_fee.saturating_sub(fee);
+				Self::deposit_event(Event::OrderExecuted(
+					order_id,
+					order.owner.clone(),
+					supply_amount,
+					target_amount,
+				));
+			}
+			Err(e) => {
+				Self::deposit_event(Event::OrderExecutionFailed(order_id, e));
+			}
+		}
+
+		if order.remaining_budget.is_zero() && Self::close_order(order_id, &order).is_ok() {
+			Self::deposit_event(Event::OrderCompleted(order_id, order.owner));
+			return;
+		}
+
+		order.next_execution = now.saturating_add(order.interval);
+		Orders::<T>::insert(order_id, order);
+	}
+
+	/// Swap the supply amount of the order through DEX with the slippage
+	/// limit, transfer the target currency to the owner and pay the execution
+	/// fee. Return the target amount.
+	#[transactional]
+	fn swap_and_pay_fee(
+		order: &DcaOrder<T::AccountId, T::BlockNumber>,
+		supply_amount: Balance,
+		fee: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let min_target_amount = T::PriceSource::get_relative_price(order.supply_currency_id, order.target_currency_id)
+			.ok_or(Error::<T>::InvalidFeedPrice)?
+			.saturating_mul(Ratio::one().saturating_sub(order.max_slippage))
+			.saturating_mul_int(supply_amount);
+
+		let module_account = Self::account_id();
+		let target_amount = T::DEX::swap_with_exact_supply(
+			&module_account,
+			&[order.supply_currency_id, order.target_currency_id],
+			supply_amount,
+			min_target_amount,
+		)?;
+		T::Currency::transfer(order.target_currency_id, &module_account, &order.owner, target_amount)?;
+		T::Currency::transfer(
+			T::GetNativeCurrencyId::get(),
+			&module_account,
+			&T::TreasuryAccount::get(),
+			fee,
+		)?;
+		Ok(target_amount)
+	}
+}
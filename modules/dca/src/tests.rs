@@ -0,0 +1,274 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the DCA module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use support::Price;
+
+const LARGE_WEIGHT: Weight = 10_000_000_000;
+
+fn slippage() -> Ratio {
+	Ratio::saturating_from_rational(5, 100)
+}
+
+#[test]
+fn create_order_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			DcaModule::create_order(Origin::signed(ALICE), AUSD, AUSD, 100, 10, 250, slippage()),
+			Error::<Runtime>::InvalidCurrencyPair
+		);
+		assert_noop!(
+			DcaModule::create_order(Origin::signed(ALICE), AUSD, DOT, 0, 10, 250, slippage()),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			DcaModule::create_order(Origin::signed(ALICE), AUSD, DOT, 100, 10, 99, slippage()),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			DcaModule::create_order(Origin::signed(ALICE), AUSD, DOT, 100, 9, 250, slippage()),
+			Error::<Runtime>::IntervalTooShort
+		);
+		assert_noop!(
+			DcaModule::create_order(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				100,
+				10,
+				250,
+				Ratio::saturating_from_rational(101, 100)
+			),
+			Error::<Runtime>::InvalidSlippage
+		);
+
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderCreated(
+			0, ALICE, AUSD, DOT, 100, 250,
+		)));
+		assert_eq!(
+			DcaModule::orders(0),
+			Some(DcaOrder {
+				owner: ALICE,
+				supply_currency_id: AUSD,
+				target_currency_id: DOT,
+				amount_per_execution: 100,
+				interval: 10,
+				remaining_budget: 250,
+				max_slippage: slippage(),
+				next_execution: 1,
+				prepaid_fee: 6,
+			})
+		);
+		assert_eq!(DcaModule::next_order_id(), 1);
+		assert_eq!(DcaModule::order_count(ALICE), 1);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 750);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 94);
+		assert_eq!(Tokens::free_balance(AUSD, &DcaModule::account_id()), 250);
+		assert_eq!(Tokens::free_balance(ACA, &DcaModule::account_id()), 6);
+
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			100,
+			slippage()
+		));
+		assert_noop!(
+			DcaModule::create_order(Origin::signed(ALICE), AUSD, DOT, 100, 10, 100, slippage()),
+			Error::<Runtime>::TooManyOrders
+		);
+	});
+}
+
+#[test]
+fn cancel_order_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+
+		assert_noop!(
+			DcaModule::cancel_order(Origin::signed(ALICE), 1),
+			Error::<Runtime>::OrderNotFound
+		);
+		assert_noop!(
+			DcaModule::cancel_order(Origin::signed(BOB), 0),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(DcaModule::cancel_order(Origin::signed(ALICE), 0));
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderCancelled(0, ALICE, 250, 6)));
+		assert_eq!(DcaModule::orders(0), None);
+		assert_eq!(DcaModule::order_count(ALICE), 0);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1000);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 100);
+		assert_eq!(Tokens::free_balance(AUSD, &DcaModule::account_id()), 0);
+		assert_eq!(Tokens::free_balance(ACA, &DcaModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn execute_due_orders_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+
+		DcaModule::execute_due_orders(1, LARGE_WEIGHT);
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderExecuted(0, ALICE, 100, 10)));
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 10);
+		assert_eq!(Tokens::free_balance(ACA, &TREASURY), 2);
+		let order = DcaModule::orders(0).unwrap();
+		assert_eq!(order.remaining_budget, 150);
+		assert_eq!(order.prepaid_fee, 4);
+		assert_eq!(order.next_execution, 11);
+
+		// not due yet
+		DcaModule::execute_due_orders(10, LARGE_WEIGHT);
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 10);
+
+		DcaModule::execute_due_orders(11, LARGE_WEIGHT);
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 20);
+		assert_eq!(DcaModule::orders(0).unwrap().remaining_budget, 50);
+
+		// the last execution spends the rest of the budget and completes the order
+		DcaModule::execute_due_orders(21, LARGE_WEIGHT);
+		System::assert_has_event(Event::DcaModule(crate::Event::OrderExecuted(0, ALICE, 50, 5)));
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderCompleted(0, ALICE)));
+		assert_eq!(DcaModule::orders(0), None);
+		assert_eq!(DcaModule::order_count(ALICE), 0);
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 25);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 750);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 94);
+		assert_eq!(Tokens::free_balance(ACA, &TREASURY), 6);
+		assert_eq!(Tokens::free_balance(AUSD, &DcaModule::account_id()), 0);
+		assert_eq!(Tokens::free_balance(ACA, &DcaModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn failed_execution_is_retried_at_next_interval() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+
+		set_swap_rate(Price::saturating_from_rational(8, 100));
+		DcaModule::execute_due_orders(1, LARGE_WEIGHT);
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderExecutionFailed(
+			0,
+			DispatchError::Other("slippage"),
+		)));
+		let order = DcaModule::orders(0).unwrap();
+		assert_eq!(order.remaining_budget, 250);
+		assert_eq!(order.prepaid_fee, 6);
+		assert_eq!(order.next_execution, 11);
+		assert_eq!(Tokens::free_balance(DOT, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &TREASURY), 0);
+
+		set_dot_price(None);
+		DcaModule::execute_due_orders(11, LARGE_WEIGHT);
+		System::assert_last_event(Event::DcaModule(crate::Event::OrderExecutionFailed(
+			0,
+			Error::<Runtime>::InvalidFeedPrice.into(),
+		)));
+		assert_eq!(DcaModule::orders(0).unwrap().next_execution, 21);
+	});
+}
+
+#[test]
+fn execute_due_orders_resume_from_last_scanned_order() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+		assert_ok!(DcaModule::create_order(
+			Origin::signed(BOB),
+			AUSD,
+			DOT,
+			100,
+			10,
+			250,
+			slippage()
+		));
+
+		// at most 2 executions in a block
+		DcaModule::execute_due_orders(1, LARGE_WEIGHT);
+		assert!(IterationStartKey::<Runtime>::get().is_some());
+		assert_eq!(
+			(0..3u64)
+				.filter(|id| DcaModule::orders(id).unwrap().next_execution == 11)
+				.count(),
+			2
+		);
+
+		DcaModule::execute_due_orders(1, LARGE_WEIGHT);
+		assert_eq!(IterationStartKey::<Runtime>::get(), None);
+		assert!((0..3u64).all(|id| DcaModule::orders(id).unwrap().next_execution == 11));
+	});
+}
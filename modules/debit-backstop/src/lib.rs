@@ -0,0 +1,407 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Debit Backstop Module
+//!
+//! ## Overview
+//!
+//! Debit backstop module is the last resort to cover the bad debit of the
+//! system. Backstop providers pre-commit stable currency to the module. When
+//! the debit pool of CDP treasury stays uncovered by the surplus pool for
+//! `BackstopWindow` blocks, the committed stable currency is drawn
+//! automatically to cover the uncovered debit, and the providers are paid
+//! with newly issued native currency at the price of `PriceSource`.
+//!
+//! Providers earn native currency rewards for standing ready, which accrue
+//! per block at `RewardRate` of the committed amount. The rewards are paid
+//! from the treasury account within the reward budget set by governance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{CDPTreasury, Price, PriceProvider, Rate};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The commitment of a backstop provider
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct Commitment<BlockNumber> {
+	/// The committed amount of stable currency
+	pub amount: Balance,
+	/// The block number up to which the rewards have been paid
+	pub rewarded_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to take the commitments and issue the native currency
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// Native currency id
+		#[pallet::constant]
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		/// CDP treasury to check the debit pool and receive the drawn stable
+		/// currency
+		type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The number of blocks the debit pool stays uncovered before the
+		/// backstop is drawn
+		#[pallet::constant]
+		type BackstopWindow: Get<Self::BlockNumber>;
+
+		/// The minimum amount of stable currency of a commitment
+		#[pallet::constant]
+		type MinimumCommitment: Get<Balance>;
+
+		/// The maximum number of backstop providers
+		#[pallet::constant]
+		type MaxProviders: Get<u32>;
+
+		/// The native currency rewarded for each committed stable currency per
+		/// block
+		#[pallet::constant]
+		type RewardRate: Get<Rate>;
+
+		/// The price source to pay the drawn commitments in native currency
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The treasury account to pay the standing ready rewards
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The origin which may update the reward budget
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The debit backstop module id, keep the committed stable currency
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Exceed the maximum number of backstop providers
+		TooManyProviders,
+		/// The commitment is below the minimum commitment
+		BelowMinimumCommitment,
+		/// The caller has no commitment
+		NoCommitment,
+		/// The backstop is going to be drawn, commitments can not be withdrawn
+		BackstopInUse,
+		/// The drawn amount exceeds the commitment
+		ExceedCommitment,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The commitment is updated. \[who, total_amount\]
+		Committed(T::AccountId, Balance),
+		/// The commitment is withdrawn. \[who, amount\]
+		Withdrawn(T::AccountId, Balance),
+		/// The standing ready rewards are paid. \[who, amount\]
+		RewardsClaimed(T::AccountId, Balance),
+		/// The commitment is drawn to cover the debit. \[who, stable_amount,
+		/// native_amount\]
+		BackstopDrawn(T::AccountId, Balance, Balance),
+		/// The reward budget is updated. \[new_budget\]
+		RewardBudgetUpdated(Balance),
+	}
+
+	/// The commitments of backstop providers.
+	///
+	/// Commitments: map AccountId => Option<Commitment>
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Commitment<T::BlockNumber>, OptionQuery>;
+
+	/// The number of backstop providers.
+	///
+	/// ProvidersCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn providers_count)]
+	pub type ProvidersCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The native currency left to pay as the standing ready rewards from the
+	/// treasury account.
+	///
+	/// RewardBudget: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn reward_budget)]
+	pub type RewardBudget<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	/// The block number since which the debit pool is uncovered by the
+	/// surplus pool.
+	///
+	/// UncoveredSince: Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn uncovered_since)]
+	pub type UncoveredSince<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Track the uncovered debit, and draw the backstop if the debit is
+		/// uncovered for `BackstopWindow` blocks.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let uncovered_debit = T::CDPTreasury::get_debit_pool().saturating_sub(T::CDPTreasury::get_surplus_pool());
+
+			if uncovered_debit.is_zero() {
+				if Self::uncovered_since().is_some() {
+					UncoveredSince::<T>::kill();
+				}
+				return T::WeightInfo::on_initialize();
+			}
+
+			match Self::uncovered_since() {
+				None => {
+					UncoveredSince::<T>::put(now);
+					T::WeightInfo::on_initialize()
+				}
+				Some(since) if now >= since.saturating_add(T::BackstopWindow::get()) => {
+					let providers_count = Self::providers_count();
+					let maybe_price = T::PriceSource::get_relative_price(
+						T::GetStableCurrencyId::get(),
+						T::GetNativeCurrencyId::get(),
+					)
+					.filter(|price| !price.is_zero());
+					match maybe_price {
+						Some(price) => {
+							Self::draw_backstop(uncovered_debit, price);
+							UncoveredSince::<T>::kill();
+						}
+						// retry in the next block
+						None => log::warn!(
+							target: "debit-backstop",
+							"draw_backstop: the feed price of native currency is unavailable",
+						),
+					}
+					T::WeightInfo::on_initialize_with_draw(providers_count)
+				}
+				_ => T::WeightInfo::on_initialize(),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Commit stable currency to the backstop, or increase the existing
+		/// commitment. The accrued rewards are paid before the update.
+		///
+		/// - `amount`: the stable currency to commit.
+		#[pallet::weight(<T as Config>::WeightInfo::commit())]
+		#[transactional]
+		pub fn commit(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let commitment = Commitments::<T>::try_mutate(&who, |maybe_commitment| -> Result<_, DispatchError> {
+				let mut commitment = match maybe_commitment.take() {
+					Some(mut commitment) => {
+						Self::pay_rewards(&who, &mut commitment)?;
+						commitment
+					}
+					None => {
+						ensure!(
+							Self::providers_count() < T::MaxProviders::get(),
+							Error::<T>::TooManyProviders
+						);
+						ProvidersCount::<T>::mutate(|count| *count = count.saturating_add(1));
+						Commitment {
+							amount: Zero::zero(),
+							rewarded_at: <frame_system::Pallet<T>>::block_number(),
+						}
+					}
+				};
+
+				commitment.amount = commitment.amount.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				ensure!(
+					commitment.amount >= T::MinimumCommitment::get(),
+					Error::<T>::BelowMinimumCommitment
+				);
+
+				T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::account_id(), amount)?;
+				*maybe_commitment = Some(commitment);
+				Ok(commitment)
+			})?;
+
+			Self::deposit_event(Event::Committed(who, commitment.amount));
+			Ok(())
+		}
+
+		/// Withdraw the whole commitment and the accrued rewards. Not allowed
+		/// while the debit pool is uncovered.
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
+		#[transactional]
+		pub fn withdraw(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::uncovered_since().is_none(), Error::<T>::BackstopInUse);
+
+			let mut commitment = Commitments::<T>::take(&who).ok_or(Error::<T>::NoCommitment)?;
+			Self::pay_rewards(&who, &mut commitment)?;
+			T::Currency::transfer(
+				T::GetStableCurrencyId::get(),
+				&Self::account_id(),
+				&who,
+				commitment.amount,
+			)?;
+			ProvidersCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(Event::Withdrawn(who, commitment.amount));
+			Ok(())
+		}
+
+		/// Claim the accrued rewards of the commitment.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_rewards())]
+		#[transactional]
+		pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Commitments::<T>::try_mutate(&who, |maybe_commitment| -> DispatchResult {
+				let commitment = maybe_commitment.as_mut().ok_or(Error::<T>::NoCommitment)?;
+				Self::pay_rewards(&who, commitment)
+			})
+		}
+
+		/// Update the native currency left to pay as the standing ready
+		/// rewards from the treasury account.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `budget`: the new reward budget.
+		#[pallet::weight((<T as Config>::WeightInfo::set_reward_budget(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_reward_budget(origin: OriginFor<T>, #[pallet::compact] budget: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			RewardBudget::<T>::put(budget);
+			Self::deposit_event(Event::RewardBudgetUpdated(budget));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of debit backstop module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Pay the rewards accrued since the last payment to `who` from the
+	/// treasury account, capped by the reward budget and the free balance of
+	/// the treasury account. The rewards exceeding the cap are forfeited.
+	fn pay_rewards(who: &T::AccountId, commitment: &mut Commitment<T::BlockNumber>) -> DispatchResult {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let blocks: Balance = now.saturating_sub(commitment.rewarded_at).unique_saturated_into();
+		let native_currency_id = T::GetNativeCurrencyId::get();
+		let treasury_account = T::TreasuryAccount::get();
+		let rewards = T::RewardRate::get()
+			.saturating_mul_int(commitment.amount)
+			.saturating_mul(blocks)
+			.min(Self::reward_budget())
+			.min(T::Currency::free_balance(native_currency_id, &treasury_account));
+		commitment.rewarded_at = now;
+
+		if !rewards.is_zero() {
+			T::Currency::transfer(native_currency_id, &treasury_account, who, rewards)?;
+			RewardBudget::<T>::mutate(|budget| *budget = budget.saturating_sub(rewards));
+			Self::deposit_event(Event::RewardsClaimed(who.clone(), rewards));
+		}
+		Ok(())
+	}
+
+	/// Draw the commitments until `uncovered_debit` is covered or all
+	/// commitments are drawn, `price` is the native currency paid for each
+	/// stable currency drawn.
+	fn draw_backstop(uncovered_debit: Balance, price: Price) {
+		let commitments: Vec<(T::AccountId, Commitment<T::BlockNumber>)> = Commitments::<T>::iter().collect();
+
+		let mut remaining = uncovered_debit;
+		for (who, commitment) in commitments {
+			if remaining.is_zero() {
+				break;
+			}
+
+			let amount = commitment.amount.min(remaining);
+			match Self::draw_commitment(&who, amount, price) {
+				Ok(_) => remaining = remaining.saturating_sub(amount),
+				Err(e) => log::warn!(
+					target: "debit-backstop",
+					"draw_commitment: failed to draw {:?} from {:?}: {:?}. \
+					This is unexpected but should be safe",
+					amount, who, e
+				),
+			}
+		}
+	}
+
+	/// Draw `amount` stable currency out of the commitment of `who` into the
+	/// surplus pool, and pay `who` the native currency at `price`.
+	#[transactional]
+	fn draw_commitment(who: &T::AccountId, amount: Balance, price: Price) -> DispatchResult {
+		Commitments::<T>::try_mutate_exists(who, |maybe_commitment| -> DispatchResult {
+			let mut commitment = maybe_commitment.take().ok_or(Error::<T>::NoCommitment)?;
+			Self::pay_rewards(who, &mut commitment)?;
+			commitment.amount = commitment
+				.amount
+				.checked_sub(amount)
+				.ok_or(Error::<T>::ExceedCommitment)?;
+
+			let native_amount = price.saturating_mul_int(amount);
+			T::CDPTreasury::deposit_surplus(&Self::account_id(), amount)?;
+			T::Currency::deposit(T::GetNativeCurrencyId::get(), who, native_amount)?;
+
+			if commitment.amount.is_zero() {
+				ProvidersCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			} else {
+				*maybe_commitment = Some(commitment);
+			}
+
+			Self::deposit_event(Event::BackstopDrawn(who.clone(), amount, native_amount));
+			Ok(())
+		})
+	}
+}
@@ -0,0 +1,264 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the debit backstop module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, traits::Nothing};
+use frame_system::EnsureSignedBy;
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, TokenSymbol};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult};
+use sp_std::cell::RefCell;
+use support::Ratio;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const TREASURY: AccountId = 10;
+pub const NATIVE_TREASURY: AccountId = 11;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
+
+mod debit_backstop {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = Nothing;
+}
+
+thread_local! {
+	static DEBIT_POOL: RefCell<Balance> = RefCell::new(0);
+	static SURPLUS_POOL: RefCell<Balance> = RefCell::new(0);
+	static NATIVE_PRICE: RefCell<Option<Price>> = RefCell::new(Some(Price::saturating_from_rational(1, 2)));
+}
+
+pub fn set_native_price(price: Option<Price>) {
+	NATIVE_PRICE.with(|v| *v.borrow_mut() = price);
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		match currency_id {
+			AUSD => Some(Price::saturating_from_integer(1)),
+			ACA => NATIVE_PRICE.with(|v| *v.borrow()),
+			_ => None,
+		}
+	}
+}
+
+pub fn set_debit_pool(amount: Balance) {
+	DEBIT_POOL.with(|v| *v.borrow_mut() = amount);
+}
+
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		SURPLUS_POOL.with(|v| *v.borrow())
+	}
+
+	fn get_debit_pool() -> Balance {
+		DEBIT_POOL.with(|v| *v.borrow())
+	}
+
+	fn get_total_collaterals(_id: CurrencyId) -> Balance {
+		unimplemented!()
+	}
+
+	fn get_debit_proportion(_amount: Balance) -> Ratio {
+		unimplemented!()
+	}
+
+	fn on_system_debit(_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_system_surplus(_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_debit(_who: &AccountId, _debit: Balance, _backed: bool) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_debit(_who: &AccountId, _debit: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		Tokens::transfer(AUSD, from, &TREASURY, surplus)?;
+		SURPLUS_POOL.with(|v| *v.borrow_mut() += surplus);
+		Ok(())
+	}
+
+	fn withdraw_surplus(_to: &AccountId, _surplus: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_collateral(_from: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn withdraw_collateral(_to: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
+parameter_types! {
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const BackstopWindow: BlockNumber = 10;
+	pub const MinimumCommitment: Balance = 100;
+	pub const MaxProviders: u32 = 2;
+	pub RewardRate: Rate = Rate::saturating_from_rational(1, 100);
+	pub const DebitBackstopPalletId: PalletId = PalletId(*b"aca/dbbs");
+	pub const TreasuryAccount: AccountId = NATIVE_TREASURY;
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type CDPTreasury = MockCDPTreasury;
+	type BackstopWindow = BackstopWindow;
+	type MinimumCommitment = MinimumCommitment;
+	type MaxProviders = MaxProviders;
+	type RewardRate = RewardRate;
+	type PriceSource = MockPriceSource;
+	type TreasuryAccount = TreasuryAccount;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type PalletId = DebitBackstopPalletId;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		DebitBackstopModule: debit_backstop::{Pallet, Storage, Call, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, AUSD, 1000),
+				(BOB, AUSD, 1000),
+				(CAROL, AUSD, 1000),
+				(NATIVE_TREASURY, ACA, 1000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		set_debit_pool(0);
+		set_native_price(Some(Price::saturating_from_rational(1, 2)));
+		SURPLUS_POOL.with(|v| *v.borrow_mut() = 0);
+
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			RewardBudget::<Runtime>::put(500);
+		});
+		ext
+	}
+}
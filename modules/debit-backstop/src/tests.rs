@@ -0,0 +1,209 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the debit backstop module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn commit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			DebitBackstopModule::commit(Origin::signed(ALICE), 99),
+			Error::<Runtime>::BelowMinimumCommitment
+		);
+
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(ALICE), 100));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::Committed(ALICE, 100)));
+		assert_eq!(
+			DebitBackstopModule::commitments(ALICE),
+			Some(Commitment {
+				amount: 100,
+				rewarded_at: 1,
+			})
+		);
+		assert_eq!(DebitBackstopModule::providers_count(), 1);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(AUSD, &DebitBackstopModule::account_id()), 100);
+
+		// increasing the commitment pays the accrued rewards from the treasury
+		System::set_block_number(11);
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(ALICE), 50));
+		System::assert_has_event(Event::DebitBackstopModule(crate::Event::RewardsClaimed(ALICE, 10)));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::Committed(ALICE, 150)));
+		assert_eq!(
+			DebitBackstopModule::commitments(ALICE),
+			Some(Commitment {
+				amount: 150,
+				rewarded_at: 11,
+			})
+		);
+		assert_eq!(DebitBackstopModule::providers_count(), 1);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 10);
+		assert_eq!(Tokens::free_balance(ACA, &NATIVE_TREASURY), 990);
+		assert_eq!(DebitBackstopModule::reward_budget(), 490);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 850);
+
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(BOB), 100));
+		assert_eq!(DebitBackstopModule::providers_count(), 2);
+		assert_noop!(
+			DebitBackstopModule::commit(Origin::signed(CAROL), 100),
+			Error::<Runtime>::TooManyProviders
+		);
+	});
+}
+
+#[test]
+fn withdraw_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			DebitBackstopModule::withdraw(Origin::signed(ALICE)),
+			Error::<Runtime>::NoCommitment
+		);
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(ALICE), 100));
+
+		System::set_block_number(6);
+		set_debit_pool(100);
+		DebitBackstopModule::on_initialize(6);
+		assert_eq!(DebitBackstopModule::uncovered_since(), Some(6));
+		assert_noop!(
+			DebitBackstopModule::withdraw(Origin::signed(ALICE)),
+			Error::<Runtime>::BackstopInUse
+		);
+
+		set_debit_pool(0);
+		DebitBackstopModule::on_initialize(6);
+		assert_eq!(DebitBackstopModule::uncovered_since(), None);
+		assert_ok!(DebitBackstopModule::withdraw(Origin::signed(ALICE)));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::Withdrawn(ALICE, 100)));
+		assert_eq!(DebitBackstopModule::commitments(ALICE), None);
+		assert_eq!(DebitBackstopModule::providers_count(), 0);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1000);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 5);
+	});
+}
+
+#[test]
+fn claim_rewards_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			DebitBackstopModule::claim_rewards(Origin::signed(ALICE)),
+			Error::<Runtime>::NoCommitment
+		);
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(ALICE), 200));
+
+		System::set_block_number(4);
+		assert_ok!(DebitBackstopModule::claim_rewards(Origin::signed(ALICE)));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::RewardsClaimed(ALICE, 6)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 6);
+		assert_eq!(DebitBackstopModule::commitments(ALICE).unwrap().rewarded_at, 4);
+
+		assert_ok!(DebitBackstopModule::claim_rewards(Origin::signed(ALICE)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 6);
+	});
+}
+
+#[test]
+fn reward_budget_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			DebitBackstopModule::set_reward_budget(Origin::signed(BOB), 3),
+			BadOrigin
+		);
+		assert_ok!(DebitBackstopModule::set_reward_budget(Origin::signed(ALICE), 3));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::RewardBudgetUpdated(3)));
+		assert_eq!(DebitBackstopModule::reward_budget(), 3);
+
+		// the rewards are capped by the budget
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(BOB), 200));
+		System::set_block_number(10);
+		assert_ok!(DebitBackstopModule::claim_rewards(Origin::signed(BOB)));
+		System::assert_last_event(Event::DebitBackstopModule(crate::Event::RewardsClaimed(BOB, 3)));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 3);
+		assert_eq!(DebitBackstopModule::reward_budget(), 0);
+
+		System::set_block_number(20);
+		assert_ok!(DebitBackstopModule::claim_rewards(Origin::signed(BOB)));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 3);
+		assert_eq!(DebitBackstopModule::commitments(BOB).unwrap().rewarded_at, 20);
+
+		// the rewards are capped by the balance of the treasury account
+		assert_ok!(DebitBackstopModule::set_reward_budget(Origin::signed(ALICE), 2000));
+		System::set_block_number(1020);
+		assert_ok!(DebitBackstopModule::claim_rewards(Origin::signed(BOB)));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(ACA, &NATIVE_TREASURY), 0);
+		assert_eq!(DebitBackstopModule::reward_budget(), 1003);
+	});
+}
+
+#[test]
+fn draw_backstop_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(ALICE), 200));
+		assert_ok!(DebitBackstopModule::commit(Origin::signed(BOB), 200));
+
+		set_debit_pool(300);
+		DebitBackstopModule::on_initialize(1);
+		assert_eq!(DebitBackstopModule::uncovered_since(), Some(1));
+
+		// not drawn within the window
+		System::set_block_number(10);
+		DebitBackstopModule::on_initialize(10);
+		assert_eq!(DebitBackstopModule::uncovered_since(), Some(1));
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 0);
+
+		// not drawn without the feed price
+		set_native_price(None);
+		System::set_block_number(11);
+		DebitBackstopModule::on_initialize(11);
+		assert_eq!(DebitBackstopModule::uncovered_since(), Some(1));
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 0);
+
+		// drawn out of the committed stable currency, paid at the feed price
+		set_native_price(Some(Price::saturating_from_rational(1, 2)));
+		System::set_block_number(12);
+		DebitBackstopModule::on_initialize(12);
+		assert_eq!(DebitBackstopModule::uncovered_since(), None);
+		assert_eq!(MockCDPTreasury::get_surplus_pool(), 300);
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 300);
+		assert_eq!(Tokens::free_balance(AUSD, &DebitBackstopModule::account_id()), 100);
+		let remaining_commitments: Balance = [ALICE, BOB]
+			.iter()
+			.filter_map(|who| DebitBackstopModule::commitments(who))
+			.map(|commitment| commitment.amount)
+			.sum();
+		assert_eq!(remaining_commitments, 100);
+		assert_eq!(DebitBackstopModule::providers_count(), 1);
+
+		// the standing ready rewards are paid before drawn
+		assert_eq!(
+			Tokens::free_balance(ACA, &ALICE) + Tokens::free_balance(ACA, &BOB),
+			22 + 22 + 600
+		);
+		assert_eq!(Tokens::free_balance(ACA, &NATIVE_TREASURY), 956);
+
+		DebitBackstopModule::on_initialize(13);
+		assert_eq!(DebitBackstopModule::uncovered_since(), None);
+	});
+}
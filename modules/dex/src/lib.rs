@@ -36,16 +36,19 @@
 use codec::MaxEncodedLen;
 use frame_support::{log, pallet_prelude::*, transactional, PalletId};
 use frame_system::pallet_prelude::*;
-use orml_traits::{MultiCurrency, MultiCurrencyExtended};
-use primitives::{Balance, CurrencyId, TradingPair};
+use orml_traits::{Happened, MultiCurrency, MultiCurrencyExtended};
+use primitives::{Balance, CurrencyId, StableAssetPoolId, TradingPair};
 use scale_info::TypeInfo;
 use sp_core::{H160, U256};
+use sp_io::KillStorageResult;
 use sp_runtime::{
-	traits::{AccountIdConversion, One, Zero},
+	traits::{AccountIdConversion, CheckedDiv, One, Saturating, UniqueSaturatedInto, Zero},
 	ArithmeticError, DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug, SaturatedConversion,
 };
 use sp_std::{convert::TryInto, prelude::*, vec};
-use support::{DEXIncentives, DEXManager, Erc20InfoMapping, ExchangeRate, Ratio};
+use support::{
+	DEXIncentives, DEXManager, DEXPriceCumulative, Erc20InfoMapping, ExchangeRate, Price, Ratio, StableAssetPool,
+};
 
 mod mock;
 mod tests;
@@ -87,6 +90,56 @@ impl<Balance, BlockNumber> Default for TradingPairStatus<Balance, BlockNumber> {
 	}
 }
 
+/// The type of pool which holds the liquidity of a currency pair.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum PoolType {
+	/// The constant product liquidity pool of this module.
+	ConstantProduct,
+	/// The stable swap pool with specific pool id.
+	StableSwap(StableAssetPoolId),
+}
+
+/// Anti-MEV limits of swaps through a trading pair.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, MaxEncodedLen, TypeInfo)]
+pub struct SwapLimits {
+	/// The max price impact of an individual swap through the trading pair.
+	pub max_price_impact: Ratio,
+	/// The max volume an account could swap through the trading pair in a
+	/// block, counted in the first currency of the trading pair.
+	pub max_volume_per_block: Balance,
+}
+
+/// The execution mode of swaps through a trading pair.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, MaxEncodedLen, TypeInfo)]
+pub enum ExecutionMode {
+	/// Swaps are executed against the liquidity pool immediately.
+	Instant,
+	/// Swaps submitted within a block are buffered and settled at a uniform
+	/// clearing price at the beginning of the next block, at most
+	/// `max_orders` orders per block.
+	BatchAuction { max_orders: u32 },
+}
+
+impl Default for ExecutionMode {
+	fn default() -> Self {
+		Self::Instant
+	}
+}
+
+/// A swap order buffered for the batch auction of a trading pair.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct BatchSwapOrder<AccountId> {
+	/// The owner of the order.
+	pub who: AccountId,
+	/// The currency supplied by the order, the other currency of the trading
+	/// pair is the target.
+	pub supply_currency_id: CurrencyId,
+	/// The supply amount, escrowed by the module account.
+	pub supply_amount: Balance,
+	/// The minimum acceptable target amount.
+	pub min_target_amount: Balance,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -110,6 +163,11 @@ pub mod module {
 		#[pallet::constant]
 		type TradingPathLimit: Get<u32>;
 
+		/// The maximum of `max_orders` of the trading pairs in batch auction
+		/// execution mode, bounds the work of settling a batch auction.
+		#[pallet::constant]
+		type MaxBatchSwapOrders: Get<u32>;
+
 		/// The DEX's module id, keep all assets in DEX.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
@@ -126,6 +184,16 @@ pub mod module {
 
 		/// The origin which may list, enable or disable trading pairs.
 		type ListingOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Stable swap pools, used to migrate liquidity between pool types.
+		type StableAsset: StableAssetPool<Self::AccountId, CurrencyId, Balance>;
+
+		/// The account receiving the protocol fee of swaps.
+		type ProtocolFeeReceiver: Get<Self::AccountId>;
+
+		/// Handler for the protocol fee charged, with the currency and the
+		/// amount of the fee
+		type OnProtocolFee: Happened<(CurrencyId, Balance)>;
 	}
 
 	#[pallet::error]
@@ -168,6 +236,30 @@ pub mod module {
 		UnqualifiedProvision,
 		/// Trading pair is still provisioning
 		StillProvisioning,
+		/// The pool types of liquidity migration are invalid
+		InvalidPoolType,
+		/// The stable swap pool does not contain the currency pair
+		InvalidStableAssetPool,
+		/// The price impact of the swap exceeds the limit of trading pair
+		ExceedMaxPriceImpact,
+		/// The swap volume of the account in this block exceeds the limit of
+		/// trading pair
+		ExceedSwapVolumeLimit,
+		/// Trading pair must be in batch auction execution mode
+		MustBeBatchAuctionMode,
+		/// Trading pair must be in instant execution mode
+		MustBeInstantMode,
+		/// The batch swap orders of trading pair in this block reach the limit
+		TooManyBatchSwapOrders,
+		/// The max orders of batch auction execution mode exceed
+		/// `MaxBatchSwapOrders`
+		InvalidExecutionMode,
+		/// The dex share of provision is still vesting
+		ProvisionSharesStillVesting,
+		/// The dex share of provision is not vesting
+		ProvisionSharesNotVesting,
+		/// The protocol fee rate is invalid
+		InvalidProtocolFeeRate,
 	}
 
 	#[pallet::event]
@@ -195,6 +287,39 @@ pub mod module {
 		/// Provisioning trading pair convert to Enabled. \[trading_pair,
 		/// pool_0_amount, pool_1_amount, total_share_amount\]
 		ProvisioningToEnabled(TradingPair, Balance, Balance, Balance),
+		/// Liquidity migrated between pool types. \[who, trading_pair,
+		/// from_pool, to_pool, share_decrement, share_increment\]
+		LiquidityMigrated(T::AccountId, TradingPair, PoolType, PoolType, Balance, Balance),
+		/// The anti-MEV swap limits of trading pair updated. \[trading_pair,
+		/// swap_limits\]
+		SwapLimitsUpdated(TradingPair, Option<SwapLimits>),
+		/// The execution mode of trading pair updated. \[trading_pair,
+		/// execution_mode\]
+		ExecutionModeUpdated(TradingPair, ExecutionMode),
+		/// Submit swap order to the batch auction of trading pair. \[who,
+		/// supply_currency_id, target_currency_id, supply_amount,
+		/// min_target_amount\]
+		BatchSwapOrderSubmitted(T::AccountId, CurrencyId, CurrencyId, Balance, Balance),
+		/// The batch swap order is settled at the clearing price. \[who,
+		/// supply_currency_id, supply_amount, target_currency_id,
+		/// target_amount\]
+		BatchSwapOrderSettled(T::AccountId, CurrencyId, Balance, CurrencyId, Balance),
+		/// The batch swap order is refunded because of unacceptable clearing
+		/// price or settlement failure. \[who, supply_currency_id,
+		/// supply_amount\]
+		BatchSwapOrderRefunded(T::AccountId, CurrencyId, Balance),
+		/// The batch auction of trading pair is cleared at a uniform price.
+		/// \[trading_pair, supply_amount_0, supply_amount_1, target_amount_0,
+		/// target_amount_1\]
+		BatchAuctionCleared(TradingPair, Balance, Balance, Balance, Balance),
+		/// The vesting period of the dex share of provision updated.
+		/// \[trading_pair, vesting_period\]
+		ProvisionVestingPeriodUpdated(TradingPair, T::BlockNumber),
+		/// The vested dex share of provision is claimed. \[who,
+		/// trading_pair, share_amount\]
+		VestedProvisionSharesClaimed(T::AccountId, TradingPair, Balance),
+		/// The protocol fee rate of swaps updated. \[protocol_fee_rate\]
+		ProtocolFeeRateUpdated(Ratio),
 	}
 
 	/// Liquidity pool for TradingPair.
@@ -229,6 +354,85 @@ pub mod module {
 	pub type InitialShareExchangeRates<T: Config> =
 		StorageMap<_, Twox64Concat, TradingPair, (ExchangeRate, ExchangeRate), ValueQuery>;
 
+	/// The period over which the dex share of provision vests linearly
+	/// after the Provisioning TradingPair converts to Enabled, zero means
+	/// claimable immediately.
+	///
+	/// ProvisionVestingPeriods: map TradingPair => BlockNumber
+	#[pallet::storage]
+	#[pallet::getter(fn provision_vesting_periods)]
+	pub type ProvisionVestingPeriods<T: Config> = StorageMap<_, Twox64Concat, TradingPair, T::BlockNumber, ValueQuery>;
+
+	/// The vesting schedule of the dex share of provision for TradingPair
+	/// converted to Enabled.
+	///
+	/// ProvisionVestingSchedules: map TradingPair => Option<(StartBlock,
+	/// VestingPeriod)>
+	#[pallet::storage]
+	#[pallet::getter(fn provision_vesting_schedules)]
+	pub type ProvisionVestingSchedules<T: Config> =
+		StorageMap<_, Twox64Concat, TradingPair, (T::BlockNumber, T::BlockNumber), OptionQuery>;
+
+	/// The vested dex share of provision already claimed by AccountId.
+	///
+	/// ClaimedProvisionShares: double_map TradingPair, AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_provision_shares)]
+	pub type ClaimedProvisionShares<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, TradingPair, Twox64Concat, T::AccountId, Balance, ValueQuery>;
+
+	/// The anti-MEV swap limits of TradingPair, no limits if not set.
+	///
+	/// TradingPairSwapLimits: map TradingPair => Option<SwapLimits>
+	#[pallet::storage]
+	#[pallet::getter(fn trading_pair_swap_limits)]
+	pub type TradingPairSwapLimits<T: Config> = StorageMap<_, Twox64Concat, TradingPair, SwapLimits, OptionQuery>;
+
+	/// The volume swapped by AccountId through TradingPair in current block,
+	/// only tracked for the trading pairs with swap limits and cleared on
+	/// the initialization of the next block.
+	///
+	/// SwapVolumes: double_map TradingPair, AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn swap_volumes)]
+	pub type SwapVolumes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, TradingPair, Twox64Concat, T::AccountId, Balance, ValueQuery>;
+
+	/// The execution mode of TradingPair.
+	///
+	/// TradingPairExecutionModes: map TradingPair => ExecutionMode
+	#[pallet::storage]
+	#[pallet::getter(fn trading_pair_execution_modes)]
+	pub type TradingPairExecutionModes<T: Config> = StorageMap<_, Twox64Concat, TradingPair, ExecutionMode, ValueQuery>;
+
+	/// The swap orders submitted to the batch auction of TradingPair in
+	/// current block, settled on block finalization.
+	///
+	/// BatchSwapOrders: map TradingPair => Vec<BatchSwapOrder>
+	#[pallet::storage]
+	#[pallet::getter(fn batch_swap_orders)]
+	pub type BatchSwapOrders<T: Config> =
+		StorageMap<_, Twox64Concat, TradingPair, Vec<BatchSwapOrder<T::AccountId>>, ValueQuery>;
+
+	/// The prices of TradingPair accumulated over blocks, and the block
+	/// number of the last accumulation. The price of the first currency is
+	/// in the second currency, and the price of the second currency is in the
+	/// first currency.
+	///
+	/// CumulativePrices: map TradingPair => (U256, U256, BlockNumber)
+	#[pallet::storage]
+	#[pallet::getter(fn cumulative_prices)]
+	pub type CumulativePrices<T: Config> =
+		StorageMap<_, Twox64Concat, TradingPair, (U256, U256, T::BlockNumber), ValueQuery>;
+
+	/// The share of the supply amount of swaps charged as protocol fee and
+	/// sent to `ProtocolFeeReceiver`.
+	///
+	/// ProtocolFeeRate: Ratio
+	#[pallet::storage]
+	#[pallet::getter(fn protocol_fee_rate)]
+	pub type ProtocolFeeRate<T: Config> = StorageValue<_, Ratio, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub initial_listing_trading_pairs: Vec<(TradingPair, (Balance, Balance), (Balance, Balance), T::BlockNumber)>,
@@ -297,7 +501,24 @@ pub mod module {
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Clear the swap volumes tracked in the previous block, weighted by
+		/// the number of entries removed, and settle the batch auctions of the
+		/// orders submitted in the previous block, each bounded by
+		/// `MaxBatchSwapOrders`.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			let removed = match SwapVolumes::<T>::remove_all(None) {
+				KillStorageResult::AllRemoved(count) => count,
+				KillStorageResult::SomeRemaining(count) => count,
+			};
+			let mut weight = T::DbWeight::get().writes(removed.into());
+
+			for (trading_pair, orders) in BatchSwapOrders::<T>::drain() {
+				weight = weight.saturating_add(Self::settle_batch_auction(trading_pair, orders));
+			}
+			weight
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -315,6 +536,7 @@ pub mod module {
 			#[pallet::compact] min_target_amount: Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_instant_execution(&path)?;
 			Self::do_swap_with_exact_supply(&who, &path, supply_amount, min_target_amount)?;
 			Ok(())
 		}
@@ -333,6 +555,7 @@ pub mod module {
 			#[pallet::compact] max_supply_amount: Balance,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_instant_execution(&path)?;
 			Self::do_swap_with_exact_target(&who, &path, target_amount, max_supply_amount)?;
 			Ok(())
 		}
@@ -400,7 +623,8 @@ pub mod module {
 			Ok(())
 		}
 
-		/// Claim dex share for founders who have participated in trading pair provision.
+		/// Claim dex share for founders who have participated in trading pair provision. If the
+		/// dex share of provision is vesting, it can only be claimed after fully vested.
 		///
 		/// - `owner`: founder account.
 		/// - `currency_id_a`: currency id A.
@@ -418,6 +642,25 @@ pub mod module {
 			Ok(())
 		}
 
+		/// Claim the vested part of dex share for founders who have participated in trading
+		/// pair provision, the dex share vests linearly over the vesting period.
+		///
+		/// - `owner`: founder account.
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_vested_provision_shares())]
+		#[transactional]
+		pub fn claim_vested_provision_shares(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+		) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			Self::do_claim_vested_provision_shares(&owner, currency_id_a, currency_id_b)?;
+			Ok(())
+		}
+
 		/// Remove liquidity from specific liquidity pool in the form of burning
 		/// shares, and withdrawing currencies in trading pairs from liquidity
 		/// pool in proportion, and withdraw liquidity incentive interest.
@@ -456,6 +699,45 @@ pub mod module {
 			Ok(())
 		}
 
+		/// Migrate liquidity of a currency pair from one pool type to another
+		/// atomically, by removing it from `from_pool` and adding the withdrawn
+		/// currencies to `to_pool`. Currencies not consumed by `to_pool` are
+		/// left to the caller.
+		///
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		/// - `from_pool`: the pool type to remove liquidity from.
+		/// - `to_pool`: the pool type to add liquidity to.
+		/// - `remove_share`: share amount of `from_pool` to migrate.
+		/// - `min_share_increment`: minimum acceptable share amount of `to_pool`.
+		/// - `by_unstake`: this flag indicates whether to withdraw the constant product share
+		///   which is on incentives, and to stake the increased one.
+		#[pallet::weight(<T as Config>::WeightInfo::migrate_liquidity())]
+		#[transactional]
+		pub fn migrate_liquidity(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			from_pool: PoolType,
+			to_pool: PoolType,
+			#[pallet::compact] remove_share: Balance,
+			#[pallet::compact] min_share_increment: Balance,
+			by_unstake: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_migrate_liquidity(
+				&who,
+				currency_id_a,
+				currency_id_b,
+				from_pool,
+				to_pool,
+				remove_share,
+				min_share_increment,
+				by_unstake,
+			)?;
+			Ok(())
+		}
+
 		/// List a new provisioning trading pair.
 		#[pallet::weight((<T as Config>::WeightInfo::list_provisioning(), DispatchClass::Operational))]
 		#[transactional]
@@ -614,6 +896,7 @@ pub mod module {
 					)?;
 
 					// inject provision to liquidity pool
+					Self::update_cumulative_prices(trading_pair);
 					LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> DispatchResult {
 						*pool_0 = pool_0.checked_add(total_provision_0).ok_or(ArithmeticError::Overflow)?;
 						*pool_1 = pool_1.checked_add(total_provision_1).ok_or(ArithmeticError::Overflow)?;
@@ -629,6 +912,15 @@ pub mod module {
 						(share_exchange_rate_0, share_exchange_rate_1),
 					);
 
+					// start vesting the shares of founders if the vesting period is set
+					let vesting_period = ProvisionVestingPeriods::<T>::take(trading_pair);
+					if !vesting_period.is_zero() {
+						ProvisionVestingSchedules::<T>::insert(
+							trading_pair,
+							(frame_system::Pallet::<T>::block_number(), vesting_period),
+						);
+					}
+
 					Self::deposit_event(Event::ProvisioningToEnabled(
 						trading_pair,
 						total_provision_0,
@@ -695,6 +987,172 @@ pub mod module {
 			Self::deposit_event(Event::DisableTradingPair(trading_pair));
 			Ok(())
 		}
+
+		/// Set the period over which the dex share of provision vests linearly
+		/// after the Provisioning trading pair converts to Enabled, zero means
+		/// claimable immediately.
+		///
+		/// The dispatch origin of this call must be `ListingOrigin`.
+		///
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		/// - `vesting_period`: the vesting period in blocks.
+		#[pallet::weight((<T as Config>::WeightInfo::set_provision_vesting_period(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_provision_vesting_period(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			vesting_period: T::BlockNumber,
+		) -> DispatchResult {
+			T::ListingOrigin::ensure_origin(origin)?;
+			let trading_pair =
+				TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+			ensure!(
+				matches!(
+					Self::trading_pair_statuses(trading_pair),
+					TradingPairStatus::<_, _>::Provisioning(_)
+				),
+				Error::<T>::MustBeProvisioning
+			);
+
+			ProvisionVestingPeriods::<T>::mutate_exists(trading_pair, |maybe_period| {
+				*maybe_period = Some(vesting_period).filter(|period| !period.is_zero())
+			});
+			Self::deposit_event(Event::ProvisionVestingPeriodUpdated(trading_pair, vesting_period));
+			Ok(())
+		}
+
+		/// Set the anti-MEV swap limits of a trading pair, `None` means
+		/// removing the limits.
+		///
+		/// The dispatch origin of this call must be `ListingOrigin`.
+		///
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		/// - `swap_limits`: the max price impact per swap and the max volume per
+		///   account per block.
+		#[pallet::weight((<T as Config>::WeightInfo::set_swap_limits(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_swap_limits(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			swap_limits: Option<SwapLimits>,
+		) -> DispatchResult {
+			T::ListingOrigin::ensure_origin(origin)?;
+			let trading_pair =
+				TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+
+			TradingPairSwapLimits::<T>::mutate_exists(trading_pair, |maybe_limits| *maybe_limits = swap_limits);
+			Self::deposit_event(Event::SwapLimitsUpdated(trading_pair, swap_limits));
+			Ok(())
+		}
+
+		/// Set the execution mode of a trading pair. The orders already
+		/// submitted to the batch auction are still settled at the beginning
+		/// of the next block.
+		///
+		/// The dispatch origin of this call must be `ListingOrigin`.
+		///
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		/// - `execution_mode`: instant or batch auction.
+		#[pallet::weight((<T as Config>::WeightInfo::set_execution_mode(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_execution_mode(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			execution_mode: ExecutionMode,
+		) -> DispatchResult {
+			T::ListingOrigin::ensure_origin(origin)?;
+			let trading_pair =
+				TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+			if let ExecutionMode::BatchAuction { max_orders } = execution_mode {
+				ensure!(
+					max_orders <= T::MaxBatchSwapOrders::get(),
+					Error::<T>::InvalidExecutionMode
+				);
+			}
+
+			TradingPairExecutionModes::<T>::insert(trading_pair, execution_mode);
+			Self::deposit_event(Event::ExecutionModeUpdated(trading_pair, execution_mode));
+			Ok(())
+		}
+
+		/// Set the protocol fee rate of swaps, the share of the supply amount
+		/// sent to `ProtocolFeeReceiver`.
+		///
+		/// The dispatch origin of this call must be `ListingOrigin`.
+		///
+		/// - `protocol_fee_rate`: the protocol fee rate, must be less than one.
+		#[pallet::weight((<T as Config>::WeightInfo::set_protocol_fee_rate(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_protocol_fee_rate(origin: OriginFor<T>, protocol_fee_rate: Ratio) -> DispatchResult {
+			T::ListingOrigin::ensure_origin(origin)?;
+			ensure!(protocol_fee_rate < Ratio::one(), Error::<T>::InvalidProtocolFeeRate);
+
+			ProtocolFeeRate::<T>::put(protocol_fee_rate);
+			Self::deposit_event(Event::ProtocolFeeRateUpdated(protocol_fee_rate));
+			Ok(())
+		}
+
+		/// Submit a swap order to the batch auction of a trading pair. The
+		/// supply amount is escrowed, and the order is settled at the uniform
+		/// clearing price of the batch at the end of the block, or refunded if
+		/// the target amount is less than `min_target_amount`.
+		///
+		/// - `supply_currency_id`: the currency to supply.
+		/// - `target_currency_id`: the currency to receive.
+		/// - `supply_amount`: the exact supply amount.
+		/// - `min_target_amount`: the acceptable minimum target amount.
+		#[pallet::weight(<T as Config>::WeightInfo::submit_batch_swap_order())]
+		#[transactional]
+		pub fn submit_batch_swap_order(
+			origin: OriginFor<T>,
+			supply_currency_id: CurrencyId,
+			target_currency_id: CurrencyId,
+			#[pallet::compact] supply_amount: Balance,
+			#[pallet::compact] min_target_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let trading_pair = TradingPair::from_currency_ids(supply_currency_id, target_currency_id)
+				.ok_or(Error::<T>::InvalidCurrencyId)?;
+			ensure!(
+				matches!(
+					Self::trading_pair_statuses(trading_pair),
+					TradingPairStatus::<_, _>::Enabled
+				),
+				Error::<T>::MustBeEnabled
+			);
+			let max_orders = match Self::trading_pair_execution_modes(trading_pair) {
+				ExecutionMode::BatchAuction { max_orders } => max_orders,
+				ExecutionMode::Instant => return Err(Error::<T>::MustBeBatchAuctionMode.into()),
+			};
+			ensure!(!supply_amount.is_zero(), Error::<T>::ZeroSupplyAmount);
+
+			BatchSwapOrders::<T>::try_mutate(trading_pair, |orders| -> DispatchResult {
+				ensure!((orders.len() as u32) < max_orders, Error::<T>::TooManyBatchSwapOrders);
+				T::Currency::transfer(supply_currency_id, &who, &Self::account_id(), supply_amount)?;
+				orders.push(BatchSwapOrder {
+					who: who.clone(),
+					supply_currency_id,
+					supply_amount,
+					min_target_amount,
+				});
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::BatchSwapOrderSubmitted(
+				who,
+				supply_currency_id,
+				target_currency_id,
+				supply_amount,
+				min_target_amount,
+			));
+			Ok(())
+		}
 	}
 }
 
@@ -714,18 +1172,15 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::StillProvisioning
 		);
 
+		ensure!(
+			Self::vested_proportion(trading_pair) >= Ratio::one(),
+			Error::<T>::ProvisionSharesStillVesting
+		);
+
 		ProvisioningPool::<T>::try_mutate_exists(trading_pair, who, |maybe_contribution| -> DispatchResult {
-			if let Some((contribution_0, contribution_1)) = maybe_contribution.take() {
-				let (exchange_rate_0, exchange_rate_1) = Self::initial_share_exchange_rates(trading_pair);
-				let shares_from_provision_0 = exchange_rate_0
-					.checked_mul_int(contribution_0)
-					.ok_or(ArithmeticError::Overflow)?;
-				let shares_from_provision_1 = exchange_rate_1
-					.checked_mul_int(contribution_1)
-					.ok_or(ArithmeticError::Overflow)?;
-				let shares_to_claim = shares_from_provision_0
-					.checked_add(shares_from_provision_1)
-					.ok_or(ArithmeticError::Overflow)?;
+			if let Some(contribution) = maybe_contribution.take() {
+				let shares_to_claim = Self::provision_shares(trading_pair, contribution)?
+					.saturating_sub(ClaimedProvisionShares::<T>::take(trading_pair, who));
 
 				T::Currency::transfer(
 					trading_pair.dex_share_currency_id(),
@@ -743,11 +1198,88 @@ impl<T: Config> Pallet<T> {
 		// clear InitialShareExchangeRates once it is all claimed
 		if ProvisioningPool::<T>::iter_prefix(trading_pair).next().is_none() {
 			InitialShareExchangeRates::<T>::remove(trading_pair);
+			ProvisionVestingSchedules::<T>::remove(trading_pair);
 		}
 
 		Ok(())
 	}
 
+	fn do_claim_vested_provision_shares(
+		who: &T::AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+	) -> DispatchResult {
+		let trading_pair =
+			TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+		ensure!(
+			Self::provision_vesting_schedules(trading_pair).is_some(),
+			Error::<T>::ProvisionSharesNotVesting
+		);
+
+		// claim all the remaining shares once fully vested
+		let vested_proportion = Self::vested_proportion(trading_pair);
+		if vested_proportion >= Ratio::one() {
+			return Self::do_claim_dex_share(who, currency_id_a, currency_id_b);
+		}
+
+		let contribution = Self::provisioning_pool(trading_pair, who);
+		let vested_shares = vested_proportion.saturating_mul_int(Self::provision_shares(trading_pair, contribution)?);
+		let shares_to_claim =
+			ClaimedProvisionShares::<T>::try_mutate(trading_pair, who, |claimed| -> Result<Balance, DispatchError> {
+				let shares_to_claim = vested_shares.saturating_sub(*claimed);
+				*claimed = vested_shares.max(*claimed);
+				Ok(shares_to_claim)
+			})?;
+
+		if !shares_to_claim.is_zero() {
+			T::Currency::transfer(
+				trading_pair.dex_share_currency_id(),
+				&Self::account_id(),
+				who,
+				shares_to_claim,
+			)?;
+			Self::deposit_event(Event::VestedProvisionSharesClaimed(
+				who.clone(),
+				trading_pair,
+				shares_to_claim,
+			));
+		}
+		Ok(())
+	}
+
+	/// Calculate the dex share amount of the provision contribution by the
+	/// initial exchange rates.
+	fn provision_shares(trading_pair: TradingPair, contribution: (Balance, Balance)) -> Result<Balance, DispatchError> {
+		let (exchange_rate_0, exchange_rate_1) = Self::initial_share_exchange_rates(trading_pair);
+		let shares_from_provision_0 = exchange_rate_0
+			.checked_mul_int(contribution.0)
+			.ok_or(ArithmeticError::Overflow)?;
+		let shares_from_provision_1 = exchange_rate_1
+			.checked_mul_int(contribution.1)
+			.ok_or(ArithmeticError::Overflow)?;
+		let shares = shares_from_provision_0
+			.checked_add(shares_from_provision_1)
+			.ok_or(ArithmeticError::Overflow)?;
+		Ok(shares)
+	}
+
+	/// The vested proportion of the dex share of provision, fully vested if
+	/// not vesting.
+	pub fn vested_proportion(trading_pair: TradingPair) -> Ratio {
+		match Self::provision_vesting_schedules(trading_pair) {
+			Some((start, period)) => {
+				let elapsed: u128 = frame_system::Pallet::<T>::block_number()
+					.saturating_sub(start)
+					.unique_saturated_into();
+				let period: u128 = period.unique_saturated_into();
+				Ratio::checked_from_rational(elapsed, period)
+					.unwrap_or_else(Ratio::one)
+					.min(Ratio::one())
+			}
+			None => Ratio::one(),
+		}
+	}
+
 	fn do_add_provision(
 		who: &T::AccountId,
 		currency_id_a: CurrencyId,
@@ -832,7 +1364,7 @@ impl<T: Config> Pallet<T> {
 		max_amount_b: Balance,
 		min_share_increment: Balance,
 		stake_increment_share: bool,
-	) -> DispatchResult {
+	) -> sp_std::result::Result<Balance, DispatchError> {
 		let trading_pair =
 			TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
 		ensure!(
@@ -848,7 +1380,8 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::InvalidLiquidityIncrement
 		);
 
-		LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> DispatchResult {
+		Self::update_cumulative_prices(trading_pair);
+		LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> Result<Balance, DispatchError> {
 			let dex_share_currency_id = trading_pair.dex_share_currency_id();
 			let total_shares = T::Currency::total_issuance(dex_share_currency_id);
 			let (max_amount_0, max_amount_1) = if currency_id_a == trading_pair.first() {
@@ -934,7 +1467,7 @@ impl<T: Config> Pallet<T> {
 				pool_1_increment,
 				share_increment,
 			));
-			Ok(())
+			Ok(share_increment)
 		})
 	}
 
@@ -947,15 +1480,16 @@ impl<T: Config> Pallet<T> {
 		min_withdrawn_a: Balance,
 		min_withdrawn_b: Balance,
 		by_unstake: bool,
-	) -> DispatchResult {
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
 		if remove_share.is_zero() {
-			return Ok(());
+			return Ok((Zero::zero(), Zero::zero()));
 		}
 		let trading_pair =
 			TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
 		let dex_share_currency_id = trading_pair.dex_share_currency_id();
 
-		LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> DispatchResult {
+		Self::update_cumulative_prices(trading_pair);
+		LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> Result<_, DispatchError> {
 			let (min_withdrawn_0, min_withdrawn_1) = if currency_id_a == trading_pair.first() {
 				(min_withdrawn_a, min_withdrawn_b)
 			} else {
@@ -991,10 +1525,100 @@ impl<T: Config> Pallet<T> {
 				pool_1_decrement,
 				remove_share,
 			));
-			Ok(())
+
+			if currency_id_a == trading_pair.first() {
+				Ok((pool_0_decrement, pool_1_decrement))
+			} else {
+				Ok((pool_1_decrement, pool_0_decrement))
+			}
 		})
 	}
 
+	#[transactional]
+	fn do_migrate_liquidity(
+		who: &T::AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		from_pool: PoolType,
+		to_pool: PoolType,
+		remove_share: Balance,
+		min_share_increment: Balance,
+		by_unstake: bool,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let trading_pair =
+			TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+		ensure!(from_pool != to_pool, Error::<T>::InvalidPoolType);
+		ensure!(!remove_share.is_zero(), Error::<T>::InvalidLiquidityIncrement);
+
+		let (withdrawn_a, withdrawn_b) = match from_pool {
+			PoolType::ConstantProduct => Self::do_remove_liquidity(
+				who,
+				currency_id_a,
+				currency_id_b,
+				remove_share,
+				Zero::zero(),
+				Zero::zero(),
+				by_unstake,
+			)?,
+			PoolType::StableSwap(pool_id) => {
+				let (assets_count, index_a, index_b) =
+					Self::stable_asset_pool_indexes(pool_id, currency_id_a, currency_id_b)?;
+				let redeemed =
+					T::StableAsset::redeem_proportion(who, pool_id, remove_share, vec![Zero::zero(); assets_count])?;
+				(redeemed[index_a], redeemed[index_b])
+			}
+		};
+
+		let share_increment = match to_pool {
+			PoolType::ConstantProduct => Self::do_add_liquidity(
+				who,
+				currency_id_a,
+				currency_id_b,
+				withdrawn_a,
+				withdrawn_b,
+				min_share_increment,
+				by_unstake,
+			)?,
+			PoolType::StableSwap(pool_id) => {
+				let (assets_count, index_a, index_b) =
+					Self::stable_asset_pool_indexes(pool_id, currency_id_a, currency_id_b)?;
+				let mut amounts = vec![Zero::zero(); assets_count];
+				amounts[index_a] = withdrawn_a;
+				amounts[index_b] = withdrawn_b;
+				T::StableAsset::mint(who, pool_id, amounts, min_share_increment)?
+			}
+		};
+
+		Self::deposit_event(Event::LiquidityMigrated(
+			who.clone(),
+			trading_pair,
+			from_pool,
+			to_pool,
+			remove_share,
+			share_increment,
+		));
+		Ok(share_increment)
+	}
+
+	/// Get the count of currencies in the stable swap pool, and the indexes of
+	/// `currency_id_a` and `currency_id_b` in it.
+	fn stable_asset_pool_indexes(
+		pool_id: StableAssetPoolId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+	) -> sp_std::result::Result<(usize, usize, usize), DispatchError> {
+		let assets = T::StableAsset::get_pool_assets(pool_id).ok_or(Error::<T>::InvalidStableAssetPool)?;
+		let index_a = assets
+			.iter()
+			.position(|currency_id| *currency_id == currency_id_a)
+			.ok_or(Error::<T>::InvalidStableAssetPool)?;
+		let index_b = assets
+			.iter()
+			.position(|currency_id| *currency_id == currency_id_b)
+			.ok_or(Error::<T>::InvalidStableAssetPool)?;
+		Ok((assets.len(), index_a, index_b))
+	}
+
 	fn get_liquidity(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
 		if let Some(trading_pair) = TradingPair::from_currency_ids(currency_id_a, currency_id_b) {
 			let (pool_0, pool_1) = Self::liquidity_pool(trading_pair);
@@ -1008,6 +1632,36 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Get the cumulative prices of the trading pair up to `now`, the prices
+	/// since the last accumulation are from the current liquidity pool.
+	fn accumulated_prices(trading_pair: TradingPair, now: T::BlockNumber) -> (U256, U256) {
+		let (cumulative_0, cumulative_1, last_accumulated) = Self::cumulative_prices(trading_pair);
+		let elapsed = U256::from(now.saturating_sub(last_accumulated).saturated_into::<u128>());
+		let (pool_0, pool_1) = Self::liquidity_pool(trading_pair);
+		let increment = |supply_pool: Balance, target_pool: Balance| -> U256 {
+			Price::checked_from_rational(target_pool, supply_pool).map_or_else(U256::zero, |price| {
+				U256::from(price.into_inner()).saturating_mul(elapsed)
+			})
+		};
+		(
+			cumulative_0.saturating_add(increment(pool_0, pool_1)),
+			cumulative_1.saturating_add(increment(pool_1, pool_0)),
+		)
+	}
+
+	/// Accumulate the prices of the trading pair on the first change of the
+	/// liquidity pool in a block, must be called before the liquidity pool is
+	/// changed, so the prices at the end of the previous block are
+	/// accumulated, and the changes in the current block are not.
+	fn update_cumulative_prices(trading_pair: TradingPair) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let (_, _, last_accumulated) = Self::cumulative_prices(trading_pair);
+		if last_accumulated < now {
+			let (cumulative_0, cumulative_1) = Self::accumulated_prices(trading_pair, now);
+			CumulativePrices::<T>::insert(trading_pair, (cumulative_0, cumulative_1, now));
+		}
+	}
+
 	/// Get how much target amount will be got for specific supply amount.
 	fn get_target_amount(supply_pool: Balance, target_pool: Balance, supply_amount: Balance) -> Balance {
 		if supply_amount.is_zero() || supply_pool.is_zero() || target_pool.is_zero() {
@@ -1125,6 +1779,38 @@ impl<T: Config> Pallet<T> {
 		Ok(supply_amounts)
 	}
 
+	/// Split the protocol fee from the supply amount of a swap, returns the
+	/// protocol fee and the amount left to swap through the liquidity pools.
+	fn split_protocol_fee(supply_amount: Balance) -> (Balance, Balance) {
+		let protocol_fee = Self::protocol_fee_rate().saturating_mul_int(supply_amount);
+		(protocol_fee, supply_amount.saturating_sub(protocol_fee))
+	}
+
+	/// Get the protocol fee to add to the amount swapped through the liquidity
+	/// pools, so that the fee is the protocol fee rate of the supply amount.
+	fn protocol_fee_on_top(pool_supply_amount: Balance) -> Balance {
+		let protocol_fee_rate = Self::protocol_fee_rate();
+		if protocol_fee_rate.is_zero() {
+			return Zero::zero();
+		}
+
+		Ratio::one()
+			.saturating_sub(protocol_fee_rate)
+			.reciprocal()
+			.map(|r| r.saturating_mul_int(pool_supply_amount))
+			.unwrap_or_default()
+			.saturating_sub(pool_supply_amount)
+	}
+
+	/// Charge the protocol fee of a swap from `who`.
+	fn charge_protocol_fee(who: &T::AccountId, currency_id: CurrencyId, protocol_fee: Balance) -> DispatchResult {
+		if !protocol_fee.is_zero() {
+			T::Currency::transfer(currency_id, who, &T::ProtocolFeeReceiver::get(), protocol_fee)?;
+			T::OnProtocolFee::happened(&(currency_id, protocol_fee));
+		}
+		Ok(())
+	}
+
 	fn _swap(
 		supply_currency_id: CurrencyId,
 		target_currency_id: CurrencyId,
@@ -1132,6 +1818,7 @@ impl<T: Config> Pallet<T> {
 		target_decrement: Balance,
 	) -> DispatchResult {
 		if let Some(trading_pair) = TradingPair::from_currency_ids(supply_currency_id, target_currency_id) {
+			Self::update_cumulative_prices(trading_pair);
 			LiquidityPool::<T>::try_mutate(trading_pair, |(pool_0, pool_1)| -> DispatchResult {
 				let invariant_before_swap: U256 = U256::from(*pool_0).saturating_mul(U256::from(*pool_1));
 
@@ -1155,6 +1842,277 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Check the swap of `who` against the swap limits of the trading pairs
+	/// in path, must be called before the liquidity pools are changed.
+	fn check_swap_limits(who: &T::AccountId, path: &[CurrencyId], amounts: &[Balance]) -> DispatchResult {
+		let mut i: usize = 0;
+		while i + 1 < path.len() {
+			let trading_pair =
+				TradingPair::from_currency_ids(path[i], path[i + 1]).ok_or(Error::<T>::InvalidCurrencyId)?;
+			if let Some(swap_limits) = Self::trading_pair_swap_limits(trading_pair) {
+				let (supply_amount, target_amount) = (amounts[i], amounts[i + 1]);
+				let (supply_pool, target_pool) = Self::get_liquidity(path[i], path[i + 1]);
+
+				// price impact = 1 - execution price / spot price
+				let price_impact = Ratio::checked_from_rational(target_amount, supply_amount)
+					.zip(Ratio::checked_from_rational(target_pool, supply_pool))
+					.and_then(|(execution_price, spot_price)| execution_price.checked_div(&spot_price))
+					.map(|ratio| Ratio::one().saturating_sub(ratio))
+					.ok_or(Error::<T>::InsufficientLiquidity)?;
+				ensure!(
+					price_impact <= swap_limits.max_price_impact,
+					Error::<T>::ExceedMaxPriceImpact
+				);
+
+				let volume = if path[i] == trading_pair.first() {
+					supply_amount
+				} else {
+					target_amount
+				};
+				SwapVolumes::<T>::try_mutate(trading_pair, who, |swapped| -> DispatchResult {
+					*swapped = swapped.saturating_add(volume);
+					ensure!(
+						*swapped <= swap_limits.max_volume_per_block,
+						Error::<T>::ExceedSwapVolumeLimit
+					);
+					Ok(())
+				})?;
+			}
+			i += 1;
+		}
+		Ok(())
+	}
+
+	/// Ensure all the trading pairs in path are in instant execution mode,
+	/// swaps of users through trading pairs in batch auction mode must be
+	/// submitted as batch swap orders. The swaps of other modules through
+	/// `DEXManager` are executed instantly in any mode.
+	fn ensure_instant_execution(path: &[CurrencyId]) -> DispatchResult {
+		let mut i: usize = 0;
+		while i + 1 < path.len() {
+			let trading_pair =
+				TradingPair::from_currency_ids(path[i], path[i + 1]).ok_or(Error::<T>::InvalidCurrencyId)?;
+			ensure!(
+				matches!(Self::trading_pair_execution_modes(trading_pair), ExecutionMode::Instant),
+				Error::<T>::MustBeInstantMode
+			);
+			i += 1;
+		}
+		Ok(())
+	}
+
+	/// Calculate the clearing of a batch auction with the total supply amounts
+	/// of both sides. The orders of both sides are matched against each other
+	/// at a uniform price, and the net imbalance is swapped through the
+	/// liquidity pool at the same price.
+	///
+	/// Returns the total target amounts `(target_0, target_1)` shared by the
+	/// orders supplying `currency_1` and `currency_0` respectively, and the
+	/// `(supply_currency_id, target_currency_id, supply_amount,
+	/// target_amount)` swapped through the liquidity pool.
+	fn calculate_batch_clearing(
+		trading_pair: TradingPair,
+		supply_0: Balance,
+		supply_1: Balance,
+	) -> sp_std::result::Result<((Balance, Balance), Option<(CurrencyId, CurrencyId, Balance, Balance)>), DispatchError>
+	{
+		let (pool_0, pool_1) = Self::liquidity_pool(trading_pair);
+		ensure!(
+			!pool_0.is_zero() && !pool_1.is_zero(),
+			Error::<T>::InsufficientLiquidity
+		);
+		let (fee_numerator, fee_denominator) = T::GetExchangeFee::get();
+		let fee_denominator = U256::from(fee_denominator);
+		let fee_complement = fee_denominator.saturating_sub(U256::from(fee_numerator));
+
+		// the net supply amount of side A through the pool, if side A is the net seller.
+		let net_supply = |supply_a: Balance, supply_b: Balance, pool_a: Balance, pool_b: Balance| -> Option<Balance> {
+			// side A is the net seller if supply_a / supply_b > pool_a / (pool_b * (1 - fee))
+			let is_net_seller = U256::from(supply_a)
+				.saturating_mul(fee_complement)
+				.saturating_mul(U256::from(pool_b))
+				> U256::from(supply_b)
+					.saturating_mul(fee_denominator)
+					.saturating_mul(U256::from(pool_a));
+			if !is_net_seller {
+				return None;
+			}
+
+			// the uniform price is p = (1 - fee) * (pool_b + supply_b) / (pool_a + (1 - fee) * supply_a),
+			// at which supply_b / p of side A is matched with side B and the rest goes through the pool.
+			let matched: Balance = U256::from(supply_b)
+				.saturating_mul(
+					U256::from(pool_a)
+						.saturating_mul(fee_denominator)
+						.saturating_add(U256::from(supply_a).saturating_mul(fee_complement)),
+				)
+				.checked_div(fee_complement.saturating_mul(U256::from(pool_b).saturating_add(U256::from(supply_b))))
+				.and_then(|n| TryInto::<Balance>::try_into(n).ok())?;
+			Some(supply_a.saturating_sub(matched))
+		};
+
+		if let Some(net_supply_0) = net_supply(supply_0, supply_1, pool_0, pool_1) {
+			let target_amount = Self::get_target_amount(pool_0, pool_1, net_supply_0);
+			Ok((
+				(
+					supply_0.saturating_sub(net_supply_0),
+					supply_1.saturating_add(target_amount),
+				),
+				Some((trading_pair.first(), trading_pair.second(), net_supply_0, target_amount)),
+			))
+		} else if let Some(net_supply_1) = net_supply(supply_1, supply_0, pool_1, pool_0) {
+			let target_amount = Self::get_target_amount(pool_1, pool_0, net_supply_1);
+			Ok((
+				(
+					supply_0.saturating_add(target_amount),
+					supply_1.saturating_sub(net_supply_1),
+				),
+				Some((trading_pair.second(), trading_pair.first(), net_supply_1, target_amount)),
+			))
+		} else {
+			// both sides are crossed within the fee range of the pool price.
+			Ok(((supply_0, supply_1), None))
+		}
+	}
+
+	/// Settle the batch auction of `trading_pair`. The orders which can't
+	/// accept the clearing price are refunded and the clearing is
+	/// recalculated without them, all orders are refunded if the settlement
+	/// fails. Returns the weight of the rounds of settlement.
+	fn settle_batch_auction(trading_pair: TradingPair, orders: Vec<BatchSwapOrder<T::AccountId>>) -> Weight {
+		let mut orders = orders;
+		let mut weight: Weight = 0;
+		while !orders.is_empty() {
+			weight = weight.saturating_add(T::WeightInfo::settle_batch_auction(orders.len() as u32));
+			match Self::do_settle_batch_auction(trading_pair, &orders) {
+				Ok(unacceptable) if unacceptable.is_empty() => return weight,
+				Ok(unacceptable) => {
+					let (rejected, remaining): (Vec<_>, Vec<_>) = orders
+						.into_iter()
+						.enumerate()
+						.partition(|(index, _)| unacceptable.contains(index));
+					rejected
+						.into_iter()
+						.for_each(|(_, order)| Self::refund_batch_swap_order(order));
+					orders = remaining.into_iter().map(|(_, order)| order).collect();
+				}
+				Err(e) => {
+					log::warn!(
+						target: "dex",
+						"settle batch auction of {:?} failed: {:?}. \
+						This is unexpected but should be safe",
+						trading_pair, e
+					);
+					orders.into_iter().for_each(Self::refund_batch_swap_order);
+					return weight;
+				}
+			}
+		}
+		weight
+	}
+
+	/// Settle the orders at the uniform clearing price if all of them accept
+	/// it, otherwise returns the indexes of the unacceptable orders without
+	/// any changes.
+	#[transactional]
+	fn do_settle_batch_auction(
+		trading_pair: TradingPair,
+		orders: &[BatchSwapOrder<T::AccountId>],
+	) -> sp_std::result::Result<Vec<usize>, DispatchError> {
+		let (supply_0, supply_1) = orders
+			.iter()
+			.fold((0, 0), |(supply_0, supply_1): (Balance, Balance), order| {
+				if order.supply_currency_id == trading_pair.first() {
+					(supply_0.saturating_add(order.supply_amount), supply_1)
+				} else {
+					(supply_0, supply_1.saturating_add(order.supply_amount))
+				}
+			});
+		let ((target_0, target_1), pool_swap) = Self::calculate_batch_clearing(trading_pair, supply_0, supply_1)?;
+
+		// every order shares the total target amount of its side pro rata, so they are
+		// all settled at the uniform clearing price.
+		let target_amounts: Vec<(CurrencyId, Balance)> = orders
+			.iter()
+			.map(|order| {
+				let (target_currency_id, total_supply, total_target) =
+					if order.supply_currency_id == trading_pair.first() {
+						(trading_pair.second(), supply_0, target_1)
+					} else {
+						(trading_pair.first(), supply_1, target_0)
+					};
+				let target_amount = U256::from(order.supply_amount)
+					.saturating_mul(U256::from(total_target))
+					.checked_div(U256::from(total_supply))
+					.and_then(|n| TryInto::<Balance>::try_into(n).ok())
+					.unwrap_or_else(Zero::zero);
+				(target_currency_id, target_amount)
+			})
+			.collect();
+
+		let unacceptable: Vec<usize> = orders
+			.iter()
+			.zip(target_amounts.iter())
+			.enumerate()
+			.filter(|(_, (order, (_, target_amount)))| {
+				target_amount.is_zero() || *target_amount < order.min_target_amount
+			})
+			.map(|(index, _)| index)
+			.collect();
+		if !unacceptable.is_empty() {
+			return Ok(unacceptable);
+		}
+
+		if let Some((supply_currency_id, target_currency_id, supply_amount, target_amount)) = pool_swap {
+			if !supply_amount.is_zero() {
+				ensure!(!target_amount.is_zero(), Error::<T>::ZeroTargetAmount);
+				Self::_swap(supply_currency_id, target_currency_id, supply_amount, target_amount)?;
+			}
+		}
+
+		let module_account_id = Self::account_id();
+		for (order, (target_currency_id, target_amount)) in orders.iter().zip(target_amounts.into_iter()) {
+			T::Currency::transfer(target_currency_id, &module_account_id, &order.who, target_amount)?;
+			Self::deposit_event(Event::BatchSwapOrderSettled(
+				order.who.clone(),
+				order.supply_currency_id,
+				order.supply_amount,
+				target_currency_id,
+				target_amount,
+			));
+		}
+
+		Self::deposit_event(Event::BatchAuctionCleared(
+			trading_pair,
+			supply_0,
+			supply_1,
+			target_0,
+			target_1,
+		));
+		Ok(vec![])
+	}
+
+	fn refund_batch_swap_order(order: BatchSwapOrder<T::AccountId>) {
+		match T::Currency::transfer(
+			order.supply_currency_id,
+			&Self::account_id(),
+			&order.who,
+			order.supply_amount,
+		) {
+			Ok(_) => Self::deposit_event(Event::BatchSwapOrderRefunded(
+				order.who,
+				order.supply_currency_id,
+				order.supply_amount,
+			)),
+			Err(e) => log::warn!(
+				target: "dex",
+				"refund batch swap order of {:?} failed: {:?}. \
+				This is unexpected but should be safe",
+				order.who, e
+			),
+		}
+	}
+
 	fn _swap_by_path(path: &[CurrencyId], amounts: &[Balance]) -> DispatchResult {
 		let mut i: usize = 0;
 		while i + 1 < path.len() {
@@ -1179,7 +2137,8 @@ impl<T: Config> Pallet<T> {
 		supply_amount: Balance,
 		min_target_amount: Balance,
 	) -> sp_std::result::Result<Balance, DispatchError> {
-		let amounts = Self::get_target_amounts(path, supply_amount)?;
+		let (protocol_fee, pool_supply_amount) = Self::split_protocol_fee(supply_amount);
+		let amounts = Self::get_target_amounts(path, pool_supply_amount)?;
 		ensure!(
 			amounts[amounts.len() - 1] >= min_target_amount,
 			Error::<T>::InsufficientTargetAmount
@@ -1187,7 +2146,9 @@ impl<T: Config> Pallet<T> {
 		let module_account_id = Self::account_id();
 		let actual_target_amount = amounts[amounts.len() - 1];
 
-		T::Currency::transfer(path[0], who, &module_account_id, supply_amount)?;
+		Self::charge_protocol_fee(who, path[0], protocol_fee)?;
+		T::Currency::transfer(path[0], who, &module_account_id, pool_supply_amount)?;
+		Self::check_swap_limits(who, path, &amounts)?;
 		Self::_swap_by_path(path, &amounts)?;
 		T::Currency::transfer(path[path.len() - 1], &module_account_id, who, actual_target_amount)?;
 
@@ -1204,11 +2165,17 @@ impl<T: Config> Pallet<T> {
 		max_supply_amount: Balance,
 	) -> sp_std::result::Result<Balance, DispatchError> {
 		let amounts = Self::get_supply_amounts(path, target_amount)?;
-		ensure!(amounts[0] <= max_supply_amount, Error::<T>::ExcessiveSupplyAmount);
+		let protocol_fee = Self::protocol_fee_on_top(amounts[0]);
+		let actual_supply_amount = amounts[0].saturating_add(protocol_fee);
+		ensure!(
+			actual_supply_amount <= max_supply_amount,
+			Error::<T>::ExcessiveSupplyAmount
+		);
 		let module_account_id = Self::account_id();
-		let actual_supply_amount = amounts[0];
 
-		T::Currency::transfer(path[0], who, &module_account_id, actual_supply_amount)?;
+		Self::charge_protocol_fee(who, path[0], protocol_fee)?;
+		T::Currency::transfer(path[0], who, &module_account_id, amounts[0])?;
+		Self::check_swap_limits(who, path, &amounts)?;
 		Self::_swap_by_path(path, &amounts)?;
 		T::Currency::transfer(path[path.len() - 1], &module_account_id, who, target_amount)?;
 
@@ -1228,15 +2195,20 @@ impl<T: Config> DEXManager<T::AccountId, CurrencyId, Balance> for Pallet<T> {
 	}
 
 	fn get_swap_target_amount(path: &[CurrencyId], supply_amount: Balance) -> Option<Balance> {
-		Self::get_target_amounts(path, supply_amount)
+		let (_, pool_supply_amount) = Self::split_protocol_fee(supply_amount);
+		Self::get_target_amounts(path, pool_supply_amount)
 			.ok()
 			.map(|amounts| amounts[amounts.len() - 1])
 	}
 
+	fn is_instant_execution(path: &[CurrencyId]) -> bool {
+		Self::ensure_instant_execution(path).is_ok()
+	}
+
 	fn get_swap_supply_amount(path: &[CurrencyId], target_amount: Balance) -> Option<Balance> {
 		Self::get_supply_amounts(path, target_amount)
 			.ok()
-			.map(|amounts| amounts[0])
+			.map(|amounts| amounts[0].saturating_add(Self::protocol_fee_on_top(amounts[0])))
 	}
 
 	fn swap_with_exact_supply(
@@ -1280,6 +2252,7 @@ impl<T: Config> DEXManager<T::AccountId, CurrencyId, Balance> for Pallet<T> {
 			min_share_increment,
 			stake_increment_share,
 		)
+		.map(|_| ())
 	}
 
 	fn remove_liquidity(
@@ -1300,5 +2273,19 @@ impl<T: Config> DEXManager<T::AccountId, CurrencyId, Balance> for Pallet<T> {
 			min_withdrawn_b,
 			by_unstake,
 		)
+		.map(|_| ())
+	}
+}
+
+impl<T: Config> DEXPriceCumulative<CurrencyId, T::BlockNumber> for Pallet<T> {
+	fn get_cumulative_price(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<(U256, T::BlockNumber)> {
+		let trading_pair = TradingPair::from_currency_ids(currency_id_a, currency_id_b)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+		let (cumulative_0, cumulative_1) = Self::accumulated_prices(trading_pair, now);
+		if currency_id_a == trading_pair.first() {
+			Some((cumulative_0, now))
+		} else {
+			Some((cumulative_1, now))
+		}
 	}
 }
@@ -26,7 +26,7 @@ use frame_support::{
 	traits::{Everything, Nothing},
 };
 use frame_system::EnsureSignedBy;
-use orml_traits::{parameter_type_with_key, MultiReservableCurrency};
+use orml_traits::{parameter_type_with_key, MultiCurrency, MultiReservableCurrency};
 use primitives::{Amount, TokenSymbol};
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup};
@@ -40,6 +40,8 @@ pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
 pub const BTC: CurrencyId = CurrencyId::Token(TokenSymbol::RENBTC);
 pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
 pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const STABLE_POOL_TOKEN: CurrencyId = CurrencyId::StableAssetPoolToken(0);
+pub const STABLE_POOL_ACCOUNT: AccountId = 100;
 
 parameter_types! {
 	pub static AUSDBTCPair: TradingPair = TradingPair::from_currency_ids(AUSD, BTC).unwrap();
@@ -109,6 +111,84 @@ impl DEXIncentives<AccountId, CurrencyId, Balance> for MockDEXIncentives {
 		let _ = Tokens::unreserve(lp_currency_id, who, amount);
 		Ok(())
 	}
+
+	fn do_claim_dex_share_rewards(_who: &AccountId, _lp_currency_id: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// A stable swap pool of AUSD and DOT with pool id 0, which mints one pool
+/// token for each supplied currency unit and swaps them 1:1.
+pub struct MockStableAsset;
+impl StableAssetPool<AccountId, CurrencyId, Balance> for MockStableAsset {
+	fn get_pool_assets(pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>> {
+		if pool_id == 0 {
+			Some(vec![AUSD, DOT])
+		} else {
+			None
+		}
+	}
+
+	fn mint(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amounts: Vec<Balance>,
+		min_mint_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let assets = Self::get_pool_assets(pool_id).ok_or(DispatchError::Other("invalid pool"))?;
+		let mint_amount: Balance = amounts.iter().sum();
+		ensure!(
+			mint_amount >= min_mint_amount,
+			DispatchError::Other("mint amount too small")
+		);
+		for (currency_id, amount) in assets.iter().zip(amounts) {
+			<Tokens as MultiCurrency<AccountId>>::transfer(*currency_id, who, &STABLE_POOL_ACCOUNT, amount)?;
+		}
+		Tokens::deposit(STABLE_POOL_TOKEN, who, mint_amount)?;
+		Ok(mint_amount)
+	}
+
+	fn redeem_proportion(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amount: Balance,
+		_min_redeem_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError> {
+		let assets = Self::get_pool_assets(pool_id).ok_or(DispatchError::Other("invalid pool"))?;
+		let proportion = Ratio::checked_from_rational(amount, Tokens::total_issuance(STABLE_POOL_TOKEN))
+			.ok_or(ArithmeticError::Overflow)?;
+		Tokens::withdraw(STABLE_POOL_TOKEN, who, amount)?;
+		let mut redeemed = vec![];
+		for currency_id in assets {
+			let redeem_amount = proportion.saturating_mul_int(Tokens::free_balance(currency_id, &STABLE_POOL_ACCOUNT));
+			<Tokens as MultiCurrency<AccountId>>::transfer(currency_id, &STABLE_POOL_ACCOUNT, who, redeem_amount)?;
+			redeemed.push(redeem_amount);
+		}
+		Ok(redeemed)
+	}
+
+	fn swap(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		i: u32,
+		j: u32,
+		dx: Balance,
+		min_dy: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let assets = Self::get_pool_assets(pool_id).ok_or(DispatchError::Other("invalid pool"))?;
+		ensure!(dx >= min_dy, DispatchError::Other("swap output too small"));
+		<Tokens as MultiCurrency<AccountId>>::transfer(assets[i as usize], who, &STABLE_POOL_ACCOUNT, dx)?;
+		<Tokens as MultiCurrency<AccountId>>::transfer(assets[j as usize], &STABLE_POOL_ACCOUNT, who, dx)?;
+		Ok(dx)
+	}
+
+	fn get_swap_output_amount(pool_id: StableAssetPoolId, i: u32, j: u32, dx: Balance) -> Option<Balance> {
+		let assets = Self::get_pool_assets(pool_id)?;
+		if i as usize >= assets.len() || j as usize >= assets.len() {
+			return None;
+		}
+		Some(dx)
+	}
 }
 
 ord_parameter_types! {
@@ -118,7 +198,9 @@ ord_parameter_types! {
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (1, 100);
 	pub const TradingPathLimit: u32 = 3;
+	pub const MaxBatchSwapOrders: u32 = 10;
 	pub const DEXPalletId: PalletId = PalletId(*b"aca/dexm");
+	pub const ProtocolFeeReceiver: AccountId = 4;
 }
 
 impl Config for Runtime {
@@ -126,11 +208,15 @@ impl Config for Runtime {
 	type Currency = Tokens;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = ();
 	type WeightInfo = ();
 	type DEXIncentives = MockDEXIncentives;
 	type ListingOrigin = EnsureSignedBy<ListingOrigin, AccountId>;
+	type StableAsset = MockStableAsset;
+	type ProtocolFeeReceiver = ProtocolFeeReceiver;
+	type OnProtocolFee = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -23,8 +23,8 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{
-	AUSDBTCPair, AUSDDOTPair, DexModule, Event, ExtBuilder, ListingOrigin, Origin, Runtime, System, Tokens, ACA, ALICE,
-	AUSD, BOB, BTC, DOT,
+	AUSDBTCPair, AUSDDOTPair, DexModule, Event, ExtBuilder, ListingOrigin, Origin, ProtocolFeeReceiver, Runtime,
+	System, Tokens, ACA, ALICE, AUSD, BOB, BTC, DOT, STABLE_POOL_TOKEN,
 };
 use orml_traits::MultiReservableCurrency;
 use sp_runtime::traits::BadOrigin;
@@ -610,6 +610,126 @@ fn claim_dex_share_work() {
 	});
 }
 
+#[test]
+fn claim_vested_provision_shares_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(DexModule::list_provisioning(
+			Origin::signed(ListingOrigin::get()),
+			AUSD,
+			DOT,
+			5_000_000_000_000u128,
+			1_000_000_000_000u128,
+			5_000_000_000_000_000u128,
+			1_000_000_000_000_000u128,
+			0,
+		));
+		assert_noop!(
+			DexModule::set_provision_vesting_period(Origin::signed(ALICE), AUSD, DOT, 100),
+			BadOrigin
+		);
+		assert_ok!(DexModule::set_provision_vesting_period(
+			Origin::signed(ListingOrigin::get()),
+			AUSD,
+			DOT,
+			100
+		));
+		System::assert_last_event(Event::DexModule(crate::Event::ProvisionVestingPeriodUpdated(
+			AUSDDOTPair::get(),
+			100,
+		)));
+		assert_eq!(DexModule::provision_vesting_periods(AUSDDOTPair::get()), 100);
+
+		assert_ok!(DexModule::add_provision(
+			Origin::signed(ALICE),
+			AUSD,
+			DOT,
+			1_000_000_000_000_000u128,
+			200_000_000_000_000u128,
+		));
+		assert_ok!(DexModule::add_provision(
+			Origin::signed(BOB),
+			AUSD,
+			DOT,
+			4_000_000_000_000_000u128,
+			800_000_000_000_000u128,
+		));
+		assert_noop!(
+			DexModule::claim_vested_provision_shares(Origin::signed(ALICE), ALICE, AUSD, DOT),
+			Error::<Runtime>::ProvisionSharesNotVesting
+		);
+
+		assert_ok!(DexModule::end_provisioning(
+			Origin::signed(ListingOrigin::get()),
+			AUSD,
+			DOT
+		));
+		assert_eq!(
+			DexModule::provision_vesting_schedules(AUSDDOTPair::get()),
+			Some((1, 100))
+		);
+		assert_eq!(DexModule::provision_vesting_periods(AUSDDOTPair::get()), 0);
+		assert_noop!(
+			DexModule::set_provision_vesting_period(Origin::signed(ListingOrigin::get()), AUSD, DOT, 100),
+			Error::<Runtime>::MustBeProvisioning
+		);
+
+		let lp_currency_id = AUSDDOTPair::get().dex_share_currency_id();
+		assert_noop!(
+			DexModule::claim_dex_share(Origin::signed(ALICE), ALICE, AUSD, DOT),
+			Error::<Runtime>::ProvisionSharesStillVesting
+		);
+
+		System::set_block_number(26);
+		assert_ok!(DexModule::claim_vested_provision_shares(
+			Origin::signed(ALICE),
+			ALICE,
+			AUSD,
+			DOT
+		));
+		System::assert_last_event(Event::DexModule(crate::Event::VestedProvisionSharesClaimed(
+			ALICE,
+			AUSDDOTPair::get(),
+			500_000_000_000_000u128,
+		)));
+		assert_eq!(Tokens::free_balance(lp_currency_id, &ALICE), 500_000_000_000_000u128);
+		assert_eq!(
+			DexModule::claimed_provision_shares(AUSDDOTPair::get(), ALICE),
+			500_000_000_000_000u128
+		);
+
+		System::set_block_number(51);
+		assert_ok!(DexModule::claim_vested_provision_shares(
+			Origin::signed(ALICE),
+			ALICE,
+			AUSD,
+			DOT
+		));
+		assert_eq!(Tokens::free_balance(lp_currency_id, &ALICE), 1_000_000_000_000_000u128);
+
+		// claim all the remaining shares once fully vested
+		System::set_block_number(101);
+		let alice_ref_count_0 = System::consumers(&ALICE);
+		assert_ok!(DexModule::claim_vested_provision_shares(
+			Origin::signed(ALICE),
+			ALICE,
+			AUSD,
+			DOT
+		));
+		assert_eq!(Tokens::free_balance(lp_currency_id, &ALICE), 2_000_000_000_000_000u128);
+		assert_eq!(DexModule::provisioning_pool(AUSDDOTPair::get(), ALICE), (0, 0));
+		assert_eq!(DexModule::claimed_provision_shares(AUSDDOTPair::get(), ALICE), 0);
+		assert_eq!(System::consumers(&ALICE), alice_ref_count_0 - 1);
+
+		assert_ok!(DexModule::claim_dex_share(Origin::signed(BOB), BOB, AUSD, DOT));
+		assert_eq!(Tokens::free_balance(lp_currency_id, &BOB), 8_000_000_000_000_000u128);
+		assert_eq!(Tokens::free_balance(lp_currency_id, &DexModule::account_id()), 0);
+		assert!(!InitialShareExchangeRates::<Runtime>::contains_key(AUSDDOTPair::get()));
+		assert_eq!(DexModule::provision_vesting_schedules(AUSDDOTPair::get()), None);
+	});
+}
+
 #[test]
 fn get_liquidity_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -1079,6 +1199,121 @@ fn remove_liquidity_work() {
 		});
 }
 
+#[test]
+fn migrate_liquidity_work() {
+	ExtBuilder::default()
+		.initialize_enabled_trading_pairs()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(DexModule::add_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				5_000_000_000_000,
+				1_000_000_000_000,
+				0,
+				false
+			));
+			assert_noop!(
+				DexModule::migrate_liquidity(
+					Origin::signed(ALICE),
+					AUSD,
+					DOT,
+					PoolType::ConstantProduct,
+					PoolType::ConstantProduct,
+					8_000_000_000_000,
+					0,
+					false,
+				),
+				Error::<Runtime>::InvalidPoolType
+			);
+			assert_noop!(
+				DexModule::migrate_liquidity(
+					Origin::signed(ALICE),
+					AUSD,
+					DOT,
+					PoolType::ConstantProduct,
+					PoolType::StableSwap(1),
+					8_000_000_000_000,
+					0,
+					false,
+				),
+				Error::<Runtime>::InvalidStableAssetPool
+			);
+
+			assert_ok!(DexModule::migrate_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				PoolType::ConstantProduct,
+				PoolType::StableSwap(0),
+				8_000_000_000_000,
+				4_800_000_000_000,
+				false,
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::LiquidityMigrated(
+				ALICE,
+				AUSDDOTPair::get(),
+				PoolType::ConstantProduct,
+				PoolType::StableSwap(0),
+				8_000_000_000_000,
+				4_800_000_000_000,
+			)));
+			assert_eq!(
+				DexModule::get_liquidity(AUSD, DOT),
+				(1_000_000_000_000, 200_000_000_000)
+			);
+			assert_eq!(
+				Tokens::free_balance(AUSDDOTPair::get().dex_share_currency_id(), &ALICE),
+				2_000_000_000_000
+			);
+			assert_eq!(Tokens::free_balance(STABLE_POOL_TOKEN, &ALICE), 4_800_000_000_000);
+
+			assert_noop!(
+				DexModule::migrate_liquidity(
+					Origin::signed(ALICE),
+					AUSD,
+					DOT,
+					PoolType::StableSwap(0),
+					PoolType::ConstantProduct,
+					4_800_000_000_000,
+					8_000_000_000_001,
+					true,
+				),
+				Error::<Runtime>::UnacceptableShareIncrement
+			);
+			assert_ok!(DexModule::migrate_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				PoolType::StableSwap(0),
+				PoolType::ConstantProduct,
+				4_800_000_000_000,
+				8_000_000_000_000,
+				true,
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::LiquidityMigrated(
+				ALICE,
+				AUSDDOTPair::get(),
+				PoolType::StableSwap(0),
+				PoolType::ConstantProduct,
+				4_800_000_000_000,
+				8_000_000_000_000,
+			)));
+			assert_eq!(
+				DexModule::get_liquidity(AUSD, DOT),
+				(5_000_000_000_000, 1_000_000_000_000)
+			);
+			assert_eq!(Tokens::free_balance(STABLE_POOL_TOKEN, &ALICE), 0);
+			assert_eq!(
+				Tokens::reserved_balance(AUSDDOTPair::get().dex_share_currency_id(), &ALICE),
+				8_000_000_000_000
+			);
+		});
+}
+
 #[test]
 fn do_swap_with_exact_supply_work() {
 	ExtBuilder::default()
@@ -1338,3 +1573,360 @@ fn initialize_added_liquidity_pools_genesis_work() {
 			);
 		});
 }
+
+#[test]
+fn swap_limits_work() {
+	ExtBuilder::default()
+		.initialize_enabled_trading_pairs()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(DexModule::add_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				500_000_000_000_000,
+				100_000_000_000_000,
+				0,
+				false,
+			));
+
+			let swap_limits = SwapLimits {
+				max_price_impact: Ratio::saturating_from_rational(5, 100),
+				max_volume_per_block: 5_000_000_000_000,
+			};
+			assert_noop!(
+				DexModule::set_swap_limits(Origin::signed(ALICE), AUSD, DOT, Some(swap_limits)),
+				BadOrigin
+			);
+			assert_ok!(DexModule::set_swap_limits(
+				Origin::signed(ListingOrigin::get()),
+				AUSD,
+				DOT,
+				Some(swap_limits)
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::SwapLimitsUpdated(
+				AUSDDOTPair::get(),
+				Some(swap_limits),
+			)));
+			assert_eq!(
+				DexModule::trading_pair_swap_limits(AUSDDOTPair::get()),
+				Some(swap_limits)
+			);
+
+			// price impact is about 9.9%
+			assert_noop!(
+				DexModule::do_swap_with_exact_supply(&BOB, &[DOT, AUSD], 10_000_000_000_000, 0),
+				Error::<Runtime>::ExceedMaxPriceImpact
+			);
+
+			// price impact is about 1.97%
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				0
+			));
+			assert_eq!(DexModule::swap_volumes(AUSDDOTPair::get(), BOB), 4_901_475_393_603);
+
+			// the volume of BOB in this block exceeds the limit
+			assert_noop!(
+				DexModule::do_swap_with_exact_supply(&BOB, &[DOT, AUSD], 1_000_000_000_000, 0),
+				Error::<Runtime>::ExceedSwapVolumeLimit
+			);
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&ALICE,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				0
+			));
+
+			// volumes are cleared on the initialization of the next block
+			DexModule::on_initialize(2);
+			assert_eq!(DexModule::swap_volumes(AUSDDOTPair::get(), BOB), 0);
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				0
+			));
+
+			assert_ok!(DexModule::set_swap_limits(
+				Origin::signed(ListingOrigin::get()),
+				AUSD,
+				DOT,
+				None
+			));
+			assert_eq!(DexModule::trading_pair_swap_limits(AUSDDOTPair::get()), None);
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				10_000_000_000_000,
+				0
+			));
+		});
+}
+
+#[test]
+fn protocol_fee_work() {
+	ExtBuilder::default()
+		.initialize_enabled_trading_pairs()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(DexModule::add_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				500_000_000_000_000,
+				100_000_000_000_000,
+				0,
+				false,
+			));
+
+			assert_noop!(
+				DexModule::set_protocol_fee_rate(Origin::signed(ALICE), Ratio::saturating_from_rational(1, 100)),
+				BadOrigin
+			);
+			assert_noop!(
+				DexModule::set_protocol_fee_rate(Origin::signed(ListingOrigin::get()), Ratio::one()),
+				Error::<Runtime>::InvalidProtocolFeeRate
+			);
+			assert_ok!(DexModule::set_protocol_fee_rate(
+				Origin::signed(ListingOrigin::get()),
+				Ratio::saturating_from_rational(1, 100)
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::ProtocolFeeRateUpdated(
+				Ratio::saturating_from_rational(1, 100),
+			)));
+
+			// 1% of the supply amount is charged as protocol fee
+			let target_amount = DexModule::get_swap_target_amount(&[DOT, AUSD], 1_000_000_000_000).unwrap();
+			assert_eq!(
+				target_amount,
+				DexModule::get_target_amounts(&[DOT, AUSD], 990_000_000_000).unwrap()[1]
+			);
+			let bob_ausd = Tokens::free_balance(AUSD, &BOB);
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				target_amount
+			));
+			assert_eq!(Tokens::free_balance(AUSD, &BOB), bob_ausd + target_amount);
+			assert_eq!(Tokens::free_balance(DOT, &ProtocolFeeReceiver::get()), 10_000_000_000);
+			assert_eq!(DexModule::get_liquidity(DOT, AUSD).0, 100_990_000_000_000);
+
+			// the protocol fee is added to the supply amount of exact target swaps
+			let supply_amount = DexModule::get_swap_supply_amount(&[AUSD, DOT], 1_000_000_000_000).unwrap();
+			let pool_supply_amount = DexModule::get_supply_amounts(&[AUSD, DOT], 1_000_000_000_000).unwrap()[0];
+			assert_eq!(
+				supply_amount - pool_supply_amount,
+				DexModule::protocol_fee_on_top(pool_supply_amount)
+			);
+			assert_ok!(DexModule::do_swap_with_exact_target(
+				&BOB,
+				&[AUSD, DOT],
+				1_000_000_000_000,
+				supply_amount
+			));
+			assert_eq!(
+				Tokens::free_balance(AUSD, &BOB),
+				bob_ausd + target_amount - supply_amount
+			);
+			assert_eq!(
+				Tokens::free_balance(AUSD, &ProtocolFeeReceiver::get()),
+				supply_amount - pool_supply_amount
+			);
+		});
+}
+
+#[test]
+fn batch_auction_work() {
+	ExtBuilder::default()
+		.initialize_enabled_trading_pairs()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(DexModule::add_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				500_000_000_000_000,
+				100_000_000_000_000,
+				0,
+				false,
+			));
+
+			assert_noop!(
+				DexModule::submit_batch_swap_order(Origin::signed(BOB), DOT, AUSD, 1_000_000_000_000, 0),
+				Error::<Runtime>::MustBeBatchAuctionMode
+			);
+			assert_noop!(
+				DexModule::set_execution_mode(
+					Origin::signed(ALICE),
+					AUSD,
+					DOT,
+					ExecutionMode::BatchAuction { max_orders: 2 }
+				),
+				BadOrigin
+			);
+			assert_noop!(
+				DexModule::set_execution_mode(
+					Origin::signed(ListingOrigin::get()),
+					AUSD,
+					DOT,
+					ExecutionMode::BatchAuction { max_orders: 11 }
+				),
+				Error::<Runtime>::InvalidExecutionMode
+			);
+			assert_ok!(DexModule::set_execution_mode(
+				Origin::signed(ListingOrigin::get()),
+				AUSD,
+				DOT,
+				ExecutionMode::BatchAuction { max_orders: 2 }
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::ExecutionModeUpdated(
+				AUSDDOTPair::get(),
+				ExecutionMode::BatchAuction { max_orders: 2 },
+			)));
+
+			// instant swaps of users through the trading pair are not allowed
+			assert!(!DexModule::is_instant_execution(&[DOT, AUSD]));
+			assert_noop!(
+				DexModule::swap_with_exact_supply(Origin::signed(BOB), vec![DOT, AUSD], 1_000_000_000_000, 0),
+				Error::<Runtime>::MustBeInstantMode
+			);
+
+			let alice_ausd = Tokens::free_balance(AUSD, &ALICE);
+			let alice_dot = Tokens::free_balance(DOT, &ALICE);
+			let bob_ausd = Tokens::free_balance(AUSD, &BOB);
+			let bob_dot = Tokens::free_balance(DOT, &BOB);
+			assert_ok!(DexModule::submit_batch_swap_order(
+				Origin::signed(BOB),
+				DOT,
+				AUSD,
+				1_000_000_000_000,
+				0
+			));
+			assert_ok!(DexModule::submit_batch_swap_order(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				2_000_000_000_000,
+				0
+			));
+			System::assert_last_event(Event::DexModule(crate::Event::BatchSwapOrderSubmitted(
+				ALICE,
+				AUSD,
+				DOT,
+				2_000_000_000_000,
+				0,
+			)));
+			assert_noop!(
+				DexModule::submit_batch_swap_order(Origin::signed(BOB), DOT, AUSD, 1_000_000_000_000, 0),
+				Error::<Runtime>::TooManyBatchSwapOrders
+			);
+			assert_eq!(DexModule::batch_swap_orders(AUSDDOTPair::get()).len(), 2);
+			assert_eq!(Tokens::free_balance(DOT, &BOB), bob_dot - 1_000_000_000_000);
+			assert_eq!(Tokens::free_balance(AUSD, &ALICE), alice_ausd - 2_000_000_000_000);
+
+			// the net imbalance of DOT is swapped through the pool, and both sides are
+			// settled at the same price of about 4.92 AUSD per DOT at the beginning of
+			// the next block
+			DexModule::on_initialize(2);
+			System::assert_last_event(Event::DexModule(crate::Event::BatchAuctionCleared(
+				AUSDDOTPair::get(),
+				2_000_000_000_000,
+				1_000_000_000_000,
+				4_921_081_295_178,
+				406_414_745_060,
+			)));
+			assert!(DexModule::batch_swap_orders(AUSDDOTPair::get()).is_empty());
+			assert_eq!(Tokens::free_balance(AUSD, &BOB), bob_ausd + 4_921_081_295_178);
+			assert_eq!(Tokens::free_balance(DOT, &ALICE), alice_dot + 406_414_745_060);
+			assert_eq!(
+				DexModule::get_liquidity_pool(AUSD, DOT),
+				(497_078_918_704_822, 100_593_585_254_940)
+			);
+
+			// the order which can't accept the clearing price is refunded
+			let bob_dot = Tokens::free_balance(DOT, &BOB);
+			assert_ok!(DexModule::submit_batch_swap_order(
+				Origin::signed(BOB),
+				DOT,
+				AUSD,
+				1_000_000_000_000,
+				5_000_000_000_000
+			));
+			DexModule::on_initialize(3);
+			System::assert_last_event(Event::DexModule(crate::Event::BatchSwapOrderRefunded(
+				BOB,
+				DOT,
+				1_000_000_000_000,
+			)));
+			assert_eq!(Tokens::free_balance(DOT, &BOB), bob_dot);
+			assert_eq!(
+				DexModule::get_liquidity_pool(AUSD, DOT),
+				(497_078_918_704_822, 100_593_585_254_940)
+			);
+
+			// the swaps of other modules through DEXManager are executed instantly
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				0
+			));
+		});
+}
+
+#[test]
+fn cumulative_prices_work() {
+	ExtBuilder::default()
+		.initialize_enabled_trading_pairs()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			assert_eq!(DexModule::get_cumulative_price(DOT, AUSD), Some((U256::zero(), 1)));
+			assert_eq!(DexModule::get_cumulative_price(DOT, DOT), None);
+
+			assert_ok!(DexModule::add_liquidity(
+				Origin::signed(ALICE),
+				AUSD,
+				DOT,
+				5_000_000_000_000,
+				1_000_000_000_000,
+				0,
+				false,
+			));
+			assert_eq!(DexModule::get_cumulative_price(DOT, AUSD), Some((U256::zero(), 1)));
+
+			// the price at the end of block 1 is accumulated for the elapsed blocks
+			System::set_block_number(4);
+			let price_inner = U256::from(Price::saturating_from_integer(5).into_inner());
+			let price_inner_reverse = U256::from(Price::saturating_from_rational(1, 5).into_inner());
+			assert_eq!(DexModule::get_cumulative_price(DOT, AUSD), Some((price_inner * 3, 4)));
+			assert_eq!(
+				DexModule::get_cumulative_price(AUSD, DOT),
+				Some((price_inner_reverse * 3, 4))
+			);
+
+			// the price changed in block 4 is not accumulated in block 4
+			assert_ok!(DexModule::do_swap_with_exact_supply(
+				&BOB,
+				&[DOT, AUSD],
+				1_000_000_000_000,
+				0
+			));
+			assert_eq!(DexModule::get_cumulative_price(DOT, AUSD), Some((price_inner * 3, 4)));
+
+			System::set_block_number(5);
+			let (pool_dot, pool_ausd) = DexModule::get_liquidity(DOT, AUSD);
+			let new_price_inner = U256::from(Price::checked_from_rational(pool_ausd, pool_dot).unwrap().into_inner());
+			assert_eq!(
+				DexModule::get_cumulative_price(DOT, AUSD),
+				Some((price_inner * 3 + new_price_inner, 5))
+			);
+		});
+}
@@ -37,7 +37,7 @@ use frame_system::{ensure_signed, pallet_prelude::*};
 use primitives::{Balance, CurrencyId};
 use sp_runtime::{traits::Zero, FixedPointNumber};
 use sp_std::prelude::*;
-use support::{AuctionManager, CDPTreasury, EmergencyShutdown, LockablePrice, Ratio};
+use support::{AuctionManager, CDPTreasury, EmergencyShutdown, LockablePrice, Ratio, StableCurrencyReserve};
 
 mod mock;
 mod tests;
@@ -72,6 +72,10 @@ pub mod module {
 		/// this.
 		type ShutdownOrigin: EnsureOrigin<Self::Origin>;
 
+		/// The reserve backing the stable currency besides the collaterals,
+		/// refunded with them in the final redemption
+		type StableCurrencyReserve: StableCurrencyReserve<Self::AccountId, CurrencyId, Balance>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -179,10 +183,13 @@ pub mod module {
 			Ok(())
 		}
 
-		/// Refund a basket of remaining collateral assets to caller
+		/// Refund a basket of remaining collateral assets and the reserve
+		/// backing the stable currency to caller
 		///
 		/// - `amount`: stable currency amount used to refund.
-		#[pallet::weight(T::WeightInfo::refund_collaterals(T::CollateralCurrencyIds::get().len() as u32))]
+		#[pallet::weight(T::WeightInfo::refund_collaterals(
+			(T::CollateralCurrencyIds::get().len() + T::StableCurrencyReserve::reserve_currency_ids().len()) as u32
+		))]
 		#[transactional]
 		pub fn refund_collaterals(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
 			let who = ensure_signed(origin)?;
@@ -206,6 +213,15 @@ pub mod module {
 				}
 			}
 
+			// refund the reserve backing the stable currency, such as the reserve of
+			// PSM, in the same proportion
+			for currency_id in T::StableCurrencyReserve::reserve_currency_ids() {
+				let refund_amount = T::StableCurrencyReserve::refund_reserve(&who, currency_id, refund_ratio)?;
+				if !refund_amount.is_zero() {
+					refund_assets.push((currency_id, refund_amount));
+				}
+			}
+
 			Self::deposit_event(Event::Refund(who, amount, refund_assets));
 			Ok(())
 		}
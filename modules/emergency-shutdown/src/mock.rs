@@ -149,6 +149,8 @@ impl loans::Config for Runtime {
 	type CDPTreasury = CDPTreasuryModule;
 	type PalletId = LoansPalletId;
 	type OnUpdateLoan = ();
+	type OnUpdateDebit = ();
+	type OnPositionUpdated = ();
 }
 
 pub struct MockLockablePrice;
@@ -197,25 +199,49 @@ ord_parameter_types! {
 parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 10;
+	pub const OffsetPeriod: BlockNumber = 1;
 	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"aca/cdpt");
 	pub TreasuryAccount: AccountId = PalletId(*b"aca/hztr").into_account();
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(50, 100);
 }
 
 impl cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = MockAuctionManager;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
+	type Swap = ();
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = TreasuryAccount;
+	type PriceSource = ();
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = ();
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = ();
+	type MinimumIdleWorkWeight = ();
+	type EmergencyShutdown = EmergencyShutdownModule;
 	type WeightInfo = ();
 }
 
 ord_parameter_types! {
 	pub const CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![];
 }
 
 impl Config for Runtime {
@@ -225,6 +251,7 @@ impl Config for Runtime {
 	type CDPTreasury = CDPTreasuryModule;
 	type AuctionManagerHandler = MockAuctionManager;
 	type ShutdownOrigin = EnsureSignedBy<One, AccountId>;
+	type StableCurrencyReserve = ();
 	type WeightInfo = ();
 }
 
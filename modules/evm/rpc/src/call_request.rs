@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
@@ -51,3 +51,38 @@ pub struct EstimateResourcesResponse {
 	/// Adjusted weight fee
 	pub weight_fee: U256,
 }
+
+/// Logs filter request
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsRequest {
+	/// From block, defaults to `to_block`
+	pub from_block: Option<u64>,
+	/// To block, defaults to the best block
+	pub to_block: Option<u64>,
+	/// Contract addresses
+	pub address: Option<Vec<H160>>,
+	/// Topics at each position
+	pub topics: Option<Vec<Option<Vec<H256>>>>,
+}
+
+/// Log response
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogResponse<BlockHash> {
+	/// Block hash
+	pub block_hash: BlockHash,
+	/// Block number
+	pub block_number: u64,
+	/// Index of the extrinsic which emitted the log
+	pub extrinsic_index: Option<u32>,
+	/// Index of the log in the block
+	pub log_index: u32,
+	/// Contract address
+	pub address: H160,
+	/// Topics
+	pub topics: Vec<H256>,
+	/// Data
+	pub data: Bytes,
+}
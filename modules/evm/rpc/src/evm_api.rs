@@ -25,7 +25,7 @@ use sp_core::Bytes;
 
 pub use rpc_impl_EVMApi::gen_server::EVMApi as EVMApiServer;
 
-use crate::call_request::{CallRequest, EstimateResourcesResponse};
+use crate::call_request::{CallRequest, EstimateResourcesResponse, LogResponse, LogsRequest};
 
 /// EVM rpc interface.
 #[rpc(server)]
@@ -42,4 +42,9 @@ pub trait EVMApi<BlockHash> {
 		unsigned_extrinsic: Bytes,
 		at: Option<BlockHash>,
 	) -> Result<EstimateResourcesResponse>;
+
+	/// Get the logs matching the filter in a limited range of blocks, the
+	/// filtering is done by the runtime.
+	#[rpc(name = "evm_getLogs")]
+	fn get_logs(&self, _: LogsRequest) -> Result<Vec<LogResponse<BlockHash>>>;
 }
@@ -24,7 +24,7 @@ use jsonrpc_core::{Error, ErrorCode, Result, Value};
 use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
 use rustc_hex::ToHex;
 use sc_rpc_api::DenyUnsafe;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, Decode};
 use sp_rpc::number::NumberOrHex;
@@ -37,8 +37,8 @@ use sp_runtime::{
 use std::convert::{TryFrom, TryInto};
 use std::{marker::PhantomData, sync::Arc};
 
-use call_request::{CallRequest, EstimateResourcesResponse};
-pub use module_evm::{ExitError, ExitReason};
+use call_request::{CallRequest, EstimateResourcesResponse, LogResponse, LogsRequest};
+pub use module_evm::{ExitError, ExitReason, LogFilter};
 pub use module_evm_rpc_runtime_api::EVMRuntimeRPCApi;
 
 pub use crate::evm_api::{EVMApi as EVMApiT, EVMApiServer};
@@ -46,6 +46,9 @@ pub use crate::evm_api::{EVMApi as EVMApiT, EVMApiServer};
 mod call_request;
 mod evm_api;
 
+/// The maximum number of blocks queried by one `evm_getLogs` request.
+pub const MAX_LOGS_BLOCK_RANGE: u64 = 1024;
+
 fn internal_err<T: ToString>(message: T) -> Error {
 	Error {
 		code: ErrorCode::InternalError,
@@ -413,6 +416,67 @@ where
 			weight_fee: adjusted_weight_fee.into(),
 		})
 	}
+
+	fn get_logs(&self, request: LogsRequest) -> Result<Vec<LogResponse<<B as BlockT>::Hash>>> {
+		let best_number: u64 = self.client.info().best_number.saturated_into();
+		let to_block = request.to_block.unwrap_or(best_number);
+		let from_block = request.from_block.unwrap_or(to_block);
+
+		if from_block > to_block || to_block > best_number {
+			return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: format!("Invalid block range: {} - {}", from_block, to_block),
+				data: None,
+			});
+		}
+		if to_block - from_block >= MAX_LOGS_BLOCK_RANGE {
+			return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: format!("Block range exceeds the limit {}", MAX_LOGS_BLOCK_RANGE),
+				data: None,
+			});
+		}
+
+		let filter = LogFilter {
+			addresses: request.address.unwrap_or_default(),
+			topics: request.topics.unwrap_or_default(),
+		};
+		let api = self.client.runtime_api();
+		let mut logs = Vec::new();
+
+		for block_number in from_block..=to_block {
+			let hash = self
+				.client
+				.hash(block_number.saturated_into())
+				.map_err(|err| internal_err(format!("fetch block hash error: {:?}", err)))?
+				.ok_or_else(|| internal_err(format!("block #{} not found", block_number)))?;
+
+			let block_id = BlockId::Hash(hash);
+			// `block_logs` is available since version 2 of the runtime api
+			let has_block_logs = api
+				.has_api_with::<dyn EVMRuntimeRPCApi<B, Balance>, _>(&block_id, |version| version >= 2)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+			if !has_block_logs {
+				continue;
+			}
+
+			let block_logs = api
+				.block_logs(&block_id, filter.clone())
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+			logs.extend(block_logs.into_iter().map(|block_log| LogResponse {
+				block_hash: hash,
+				block_number,
+				extrinsic_index: block_log.extrinsic_index,
+				log_index: block_log.log_index,
+				address: block_log.log.address,
+				topics: block_log.log.topics,
+				data: Bytes(block_log.log.data),
+			}));
+		}
+
+		Ok(logs)
+	}
 }
 
 #[test]
@@ -62,7 +62,7 @@ pub use orml_traits::currency::TransferAll;
 use primitive_types::{H160, H256, U256};
 pub use primitives::{
 	evm::{
-		CallInfo, CreateInfo, EvmAddress, ExecutionInfo, Vicinity, MIRRORED_NFT_ADDRESS_START,
+		BlockLog, CallInfo, CreateInfo, EvmAddress, ExecutionInfo, LogFilter, Vicinity, MIRRORED_NFT_ADDRESS_START,
 		MIRRORED_TOKENS_ADDRESS_START,
 	},
 	task::TaskResult,
@@ -108,6 +108,14 @@ pub type NegativeImbalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 pub const RESERVE_ID_STORAGE_DEPOSIT: ReserveIdentifier = ReserveIdentifier::EvmStorageDeposit;
 pub const RESERVE_ID_DEVELOPER_DEPOSIT: ReserveIdentifier = ReserveIdentifier::EvmDeveloperDeposit;
+pub const RESERVE_ID_STORAGE_SPONSOR: ReserveIdentifier = ReserveIdentifier::EvmStorageSponsor;
+
+/// The length of the period of the storage sponsor quota, in milliseconds.
+pub const SPONSOR_QUOTA_PERIOD: u64 = 24 * 60 * 60 * 1000;
+
+/// The maximum number of the sponsored storage usages removed when the storage
+/// sponsor is unregistered, the rest are reset by the next quota period.
+pub const MAX_SPONSORED_STORAGE_USAGES_REMOVAL: u32 = 100;
 
 // Initially based on Istanbul hard fork configuration.
 static ACALA_CONFIG: EvmConfig = EvmConfig {
@@ -267,6 +275,18 @@ pub mod module {
 		pub ref_count: u32,
 	}
 
+	/// The storage sponsorship of a contract.
+	#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+	pub struct StorageSponsorInfo<AccountId, Balance> {
+		/// The account sponsoring the storage deposits of the users.
+		pub sponsor: AccountId,
+		/// The pooled amount to cover the storage deposits, reserved from the
+		/// sponsor.
+		pub pool: Balance,
+		/// The maximum storage bytes sponsored for each user per day.
+		pub daily_quota: u32,
+	}
+
 	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	/// Account definition used for genesis block construction.
@@ -334,6 +354,32 @@ pub mod module {
 	#[pallet::getter(fn extrinsic_origin)]
 	pub type ExtrinsicOrigin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+	/// The storage sponsorships of contracts.
+	///
+	/// StorageSponsors: map EvmAddress => Option<StorageSponsorInfo<AccountId, Balance>>
+	#[pallet::storage]
+	#[pallet::getter(fn storage_sponsors)]
+	pub type StorageSponsors<T: Config> =
+		StorageMap<_, Twox64Concat, EvmAddress, StorageSponsorInfo<T::AccountId, BalanceOf<T>>, OptionQuery>;
+
+	/// The storage bytes sponsored for the user of a contract in the latest
+	/// quota period.
+	///
+	/// SponsoredStorageUsages: double_map EvmAddress, EvmAddress => (u64, u32)
+	#[pallet::storage]
+	#[pallet::getter(fn sponsored_storage_usages)]
+	pub type SponsoredStorageUsages<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, EvmAddress, Twox64Concat, EvmAddress, (u64, u32), ValueQuery>;
+
+	/// The account paid the storage deposits of a contract by the storage
+	/// sponsor pool, and the storage bytes not freed yet. It's kept after the
+	/// sponsor is unregistered, so that the deposits are refunded to it.
+	///
+	/// SponsoredStorages: map EvmAddress => Option<(AccountId, u32)>
+	#[pallet::storage]
+	#[pallet::getter(fn sponsored_storages)]
+	pub type SponsoredStorages<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, (T::AccountId, u32), OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub accounts: BTreeMap<EvmAddress, GenesisAccount<BalanceOf<T>, T::Index>>,
@@ -439,6 +485,14 @@ pub mod module {
 		ContractSetCode(EvmAddress),
 		/// Selfdestructed contract code. \[contract\]
 		ContractSelfdestructed(EvmAddress),
+		/// Registered storage sponsor. \[contract, sponsor, daily_quota\]
+		StorageSponsorRegistered(EvmAddress, T::AccountId, u32),
+		/// Unregistered storage sponsor. \[contract\]
+		StorageSponsorUnregistered(EvmAddress),
+		/// Deposited to the storage sponsor pool. \[contract, amount\]
+		StorageSponsorPoolDeposited(EvmAddress, BalanceOf<T>),
+		/// Withdrawn from the storage sponsor pool. \[contract, amount\]
+		StorageSponsorPoolWithdrawn(EvmAddress, BalanceOf<T>),
 	}
 
 	#[pallet::error]
@@ -471,6 +525,14 @@ pub mod module {
 		UnreserveStorageFailed,
 		/// Charge storage failed
 		ChargeStorageFailed,
+		/// Storage sponsor already registered by another account
+		StorageSponsorAlreadyRegistered,
+		/// Storage sponsor not found
+		StorageSponsorNotFound,
+		/// The storage sponsor pool is not enough
+		StorageSponsorPoolNotEnough,
+		/// The storage sponsored by another account is not freed yet
+		SponsoredStorageNotFreed,
 	}
 
 	#[pallet::pallet]
@@ -856,6 +918,115 @@ pub mod module {
 
 			Ok(().into())
 		}
+
+		/// Register the caller as the storage sponsor of a contract, or update
+		/// the quota if the caller is the sponsor already. The storage
+		/// deposits of the users calling the contract are paid by the sponsor
+		/// pool within the daily quota.
+		///
+		/// - `contract`: the contract to sponsor, the caller must be the contract's maintainer
+		/// - `daily_quota`: the maximum storage bytes sponsored for each user per day
+		#[pallet::weight(<T as Config>::WeightInfo::register_storage_sponsor())]
+		#[transactional]
+		pub fn register_storage_sponsor(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			daily_quota: u32,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let address = T::AddressMapping::get_evm_address(&who).ok_or(Error::<T>::AddressNotMapped)?;
+			let contract_info = Self::accounts(contract)
+				.and_then(|account_info| account_info.contract_info)
+				.ok_or(Error::<T>::ContractNotFound)?;
+			ensure!(contract_info.maintainer == address, Error::<T>::NoPermission);
+			ensure!(
+				Self::sponsored_storages(contract).map_or(true, |(payer, _)| payer == who),
+				Error::<T>::SponsoredStorageNotFreed
+			);
+
+			StorageSponsors::<T>::try_mutate(contract, |maybe_info| -> DispatchResult {
+				if let Some(info) = maybe_info {
+					ensure!(info.sponsor == who, Error::<T>::StorageSponsorAlreadyRegistered);
+					info.daily_quota = daily_quota;
+				} else {
+					*maybe_info = Some(StorageSponsorInfo {
+						sponsor: who.clone(),
+						pool: Zero::zero(),
+						daily_quota,
+					});
+				}
+				Ok(())
+			})?;
+
+			Pallet::<T>::deposit_event(Event::<T>::StorageSponsorRegistered(contract, who, daily_quota));
+			Ok(().into())
+		}
+
+		/// Unregister the storage sponsor of a contract and release the pool
+		/// to the sponsor. The deposits of the sponsored storage still in use
+		/// stay with the contract, and are refunded to the sponsor when the
+		/// storage is freed.
+		///
+		/// - `contract`: the sponsored contract, the caller must be the sponsor
+		#[pallet::weight(<T as Config>::WeightInfo::unregister_storage_sponsor())]
+		#[transactional]
+		pub fn unregister_storage_sponsor(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let info = Self::storage_sponsors(contract).ok_or(Error::<T>::StorageSponsorNotFound)?;
+			ensure!(info.sponsor == who, Error::<T>::NoPermission);
+			Self::do_unregister_storage_sponsor(&contract);
+			Ok(().into())
+		}
+
+		/// Deposit to the storage sponsor pool of a contract.
+		///
+		/// - `contract`: the sponsored contract, the caller must be the sponsor
+		/// - `amount`: the amount to reserve into the pool
+		#[pallet::weight(<T as Config>::WeightInfo::deposit_storage_sponsor_pool())]
+		#[transactional]
+		pub fn deposit_storage_sponsor_pool(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			StorageSponsors::<T>::try_mutate(contract, |maybe_info| -> DispatchResult {
+				let info = maybe_info.as_mut().ok_or(Error::<T>::StorageSponsorNotFound)?;
+				ensure!(info.sponsor == who, Error::<T>::NoPermission);
+				T::Currency::reserve_named(&RESERVE_ID_STORAGE_SPONSOR, &who, amount)?;
+				info.pool = info.pool.saturating_add(amount);
+				Ok(())
+			})?;
+
+			Pallet::<T>::deposit_event(Event::<T>::StorageSponsorPoolDeposited(contract, amount));
+			Ok(().into())
+		}
+
+		/// Withdraw from the storage sponsor pool of a contract.
+		///
+		/// - `contract`: the sponsored contract, the caller must be the sponsor
+		/// - `amount`: the amount to release from the pool
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_storage_sponsor_pool())]
+		#[transactional]
+		pub fn withdraw_storage_sponsor_pool(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			StorageSponsors::<T>::try_mutate(contract, |maybe_info| -> DispatchResult {
+				let info = maybe_info.as_mut().ok_or(Error::<T>::StorageSponsorNotFound)?;
+				ensure!(info.sponsor == who, Error::<T>::NoPermission);
+				ensure!(info.pool >= amount, Error::<T>::StorageSponsorPoolNotEnough);
+				let err_amount = T::Currency::unreserve_named(&RESERVE_ID_STORAGE_SPONSOR, &who, amount);
+				debug_assert!(err_amount.is_zero());
+				info.pool = info.pool.saturating_sub(amount);
+				Ok(())
+			})?;
+
+			Pallet::<T>::deposit_event(Event::<T>::StorageSponsorPoolWithdrawn(contract, amount));
+			Ok(().into())
+		}
 	}
 }
 
@@ -874,6 +1045,37 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Get the logs of successful EVM executions in the current block which
+	/// match the `filter`.
+	pub fn block_logs(filter: &LogFilter) -> Vec<BlockLog>
+	where
+		<T as frame_system::Config>::Event: TryInto<Event<T>>,
+	{
+		frame_system::Pallet::<T>::events()
+			.into_iter()
+			.filter_map(|record| {
+				let extrinsic_index = match record.phase {
+					frame_system::Phase::ApplyExtrinsic(index) => Some(index),
+					_ => None,
+				};
+				match record.event.try_into() {
+					Ok(Event::Created(_, _, logs)) | Ok(Event::Executed(_, _, logs)) => {
+						Some(logs.into_iter().map(move |log| (extrinsic_index, log)))
+					}
+					_ => None,
+				}
+			})
+			.flatten()
+			.enumerate()
+			.filter(|(_, (_, log))| filter.matches(log))
+			.map(|(log_index, (extrinsic_index, log))| BlockLog {
+				extrinsic_index,
+				log_index: log_index as u32,
+				log,
+			})
+			.collect()
+	}
+
 	/// Remove an account if its empty.
 	/// Unused now.
 	pub fn remove_account_if_empty(address: &H160) {
@@ -1278,9 +1480,11 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	fn charge_storage(caller: &H160, contract: &H160, storage: i32) -> DispatchResult {
+	/// Charge the storage deposit of the contract, returns the storage bytes
+	/// reclaimed to the storage sponsor pool.
+	fn charge_storage(caller: &H160, contract: &H160, storage: i32) -> Result<u32, DispatchError> {
 		if storage.is_zero() {
-			return Ok(());
+			return Ok(0);
 		}
 
 		let user = T::AddressMapping::get_account_id(caller);
@@ -1301,27 +1505,148 @@ impl<T: Config> Pallet<T> {
 			// but otherwise we will just ignore the issue here.
 			let err_amount = T::Currency::unreserve_named(&RESERVE_ID_STORAGE_DEPOSIT, &user, amount);
 			debug_assert!(err_amount.is_zero());
-			T::Currency::transfer(&user, &contract_acc, amount, ExistenceRequirement::AllowDeath)?;
+			// the storage sponsor pays the deposit instead if the quota of the user allows.
+			let payer = Self::take_from_sponsor_pool(caller, contract, storage as u32, amount).unwrap_or(user);
+			T::Currency::transfer(&payer, &contract_acc, amount, ExistenceRequirement::AllowDeath)?;
 			T::Currency::reserve_named(&RESERVE_ID_STORAGE_DEPOSIT, &contract_acc, amount)?;
+			Ok(0)
 		} else {
+			// the freed storage paid by the sponsor pool is reclaimed to the pool first.
+			let reclaimed = Self::reclaim_to_sponsor_pool(contract, &contract_acc, storage.unsigned_abs())?;
+			let reclaimed_amount = T::StorageDepositPerByte::get().saturating_mul(reclaimed.into());
+
 			// user can't be a dead account
 			let val = T::Currency::repatriate_reserved_named(
 				&RESERVE_ID_STORAGE_DEPOSIT,
 				&contract_acc,
 				&user,
-				amount,
+				amount.saturating_sub(reclaimed_amount),
 				BalanceStatus::Reserved,
 			)?;
 			debug_assert!(val.is_zero());
-		};
+			Ok(reclaimed)
+		}
+	}
 
-		Ok(())
+	fn current_sponsor_quota_period() -> u64 {
+		let now: u64 = pallet_timestamp::Pallet::<T>::get().unique_saturated_into();
+		now / SPONSOR_QUOTA_PERIOD
+	}
+
+	/// Take `amount` from the storage sponsor pool of the contract to pay for
+	/// `storage` bytes of the caller, returns the sponsor if the pool and the
+	/// daily quota of the caller are enough.
+	fn take_from_sponsor_pool(
+		caller: &H160,
+		contract: &H160,
+		storage: u32,
+		amount: BalanceOf<T>,
+	) -> Option<T::AccountId> {
+		StorageSponsors::<T>::try_mutate(contract, |maybe_info| -> Result<T::AccountId, ()> {
+			let info = maybe_info.as_mut().ok_or(())?;
+			if info.pool < amount {
+				return Err(());
+			}
+
+			let period = Self::current_sponsor_quota_period();
+			SponsoredStorageUsages::<T>::try_mutate(contract, caller, |(usage_period, usage)| -> Result<(), ()> {
+				if *usage_period != period {
+					*usage_period = period;
+					*usage = 0;
+				}
+				*usage = usage
+					.checked_add(storage)
+					.filter(|new_usage| *new_usage <= info.daily_quota)
+					.ok_or(())?;
+				Ok(())
+			})?;
+
+			let err_amount = T::Currency::unreserve_named(&RESERVE_ID_STORAGE_SPONSOR, &info.sponsor, amount);
+			debug_assert!(err_amount.is_zero());
+			info.pool = info.pool.saturating_sub(amount);
+			SponsoredStorages::<T>::mutate(contract, |maybe_sponsored| {
+				let (_, sponsored_storage) = maybe_sponsored.get_or_insert_with(|| (info.sponsor.clone(), 0));
+				*sponsored_storage = sponsored_storage.saturating_add(storage);
+			});
+
+			log::debug!(
+				target: "evm",
+				"take_from_sponsor_pool: [caller: {:?}, contract: {:?}, sponsor: {:?}, storage: {:?}, amount: {:?}]",
+				caller, contract, info.sponsor, storage, amount
+			);
+
+			Ok(info.sponsor.clone())
+		})
+		.ok()
+	}
+
+	/// Reclaim the deposit of at most `storage` freed bytes paid by the storage
+	/// sponsor pool of the contract back to the account paid it, into the pool
+	/// if it's still the sponsor. Returns the reclaimed bytes.
+	fn reclaim_to_sponsor_pool(
+		contract: &H160,
+		contract_acc: &T::AccountId,
+		storage: u32,
+	) -> Result<u32, DispatchError> {
+		SponsoredStorages::<T>::try_mutate_exists(contract, |maybe_sponsored| -> Result<u32, DispatchError> {
+			let (payer, sponsored_storage) = match maybe_sponsored.as_mut() {
+				Some(sponsored) => sponsored,
+				None => return Ok(0),
+			};
+			let reclaimed = storage.min(*sponsored_storage);
+			if reclaimed.is_zero() {
+				return Ok(0);
+			}
+
+			let amount = T::StorageDepositPerByte::get().saturating_mul(reclaimed.into());
+			let val = T::Currency::repatriate_reserved_named(
+				&RESERVE_ID_STORAGE_DEPOSIT,
+				contract_acc,
+				payer,
+				amount,
+				BalanceStatus::Free,
+			)?;
+			debug_assert!(val.is_zero());
+			StorageSponsors::<T>::try_mutate(contract, |maybe_info| -> DispatchResult {
+				if let Some(info) = maybe_info.as_mut().filter(|info| info.sponsor == *payer) {
+					T::Currency::reserve_named(&RESERVE_ID_STORAGE_SPONSOR, payer, amount)?;
+					info.pool = info.pool.saturating_add(amount);
+				}
+				Ok(())
+			})?;
+
+			log::debug!(
+				target: "evm",
+				"reclaim_to_sponsor_pool: [contract: {:?}, payer: {:?}, reclaimed: {:?}, amount: {:?}]",
+				contract, payer, reclaimed, amount
+			);
+
+			*sponsored_storage = sponsored_storage.saturating_sub(reclaimed);
+			if sponsored_storage.is_zero() {
+				*maybe_sponsored = None;
+			}
+			Ok(reclaimed)
+		})
+	}
+
+	fn do_unregister_storage_sponsor(contract: &H160) {
+		if let Some(info) = StorageSponsors::<T>::take(contract) {
+			let err_amount = T::Currency::unreserve_named(&RESERVE_ID_STORAGE_SPONSOR, &info.sponsor, info.pool);
+			debug_assert!(err_amount.is_zero());
+			SponsoredStorageUsages::<T>::remove_prefix(contract, Some(MAX_SPONSORED_STORAGE_USAGES_REMOVAL));
+			Pallet::<T>::deposit_event(Event::<T>::StorageSponsorUnregistered(*contract));
+		}
 	}
 
 	fn refund_storage(caller: &H160, contract: &H160, maintainer: &H160) -> DispatchResult {
 		let user = T::AddressMapping::get_account_id(caller);
 		let contract_acc = T::AddressMapping::get_account_id(contract);
 		let maintainer_acc = T::AddressMapping::get_account_id(maintainer);
+
+		// the deposit paid by the storage sponsor pool is reclaimed to the account paid it.
+		Self::reclaim_to_sponsor_pool(contract, &contract_acc, u32::MAX)?;
+		Self::do_unregister_storage_sponsor(contract);
+
 		let amount = T::Currency::reserved_balance_named(&RESERVE_ID_STORAGE_DEPOSIT, &contract_acc);
 
 		log::debug!(
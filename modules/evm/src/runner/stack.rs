@@ -132,9 +132,10 @@ impl<T: Config> Runner<T> {
 			state.substate.storage_logs
 		);
 		let mut sum_storage: i32 = 0;
+		let mut reclaimed_storage: u32 = 0;
 		for (target, storage) in &state.substate.storage_logs {
 			if !config.estimate {
-				Pallet::<T>::charge_storage(&origin, target, *storage).map_err(|e| {
+				let reclaimed = Pallet::<T>::charge_storage(&origin, target, *storage).map_err(|e| {
 					log::debug!(
 						target: "evm",
 						"ChargeStorageFailed {:?} [source: {:?}, target: {:?}, storage: {:?}]",
@@ -145,6 +146,7 @@ impl<T: Config> Runner<T> {
 					);
 					Error::<T>::ChargeStorageFailed
 				})?;
+				reclaimed_storage = reclaimed_storage.saturating_add(reclaimed);
 			}
 			sum_storage += storage;
 		}
@@ -158,6 +160,8 @@ impl<T: Config> Runner<T> {
 		}
 
 		if !config.estimate {
+			// the storage reclaimed to the storage sponsor pool is not refunded to the origin.
+			let refunded_storage = refunded_storage.saturating_sub(reclaimed_storage);
 			Pallet::<T>::unreserve_storage(&origin, storage_limit, used_storage, refunded_storage).map_err(|e| {
 				log::debug!(
 					target: "evm",
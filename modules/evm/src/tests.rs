@@ -1686,6 +1686,169 @@ fn should_update_storage() {
 	});
 }
 
+#[test]
+fn storage_sponsor_should_work() {
+	// pragma solidity ^0.5.0;
+	//
+	// contract Test {
+	//     mapping(address => uint256) public values;
+	//
+	//     constructor() public {
+	//         values[msg.sender] = 42;
+	//     }
+	//
+	//     function set(uint val) public {
+	//      values[msg.sender] = val;
+	//     }
+	// }
+
+	let contract = from_hex(
+		"0x608060405234801561001057600080fd5b50602a6000803373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002081905550610154806100646000396000f3fe608060405234801561001057600080fd5b50600436106100365760003560e01c806354fe9fd71461003b57806360fe47b114610093575b600080fd5b61007d6004803603602081101561005157600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff1690602001909291905050506100c1565b6040518082815260200191505060405180910390f35b6100bf600480360360208110156100a957600080fd5b81019080803590602001909291905050506100d9565b005b60006020528060005260406000206000915090505481565b806000803373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff168152602001908152602001600020819055505056fea265627a7a723158207ab6991e97c9c12f57d81df0c7f955435418354adeb26116b581d7f2f035ca8f64736f6c63430005110032"
+	).unwrap();
+	let set_123 = from_hex("0x60fe47b1000000000000000000000000000000000000000000000000000000000000007b").unwrap();
+	let set_0 = from_hex("0x60fe47b10000000000000000000000000000000000000000000000000000000000000000").unwrap();
+	let storage_deposit = STORAGE_SIZE as u64 * <Runtime as Config>::StorageDepositPerByte::get();
+
+	new_test_ext().execute_with(|| {
+		let result =
+			<Runtime as Config>::Runner::create(alice(), contract, 0, 500000, 100000, <Runtime as Config>::config())
+				.unwrap();
+		let contract_address = result.value;
+		let bob_set = |input: Vec<u8>| {
+			<Runtime as Config>::Runner::call(
+				bob(),
+				bob(),
+				contract_address,
+				input,
+				0,
+				1000000,
+				STORAGE_SIZE,
+				<Runtime as Config>::config(),
+			)
+			.unwrap()
+		};
+
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		deploy_free(contract_address);
+
+		let alice_account_id = <Runtime as Config>::AddressMapping::get_account_id(&alice());
+		let bob_account_id = <Runtime as Config>::AddressMapping::get_account_id(&bob());
+		let contract_deposit = reserved_balance(contract_address);
+
+		assert_noop!(
+			EVM::register_storage_sponsor(Origin::signed(bob_account_id.clone()), contract_address, STORAGE_SIZE),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(EVM::register_storage_sponsor(
+			Origin::signed(alice_account_id.clone()),
+			contract_address,
+			STORAGE_SIZE
+		));
+		System::assert_last_event(Event::EVM(crate::Event::StorageSponsorRegistered(
+			contract_address,
+			alice_account_id.clone(),
+			STORAGE_SIZE,
+		)));
+
+		assert_noop!(
+			EVM::deposit_storage_sponsor_pool(Origin::signed(bob_account_id.clone()), contract_address, 1000),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(EVM::deposit_storage_sponsor_pool(
+			Origin::signed(alice_account_id.clone()),
+			contract_address,
+			1000
+		));
+		System::assert_last_event(Event::EVM(crate::Event::StorageSponsorPoolDeposited(
+			contract_address,
+			1000,
+		)));
+		assert_eq!(reserved_balance(alice()), 1000);
+
+		// the storage deposit of bob is paid by the pool
+		assert_eq!(bob_set(set_123.clone()).used_storage, STORAGE_SIZE as i32);
+		assert_eq!(balance(bob()), INITIAL_BALANCE);
+		assert_eq!(reserved_balance(bob()), 0);
+		assert_eq!(reserved_balance(alice()), 1000 - storage_deposit);
+		assert_eq!(reserved_balance(contract_address), contract_deposit + storage_deposit);
+		assert_eq!(
+			EVM::sponsored_storages(contract_address),
+			Some((alice_account_id.clone(), STORAGE_SIZE))
+		);
+
+		// the freed deposit is reclaimed to the pool
+		assert_eq!(bob_set(set_0.clone()).used_storage, -(STORAGE_SIZE as i32));
+		assert_eq!(balance(bob()), INITIAL_BALANCE);
+		assert_eq!(reserved_balance(bob()), 0);
+		assert_eq!(reserved_balance(alice()), 1000);
+		assert_eq!(reserved_balance(contract_address), contract_deposit);
+		assert_eq!(EVM::sponsored_storages(contract_address), None);
+
+		// bob pays the storage deposit after the daily quota is used up
+		bob_set(set_123.clone());
+		assert_eq!(balance(bob()), INITIAL_BALANCE - storage_deposit);
+		assert_eq!(reserved_balance(alice()), 1000);
+		bob_set(set_0.clone());
+		assert_eq!(balance(bob()), INITIAL_BALANCE);
+
+		// the daily quota is reset in the next day
+		Timestamp::set_timestamp(SPONSOR_QUOTA_PERIOD);
+		bob_set(set_123.clone());
+		assert_eq!(balance(bob()), INITIAL_BALANCE);
+		assert_eq!(reserved_balance(alice()), 1000 - storage_deposit);
+
+		assert_noop!(
+			EVM::withdraw_storage_sponsor_pool(Origin::signed(alice_account_id.clone()), contract_address, 1000),
+			Error::<Runtime>::StorageSponsorPoolNotEnough
+		);
+		assert_ok!(EVM::withdraw_storage_sponsor_pool(
+			Origin::signed(alice_account_id.clone()),
+			contract_address,
+			100
+		));
+		System::assert_last_event(Event::EVM(crate::Event::StorageSponsorPoolWithdrawn(
+			contract_address,
+			100,
+		)));
+		assert_eq!(reserved_balance(alice()), 900 - storage_deposit);
+
+		assert_noop!(
+			EVM::unregister_storage_sponsor(Origin::signed(bob_account_id.clone()), contract_address),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(EVM::unregister_storage_sponsor(
+			Origin::signed(alice_account_id.clone()),
+			contract_address
+		));
+		System::assert_last_event(Event::EVM(crate::Event::StorageSponsorUnregistered(contract_address)));
+		assert_eq!(EVM::storage_sponsors(contract_address), None);
+		assert_eq!(reserved_balance(alice()), 0);
+		assert_eq!(
+			EVM::sponsored_storages(contract_address),
+			Some((alice_account_id.clone(), STORAGE_SIZE))
+		);
+
+		// another sponsor can't register before the storage sponsored by alice is freed
+		assert_ok!(EVM::transfer_maintainer(
+			Origin::signed(alice_account_id),
+			contract_address,
+			bob()
+		));
+		assert_noop!(
+			EVM::register_storage_sponsor(Origin::signed(bob_account_id), contract_address, STORAGE_SIZE),
+			Error::<Runtime>::SponsoredStorageNotFreed
+		);
+
+		// the freed deposit is refunded to alice rather than bob
+		let alice_balance = balance(alice());
+		bob_set(set_0);
+		assert_eq!(balance(bob()), INITIAL_BALANCE);
+		assert_eq!(reserved_balance(bob()), 0);
+		assert_eq!(balance(alice()), alice_balance + storage_deposit);
+		assert_eq!(EVM::sponsored_storages(contract_address), None);
+	});
+}
+
 #[test]
 fn code_hash_with_non_existent_address_should_work() {
 	new_test_ext().execute_with(|| {
@@ -1695,3 +1858,61 @@ fn code_hash_with_non_existent_address_should_work() {
 		);
 	});
 }
+
+#[test]
+fn block_logs_should_work() {
+	new_test_ext().execute_with(|| {
+		let log = |address: u64, topic: u64| Log {
+			address: H160::from_low_u64_be(address),
+			topics: vec![H256::from_low_u64_be(topic)],
+			data: vec![],
+		};
+
+		Pallet::<Runtime>::deposit_event(crate::Event::Executed(
+			alice(),
+			contract_a(),
+			vec![log(1, 1), log(2, 1)],
+		));
+		Pallet::<Runtime>::deposit_event(crate::Event::ExecutedFailed(
+			alice(),
+			contract_a(),
+			ExitReason::Revert(ExitRevert::Reverted),
+			vec![],
+			vec![log(1, 1)],
+		));
+		System::note_applied_extrinsic(&Ok(().into()), Default::default());
+		Pallet::<Runtime>::deposit_event(crate::Event::Created(alice(), contract_b(), vec![log(1, 2)]));
+
+		assert_eq!(
+			EVM::block_logs(&LogFilter::default()),
+			vec![
+				BlockLog {
+					extrinsic_index: Some(0),
+					log_index: 0,
+					log: log(1, 1),
+				},
+				BlockLog {
+					extrinsic_index: Some(0),
+					log_index: 1,
+					log: log(2, 1),
+				},
+				BlockLog {
+					extrinsic_index: Some(1),
+					log_index: 2,
+					log: log(1, 2),
+				},
+			]
+		);
+		assert_eq!(
+			EVM::block_logs(&LogFilter {
+				addresses: vec![H160::from_low_u64_be(1)],
+				topics: vec![Some(vec![H256::from_low_u64_be(2)])],
+			}),
+			vec![BlockLog {
+				extrinsic_index: Some(1),
+				log_index: 2,
+				log: log(1, 2),
+			}]
+		);
+	});
+}
@@ -107,6 +107,14 @@ benchmarks! {
 	xcm_unbond {}: {
 		let _ = crate::Pallet::<T>::process_scheduled_unbond(1_000_000_000_000_000);
 	}
+
+	force_abandon_xcm_operation {
+		ExhaustedXcmOperations::<T>::insert(0, FailedXcmOperation {
+			operation: XcmOperation::WithdrawUnbonded(1_000_000_000_000_000),
+			attempts: 1,
+			next_retry_at: Default::default(),
+		});
+	}: _(RawOrigin::Root, 0, true)
 }
 
 #[cfg(test)]
@@ -207,4 +215,10 @@ mod tests {
 			assert_ok!(Pallet::<Runtime>::test_benchmark_xcm_unbond());
 		});
 	}
+	#[test]
+	fn test_force_abandon_xcm_operation() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(Pallet::<Runtime>::test_benchmark_force_abandon_xcm_operation());
+		});
+	}
 }
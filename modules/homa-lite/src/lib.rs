@@ -30,7 +30,7 @@ pub mod weights;
 use frame_support::{log, pallet_prelude::*, transactional, weights::Weight, BoundedVec};
 use frame_system::{ensure_signed, pallet_prelude::*};
 
-use module_support::{CallBuilder, ExchangeRate, ExchangeRateProvider, Ratio};
+use module_support::{CallBuilder, ExchangeRate, ExchangeRateProvider, HomaManager, Ratio};
 use orml_traits::{
 	arithmetic::Signed, BalanceStatus, MultiCurrency, MultiCurrencyExtended, MultiReservableCurrency, XcmTransfer,
 };
@@ -51,6 +51,38 @@ use xcm::latest::prelude::*;
 pub use module::*;
 pub use weights::WeightInfo;
 
+/// The XCM operation sent to the relaychain.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum XcmOperation {
+	/// Withdraw the unbonded staking currency from the relaychain.
+	WithdrawUnbonded(Balance),
+}
+
+/// The XCM operation sent to the relaychain, awaiting the report of its outcome.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct PendingXcmOperation<BlockNumber> {
+	/// The XCM operation sent.
+	pub operation: XcmOperation,
+	/// The id of the `pallet_xcm` query expecting the outcome.
+	pub query_id: QueryId,
+	/// The number of the previously failed attempts.
+	pub attempts: u32,
+	/// The block number after which the operation is failed if no outcome is reported.
+	pub timeout_at: BlockNumber,
+}
+
+/// The XCM operation that failed, either to be sent or on the relaychain, queued to be
+/// retried.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct FailedXcmOperation<BlockNumber> {
+	/// The XCM operation to retry.
+	pub operation: XcmOperation,
+	/// The number of the failed attempts.
+	pub attempts: u32,
+	/// The relaychain block number from which the operation can be retried.
+	pub next_retry_at: BlockNumber,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -146,6 +178,20 @@ pub mod module {
 		/// The number of blocks to pass before TotalStakingCurrency is updated.
 		#[pallet::constant]
 		type StakingUpdateFrequency: Get<Self::BlockNumber>;
+
+		/// The number of relaychain blocks to wait before retrying a failed XCM operation.
+		/// The delay doubles on each further failure.
+		#[pallet::constant]
+		type XcmRetryBaseDelay: Get<RelayChainBlockNumberOf<Self>>;
+
+		/// The maximum attempts of a failed XCM operation, after which it is left to governance.
+		#[pallet::constant]
+		type MaxXcmRetryAttempts: Get<u32>;
+
+		/// The number of blocks to wait for the outcome of an XCM operation to be reported by
+		/// the relaychain, after which the operation is failed.
+		#[pallet::constant]
+		type XcmResponseTimeout: Get<Self::BlockNumber>;
 	}
 
 	#[pallet::error]
@@ -166,6 +212,10 @@ pub mod module {
 		TooManyScheduledUnbonds,
 		/// The xcm operation have failed
 		XcmFailed,
+		/// The failed xcm operation does not exist
+		XcmOperationNotFound,
+		/// The xcm message cannot be constructed
+		XcmMessageInvalid,
 	}
 
 	#[pallet::event]
@@ -214,6 +264,30 @@ pub mod module {
 		/// The amount of the staking currency available to be redeemed is set.
 		/// \[total_available_staking_balance\]
 		AvailableStakingBalanceSet(Balance),
+
+		/// The XCM operation is sent, and its outcome is awaited.
+		/// \[operation_id, operation, query_id\]
+		XcmOperationSent(u32, XcmOperation, QueryId),
+
+		/// The XCM operation has failed and is queued to be retried.
+		/// \[operation_id, operation\]
+		XcmOperationQueued(u32, XcmOperation),
+
+		/// The failed XCM operation is scheduled to be retried.
+		/// \[operation_id, attempts, next_retry_at\]
+		XcmOperationRetryScheduled(u32, u32, RelayChainBlockNumberOf<T>),
+
+		/// The XCM operation has reached the max attempts, and is left to governance.
+		/// \[operation_id, attempts\]
+		XcmOperationRetriesExhausted(u32, u32),
+
+		/// The retry of the failed XCM operation has succeeded.
+		/// \[operation_id, failed_attempts\]
+		XcmOperationRetrySucceeded(u32, u32),
+
+		/// The failed XCM operation is abandoned by governance.
+		/// \[operation_id, operation, completed\]
+		XcmOperationAbandoned(u32, XcmOperation, bool),
 	}
 
 	/// The total amount of the staking currency on the relaychain.
@@ -260,6 +334,34 @@ pub mod module {
 	#[pallet::getter(fn staking_interest_rate_per_update)]
 	pub type StakingInterestRatePerUpdate<T: Config> = StorageValue<_, Permill, ValueQuery>;
 
+	/// The id of the next failed XCM operation.
+	/// NextXcmOperationId: value: u32
+	#[pallet::storage]
+	#[pallet::getter(fn next_xcm_operation_id)]
+	pub type NextXcmOperationId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The failed XCM operations queued to be retried.
+	/// FailedXcmOperations: Map: u32 => Option<FailedXcmOperation>
+	#[pallet::storage]
+	#[pallet::getter(fn failed_xcm_operations)]
+	pub type FailedXcmOperations<T: Config> =
+		StorageMap<_, Twox64Concat, u32, FailedXcmOperation<RelayChainBlockNumberOf<T>>, OptionQuery>;
+
+	/// The XCM operations sent to the relaychain, awaiting the report of their outcome.
+	/// PendingXcmOperations: Map: u32 => Option<PendingXcmOperation>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_xcm_operations)]
+	pub type PendingXcmOperations<T: Config> =
+		StorageMap<_, Twox64Concat, u32, PendingXcmOperation<T::BlockNumber>, OptionQuery>;
+
+	/// The XCM operations that have reached `T::MaxXcmRetryAttempts`, left to governance.
+	/// They are kept apart from `FailedXcmOperations` so they are not read by every retry.
+	/// ExhaustedXcmOperations: Map: u32 => Option<FailedXcmOperation>
+	#[pallet::storage]
+	#[pallet::getter(fn exhausted_xcm_operations)]
+	pub type ExhaustedXcmOperations<T: Config> =
+		StorageMap<_, Twox64Concat, u32, FailedXcmOperation<RelayChainBlockNumberOf<T>>, OptionQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -273,20 +375,28 @@ pub mod module {
 				if !scheduled_unbond.is_empty() {
 					let (staking_amount, block_number) = scheduled_unbond[0];
 					if T::RelayChainBlockNumber::current_block_number() >= block_number {
+						// A failed unbond is queued to be retried, so it doesn't block the schedule.
 						let res = Self::process_scheduled_unbond(staking_amount);
 						log::debug!("{:?}", res);
-						debug_assert!(res.is_ok());
-
-						if res.is_ok() {
-							current_weight = <T as Config>::WeightInfo::xcm_unbond();
+						current_weight = <T as Config>::WeightInfo::xcm_unbond();
 
-							scheduled_unbond.remove(0);
-							ScheduledUnbond::<T>::put(scheduled_unbond);
-						}
+						scheduled_unbond.remove(0);
+						ScheduledUnbond::<T>::put(scheduled_unbond);
 					}
 				}
 			}
 
+			// With remaining weight, retry the failed XCM operations that are due.
+			current_weight = current_weight.saturating_add(Self::retry_failed_xcm_operations(
+				remaining_weight.saturating_sub(current_weight),
+			));
+
+			// With remaining weight, settle the sent XCM operations whose outcome is reported or
+			// timed out.
+			current_weight = current_weight.saturating_add(Self::process_pending_xcm_operations(
+				remaining_weight.saturating_sub(current_weight),
+			));
+
 			// With remaining weight, calculate max number of redeems that can be matched
 			let num_redeem_matches = remaining_weight
 				.saturating_sub(current_weight)
@@ -458,77 +568,7 @@ pub mod module {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			if liquid_amount.is_zero() {
-				// If the amount is zero, cancel previous redeem request.
-				if let Some((request_amount, _)) = RedeemRequests::<T>::take(&who) {
-					// Unreserve the liquid fee and remove the redeem request.
-					let unreserved = T::Currency::unreserve(T::LiquidCurrencyId::get(), &who, request_amount);
-					ensure!(unreserved.is_zero(), Error::<T>::InsufficientReservedBalances);
-
-					Self::deposit_event(Event::<T>::RedeemRequestCancelled(who, request_amount));
-				}
-				return Ok(());
-			}
-
-			// Redeem amount must be above a certain limit.
-			ensure!(
-				Self::liquid_amount_is_above_minimum_threshold(liquid_amount),
-				Error::<T>::AmountBelowMinimumThreshold
-			);
-
-			// Deduct base withdraw fee and add the redeem request to the queue.
-			RedeemRequests::<T>::try_mutate(&who, |request| -> DispatchResult {
-				let old_amount = request.take().map(|(amount, _)| amount).unwrap_or_default();
-
-				let diff_amount = liquid_amount.saturating_sub(old_amount);
-
-				let base_withdraw_fee = T::BaseWithdrawFee::get().mul(diff_amount);
-				if !base_withdraw_fee.is_zero() {
-					// Burn withdraw fee for increased amount
-					let slash_amount = T::Currency::slash(T::LiquidCurrencyId::get(), &who, base_withdraw_fee);
-					ensure!(slash_amount.is_zero(), Error::<T>::InsufficientLiquidBalance);
-				}
-
-				// Deduct BaseWithdrawFee from the liquid amount.
-				let liquid_amount = liquid_amount.saturating_sub(base_withdraw_fee);
-
-				// Reserve/unreserve the difference amount.
-				match liquid_amount.cmp(&old_amount) {
-					// Lock more liquid currency.
-					Ordering::Greater => T::Currency::reserve(
-						T::LiquidCurrencyId::get(),
-						&who,
-						liquid_amount.saturating_sub(old_amount),
-					),
-					Ordering::Less => {
-						// If the new amount is less, unlock the difference.
-						let unserved_leftover = T::Currency::unreserve(
-							T::LiquidCurrencyId::get(),
-							&who,
-							old_amount.saturating_sub(liquid_amount),
-						);
-						ensure!(unserved_leftover.is_zero(), Error::<T>::InsufficientLiquidBalance);
-						Ok(())
-					}
-					_ => Ok(()),
-				}?;
-
-				// Set the new amount into storage.
-				*request = Some((liquid_amount, additional_fee));
-
-				Self::deposit_event(Event::<T>::RedeemRequested(
-					who.clone(),
-					liquid_amount,
-					additional_fee,
-					base_withdraw_fee,
-				));
-
-				Ok(())
-			})?;
-
-			// With redeem request added to the queue, try to redeem it with available staking balance.
-			Self::process_redeem_requests_with_available_staking_balance(1)?;
-			Ok(())
+			Self::do_request_redeem(who, liquid_amount, additional_fee)
 		}
 
 		/// Request staking currencies to be unbonded from the RelayChain.
@@ -651,6 +691,39 @@ pub mod module {
 
 			Ok(())
 		}
+
+		/// Abandon a failed or exhausted XCM operation and remove it from the retry queue.
+		/// If the operation has been completed by other means, e.g. the unbonded staking
+		/// currency is transferred to the parachain account manually, its effect is applied.
+		/// Otherwise the staking currency stays on the relaychain, still counted in the
+		/// TotalStakingCurrency.
+		///
+		/// Requires `T::GovernanceOrigin`
+		///
+		/// Parameters:
+		/// - `operation_id`: The id of the failed XCM operation.
+		/// - `completed`: Whether the operation has been completed by other means.
+		#[pallet::weight(< T as Config >::WeightInfo::force_abandon_xcm_operation())]
+		#[transactional]
+		pub fn force_abandon_xcm_operation(origin: OriginFor<T>, operation_id: u32, completed: bool) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+
+			let failed_operation = FailedXcmOperations::<T>::take(operation_id)
+				.or_else(|| ExhaustedXcmOperations::<T>::take(operation_id))
+				.ok_or(Error::<T>::XcmOperationNotFound)?;
+
+			if completed {
+				Self::apply_xcm_operation(failed_operation.operation);
+			}
+
+			Self::deposit_event(Event::<T>::XcmOperationAbandoned(
+				operation_id,
+				failed_operation.operation,
+				completed,
+			));
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -741,6 +814,86 @@ pub mod module {
 			Ok(())
 		}
 
+		/// Put in an request to redeem Staking currencies used to mint Liquid currency, and
+		/// try to redeem it with available staking balance.
+		///
+		/// Parameters:
+		/// - `liquid_amount`: The amount of liquid currency to be redeemed into Staking currency.
+		/// - `additional_fee`: Percentage of the fee to be awarded to the minter.
+		fn do_request_redeem(who: T::AccountId, liquid_amount: Balance, additional_fee: Permill) -> DispatchResult {
+			if liquid_amount.is_zero() {
+				// If the amount is zero, cancel previous redeem request.
+				if let Some((request_amount, _)) = RedeemRequests::<T>::take(&who) {
+					// Unreserve the liquid fee and remove the redeem request.
+					let unreserved = T::Currency::unreserve(T::LiquidCurrencyId::get(), &who, request_amount);
+					ensure!(unreserved.is_zero(), Error::<T>::InsufficientReservedBalances);
+
+					Self::deposit_event(Event::<T>::RedeemRequestCancelled(who, request_amount));
+				}
+				return Ok(());
+			}
+
+			// Redeem amount must be above a certain limit.
+			ensure!(
+				Self::liquid_amount_is_above_minimum_threshold(liquid_amount),
+				Error::<T>::AmountBelowMinimumThreshold
+			);
+
+			// Deduct base withdraw fee and add the redeem request to the queue.
+			RedeemRequests::<T>::try_mutate(&who, |request| -> DispatchResult {
+				let old_amount = request.take().map(|(amount, _)| amount).unwrap_or_default();
+
+				let diff_amount = liquid_amount.saturating_sub(old_amount);
+
+				let base_withdraw_fee = T::BaseWithdrawFee::get().mul(diff_amount);
+				if !base_withdraw_fee.is_zero() {
+					// Burn withdraw fee for increased amount
+					let slash_amount = T::Currency::slash(T::LiquidCurrencyId::get(), &who, base_withdraw_fee);
+					ensure!(slash_amount.is_zero(), Error::<T>::InsufficientLiquidBalance);
+				}
+
+				// Deduct BaseWithdrawFee from the liquid amount.
+				let liquid_amount = liquid_amount.saturating_sub(base_withdraw_fee);
+
+				// Reserve/unreserve the difference amount.
+				match liquid_amount.cmp(&old_amount) {
+					// Lock more liquid currency.
+					Ordering::Greater => T::Currency::reserve(
+						T::LiquidCurrencyId::get(),
+						&who,
+						liquid_amount.saturating_sub(old_amount),
+					),
+					Ordering::Less => {
+						// If the new amount is less, unlock the difference.
+						let unserved_leftover = T::Currency::unreserve(
+							T::LiquidCurrencyId::get(),
+							&who,
+							old_amount.saturating_sub(liquid_amount),
+						);
+						ensure!(unserved_leftover.is_zero(), Error::<T>::InsufficientLiquidBalance);
+						Ok(())
+					}
+					_ => Ok(()),
+				}?;
+
+				// Set the new amount into storage.
+				*request = Some((liquid_amount, additional_fee));
+
+				Self::deposit_event(Event::<T>::RedeemRequested(
+					who.clone(),
+					liquid_amount,
+					additional_fee,
+					base_withdraw_fee,
+				));
+
+				Ok(())
+			})?;
+
+			// With redeem request added to the queue, try to redeem it with available staking balance.
+			Self::process_redeem_requests_with_available_staking_balance(1)?;
+			Ok(())
+		}
+
 		/// Mint some Liquid currency, by locking up the given amount of Staking currency.
 		/// The redeem requests given in `requests` are prioritized to be matched. All other redeem
 		/// requests are matched after. The remaining amount is minted through Staking on the
@@ -867,27 +1020,216 @@ pub mod module {
 		}
 
 		/// Construct XCM message and sent it to the relaychain to withdraw_unbonded Staking
-		/// currency. The staking currency withdrew becomes available to be redeemed.
+		/// currency. The staking currency withdrew becomes available to be redeemed once the
+		/// relaychain reports the success of the message. If it cannot be sent, it is queued to
+		/// be retried.
 		///
 		/// params:
 		/// 	- `staking_amount_unbonded`: amount of staking currency to withdraw unbond via XCM
-		#[transactional]
 		pub fn process_scheduled_unbond(staking_amount_unbonded: Balance) -> DispatchResult {
-			let msg = Self::construct_xcm_unreserve_message(T::ParachainAccount::get(), staking_amount_unbonded);
+			let operation = XcmOperation::WithdrawUnbonded(staking_amount_unbonded);
+			let operation_id = NextXcmOperationId::<T>::mutate(|id| {
+				let current = *id;
+				*id = id.wrapping_add(1);
+				current
+			});
+
+			let res = Self::send_xcm_operation(operation_id, operation, 0);
+			if res.is_err() {
+				Self::deposit_event(Event::<T>::XcmOperationQueued(operation_id, operation));
+				Self::record_xcm_operation_failure(operation_id, operation, 0);
+			}
+			res
+		}
+
+		/// Construct the XCM message of the operation, which reports its outcome back.
+		/// The relaychain barrier requires the message to start by paying for its execution, so
+		/// the appendix reporting the outcome is moved after `BuyExecution`.
+		fn construct_xcm_operation_message(
+			operation: XcmOperation,
+			timeout_at: T::BlockNumber,
+		) -> Result<(Xcm<()>, QueryId), DispatchError> {
+			let mut msg = match operation {
+				XcmOperation::WithdrawUnbonded(staking_amount) => {
+					Self::construct_xcm_unreserve_message(T::ParachainAccount::get(), staking_amount)
+				}
+			};
+			let query_id = pallet_xcm::Pallet::<T>::report_outcome(&mut msg, Parent.into(), timeout_at)
+				.map_err(|_| Error::<T>::XcmMessageInvalid)?;
+
+			ensure!(msg.0.len() > 2, Error::<T>::XcmMessageInvalid);
+			let report_outcome = msg.0.remove(0);
+			msg.0.insert(2, report_outcome);
+
+			Ok((msg, query_id))
+		}
+
+		/// Send the XCM operation to the relaychain, and record it as pending until its
+		/// outcome is reported.
+		#[transactional]
+		fn send_xcm_operation(operation_id: u32, operation: XcmOperation, previous_attempts: u32) -> DispatchResult {
+			let timeout_at = frame_system::Pallet::<T>::block_number().saturating_add(T::XcmResponseTimeout::get());
+			let (msg, query_id) = Self::construct_xcm_operation_message(operation, timeout_at)?;
 
 			let res = pallet_xcm::Pallet::<T>::send_xcm(Here, Parent, msg);
 			log::debug!("on_idle XCM result: {:?}", res);
 			ensure!(res.is_ok(), Error::<T>::XcmFailed);
 
-			// Update storage with the new available amount
-			AvailableStakingBalance::<T>::mutate(|current| {
-				*current = current.saturating_add(staking_amount_unbonded);
-			});
-
-			Self::deposit_event(Event::<T>::ScheduledUnbondWithdrew(staking_amount_unbonded));
+			PendingXcmOperations::<T>::insert(
+				operation_id,
+				PendingXcmOperation {
+					operation,
+					query_id,
+					attempts: previous_attempts,
+					timeout_at,
+				},
+			);
+			Self::deposit_event(Event::<T>::XcmOperationSent(operation_id, operation, query_id));
 			Ok(())
 		}
 
+		/// Apply the effect of the completed XCM operation.
+		fn apply_xcm_operation(operation: XcmOperation) {
+			match operation {
+				XcmOperation::WithdrawUnbonded(staking_amount) => {
+					// Update storage with the new available amount
+					AvailableStakingBalance::<T>::mutate(|current| {
+						*current = current.saturating_add(staking_amount);
+					});
+
+					Self::deposit_event(Event::<T>::ScheduledUnbondWithdrew(staking_amount));
+				}
+			}
+		}
+
+		/// Record the failed attempt of the XCM operation, and schedule the next retry with
+		/// exponential backoff. Once it has reached `T::MaxXcmRetryAttempts`, it is moved out of
+		/// the retry queue and left to governance.
+		fn record_xcm_operation_failure(operation_id: u32, operation: XcmOperation, previous_attempts: u32) {
+			let attempts = previous_attempts.saturating_add(1);
+			let delay =
+				T::XcmRetryBaseDelay::get().saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)).into());
+			let next_retry_at = T::RelayChainBlockNumber::current_block_number().saturating_add(delay);
+			let failed_operation = FailedXcmOperation {
+				operation,
+				attempts,
+				next_retry_at,
+			};
+
+			if attempts >= T::MaxXcmRetryAttempts::get() {
+				ExhaustedXcmOperations::<T>::insert(operation_id, failed_operation);
+				Self::deposit_event(Event::<T>::XcmOperationRetriesExhausted(operation_id, attempts));
+			} else {
+				FailedXcmOperations::<T>::insert(operation_id, failed_operation);
+				Self::deposit_event(Event::<T>::XcmOperationRetryScheduled(
+					operation_id,
+					attempts,
+					next_retry_at,
+				));
+			}
+		}
+
+		/// Retry the failed XCM operations that are due, within the given weight.
+		/// An operation is removed from the queue in the same transaction as it is sent, so it is
+		/// never sent twice.
+		///
+		/// return:
+		/// 	Weight: the weight used.
+		fn retry_failed_xcm_operations(weight_limit: Weight) -> Weight {
+			let now = T::RelayChainBlockNumber::current_block_number();
+			let read_weight = T::DbWeight::get().reads(1);
+			let retry_weight = <T as Config>::WeightInfo::xcm_unbond();
+
+			let mut used_weight: Weight = 0;
+			let mut due_operations: Vec<(u32, FailedXcmOperation<RelayChainBlockNumberOf<T>>)> = vec![];
+			for (operation_id, failed_operation) in FailedXcmOperations::<T>::iter() {
+				let weight = used_weight.saturating_add(read_weight);
+				if weight.saturating_add(retry_weight) > weight_limit {
+					break;
+				}
+				used_weight = weight;
+
+				if now >= failed_operation.next_retry_at {
+					used_weight = used_weight.saturating_add(retry_weight);
+					due_operations.push((operation_id, failed_operation));
+				}
+			}
+
+			for (operation_id, failed_operation) in due_operations {
+				FailedXcmOperations::<T>::remove(operation_id);
+				if Self::send_xcm_operation(operation_id, failed_operation.operation, failed_operation.attempts)
+					.is_err()
+				{
+					Self::record_xcm_operation_failure(
+						operation_id,
+						failed_operation.operation,
+						failed_operation.attempts,
+					);
+				}
+			}
+
+			used_weight
+		}
+
+		/// Settle the sent XCM operations within the given weight. An operation reported as
+		/// succeeded is applied, and one reported as failed, or without a report before its
+		/// timeout, is queued to be retried.
+		///
+		/// return:
+		/// 	Weight: the weight used.
+		fn process_pending_xcm_operations(weight_limit: Weight) -> Weight {
+			let now = frame_system::Pallet::<T>::block_number();
+			let process_weight = T::DbWeight::get().reads_writes(3, 3);
+
+			let mut used_weight: Weight = 0;
+			let mut pending_operations: Vec<(u32, PendingXcmOperation<T::BlockNumber>)> = vec![];
+			for (operation_id, pending_operation) in PendingXcmOperations::<T>::iter() {
+				let weight = used_weight.saturating_add(process_weight);
+				if weight > weight_limit {
+					break;
+				}
+				used_weight = weight;
+				pending_operations.push((operation_id, pending_operation));
+			}
+
+			for (operation_id, pending_operation) in pending_operations {
+				let succeeded = match pallet_xcm::Pallet::<T>::take_response(pending_operation.query_id) {
+					Some((Response::ExecutionResult(None), _)) => true,
+					Some((response, _)) => {
+						log::debug!("XCM operation {:?} failed: {:?}", operation_id, response);
+						false
+					}
+					None if now >= pending_operation.timeout_at => false,
+					None => continue,
+				};
+
+				PendingXcmOperations::<T>::remove(operation_id);
+				if succeeded {
+					Self::apply_xcm_operation(pending_operation.operation);
+					if !pending_operation.attempts.is_zero() {
+						Self::deposit_event(Event::<T>::XcmOperationRetrySucceeded(
+							operation_id,
+							pending_operation.attempts,
+						));
+					}
+				} else {
+					if pending_operation.attempts.is_zero() {
+						Self::deposit_event(Event::<T>::XcmOperationQueued(
+							operation_id,
+							pending_operation.operation,
+						));
+					}
+					Self::record_xcm_operation_failure(
+						operation_id,
+						pending_operation.operation,
+						pending_operation.attempts,
+					);
+				}
+			}
+
+			used_weight
+		}
+
 		/// Iterate through all redeem requests, then match them with available_staking_balance.
 		/// This should be called when new available_staking_balance becomes available.
 		///
@@ -1010,6 +1352,22 @@ pub mod module {
 		}
 	}
 
+	impl<T: Config> HomaManager<T::AccountId, Balance> for Pallet<T> {
+		#[transactional]
+		fn mint(who: &T::AccountId, amount: Balance) -> DispatchResult {
+			Self::do_mint_with_requests(who, amount, vec![])
+		}
+
+		#[transactional]
+		fn request_redeem(who: &T::AccountId, amount: Balance, fast_match_fee_rate: Permill) -> DispatchResult {
+			Self::do_request_redeem(who.clone(), amount, fast_match_fee_rate)
+		}
+
+		fn get_exchange_rate() -> ExchangeRate {
+			<Self as ExchangeRateProvider>::get_exchange_rate()
+		}
+	}
+
 	impl<T: Config> ExchangeRateProvider for Pallet<T> {
 		/// Calculate the exchange rate between the Staking and Liquid currency.
 		/// returns Ratio(staking : liquid) = total_staking_amount / liquid_total_issuance
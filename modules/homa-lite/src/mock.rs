@@ -35,7 +35,7 @@ pub use sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32};
 
 pub use cumulus_primitives_core::ParaId;
 pub use xcm::latest::prelude::*;
-pub use xcm_executor::traits::{InvertLocation, WeightBounds};
+pub use xcm_executor::traits::{InvertLocation, OnResponse, WeightBounds};
 
 pub type AccountId = AccountId32;
 pub type BlockNumber = u64;
@@ -74,6 +74,23 @@ pub fn millicent(b: Balance) -> Balance {
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
+	pub static MockXcmSendFailure: bool = false;
+	pub static MockXcmResponse: Option<Response> = Some(Response::ExecutionResult(None));
+}
+
+/// Report the outcome of the XCM message as the relaychain would, with `MockXcmResponse`.
+pub fn report_mock_xcm_outcome<Handler: OnResponse>(msg: &Xcm<()>) {
+	if let Some(response) = MockXcmResponse::get() {
+		for instruction in msg.0.iter() {
+			if let SetAppendix(Xcm(appendix)) = instruction {
+				for instruction in appendix.iter() {
+					if let ReportError { query_id, .. } = instruction {
+						Handler::on_response(&Parent.into(), *query_id, response.clone(), 0);
+					}
+				}
+			}
+		}
+	}
 }
 
 /// A mock XCM transfer.
@@ -112,12 +129,18 @@ impl InvertLocation for MockXcm {
 
 impl SendXcm for MockXcm {
 	fn send_xcm(dest: impl Into<MultiLocation>, msg: Xcm<()>) -> SendResult {
+		if MockXcmSendFailure::get() {
+			return Err(SendError::Transport("mock send failure"));
+		}
 		let dest = dest.into();
 		match dest {
 			MultiLocation {
 				parents: 1,
 				interior: Junctions::Here,
-			} => Ok(()),
+			} => {
+				report_mock_xcm_outcome::<PalletXcm>(&msg);
+				Ok(())
+			}
 			_ => Err(SendError::CannotReachDestination(dest, msg)),
 		}
 	}
@@ -250,6 +273,7 @@ impl module_currencies::Config for Runtime {
 	type EVMBridge = ();
 	type SweepOrigin = EnsureSignedBy<Root, AccountId>;
 	type OnDust = ();
+	type OnTransfer = ();
 }
 
 parameter_types! {
@@ -272,6 +296,9 @@ parameter_types! {
 	pub const SubAccountIndex: u16 = 0;
 	pub ParachainId: ParaId = ParaId::from(PARACHAIN_ID);
 	pub const StakingUpdateFrequency: BlockNumber = 100;
+	pub const XcmRetryBaseDelay: BlockNumber = 10;
+	pub const MaxXcmRetryAttempts: u32 = 3;
+	pub const XcmResponseTimeout: BlockNumber = 5;
 }
 ord_parameter_types! {
 	pub const Root: AccountId = DAVE;
@@ -309,6 +336,9 @@ impl Config for Runtime {
 	type RelayChainUnbondingSlashingSpans = RelayChainUnbondingSlashingSpans;
 	type MaxScheduledUnbonds = MaxScheduledUnbonds;
 	type StakingUpdateFrequency = StakingUpdateFrequency;
+	type XcmRetryBaseDelay = XcmRetryBaseDelay;
+	type MaxXcmRetryAttempts = MaxXcmRetryAttempts;
+	type XcmResponseTimeout = XcmResponseTimeout;
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -65,7 +65,10 @@ impl SendXcm for MockXcm {
 			MultiLocation {
 				parents: 1,
 				interior: Junctions::Here,
-			} => Ok(()),
+			} => {
+				report_mock_xcm_outcome::<PalletXcm>(&msg);
+				Ok(())
+			}
 			_ => Err(SendError::CannotReachDestination(dest, msg)),
 		}
 	}
@@ -199,6 +202,7 @@ impl module_currencies::Config for NoFeeRuntime {
 	type EVMBridge = ();
 	type SweepOrigin = EnsureSignedBy<Root, AccountId>;
 	type OnDust = ();
+	type OnTransfer = ();
 }
 
 parameter_types! {
@@ -221,6 +225,9 @@ parameter_types! {
 	pub const SubAccountIndex: u16 = 0;
 	pub ParachainId: ParaId = ParaId::from(PARACHAIN_ID);
 	pub const StakingUpdateFrequency: BlockNumber = 100;
+	pub const XcmRetryBaseDelay: BlockNumber = 10;
+	pub const MaxXcmRetryAttempts: u32 = 3;
+	pub const XcmResponseTimeout: BlockNumber = 5;
 }
 ord_parameter_types! {
 	pub const Root: AccountId = DAVE;
@@ -258,6 +265,9 @@ impl Config for NoFeeRuntime {
 	type RelayChainUnbondingSlashingSpans = RelayChainUnbondingSlashingSpans;
 	type MaxScheduledUnbonds = MaxScheduledUnbonds;
 	type StakingUpdateFrequency = StakingUpdateFrequency;
+	type XcmRetryBaseDelay = XcmRetryBaseDelay;
+	type MaxXcmRetryAttempts = MaxXcmRetryAttempts;
+	type XcmResponseTimeout = XcmResponseTimeout;
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<NoFeeRuntime>;
@@ -23,8 +23,9 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{
-	dollar, millicent, Currencies, Event, ExtBuilder, HomaLite, MockRelayBlockNumberProvider, Origin, Runtime, System,
-	ACALA, ALICE, BOB, CHARLIE, DAVE, INITIAL_BALANCE, INVALID_CALLER, KSM, LKSM,
+	dollar, millicent, Currencies, Event, ExtBuilder, HomaLite, MockRelayBlockNumberProvider, MockXcmResponse,
+	MockXcmSendFailure, Origin, Runtime, System, ACALA, ALICE, BOB, CHARLIE, DAVE, INITIAL_BALANCE, INVALID_CALLER,
+	KSM, LKSM,
 };
 use sp_runtime::traits::BadOrigin;
 
@@ -425,6 +426,167 @@ fn on_idle_can_process_xcm_to_increase_available_staking_balance() {
 	});
 }
 
+// on_idle retries the failed xcm operations with exponential backoff
+#[test]
+fn on_idle_can_retry_failed_xcm_operations() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(HomaLite::replace_schedule_unbond(Origin::root(), vec![(100, 1)]));
+
+		// The failed unbond is moved from the schedule into the retry queue.
+		MockXcmSendFailure::set(true);
+		MockRelayBlockNumberProvider::set(1);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_has_event(Event::HomaLite(crate::Event::XcmOperationQueued(
+			0,
+			XcmOperation::WithdrawUnbonded(100),
+		)));
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetryScheduled(0, 1, 11)));
+		assert_eq!(ScheduledUnbond::<Runtime>::get(), vec![]);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+		assert_eq!(
+			HomaLite::failed_xcm_operations(0),
+			Some(FailedXcmOperation {
+				operation: XcmOperation::WithdrawUnbonded(100),
+				attempts: 1,
+				next_retry_at: 11,
+			})
+		);
+
+		// Not retried before the backoff passes.
+		MockRelayBlockNumberProvider::set(10);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		assert_eq!(HomaLite::failed_xcm_operations(0).unwrap().attempts, 1);
+
+		// The backoff doubles on each failure.
+		MockRelayBlockNumberProvider::set(11);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetryScheduled(0, 2, 31)));
+
+		// The operation is left to governance after the max attempts.
+		MockRelayBlockNumberProvider::set(31);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetriesExhausted(0, 3)));
+
+		MockXcmSendFailure::set(false);
+		MockRelayBlockNumberProvider::set(1_000);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		assert_eq!(HomaLite::failed_xcm_operations(0), None);
+		assert_eq!(HomaLite::exhausted_xcm_operations(0).unwrap().attempts, 3);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+
+		assert_noop!(
+			HomaLite::force_abandon_xcm_operation(Origin::signed(ALICE), 0, false),
+			BadOrigin
+		);
+		assert_noop!(
+			HomaLite::force_abandon_xcm_operation(Origin::root(), 1, false),
+			Error::<Runtime>::XcmOperationNotFound
+		);
+		assert_ok!(HomaLite::force_abandon_xcm_operation(Origin::root(), 0, false));
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationAbandoned(
+			0,
+			XcmOperation::WithdrawUnbonded(100),
+			false,
+		)));
+		assert_eq!(HomaLite::exhausted_xcm_operations(0), None);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+
+		// The retry succeeds once the xcm can be sent, and is applied only once.
+		assert_ok!(HomaLite::replace_schedule_unbond(Origin::root(), vec![(200, 1_000)]));
+		MockXcmSendFailure::set(true);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetryScheduled(1, 1, 1_010)));
+
+		MockXcmSendFailure::set(false);
+		MockRelayBlockNumberProvider::set(1_010);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_has_event(Event::HomaLite(crate::Event::XcmOperationRetrySucceeded(1, 1)));
+		assert_eq!(HomaLite::failed_xcm_operations(1), None);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 200);
+
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 200);
+	});
+}
+
+// on_idle retries the xcm operations reported as failed or without a report
+#[test]
+fn on_idle_can_retry_xcm_operations_without_successful_response() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(HomaLite::replace_schedule_unbond(Origin::root(), vec![(100, 1)]));
+
+		// The sent unbond awaits the report of its outcome.
+		MockXcmResponse::set(None);
+		MockRelayBlockNumberProvider::set(1);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationSent(
+			0,
+			XcmOperation::WithdrawUnbonded(100),
+			0,
+		)));
+		assert_eq!(
+			HomaLite::pending_xcm_operations(0),
+			Some(PendingXcmOperation {
+				operation: XcmOperation::WithdrawUnbonded(100),
+				query_id: 0,
+				attempts: 0,
+				timeout_at: 6,
+			})
+		);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+
+		// The operation without a report is failed after the timeout.
+		System::set_block_number(5);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		assert!(HomaLite::pending_xcm_operations(0).is_some());
+
+		System::set_block_number(6);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_has_event(Event::HomaLite(crate::Event::XcmOperationQueued(
+			0,
+			XcmOperation::WithdrawUnbonded(100),
+		)));
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetryScheduled(0, 1, 11)));
+		assert_eq!(HomaLite::pending_xcm_operations(0), None);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+
+		// The operation reported as failed is retried.
+		MockXcmResponse::set(Some(Response::ExecutionResult(Some((1, XcmError::Barrier)))));
+		MockRelayBlockNumberProvider::set(11);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetryScheduled(0, 2, 31)));
+		assert_eq!(HomaLite::pending_xcm_operations(0), None);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 0);
+
+		// The exhausted operation is moved out of the retry queue.
+		MockRelayBlockNumberProvider::set(31);
+		HomaLite::on_idle(MockRelayBlockNumberProvider::get(), 5_000_000_000);
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationRetriesExhausted(0, 3)));
+		assert_eq!(HomaLite::failed_xcm_operations(0), None);
+		assert_eq!(
+			HomaLite::exhausted_xcm_operations(0),
+			Some(FailedXcmOperation {
+				operation: XcmOperation::WithdrawUnbonded(100),
+				attempts: 3,
+				next_retry_at: 71,
+			})
+		);
+
+		// The operation completed by other means is applied on abandon.
+		assert_ok!(HomaLite::force_abandon_xcm_operation(Origin::root(), 0, true));
+		System::assert_has_event(Event::HomaLite(crate::Event::ScheduledUnbondWithdrew(100)));
+		System::assert_last_event(Event::HomaLite(crate::Event::XcmOperationAbandoned(
+			0,
+			XcmOperation::WithdrawUnbonded(100),
+			true,
+		)));
+		assert_eq!(HomaLite::exhausted_xcm_operations(0), None);
+		assert_eq!(AvailableStakingBalance::<Runtime>::get(), 100);
+	});
+}
+
 // New available staking balances can redeem queued requests immediately
 #[test]
 fn new_available_staking_currency_can_handle_redeem_requests() {
@@ -101,9 +101,17 @@ fn no_fee_runtime_has_no_fees() {
 
 		let events = System::events();
 		assert_eq!(
-			events[events.len() - 9].event,
+			events[events.len() - 11].event,
 			Event::HomaLite(crate::Event::ScheduledUnbondAdded(dollar(50_000), 0))
 		);
+		assert_eq!(
+			events[events.len() - 9].event,
+			Event::HomaLite(crate::Event::XcmOperationSent(
+				0,
+				XcmOperation::WithdrawUnbonded(dollar(50_000)),
+				0
+			))
+		);
 		assert_eq!(
 			events[events.len() - 8].event,
 			Event::HomaLite(crate::Event::ScheduledUnbondWithdrew(dollar(50_000)))
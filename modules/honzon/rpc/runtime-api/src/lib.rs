@@ -0,0 +1,54 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime API definition for honzon module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::{Codec, Decode, Encode};
+use primitives::CurrencyId;
+use sp_runtime::{FixedU128, RuntimeDebug};
+
+/// The health of the CDP
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PositionHealth<Balance> {
+	/// The current collateral ratio, counting the extra collaterals
+	pub collateral_ratio: FixedU128,
+	/// The required collateral ratio of the collateral type
+	pub required_collateral_ratio: Option<FixedU128>,
+	/// The maximum debit value in stable currency that can be additionally
+	/// issued
+	pub max_additional_debit_value: Balance,
+	/// The oracle price of the collateral at which the CDP becomes unsafe,
+	/// `None` if the CDP has no debit
+	pub liquidation_price: Option<FixedU128>,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait HonzonApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The health of the CDP of the account under the collateral type,
+		/// `None` if the price of the collateral is not available.
+		fn position_health(currency_id: CurrencyId, who: AccountId) -> Option<PositionHealth<Balance>>;
+	}
+}
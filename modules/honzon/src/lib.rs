@@ -22,7 +22,10 @@
 //!
 //! The entry of the Honzon protocol for users, user can manipulate their CDP
 //! position to loan/payback, and can also authorize others to manage the their
-//! CDP under specific collateral type.
+//! CDP under specific collateral type. Separately from the authorization, which
+//! allows to take over the whole CDP, the owner can delegate others to adjust
+//! the CDP on their behalf, bounded by the debit increase and collateral
+//! withdrawal allowances.
 //!
 //! After system shutdown, some operations will be restricted.
 
@@ -31,13 +34,14 @@
 
 use frame_support::{pallet_prelude::*, traits::NamedReservableCurrency, transactional};
 use frame_system::pallet_prelude::*;
+use loans::Position;
 use primitives::{Amount, Balance, CurrencyId, ReserveIdentifier};
 use sp_runtime::{
-	traits::{StaticLookup, Zero},
-	DispatchResult,
+	traits::{Saturating, StaticLookup, Zero},
+	DispatchResult, FixedPointNumber,
 };
 use sp_std::vec::Vec;
-use support::EmergencyShutdown;
+use support::{EmergencyShutdown, Price, Ratio};
 
 mod mock;
 mod tests;
@@ -46,6 +50,18 @@ pub mod weights;
 pub use module::*;
 pub use weights::WeightInfo;
 
+/// The allowances of the delegate to adjust the CDP on behalf of the owner,
+/// consumed by the adjustments.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct DelegationLimit {
+	/// The remaining debit amount allowed to be issued.
+	pub max_debit_increase: Balance,
+	/// The remaining collateral amount allowed to be withdrawn.
+	pub max_collateral_withdrawal: Balance,
+	/// The amount reserved from the owner for the delegation.
+	pub deposit: Balance,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -67,6 +83,10 @@ pub mod module {
 		#[pallet::constant]
 		type DepositPerAuthorization: Get<Balance>;
 
+		/// The maximum number of loans that can be merged in one call.
+		#[pallet::constant]
+		type MaxMergeLoans: Get<u32>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -79,8 +99,14 @@ pub mod module {
 		AlreadyShutdown,
 		// Authorization not exists
 		AuthorizationNotExists,
+		// Delegation not exists
+		DelegationNotExists,
 		// Have authorized already
 		AlreadyAuthorized,
+		// The adjustment exceeds the delegation limit
+		DelegationLimitExceeded,
+		// Too many loans to merge
+		TooManyLoans,
 	}
 
 	#[pallet::event]
@@ -94,6 +120,17 @@ pub mod module {
 		UnAuthorization(T::AccountId, T::AccountId, CurrencyId),
 		/// Cancel all authorization. \[authorizer\]
 		UnAuthorizationAll(T::AccountId),
+		/// Set the delegation limit to adjust the loan of specific collateral.
+		/// \[owner, delegate, collateral_type, max_debit_increase,
+		/// max_collateral_withdrawal\]
+		DelegationLimitSet(T::AccountId, T::AccountId, CurrencyId, Balance, Balance),
+		/// Revoke the delegation of specific collateral.
+		/// \[owner, delegate, collateral_type\]
+		DelegationRevoked(T::AccountId, T::AccountId, CurrencyId),
+		/// The delegate adjusts the loan on behalf of the owner.
+		/// \[owner, delegate, collateral_type, collateral_adjustment,
+		/// debit_adjustment\]
+		AdjustLoanOnBehalf(T::AccountId, T::AccountId, CurrencyId, Amount, Amount),
 	}
 
 	/// The authorization relationship map from
@@ -112,6 +149,23 @@ pub mod module {
 		OptionQuery,
 	>;
 
+	/// The remaining allowances of the delegate to adjust the loan on behalf
+	/// of the owner. A delegation doesn't grant the authorization.
+	///
+	/// DelegationLimits: double_map AccountId, (CurrencyId, T::AccountId) =>
+	/// Option<DelegationLimit>
+	#[pallet::storage]
+	#[pallet::getter(fn delegation_limits)]
+	pub type DelegationLimits<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(CurrencyId, T::AccountId),
+		DelegationLimit,
+		OptionQuery,
+	>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -129,6 +183,8 @@ pub mod module {
 		/// - `debit_adjustment`: signed amount, positive means to issue some amount of stablecoin
 		///   to caller according to the debit adjustment, negative means caller will payback some
 		///   amount of stablecoin to CDP according to to the debit adjustment.
+		///
+		/// The weight is refunded if only the collateral is adjusted.
 		#[pallet::weight(<T as Config>::WeightInfo::adjust_loan())]
 		#[transactional]
 		pub fn adjust_loan(
@@ -136,7 +192,7 @@ pub mod module {
 			currency_id: CurrencyId,
 			collateral_adjustment: Amount,
 			debit_adjustment: Amount,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
 			// not allowed to adjust the debit after system shutdown
@@ -144,6 +200,35 @@ pub mod module {
 				ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
 			}
 			<cdp_engine::Pallet<T>>::adjust_position(&who, currency_id, collateral_adjustment, debit_adjustment)?;
+
+			// no stable currency is issued or burned without the debit adjustment
+			if debit_adjustment.is_zero() {
+				Ok(Some(<T as Config>::WeightInfo::adjust_loan_without_debit()).into())
+			} else {
+				Ok(().into())
+			}
+		}
+
+		/// Adjust the loans of caller under multiple collateral types atomically,
+		/// the adjustments are performed in order.
+		///
+		/// - `adjustments`: the list of collateral currency id, collateral adjustment and debit
+		///   adjustment, with the same meaning of the params of `adjust_loan`.
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_loans(adjustments.len() as u32))]
+		#[transactional]
+		pub fn adjust_loans(origin: OriginFor<T>, adjustments: Vec<(CurrencyId, Amount, Amount)>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// not allowed to adjust the debit after system shutdown
+			if adjustments
+				.iter()
+				.any(|(_, _, debit_adjustment)| !debit_adjustment.is_zero())
+			{
+				ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			}
+			for (currency_id, collateral_adjustment, debit_adjustment) in adjustments {
+				<cdp_engine::Pallet<T>>::adjust_position(&who, currency_id, collateral_adjustment, debit_adjustment)?;
+			}
 			Ok(())
 		}
 
@@ -178,6 +263,92 @@ pub mod module {
 			Ok(())
 		}
 
+		/// Generate the debit of `increase_debit_value` for caller's CDP, swap
+		/// the stable token issued to collateral on DEX and deposit it into
+		/// the CDP, to lever up the CDP in one step.
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `increase_debit_value`: the debit value to increase.
+		/// - `min_increase_collateral`: the min collateral amount swapped from the stable token.
+		#[pallet::weight(<T as Config>::WeightInfo::expand_position_collateral())]
+		#[transactional]
+		pub fn expand_position_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] increase_debit_value: Balance,
+			#[pallet::compact] min_increase_collateral: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			<cdp_engine::Pallet<T>>::expand_position_collateral(
+				&who,
+				currency_id,
+				increase_debit_value,
+				min_increase_collateral,
+			)?;
+			Ok(())
+		}
+
+		/// Swap `decrease_collateral` of caller's CDP to stable token on DEX
+		/// to repay the debit, to deleverage the CDP in one step.
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `decrease_collateral`: the collateral amount to swap.
+		/// - `min_decrease_debit_value`: the min stable token amount swapped from the collateral.
+		#[pallet::weight(<T as Config>::WeightInfo::shrink_position_debit())]
+		#[transactional]
+		pub fn shrink_position_debit(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] decrease_collateral: Balance,
+			#[pallet::compact] min_decrease_debit_value: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			<cdp_engine::Pallet<T>>::shrink_position_debit(
+				&who,
+				currency_id,
+				decrease_collateral,
+				min_decrease_debit_value,
+			)?;
+			Ok(())
+		}
+
+		/// Adjust the extra collateral of `extra_currency_id` backing caller's CDP
+		/// of `currency_id`, the collateral ratio of the CDP is computed over the
+		/// aggregate value of its collaterals.
+		///
+		/// - `currency_id`: collateral currency id of the CDP.
+		/// - `extra_currency_id`: the currency id of the extra collateral.
+		/// - `collateral_adjustment`: signed amount, positive means to deposit the extra collateral
+		///   into CDP, negative means withdraw the extra collateral from CDP.
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_extra_collateral())]
+		#[transactional]
+		pub fn adjust_extra_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			extra_currency_id: CurrencyId,
+			collateral_adjustment: Amount,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// the extra collateral of the CDP with debit is kept for settlement after system
+			// shutdown
+			if T::EmergencyShutdown::is_shutdown() {
+				ensure!(
+					<loans::Pallet<T>>::positions(currency_id, &who).debit.is_zero(),
+					Error::<T>::AlreadyShutdown
+				);
+			}
+			<cdp_engine::Pallet<T>>::adjust_extra_collateral(
+				&who,
+				currency_id,
+				extra_currency_id,
+				collateral_adjustment,
+			)?;
+			Ok(())
+		}
+
 		/// Transfer the whole CDP of `from` under `currency_id` to caller's CDP
 		/// under the same `currency_id`, caller must have the authorization of
 		/// `from` for the specific collateral type
@@ -199,6 +370,54 @@ pub mod module {
 			Ok(())
 		}
 
+		/// Transfer the whole CDP of caller under `currency_id` to `to`'s CDP
+		/// under the same `currency_id`, caller must have the authorization of
+		/// `to` for the specific collateral type
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `to`: authorizer account to receive the CDP
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_loan_to())]
+		#[transactional]
+		pub fn transfer_loan_to(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			to: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			Self::check_authorization(&to, &from, currency_id)?;
+			<loans::Pallet<T>>::transfer_loan(&from, &to, currency_id)?;
+			Ok(())
+		}
+
+		/// Merge the whole CDPs of `from` accounts under `currency_id` into
+		/// caller's CDP under the same `currency_id`, caller must have the
+		/// authorization of each of `from` for the specific collateral type
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `from`: authorizer accounts, at most `T::MaxMergeLoans`
+		#[pallet::weight(<T as Config>::WeightInfo::merge_loans(from.len() as u32))]
+		#[transactional]
+		pub fn merge_loans(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			from: Vec<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let to = ensure_signed(origin)?;
+			ensure!(from.len() <= T::MaxMergeLoans::get() as usize, Error::<T>::TooManyLoans);
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			for from in from {
+				let from = T::Lookup::lookup(from)?;
+				if from == to {
+					continue;
+				}
+				Self::check_authorization(&from, &to, currency_id)?;
+				<loans::Pallet<T>>::transfer_loan(&from, &to, currency_id)?;
+			}
+			Ok(())
+		}
+
 		/// Authorize `to` to manipulate the loan under `currency_id`
 		///
 		/// - `currency_id`: collateral currency id.
@@ -250,16 +469,145 @@ pub mod module {
 			Ok(())
 		}
 
-		/// Cancel all authorization of caller
+		/// Cancel all authorization and delegations of caller
 		#[pallet::weight(<T as Config>::WeightInfo::unauthorize_all(<T as cdp_engine::Config>::CollateralCurrencyIds::get().len() as u32))]
 		#[transactional]
 		pub fn unauthorize_all(origin: OriginFor<T>) -> DispatchResult {
 			let from = ensure_signed(origin)?;
 			Authorization::<T>::remove_prefix(&from, None);
+			DelegationLimits::<T>::remove_prefix(&from, None);
 			<T as Config>::Currency::unreserve_all_named(&RESERVE_ID, &from);
 			Self::deposit_event(Event::UnAuthorizationAll(from));
 			Ok(())
 		}
+
+		/// Set the delegation limit for `to` under `currency_id`, to let `to`
+		/// adjust the loan on behalf of caller. The delegation is independent of
+		/// the authorization, and doesn't allow `to` to transfer the loan.
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `to`: authorizee account
+		/// - `max_debit_increase`: the debit amount allowed to be issued.
+		/// - `max_collateral_withdrawal`: the collateral amount allowed to be withdrawn.
+		#[pallet::weight(<T as Config>::WeightInfo::set_delegation_limit())]
+		#[transactional]
+		pub fn set_delegation_limit(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			to: <T::Lookup as StaticLookup>::Source,
+			max_debit_increase: Balance,
+			max_collateral_withdrawal: Balance,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			if from == to {
+				return Ok(());
+			}
+
+			DelegationLimits::<T>::try_mutate(&from, (currency_id, &to), |maybe_limit| -> DispatchResult {
+				let deposit = match maybe_limit {
+					Some(limit) => limit.deposit,
+					None => {
+						let reserve_amount = T::DepositPerAuthorization::get();
+						<T as Config>::Currency::reserve_named(&RESERVE_ID, &from, reserve_amount)?;
+						reserve_amount
+					}
+				};
+				*maybe_limit = Some(DelegationLimit {
+					max_debit_increase,
+					max_collateral_withdrawal,
+					deposit,
+				});
+				Ok(())
+			})?;
+			Self::deposit_event(Event::DelegationLimitSet(
+				from,
+				to,
+				currency_id,
+				max_debit_increase,
+				max_collateral_withdrawal,
+			));
+			Ok(())
+		}
+
+		/// Revoke the delegation of `to` under `currency_id`
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `to`: delegate account
+		#[pallet::weight(<T as Config>::WeightInfo::unauthorize())]
+		#[transactional]
+		pub fn revoke_delegation(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			to: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(to)?;
+			let limit =
+				DelegationLimits::<T>::take(&from, (currency_id, &to)).ok_or(Error::<T>::DelegationNotExists)?;
+			<T as Config>::Currency::unreserve_named(&RESERVE_ID, &from, limit.deposit);
+			Self::deposit_event(Event::DelegationRevoked(from, to, currency_id));
+			Ok(())
+		}
+
+		/// Adjust the loans of `owner` under `currency_id` by specific
+		/// `collateral_adjustment` and `debit_adjustment`, caller must have the
+		/// delegation of `owner` for the specific collateral type. The collateral
+		/// and stablecoin are transferred from/to `owner`.
+		///
+		/// - `owner`: delegating account
+		/// - `currency_id`: collateral currency id.
+		/// - `collateral_adjustment`: signed amount, positive means to deposit collateral currency
+		///   into CDP, negative means withdraw collateral currency from CDP.
+		/// - `debit_adjustment`: signed amount, positive means to issue some amount of stablecoin
+		///   to owner according to the debit adjustment, negative means owner will payback some
+		///   amount of stablecoin to CDP according to to the debit adjustment.
+		#[pallet::weight(<T as Config>::WeightInfo::adjust_loan_on_behalf())]
+		#[transactional]
+		pub fn adjust_loan_on_behalf(
+			origin: OriginFor<T>,
+			owner: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyId,
+			collateral_adjustment: Amount,
+			debit_adjustment: Amount,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			// not allowed to adjust the debit after system shutdown
+			if !debit_adjustment.is_zero() {
+				ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			}
+
+			if owner != who {
+				DelegationLimits::<T>::try_mutate(&owner, (currency_id, &who), |maybe_limit| -> DispatchResult {
+					let limit = maybe_limit.as_mut().ok_or(Error::<T>::NoPermission)?;
+					if debit_adjustment.is_positive() {
+						limit.max_debit_increase = limit
+							.max_debit_increase
+							.checked_sub(<loans::Pallet<T>>::balance_try_from_amount_abs(debit_adjustment)?)
+							.ok_or(Error::<T>::DelegationLimitExceeded)?;
+					}
+					if collateral_adjustment.is_negative() {
+						limit.max_collateral_withdrawal = limit
+							.max_collateral_withdrawal
+							.checked_sub(<loans::Pallet<T>>::balance_try_from_amount_abs(collateral_adjustment)?)
+							.ok_or(Error::<T>::DelegationLimitExceeded)?;
+					}
+					Ok(())
+				})?;
+			}
+
+			<cdp_engine::Pallet<T>>::adjust_position(&owner, currency_id, collateral_adjustment, debit_adjustment)?;
+			Self::deposit_event(Event::AdjustLoanOnBehalf(
+				owner,
+				who,
+				currency_id,
+				collateral_adjustment,
+				debit_adjustment,
+			));
+			Ok(())
+		}
 	}
 }
 
@@ -272,4 +620,49 @@ impl<T: Config> Pallet<T> {
 		);
 		Ok(())
 	}
+
+	/// The health of the CDP of `who` under `currency_id`, with the current
+	/// collateral ratio, the required collateral ratio, the maximum debit
+	/// value that can be additionally issued, and the price of the collateral
+	/// at which the CDP becomes unsafe. `None` if the price of the collateral
+	/// is not available.
+	pub fn position_health(
+		currency_id: CurrencyId,
+		who: &T::AccountId,
+	) -> Option<(Ratio, Option<Ratio>, Balance, Option<Price>)> {
+		let price = <cdp_engine::Pallet<T>>::get_collateral_price(currency_id)?;
+		let Position { collateral, debit } = <loans::Pallet<T>>::positions(currency_id, who);
+		let vault_collateral = <cdp_engine::Pallet<T>>::vault_collateral(currency_id, who, collateral);
+		let debit_value = <cdp_engine::Pallet<T>>::get_debit_value(currency_id, debit);
+
+		let collateral_ratio =
+			<cdp_engine::Pallet<T>>::calculate_collateral_ratio(currency_id, vault_collateral, debit, price);
+		let required_collateral_ratio = <cdp_engine::Pallet<T>>::required_collateral_ratio(currency_id);
+		let liquidation_ratio = <cdp_engine::Pallet<T>>::get_liquidation_ratio(currency_id);
+		let max_additional_debit_value = <cdp_engine::Pallet<T>>::max_additional_debit_value(currency_id, who);
+
+		// the extra collaterals are valued in stable currency, only the price of the
+		// collateral moves
+		let liquidation_price = if debit.is_zero() {
+			None
+		} else {
+			let extra_collateral_value = price.saturating_mul_int(vault_collateral.saturating_sub(collateral));
+			let unsafe_collateral_value = liquidation_ratio.saturating_mul_int(debit_value);
+			if unsafe_collateral_value <= extra_collateral_value {
+				Some(Price::zero())
+			} else {
+				Price::checked_from_rational(
+					unsafe_collateral_value.saturating_sub(extra_collateral_value),
+					collateral,
+				)
+			}
+		};
+
+		Some((
+			collateral_ratio,
+			required_collateral_ratio,
+			max_additional_debit_value,
+			liquidation_price,
+		))
+	}
 }
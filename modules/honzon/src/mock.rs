@@ -144,6 +144,8 @@ impl loans::Config for Runtime {
 	type CDPTreasury = CDPTreasuryModule;
 	type PalletId = LoansPalletId;
 	type OnUpdateLoan = ();
+	type OnUpdateDebit = ();
+	type OnPositionUpdated = ();
 }
 
 pub struct MockPriceSource;
@@ -207,6 +209,9 @@ ord_parameter_types! {
 parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = AUSD;
 	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 10;
+	pub const OffsetPeriod: BlockNumber = 1;
 	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"aca/cdpt");
 	pub TreasuryAccount: AccountId = PalletId(*b"aca/hztr").into_account();
 }
@@ -215,12 +220,31 @@ impl cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = MockAuctionManager;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
 	type DEX = ();
+	type Swap = ();
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = TreasuryAccount;
+	type PriceSource = MockPriceSource;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = ();
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = ();
+	type MinimumIdleWorkWeight = ();
+	type EmergencyShutdown = MockEmergencyShutdown;
 	type WeightInfo = ();
 }
 
@@ -236,15 +260,25 @@ impl pallet_timestamp::Config for Runtime {
 
 parameter_types! {
 	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![BTC, DOT];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![];
 	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(3, 2);
 	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::one();
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(10, 100);
 	pub const MinimumDebitValue: Balance = 2;
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(50, 100);
 	pub const UnsignedPriority: u64 = 1 << 20;
+	pub const UnsignedLongevity: u64 = 64;
 	pub DefaultSwapParitalPathList: Vec<Vec<CurrencyId>> = vec![
 		vec![AUSD],
 	];
+	pub const DebitExchangeRateCheckpointInterval: BlockNumber = 10;
+	pub const MaxDebitExchangeRateCheckpoints: u32 = 10;
+	pub const InterestRateCurvePeriod: BlockNumber = 10;
+	pub const StopLossExecutionFee: Balance = 2;
+	pub const RiskParamsUpdateDelay: BlockNumber = 0;
+	pub const LiquidationKeeperBounty: Balance = 2;
+	pub LiquidationKeeperBountyShare: Ratio = Ratio::saturating_from_rational(1, 10);
+	pub const MaxLiquidationBountyPerBlock: Balance = 50;
 }
 
 impl cdp_engine::Config for Runtime {
@@ -258,11 +292,25 @@ impl cdp_engine::Config for Runtime {
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CDPTreasuryModule;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyOrigin = EnsureSignedBy<One, AccountId>;
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type UnsignedPriority = UnsignedPriority;
+	type UnsignedLongevity = UnsignedLongevity;
 	type EmergencyShutdown = MockEmergencyShutdown;
 	type UnixTime = Timestamp;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type DEX = ();
+	type DEXPriceCumulative = ();
+	type DebitExchangeRateCheckpointInterval = DebitExchangeRateCheckpointInterval;
+	type MaxDebitExchangeRateCheckpoints = MaxDebitExchangeRateCheckpoints;
+	type InterestRateCurvePeriod = InterestRateCurvePeriod;
+	type OnLiquidation = ();
+	type StopLossExecutionFee = StopLossExecutionFee;
+	type CircuitBreakerRecorder = ();
+	type RiskParamsUpdateDelay = RiskParamsUpdateDelay;
+	type LiquidationKeeperBounty = LiquidationKeeperBounty;
+	type LiquidationKeeperBountyShare = LiquidationKeeperBountyShare;
+	type MaxLiquidationBountyPerBlock = MaxLiquidationBountyPerBlock;
 	type WeightInfo = ();
 }
 
@@ -271,12 +319,14 @@ type Block = frame_system::mocking::MockBlock<Runtime>;
 
 parameter_types! {
 	pub const DepositPerAuthorization: Balance = 100;
+	pub const MaxMergeLoans: u32 = 2;
 }
 
 impl Config for Runtime {
 	type Event = Event;
 	type Currency = PalletBalances;
 	type DepositPerAuthorization = DepositPerAuthorization;
+	type MaxMergeLoans = MaxMergeLoans;
 	type WeightInfo = ();
 }
 
@@ -23,7 +23,7 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{Event, *};
-use orml_traits::Change;
+use orml_traits::{Change, MultiCurrency};
 use sp_runtime::FixedPointNumber;
 use support::{Rate, Ratio};
 
@@ -87,6 +87,155 @@ fn unauthorize_all_should_work() {
 	});
 }
 
+#[test]
+fn set_delegation_limit_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(HonzonModule::set_delegation_limit(
+			Origin::signed(ALICE),
+			BTC,
+			BOB,
+			100,
+			50
+		));
+		System::assert_last_event(Event::HonzonModule(crate::Event::DelegationLimitSet(
+			ALICE, BOB, BTC, 100, 50,
+		)));
+		assert_eq!(
+			HonzonModule::delegation_limits(ALICE, (BTC, BOB)),
+			Some(DelegationLimit {
+				max_debit_increase: 100,
+				max_collateral_withdrawal: 50,
+				deposit: DepositPerAuthorization::get(),
+			})
+		);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), DepositPerAuthorization::get());
+
+		// the deposit is reserved once
+		assert_ok!(HonzonModule::set_delegation_limit(
+			Origin::signed(ALICE),
+			BTC,
+			BOB,
+			200,
+			0
+		));
+		assert_eq!(
+			HonzonModule::delegation_limits(ALICE, (BTC, BOB)),
+			Some(DelegationLimit {
+				max_debit_increase: 200,
+				max_collateral_withdrawal: 0,
+				deposit: DepositPerAuthorization::get(),
+			})
+		);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), DepositPerAuthorization::get());
+
+		// the delegation doesn't grant the authorization
+		assert_noop!(
+			HonzonModule::transfer_loan_from(Origin::signed(BOB), BTC, ALICE),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_noop!(
+			HonzonModule::revoke_delegation(Origin::signed(ALICE), BTC, CAROL),
+			Error::<Runtime>::DelegationNotExists
+		);
+		assert_ok!(HonzonModule::revoke_delegation(Origin::signed(ALICE), BTC, BOB));
+		System::assert_last_event(Event::HonzonModule(crate::Event::DelegationRevoked(ALICE, BOB, BTC)));
+		assert_eq!(HonzonModule::delegation_limits(ALICE, (BTC, BOB)), None);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn adjust_loan_on_behalf_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_noop!(
+			HonzonModule::adjust_loan_on_behalf(Origin::signed(BOB), ALICE, BTC, 100, 50),
+			Error::<Runtime>::NoPermission
+		);
+
+		// the authorization without the delegation is not enough
+		assert_ok!(HonzonModule::authorize(Origin::signed(ALICE), BTC, BOB));
+		assert_noop!(
+			HonzonModule::adjust_loan_on_behalf(Origin::signed(BOB), ALICE, BTC, 100, 50),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(HonzonModule::set_delegation_limit(
+			Origin::signed(ALICE),
+			BTC,
+			BOB,
+			100,
+			50
+		));
+		assert_ok!(HonzonModule::adjust_loan_on_behalf(
+			Origin::signed(BOB),
+			ALICE,
+			BTC,
+			100,
+			50
+		));
+		System::assert_last_event(Event::HonzonModule(crate::Event::AdjustLoanOnBehalf(
+			ALICE, BOB, BTC, 100, 50,
+		)));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+		assert_eq!(Currencies::free_balance(BTC, &ALICE), 900);
+		assert_eq!(Currencies::free_balance(AUSD, &ALICE), 50);
+		assert_eq!(Currencies::free_balance(BTC, &BOB), 1000);
+		assert_eq!(
+			HonzonModule::delegation_limits(ALICE, (BTC, BOB)),
+			Some(DelegationLimit {
+				max_debit_increase: 50,
+				max_collateral_withdrawal: 50,
+				deposit: DepositPerAuthorization::get(),
+			})
+		);
+
+		assert_noop!(
+			HonzonModule::adjust_loan_on_behalf(Origin::signed(BOB), ALICE, BTC, 0, 51),
+			Error::<Runtime>::DelegationLimitExceeded
+		);
+		assert_noop!(
+			HonzonModule::adjust_loan_on_behalf(Origin::signed(BOB), ALICE, BTC, -51, 0),
+			Error::<Runtime>::DelegationLimitExceeded
+		);
+		assert_ok!(HonzonModule::adjust_loan_on_behalf(
+			Origin::signed(BOB),
+			ALICE,
+			BTC,
+			-20,
+			-10
+		));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 80);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 40);
+		assert_eq!(
+			HonzonModule::delegation_limits(ALICE, (BTC, BOB)),
+			Some(DelegationLimit {
+				max_debit_increase: 50,
+				max_collateral_withdrawal: 30,
+				deposit: DepositPerAuthorization::get(),
+			})
+		);
+
+		mock_shutdown();
+		assert_noop!(
+			HonzonModule::adjust_loan_on_behalf(Origin::signed(BOB), ALICE, BTC, 0, 10),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
+
 #[test]
 fn transfer_loan_from_should_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -117,6 +266,77 @@ fn transfer_unauthorization_loans_should_not_work() {
 	});
 }
 
+#[test]
+fn transfer_loan_to_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(BOB), BTC, 100, 50));
+		assert_noop!(
+			HonzonModule::transfer_loan_to(Origin::signed(BOB), BTC, ALICE),
+			Error::<Runtime>::NoPermission,
+		);
+
+		assert_ok!(HonzonModule::authorize(Origin::signed(ALICE), BTC, BOB));
+		assert_ok!(HonzonModule::transfer_loan_to(Origin::signed(BOB), BTC, ALICE));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 0);
+		assert_eq!(LoansModule::positions(BTC, BOB).debit, 0);
+
+		mock_shutdown();
+		assert_noop!(
+			HonzonModule::transfer_loan_to(Origin::signed(BOB), BTC, ALICE),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+	});
+}
+
+#[test]
+fn merge_loans_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 100, 50));
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(BOB), BTC, 200, 100));
+		assert_noop!(
+			HonzonModule::merge_loans(Origin::signed(BOB), BTC, vec![BOB, ALICE]),
+			Error::<Runtime>::NoPermission,
+		);
+
+		assert_ok!(HonzonModule::authorize(Origin::signed(ALICE), BTC, BOB));
+		assert_noop!(
+			HonzonModule::merge_loans(Origin::signed(BOB), BTC, vec![BOB, ALICE, ALICE]),
+			Error::<Runtime>::TooManyLoans,
+		);
+		assert_ok!(HonzonModule::merge_loans(Origin::signed(BOB), BTC, vec![BOB, ALICE]));
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 300);
+		assert_eq!(LoansModule::positions(BTC, BOB).debit, 150);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+
+		mock_shutdown();
+		assert_noop!(
+			HonzonModule::merge_loans(Origin::signed(BOB), BTC, vec![ALICE]),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+	});
+}
+
 #[test]
 fn adjust_loan_should_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -135,6 +355,199 @@ fn adjust_loan_should_work() {
 	});
 }
 
+#[test]
+fn adjust_loan_refunds_weight_without_debit_adjustment() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_eq!(
+			HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 100, 0)
+				.unwrap()
+				.actual_weight,
+			Some(<() as WeightInfo>::adjust_loan_without_debit())
+		);
+		assert_eq!(
+			HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 0, 50)
+				.unwrap()
+				.actual_weight,
+			None
+		);
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+	});
+}
+
+#[test]
+fn adjust_loans_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		for currency_id in [BTC, DOT] {
+			assert_ok!(CDPEngineModule::set_collateral_params(
+				Origin::signed(1),
+				currency_id,
+				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+				Change::NewValue(10000),
+			));
+		}
+		assert_ok!(HonzonModule::adjust_loans(
+			Origin::signed(ALICE),
+			vec![(BTC, 100, 50), (DOT, 100, 50)]
+		));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+		assert_eq!(LoansModule::positions(DOT, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(DOT, ALICE).debit, 50);
+
+		// all adjustments fail if any of them fails
+		assert_noop!(
+			HonzonModule::adjust_loans(Origin::signed(ALICE), vec![(BTC, -100, -50), (DOT, 0, 100)]),
+			cdp_engine::Error::<Runtime>::BelowRequiredCollateralRatio,
+		);
+
+		assert_ok!(HonzonModule::adjust_loans(
+			Origin::signed(ALICE),
+			vec![(BTC, -100, -50), (DOT, 100, 50)]
+		));
+		assert_eq!(LoansModule::positions(BTC, ALICE).collateral, 0);
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 0);
+		assert_eq!(LoansModule::positions(DOT, ALICE).collateral, 200);
+		assert_eq!(LoansModule::positions(DOT, ALICE).debit, 100);
+
+		mock_shutdown();
+		assert_noop!(
+			HonzonModule::adjust_loans(Origin::signed(ALICE), vec![(BTC, 0, 0), (DOT, 0, -10)]),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+		assert_ok!(HonzonModule::adjust_loans(Origin::signed(ALICE), vec![(DOT, -10, 0)]));
+	});
+}
+
+#[test]
+fn adjust_extra_collateral_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 100, 50));
+		assert_noop!(
+			HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 0, 20),
+			cdp_engine::Error::<Runtime>::BelowRequiredCollateralRatio,
+		);
+
+		// the extra collateral is counted in the collateral ratio
+		assert_ok!(HonzonModule::adjust_extra_collateral(
+			Origin::signed(ALICE),
+			BTC,
+			DOT,
+			50
+		));
+		assert_eq!(LoansModule::extra_collateral((BTC, ALICE), DOT), 50);
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 0, 20));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 70);
+
+		assert_noop!(
+			HonzonModule::adjust_extra_collateral(Origin::signed(ALICE), BTC, DOT, -50),
+			cdp_engine::Error::<Runtime>::BelowRequiredCollateralRatio,
+		);
+		assert_noop!(
+			HonzonModule::adjust_extra_collateral(Origin::signed(ALICE), BTC, ACA, 50),
+			cdp_engine::Error::<Runtime>::InvalidCollateralType,
+		);
+
+		mock_shutdown();
+		assert_noop!(
+			HonzonModule::adjust_extra_collateral(Origin::signed(ALICE), BTC, DOT, -10),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+	});
+}
+
+#[test]
+fn position_health_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(1),
+			BTC,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10000),
+		));
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 100, 0));
+		assert_eq!(
+			HonzonModule::position_health(BTC, &ALICE),
+			Some((
+				Ratio::max_value(),
+				Some(Ratio::saturating_from_rational(9, 5)),
+				55,
+				None
+			))
+		);
+
+		assert_ok!(HonzonModule::adjust_loan(Origin::signed(ALICE), BTC, 0, 50));
+		assert_eq!(
+			HonzonModule::position_health(BTC, &ALICE),
+			Some((
+				Ratio::saturating_from_rational(2, 1),
+				Some(Ratio::saturating_from_rational(9, 5)),
+				5,
+				Some(Price::saturating_from_rational(3, 4))
+			))
+		);
+
+		// the extra collateral is counted, and keeps its value when the price moves
+		assert_ok!(HonzonModule::adjust_extra_collateral(
+			Origin::signed(ALICE),
+			BTC,
+			DOT,
+			50
+		));
+		assert_eq!(
+			HonzonModule::position_health(BTC, &ALICE),
+			Some((
+				Ratio::saturating_from_rational(3, 1),
+				Some(Ratio::saturating_from_rational(9, 5)),
+				33,
+				Some(Price::saturating_from_rational(1, 4))
+			))
+		);
+
+		// limited by the cap per account
+		assert_ok!(CDPEngineModule::set_max_debit_per_account(
+			Origin::signed(1),
+			BTC,
+			Some(60)
+		));
+		assert_eq!(
+			HonzonModule::position_health(BTC, &ALICE).map(|health| health.2),
+			Some(10)
+		);
+
+		// nothing can be issued while the debit issuance is paused
+		assert_ok!(CDPEngineModule::set_debit_issuance_paused(Origin::signed(1), true));
+		assert_eq!(
+			HonzonModule::position_health(BTC, &ALICE).map(|health| health.2),
+			Some(0)
+		);
+	});
+}
+
 #[test]
 fn on_emergency_shutdown_should_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -151,6 +564,14 @@ fn on_emergency_shutdown_should_work() {
 			HonzonModule::close_loan_has_debit_by_dex(Origin::signed(ALICE), BTC, 100, None),
 			Error::<Runtime>::AlreadyShutdown,
 		);
+		assert_noop!(
+			HonzonModule::expand_position_collateral(Origin::signed(ALICE), BTC, 100, 0),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+		assert_noop!(
+			HonzonModule::shrink_position_debit(Origin::signed(ALICE), BTC, 100, 0),
+			Error::<Runtime>::AlreadyShutdown,
+		);
 	});
 }
 
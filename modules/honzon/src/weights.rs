@@ -54,6 +54,15 @@ pub trait WeightInfo {
 	fn transfer_loan_from() -> Weight;
 	fn close_loan_has_debit_by_dex(u: u32, ) -> Weight;
 	fn close_loan_has_debit_by_dex_no_path() -> Weight;
+	fn expand_position_collateral() -> Weight;
+	fn shrink_position_debit() -> Weight;
+	fn adjust_extra_collateral() -> Weight;
+	fn transfer_loan_to() -> Weight;
+	fn merge_loans(c: u32, ) -> Weight;
+	fn set_delegation_limit() -> Weight;
+	fn adjust_loan_on_behalf() -> Weight;
+	fn adjust_loans(c: u32, ) -> Weight;
+	fn adjust_loan_without_debit() -> Weight;
 }
 
 /// Weights for module_honzon using the Acala node and recommended hardware.
@@ -100,6 +109,54 @@ impl<T: frame_system::Config> WeightInfo for AcalaWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(32 as Weight))
 			.saturating_add(T::DbWeight::get().writes(14 as Weight))
 	}
+	fn expand_position_collateral() -> Weight {
+		(312_460_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(28 as Weight))
+			.saturating_add(T::DbWeight::get().writes(15 as Weight))
+	}
+	fn shrink_position_debit() -> Weight {
+		(298_741_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(27 as Weight))
+			.saturating_add(T::DbWeight::get().writes(14 as Weight))
+	}
+	fn adjust_extra_collateral() -> Weight {
+		(186_214_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(22 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	fn transfer_loan_to() -> Weight {
+		(166_925_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(21 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn merge_loans(c: u32, ) -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add((166_925_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((21 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(c as Weight)))
+	}
+	fn set_delegation_limit() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn adjust_loan_on_behalf() -> Weight {
+		(255_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(28 as Weight))
+			.saturating_add(T::DbWeight::get().writes(13 as Weight))
+	}
+	fn adjust_loans(c: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((245_614_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads((26 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes((11 as Weight).saturating_mul(c as Weight)))
+	}
+	fn adjust_loan_without_debit() -> Weight {
+		(245_614_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(26 as Weight))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -145,4 +202,52 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(32 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(14 as Weight))
 	}
+	fn expand_position_collateral() -> Weight {
+		(312_460_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(28 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(15 as Weight))
+	}
+	fn shrink_position_debit() -> Weight {
+		(298_741_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(27 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(14 as Weight))
+	}
+	fn adjust_extra_collateral() -> Weight {
+		(186_214_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(22 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn transfer_loan_to() -> Weight {
+		(166_925_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(21 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn merge_loans(c: u32, ) -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add((166_925_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((21 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(c as Weight)))
+	}
+	fn set_delegation_limit() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn adjust_loan_on_behalf() -> Weight {
+		(255_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(28 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(13 as Weight))
+	}
+	fn adjust_loans(c: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((245_614_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads((26 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((11 as Weight).saturating_mul(c as Weight)))
+	}
+	fn adjust_loan_without_debit() -> Weight {
+		(245_614_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(26 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+	}
 }
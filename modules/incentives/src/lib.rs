@@ -29,6 +29,7 @@
 //! Pool types:
 //! 1. Loans: record the shares and rewards for users of Loans(Honzon protocol).
 //! 2. Dex: record the shares and rewards for DEX makers who staking LP token.
+//! 3. Token: record the shares and rewards for users who staking token, such as LDOT.
 //!
 //! Rewards accumulation:
 //! 1. Incentives: periodicly(AccumulatePeriod), accumulate fixed amount according to Incentive.
@@ -37,6 +38,10 @@
 //! 2. DexSaving: periodicly(AccumulatePeriod), the reward currency is Stable(KUSD/AUSD),
 //! the accumulation amount is the multiplier of DexSavingRewardRates and the stable amount of
 //! corresponding liquidity pool. CDPTreasury will issue the stable currency to RewardsSource.
+//!
+//! The pools are accumulated at most `MaxAccumulatePoolsPerBlock` per block, the accumulation of
+//! a period continues in the following blocks from the cursor of the last pool accumulated. The
+//! periods started before it finishes are queued and accumulated in turn, none is skipped.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -52,7 +57,7 @@ use sp_runtime::{
 	DispatchResult, FixedPointNumber, RuntimeDebug,
 };
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
-use support::{CDPTreasury, DEXIncentives, DEXManager, EmergencyShutdown, Rate};
+use support::{CDPTreasury, DEXIncentives, DEXManager, EmergencyShutdown, PayCleanupBounty, Rate, TokenIncentives};
 
 mod mock;
 mod tests;
@@ -69,6 +74,9 @@ pub enum PoolId {
 
 	/// Rewards and shares pool for DEX makers who stake LP token(LPCurrencyId)
 	Dex(CurrencyId),
+
+	/// Rewards and shares pool for users who stake the token(TokenCurrencyId)
+	Token(CurrencyId),
 }
 
 #[frame_support::pallet]
@@ -86,6 +94,10 @@ pub mod module {
 		#[pallet::constant]
 		type AccumulatePeriod: Get<Self::BlockNumber>;
 
+		/// The max number of pools to accumulate rewards in a block.
+		#[pallet::constant]
+		type MaxAccumulatePoolsPerBlock: Get<u32>;
+
 		/// The reward type for dex saving.
 		#[pallet::constant]
 		type StableCurrencyId: Get<CurrencyId>;
@@ -113,6 +125,9 @@ pub mod module {
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
 
+		/// Pay the bounty to the caller for each closed pool
+		type CleanupBounty: PayCleanupBounty<Self::AccountId>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -127,6 +142,8 @@ pub mod module {
 		InvalidPoolId,
 		/// Invalid rate
 		InvalidRate,
+		/// None of the pools can be closed
+		NothingToClose,
 	}
 
 	#[pallet::event]
@@ -136,6 +153,10 @@ pub mod module {
 		DepositDexShare(T::AccountId, CurrencyId, Balance),
 		/// Withdraw DEX share. \[who, dex_share_type, withdraw_amount\]
 		WithdrawDexShare(T::AccountId, CurrencyId, Balance),
+		/// Deposit token. \[who, currency_type, deposit_amount\]
+		DepositToken(T::AccountId, CurrencyId, Balance),
+		/// Withdraw token. \[who, currency_type, withdraw_amount\]
+		WithdrawToken(T::AccountId, CurrencyId, Balance),
 		/// Claim rewards. \[who, pool_id, reward_currency_id, actual_amount, deduction_amount\]
 		ClaimRewards(T::AccountId, PoolId, CurrencyId, Balance, Balance),
 		/// Incentive reward amount updated. \[pool_id, reward_currency_id,
@@ -145,6 +166,8 @@ pub mod module {
 		SavingRewardRateUpdated(PoolId, Rate),
 		/// Payout deduction rate updated. \[pool_id, deduction_rate\]
 		ClaimRewardDeductionRateUpdated(PoolId, Rate),
+		/// The empty pools are closed. \[who, closed_count, bounty\]
+		IncentivePoolsClosed(T::AccountId, u32, Balance),
 	}
 
 	/// Mapping from pool to its fixed incentive amounts of multi currencies per period.
@@ -184,41 +207,46 @@ pub mod module {
 		ValueQuery,
 	>;
 
+	/// The raw storage key of the last pool accumulated in the ongoing
+	/// accumulation, empty if no pool is accumulated yet.
+	///
+	/// AccumulationCursor: Option<Vec<u8>>
+	#[pallet::storage]
+	#[pallet::getter(fn accumulation_cursor)]
+	pub type AccumulationCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// The number of the periods started while the accumulation of the last
+	/// period is unfinished, they are accumulated in turn after it.
+	///
+	/// PendingAccumulations: u32
+	#[pallet::storage]
+	#[pallet::getter(fn pending_accumulations)]
+	pub type PendingAccumulations<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
 		fn on_initialize(now: T::BlockNumber) -> Weight {
-			// accumulate reward periodically
+			// start to accumulate reward periodically, if the accumulation of the last
+			// period is unfinished, queue the period after it.
 			if now % T::AccumulatePeriod::get() == Zero::zero() {
-				let mut count: u32 = 0;
-				let shutdown = T::EmergencyShutdown::is_shutdown();
-
-				for (pool_id, pool_info) in orml_rewards::PoolInfos::<T>::iter() {
-					if !pool_info.total_shares.is_zero() {
-						match pool_id {
-							// do not accumulate incentives for PoolId::Loans after shutdown
-							PoolId::Loans(_) if !shutdown => {
-								count += 1;
-								Self::accumulate_incentives(pool_id);
-							}
-							PoolId::Dex(lp_currency_id) => {
-								// do not accumulate dex saving any more after shutdown
-								if !shutdown {
-									Self::accumulate_dex_saving(lp_currency_id, pool_id);
-								}
-								count += 1;
-								Self::accumulate_incentives(pool_id);
-							}
-							_ => {}
-						}
-					}
+				if Self::accumulation_cursor().is_some() {
+					log::warn!(
+						target: "incentives",
+						"accumulation of the last period is unfinished at {:?}, queue the period after it",
+						now
+					);
+					PendingAccumulations::<T>::mutate(|pending| *pending = pending.saturating_add(1));
+				} else {
+					AccumulationCursor::<T>::put(Vec::<u8>::new());
 				}
+			}
 
-				T::WeightInfo::on_initialize(count)
-			} else {
-				0
+			match Self::accumulation_cursor() {
+				Some(cursor) => T::WeightInfo::on_initialize(Self::accumulate_pools(cursor)),
+				None => T::DbWeight::get().reads(1),
 			}
 		}
 	}
@@ -270,42 +298,7 @@ pub mod module {
 		#[transactional]
 		pub fn claim_rewards(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-
-			// orml_rewards will claim rewards for all currencies rewards
-			<orml_rewards::Pallet<T>>::claim_rewards(&who, &pool_id);
-
-			let pending_multi_rewards: BTreeMap<CurrencyId, Balance> = PendingMultiRewards::<T>::take(&pool_id, &who);
-			let deduction_rate = Self::claim_reward_deduction_rates(&pool_id);
-
-			for (currency_id, pending_reward) in pending_multi_rewards {
-				if pending_reward.is_zero() {
-					continue;
-				}
-				// calculate actual rewards and deduction amount
-				let (actual_amount, deduction_amount) = {
-					let deduction_amount = deduction_rate.saturating_mul_int(pending_reward).min(pending_reward);
-					if !deduction_amount.is_zero() {
-						// re-accumulate deduction to rewards pool if deduction amount is not zero
-						<orml_rewards::Pallet<T>>::accumulate_reward(&pool_id, currency_id, deduction_amount)?;
-					}
-					(pending_reward.saturating_sub(deduction_amount), deduction_amount)
-				};
-
-				// transfer the actual reward(pending reward exclude deduction) to user from the pool. it should not
-				// affect the process, ignore the result to continue. if it fails, just the user will not
-				// be rewarded, there will not increase user balance.
-				T::Currency::transfer(currency_id, &Self::account_id(), &who, actual_amount)?;
-
-				Self::deposit_event(Event::ClaimRewards(
-					who.clone(),
-					pool_id,
-					currency_id,
-					actual_amount,
-					deduction_amount,
-				));
-			}
-
-			Ok(())
+			Self::do_claim_rewards(&who, pool_id)
 		}
 
 		/// Update incentive reward amount for specific PoolId
@@ -323,9 +316,7 @@ pub mod module {
 		) -> DispatchResult {
 			T::UpdateOrigin::ensure_origin(origin)?;
 			for (pool_id, update_list) in updates {
-				if let PoolId::Dex(currency_id) = pool_id {
-					ensure!(currency_id.is_dex_share_currency_id(), Error::<T>::InvalidPoolId);
-				}
+				Self::ensure_valid_pool_id(pool_id)?;
 
 				for (currency_id, amount) in update_list {
 					IncentiveRewardAmounts::<T>::mutate_exists(pool_id, currency_id, |maybe_amount| {
@@ -392,9 +383,7 @@ pub mod module {
 		) -> DispatchResult {
 			T::UpdateOrigin::ensure_origin(origin)?;
 			for (pool_id, deduction_rate) in updates {
-				if let PoolId::Dex(currency_id) = pool_id {
-					ensure!(currency_id.is_dex_share_currency_id(), Error::<T>::InvalidPoolId);
-				}
+				Self::ensure_valid_pool_id(pool_id)?;
 				ensure!(deduction_rate <= Rate::one(), Error::<T>::InvalidRate);
 				ClaimRewardDeductionRates::<T>::mutate_exists(&pool_id, |maybe_rate| {
 					let mut v = maybe_rate.unwrap_or_default();
@@ -412,6 +401,28 @@ pub mod module {
 			}
 			Ok(())
 		}
+
+		/// Close the pools which have no shares, no pending rewards and no
+		/// rewards to accumulate any more, the caller receives `CleanupBounty`
+		/// from the treasury for each closed pool.
+		///
+		/// The dispatch origin of this call must be `Signed` by the transactor.
+		///
+		/// - `pool_ids`: Vec<PoolId>
+		#[pallet::weight(<T as Config>::WeightInfo::close_incentive_pools(pool_ids.len() as u32))]
+		#[transactional]
+		pub fn close_incentive_pools(origin: OriginFor<T>, pool_ids: Vec<PoolId>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let closed_count = pool_ids
+				.into_iter()
+				.filter(|pool_id| Self::close_pool(*pool_id).is_ok())
+				.count() as u32;
+			ensure!(!closed_count.is_zero(), Error::<T>::NothingToClose);
+
+			let bounty = T::CleanupBounty::pay_cleanup_bounty(&who, closed_count);
+			Self::deposit_event(Event::IncentivePoolsClosed(who, closed_count, bounty));
+			Ok(())
+		}
 	}
 }
 
@@ -420,6 +431,147 @@ impl<T: Config> Pallet<T> {
 		T::PalletId::get().into_account()
 	}
 
+	fn ensure_valid_pool_id(pool_id: PoolId) -> DispatchResult {
+		match pool_id {
+			PoolId::Dex(currency_id) => ensure!(currency_id.is_dex_share_currency_id(), Error::<T>::InvalidPoolId),
+			PoolId::Token(currency_id) => ensure!(currency_id.is_token_currency_id(), Error::<T>::InvalidPoolId),
+			PoolId::Loans(_) => {}
+		}
+		Ok(())
+	}
+
+	/// Claim all the multi currencies rewards of `who` for the pool.
+	fn do_claim_rewards(who: &T::AccountId, pool_id: PoolId) -> DispatchResult {
+		// orml_rewards will claim rewards for all currencies rewards
+		<orml_rewards::Pallet<T>>::claim_rewards(who, &pool_id);
+
+		let pending_multi_rewards: BTreeMap<CurrencyId, Balance> = PendingMultiRewards::<T>::take(&pool_id, who);
+		let deduction_rate = Self::claim_reward_deduction_rates(&pool_id);
+
+		for (currency_id, pending_reward) in pending_multi_rewards {
+			if pending_reward.is_zero() {
+				continue;
+			}
+			// calculate actual rewards and deduction amount
+			let (actual_amount, deduction_amount) = {
+				let deduction_amount = deduction_rate.saturating_mul_int(pending_reward).min(pending_reward);
+				if !deduction_amount.is_zero() {
+					// re-accumulate deduction to rewards pool if deduction amount is not zero
+					<orml_rewards::Pallet<T>>::accumulate_reward(&pool_id, currency_id, deduction_amount)?;
+				}
+				(pending_reward.saturating_sub(deduction_amount), deduction_amount)
+			};
+
+			// transfer the actual reward(pending reward exclude deduction) to user from the pool. it should not
+			// affect the process, ignore the result to continue. if it fails, just the user will not
+			// be rewarded, there will not increase user balance.
+			T::Currency::transfer(currency_id, &Self::account_id(), who, actual_amount)?;
+
+			Self::deposit_event(Event::ClaimRewards(
+				who.clone(),
+				pool_id,
+				currency_id,
+				actual_amount,
+				deduction_amount,
+			));
+		}
+
+		Ok(())
+	}
+
+	/// Remove the pool if it has no shares, no pending rewards, no incentive
+	/// rewards and no DEX saving rewards. The rewards accumulated to the pool
+	/// but never withdrawn can't be claimed by anyone without shares, they're
+	/// returned to `RewardsSource`.
+	#[transactional]
+	fn close_pool(pool_id: PoolId) -> DispatchResult {
+		let pool_info = <orml_rewards::Pallet<T>>::pool_infos(&pool_id);
+		ensure!(
+			orml_rewards::PoolInfos::<T>::contains_key(&pool_id)
+				&& pool_info.total_shares.is_zero()
+				&& IncentiveRewardAmounts::<T>::iter_prefix(&pool_id).next().is_none()
+				&& Self::dex_saving_reward_rates(&pool_id).is_zero()
+				&& PendingMultiRewards::<T>::iter_prefix(&pool_id).next().is_none(),
+			Error::<T>::NothingToClose
+		);
+
+		for (currency_id, (total_reward, total_withdrawn_reward)) in pool_info.rewards {
+			let unclaimed_reward = total_reward.saturating_sub(total_withdrawn_reward);
+			if !unclaimed_reward.is_zero() {
+				T::Currency::transfer(
+					currency_id,
+					&Self::account_id(),
+					&T::RewardsSource::get(),
+					unclaimed_reward,
+				)?;
+			}
+		}
+
+		orml_rewards::PoolInfos::<T>::remove(&pool_id);
+		ClaimRewardDeductionRates::<T>::remove(&pool_id);
+		Ok(())
+	}
+
+	/// Accumulate the rewards of the pools following `cursor`, at most
+	/// `MaxAccumulatePoolsPerBlock` pools are iterated, and persist the cursor
+	/// if the iteration is unfinished. Returns the number of pools accumulated.
+	fn accumulate_pools(cursor: Vec<u8>) -> u32 {
+		let shutdown = T::EmergencyShutdown::is_shutdown();
+		let max_iterations = T::MaxAccumulatePoolsPerBlock::get();
+		let mut iterator = if cursor.is_empty() {
+			orml_rewards::PoolInfos::<T>::iter()
+		} else {
+			orml_rewards::PoolInfos::<T>::iter_from(cursor)
+		};
+
+		let mut count: u32 = 0;
+		let mut iteration_count: u32 = 0;
+		let mut finished = true;
+
+		#[allow(clippy::while_let_on_iterator)]
+		while let Some((pool_id, pool_info)) = iterator.next() {
+			if !pool_info.total_shares.is_zero() {
+				match pool_id {
+					// do not accumulate incentives for PoolId::Loans and PoolId::Token after shutdown
+					PoolId::Loans(_) | PoolId::Token(_) if !shutdown => {
+						count += 1;
+						Self::accumulate_incentives(pool_id);
+					}
+					PoolId::Dex(lp_currency_id) => {
+						// do not accumulate dex saving any more after shutdown
+						if !shutdown {
+							Self::accumulate_dex_saving(lp_currency_id, pool_id);
+						}
+						count += 1;
+						Self::accumulate_incentives(pool_id);
+					}
+					_ => {}
+				}
+			}
+
+			iteration_count += 1;
+			if iteration_count >= max_iterations {
+				finished = false;
+				break;
+			}
+		}
+
+		if finished {
+			// start the accumulation of the next queued period
+			let pending = Self::pending_accumulations();
+			if pending.is_zero() {
+				AccumulationCursor::<T>::kill();
+			} else {
+				PendingAccumulations::<T>::put(pending - 1);
+				AccumulationCursor::<T>::put(Vec::<u8>::new());
+			}
+		} else {
+			AccumulationCursor::<T>::put(iterator.last_raw_key());
+		}
+
+		count
+	}
+
 	// accumulate incentive rewards of multi currencies
 	fn accumulate_incentives(pool_id: PoolId) {
 		for (reward_currency_id, reward_amount) in IncentiveRewardAmounts::<T>::iter_prefix(pool_id) {
@@ -535,6 +687,42 @@ impl<T: Config> DEXIncentives<T::AccountId, CurrencyId, Balance> for Pallet<T> {
 		Self::deposit_event(Event::WithdrawDexShare(who.clone(), lp_currency_id, amount));
 		Ok(())
 	}
+
+	fn do_claim_dex_share_rewards(who: &T::AccountId, lp_currency_id: CurrencyId) -> DispatchResult {
+		ensure!(lp_currency_id.is_dex_share_currency_id(), Error::<T>::InvalidCurrencyId);
+		Self::do_claim_rewards(who, PoolId::Dex(lp_currency_id))
+	}
+}
+
+impl<T: Config> TokenIncentives<T::AccountId, CurrencyId, Balance> for Pallet<T> {
+	fn do_deposit_token(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		ensure!(currency_id.is_token_currency_id(), Error::<T>::InvalidCurrencyId);
+
+		T::Currency::transfer(currency_id, who, &Self::account_id(), amount)?;
+		<orml_rewards::Pallet<T>>::add_share(who, &PoolId::Token(currency_id), amount.unique_saturated_into());
+
+		Self::deposit_event(Event::DepositToken(who.clone(), currency_id, amount));
+		Ok(())
+	}
+
+	fn do_withdraw_token(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		ensure!(currency_id.is_token_currency_id(), Error::<T>::InvalidCurrencyId);
+		ensure!(
+			<orml_rewards::Pallet<T>>::shares_and_withdrawn_rewards(&PoolId::Token(currency_id), &who).0 >= amount,
+			Error::<T>::NotEnough,
+		);
+
+		T::Currency::transfer(currency_id, &Self::account_id(), who, amount)?;
+		<orml_rewards::Pallet<T>>::remove_share(who, &PoolId::Token(currency_id), amount.unique_saturated_into());
+
+		Self::deposit_event(Event::WithdrawToken(who.clone(), currency_id, amount));
+		Ok(())
+	}
+
+	fn do_claim_token_rewards(who: &T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+		ensure!(currency_id.is_token_currency_id(), Error::<T>::InvalidCurrencyId);
+		Self::do_claim_rewards(who, PoolId::Token(currency_id))
+	}
 }
 
 pub struct OnUpdateLoan<T>(sp_std::marker::PhantomData<T>);
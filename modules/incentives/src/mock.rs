@@ -34,6 +34,7 @@ use primitives::{DexShare, TokenSymbol};
 use sp_core::{H160, H256};
 use sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32};
 use sp_std::cell::RefCell;
+use support::TreasuryCleanupBounty;
 pub use support::{CDPTreasury, DEXManager, Price, Ratio};
 
 pub type AccountId = AccountId32;
@@ -58,6 +59,7 @@ ord_parameter_types! {
 	pub const BOB: AccountId = AccountId::from([2u8; 32]);
 	pub const VAULT: AccountId = IncentivesModule::account_id();
 	pub const RewardsSource: AccountId = AccountId::from([3u8; 32]);
+	pub const TreasuryAccount: AccountId = AccountId::from([4u8; 32]);
 	pub const ROOT: AccountId = AccountId32::new([255u8; 32]);
 }
 
@@ -150,6 +152,10 @@ impl CDPTreasury<AccountId> for MockCDPTreasury {
 		unimplemented!()
 	}
 
+	fn withdraw_surplus(_: &AccountId, _: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
 	fn deposit_collateral(_: &AccountId, _: CurrencyId, _: Balance) -> DispatchResult {
 		unimplemented!()
 	}
@@ -251,8 +257,11 @@ impl orml_rewards::Config for Runtime {
 
 parameter_types! {
 	pub const AccumulatePeriod: BlockNumber = 10;
+	pub static MaxAccumulatePoolsPerBlock: u32 = 10;
 	pub const StableCurrencyId: CurrencyId = AUSD;
 	pub const IncentivesPalletId: PalletId = PalletId(*b"aca/inct");
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const CleanupBounty: Balance = 10;
 }
 
 ord_parameter_types! {
@@ -263,6 +272,7 @@ impl Config for Runtime {
 	type Event = Event;
 	type RewardsSource = RewardsSource;
 	type AccumulatePeriod = AccumulatePeriod;
+	type MaxAccumulatePoolsPerBlock = MaxAccumulatePoolsPerBlock;
 	type StableCurrencyId = StableCurrencyId;
 	type UpdateOrigin = EnsureSignedBy<ROOT, AccountId>;
 	type CDPTreasury = MockCDPTreasury;
@@ -270,6 +280,7 @@ impl Config for Runtime {
 	type DEX = MockDEX;
 	type EmergencyShutdown = MockEmergencyShutdown;
 	type PalletId = IncentivesPalletId;
+	type CleanupBounty = TreasuryCleanupBounty<TokensModule, GetNativeCurrencyId, TreasuryAccount, CleanupBounty>;
 	type WeightInfo = ();
 }
 
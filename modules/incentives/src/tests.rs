@@ -135,6 +135,69 @@ fn withdraw_dex_share_works() {
 	});
 }
 
+#[test]
+fn token_pool_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TokensModule::deposit(LDOT, &ALICE::get(), 10000));
+		assert_ok!(TokensModule::deposit(ACA, &RewardsSource::get(), 10000));
+
+		assert_noop!(
+			IncentivesModule::do_deposit_token(&ALICE::get(), BTC_AUSD_LP, 10000),
+			Error::<Runtime>::InvalidCurrencyId,
+		);
+		assert_noop!(
+			IncentivesModule::update_incentive_rewards(
+				Origin::signed(ROOT::get()),
+				vec![(PoolId::Token(BTC_AUSD_LP), vec![])]
+			),
+			Error::<Runtime>::InvalidPoolId
+		);
+
+		assert_ok!(IncentivesModule::do_deposit_token(&ALICE::get(), LDOT, 10000));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::DepositToken(
+			ALICE::get(),
+			LDOT,
+			10000,
+		)));
+		assert_eq!(TokensModule::free_balance(LDOT, &ALICE::get()), 0);
+		assert_eq!(TokensModule::free_balance(LDOT, &VAULT::get()), 10000);
+		assert_eq!(
+			RewardsModule::shares_and_withdrawn_rewards(PoolId::Token(LDOT), ALICE::get()),
+			(10000, Default::default())
+		);
+
+		assert_ok!(IncentivesModule::update_incentive_rewards(
+			Origin::signed(ROOT::get()),
+			vec![(PoolId::Token(LDOT), vec![(ACA, 100)])],
+		));
+		IncentivesModule::on_initialize(10);
+		assert_ok!(IncentivesModule::do_claim_token_rewards(&ALICE::get(), LDOT));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::ClaimRewards(
+			ALICE::get(),
+			PoolId::Token(LDOT),
+			ACA,
+			100,
+			0,
+		)));
+		assert_eq!(TokensModule::free_balance(ACA, &ALICE::get()), 100);
+
+		assert_noop!(
+			IncentivesModule::do_withdraw_token(&ALICE::get(), LDOT, 10001),
+			Error::<Runtime>::NotEnough,
+		);
+		assert_ok!(IncentivesModule::do_withdraw_token(&ALICE::get(), LDOT, 8000));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::WithdrawToken(
+			ALICE::get(),
+			LDOT,
+			8000,
+		)));
+		assert_eq!(TokensModule::free_balance(LDOT, &ALICE::get()), 8000);
+		assert_eq!(TokensModule::free_balance(LDOT, &VAULT::get()), 2000);
+		assert_eq!(RewardsModule::pool_infos(PoolId::Token(LDOT)).total_shares, 2000);
+	});
+}
+
 #[test]
 fn update_incentive_rewards_works() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -719,6 +782,80 @@ fn claim_rewards_works() {
 	});
 }
 
+#[test]
+fn close_incentive_pools_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TokensModule::deposit(ACA, &TreasuryAccount::get(), 1000));
+		assert_ok!(TokensModule::deposit(BTC_AUSD_LP, &ALICE::get(), 100));
+		assert_ok!(TokensModule::deposit(DOT_AUSD_LP, &ALICE::get(), 100));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE::get()),
+			BTC_AUSD_LP,
+			100
+		));
+		assert_ok!(IncentivesModule::withdraw_dex_share(
+			Origin::signed(ALICE::get()),
+			BTC_AUSD_LP,
+			100
+		));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE::get()),
+			DOT_AUSD_LP,
+			100
+		));
+		assert_ok!(IncentivesModule::withdraw_dex_share(
+			Origin::signed(ALICE::get()),
+			DOT_AUSD_LP,
+			100
+		));
+		assert_ok!(IncentivesModule::update_incentive_rewards(
+			Origin::signed(ROOT::get()),
+			vec![(PoolId::Dex(DOT_AUSD_LP), vec![(ACA, 100)])],
+		));
+		OnUpdateLoan::<Runtime>::happened(&(ALICE::get(), BTC, 100, 0));
+
+		// the rewards left unclaimed in the pool without shares
+		orml_rewards::PoolInfos::<Runtime>::mutate(PoolId::Dex(BTC_AUSD_LP), |pool_info| {
+			pool_info.rewards.insert(ACA, (100, 40));
+		});
+		assert_ok!(TokensModule::deposit(ACA, &IncentivesModule::account_id(), 60));
+
+		// the pool with shares, the pool with incentive rewards and the unknown pool
+		// can't be closed
+		assert_noop!(
+			IncentivesModule::close_incentive_pools(
+				Origin::signed(BOB::get()),
+				vec![PoolId::Loans(BTC), PoolId::Dex(DOT_AUSD_LP), PoolId::Loans(DOT)]
+			),
+			Error::<Runtime>::NothingToClose
+		);
+
+		assert!(RewardsModule::pool_infos(PoolId::Dex(BTC_AUSD_LP))
+			.total_shares
+			.is_zero());
+		assert_ok!(IncentivesModule::close_incentive_pools(
+			Origin::signed(BOB::get()),
+			vec![PoolId::Dex(BTC_AUSD_LP), PoolId::Dex(DOT_AUSD_LP)]
+		));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::IncentivePoolsClosed(
+			BOB::get(),
+			1,
+			10,
+		)));
+		assert!(!orml_rewards::PoolInfos::<Runtime>::contains_key(PoolId::Dex(
+			BTC_AUSD_LP
+		)));
+		assert!(orml_rewards::PoolInfos::<Runtime>::contains_key(PoolId::Dex(
+			DOT_AUSD_LP
+		)));
+		assert_eq!(TokensModule::free_balance(ACA, &BOB::get()), 10);
+		assert_eq!(TokensModule::free_balance(ACA, &TreasuryAccount::get()), 990);
+		assert_eq!(TokensModule::free_balance(ACA, &RewardsSource::get()), 60);
+		assert_eq!(TokensModule::free_balance(ACA, &IncentivesModule::account_id()), 0);
+	});
+}
+
 #[test]
 fn on_initialize_should_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -934,3 +1071,70 @@ fn on_initialize_should_work() {
 		);
 	});
 }
+
+#[test]
+fn on_initialize_accumulate_pools_in_bounded_batches() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxAccumulatePoolsPerBlock::set(1);
+		assert_ok!(TokensModule::deposit(ACA, &RewardsSource::get(), 10000));
+		assert_ok!(IncentivesModule::update_incentive_rewards(
+			Origin::signed(ROOT::get()),
+			vec![
+				(PoolId::Loans(BTC), vec![(ACA, 1000)]),
+				(PoolId::Dex(BTC_AUSD_LP), vec![(ACA, 100)]),
+			],
+		));
+		RewardsModule::add_share(&ALICE::get(), &PoolId::Loans(BTC), 1);
+		RewardsModule::add_share(&ALICE::get(), &PoolId::Dex(BTC_AUSD_LP), 1);
+		assert_eq!(IncentivesModule::accumulation_cursor(), None);
+
+		// only one pool is accumulated in a block
+		IncentivesModule::on_initialize(10);
+		let accumulated = TokensModule::free_balance(ACA, &VAULT::get());
+		assert!(accumulated == 1000 || accumulated == 100);
+		assert!(IncentivesModule::accumulation_cursor().is_some());
+
+		// the accumulation continues from the cursor in the next block
+		IncentivesModule::on_initialize(11);
+		assert_eq!(TokensModule::free_balance(ACA, &VAULT::get()), 1100);
+
+		IncentivesModule::on_initialize(12);
+		assert_eq!(TokensModule::free_balance(ACA, &VAULT::get()), 1100);
+		assert_eq!(IncentivesModule::accumulation_cursor(), None);
+	});
+}
+
+#[test]
+fn on_initialize_queue_period_started_during_unfinished_accumulation() {
+	ExtBuilder::default().build().execute_with(|| {
+		MaxAccumulatePoolsPerBlock::set(1);
+		assert_ok!(TokensModule::deposit(ACA, &RewardsSource::get(), 10000));
+		assert_ok!(IncentivesModule::update_incentive_rewards(
+			Origin::signed(ROOT::get()),
+			vec![
+				(PoolId::Loans(BTC), vec![(ACA, 1000)]),
+				(PoolId::Dex(BTC_AUSD_LP), vec![(ACA, 100)]),
+			],
+		));
+		RewardsModule::add_share(&ALICE::get(), &PoolId::Loans(BTC), 1);
+		RewardsModule::add_share(&ALICE::get(), &PoolId::Dex(BTC_AUSD_LP), 1);
+
+		IncentivesModule::on_initialize(10);
+		assert!(IncentivesModule::accumulation_cursor().is_some());
+
+		// the next period starts before the accumulation of the last period finishes
+		IncentivesModule::on_initialize(20);
+		assert_eq!(IncentivesModule::pending_accumulations(), 1);
+		assert_eq!(TokensModule::free_balance(ACA, &VAULT::get()), 1100);
+
+		// the queued period is accumulated after the last period
+		IncentivesModule::on_initialize(21);
+		assert_eq!(IncentivesModule::pending_accumulations(), 0);
+		assert_eq!(IncentivesModule::accumulation_cursor(), Some(vec![]));
+		IncentivesModule::on_initialize(22);
+		IncentivesModule::on_initialize(23);
+		IncentivesModule::on_initialize(24);
+		assert_eq!(TokensModule::free_balance(ACA, &VAULT::get()), 2200);
+		assert_eq!(IncentivesModule::accumulation_cursor(), None);
+	});
+}
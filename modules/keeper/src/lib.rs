@@ -0,0 +1,475 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Keeper Module
+//!
+//! ## Overview
+//!
+//! Keeper module is a registry of automation jobs. A job is a condition and a
+//! call registered by its owner, with an execution budget in native currency
+//! prepaid into the module account. Anyone, such as a third-party keeper, can
+//! execute a job which is due and whose condition is met: the call is
+//! dispatched on behalf of the job owner and the keeper earns the keeper fee
+//! from the budget. The off-chain worker also submits the executable jobs as
+//! unsigned transactions, whose keeper fee is burned. The conditions are
+//! defined by the runtime, and the calls are restricted by the runtime call
+//! filter at both registration and execution. A job can be executed once per
+//! interval, and it can be funded or cancelled by its owner at any time.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{
+	log,
+	pallet_prelude::*,
+	storage::{with_transaction, TransactionOutcome},
+	traits::Contains,
+	transactional,
+	weights::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo},
+	PalletId,
+};
+use frame_system::{
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+use orml_traits::MultiCurrency;
+use orml_utilities::OffchainErr;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	offchain::{
+		storage_lock::{StorageLock, Time},
+		Duration,
+	},
+	traits::{AccountIdConversion, Dispatchable, One, Saturating, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	ArithmeticError, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::KeeperCondition;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub const OFFCHAIN_WORKER_LOCK: &[u8] = b"acala/keeper/lock/";
+pub const LOCK_DURATION: u64 = 100;
+pub const MAX_OFFCHAIN_EXECUTIONS: usize = 100;
+
+pub type JobId = u64;
+
+/// The automation job.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct KeeperJob<AccountId, Condition, Call, BlockNumber> {
+	/// The owner of the job, the call is dispatched on behalf of.
+	pub owner: AccountId,
+	/// The condition checked before the call is dispatched.
+	pub condition: Condition,
+	/// The call to dispatch.
+	pub call: Call,
+	/// The minimum blocks between two executions.
+	pub interval: BlockNumber,
+	/// The block from which the job can be executed.
+	pub next_execution: BlockNumber,
+	/// The execution budget in native currency not paid yet.
+	pub budget: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	pub(crate) type KeeperJobOf<T> = KeeperJob<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::Condition,
+		<T as Config>::Call,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The condition of the jobs
+		type Condition: Parameter + Member + KeeperCondition<Self::AccountId>;
+
+		/// The maximum weight of checking the condition of a job
+		#[pallet::constant]
+		type MaxConditionWeight: Get<Weight>;
+
+		/// The call of the jobs
+		type Call: Parameter + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo> + GetDispatchInfo;
+
+		/// The filter of the calls allowed in the jobs
+		type CallFilter: Contains<<Self as Config>::Call>;
+
+		/// Currency to prepay the execution budget and pay the keeper fee
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Native currency id, the execution budget is paid in
+		#[pallet::constant]
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		/// The fee paid to the keeper for each execution
+		#[pallet::constant]
+		type KeeperFee: Get<Balance>;
+
+		/// The minimum blocks between two executions of a job
+		#[pallet::constant]
+		type MinExecutionInterval: Get<Self::BlockNumber>;
+
+		/// The maximum weight of the call of a job
+		#[pallet::constant]
+		type MaxCallWeight: Get<Weight>;
+
+		/// The maximum jobs an account can have
+		#[pallet::constant]
+		type MaxJobsPerAccount: Get<u32>;
+
+		/// The keeper module id, keep the execution budgets
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// A configuration for base priority of unsigned transactions.
+		///
+		/// This is exposed so that it can be tuned for particular runtime, when
+		/// multiple modules send unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call is not allowed in the jobs
+		CallFiltered,
+		/// The weight of the call exceeds the maximum
+		CallTooHeavy,
+		/// The weight of checking the condition exceeds the maximum
+		ConditionTooHeavy,
+		/// The interval is less than the minimum execution interval
+		IntervalTooShort,
+		/// The account has reached the maximum jobs
+		TooManyJobs,
+		/// The job does not exist
+		JobNotFound,
+		/// The caller is not the owner of the job
+		NoPermission,
+		/// The job can not be executed before the next execution block
+		JobNotDue,
+		/// The budget of the job can not pay the keeper fee
+		InsufficientBudget,
+		/// The condition of the job is not met
+		ConditionNotMet,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A job is registered. \[job_id, owner, budget\]
+		JobRegistered(JobId, T::AccountId, Balance),
+		/// A job is funded. \[job_id, amount\]
+		JobFunded(JobId, Balance),
+		/// A job is cancelled. \[job_id, owner, refunded_budget\]
+		JobCancelled(JobId, T::AccountId, Balance),
+		/// A job is executed by the keeper, or by the off-chain worker if the
+		/// keeper is `None`. \[job_id, keeper, keeper_fee, call_result\]
+		JobExecuted(JobId, Option<T::AccountId>, Balance, DispatchResult),
+	}
+
+	/// The id of the next job.
+	///
+	/// NextJobId: JobId
+	#[pallet::storage]
+	#[pallet::getter(fn next_job_id)]
+	pub type NextJobId<T: Config> = StorageValue<_, JobId, ValueQuery>;
+
+	/// The automation jobs.
+	///
+	/// Jobs: map JobId => Option<KeeperJob>
+	#[pallet::storage]
+	#[pallet::getter(fn jobs)]
+	pub type Jobs<T: Config> = StorageMap<_, Twox64Concat, JobId, KeeperJobOf<T>, OptionQuery>;
+
+	/// The count of the jobs of the accounts.
+	///
+	/// JobCount: map AccountId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn job_count)]
+	pub type JobCount<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Runs after every block. Start offchain worker to submit unsigned
+		/// tx to execute the executable jobs.
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(e) = Self::_offchain_worker() {
+				log::info!(
+					target: "keeper offchain worker",
+					"cannot run offchain worker at {:?}: {:?}",
+					now,
+					e,
+				);
+			} else {
+				log::debug!(
+					target: "keeper offchain worker",
+					"offchain worker start at block: {:?} already done!",
+					now,
+				);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a job and prepay its execution budget. The job can be
+		/// executed immediately once the condition is met.
+		///
+		/// - `condition`: the condition checked before the call is dispatched.
+		/// - `call`: the call dispatched on behalf of the caller.
+		/// - `interval`: the minimum blocks between two executions.
+		/// - `budget`: the execution budget in native currency.
+		#[pallet::weight(<T as Config>::WeightInfo::register_job())]
+		#[transactional]
+		pub fn register_job(
+			origin: OriginFor<T>,
+			condition: T::Condition,
+			call: Box<<T as Config>::Call>,
+			interval: T::BlockNumber,
+			#[pallet::compact] budget: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::CallFilter::contains(&call), Error::<T>::CallFiltered);
+			ensure!(
+				call.get_dispatch_info().weight <= T::MaxCallWeight::get(),
+				Error::<T>::CallTooHeavy
+			);
+			ensure!(
+				condition.is_met_weight() <= T::MaxConditionWeight::get(),
+				Error::<T>::ConditionTooHeavy
+			);
+			ensure!(interval >= T::MinExecutionInterval::get(), Error::<T>::IntervalTooShort);
+
+			JobCount::<T>::try_mutate(&who, |count| -> DispatchResult {
+				ensure!(*count < T::MaxJobsPerAccount::get(), Error::<T>::TooManyJobs);
+				*count = count.saturating_add(1);
+				Ok(())
+			})?;
+			T::Currency::transfer(T::GetNativeCurrencyId::get(), &who, &Self::account_id(), budget)?;
+
+			let job_id = NextJobId::<T>::try_mutate(|id| -> Result<JobId, DispatchError> {
+				let current_id = *id;
+				*id = id.checked_add(One::one()).ok_or(ArithmeticError::Overflow)?;
+				Ok(current_id)
+			})?;
+			Jobs::<T>::insert(
+				job_id,
+				KeeperJob {
+					owner: who.clone(),
+					condition,
+					call: *call,
+					interval,
+					next_execution: <frame_system::Pallet<T>>::block_number(),
+					budget,
+				},
+			);
+			Self::deposit_event(Event::JobRegistered(job_id, who, budget));
+			Ok(())
+		}
+
+		/// Add the execution budget of a job.
+		///
+		/// - `job_id`: the job to fund.
+		/// - `amount`: the native currency amount to add.
+		#[pallet::weight(<T as Config>::WeightInfo::fund_job())]
+		#[transactional]
+		pub fn fund_job(origin: OriginFor<T>, job_id: JobId, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Jobs::<T>::try_mutate(job_id, |maybe_job| -> DispatchResult {
+				let job = maybe_job.as_mut().ok_or(Error::<T>::JobNotFound)?;
+				T::Currency::transfer(T::GetNativeCurrencyId::get(), &who, &Self::account_id(), amount)?;
+				job.budget = job.budget.saturating_add(amount);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::JobFunded(job_id, amount));
+			Ok(())
+		}
+
+		/// Cancel a job and refund the remaining budget.
+		///
+		/// The dispatch origin of this call must be the owner of the job.
+		///
+		/// - `job_id`: the job to cancel.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_job())]
+		#[transactional]
+		pub fn cancel_job(origin: OriginFor<T>, job_id: JobId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let job = Self::jobs(job_id).ok_or(Error::<T>::JobNotFound)?;
+			ensure!(job.owner == who, Error::<T>::NoPermission);
+
+			T::Currency::transfer(T::GetNativeCurrencyId::get(), &Self::account_id(), &who, job.budget)?;
+			Jobs::<T>::remove(job_id);
+			JobCount::<T>::mutate_exists(&who, |maybe_count| {
+				let count = maybe_count.unwrap_or_default().saturating_sub(1);
+				*maybe_count = if count.is_zero() { None } else { Some(count) };
+			});
+			Self::deposit_event(Event::JobCancelled(job_id, who, job.budget));
+			Ok(())
+		}
+
+		/// Execute a due job whose condition is met, the call is dispatched
+		/// on behalf of the job owner and the caller earns the keeper fee.
+		/// The keeper fee is paid even if the call fails, in which case the
+		/// changes of the call are reverted.
+		///
+		/// The dispatch origin of this call must be signed by the keeper, or
+		/// be none if submitted by the off-chain worker, in which case the
+		/// keeper fee is burned.
+		///
+		/// - `job_id`: the job to execute.
+		#[pallet::weight(<T as Config>::WeightInfo::execute_job()
+			.saturating_add(T::MaxConditionWeight::get())
+			.saturating_add(T::MaxCallWeight::get()))]
+		#[transactional]
+		pub fn execute_job(origin: OriginFor<T>, job_id: JobId) -> DispatchResultWithPostInfo {
+			let maybe_keeper = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| ensure_none(origin).map(|_| None))?;
+			let mut job = Self::jobs(job_id).ok_or(Error::<T>::JobNotFound)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= job.next_execution, Error::<T>::JobNotDue);
+			let keeper_fee = T::KeeperFee::get();
+			ensure!(job.budget >= keeper_fee, Error::<T>::InsufficientBudget);
+			// the filter may have changed since the job is registered
+			ensure!(T::CallFilter::contains(&job.call), Error::<T>::CallFiltered);
+			ensure!(job.condition.is_met(&job.owner), Error::<T>::ConditionNotMet);
+
+			let origin: T::Origin = frame_system::RawOrigin::Signed(job.owner.clone()).into();
+			let call = job.call.clone();
+			let (call_result, actual_call_weight) = with_transaction(|| {
+				let result = call.dispatch(origin);
+				let actual_weight = extract_actual_weight(&result, &call.get_dispatch_info());
+				let result = result.map(|_| ()).map_err(|e| e.error);
+				if result.is_ok() {
+					TransactionOutcome::Commit((result, actual_weight))
+				} else {
+					TransactionOutcome::Rollback((result, actual_weight))
+				}
+			});
+
+			match &maybe_keeper {
+				Some(keeper) => {
+					T::Currency::transfer(T::GetNativeCurrencyId::get(), &Self::account_id(), keeper, keeper_fee)?
+				}
+				None => T::Currency::withdraw(T::GetNativeCurrencyId::get(), &Self::account_id(), keeper_fee)?,
+			}
+			let condition_weight = job.condition.is_met_weight();
+			job.budget = job.budget.saturating_sub(keeper_fee);
+			job.next_execution = now.saturating_add(job.interval);
+			Jobs::<T>::insert(job_id, job);
+
+			Self::deposit_event(Event::JobExecuted(job_id, maybe_keeper, keeper_fee, call_result));
+			Ok(Some(
+				<T as Config>::WeightInfo::execute_job()
+					.saturating_add(condition_weight)
+					.saturating_add(actual_call_weight),
+			)
+			.into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::execute_job { job_id } => {
+					let job = Self::jobs(job_id).ok_or(InvalidTransaction::Stale)?;
+					if !Self::is_executable(&job) {
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("KeeperOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides((job_id, job.next_execution))
+						.longevity(64_u64)
+						.propagate(true)
+						.build()
+				}
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of keeper module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Return true if the job is due, its budget pays the keeper fee, its
+	/// call passes the filter and its condition is met.
+	fn is_executable(job: &KeeperJobOf<T>) -> bool {
+		<frame_system::Pallet<T>>::block_number() >= job.next_execution
+			&& job.budget >= T::KeeperFee::get()
+			&& T::CallFilter::contains(&job.call)
+			&& job.condition.is_met(&job.owner)
+	}
+
+	fn submit_unsigned_execution_tx(job_id: JobId) {
+		let call = Call::<T>::execute_job { job_id };
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "keeper offchain worker",
+				"submit unsigned execution tx for job {:?} failed!",
+				job_id,
+			);
+		}
+	}
+
+	fn _offchain_worker() -> Result<(), OffchainErr> {
+		// check if we are a potential validator
+		if !sp_io::offchain::is_validator() {
+			return Err(OffchainErr::NotValidator);
+		}
+
+		// acquire offchain worker lock
+		let lock_expiration = Duration::from_millis(LOCK_DURATION);
+		let mut lock = StorageLock::<'_, Time>::with_deadline(OFFCHAIN_WORKER_LOCK, lock_expiration);
+		let _guard = lock.try_lock().map_err(|_| OffchainErr::OffchainLock)?;
+
+		for (job_id, _) in Jobs::<T>::iter()
+			.filter(|(_, job)| Self::is_executable(job))
+			.take(MAX_OFFCHAIN_EXECUTIONS)
+		{
+			Self::submit_unsigned_execution_tx(job_id);
+		}
+		Ok(())
+	}
+}
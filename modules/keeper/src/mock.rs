@@ -0,0 +1,218 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the keeper module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, parameter_types, traits::Nothing};
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, TokenSymbol};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::IdentityLookup,
+};
+use sp_std::cell::RefCell;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const KEEPER: AccountId = 3;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+
+mod keeper {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = Nothing;
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum MockCondition {
+	Met,
+	NotMet,
+	TooHeavy,
+}
+
+impl KeeperCondition<AccountId> for MockCondition {
+	fn is_met(&self, _who: &AccountId) -> bool {
+		matches!(self, MockCondition::Met)
+	}
+
+	fn is_met_weight(&self) -> Weight {
+		match self {
+			MockCondition::TooHeavy => 2_000,
+			_ => 1_000,
+		}
+	}
+}
+
+thread_local! {
+	static TOKENS_CALLS_FILTERED: RefCell<bool> = RefCell::new(false);
+}
+
+pub fn set_tokens_calls_filtered(filtered: bool) {
+	TOKENS_CALLS_FILTERED.with(|v| *v.borrow_mut() = filtered);
+}
+
+/// Allow all the calls except the calls of keeper module, and the calls of
+/// tokens module if they are set filtered.
+pub struct MockCallFilter;
+impl Contains<Call> for MockCallFilter {
+	fn contains(call: &Call) -> bool {
+		match call {
+			Call::KeeperModule(..) => false,
+			Call::Tokens(..) => !TOKENS_CALLS_FILTERED.with(|v| *v.borrow()),
+			_ => true,
+		}
+	}
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = ACA;
+	pub const KeeperFee: Balance = 10;
+	pub const MinExecutionInterval: BlockNumber = 10;
+	pub const MaxCallWeight: Weight = 1_000_000_000;
+	pub const MaxJobsPerAccount: u32 = 2;
+	pub const KeeperPalletId: PalletId = PalletId(*b"aca/kpjb");
+	pub const MaxConditionWeight: Weight = 1_000;
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Condition = MockCondition;
+	type MaxConditionWeight = MaxConditionWeight;
+	type Call = Call;
+	type CallFilter = MockCallFilter;
+	type Currency = Tokens;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type KeeperFee = KeeperFee;
+	type MinExecutionInterval = MinExecutionInterval;
+	type MaxCallWeight = MaxCallWeight;
+	type MaxJobsPerAccount = MaxJobsPerAccount;
+	type PalletId = KeeperPalletId;
+	type UnsignedPriority = UnsignedPriority;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Call, Event<T>, Config<T>},
+		KeeperModule: keeper::{Pallet, Storage, Call, Event<T>, ValidateUnsigned},
+	}
+);
+
+/// An extrinsic type used for tests.
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Runtime
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![(ALICE, ACA, 1000), (BOB, ACA, 1000)],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		set_tokens_calls_filtered(false);
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,297 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the keeper module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+use sp_runtime::traits::BadOrigin;
+
+fn transfer_call(amount: Balance) -> Box<Call> {
+	Box::new(Call::Tokens(orml_tokens::Call::transfer {
+		dest: BOB,
+		currency_id: ACA,
+		amount,
+	}))
+}
+
+#[test]
+fn register_job_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			KeeperModule::register_job(
+				Origin::signed(ALICE),
+				MockCondition::Met,
+				Box::new(Call::KeeperModule(crate::Call::cancel_job { job_id: 0 })),
+				10,
+				100
+			),
+			Error::<Runtime>::CallFiltered
+		);
+		assert_noop!(
+			KeeperModule::register_job(Origin::signed(ALICE), MockCondition::Met, transfer_call(100), 9, 100),
+			Error::<Runtime>::IntervalTooShort
+		);
+		assert_noop!(
+			KeeperModule::register_job(
+				Origin::signed(ALICE),
+				MockCondition::TooHeavy,
+				transfer_call(100),
+				10,
+				100
+			),
+			Error::<Runtime>::ConditionTooHeavy
+		);
+
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobRegistered(0, ALICE, 100)));
+		assert_eq!(
+			KeeperModule::jobs(0),
+			Some(KeeperJob {
+				owner: ALICE,
+				condition: MockCondition::Met,
+				call: *transfer_call(100),
+				interval: 10,
+				next_execution: 1,
+				budget: 100,
+			})
+		);
+		assert_eq!(KeeperModule::next_job_id(), 1);
+		assert_eq!(KeeperModule::job_count(ALICE), 1);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(ACA, &KeeperModule::account_id()), 100);
+
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+		assert_noop!(
+			KeeperModule::register_job(Origin::signed(ALICE), MockCondition::Met, transfer_call(100), 10, 100),
+			Error::<Runtime>::TooManyJobs
+		);
+	});
+}
+
+#[test]
+fn fund_and_cancel_job_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			KeeperModule::fund_job(Origin::signed(BOB), 0, 50),
+			Error::<Runtime>::JobNotFound
+		);
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+
+		assert_ok!(KeeperModule::fund_job(Origin::signed(BOB), 0, 50));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobFunded(0, 50)));
+		assert_eq!(KeeperModule::jobs(0).unwrap().budget, 150);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 950);
+
+		assert_noop!(
+			KeeperModule::cancel_job(Origin::signed(BOB), 0),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(KeeperModule::cancel_job(Origin::signed(ALICE), 0));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobCancelled(0, ALICE, 150)));
+		assert_eq!(KeeperModule::jobs(0), None);
+		assert_eq!(KeeperModule::job_count(ALICE), 0);
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 1050);
+		assert_eq!(Tokens::free_balance(ACA, &KeeperModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn execute_job_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			KeeperModule::execute_job(Origin::signed(KEEPER), 0),
+			Error::<Runtime>::JobNotFound
+		);
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::NotMet,
+			transfer_call(100),
+			10,
+			100
+		));
+		assert_noop!(
+			KeeperModule::execute_job(Origin::signed(KEEPER), 0),
+			Error::<Runtime>::ConditionNotMet
+		);
+
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			15
+		));
+		assert_ok!(KeeperModule::execute_job(Origin::signed(KEEPER), 1));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobExecuted(
+			1,
+			Some(KEEPER),
+			10,
+			Ok(()),
+		)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 785);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 1100);
+		assert_eq!(Tokens::free_balance(ACA, &KEEPER), 10);
+		let job = KeeperModule::jobs(1).unwrap();
+		assert_eq!(job.budget, 5);
+		assert_eq!(job.next_execution, 11);
+
+		System::set_block_number(10);
+		assert_noop!(
+			KeeperModule::execute_job(Origin::signed(KEEPER), 1),
+			Error::<Runtime>::JobNotDue
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			KeeperModule::execute_job(Origin::signed(KEEPER), 1),
+			Error::<Runtime>::InsufficientBudget
+		);
+	});
+}
+
+#[test]
+fn execute_job_pays_keeper_when_call_fails() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(2000),
+			10,
+			100
+		));
+
+		assert_ok!(KeeperModule::execute_job(Origin::signed(KEEPER), 0));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobExecuted(
+			0,
+			Some(KEEPER),
+			10,
+			Err(orml_tokens::Error::<Runtime>::BalanceTooLow.into()),
+		)));
+		assert_eq!(Tokens::free_balance(ACA, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(ACA, &KEEPER), 10);
+		assert_eq!(KeeperModule::jobs(0).unwrap().budget, 90);
+	});
+}
+
+#[test]
+fn execute_job_rechecks_call_filter() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+
+		// the calls filtered after the registration can't be executed
+		set_tokens_calls_filtered(true);
+		assert_noop!(
+			KeeperModule::execute_job(Origin::signed(KEEPER), 0),
+			Error::<Runtime>::CallFiltered
+		);
+
+		set_tokens_calls_filtered(false);
+		assert_ok!(KeeperModule::execute_job(Origin::signed(KEEPER), 0));
+	});
+}
+
+#[test]
+fn execute_job_unsigned_burns_keeper_fee() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+		let call = crate::Call::<Runtime>::execute_job { job_id: 0 };
+		assert!(KeeperModule::validate_unsigned(TransactionSource::Local, &call).is_ok());
+		assert_noop!(KeeperModule::execute_job(Origin::root(), 0), BadOrigin);
+
+		assert_ok!(KeeperModule::execute_job(Origin::none(), 0));
+		System::assert_last_event(Event::KeeperModule(crate::Event::JobExecuted(0, None, 10, Ok(()))));
+		assert_eq!(Tokens::free_balance(ACA, &BOB), 1100);
+		assert_eq!(Tokens::free_balance(ACA, &KeeperModule::account_id()), 90);
+		assert_eq!(Tokens::total_issuance(ACA), 1990);
+
+		// the job is not due
+		assert!(KeeperModule::validate_unsigned(TransactionSource::Local, &call).is_err());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_executable_jobs() {
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::NotMet,
+			transfer_call(100),
+			10,
+			100
+		));
+		assert_ok!(KeeperModule::register_job(
+			Origin::signed(ALICE),
+			MockCondition::Met,
+			transfer_call(100),
+			10,
+			100
+		));
+
+		KeeperModule::offchain_worker(1);
+
+		// only the job whose condition is met is submitted
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		assert_eq!(tx.call, Call::KeeperModule(crate::Call::execute_job { job_id: 1 }));
+		assert!(pool_state.write().transactions.pop().is_none());
+	});
+}
@@ -0,0 +1,316 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Liquidation Insurance Module
+//!
+//! ## Overview
+//!
+//! Liquidation insurance module sells per-position liquidation protection,
+//! the premiums are paid in stable currency to the insurance fund kept by the
+//! module account. If the covered position is liquidated within the coverage
+//! period, a portion of the liquidation penalty is refunded to the owner from
+//! the fund, capped by the coverage of the policy and the balance of the fund.
+//! The fund is shared with the CDP insurance fund, which covers the bad debit
+//! of the system with it.
+//!
+//! The coverage of each collateral type is configured by governance, which
+//! can toggle its availability and cap the total outstanding coverage. Only
+//! the positions above the liquidation ratio can buy policies.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::{Happened, MultiCurrency};
+use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{Rate, Ratio, RiskManager};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The coverage params of a collateral type
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CoverageParams {
+	/// The premium paid for each stable currency of coverage
+	pub premium_rate: Rate,
+	/// The portion of the liquidation penalty refunded
+	pub refund_ratio: Ratio,
+	/// The cap of the total outstanding coverage
+	pub max_total_coverage: Balance,
+	/// Whether new policies can be bought
+	pub available: bool,
+}
+
+/// The liquidation insurance policy of a position
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct Policy<BlockNumber> {
+	/// The maximum amount of stable currency refunded
+	pub coverage: Balance,
+	/// The portion of the liquidation penalty refunded, fixed when bought
+	pub refund_ratio: Ratio,
+	/// The block number at which the policy expires
+	pub expire_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + loans::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to pay the premiums and the refunds
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// The number of blocks a policy covers since it's bought
+		#[pallet::constant]
+		type CoveragePeriod: Get<Self::BlockNumber>;
+
+		/// The origin which may update the coverage params.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The liquidation insurance module id, keep the insurance fund
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The coverage of the collateral type is not available
+		CoverageNotAvailable,
+		/// The coverage is zero
+		InvalidCoverage,
+		/// The position already has an active policy
+		PolicyAlreadyExists,
+		/// Exceed the cap of the total outstanding coverage
+		ExceedCoverageCap,
+		/// The position is below the liquidation ratio
+		PositionUnsafe,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The coverage params of the collateral type updated.
+		/// \[collateral_type, new_params\]
+		CoverageParamsUpdated(CurrencyId, Option<CoverageParams>),
+		/// The policy is bought. \[who, collateral_type, coverage, premium,
+		/// expire_at\]
+		PolicyBought(T::AccountId, CurrencyId, Balance, Balance, T::BlockNumber),
+		/// The policy expired. \[who, collateral_type\]
+		PolicyExpired(T::AccountId, CurrencyId),
+		/// The liquidation penalty is refunded by the policy. \[who,
+		/// collateral_type, refund_amount\]
+		PolicyClaimed(T::AccountId, CurrencyId, Balance),
+	}
+
+	/// The coverage params of collateral types.
+	///
+	/// CoverageParamsOf: map CurrencyId => Option<CoverageParams>
+	#[pallet::storage]
+	#[pallet::getter(fn coverage_params)]
+	pub type CoverageParamsOf<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, CoverageParams, OptionQuery>;
+
+	/// The policies of positions.
+	///
+	/// Policies: double_map CurrencyId, AccountId => Option<Policy>
+	#[pallet::storage]
+	#[pallet::getter(fn policies)]
+	pub type Policies<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, Policy<T::BlockNumber>, OptionQuery>;
+
+	/// The policies expiring at the block.
+	///
+	/// PolicyExpiries: double_map BlockNumber, (CurrencyId, AccountId) => ()
+	#[pallet::storage]
+	pub type PolicyExpiries<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, (CurrencyId, T::AccountId), (), OptionQuery>;
+
+	/// The total outstanding coverage of collateral types.
+	///
+	/// TotalCoverage: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_coverage)]
+	pub type TotalCoverage<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Remove the policies expiring at the block.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut count: u32 = 0;
+			for ((currency_id, who), _) in PolicyExpiries::<T>::drain_prefix(now) {
+				if let Some(policy) = Policies::<T>::take(currency_id, &who) {
+					TotalCoverage::<T>::mutate(currency_id, |total| *total = total.saturating_sub(policy.coverage));
+					Self::deposit_event(Event::PolicyExpired(who, currency_id));
+				}
+				count = count.saturating_add(1);
+			}
+			<T as Config>::WeightInfo::on_initialize(count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Update the coverage params of the collateral type, `None` means
+		/// the coverage is removed. The existing policies are not affected.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `params`: the new coverage params.
+		#[pallet::weight((<T as Config>::WeightInfo::set_coverage_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_coverage_params(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			params: Option<CoverageParams>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			CoverageParamsOf::<T>::mutate_exists(currency_id, |maybe_params| *maybe_params = params);
+			Self::deposit_event(Event::CoverageParamsUpdated(currency_id, params));
+			Ok(())
+		}
+
+		/// Buy the liquidation insurance policy for the position of the
+		/// collateral type, covering `CoveragePeriod` blocks.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `coverage`: the maximum amount of stable currency refunded.
+		#[pallet::weight(<T as Config>::WeightInfo::buy_policy())]
+		#[transactional]
+		pub fn buy_policy(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] coverage: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!coverage.is_zero(), Error::<T>::InvalidCoverage);
+			let params = Self::coverage_params(currency_id)
+				.filter(|params| params.available)
+				.ok_or(Error::<T>::CoverageNotAvailable)?;
+			ensure!(
+				!Policies::<T>::contains_key(currency_id, &who),
+				Error::<T>::PolicyAlreadyExists
+			);
+
+			// already unsafe positions can't be covered
+			let position = <loans::Pallet<T>>::positions(currency_id, &who);
+			<T as loans::Config>::RiskManager::check_vault_valid(
+				currency_id,
+				&who,
+				position.collateral,
+				position.debit,
+				false,
+			)
+			.map_err(|_| Error::<T>::PositionUnsafe)?;
+
+			TotalCoverage::<T>::try_mutate(currency_id, |total| -> DispatchResult {
+				let new_total = total.checked_add(coverage).ok_or(ArithmeticError::Overflow)?;
+				ensure!(new_total <= params.max_total_coverage, Error::<T>::ExceedCoverageCap);
+				*total = new_total;
+				Ok(())
+			})?;
+
+			let premium = params.premium_rate.saturating_mul_int(coverage);
+			<T as Config>::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::account_id(), premium)?;
+
+			let expire_at = <frame_system::Pallet<T>>::block_number().saturating_add(T::CoveragePeriod::get());
+			Policies::<T>::insert(
+				currency_id,
+				&who,
+				Policy {
+					coverage,
+					refund_ratio: params.refund_ratio,
+					expire_at,
+				},
+			);
+			PolicyExpiries::<T>::insert(expire_at, (currency_id, who.clone()), ());
+
+			Self::deposit_event(Event::PolicyBought(who, currency_id, coverage, premium, expire_at));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of liquidation insurance module.
+	pub fn account_id() -> T::AccountId {
+		<T as Config>::PalletId::get().into_account()
+	}
+
+	/// Refund the liquidation penalty to the owner of the liquidated position
+	/// if it's covered.
+	#[transactional]
+	fn claim_policy(who: &T::AccountId, currency_id: CurrencyId, liquidation_penalty: Balance) -> DispatchResult {
+		let policy = match Policies::<T>::take(currency_id, who) {
+			Some(policy) => policy,
+			None => return Ok(()),
+		};
+		PolicyExpiries::<T>::remove(policy.expire_at, (currency_id, who.clone()));
+		TotalCoverage::<T>::mutate(currency_id, |total| *total = total.saturating_sub(policy.coverage));
+
+		let fund = <T as Config>::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id());
+		let refund_amount = policy
+			.refund_ratio
+			.saturating_mul_int(liquidation_penalty)
+			.min(policy.coverage)
+			.min(fund);
+
+		if !refund_amount.is_zero() {
+			<T as Config>::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), who, refund_amount)?;
+		}
+
+		Self::deposit_event(Event::PolicyClaimed(who.clone(), currency_id, refund_amount));
+		Ok(())
+	}
+}
+
+impl<T: Config> Happened<(T::AccountId, CurrencyId, Balance)> for Pallet<T> {
+	fn happened((who, currency_id, liquidation_penalty): &(T::AccountId, CurrencyId, Balance)) {
+		if let Err(e) = Self::claim_policy(who, *currency_id, *liquidation_penalty) {
+			log::warn!(
+				target: "liquidation-insurance",
+				"claim_policy: failed to refund the liquidation penalty of {:?} {:?}: {:?}. \
+				This is unexpected but should be safe",
+				who, currency_id, e
+			);
+		}
+	}
+}
@@ -0,0 +1,193 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the liquidation insurance module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiCurrency;
+use sp_runtime::traits::BadOrigin;
+
+fn coverage_params(available: bool) -> CoverageParams {
+	CoverageParams {
+		premium_rate: Rate::saturating_from_rational(1, 10),
+		refund_ratio: Ratio::saturating_from_rational(1, 2),
+		max_total_coverage: 300,
+		available,
+	}
+}
+
+#[test]
+fn set_coverage_params_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			LiquidationInsuranceModule::set_coverage_params(Origin::signed(BOB), BTC, Some(coverage_params(true))),
+			BadOrigin
+		);
+
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			Some(coverage_params(true))
+		));
+		System::assert_last_event(Event::LiquidationInsuranceModule(crate::Event::CoverageParamsUpdated(
+			BTC,
+			Some(coverage_params(true)),
+		)));
+		assert_eq!(
+			LiquidationInsuranceModule::coverage_params(BTC),
+			Some(coverage_params(true))
+		);
+
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			None
+		));
+		assert_eq!(LiquidationInsuranceModule::coverage_params(BTC), None);
+	});
+}
+
+#[test]
+fn buy_policy_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 100),
+			Error::<Runtime>::CoverageNotAvailable
+		);
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			Some(coverage_params(false))
+		));
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 100),
+			Error::<Runtime>::CoverageNotAvailable
+		);
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			Some(coverage_params(true))
+		));
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 0),
+			Error::<Runtime>::InvalidCoverage
+		);
+
+		assert_ok!(LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 200));
+		System::assert_last_event(Event::LiquidationInsuranceModule(crate::Event::PolicyBought(
+			BOB, BTC, 200, 20, 11,
+		)));
+		assert_eq!(
+			LiquidationInsuranceModule::policies(BTC, BOB),
+			Some(Policy {
+				coverage: 200,
+				refund_ratio: Ratio::saturating_from_rational(1, 2),
+				expire_at: 11,
+			})
+		);
+		assert_eq!(LiquidationInsuranceModule::total_coverage(BTC), 200);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 980);
+		assert_eq!(
+			Tokens::free_balance(AUSD, &LiquidationInsuranceModule::account_id()),
+			20
+		);
+
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 100),
+			Error::<Runtime>::PolicyAlreadyExists
+		);
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(ALICE), BTC, 101),
+			Error::<Runtime>::ExceedCoverageCap
+		);
+
+		// the positions of DOT are unsafe
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			DOT,
+			Some(coverage_params(true))
+		));
+		assert_noop!(
+			LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), DOT, 100),
+			Error::<Runtime>::PositionUnsafe
+		);
+	});
+}
+
+#[test]
+fn policy_expire_on_initialize_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			Some(coverage_params(true))
+		));
+		assert_ok!(LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 200));
+
+		LiquidationInsuranceModule::on_initialize(10);
+		assert!(LiquidationInsuranceModule::policies(BTC, BOB).is_some());
+
+		LiquidationInsuranceModule::on_initialize(11);
+		System::assert_last_event(Event::LiquidationInsuranceModule(crate::Event::PolicyExpired(BOB, BTC)));
+		assert_eq!(LiquidationInsuranceModule::policies(BTC, BOB), None);
+		assert_eq!(LiquidationInsuranceModule::total_coverage(BTC), 0);
+
+		// the liquidation after expired is not covered
+		LiquidationInsuranceModule::happened(&(BOB, BTC, 100));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 980);
+	});
+}
+
+#[test]
+fn claim_policy_on_liquidation_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LiquidationInsuranceModule::set_coverage_params(
+			Origin::signed(ALICE),
+			BTC,
+			Some(coverage_params(true))
+		));
+		assert_ok!(LiquidationInsuranceModule::buy_policy(Origin::signed(BOB), BTC, 200));
+		assert_ok!(LiquidationInsuranceModule::buy_policy(Origin::signed(ALICE), BTC, 10));
+		assert_eq!(
+			Tokens::free_balance(AUSD, &LiquidationInsuranceModule::account_id()),
+			21
+		);
+
+		// the refund is capped by the balance of the fund
+		LiquidationInsuranceModule::happened(&(BOB, BTC, 100));
+		System::assert_last_event(Event::LiquidationInsuranceModule(crate::Event::PolicyClaimed(
+			BOB, BTC, 21,
+		)));
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1001);
+		assert_eq!(LiquidationInsuranceModule::policies(BTC, BOB), None);
+		assert_eq!(LiquidationInsuranceModule::total_coverage(BTC), 10);
+
+		// the refund is capped by the coverage of the policy
+		assert_ok!(Tokens::deposit(AUSD, &LiquidationInsuranceModule::account_id(), 100));
+		LiquidationInsuranceModule::happened(&(ALICE, BTC, 100));
+		System::assert_last_event(Event::LiquidationInsuranceModule(crate::Event::PolicyClaimed(
+			ALICE, BTC, 10,
+		)));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1009);
+		assert_eq!(LiquidationInsuranceModule::total_coverage(BTC), 0);
+	});
+}
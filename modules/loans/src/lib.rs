@@ -22,6 +22,12 @@
 //!
 //! Loans module manages CDP's collateral assets and the debits backed by these
 //! assets.
+//!
+//! Besides the collateral of its collateral type, a position can be backed by
+//! the extra collaterals of other collateral currencies, and the collateral
+//! ratio of the position is computed over the aggregate value by the risk
+//! manager. The debit of the position is still accounted under its collateral
+//! type.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
@@ -34,10 +40,10 @@ use primitives::{Amount, Balance, CurrencyId};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{AccountIdConversion, Convert, Zero},
-	ArithmeticError, DispatchResult, RuntimeDebug,
+	ArithmeticError, DispatchError, DispatchResult, RuntimeDebug,
 };
-use sp_std::{convert::TryInto, result};
-use support::{CDPTreasury, RiskManager};
+use sp_std::{convert::TryInto, prelude::*, result};
+use support::{CDPTreasury, DebtDenominations, RiskManager};
 
 mod mock;
 mod tests;
@@ -78,8 +84,9 @@ pub mod module {
 		type RiskManager: RiskManager<Self::AccountId, CurrencyId, Balance, Balance>;
 
 		/// CDP treasury for issuing/burning stable currency adjust debit value
-		/// adjustment
-		type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+		/// adjustment, or the debt denomination of the collateral type if it's
+		/// denominated in other stable asset
+		type CDPTreasury: DebtDenominations<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
 
 		/// The loan's module id, keep all collaterals of CDPs.
 		#[pallet::constant]
@@ -87,11 +94,21 @@ pub mod module {
 
 		/// Event handler which calls when update loan.
 		type OnUpdateLoan: Happened<(Self::AccountId, CurrencyId, Amount, Balance)>;
+
+		/// Event handler which calls when update the debit of the loan, with
+		/// the debit before the update.
+		type OnUpdateDebit: Happened<(Self::AccountId, CurrencyId, Balance)>;
+
+		/// Event handler which calls after the collateral, debit or extra
+		/// collaterals of the loan are updated.
+		type OnPositionUpdated: Happened<(Self::AccountId, CurrencyId)>;
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		AmountConvertFailed,
+		/// The extra collateral is the collateral type of the position
+		InvalidExtraCollateral,
 	}
 
 	#[pallet::event]
@@ -106,6 +123,13 @@ pub mod module {
 		ConfiscateCollateralAndDebit(T::AccountId, CurrencyId, Balance, Balance),
 		/// Transfer loan. \[from, to, currency_id\]
 		TransferLoan(T::AccountId, T::AccountId, CurrencyId),
+		/// Extra collateral of the position updated. \[owner, collateral_type,
+		/// extra_collateral_type, collateral_adjustment\]
+		ExtraCollateralUpdated(T::AccountId, CurrencyId, CurrencyId, Amount),
+		/// Confiscate the extra collateral of the position. \[owner,
+		/// collateral_type, extra_collateral_type,
+		/// confiscated_collateral_amount\]
+		ConfiscateExtraCollateral(T::AccountId, CurrencyId, CurrencyId, Balance),
 	}
 
 	/// The collateralized debit positions, map from
@@ -118,13 +142,32 @@ pub mod module {
 		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, Position, ValueQuery>;
 
 	/// The total collateralized debit positions, map from
-	/// CollateralType -> Position
+	/// CollateralType -> Position, the collateral includes the extra
+	/// collaterals of the type backing the positions of other types
 	///
 	/// TotalPositions: CurrencyId => Position
 	#[pallet::storage]
 	#[pallet::getter(fn total_positions)]
 	pub type TotalPositions<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Position, ValueQuery>;
 
+	/// The extra collaterals backing the positions, map from
+	/// (CollateralType, Owner) -> ExtraCollateralType -> Amount
+	///
+	/// ExtraCollaterals: double_map (CurrencyId, AccountId), CurrencyId =>
+	/// Balance
+	#[pallet::storage]
+	#[pallet::getter(fn extra_collateral)]
+	pub type ExtraCollaterals<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, (CurrencyId, T::AccountId), Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The total extra collaterals backing the positions, map from
+	/// ExtraCollateralType -> Amount
+	///
+	/// TotalExtraCollaterals: CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_extra_collaterals)]
+	pub type TotalExtraCollaterals<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -159,7 +202,10 @@ impl<T: Config> Pallet<T> {
 
 		// deposit debit to cdp treasury
 		let bad_debt_value = T::RiskManager::get_bad_debt_value(currency_id, debit_decrease);
-		T::CDPTreasury::on_system_debit(bad_debt_value)?;
+		match T::RiskManager::get_debt_denomination(currency_id) {
+			Some(denomination) => T::CDPTreasury::on_system_debit_of(denomination, bad_debt_value)?,
+			None => T::CDPTreasury::on_system_debit(bad_debt_value)?,
+		}
 
 		// update loan
 		Self::update_loan(
@@ -207,17 +253,26 @@ impl<T: Config> Pallet<T> {
 			T::RiskManager::check_debit_cap(currency_id, Self::total_positions(currency_id).debit)?;
 
 			// issue debit with collateral backed by cdp treasury
-			T::CDPTreasury::issue_debit(who, T::Convert::convert((currency_id, debit_balance_adjustment)), true)?;
+			let debit_value = T::Convert::convert((currency_id, debit_balance_adjustment));
+			match T::RiskManager::get_debt_denomination(currency_id) {
+				Some(denomination) => T::CDPTreasury::issue_debit_of(denomination, who, debit_value, true)?,
+				None => T::CDPTreasury::issue_debit(who, debit_value, true)?,
+			}
 		} else if debit_adjustment.is_negative() {
 			// repay debit
 			// burn debit by cdp treasury
-			T::CDPTreasury::burn_debit(who, T::Convert::convert((currency_id, debit_balance_adjustment)))?;
+			let debit_value = T::Convert::convert((currency_id, debit_balance_adjustment));
+			match T::RiskManager::get_debt_denomination(currency_id) {
+				Some(denomination) => T::CDPTreasury::burn_debit_of(denomination, who, debit_value)?,
+				None => T::CDPTreasury::burn_debit(who, debit_value)?,
+			}
 		}
 
 		// ensure pass risk check
 		let Position { collateral, debit } = Self::positions(currency_id, who);
-		T::RiskManager::check_position_valid(
+		T::RiskManager::check_vault_valid(
 			currency_id,
+			who,
 			collateral,
 			debit,
 			collateral_adjustment.is_negative() || debit_adjustment.is_positive(),
@@ -232,11 +287,21 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	/// transfer whole loan of `from` to `to`
+	/// transfer whole loan of `from` to `to`, including the extra collaterals
+	///
+	/// Ensured atomic.
+	#[transactional]
 	pub fn transfer_loan(from: &T::AccountId, to: &T::AccountId, currency_id: CurrencyId) -> DispatchResult {
 		// get `from` position data
 		let Position { collateral, debit } = Self::positions(currency_id, from);
 
+		// move the extra collaterals of `from` to `to`
+		for (extra_currency_id, amount) in Self::extra_collaterals(currency_id, from) {
+			let adjustment = Self::amount_try_from_balance(amount)?;
+			Self::update_extra_collateral(from, currency_id, extra_currency_id, adjustment.saturating_neg())?;
+			Self::update_extra_collateral(to, currency_id, extra_currency_id, adjustment)?;
+		}
+
 		let Position {
 			collateral: to_collateral,
 			debit: to_debit,
@@ -249,7 +314,7 @@ impl<T: Config> Pallet<T> {
 			.expect("existing debit balance cannot overflow; qed");
 
 		// check new position
-		T::RiskManager::check_position_valid(currency_id, new_to_collateral_balance, new_to_debit_balance, true)?;
+		T::RiskManager::check_vault_valid(currency_id, to, new_to_collateral_balance, new_to_debit_balance, true)?;
 
 		// balance -> amount
 		let collateral_adjustment = Self::amount_try_from_balance(collateral)?;
@@ -267,8 +332,171 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// adjust the extra collateral of the position.
+	///
+	/// Ensured atomic.
+	#[transactional]
+	pub fn adjust_extra_collateral(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		extra_currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+	) -> DispatchResult {
+		Self::update_extra_collateral(who, currency_id, extra_currency_id, collateral_adjustment)?;
+
+		let collateral_balance_adjustment = Self::balance_try_from_amount_abs(collateral_adjustment)?;
+		let module_account = Self::account_id();
+
+		if collateral_adjustment.is_positive() {
+			T::Currency::transfer(extra_currency_id, who, &module_account, collateral_balance_adjustment)?;
+		} else if collateral_adjustment.is_negative() {
+			T::Currency::transfer(extra_currency_id, &module_account, who, collateral_balance_adjustment)?;
+		}
+
+		// ensure pass risk check
+		let Position { collateral, debit } = Self::positions(currency_id, who);
+		T::RiskManager::check_vault_valid(currency_id, who, collateral, debit, collateral_adjustment.is_negative())?;
+
+		Self::deposit_event(Event::ExtraCollateralUpdated(
+			who.clone(),
+			currency_id,
+			extra_currency_id,
+			collateral_adjustment,
+		));
+		Ok(())
+	}
+
+	/// confiscate all extra collaterals of the position to cdp treasury,
+	/// return the confiscated extra collaterals.
+	///
+	/// Ensured atomic.
+	#[transactional]
+	pub fn confiscate_extra_collaterals(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+	) -> result::Result<Vec<(CurrencyId, Balance)>, DispatchError> {
+		let extra_collaterals = Self::extra_collaterals(currency_id, who);
+		for (extra_currency_id, amount) in extra_collaterals.iter() {
+			Self::confiscate_extra_collateral(who, currency_id, *extra_currency_id, *amount)?;
+		}
+		Ok(extra_collaterals)
+	}
+
+	/// confiscate `amount` of the extra collateral of `extra_currency_id`
+	/// of the position to cdp treasury.
+	///
+	/// Ensured atomic.
+	#[transactional]
+	pub fn confiscate_extra_collateral(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		extra_currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		// transfer extra collateral to cdp treasury
+		T::CDPTreasury::deposit_collateral(&Self::account_id(), extra_currency_id, amount)?;
+
+		Self::update_extra_collateral(
+			who,
+			currency_id,
+			extra_currency_id,
+			Self::amount_try_from_balance(amount)?.saturating_neg(),
+		)?;
+
+		Self::deposit_event(Event::ConfiscateExtraCollateral(
+			who.clone(),
+			currency_id,
+			extra_currency_id,
+			amount,
+		));
+		Ok(())
+	}
+
+	/// get the extra collaterals of the position.
+	pub fn extra_collaterals(currency_id: CurrencyId, who: &T::AccountId) -> Vec<(CurrencyId, Balance)> {
+		ExtraCollaterals::<T>::iter_prefix((currency_id, who.clone())).collect()
+	}
+
+	/// mutate records of extra collaterals
+	pub fn update_extra_collateral(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		extra_currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+	) -> DispatchResult {
+		ensure!(extra_currency_id != currency_id, Error::<T>::InvalidExtraCollateral);
+		let collateral_balance = Self::balance_try_from_amount_abs(collateral_adjustment)?;
+
+		ExtraCollaterals::<T>::try_mutate_exists(
+			(currency_id, who.clone()),
+			extra_currency_id,
+			|maybe_collateral| -> DispatchResult {
+				let collateral = maybe_collateral.take().unwrap_or_default();
+				let new_collateral = if collateral_adjustment.is_positive() {
+					collateral
+						.checked_add(collateral_balance)
+						.ok_or(ArithmeticError::Overflow)
+				} else {
+					collateral
+						.checked_sub(collateral_balance)
+						.ok_or(ArithmeticError::Underflow)
+				}?;
+
+				// increase account ref if new extra collateral
+				if collateral.is_zero() && !new_collateral.is_zero() {
+					if frame_system::Pallet::<T>::inc_consumers(who).is_err() {
+						log::warn!(
+							"Warning: Attempt to introduce lock consumer reference, yet no providers. \
+							This is unexpected but should be safe."
+						);
+					}
+				}
+
+				if new_collateral.is_zero() {
+					// decrease account ref and remove storage if zero extra collateral
+					if !collateral.is_zero() {
+						frame_system::Pallet::<T>::dec_consumers(who);
+					}
+					*maybe_collateral = None;
+				} else {
+					*maybe_collateral = Some(new_collateral);
+				}
+
+				Ok(())
+			},
+		)?;
+
+		TotalExtraCollaterals::<T>::try_mutate(extra_currency_id, |total| -> DispatchResult {
+			*total = if collateral_adjustment.is_positive() {
+				total.checked_add(collateral_balance).ok_or(ArithmeticError::Overflow)
+			} else {
+				total.checked_sub(collateral_balance).ok_or(ArithmeticError::Underflow)
+			}?;
+			Ok(())
+		})?;
+
+		// the extra collateral is held by the loans as the collateral of its type
+		TotalPositions::<T>::try_mutate(extra_currency_id, |total_positions| -> DispatchResult {
+			total_positions.collateral = if collateral_adjustment.is_positive() {
+				total_positions
+					.collateral
+					.checked_add(collateral_balance)
+					.ok_or(ArithmeticError::Overflow)
+			} else {
+				total_positions
+					.collateral
+					.checked_sub(collateral_balance)
+					.ok_or(ArithmeticError::Underflow)
+			}?;
+			Ok(())
+		})?;
+
+		T::OnPositionUpdated::happened(&(who.clone(), currency_id));
+		Ok(())
+	}
+
 	/// mutate records of collaterals and debits
-	fn update_loan(
+	pub fn update_loan(
 		who: &T::AccountId,
 		currency_id: CurrencyId,
 		collateral_adjustment: Amount,
@@ -294,6 +522,11 @@ impl<T: Config> Pallet<T> {
 				p.debit.checked_sub(debit_balance).ok_or(ArithmeticError::Underflow)
 			}?;
 
+			// check the cap per account when increase debit
+			if debit_adjustment.is_positive() {
+				T::RiskManager::check_debit_per_account(currency_id, who, new_debit)?;
+			}
+
 			// increase account ref if new position
 			if p.collateral.is_zero() && p.debit.is_zero() {
 				if frame_system::Pallet::<T>::inc_consumers(who).is_err() {
@@ -312,6 +545,9 @@ impl<T: Config> Pallet<T> {
 			// and the data will been messed up, before migration or calibration,
 			// it is forbidden to turn on incentives for pool LoansIncentive(KSM).
 			T::OnUpdateLoan::happened(&(who.clone(), currency_id, collateral_adjustment, p.collateral));
+			if !debit_adjustment.is_zero() {
+				T::OnUpdateDebit::happened(&(who.clone(), currency_id, p.debit));
+			}
 			p.collateral = new_collateral;
 			p.debit = new_debit;
 
@@ -354,18 +590,21 @@ impl<T: Config> Pallet<T> {
 			}?;
 
 			Ok(())
-		})
+		})?;
+
+		T::OnPositionUpdated::happened(&(who.clone(), currency_id));
+		Ok(())
 	}
 }
 
 impl<T: Config> Pallet<T> {
 	/// Convert `Balance` to `Amount`.
-	fn amount_try_from_balance(b: Balance) -> result::Result<Amount, Error<T>> {
+	pub fn amount_try_from_balance(b: Balance) -> result::Result<Amount, Error<T>> {
 		TryInto::<Amount>::try_into(b).map_err(|_| Error::<T>::AmountConvertFailed)
 	}
 
 	/// Convert the absolute value of `Amount` to `Balance`.
-	fn balance_try_from_amount_abs(a: Amount) -> result::Result<Balance, Error<T>> {
+	pub fn balance_try_from_amount_abs(a: Amount) -> result::Result<Balance, Error<T>> {
 		TryInto::<Balance>::try_into(a.saturating_abs()).map_err(|_| Error::<T>::AmountConvertFailed)
 	}
 }
@@ -219,3 +219,57 @@ fn loan_updated_updated_when_adjust_collateral() {
 		assert_eq!(DOT_SHARES.with(|v| *v.borrow().get(&BOB).unwrap_or(&0)), 200);
 	});
 }
+
+#[test]
+fn adjust_extra_collateral_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 500, 300));
+
+		assert_noop!(
+			LoansModule::adjust_extra_collateral(&ALICE, BTC, BTC, 100),
+			Error::<Runtime>::InvalidExtraCollateral
+		);
+
+		assert_ok!(LoansModule::adjust_extra_collateral(&ALICE, BTC, DOT, 300));
+		System::assert_last_event(Event::LoansModule(crate::Event::ExtraCollateralUpdated(
+			ALICE, BTC, DOT, 300,
+		)));
+		assert_eq!(LoansModule::extra_collateral((BTC, ALICE), DOT), 300);
+		assert_eq!(LoansModule::extra_collaterals(BTC, &ALICE), vec![(DOT, 300)]);
+		assert_eq!(LoansModule::total_extra_collaterals(DOT), 300);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 700);
+		assert_eq!(Currencies::free_balance(DOT, &LoansModule::account_id()), 300);
+		// the extra collateral doesn't change the position, but is counted in the
+		// total positions of its type
+		assert_eq!(LoansModule::positions(BTC, &ALICE).collateral, 500);
+		assert_eq!(LoansModule::total_positions(BTC).collateral, 500);
+		assert_eq!(LoansModule::total_positions(DOT).collateral, 300);
+
+		assert_ok!(LoansModule::adjust_extra_collateral(&ALICE, BTC, DOT, -100));
+		assert_eq!(LoansModule::extra_collateral((BTC, ALICE), DOT), 200);
+		assert_eq!(LoansModule::total_extra_collaterals(DOT), 200);
+		assert_eq!(LoansModule::total_positions(DOT).collateral, 200);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 800);
+		assert_noop!(
+			LoansModule::adjust_extra_collateral(&ALICE, BTC, DOT, -300),
+			ArithmeticError::Underflow
+		);
+
+		// the extra collaterals move with the loan
+		assert_ok!(LoansModule::transfer_loan(&ALICE, &BOB, BTC));
+		assert_eq!(LoansModule::extra_collaterals(BTC, &ALICE), vec![]);
+		assert_eq!(LoansModule::extra_collaterals(BTC, &BOB), vec![(DOT, 200)]);
+		assert_eq!(LoansModule::total_extra_collaterals(DOT), 200);
+
+		assert_ok!(LoansModule::confiscate_extra_collaterals(&BOB, BTC), vec![(DOT, 200)]);
+		System::assert_last_event(Event::LoansModule(crate::Event::ConfiscateExtraCollateral(
+			BOB, BTC, DOT, 200,
+		)));
+		assert_eq!(LoansModule::extra_collaterals(BTC, &BOB), vec![]);
+		assert_eq!(LoansModule::total_extra_collaterals(DOT), 0);
+		assert_eq!(LoansModule::total_positions(DOT).collateral, 0);
+		assert_eq!(CDPTreasuryModule::get_total_collaterals(DOT), 200);
+		assert_eq!(Currencies::free_balance(DOT, &LoansModule::account_id()), 0);
+	});
+}
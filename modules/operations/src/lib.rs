@@ -0,0 +1,423 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Operations Module
+//!
+//! ## Overview
+//!
+//! Operations module allows the operator appointed by governance, usually an
+//! operations multisig, to execute a constrained menu of pre-approved market
+//! operations without full governance votes:
+//! - swap the collateral held by CDP treasury to stable currency on DEX, at a
+//!   price within `MaxSwapSlippageCompareToOracle` of the oracle price.
+//! - top up the insurance fund with the surplus of CDP treasury.
+//! - roll the DEX liquidity positions of the treasury account, only while the
+//!   pool price is within `MaxSwapSlippageCompareToOracle` of the oracle price.
+//!
+//! The total value of the operations of a day, in stable currency, is capped
+//! by the daily value limit set by governance. Every operation deposits an
+//! event with the operator and the amounts for auditing.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId, TradingPair};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{CheckedDiv, One, Saturating, UniqueSaturatedInto, Zero},
+	FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{CDPTreasury, CDPTreasuryExtended, DEXManager, Price, PriceProvider, Ratio};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The roll of the DEX liquidity position of the treasury account.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum DexPositionRoll {
+	/// Add liquidity with at most the amounts of the two currencies.
+	Add(Balance, Balance),
+	/// Remove the liquidity of the dex share amount.
+	Remove(Balance),
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may appoint the operator and set the daily value
+		/// limit.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// CDP treasury to swap the collateral and spend the surplus
+		type CDPTreasury: CDPTreasuryExtended<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The oracle price source to value the operations
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The max slippage of the swap price compared to the oracle price
+		#[pallet::constant]
+		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
+
+		/// The account of the insurance fund
+		#[pallet::constant]
+		type InsuranceFundAccount: Get<Self::AccountId>;
+
+		/// Currency to measure the liquidity rolled by the treasury account
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// DEX to roll the liquidity positions of the treasury account
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The account holding the DEX liquidity positions of the treasury
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The number of blocks of a day
+		#[pallet::constant]
+		type BlocksPerDay: Get<Self::BlockNumber>;
+
+		/// The maximum length of the swap path of the treasury collateral
+		#[pallet::constant]
+		type TradingPathLimit: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller is not the operator
+		NoPermission,
+		/// The value of the operations exceeds the daily value limit
+		ExceedDailyValueLimit,
+		/// The oracle price is not available
+		InvalidFeedPrice,
+		/// The surplus of CDP treasury is not enough
+		InsufficientSurplus,
+		/// The currency ids are not a valid trading pair
+		InvalidCurrencyId,
+		/// The pool price is out of the band of the oracle price
+		PoolPriceOutOfBand,
+		/// The swap path of the collateral exceeds `TradingPathLimit`
+		InvalidSwapPath,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The operator is updated. \[new_operator\]
+		OperatorUpdated(Option<T::AccountId>),
+		/// The daily value limit is updated. \[new_limit\]
+		DailyValueLimitUpdated(Balance),
+		/// The collateral of CDP treasury is swapped to stable currency.
+		/// \[operator, collateral_type, supply_amount, target_amount\]
+		TreasuryCollateralSwapped(T::AccountId, CurrencyId, Balance, Balance),
+		/// The insurance fund is topped up with the surplus of CDP treasury.
+		/// \[operator, amount\]
+		InsuranceFundToppedUp(T::AccountId, Balance),
+		/// Liquidity is added to DEX by the treasury account.
+		/// \[operator, currency_id_a, amount_a, currency_id_b, amount_b\]
+		TreasuryLiquidityAdded(T::AccountId, CurrencyId, Balance, CurrencyId, Balance),
+		/// Liquidity is removed from DEX by the treasury account.
+		/// \[operator, currency_id_a, amount_a, currency_id_b, amount_b\]
+		TreasuryLiquidityRemoved(T::AccountId, CurrencyId, Balance, CurrencyId, Balance),
+	}
+
+	/// The operator appointed by governance.
+	///
+	/// Operator: Option<AccountId>
+	#[pallet::storage]
+	#[pallet::getter(fn operator)]
+	pub type Operator<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// The max total value of the operations of a day.
+	///
+	/// DailyValueLimit: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn daily_value_limit)]
+	pub type DailyValueLimit<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	/// The index of the day and the total value of the operations executed in
+	/// the day.
+	///
+	/// DailyValueUsed: (DayIndex, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn daily_value_used)]
+	pub type DailyValueUsed<T: Config> = StorageValue<_, (u32, Balance), ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Appoint the operator, or remove it by `None`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `operator`: the new operator.
+		#[pallet::weight((T::WeightInfo::set_operator(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_operator(origin: OriginFor<T>, operator: Option<T::AccountId>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Operator::<T>::set(operator.clone());
+			Self::deposit_event(Event::OperatorUpdated(operator));
+			Ok(())
+		}
+
+		/// Set the max total value of the operations of a day.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `limit`: the daily value limit in stable currency.
+		#[pallet::weight((T::WeightInfo::set_daily_value_limit(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_daily_value_limit(origin: OriginFor<T>, #[pallet::compact] limit: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			DailyValueLimit::<T>::put(limit);
+			Self::deposit_event(Event::DailyValueLimitUpdated(limit));
+			Ok(())
+		}
+
+		/// Swap the collateral held by CDP treasury to stable currency on DEX,
+		/// the swap price must be within `MaxSwapSlippageCompareToOracle` of
+		/// the oracle price.
+		///
+		/// The dispatch origin of this call must be the operator.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `supply_amount`: the collateral amount to swap.
+		#[pallet::weight(T::WeightInfo::swap_treasury_collateral()
+			.saturating_add(T::CDPTreasury::swap_collateral_to_stable_weight(T::TradingPathLimit::get())))]
+		#[transactional]
+		pub fn swap_treasury_collateral(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] supply_amount: Balance,
+		) -> DispatchResultWithPostInfo {
+			let operator = Self::ensure_operator(origin)?;
+			let swap_path = T::CDPTreasury::get_collateral_swap_path(currency_id);
+			let swap_path_len = swap_path.len() as u32;
+			ensure!(swap_path_len <= T::TradingPathLimit::get(), Error::<T>::InvalidSwapPath);
+
+			let price = T::PriceSource::get_relative_price(currency_id, T::GetStableCurrencyId::get())
+				.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let value = price.saturating_mul_int(supply_amount);
+			Self::use_daily_value(value)?;
+
+			let min_target_amount = Ratio::one()
+				.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+				.saturating_mul_int(value);
+			let target_amount = T::CDPTreasury::swap_exact_collateral_to_stable(
+				currency_id,
+				supply_amount,
+				min_target_amount,
+				&swap_path,
+				false,
+			)?;
+
+			Self::deposit_event(Event::TreasuryCollateralSwapped(
+				operator,
+				currency_id,
+				supply_amount,
+				target_amount,
+			));
+			Ok(Some(
+				T::WeightInfo::swap_treasury_collateral()
+					.saturating_add(T::CDPTreasury::swap_collateral_to_stable_weight(swap_path_len)),
+			)
+			.into())
+		}
+
+		/// Top up the insurance fund with the surplus of CDP treasury which
+		/// is not offset by the debit pool.
+		///
+		/// The dispatch origin of this call must be the operator.
+		///
+		/// - `amount`: the stable currency amount to top up.
+		#[pallet::weight(T::WeightInfo::top_up_insurance_fund())]
+		#[transactional]
+		pub fn top_up_insurance_fund(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let operator = Self::ensure_operator(origin)?;
+			ensure!(
+				T::CDPTreasury::get_surplus_pool().saturating_sub(T::CDPTreasury::get_debit_pool()) >= amount,
+				Error::<T>::InsufficientSurplus
+			);
+			Self::use_daily_value(amount)?;
+
+			T::CDPTreasury::withdraw_surplus(&T::InsuranceFundAccount::get(), amount)?;
+
+			Self::deposit_event(Event::InsuranceFundToppedUp(operator, amount));
+			Ok(())
+		}
+
+		/// Roll the DEX liquidity position of the treasury account in the
+		/// pool of `currency_id_a` and `currency_id_b`, the pool price must be
+		/// within `MaxSwapSlippageCompareToOracle` of the oracle price.
+		///
+		/// The dispatch origin of this call must be the operator.
+		///
+		/// - `currency_id_a`: currency id A.
+		/// - `currency_id_b`: currency id B.
+		/// - `roll`: add or remove the liquidity.
+		#[pallet::weight(T::WeightInfo::roll_treasury_dex_position())]
+		#[transactional]
+		pub fn roll_treasury_dex_position(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			roll: DexPositionRoll,
+		) -> DispatchResult {
+			let operator = Self::ensure_operator(origin)?;
+			TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+			let (price_a, price_b) = Self::ensure_pool_price_in_band(currency_id_a, currency_id_b)?;
+
+			let treasury_account = T::TreasuryAccount::get();
+			let balance_a = T::Currency::free_balance(currency_id_a, &treasury_account);
+			let balance_b = T::Currency::free_balance(currency_id_b, &treasury_account);
+			let (amount_a, amount_b) = match roll {
+				DexPositionRoll::Add(max_amount_a, max_amount_b) => {
+					T::DEX::add_liquidity(
+						&treasury_account,
+						currency_id_a,
+						currency_id_b,
+						max_amount_a,
+						max_amount_b,
+						Zero::zero(),
+						false,
+					)?;
+					(
+						balance_a.saturating_sub(T::Currency::free_balance(currency_id_a, &treasury_account)),
+						balance_b.saturating_sub(T::Currency::free_balance(currency_id_b, &treasury_account)),
+					)
+				}
+				DexPositionRoll::Remove(remove_share) => {
+					T::DEX::remove_liquidity(
+						&treasury_account,
+						currency_id_a,
+						currency_id_b,
+						remove_share,
+						Zero::zero(),
+						Zero::zero(),
+						false,
+					)?;
+					(
+						T::Currency::free_balance(currency_id_a, &treasury_account).saturating_sub(balance_a),
+						T::Currency::free_balance(currency_id_b, &treasury_account).saturating_sub(balance_b),
+					)
+				}
+			};
+
+			// value the liquidity rolled in stable currency
+			Self::use_daily_value(
+				price_a
+					.saturating_mul_int(amount_a)
+					.saturating_add(price_b.saturating_mul_int(amount_b)),
+			)?;
+
+			Self::deposit_event(match roll {
+				DexPositionRoll::Add(..) => {
+					Event::TreasuryLiquidityAdded(operator, currency_id_a, amount_a, currency_id_b, amount_b)
+				}
+				DexPositionRoll::Remove(..) => {
+					Event::TreasuryLiquidityRemoved(operator, currency_id_a, amount_a, currency_id_b, amount_b)
+				}
+			});
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn ensure_operator(origin: OriginFor<T>) -> Result<T::AccountId, DispatchError> {
+		let who = ensure_signed(origin)?;
+		ensure!(Self::operator() == Some(who.clone()), Error::<T>::NoPermission);
+		Ok(who)
+	}
+
+	/// Ensure the price of `currency_id_a` in `currency_id_b` of the DEX
+	/// pool is within `MaxSwapSlippageCompareToOracle` of the oracle price,
+	/// returns the oracle prices of the two currencies in stable currency.
+	fn ensure_pool_price_in_band(
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+	) -> Result<(Price, Price), DispatchError> {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let price_a = T::PriceSource::get_relative_price(currency_id_a, stable_currency_id)
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+		let price_b = T::PriceSource::get_relative_price(currency_id_b, stable_currency_id)
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+		let oracle_price = price_a.checked_div(&price_b).ok_or(Error::<T>::InvalidFeedPrice)?;
+
+		let (pool_a, pool_b) = T::DEX::get_liquidity_pool(currency_id_a, currency_id_b);
+		let pool_price = Price::checked_from_rational(pool_b, pool_a).ok_or(Error::<T>::PoolPriceOutOfBand)?;
+		ensure!(
+			pool_price
+				.max(oracle_price)
+				.saturating_sub(pool_price.min(oracle_price))
+				<= T::MaxSwapSlippageCompareToOracle::get().saturating_mul(oracle_price),
+			Error::<T>::PoolPriceOutOfBand
+		);
+		Ok((price_a, price_b))
+	}
+
+	/// Get the index of the day of the block.
+	pub fn day_index(block_number: T::BlockNumber) -> u32 {
+		let blocks_per_day = T::BlocksPerDay::get();
+		if blocks_per_day.is_zero() {
+			Zero::zero()
+		} else {
+			(block_number / blocks_per_day).unique_saturated_into()
+		}
+	}
+
+	/// Add `value` to the total value of the operations of today, the total
+	/// value must not exceed the daily value limit.
+	fn use_daily_value(value: Balance) -> DispatchResult {
+		let today = Self::day_index(frame_system::Pallet::<T>::block_number());
+		DailyValueUsed::<T>::try_mutate(|(day, used)| -> DispatchResult {
+			if *day != today {
+				*day = today;
+				*used = Zero::zero();
+			}
+			let new_used = used.saturating_add(value);
+			ensure!(new_used <= Self::daily_value_limit(), Error::<T>::ExceedDailyValueLimit);
+			*used = new_used;
+			Ok(())
+		})
+	}
+}
@@ -0,0 +1,436 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the operations module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, traits::Nothing};
+use frame_system::EnsureSignedBy;
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use primitives::{Amount, TokenSymbol};
+use sp_core::{H160, H256};
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, DispatchResult};
+use sp_std::cell::RefCell;
+use support::Price;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CDP_TREASURY: AccountId = 10;
+pub const INSURANCE_FUND: AccountId = 11;
+pub const TREASURY: AccountId = 12;
+pub const DEX_ACCOUNT: AccountId = 13;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
+pub const BTC: CurrencyId = CurrencyId::Token(TokenSymbol::RENBTC);
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+
+mod operations {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = Nothing;
+}
+
+thread_local! {
+	static DEBIT_POOL: RefCell<Balance> = RefCell::new(0);
+	static LIQUIDITY_POOL: RefCell<(Balance, Balance)> = RefCell::new((0, 0));
+}
+
+pub fn set_debit_pool(amount: Balance) {
+	DEBIT_POOL.with(|v| *v.borrow_mut() = amount);
+}
+
+pub fn set_liquidity_pool(pool_dot: Balance, pool_ausd: Balance) {
+	LIQUIDITY_POOL.with(|v| *v.borrow_mut() = (pool_dot, pool_ausd));
+}
+
+pub struct MockCDPTreasury;
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Tokens::free_balance(AUSD, &CDP_TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		DEBIT_POOL.with(|v| *v.borrow())
+	}
+
+	fn get_total_collaterals(_id: CurrencyId) -> Balance {
+		unimplemented!()
+	}
+
+	fn get_debit_proportion(_amount: Balance) -> Ratio {
+		unimplemented!()
+	}
+
+	fn on_system_debit(_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_system_surplus(_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, backed: bool) -> DispatchResult {
+		if !backed {
+			DEBIT_POOL.with(|v| *v.borrow_mut() += debit);
+		}
+		Tokens::deposit(AUSD, who, debit)
+	}
+
+	fn burn_debit(_who: &AccountId, _debit: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_surplus(_from: &AccountId, _surplus: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn withdraw_surplus(to: &AccountId, surplus: Balance) -> DispatchResult {
+		Tokens::transfer(AUSD, &CDP_TREASURY, to, surplus)
+	}
+
+	fn deposit_collateral(_from: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn withdraw_collateral(_to: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
+impl CDPTreasuryExtended<AccountId> for MockCDPTreasury {
+	// swap the collateral at 90% of the oracle price
+	fn swap_exact_collateral_to_stable(
+		currency_id: CurrencyId,
+		supply_amount: Balance,
+		min_target_amount: Balance,
+		_swap_path: &[CurrencyId],
+		_collateral_in_auction: bool,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let target_amount = supply_amount * 9 / 10;
+		if target_amount < min_target_amount {
+			return Err(DispatchError::Other("InsufficientTargetAmount"));
+		}
+		Tokens::withdraw(currency_id, &CDP_TREASURY, supply_amount)?;
+		Tokens::deposit(AUSD, &CDP_TREASURY, target_amount)?;
+		Ok(target_amount)
+	}
+
+	fn swap_collateral_to_exact_stable(
+		_currency_id: CurrencyId,
+		_max_supply_amount: Balance,
+		_target_amount: Balance,
+		_swap_path: &[CurrencyId],
+		_collateral_in_auction: bool,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn swap_collateral_to_stable_weight(path_len: u32) -> Weight {
+		10_000 * path_len as Weight
+	}
+
+	fn swap_collateral_to_stable_by_aggregated_paths(
+		_currency_id: CurrencyId,
+		_max_supply_amount: Balance,
+		_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn create_collateral_auctions(
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_target: Balance,
+		_refund_receiver: AccountId,
+		_splited: bool,
+	) -> sp_std::result::Result<u32, DispatchError> {
+		unimplemented!()
+	}
+
+	fn create_decreasing_price_sale(
+		_currency_id: CurrencyId,
+		_amount: Balance,
+		_target: Balance,
+		_refund_receiver: AccountId,
+	) -> sp_std::result::Result<u32, DispatchError> {
+		unimplemented!()
+	}
+
+	fn get_collateral_swap_path(currency_id: CurrencyId) -> Vec<CurrencyId> {
+		vec![currency_id, AUSD]
+	}
+
+	fn is_auction_exempt(_currency_id: CurrencyId) -> bool {
+		unimplemented!()
+	}
+
+	fn max_auction() -> u32 {
+		unimplemented!()
+	}
+}
+
+// only the pool of DOT and AUSD, the dex share is measured in DOT
+pub struct MockDEX;
+impl MockDEX {
+	fn pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<(Balance, Balance)> {
+		let (pool_dot, pool_ausd) = LIQUIDITY_POOL.with(|v| *v.borrow());
+		match (currency_id_a, currency_id_b) {
+			(DOT, AUSD) => Some((pool_dot, pool_ausd)),
+			(AUSD, DOT) => Some((pool_ausd, pool_dot)),
+			_ => None,
+		}
+	}
+
+	fn set_pool(currency_id_a: CurrencyId, pool_a: Balance, pool_b: Balance) {
+		if currency_id_a == DOT {
+			set_liquidity_pool(pool_a, pool_b);
+		} else {
+			set_liquidity_pool(pool_b, pool_a);
+		}
+	}
+}
+
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		Self::pool(currency_id_a, currency_id_b).unwrap_or_default()
+	}
+
+	fn get_liquidity_token_address(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<H160> {
+		unimplemented!()
+	}
+
+	fn get_swap_target_amount(_path: &[CurrencyId], _supply_amount: Balance) -> Option<Balance> {
+		unimplemented!()
+	}
+
+	fn get_swap_supply_amount(_path: &[CurrencyId], _target_amount: Balance) -> Option<Balance> {
+		unimplemented!()
+	}
+
+	fn swap_with_exact_supply(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_supply_amount: Balance,
+		_min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn add_liquidity(
+		who: &AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		max_amount_a: Balance,
+		max_amount_b: Balance,
+		_min_share_increment: Balance,
+		_stake_increment_share: bool,
+	) -> DispatchResult {
+		let (pool_a, pool_b) =
+			Self::pool(currency_id_a, currency_id_b).ok_or(DispatchError::Other("InvalidCurrencyId"))?;
+		let (amount_a, amount_b) = if max_amount_a * pool_b / pool_a <= max_amount_b {
+			(max_amount_a, max_amount_a * pool_b / pool_a)
+		} else {
+			(max_amount_b * pool_a / pool_b, max_amount_b)
+		};
+		Tokens::transfer(currency_id_a, who, &DEX_ACCOUNT, amount_a)?;
+		Tokens::transfer(currency_id_b, who, &DEX_ACCOUNT, amount_b)?;
+		Self::set_pool(currency_id_a, pool_a + amount_a, pool_b + amount_b);
+		Ok(())
+	}
+
+	fn remove_liquidity(
+		who: &AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		remove_share: Balance,
+		_min_withdrawn_a: Balance,
+		_min_withdrawn_b: Balance,
+		_by_unstake: bool,
+	) -> DispatchResult {
+		Self::pool(currency_id_a, currency_id_b).ok_or(DispatchError::Other("InvalidCurrencyId"))?;
+		let (pool_dot, pool_ausd) = LIQUIDITY_POOL.with(|v| *v.borrow());
+		let (amount_dot, amount_ausd) = (remove_share, remove_share * pool_ausd / pool_dot);
+		Tokens::transfer(DOT, &DEX_ACCOUNT, who, amount_dot)?;
+		Tokens::transfer(AUSD, &DEX_ACCOUNT, who, amount_ausd)?;
+		set_liquidity_pool(pool_dot - amount_dot, pool_ausd - amount_ausd);
+		Ok(())
+	}
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+		match (base, quote) {
+			(BTC, AUSD) => Some(Price::one()),
+			(DOT, AUSD) => Some(Price::saturating_from_integer(2)),
+			(AUSD, AUSD) => Some(Price::one()),
+			_ => None,
+		}
+	}
+
+	fn get_price(_currency_id: CurrencyId) -> Option<Price> {
+		unimplemented!()
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = 1;
+}
+
+parameter_types! {
+	pub const GetStableCurrencyId: CurrencyId = AUSD;
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(1, 10);
+	pub const InsuranceFundAccount: AccountId = INSURANCE_FUND;
+	pub const TreasuryAccount: AccountId = TREASURY;
+	pub const BlocksPerDay: BlockNumber = 10;
+	pub const TradingPathLimit: u32 = 3;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = MockCDPTreasury;
+	type PriceSource = MockPriceSource;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type InsuranceFundAccount = InsuranceFundAccount;
+	type Currency = Tokens;
+	type DEX = MockDEX;
+	type TreasuryAccount = TreasuryAccount;
+	type BlocksPerDay = BlocksPerDay;
+	type TradingPathLimit = TradingPathLimit;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		OperationsModule: operations::{Pallet, Storage, Call, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(CDP_TREASURY, BTC, 1000),
+				(CDP_TREASURY, DOT, 1000),
+				(TREASURY, DOT, 1000),
+				(TREASURY, AUSD, 1000),
+				(DEX_ACCOUNT, DOT, 1000),
+				(DEX_ACCOUNT, AUSD, 2000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		set_debit_pool(0);
+		set_liquidity_pool(1000, 2000);
+
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
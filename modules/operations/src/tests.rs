@@ -0,0 +1,213 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the operations module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::*;
+use orml_traits::MultiCurrency;
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn set_operator_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OperationsModule::set_operator(Origin::signed(BOB), Some(BOB)),
+			BadOrigin
+		);
+
+		assert_ok!(OperationsModule::set_operator(Origin::signed(ALICE), Some(BOB)));
+		System::assert_last_event(Event::OperationsModule(crate::Event::OperatorUpdated(Some(BOB))));
+		assert_eq!(OperationsModule::operator(), Some(BOB));
+
+		assert_ok!(OperationsModule::set_operator(Origin::signed(ALICE), None));
+		System::assert_last_event(Event::OperationsModule(crate::Event::OperatorUpdated(None)));
+		assert_eq!(OperationsModule::operator(), None);
+	});
+}
+
+#[test]
+fn set_daily_value_limit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OperationsModule::set_daily_value_limit(Origin::signed(BOB), 1000),
+			BadOrigin
+		);
+
+		assert_ok!(OperationsModule::set_daily_value_limit(Origin::signed(ALICE), 1000));
+		System::assert_last_event(Event::OperationsModule(crate::Event::DailyValueLimitUpdated(1000)));
+		assert_eq!(OperationsModule::daily_value_limit(), 1000);
+	});
+}
+
+#[test]
+fn swap_treasury_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), BTC, 100),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(OperationsModule::set_operator(Origin::signed(ALICE), Some(BOB)));
+		assert_ok!(OperationsModule::set_daily_value_limit(Origin::signed(ALICE), 300));
+		assert_noop!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), ACA, 100),
+			Error::<Runtime>::InvalidFeedPrice
+		);
+		assert_noop!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), BTC, 400),
+			Error::<Runtime>::ExceedDailyValueLimit
+		);
+		// the swap price is below the band of the oracle price
+		assert_noop!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), DOT, 100),
+			DispatchError::Other("InsufficientTargetAmount")
+		);
+
+		// the swap is weighed by the length of the swap path
+		assert_eq!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), BTC, 200)
+				.unwrap()
+				.actual_weight,
+			Some(<() as WeightInfo>::swap_treasury_collateral() + 20_000)
+		);
+		System::assert_last_event(Event::OperationsModule(crate::Event::TreasuryCollateralSwapped(
+			BOB, BTC, 200, 180,
+		)));
+		assert_eq!(Tokens::free_balance(BTC, &CDP_TREASURY), 800);
+		assert_eq!(Tokens::free_balance(AUSD, &CDP_TREASURY), 180);
+		assert_eq!(OperationsModule::daily_value_used(), (0, 200));
+
+		assert_noop!(
+			OperationsModule::swap_treasury_collateral(Origin::signed(BOB), BTC, 101),
+			Error::<Runtime>::ExceedDailyValueLimit
+		);
+
+		// the daily value used is reset in the next day
+		System::set_block_number(10);
+		assert_ok!(OperationsModule::swap_treasury_collateral(
+			Origin::signed(BOB),
+			BTC,
+			300
+		));
+		assert_eq!(OperationsModule::daily_value_used(), (1, 300));
+	});
+}
+
+#[test]
+fn top_up_insurance_fund_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OperationsModule::top_up_insurance_fund(Origin::signed(BOB), 100),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(OperationsModule::set_operator(Origin::signed(ALICE), Some(BOB)));
+		assert_ok!(OperationsModule::set_daily_value_limit(Origin::signed(ALICE), 300));
+		assert_ok!(Tokens::deposit(AUSD, &CDP_TREASURY, 600));
+		set_debit_pool(100);
+		assert_noop!(
+			OperationsModule::top_up_insurance_fund(Origin::signed(BOB), 501),
+			Error::<Runtime>::InsufficientSurplus
+		);
+		assert_noop!(
+			OperationsModule::top_up_insurance_fund(Origin::signed(BOB), 400),
+			Error::<Runtime>::ExceedDailyValueLimit
+		);
+
+		// the surplus is transferred to the insurance fund, no debit is issued
+		assert_ok!(OperationsModule::top_up_insurance_fund(Origin::signed(BOB), 300));
+		System::assert_last_event(Event::OperationsModule(crate::Event::InsuranceFundToppedUp(BOB, 300)));
+		assert_eq!(Tokens::free_balance(AUSD, &INSURANCE_FUND), 300);
+		assert_eq!(Tokens::free_balance(AUSD, &CDP_TREASURY), 300);
+		assert_eq!(Tokens::total_issuance(AUSD), 3600);
+		assert_eq!(MockCDPTreasury::get_debit_pool(), 100);
+		assert_eq!(OperationsModule::daily_value_used(), (0, 300));
+
+		System::set_block_number(10);
+		assert_noop!(
+			OperationsModule::top_up_insurance_fund(Origin::signed(BOB), 201),
+			Error::<Runtime>::InsufficientSurplus
+		);
+	});
+}
+
+#[test]
+fn roll_treasury_dex_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			OperationsModule::roll_treasury_dex_position(Origin::signed(BOB), DOT, AUSD, DexPositionRoll::Remove(50)),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(OperationsModule::set_operator(Origin::signed(ALICE), Some(BOB)));
+		assert_ok!(OperationsModule::set_daily_value_limit(Origin::signed(ALICE), 300));
+		assert_noop!(
+			OperationsModule::roll_treasury_dex_position(Origin::signed(BOB), AUSD, AUSD, DexPositionRoll::Remove(50)),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_noop!(
+			OperationsModule::roll_treasury_dex_position(Origin::signed(BOB), ACA, AUSD, DexPositionRoll::Remove(50)),
+			Error::<Runtime>::InvalidFeedPrice
+		);
+
+		// the pool price of DOT is 2.5, out of the band of the oracle price 2
+		set_liquidity_pool(800, 2000);
+		assert_noop!(
+			OperationsModule::roll_treasury_dex_position(Origin::signed(BOB), DOT, AUSD, DexPositionRoll::Remove(50)),
+			Error::<Runtime>::PoolPriceOutOfBand
+		);
+
+		set_liquidity_pool(1000, 2000);
+		assert_ok!(OperationsModule::roll_treasury_dex_position(
+			Origin::signed(BOB),
+			DOT,
+			AUSD,
+			DexPositionRoll::Add(100, 100)
+		));
+		System::assert_last_event(Event::OperationsModule(crate::Event::TreasuryLiquidityAdded(
+			BOB, DOT, 50, AUSD, 100,
+		)));
+		assert_eq!(Tokens::free_balance(DOT, &TREASURY), 950);
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 900);
+		assert_eq!(OperationsModule::daily_value_used(), (0, 200));
+
+		assert_noop!(
+			OperationsModule::roll_treasury_dex_position(Origin::signed(BOB), AUSD, DOT, DexPositionRoll::Remove(50)),
+			Error::<Runtime>::ExceedDailyValueLimit
+		);
+
+		// the daily value used is reset in the next day
+		System::set_block_number(10);
+		assert_ok!(OperationsModule::roll_treasury_dex_position(
+			Origin::signed(BOB),
+			AUSD,
+			DOT,
+			DexPositionRoll::Remove(50)
+		));
+		System::assert_last_event(Event::OperationsModule(crate::Event::TreasuryLiquidityRemoved(
+			BOB, AUSD, 100, DOT, 50,
+		)));
+		assert_eq!(Tokens::free_balance(DOT, &TREASURY), 1000);
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 1000);
+		assert_eq!(OperationsModule::daily_value_used(), (1, 200));
+	});
+}
@@ -0,0 +1,273 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Peg Monitor Module
+//!
+//! ## Overview
+//!
+//! Peg monitor tracks the average price of the stable currency in DEX against
+//! a reference currency pegged to the same target, and adjusts the swap fees
+//! of the peg stability module (PSM) within the bounds set by governance.
+//! Minting stable currency gets cheap when it's above the peg, and redeeming
+//! gets cheap when it's below the peg.
+//!
+//! The average price is sampled from the DEX spot price at the beginning of
+//! each block, and the fees are adjusted once every `TwapPeriod` blocks.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{CheckedDiv, CheckedMul, One, Saturating, Zero},
+	FixedPointNumber, RuntimeDebug,
+};
+use support::{DEXManager, Erc20InfoMapping, PSMFeeProvider, Price, Rate};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// Parameters of the PSM fee adjustment.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct FeeParams {
+	/// The fee of the cheap side, and the lower bound of the fees.
+	pub min_fee: Rate,
+	/// The upper bound of the fees.
+	pub max_fee: Rate,
+	/// The fee increment of the expensive side for each unit of price
+	/// deviation from the peg.
+	pub adjustment_factor: Rate,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// DEX to read the price of stable currency from.
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The stable currency id of which the peg is monitored.
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// The currency id pegged to the same target as the stable currency,
+		/// used as the quote currency of the price.
+		#[pallet::constant]
+		type ReferenceCurrencyId: Get<CurrencyId>;
+
+		/// The period in blocks to average the price over and adjust the fees,
+		/// must be non-zero.
+		#[pallet::constant]
+		type TwapPeriod: Get<Self::BlockNumber>;
+
+		/// Mapping to get the decimals of currencies.
+		type Erc20InfoMapping: Erc20InfoMapping;
+
+		/// The origin which may update the fee params.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The fee params are invalid
+		InvalidFeeParams,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The fee params updated. \[min_fee, max_fee, adjustment_factor\]
+		FeeParamsUpdated(Rate, Rate, Rate),
+		/// The PSM fees adjusted by the average price. \[average_price,
+		/// mint_fee, redeem_fee\]
+		PSMFeesAdjusted(Price, Rate, Rate),
+	}
+
+	/// The params of the PSM fee adjustment.
+	///
+	/// FeeAdjustmentParams: FeeParams
+	#[pallet::storage]
+	#[pallet::getter(fn fee_adjustment_params)]
+	pub type FeeAdjustmentParams<T: Config> = StorageValue<_, FeeParams, ValueQuery>;
+
+	/// The sum of the sampled prices and the count of samples in the current
+	/// period.
+	///
+	/// PriceAccumulator: (Price, u32)
+	#[pallet::storage]
+	#[pallet::getter(fn price_accumulator)]
+	pub type PriceAccumulator<T: Config> = StorageValue<_, (Price, u32), ValueQuery>;
+
+	/// The average price of the stable currency in the last period.
+	///
+	/// AveragePrice: Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn average_price)]
+	pub type AveragePrice<T: Config> = StorageValue<_, Price, OptionQuery>;
+
+	/// The current fee rate of minting stable currency in PSM.
+	///
+	/// MintFee: Rate
+	#[pallet::storage]
+	#[pallet::getter(fn mint_fee)]
+	pub type MintFee<T: Config> = StorageValue<_, Rate, ValueQuery>;
+
+	/// The current fee rate of redeeming stable currency in PSM.
+	///
+	/// RedeemFee: Rate
+	#[pallet::storage]
+	#[pallet::getter(fn redeem_fee)]
+	pub type RedeemFee<T: Config> = StorageValue<_, Rate, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Sample the DEX price, and adjust the PSM fees at the end of a period.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if let Some(price) = Self::get_dex_price() {
+				PriceAccumulator::<T>::mutate(|(sum, count)| {
+					*sum = sum.saturating_add(price);
+					*count = count.saturating_add(1);
+				});
+			}
+
+			if (now % T::TwapPeriod::get()).is_zero() {
+				Self::adjust_fees();
+				T::WeightInfo::on_initialize_with_adjustment()
+			} else {
+				T::WeightInfo::on_initialize()
+			}
+		}
+
+		fn integrity_test() {
+			assert!(!T::TwapPeriod::get().is_zero());
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Update the params of the PSM fee adjustment, the current fees are
+		/// clamped to the new bounds.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `min_fee`: the fee of the cheap side, and the lower bound of fees.
+		/// - `max_fee`: the upper bound of fees.
+		/// - `adjustment_factor`: the fee increment for each unit of price deviation.
+		#[pallet::weight((T::WeightInfo::set_fee_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_fee_params(
+			origin: OriginFor<T>,
+			min_fee: Rate,
+			max_fee: Rate,
+			adjustment_factor: Rate,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				min_fee <= max_fee && max_fee <= Rate::one(),
+				Error::<T>::InvalidFeeParams
+			);
+
+			FeeAdjustmentParams::<T>::put(FeeParams {
+				min_fee,
+				max_fee,
+				adjustment_factor,
+			});
+			MintFee::<T>::mutate(|fee| *fee = (*fee).max(min_fee).min(max_fee));
+			RedeemFee::<T>::mutate(|fee| *fee = (*fee).max(min_fee).min(max_fee));
+			Self::deposit_event(Event::FeeParamsUpdated(min_fee, max_fee, adjustment_factor));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The spot price of stable currency in DEX, quoted in reference currency.
+	fn get_dex_price() -> Option<Price> {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let reference_currency_id = T::ReferenceCurrencyId::get();
+		let (stable_pool, reference_pool) = T::DEX::get_liquidity_pool(stable_currency_id, reference_currency_id);
+		if stable_pool.is_zero() || reference_pool.is_zero() {
+			return None;
+		}
+
+		let stable_unit = 10u128.checked_pow(T::Erc20InfoMapping::decimals(stable_currency_id)?.into())?;
+		let reference_unit = 10u128.checked_pow(T::Erc20InfoMapping::decimals(reference_currency_id)?.into())?;
+
+		Price::checked_from_rational(reference_pool, stable_pool)?
+			.checked_mul(&Price::checked_from_rational(stable_unit, reference_unit)?)
+	}
+
+	/// Average the prices sampled in the period, and adjust the PSM fees by it.
+	fn adjust_fees() {
+		let (sum, count) = PriceAccumulator::<T>::take();
+		if let Some(average_price) = sum.checked_div(&Price::saturating_from_integer(count)) {
+			let (mint_fee, redeem_fee) = Self::calculate_fees(average_price);
+			AveragePrice::<T>::put(average_price);
+			MintFee::<T>::put(mint_fee);
+			RedeemFee::<T>::put(redeem_fee);
+			Self::deposit_event(Event::PSMFeesAdjusted(average_price, mint_fee, redeem_fee));
+		}
+	}
+
+	/// Calculate the (mint_fee, redeem_fee) by the average price.
+	pub fn calculate_fees(average_price: Price) -> (Rate, Rate) {
+		let params = Self::fee_adjustment_params();
+		let peg = Price::one();
+		let adjusted_fee = |deviation: Price| {
+			params
+				.min_fee
+				.saturating_add(deviation.saturating_mul(params.adjustment_factor))
+				.min(params.max_fee)
+		};
+
+		if average_price > peg {
+			// above the peg, minting is cheap to increase the supply
+			(params.min_fee, adjusted_fee(average_price.saturating_sub(peg)))
+		} else if average_price < peg {
+			// below the peg, redeeming is cheap to decrease the supply
+			(adjusted_fee(peg.saturating_sub(average_price)), params.min_fee)
+		} else {
+			(params.min_fee, params.min_fee)
+		}
+	}
+}
+
+impl<T: Config> PSMFeeProvider for Pallet<T> {
+	fn mint_fee() -> Rate {
+		Self::mint_fee()
+	}
+
+	fn redeem_fee() -> Rate {
+		Self::redeem_fee()
+	}
+}
@@ -0,0 +1,179 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Unit tests for the peg monitor module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+const AUSD_UNIT: Balance = 1_000_000_000_000;
+const CASH_UNIT: Balance = 100_000_000;
+
+fn set_default_fee_params() {
+	assert_ok!(PegMonitorModule::set_fee_params(
+		Origin::signed(ALICE),
+		Rate::saturating_from_rational(1, 1000),
+		Rate::saturating_from_rational(5, 100),
+		Rate::one(),
+	));
+}
+
+#[test]
+fn set_fee_params_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PegMonitorModule::set_fee_params(Origin::signed(BOB), Rate::zero(), Rate::zero(), Rate::zero()),
+			BadOrigin
+		);
+		assert_noop!(
+			PegMonitorModule::set_fee_params(
+				Origin::signed(ALICE),
+				Rate::saturating_from_rational(2, 100),
+				Rate::saturating_from_rational(1, 100),
+				Rate::one()
+			),
+			Error::<Runtime>::InvalidFeeParams
+		);
+		assert_noop!(
+			PegMonitorModule::set_fee_params(
+				Origin::signed(ALICE),
+				Rate::zero(),
+				Rate::saturating_from_integer(2),
+				Rate::one()
+			),
+			Error::<Runtime>::InvalidFeeParams
+		);
+
+		set_default_fee_params();
+		System::assert_last_event(Event::PegMonitorModule(crate::Event::FeeParamsUpdated(
+			Rate::saturating_from_rational(1, 1000),
+			Rate::saturating_from_rational(5, 100),
+			Rate::one(),
+		)));
+		assert_eq!(
+			PegMonitorModule::fee_adjustment_params(),
+			FeeParams {
+				min_fee: Rate::saturating_from_rational(1, 1000),
+				max_fee: Rate::saturating_from_rational(5, 100),
+				adjustment_factor: Rate::one(),
+			}
+		);
+
+		// current fees are clamped to the new bounds
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(1, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(1, 1000));
+	});
+}
+
+#[test]
+fn sample_dex_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		PegMonitorModule::on_initialize(1);
+		assert_eq!(PegMonitorModule::price_accumulator(), (Price::zero(), 0));
+
+		set_liquidity_pool(1000 * AUSD_UNIT, 1020 * CASH_UNIT);
+		PegMonitorModule::on_initialize(2);
+		assert_eq!(
+			PegMonitorModule::price_accumulator(),
+			(Price::saturating_from_rational(102, 100), 1)
+		);
+
+		set_liquidity_pool(1000 * AUSD_UNIT, 1040 * CASH_UNIT);
+		PegMonitorModule::on_initialize(3);
+		assert_eq!(
+			PegMonitorModule::price_accumulator(),
+			(Price::saturating_from_rational(206, 100), 2)
+		);
+	});
+}
+
+#[test]
+fn adjust_fees_above_peg_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_default_fee_params();
+		set_liquidity_pool(1000 * AUSD_UNIT, 1010 * CASH_UNIT);
+		PegMonitorModule::on_initialize(9);
+		set_liquidity_pool(1000 * AUSD_UNIT, 1030 * CASH_UNIT);
+		PegMonitorModule::on_initialize(10);
+
+		assert_eq!(PegMonitorModule::price_accumulator(), (Price::zero(), 0));
+		assert_eq!(
+			PegMonitorModule::average_price(),
+			Some(Price::saturating_from_rational(102, 100))
+		);
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(1, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(21, 1000));
+		System::assert_last_event(Event::PegMonitorModule(crate::Event::PSMFeesAdjusted(
+			Price::saturating_from_rational(102, 100),
+			Rate::saturating_from_rational(1, 1000),
+			Rate::saturating_from_rational(21, 1000),
+		)));
+
+		// fee is capped by max_fee
+		set_liquidity_pool(1000 * AUSD_UNIT, 1200 * CASH_UNIT);
+		PegMonitorModule::on_initialize(20);
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(1, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(5, 100));
+	});
+}
+
+#[test]
+fn adjust_fees_below_peg_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_default_fee_params();
+		set_liquidity_pool(1000 * AUSD_UNIT, 970 * CASH_UNIT);
+		PegMonitorModule::on_initialize(10);
+
+		assert_eq!(
+			PegMonitorModule::average_price(),
+			Some(Price::saturating_from_rational(97, 100))
+		);
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(31, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(1, 1000));
+	});
+}
+
+#[test]
+fn adjust_fees_at_peg_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_default_fee_params();
+		set_liquidity_pool(1000 * AUSD_UNIT, 970 * CASH_UNIT);
+		PegMonitorModule::on_initialize(10);
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(31, 1000));
+
+		set_liquidity_pool(1000 * AUSD_UNIT, 1000 * CASH_UNIT);
+		PegMonitorModule::on_initialize(20);
+		assert_eq!(PegMonitorModule::average_price(), Some(Price::one()));
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(1, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(1, 1000));
+	});
+}
+
+#[test]
+fn adjust_fees_without_samples_keep_fees() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_default_fee_params();
+		PegMonitorModule::on_initialize(10);
+		assert_eq!(PegMonitorModule::average_price(), None);
+		assert_eq!(PegMonitorModule::mint_fee(), Rate::saturating_from_rational(1, 1000));
+		assert_eq!(PegMonitorModule::redeem_fee(), Rate::saturating_from_rational(1, 1000));
+	});
+}
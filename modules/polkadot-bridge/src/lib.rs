@@ -21,14 +21,14 @@
 
 use frame_support::{log, pallet_prelude::*, traits::Get, transactional, BoundedVec};
 use frame_system::pallet_prelude::*;
-use orml_traits::BasicCurrency;
+use orml_traits::{BasicCurrency, Happened};
 use primitives::{Balance, EraIndex};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{CheckedSub, MaybeDisplay, MaybeSerializeDeserialize, Member, StaticLookup, Zero},
 	ArithmeticError, DispatchResult, FixedPointNumber, RuntimeDebug,
 };
-use sp_std::{convert::TryInto, fmt::Debug, prelude::*};
+use sp_std::{convert::TryInto, fmt::Debug, marker::PhantomData, prelude::*};
 use support::{
 	OnNewEra, PolkadotBridge, PolkadotBridgeCall, PolkadotBridgeState, PolkadotBridgeType, PolkadotStakingLedger,
 	PolkadotUnlockChunk, Rate,
@@ -459,3 +459,11 @@ impl<T: Config> PolkadotBridgeState<Balance, EraIndex> for Pallet<T> {
 }
 
 impl<T: Config> PolkadotBridge<T::AccountId, T::BlockNumber, Balance, EraIndex> for Pallet<T> {}
+
+/// Start a new era immediately when happened.
+pub struct ForceNewEra<T>(PhantomData<T>);
+impl<T: Config> Happened<T::BlockNumber> for ForceNewEra<T> {
+	fn happened(now: &T::BlockNumber) {
+		Pallet::<T>::new_era(*now);
+	}
+}
@@ -0,0 +1,544 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Position Migration Module
+//!
+//! ## Overview
+//!
+//! Position migration module moves a CDP position to a sibling parachain
+//! running compatible honzon pallets (e.g. Karura <-> Acala) by XCM.
+//!
+//! The migration is a three-message handshake, every message is a `Transact`
+//! of this module's call executed by the sibling parachain origin:
+//! 1. `export_position` closes the position on the source chain: the debit is
+//!    repaid, the collateral is escrowed by this module and `import_position`
+//!    is sent to the destination.
+//! 2. `import_position` records the pending import on the destination and
+//!    sends `acknowledge_export` back.
+//! 3. `acknowledge_export` releases the escrowed collateral to the sovereign
+//!    account of the destination as reserve, and sends `finalize_import`
+//!    together with the reserve transfer of the collateral. The destination
+//!    deposits the reserve-backed collateral to the import account of the
+//!    source, `finalize_import` pays it out to the beneficiary from there and
+//!    re-mints the debit only then. If the position can't be reopened, e.g. it
+//!    would be unsafe or exceed the debit cap, the beneficiary just keeps the
+//!    collateral.
+//!
+//! Only the collateral reserved on this parachain can be migrated. Every
+//! message buys its execution with `TransactFee` withdrawn from the sovereign
+//! account of this parachain on the sibling.
+//!
+//! The export can be cancelled by its owner after it expires without
+//! acknowledgement, the escrowed collateral is returned then. The module must
+//! share the same pallet index and pallet id on both chains, and the sibling
+//! parachains are enabled by governance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use cumulus_primitives_core::ParaId;
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Amount, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AccountIdConversion, Convert, One, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, prelude::*};
+use support::EmergencyShutdown;
+use xcm::latest::prelude::*;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type MigrationId = u64;
+
+/// The position exported to a sibling parachain, waiting for acknowledgement.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct ExportRecord<AccountId, BlockNumber> {
+	/// The owner of the closed position.
+	pub who: AccountId,
+	/// The collateral type.
+	pub currency_id: CurrencyId,
+	/// The escrowed collateral amount.
+	pub collateral: Balance,
+	/// The repaid debit value to re-mint on the destination.
+	pub debit_value: Balance,
+	/// The destination parachain.
+	pub dest: ParaId,
+	/// The owner of the reopened position on the destination.
+	pub beneficiary: AccountId,
+	/// The export can be cancelled after this block.
+	pub expire_at: BlockNumber,
+}
+
+/// The position imported from a sibling parachain, waiting for finalization.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct ImportRecord<AccountId> {
+	/// The owner of the reopened position.
+	pub beneficiary: AccountId,
+	/// The collateral type.
+	pub currency_id: CurrencyId,
+	/// The collateral amount.
+	pub collateral: Balance,
+	/// The debit value to re-mint.
+	pub debit_value: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + cdp_engine::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to escrow and deposit the collateral
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// The overarching call type, the calls of this module are encoded by
+		/// it in the XCM sent to the sibling parachain.
+		type Call: From<Call<Self>> + Encode;
+
+		/// The XCM sender to the sibling parachains
+		type XcmSender: SendXcm;
+
+		/// The origin of the XCM dispatched by a sibling parachain
+		type SiblingOrigin: EnsureOrigin<Self::Origin, Success = ParaId>;
+
+		/// Convert the sibling parachain to its sovereign account
+		type SiblingSovereignAccount: Convert<ParaId, Self::AccountId>;
+
+		/// The parachain id of this chain
+		type SelfParaId: Get<ParaId>;
+
+		/// Convert the currency id to its location
+		type CurrencyIdConvert: Convert<CurrencyId, Option<MultiLocation>>;
+
+		/// Convert the account to its location
+		type AccountIdToMultiLocation: Convert<Self::AccountId, MultiLocation>;
+
+		/// The fee withdrawn from the sovereign account of this parachain on
+		/// the sibling to buy the execution of the XCM
+		#[pallet::constant]
+		type TransactFee: Get<MultiAsset>;
+
+		/// The blocks to wait for the acknowledgement before the export can be
+		/// cancelled
+		#[pallet::constant]
+		type AcknowledgementTimeout: Get<Self::BlockNumber>;
+
+		/// The weight limit of the `Transact` executed on the sibling
+		#[pallet::constant]
+		type TransactWeight: Get<Weight>;
+
+		/// The position migration module id, keep the escrowed collateral.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The destination is not a sibling parachain running compatible
+		/// honzon pallets
+		InvalidDestination,
+		/// The position doesn't exist
+		NoPosition,
+		/// The migration record doesn't exist
+		MigrationNotFound,
+		/// The caller has no permission to the migration
+		NoPermission,
+		/// The export hasn't expired
+		NotExpired,
+		/// The collateral type is not supported
+		InvalidCollateralType,
+		/// The collateral is not reserved on this parachain
+		NotReserveCurrency,
+		/// Failed to send XCM to the sibling parachain
+		XcmFailed,
+		/// The system has been shutdown
+		AlreadyShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The position is closed and exported. \[migration_id, who,
+		/// collateral_type, collateral_amount, debit_value, dest\]
+		PositionExported(MigrationId, T::AccountId, CurrencyId, Balance, Balance, ParaId),
+		/// The export is acknowledged by the destination and the collateral
+		/// is released. \[migration_id, dest\]
+		ExportAcknowledged(MigrationId, ParaId),
+		/// The expired export is cancelled and the collateral is returned.
+		/// \[migration_id, who\]
+		ExportCancelled(MigrationId, T::AccountId),
+		/// The position import is requested by the source. \[source,
+		/// migration_id, beneficiary\]
+		ImportRequested(ParaId, MigrationId, T::AccountId),
+		/// The position is imported. \[source, migration_id, beneficiary,
+		/// reopened\]
+		PositionImported(ParaId, MigrationId, T::AccountId, bool),
+		/// The sibling parachain is enabled or disabled. \[para_id, enabled\]
+		SiblingParachainUpdated(ParaId, bool),
+	}
+
+	/// The next migration id.
+	///
+	/// NextMigrationId: MigrationId
+	#[pallet::storage]
+	#[pallet::getter(fn next_migration_id)]
+	pub type NextMigrationId<T: Config> = StorageValue<_, MigrationId, ValueQuery>;
+
+	/// The exported positions waiting for acknowledgement.
+	///
+	/// Exports: map MigrationId => Option<ExportRecord>
+	#[pallet::storage]
+	#[pallet::getter(fn exports)]
+	pub type Exports<T: Config> =
+		StorageMap<_, Twox64Concat, MigrationId, ExportRecord<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// The imported positions waiting for finalization.
+	///
+	/// Imports: double_map ParaId, MigrationId => Option<ImportRecord>
+	#[pallet::storage]
+	#[pallet::getter(fn imports)]
+	pub type Imports<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, ParaId, Twox64Concat, MigrationId, ImportRecord<T::AccountId>, OptionQuery>;
+
+	/// The sibling parachains running compatible honzon pallets.
+	///
+	/// SiblingParachains: map ParaId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn sibling_parachains)]
+	pub type SiblingParachains<T: Config> = StorageMap<_, Twox64Concat, ParaId, bool, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Close the position of the caller and export it to a sibling
+		/// parachain. The debit is repaid by the caller and re-minted on the
+		/// destination after the acknowledgement.
+		///
+		/// - `currency_id`: collateral currency id.
+		/// - `dest`: the destination parachain.
+		/// - `beneficiary`: the owner of the reopened position.
+		#[pallet::weight(<T as Config>::WeightInfo::export_position())]
+		#[transactional]
+		pub fn export_position(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			dest: ParaId,
+			beneficiary: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!<T as cdp_engine::Config>::EmergencyShutdown::is_shutdown(),
+				Error::<T>::AlreadyShutdown
+			);
+			ensure!(Self::sibling_parachains(dest), Error::<T>::InvalidDestination);
+			Self::reserve_asset(currency_id, Zero::zero())?;
+
+			let loans::Position { collateral, debit } = <loans::Pallet<T>>::positions(currency_id, &who);
+			ensure!(!collateral.is_zero(), Error::<T>::NoPosition);
+			let debit_value = <cdp_engine::Pallet<T>>::get_debit_value(currency_id, debit);
+
+			let collateral_adjustment: Amount = collateral.try_into().map_err(|_| ArithmeticError::Overflow)?;
+			let debit_adjustment: Amount = debit.try_into().map_err(|_| ArithmeticError::Overflow)?;
+			<cdp_engine::Pallet<T>>::adjust_position(
+				&who,
+				currency_id,
+				collateral_adjustment.saturating_neg(),
+				debit_adjustment.saturating_neg(),
+			)?;
+			<T as Config>::Currency::transfer(currency_id, &who, &Self::account_id(), collateral)?;
+
+			let migration_id = Self::next_migration_id();
+			NextMigrationId::<T>::put(migration_id.checked_add(One::one()).ok_or(ArithmeticError::Overflow)?);
+			Exports::<T>::insert(
+				migration_id,
+				ExportRecord {
+					who: who.clone(),
+					currency_id,
+					collateral,
+					debit_value,
+					dest,
+					beneficiary: beneficiary.clone(),
+					expire_at: <frame_system::Pallet<T>>::block_number()
+						.saturating_add(T::AcknowledgementTimeout::get()),
+				},
+			);
+
+			Self::send_to_sibling(
+				dest,
+				Call::<T>::import_position {
+					migration_id,
+					beneficiary,
+					currency_id,
+					collateral,
+					debit_value,
+				},
+			)?;
+
+			Self::deposit_event(Event::PositionExported(
+				migration_id,
+				who,
+				currency_id,
+				collateral,
+				debit_value,
+				dest,
+			));
+			Ok(())
+		}
+
+		/// Record the position exported by the source sibling parachain and
+		/// acknowledge it.
+		///
+		/// The dispatch origin of this call must be the sibling parachain.
+		#[pallet::weight(<T as Config>::WeightInfo::import_position())]
+		#[transactional]
+		pub fn import_position(
+			origin: OriginFor<T>,
+			migration_id: MigrationId,
+			beneficiary: T::AccountId,
+			currency_id: CurrencyId,
+			collateral: Balance,
+			debit_value: Balance,
+		) -> DispatchResult {
+			let source = T::SiblingOrigin::ensure_origin(origin)?;
+			ensure!(Self::sibling_parachains(source), Error::<T>::InvalidDestination);
+			ensure!(
+				<T as cdp_engine::Config>::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType
+			);
+
+			Imports::<T>::insert(
+				source,
+				migration_id,
+				ImportRecord {
+					beneficiary: beneficiary.clone(),
+					currency_id,
+					collateral,
+					debit_value,
+				},
+			);
+			Self::send_to_sibling(source, Call::<T>::acknowledge_export { migration_id })?;
+
+			Self::deposit_event(Event::ImportRequested(source, migration_id, beneficiary));
+			Ok(())
+		}
+
+		/// Release the escrowed collateral of the export acknowledged by the
+		/// destination and finalize the import on it.
+		///
+		/// The dispatch origin of this call must be the destination parachain.
+		#[pallet::weight(<T as Config>::WeightInfo::acknowledge_export())]
+		#[transactional]
+		pub fn acknowledge_export(origin: OriginFor<T>, migration_id: MigrationId) -> DispatchResult {
+			let dest = T::SiblingOrigin::ensure_origin(origin)?;
+			let record = Self::exports(migration_id).ok_or(Error::<T>::MigrationNotFound)?;
+			ensure!(record.dest == dest, Error::<T>::NoPermission);
+
+			<T as Config>::Currency::transfer(
+				record.currency_id,
+				&Self::account_id(),
+				&T::SiblingSovereignAccount::convert(dest),
+				record.collateral,
+			)?;
+			Exports::<T>::remove(migration_id);
+			Self::send_to_sibling_with_reserve(
+				dest,
+				Call::<T>::finalize_import { migration_id },
+				Some(Self::reserve_asset(record.currency_id, record.collateral)?),
+			)?;
+
+			Self::deposit_event(Event::ExportAcknowledged(migration_id, dest));
+			Ok(())
+		}
+
+		/// Pay out the collateral received by the reserve transfer to the
+		/// beneficiary and reopen the position with the re-minted debit.
+		///
+		/// The dispatch origin of this call must be the source parachain.
+		#[pallet::weight(<T as Config>::WeightInfo::finalize_import())]
+		#[transactional]
+		pub fn finalize_import(origin: OriginFor<T>, migration_id: MigrationId) -> DispatchResult {
+			let source = T::SiblingOrigin::ensure_origin(origin)?;
+			let record = Self::imports(source, migration_id).ok_or(Error::<T>::MigrationNotFound)?;
+			Imports::<T>::remove(source, migration_id);
+
+			// the collateral must have been deposited to the import account of the source by
+			// the reserve transfer
+			<T as Config>::Currency::transfer(
+				record.currency_id,
+				&Self::import_account_id(source),
+				&record.beneficiary,
+				record.collateral,
+			)?;
+			let reopened = Self::reopen_position(&record).is_ok();
+
+			Self::deposit_event(Event::PositionImported(
+				source,
+				migration_id,
+				record.beneficiary,
+				reopened,
+			));
+			Ok(())
+		}
+
+		/// Cancel the expired export which is not acknowledged, and return the
+		/// escrowed collateral.
+		///
+		/// - `migration_id`: the migration id of the export.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_export())]
+		#[transactional]
+		pub fn cancel_export(origin: OriginFor<T>, migration_id: MigrationId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let record = Self::exports(migration_id).ok_or(Error::<T>::MigrationNotFound)?;
+			ensure!(record.who == who, Error::<T>::NoPermission);
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() > record.expire_at,
+				Error::<T>::NotExpired
+			);
+
+			<T as Config>::Currency::transfer(record.currency_id, &Self::account_id(), &who, record.collateral)?;
+			Exports::<T>::remove(migration_id);
+
+			Self::deposit_event(Event::ExportCancelled(migration_id, who));
+			Ok(())
+		}
+
+		/// Enable or disable the position migration with the sibling
+		/// parachain.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `para_id`: the sibling parachain.
+		/// - `enabled`: whether it runs compatible honzon pallets.
+		#[pallet::weight((<T as Config>::WeightInfo::set_sibling_parachain(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_sibling_parachain(origin: OriginFor<T>, para_id: ParaId, enabled: bool) -> DispatchResult {
+			<T as cdp_engine::Config>::UpdateOrigin::ensure_origin(origin)?;
+			if enabled {
+				SiblingParachains::<T>::insert(para_id, true);
+			} else {
+				SiblingParachains::<T>::remove(para_id);
+			}
+			Self::deposit_event(Event::SiblingParachainUpdated(para_id, enabled));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	pub fn account_id() -> T::AccountId {
+		<T as Config>::PalletId::get().into_account()
+	}
+
+	/// The account on the destination keeping the collateral received from
+	/// the source by the reserve transfer.
+	pub fn import_account_id(source: ParaId) -> T::AccountId {
+		<T as Config>::PalletId::get().into_sub_account(source)
+	}
+
+	/// The collateral to transfer to the sibling, only the collateral reserved
+	/// on this parachain is accepted by the sibling.
+	fn reserve_asset(currency_id: CurrencyId, amount: Balance) -> Result<MultiAsset, DispatchError> {
+		let location = T::CurrencyIdConvert::convert(currency_id).ok_or(Error::<T>::NotReserveCurrency)?;
+		ensure!(
+			location.parents == 1 && location.first_interior() == Some(&Parachain(T::SelfParaId::get().into())),
+			Error::<T>::NotReserveCurrency
+		);
+		Ok((location, amount).into())
+	}
+
+	fn send_to_sibling(para_id: ParaId, call: Call<T>) -> DispatchResult {
+		Self::send_to_sibling_with_reserve(para_id, call, None)
+	}
+
+	fn send_to_sibling_with_reserve(para_id: ParaId, call: Call<T>, reserve: Option<MultiAsset>) -> DispatchResult {
+		let fee = T::TransactFee::get();
+		let self_location = MultiLocation::new(1, X1(Parachain(T::SelfParaId::get().into())));
+		let mut instructions = vec![
+			WithdrawAsset(fee.clone().into()),
+			BuyExecution {
+				fees: fee,
+				weight_limit: Unlimited,
+			},
+		];
+		if let Some(asset) = reserve {
+			instructions.push(ReserveAssetDeposited(asset.clone().into()));
+			instructions.push(DepositAsset {
+				assets: asset.into(),
+				max_assets: 1,
+				beneficiary: T::AccountIdToMultiLocation::convert(Self::import_account_id(T::SelfParaId::get())),
+			});
+		}
+		instructions.push(Transact {
+			origin_type: OriginKind::Native,
+			require_weight_at_most: T::TransactWeight::get(),
+			call: <T as Config>::Call::from(call).encode().into(),
+		});
+		// return the unused fee to the sovereign account of this parachain
+		instructions.push(DepositAsset {
+			assets: All.into(),
+			max_assets: u32::max_value(),
+			beneficiary: self_location,
+		});
+
+		T::XcmSender::send_xcm(MultiLocation::new(1, X1(Parachain(para_id.into()))), message).map_err(|e| {
+			log::warn!(
+				target: "position-migration",
+				"send_xcm to {:?} failed: {:?}",
+				para_id,
+				e,
+			);
+			Error::<T>::XcmFailed
+		})?;
+		Ok(())
+	}
+
+	#[transactional]
+	fn reopen_position(record: &ImportRecord<T::AccountId>) -> DispatchResult {
+		let debit = <cdp_engine::Pallet<T>>::get_debit_exchange_rate(record.currency_id)
+			.reciprocal()
+			.map(|rate| rate.saturating_mul_int(record.debit_value))
+			.unwrap_or_default();
+		let collateral_adjustment: Amount = record.collateral.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		let debit_adjustment: Amount = debit.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		<cdp_engine::Pallet<T>>::adjust_position(
+			&record.beneficiary,
+			record.currency_id,
+			collateral_adjustment,
+			debit_adjustment,
+		)
+	}
+}
@@ -0,0 +1,375 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the position migration module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Call as MockCall, Event, *};
+use orml_traits::{Change, MultiCurrency};
+use sp_runtime::traits::BadOrigin;
+use support::{Rate, Ratio};
+
+fn setup_collateral_params() {
+	assert_ok!(CDPEngineModule::set_collateral_params(
+		Origin::signed(1),
+		BTC,
+		Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+		Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+		Change::NewValue(10000),
+	));
+}
+
+fn sibling_location(id: AccountId) -> MultiLocation {
+	MultiLocation::new(1, X1(Parachain(id as u32)))
+}
+
+fn transact(call: crate::Call<Runtime>, reserve: Option<MultiAsset>) -> Xcm<()> {
+	let mut instructions = vec![
+		WithdrawAsset(TransactFee::get().into()),
+		BuyExecution {
+			fees: TransactFee::get(),
+			weight_limit: Unlimited,
+		},
+	];
+	if let Some(asset) = reserve {
+		instructions.push(ReserveAssetDeposited(asset.clone().into()));
+		instructions.push(DepositAsset {
+			assets: asset.into(),
+			max_assets: 1,
+			beneficiary: X1(GeneralIndex(PositionMigrationModule::import_account_id(
+				SelfParaId::get(),
+			)))
+			.into(),
+		});
+	}
+	instructions.push(Transact {
+		origin_type: OriginKind::Native,
+		require_weight_at_most: TransactWeight::get(),
+		call: MockCall::from(call).encode().into(),
+	});
+	instructions.push(DepositAsset {
+		assets: All.into(),
+		max_assets: u32::max_value(),
+		beneficiary: sibling_location(SELF_PARA_ID.into()),
+	});
+	Xcm(instructions)
+}
+
+fn export_alice_position() {
+	setup_collateral_params();
+	assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+	assert_ok!(PositionMigrationModule::export_position(
+		Origin::signed(ALICE),
+		BTC,
+		ParaId::from(SIBLING as u32),
+		BOB
+	));
+}
+
+#[test]
+fn export_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PositionMigrationModule::export_position(Origin::signed(ALICE), BTC, ParaId::from(3000), BOB),
+			Error::<Runtime>::InvalidDestination
+		);
+		assert_noop!(
+			PositionMigrationModule::export_position(Origin::signed(ALICE), DOT, ParaId::from(SIBLING as u32), BOB),
+			Error::<Runtime>::NotReserveCurrency
+		);
+		assert_noop!(
+			PositionMigrationModule::export_position(Origin::signed(ALICE), BTC, ParaId::from(SIBLING as u32), BOB),
+			Error::<Runtime>::NoPosition
+		);
+
+		export_alice_position();
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::PositionExported(
+			0,
+			ALICE,
+			BTC,
+			100,
+			50,
+			ParaId::from(SIBLING as u32),
+		)));
+		assert_eq!(LoansModule::positions(BTC, ALICE), loans::Position::default());
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 10000);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 9900);
+		assert_eq!(Tokens::free_balance(BTC, &PositionMigrationModule::account_id()), 100);
+		assert_eq!(PositionMigrationModule::next_migration_id(), 1);
+		assert_eq!(
+			PositionMigrationModule::exports(0),
+			Some(ExportRecord {
+				who: ALICE,
+				currency_id: BTC,
+				collateral: 100,
+				debit_value: 50,
+				dest: ParaId::from(SIBLING as u32),
+				beneficiary: BOB,
+				expire_at: 11,
+			})
+		);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				sibling_location(SIBLING),
+				transact(
+					crate::Call::import_position {
+						migration_id: 0,
+						beneficiary: BOB,
+						currency_id: BTC,
+						collateral: 100,
+						debit_value: 50,
+					},
+					None
+				)
+			)]
+		);
+
+		mock_shutdown();
+		assert_noop!(
+			PositionMigrationModule::export_position(Origin::signed(ALICE), BTC, ParaId::from(SIBLING as u32), BOB),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn import_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PositionMigrationModule::import_position(Origin::signed(ALICE), 0, BOB, BTC, 100, 50),
+			BadOrigin
+		);
+		assert_noop!(
+			PositionMigrationModule::import_position(Origin::signed(3000), 0, BOB, BTC, 100, 50),
+			Error::<Runtime>::InvalidDestination
+		);
+		assert_noop!(
+			PositionMigrationModule::import_position(Origin::signed(SIBLING), 0, BOB, ACA, 100, 50),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
+		assert_ok!(PositionMigrationModule::import_position(
+			Origin::signed(SIBLING),
+			0,
+			BOB,
+			BTC,
+			100,
+			50
+		));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::ImportRequested(
+			ParaId::from(SIBLING as u32),
+			0,
+			BOB,
+		)));
+		assert_eq!(
+			PositionMigrationModule::imports(ParaId::from(SIBLING as u32), 0),
+			Some(ImportRecord {
+				beneficiary: BOB,
+				currency_id: BTC,
+				collateral: 100,
+				debit_value: 50,
+			})
+		);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				sibling_location(SIBLING),
+				transact(crate::Call::acknowledge_export { migration_id: 0 }, None)
+			)]
+		);
+	});
+}
+
+#[test]
+fn acknowledge_export_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		export_alice_position();
+
+		assert_noop!(
+			PositionMigrationModule::acknowledge_export(Origin::signed(ALICE), 0),
+			BadOrigin
+		);
+		assert_noop!(
+			PositionMigrationModule::acknowledge_export(Origin::signed(SIBLING), 1),
+			Error::<Runtime>::MigrationNotFound
+		);
+		assert_noop!(
+			PositionMigrationModule::acknowledge_export(Origin::signed(OTHER_SIBLING), 0),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(PositionMigrationModule::acknowledge_export(Origin::signed(SIBLING), 0));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::ExportAcknowledged(
+			0,
+			ParaId::from(SIBLING as u32),
+		)));
+		assert_eq!(PositionMigrationModule::exports(0), None);
+		assert_eq!(Tokens::free_balance(BTC, &PositionMigrationModule::account_id()), 0);
+		assert_eq!(Tokens::free_balance(BTC, &SIBLING), 100);
+		assert_eq!(
+			sent_xcm().last(),
+			Some(&(
+				sibling_location(SIBLING),
+				transact(
+					crate::Call::finalize_import { migration_id: 0 },
+					Some(
+						(
+							MultiLocation::new(1, X2(Parachain(SELF_PARA_ID), GeneralKey(BTC.encode()))),
+							100
+						)
+							.into()
+					)
+				)
+			))
+		);
+	});
+}
+
+#[test]
+fn finalize_import_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(PositionMigrationModule::import_position(
+			Origin::signed(SIBLING),
+			0,
+			BOB,
+			BTC,
+			100,
+			50
+		));
+		assert_noop!(
+			PositionMigrationModule::finalize_import(Origin::signed(OTHER_SIBLING), 0),
+			Error::<Runtime>::MigrationNotFound
+		);
+
+		// the collateral is credited only against the reserve transfer
+		let import_account = PositionMigrationModule::import_account_id(ParaId::from(SIBLING as u32));
+		assert_noop!(
+			PositionMigrationModule::finalize_import(Origin::signed(SIBLING), 0),
+			orml_tokens::Error::<Runtime>::BalanceTooLow
+		);
+		assert_ok!(Tokens::deposit(BTC, &import_account, 200));
+		assert_ok!(PositionMigrationModule::finalize_import(Origin::signed(SIBLING), 0));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::PositionImported(
+			ParaId::from(SIBLING as u32),
+			0,
+			BOB,
+			true,
+		)));
+		assert_eq!(PositionMigrationModule::imports(ParaId::from(SIBLING as u32), 0), None);
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 100);
+		assert_eq!(LoansModule::positions(BTC, BOB).debit, 50);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1050);
+
+		// the unsafe position is not reopened, the beneficiary keeps the collateral
+		assert_ok!(PositionMigrationModule::import_position(
+			Origin::signed(SIBLING),
+			1,
+			BOB,
+			BTC,
+			100,
+			100
+		));
+		assert_ok!(PositionMigrationModule::finalize_import(Origin::signed(SIBLING), 1));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::PositionImported(
+			ParaId::from(SIBLING as u32),
+			1,
+			BOB,
+			false,
+		)));
+		assert_eq!(LoansModule::positions(BTC, BOB).collateral, 100);
+		assert_eq!(Tokens::free_balance(BTC, &BOB), 100);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 1050);
+		assert_eq!(Tokens::free_balance(BTC, &import_account), 0);
+	});
+}
+
+#[test]
+fn set_sibling_parachain_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PositionMigrationModule::set_sibling_parachain(Origin::signed(BOB), ParaId::from(3000), true),
+			BadOrigin
+		);
+		assert!(!PositionMigrationModule::sibling_parachains(ParaId::from(3000)));
+
+		assert_ok!(PositionMigrationModule::set_sibling_parachain(
+			Origin::signed(1),
+			ParaId::from(3000),
+			true
+		));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::SiblingParachainUpdated(
+			ParaId::from(3000),
+			true,
+		)));
+		assert!(PositionMigrationModule::sibling_parachains(ParaId::from(3000)));
+
+		assert_ok!(PositionMigrationModule::set_sibling_parachain(
+			Origin::signed(1),
+			ParaId::from(SIBLING as u32),
+			false
+		));
+		assert!(!PositionMigrationModule::sibling_parachains(ParaId::from(
+			SIBLING as u32
+		)));
+		assert_noop!(
+			PositionMigrationModule::import_position(Origin::signed(SIBLING), 0, BOB, BTC, 100, 50),
+			Error::<Runtime>::InvalidDestination
+		);
+	});
+}
+
+#[test]
+fn cancel_export_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PositionMigrationModule::cancel_export(Origin::signed(ALICE), 0),
+			Error::<Runtime>::MigrationNotFound
+		);
+		export_alice_position();
+
+		assert_noop!(
+			PositionMigrationModule::cancel_export(Origin::signed(BOB), 0),
+			Error::<Runtime>::NoPermission
+		);
+		System::set_block_number(11);
+		assert_noop!(
+			PositionMigrationModule::cancel_export(Origin::signed(ALICE), 0),
+			Error::<Runtime>::NotExpired
+		);
+
+		System::set_block_number(12);
+		assert_ok!(PositionMigrationModule::cancel_export(Origin::signed(ALICE), 0));
+		System::assert_last_event(Event::PositionMigrationModule(crate::Event::ExportCancelled(0, ALICE)));
+		assert_eq!(PositionMigrationModule::exports(0), None);
+		assert_eq!(Tokens::free_balance(BTC, &ALICE), 10000);
+		assert_eq!(Tokens::free_balance(BTC, &PositionMigrationModule::account_id()), 0);
+
+		// the acknowledgement after cancelled is rejected
+		assert_noop!(
+			PositionMigrationModule::acknowledge_export(Origin::signed(SIBLING), 0),
+			Error::<Runtime>::MigrationNotFound
+		);
+	});
+}
@@ -25,18 +25,51 @@
 //!   - specify a fixed price for stable currency
 //!   - feed price in USD or related price bewteen two currencies
 //!   - lock/unlock the price data get from oracle
+//!
+//! The time-weighted average DEX prices of the monitored currencies are
+//! derived on-chain from the DEX cumulative prices once every `TwapWindow`
+//! blocks. The offchain worker reports by unsigned transaction when the
+//! oracle price deviates from the DEX TWAP price by more than the threshold,
+//! and the report is checked against the on-chain TWAP price. The reports are
+//! recorded on-chain for the oracle incident process, and are rate limited by
+//! `DeviationReportInterval`.
+//!
+//! The oracle prices are memoized on the first access in a block, so the
+//! modules reading the same price several times in a block don't repeat the
+//! oracle reads and decimal adjustment. The write of the memo and its removal
+//! on block finalization are charged to the block weight when it's populated.
+//! The memoized price of a currency is dropped when the oracle gets new data
+//! for it or a price is forced for it.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
-use frame_support::{pallet_prelude::*, transactional};
-use frame_system::pallet_prelude::*;
-use orml_traits::{DataFeeder, DataProvider, MultiCurrency};
+use frame_support::{log, pallet_prelude::*, transactional};
+use frame_system::{
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+use orml_traits::{DataFeeder, DataProvider, Happened, MultiCurrency, OnNewData};
+use orml_utilities::OffchainErr;
 use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
 use sp_core::U256;
-use sp_runtime::{traits::CheckedMul, FixedPointNumber};
-use sp_std::{convert::TryInto, marker::PhantomData};
-use support::{DEXManager, Erc20InfoMapping, ExchangeRateProvider, LockablePrice, Price, PriceProvider};
+use sp_runtime::{
+	offchain::{
+		storage_lock::{StorageLock, Time},
+		Duration,
+	},
+	traits::{CheckedMul, Saturating, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, marker::PhantomData, prelude::*};
+use support::{
+	average_cumulative_price, DEXManager, DEXPriceCumulative, Erc20InfoMapping, ExchangeRateProvider, LockablePrice,
+	Price, PriceProvider, Ratio,
+};
 
 mod mock;
 mod tests;
@@ -45,12 +78,26 @@ pub mod weights;
 pub use module::*;
 pub use weights::WeightInfo;
 
+pub const OFFCHAIN_WORKER_LOCK: &[u8] = b"acala/prices/lock/";
+pub const LOCK_DURATION: u64 = 100;
+
+/// The deviation between the oracle price and the DEX TWAP price
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct DeviationReport<BlockNumber> {
+	/// The block number of the report
+	pub block_number: BlockNumber,
+	/// The oracle price
+	pub oracle_price: Price,
+	/// The time-weighted average DEX price
+	pub dex_twap_price: Price,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
 		/// The data source, such as Oracle.
@@ -88,6 +135,25 @@ pub mod module {
 		/// Mapping between CurrencyId and ERC20 address so user can use Erc20.
 		type Erc20InfoMapping: Erc20InfoMapping;
 
+		/// The cumulative DEX prices to derive the TWAP prices.
+		type DEXPriceCumulative: DEXPriceCumulative<CurrencyId, Self::BlockNumber>;
+
+		/// The period in blocks the DEX TWAP price is averaged over.
+		#[pallet::constant]
+		type TwapWindow: Get<Self::BlockNumber>;
+
+		/// The minimum interval in blocks between two deviation reports of a
+		/// currency.
+		#[pallet::constant]
+		type DeviationReportInterval: Get<Self::BlockNumber>;
+
+		/// A configuration for base priority of unsigned transactions.
+		///
+		/// This is exposed so that it can be tuned for particular runtime, when
+		/// multiple modules send unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -98,6 +164,16 @@ pub mod module {
 		AccessPriceFailed,
 		/// There's no locked price
 		NoLockedPrice,
+		/// The price deviation of the currency is not monitored
+		DeviationNotMonitored,
+		/// The last deviation report of the currency is too recent
+		DeviationReportTooFrequent,
+		/// The reported oracle price is not the current one
+		OraclePriceMismatch,
+		/// The price deviation doesn't exceed the threshold
+		DeviationBelowThreshold,
+		/// The DEX TWAP price of the currency is not available yet
+		DexTwapPriceUnavailable,
 	}
 
 	#[pallet::event]
@@ -107,6 +183,12 @@ pub mod module {
 		LockPrice(CurrencyId, Price),
 		/// Unlock price. \[currency_id\]
 		UnlockPrice(CurrencyId),
+		/// The price deviation threshold updated. \[currency_id,
+		/// new_threshold\]
+		DeviationThresholdUpdated(CurrencyId, Option<Ratio>),
+		/// The oracle price deviates from the DEX TWAP price. \[currency_id,
+		/// oracle_price, dex_twap_price\]
+		PriceDeviationReported(CurrencyId, Price, Price),
 	}
 
 	/// Mapping from currency id to it's locked price
@@ -116,11 +198,87 @@ pub mod module {
 	#[pallet::getter(fn locked_price)]
 	pub type LockedPrice<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
 
+	/// The threshold of the deviation between the oracle price and the DEX
+	/// TWAP price of specific currency, the deviation of it is monitored only
+	/// if it's set.
+	///
+	/// DeviationThresholds: map CurrencyId => Option<Ratio>
+	#[pallet::storage]
+	#[pallet::getter(fn deviation_thresholds)]
+	pub type DeviationThresholds<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Ratio, OptionQuery>;
+
+	/// The last deviation report of specific currency.
+	///
+	/// LastDeviationReports: map CurrencyId => Option<DeviationReport>
+	#[pallet::storage]
+	#[pallet::getter(fn last_deviation_reports)]
+	pub type LastDeviationReports<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyId, DeviationReport<T::BlockNumber>, OptionQuery>;
+
+	/// The DEX cumulative price of specific monitored currency in stable
+	/// currency observed at the start of the current TWAP window, and the
+	/// block number of the observation.
+	///
+	/// TwapObservations: map CurrencyId => Option<(U256, BlockNumber)>
+	#[pallet::storage]
+	#[pallet::getter(fn twap_observations)]
+	pub type TwapObservations<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, (U256, T::BlockNumber), OptionQuery>;
+
+	/// The DEX TWAP price of specific monitored currency in the last
+	/// completed TWAP window.
+	///
+	/// DexTwapPrices: map CurrencyId => Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn dex_twap_prices)]
+	pub type DexTwapPrices<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
+
+	/// The oracle prices accessed in current block, populated on the first
+	/// access and cleared on block finalization. The write and the removal
+	/// of each entry are charged to the block weight on population.
+	///
+	/// CachedPrices: map CurrencyId => Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn cached_prices)]
+	pub type CachedPrices<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Derive the DEX TWAP prices of the monitored currencies at the end
+		/// of every TWAP window.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let twap_window = T::TwapWindow::get();
+			if twap_window.is_zero() || !(now % twap_window).is_zero() {
+				return 0;
+			}
+
+			let mut count: u32 = 0;
+			for currency_id in DeviationThresholds::<T>::iter_keys() {
+				Self::update_dex_twap_price(currency_id);
+				count = count.saturating_add(1);
+			}
+			T::WeightInfo::on_initialize(count)
+		}
+
+		fn on_finalize(_now: T::BlockNumber) {
+			CachedPrices::<T>::remove_all(None);
+		}
+
+		/// Runs after every block. Start offchain worker to submit unsigned tx
+		/// to report the price deviations.
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(e) = Self::_offchain_worker() {
+				log::info!(
+					target: "prices offchain worker",
+					"cannot run offchain worker at {:?}: {:?}",
+					now,
+					e,
+				);
+			}
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -149,6 +307,84 @@ pub mod module {
 			<Pallet<T> as LockablePrice<CurrencyId>>::unlock_price(currency_id)?;
 			Ok(())
 		}
+
+		/// Update the threshold of the deviation between the oracle price and
+		/// the DEX TWAP price, `None` means to stop monitoring the currency.
+		///
+		/// The dispatch origin of this call must be `LockOrigin`.
+		///
+		/// - `currency_id`: currency type.
+		/// - `threshold`: the deviation ratio to the oracle price.
+		#[pallet::weight((T::WeightInfo::set_deviation_threshold(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_deviation_threshold(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			threshold: Option<Ratio>,
+		) -> DispatchResult {
+			T::LockOrigin::ensure_origin(origin)?;
+			DeviationThresholds::<T>::mutate_exists(currency_id, |maybe_threshold| *maybe_threshold = threshold);
+			if threshold.is_none() {
+				TwapObservations::<T>::remove(currency_id);
+				DexTwapPrices::<T>::remove(currency_id);
+			}
+			Self::deposit_event(Event::DeviationThresholdUpdated(currency_id, threshold));
+			Ok(())
+		}
+
+		/// Report the deviation between the oracle price and the DEX TWAP
+		/// price.
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `currency_id`: currency type.
+		/// - `oracle_price`: the current oracle price.
+		#[pallet::weight((T::WeightInfo::report_price_deviation(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn report_price_deviation(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			oracle_price: Price,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			let dex_twap_price = Self::check_deviation_report(currency_id, oracle_price)?;
+			LastDeviationReports::<T>::insert(
+				currency_id,
+				DeviationReport {
+					block_number: <frame_system::Pallet<T>>::block_number(),
+					oracle_price,
+					dex_twap_price,
+				},
+			);
+			Self::deposit_event(Event::PriceDeviationReported(currency_id, oracle_price, dex_twap_price));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::report_price_deviation {
+					currency_id,
+					oracle_price,
+				} => {
+					if Self::check_deviation_report(*currency_id, *oracle_price).is_err() {
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("PricesOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides(currency_id)
+						.longevity(64_u64)
+						.propagate(true)
+						.build()
+				}
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
 	}
 }
 
@@ -180,18 +416,118 @@ impl<T: Config> Pallet<T> {
 				}
 			};
 		} else {
-			// get real-time price from oracle
-			T::Source::get(&currency_id)
+			// get real-time price from oracle, memoized within the block
+			return Self::oracle_price(currency_id);
 		};
 
-		let maybe_adjustment_multiplier = 10u128.checked_pow(T::Erc20InfoMapping::decimals(currency_id)?.into());
+		Self::adjust_decimals(currency_id, maybe_price?)
+	}
 
-		if let (Some(price), Some(adjustment_multiplier)) = (maybe_price, maybe_adjustment_multiplier) {
-			// return the price for 1 basic unit
-			Price::checked_from_rational(price.into_inner(), adjustment_multiplier)
-		} else {
-			None
+	/// Get the real-time price of the currency from oracle, and memoize it
+	/// for the rest of the block.
+	///
+	/// Note: this returns the price for 1 basic unit
+	fn oracle_price(currency_id: CurrencyId) -> Option<Price> {
+		if let Some(price) = Self::cached_prices(currency_id) {
+			return Some(price);
+		}
+
+		let price = Self::adjust_decimals(currency_id, T::Source::get(&currency_id)?)?;
+		CachedPrices::<T>::insert(currency_id, price);
+		// the memo is removed in `on_finalize`
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(
+			T::DbWeight::get().writes(2),
+			DispatchClass::Mandatory,
+		);
+		Some(price)
+	}
+
+	/// Convert the price of 1 unit of the currency to the price for 1 basic
+	/// unit.
+	fn adjust_decimals(currency_id: CurrencyId, price: Price) -> Option<Price> {
+		let adjustment_multiplier = 10u128.checked_pow(T::Erc20InfoMapping::decimals(currency_id)?.into())?;
+		Price::checked_from_rational(price.into_inner(), adjustment_multiplier)
+	}
+
+	/// Accumulate the DEX TWAP price of the currency in the window since the
+	/// last observation, and start a new window.
+	fn update_dex_twap_price(currency_id: CurrencyId) {
+		let stable_currency_id = T::GetStableCurrencyId::get();
+		let (cumulative_price, now) = match T::DEXPriceCumulative::get_cumulative_price(currency_id, stable_currency_id)
+		{
+			Some(observation) => observation,
+			None => {
+				TwapObservations::<T>::remove(currency_id);
+				DexTwapPrices::<T>::remove(currency_id);
+				return;
+			}
+		};
+
+		let maybe_dex_twap_price = Self::twap_observations(currency_id).and_then(|last_observation| {
+			average_cumulative_price(last_observation, (cumulative_price, now))?
+				.checked_mul(&Self::access_price(stable_currency_id)?)
+		});
+		DexTwapPrices::<T>::mutate_exists(currency_id, |maybe_price| *maybe_price = maybe_dex_twap_price);
+		TwapObservations::<T>::insert(currency_id, (cumulative_price, now));
+	}
+
+	/// Check the deviation report is valid and not rate limited, return the
+	/// DEX TWAP price.
+	fn check_deviation_report(currency_id: CurrencyId, oracle_price: Price) -> Result<Price, DispatchError> {
+		let threshold = Self::deviation_thresholds(currency_id).ok_or(Error::<T>::DeviationNotMonitored)?;
+		if let Some(last_report) = Self::last_deviation_reports(currency_id) {
+			ensure!(
+				<frame_system::Pallet<T>>::block_number()
+					>= last_report
+						.block_number
+						.saturating_add(T::DeviationReportInterval::get()),
+				Error::<T>::DeviationReportTooFrequent
+			);
+		}
+		ensure!(
+			Self::access_price(currency_id) == Some(oracle_price),
+			Error::<T>::OraclePriceMismatch
+		);
+		let dex_twap_price = Self::dex_twap_prices(currency_id).ok_or(Error::<T>::DexTwapPriceUnavailable)?;
+		let deviation = price_deviation(oracle_price, dex_twap_price).ok_or(Error::<T>::AccessPriceFailed)?;
+		ensure!(deviation > threshold, Error::<T>::DeviationBelowThreshold);
+		Ok(dex_twap_price)
+	}
+
+	fn submit_unsigned_deviation_report_tx(currency_id: CurrencyId, oracle_price: Price) {
+		let call = Call::<T>::report_price_deviation {
+			currency_id,
+			oracle_price,
+		};
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "prices offchain worker",
+				"submit unsigned price deviation report tx for CurrencyId {:?} failed!",
+				currency_id,
+			);
+		}
+	}
+
+	fn _offchain_worker() -> Result<(), OffchainErr> {
+		// check if we are a potential validator
+		if !sp_io::offchain::is_validator() {
+			return Err(OffchainErr::NotValidator);
+		}
+
+		// acquire offchain worker lock
+		let lock_expiration = Duration::from_millis(LOCK_DURATION);
+		let mut lock = StorageLock::<'_, Time>::with_deadline(OFFCHAIN_WORKER_LOCK, lock_expiration);
+		let _guard = lock.try_lock().map_err(|_| OffchainErr::OffchainLock)?;
+
+		for currency_id in DeviationThresholds::<T>::iter_keys() {
+			if let Some(oracle_price) = Self::access_price(currency_id) {
+				if Self::check_deviation_report(currency_id, oracle_price).is_ok() {
+					Self::submit_unsigned_deviation_report_tx(currency_id, oracle_price);
+				}
+			}
 		}
+
+		Ok(())
 	}
 }
 
@@ -212,6 +548,20 @@ impl<T: Config> LockablePrice<CurrencyId> for Pallet<T> {
 	}
 }
 
+/// Drop the memoized price of the currency when the oracle gets new data.
+impl<T: Config> OnNewData<T::AccountId, CurrencyId, Price> for Pallet<T> {
+	fn on_new_data(_who: &T::AccountId, currency_id: &CurrencyId, _price: &Price) {
+		CachedPrices::<T>::remove(currency_id);
+	}
+}
+
+/// Drop the memoized price of the currency when its price is forced.
+impl<T: Config> Happened<CurrencyId> for Pallet<T> {
+	fn happened(currency_id: &CurrencyId) {
+		CachedPrices::<T>::remove(currency_id);
+	}
+}
+
 /// PriceProvider that always provider real-time prices from oracle
 pub struct RealTimePriceProvider<T>(PhantomData<T>);
 impl<T: Config> PriceProvider<CurrencyId> for RealTimePriceProvider<T> {
@@ -237,6 +587,17 @@ impl<T: Config> PriceProvider<CurrencyId> for LockedPriceProvider<T> {
 	}
 }
 
+/// The deviation ratio of `price` to `reference_price`:
+/// deviation = |price - reference_price| / reference_price
+fn price_deviation(reference_price: Price, price: Price) -> Option<Ratio> {
+	let diff = if price > reference_price {
+		price.saturating_sub(reference_price)
+	} else {
+		reference_price.saturating_sub(price)
+	};
+	Ratio::checked_from_rational(diff.into_inner(), reference_price.into_inner())
+}
+
 /// The fair price is determined by the external feed price and the size of the liquidity pool:
 /// https://blog.alphafinance.io/fair-lp-token-pricing/
 /// fair_price = (pool_0 * pool_1)^0.5 * (price_0 * price_1)^0.5 / total_shares * 2
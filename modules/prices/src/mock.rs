@@ -24,13 +24,14 @@ use super::*;
 use frame_support::{
 	construct_runtime, ord_parameter_types, parameter_types,
 	traits::{Everything, Nothing},
+	weights::constants::RocksDbWeight,
 };
 use frame_system::EnsureSignedBy;
 use orml_traits::{parameter_type_with_key, DataFeeder};
 use primitives::{currency::DexShare, Amount, TokenSymbol};
 use sp_core::{H160, H256};
 use sp_runtime::{
-	testing::Header,
+	testing::{Header, TestXt},
 	traits::{IdentityLookup, One as OneT, Zero},
 	DispatchError, FixedPointNumber,
 };
@@ -76,7 +77,7 @@ impl frame_system::Config for Runtime {
 	type AccountData = ();
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type BaseCallFilter = Everything;
 	type SystemWeightInfo = ();
 	type SS58Prefix = ();
@@ -88,7 +89,13 @@ thread_local! {
 }
 
 pub fn mock_oracle_update() {
-	CHANGED.with(|v| *v.borrow_mut() = true)
+	CHANGED.with(|v| *v.borrow_mut() = true);
+	// the oracle notifies the new data
+	for currency_id in [AUSD, BTC, DOT, ACA, KSM].iter() {
+		if let Some(price) = MockDataProvider::get(currency_id) {
+			PricesModule::on_new_data(&0, currency_id, &price);
+		}
+	}
 }
 
 pub struct MockDataProvider;
@@ -138,6 +145,7 @@ impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
 	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
 		match (currency_id_a, currency_id_b) {
 			(AUSD, DOT) => (10000, 200),
+			(DOT, AUSD) => (100, 1_000_000),
 			_ => (0, 0),
 		}
 	}
@@ -197,6 +205,32 @@ impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
 	}
 }
 
+thread_local! {
+	static DEX_CUMULATIVE_PRICE: RefCell<(U256, BlockNumber, Price)> = RefCell::new((U256::zero(), 0, Price::zero()));
+}
+
+/// Accumulate the DEX price of DOT in AUSD up to the current block, and
+/// change it from the next block.
+pub fn mock_dex_price_change(price: Price) {
+	DEX_CUMULATIVE_PRICE.with(|v| {
+		let (cumulative_price, now) = MockDEXPriceCumulative::get_cumulative_price(DOT, AUSD).unwrap();
+		*v.borrow_mut() = (cumulative_price, now, price);
+	});
+}
+
+pub struct MockDEXPriceCumulative;
+impl DEXPriceCumulative<CurrencyId, BlockNumber> for MockDEXPriceCumulative {
+	fn get_cumulative_price(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<(U256, BlockNumber)> {
+		if (currency_id_a, currency_id_b) != (DOT, AUSD) {
+			return None;
+		}
+		let now = System::block_number();
+		let (cumulative_price, last_accumulated, price) = DEX_CUMULATIVE_PRICE.with(|v| *v.borrow());
+		let increment = U256::from(price.into_inner()).saturating_mul(U256::from(now - last_accumulated));
+		Some((cumulative_price.saturating_add(increment), now))
+	}
+}
+
 parameter_type_with_key! {
 	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
 		Default::default()
@@ -224,6 +258,9 @@ parameter_types! {
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
 	pub StableCurrencyFixedPrice: Price = Price::one();
+	pub const TwapWindow: BlockNumber = 3;
+	pub const DeviationReportInterval: BlockNumber = 10;
+	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
 impl Config for Runtime {
@@ -238,6 +275,10 @@ impl Config for Runtime {
 	type DEX = MockDEX;
 	type Currency = Tokens;
 	type Erc20InfoMapping = MockErc20InfoMapping;
+	type DEXPriceCumulative = MockDEXPriceCumulative;
+	type TwapWindow = TwapWindow;
+	type DeviationReportInterval = DeviationReportInterval;
+	type UnsignedPriority = UnsignedPriority;
 	type WeightInfo = ();
 }
 
@@ -251,11 +292,22 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		PricesModule: prices::{Pallet, Storage, Call, Event<T>},
+		PricesModule: prices::{Pallet, Storage, Call, Event<T>, ValidateUnsigned},
 		Tokens: orml_tokens::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
+/// An extrinsic type used for tests.
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Runtime
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
 pub struct ExtBuilder;
 
 impl Default for ExtBuilder {
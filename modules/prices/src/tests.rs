@@ -21,13 +21,25 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
-use mock::{Event, *};
+use frame_support::{assert_noop, assert_ok, weights::constants::RocksDbWeight};
+use mock::{Call as MockCall, Event, *};
+use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+use sp_io::offchain;
 use sp_runtime::{
 	traits::{BadOrigin, Bounded},
 	FixedPointNumber,
 };
 
+fn run_to_block_offchain(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		PricesModule::on_initialize(System::block_number());
+		PricesModule::offchain_worker(System::block_number());
+		// this unlocks the concurrency storage lock so offchain_worker will fire next block
+		offchain::sleep_until(offchain::timestamp().add(Duration::from_millis(LOCK_DURATION + 200)));
+	}
+}
+
 #[test]
 fn lp_token_fair_price_works() {
 	let lp_token_fair_price_0 = lp_token_fair_price(
@@ -231,6 +243,60 @@ fn access_price_of_other_currency() {
 	});
 }
 
+#[test]
+fn oracle_prices_memoized_within_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(PricesModule::cached_prices(BTC), None);
+		assert_eq!(
+			PricesModule::access_price(BTC),
+			Some(Price::saturating_from_integer(500000000000000u128))
+		); // 50000 USD, right shift the decimal point (18-8) places
+		assert_eq!(
+			PricesModule::cached_prices(BTC),
+			Some(Price::saturating_from_integer(500000000000000u128))
+		);
+
+		// the stable currency and the derived prices are not memoized
+		assert_eq!(
+			PricesModule::access_price(AUSD),
+			Some(Price::saturating_from_integer(1000000u128))
+		);
+		assert_eq!(PricesModule::cached_prices(AUSD), None);
+
+		// the memoized price is dropped when the oracle gets new data
+		mock_oracle_update();
+		assert_eq!(PricesModule::cached_prices(BTC), None);
+		assert_eq!(
+			PricesModule::access_price(BTC),
+			Some(Price::saturating_from_integer(400000000000000u128))
+		);
+		assert_eq!(
+			PricesModule::cached_prices(BTC),
+			Some(Price::saturating_from_integer(400000000000000u128))
+		);
+
+		// the memoized price is dropped when a price is forced
+		PricesModule::happened(&BTC);
+		assert_eq!(PricesModule::cached_prices(BTC), None);
+
+		// the write of the memo and its removal are charged to the block
+		let block_weight = System::block_weight().total();
+		assert!(PricesModule::access_price(BTC).is_some());
+		assert_eq!(
+			System::block_weight().total(),
+			block_weight + RocksDbWeight::get().writes(2)
+		);
+		assert!(PricesModule::access_price(BTC).is_some());
+		assert_eq!(
+			System::block_weight().total(),
+			block_weight + RocksDbWeight::get().writes(2)
+		);
+
+		PricesModule::on_finalize(1);
+		assert_eq!(PricesModule::cached_prices(BTC), None);
+	});
+}
+
 #[test]
 fn lock_price_work() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -508,3 +574,189 @@ fn price_providers_work() {
 		assert_eq!(LockedPriceProvider::<Runtime>::get_relative_price(BTC, KSM), None);
 	});
 }
+
+#[test]
+fn set_deviation_threshold_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			PricesModule::set_deviation_threshold(Origin::signed(2), DOT, Some(Ratio::saturating_from_rational(1, 10))),
+			BadOrigin
+		);
+		assert_ok!(PricesModule::set_deviation_threshold(
+			Origin::signed(1),
+			DOT,
+			Some(Ratio::saturating_from_rational(1, 10))
+		));
+		System::assert_last_event(Event::PricesModule(crate::Event::DeviationThresholdUpdated(
+			DOT,
+			Some(Ratio::saturating_from_rational(1, 10)),
+		)));
+		assert_eq!(
+			PricesModule::deviation_thresholds(DOT),
+			Some(Ratio::saturating_from_rational(1, 10))
+		);
+
+		assert_ok!(PricesModule::set_deviation_threshold(Origin::signed(1), DOT, None));
+		System::assert_last_event(Event::PricesModule(crate::Event::DeviationThresholdUpdated(DOT, None)));
+		assert_eq!(PricesModule::deviation_thresholds(DOT), None);
+	});
+}
+
+#[test]
+fn dex_twap_prices_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PricesModule::set_deviation_threshold(
+			Origin::signed(1),
+			DOT,
+			Some(Ratio::saturating_from_rational(1, 10))
+		));
+		mock_dex_price_change(Price::saturating_from_integer(4));
+
+		// the TWAP window starts with the first observation
+		assert_eq!(PricesModule::on_initialize(2), 0);
+		assert_eq!(PricesModule::twap_observations(DOT), None);
+		System::set_block_number(3);
+		assert_eq!(
+			PricesModule::on_initialize(3),
+			<() as crate::WeightInfo>::on_initialize(1)
+		);
+		assert_eq!(
+			PricesModule::twap_observations(DOT),
+			Some((U256::from(Price::saturating_from_integer(8).into_inner()), 3))
+		);
+		assert_eq!(PricesModule::dex_twap_prices(DOT), None);
+
+		// the DEX price changed within the window is weighted by the blocks
+		System::set_block_number(5);
+		mock_dex_price_change(Price::saturating_from_integer(1));
+		System::set_block_number(6);
+		PricesModule::on_initialize(6);
+		assert_eq!(
+			PricesModule::dex_twap_prices(DOT),
+			Price::saturating_from_integer(3).checked_mul(&PricesModule::access_price(AUSD).unwrap())
+		);
+		assert_eq!(PricesModule::twap_observations(DOT).unwrap().1, 6);
+
+		// the currency without DEX price has no TWAP price
+		assert_ok!(PricesModule::set_deviation_threshold(
+			Origin::signed(1),
+			BTC,
+			Some(Ratio::saturating_from_rational(1, 10))
+		));
+		System::set_block_number(9);
+		PricesModule::on_initialize(9);
+		assert_eq!(PricesModule::twap_observations(BTC), None);
+		assert_eq!(PricesModule::dex_twap_prices(BTC), None);
+
+		// stop monitoring clears the TWAP price
+		assert_ok!(PricesModule::set_deviation_threshold(Origin::signed(1), DOT, None));
+		assert_eq!(PricesModule::twap_observations(DOT), None);
+		assert_eq!(PricesModule::dex_twap_prices(DOT), None);
+	});
+}
+
+#[test]
+fn report_price_deviation_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let oracle_price = PricesModule::access_price(DOT).unwrap();
+		let dex_twap_price = Price::saturating_from_rational(12, 1_000_000_000);
+
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::signed(1), DOT, oracle_price),
+			BadOrigin
+		);
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price),
+			Error::<Runtime>::DeviationNotMonitored
+		);
+
+		assert_ok!(PricesModule::set_deviation_threshold(
+			Origin::signed(1),
+			DOT,
+			Some(Ratio::saturating_from_rational(1, 10))
+		));
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price),
+			Error::<Runtime>::DexTwapPriceUnavailable
+		);
+		DexTwapPrices::<Runtime>::insert(DOT, oracle_price);
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price),
+			Error::<Runtime>::DeviationBelowThreshold
+		);
+
+		DexTwapPrices::<Runtime>::insert(DOT, dex_twap_price);
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::none(), DOT, dex_twap_price),
+			Error::<Runtime>::OraclePriceMismatch
+		);
+		assert_ok!(PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price));
+		System::assert_last_event(Event::PricesModule(crate::Event::PriceDeviationReported(
+			DOT,
+			oracle_price,
+			dex_twap_price,
+		)));
+		assert_eq!(
+			PricesModule::last_deviation_reports(DOT),
+			Some(DeviationReport {
+				block_number: 1,
+				oracle_price,
+				dex_twap_price,
+			})
+		);
+
+		// the reports are rate limited
+		assert_noop!(
+			PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price),
+			Error::<Runtime>::DeviationReportTooFrequent
+		);
+		System::set_block_number(11);
+		assert_ok!(PricesModule::report_price_deviation(Origin::none(), DOT, oracle_price));
+		assert_eq!(PricesModule::last_deviation_reports(DOT).unwrap().block_number, 11);
+	});
+}
+
+#[test]
+fn offchain_worker_report_price_deviation_work() {
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		assert_ok!(PricesModule::set_deviation_threshold(
+			Origin::signed(1),
+			DOT,
+			Some(Ratio::saturating_from_rational(1, 10))
+		));
+		// the DEX price is the same as the oracle price
+		let oracle_price = PricesModule::access_price(DOT).unwrap();
+		let stable_price = PricesModule::access_price(AUSD).unwrap();
+		mock_dex_price_change(
+			Price::checked_from_rational(oracle_price.into_inner(), stable_price.into_inner()).unwrap(),
+		);
+
+		run_to_block_offchain(2 * TwapWindow::get());
+		assert!(PricesModule::dex_twap_prices(DOT).is_some());
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		// the oracle price deviates from the DEX TWAP price
+		mock_oracle_update();
+		run_to_block_offchain(System::block_number() + 1);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.call,
+			MockCall::PricesModule(crate::Call::report_price_deviation {
+				currency_id: DOT,
+				oracle_price: PricesModule::access_price(DOT).unwrap(),
+			})
+		);
+		assert!(pool_state.write().transactions.pop().is_none());
+	});
+}
@@ -0,0 +1,54 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime API definition for proof of reserve module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use primitives::{Balance, CurrencyId};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait ProofOfReserveApi<AccountId, BlockNumber, Hash> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+	{
+		/// The merkle root over the reserve leaves at the block, with the count
+		/// of the leaves.
+		fn reserve_root() -> (Hash, u32);
+
+		/// The root and the count of the leaves of the reserve digest recorded
+		/// for the block number.
+		fn recorded_digest(block_number: BlockNumber) -> Option<(Hash, u32)>;
+
+		/// The collateral and debit of the position in the encoded reserve
+		/// leaves of a reported block, kept by the offchain worker in the
+		/// persistent local storage, with the merkle proof, the index of the
+		/// leaf and the count of the leaves.
+		fn position_proof(
+			encoded_leaves: Vec<u8>,
+			currency_id: CurrencyId,
+			who: AccountId,
+		) -> Option<(Balance, Balance, Vec<Hash>, u32, u32)>;
+	}
+}
@@ -0,0 +1,573 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Proof of Reserve Module
+//!
+//! ## Overview
+//!
+//! Proof of reserve module publishes a signed digest of the reserves of the
+//! protocol every `ReportPeriod` blocks, so that custodians and auditors can
+//! verify the collateral balances and the debt attributions against the
+//! published roots without running an archive node.
+//!
+//! The digest is the merkle root over the reserve leaves of the block, in
+//! order:
+//! - the surplus pool and the debit pool of cdp treasury;
+//! - for each collateral currency, the collateral held by cdp treasury and
+//!   the total collateral and debit of the loans, followed by the positions of
+//!   the loans of the collateral currency;
+//! - the extra collaterals backing the positions of the loans, which are
+//!   included in the total collateral of their currencies.
+//!
+//! The leaves and the nodes are hashed with different domain prefixes, so a
+//! node cannot be presented as a leaf.
+//!
+//! The offchain worker computes the reserve leaves of the imported block every
+//! `ReportPeriod` blocks and keeps them in the persistent local storage under
+//! `leaves_storage_key` for `DigestHistoryDepth` blocks. If the node holds the
+//! key of a reporter set by governance, it also signs the digest of the leaves
+//! with the sr25519 key of `KEY_TYPE` and submits it by an unsigned
+//! transaction. The digests of the last `DigestHistoryDepth` blocks are kept on
+//! chain together with the reporter and the signature.
+//!
+//! The auditors fetch the leaves of a reported block from any node running the
+//! offchain worker by the `offchain_localStorageGet` RPC, so neither the
+//! historical state nor an archive node is required. The merkle proof of a
+//! position is computed from the leaves by the runtime API and verified
+//! against the recorded digest by `verify_proof`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, BoundedVec};
+use frame_system::{
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+use loans::Position;
+use orml_utilities::OffchainErr;
+use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_core::sr25519;
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	traits::{Hash, Saturating, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	KeyTypeId, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, prelude::*};
+use support::CDPTreasury;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The key type of the reporters to sign the reserve digests.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"apor");
+
+/// The prefix of the persistent local storage keys of the reserve leaves of
+/// the reported blocks.
+pub const OFFCHAIN_WORKER_RESERVE_LEAVES: &[u8] = b"acala/proof-of-reserve/leaves/";
+
+/// The domain prefix of the hashes of the reserve leaves
+const LEAF_PREFIX: u8 = 0;
+/// The domain prefix of the hashes of the merkle tree nodes
+const NODE_PREFIX: u8 = 1;
+
+/// A leaf of the merkle tree of the reserves
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum ReserveLeaf<AccountId> {
+	/// The surplus pool and the debit pool of cdp treasury
+	Treasury { surplus_pool: Balance, debit_pool: Balance },
+	/// The collateral held by cdp treasury and the total collateral and debit
+	/// of the loans of the collateral currency
+	Collateral {
+		currency_id: CurrencyId,
+		treasury_collateral: Balance,
+		total_collateral: Balance,
+		total_debit: Balance,
+	},
+	/// The collateral and debit of the position of the loans
+	Position {
+		currency_id: CurrencyId,
+		who: AccountId,
+		collateral: Balance,
+		debit: Balance,
+	},
+	/// The extra collateral backing the position of the loans
+	ExtraCollateral {
+		currency_id: CurrencyId,
+		who: AccountId,
+		extra_currency_id: CurrencyId,
+		amount: Balance,
+	},
+}
+
+/// The digest of the reserves at a block, signed by the reporter
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct ReserveDigest<BlockNumber, Hash> {
+	/// The block number at which the reserves are reported
+	pub block_number: BlockNumber,
+	/// The merkle root over the reserve leaves
+	pub root: Hash,
+	/// The count of the reserve leaves
+	pub leaf_count: u32,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + loans::Config + SendTransactionTypes<Call<Self>> {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The list of valid collateral currency types
+		type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+		/// The number of blocks between two reports, must be non-zero
+		#[pallet::constant]
+		type ReportPeriod: Get<Self::BlockNumber>;
+
+		/// The number of blocks to keep the recorded digests
+		#[pallet::constant]
+		type DigestHistoryDepth: Get<Self::BlockNumber>;
+
+		/// The max number of reporters
+		#[pallet::constant]
+		type MaxReporters: Get<u32>;
+
+		/// A configuration for base priority of unsigned transactions.
+		///
+		/// This is exposed so that it can be tuned for particular runtime, when
+		/// multiple modules send unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// The origin which may set the reporters.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The reporters exceed the limit
+		TooManyReporters,
+		/// The reporter is not set by governance
+		InvalidReporter,
+		/// The signature of the digest is invalid
+		InvalidSignature,
+		/// The block number of the digest is in the future or out of the
+		/// history kept
+		InvalidBlockNumber,
+		/// The digest of the block has already been recorded
+		DigestAlreadyRecorded,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The reporters are updated. \[reporters\]
+		ReportersUpdated(Vec<sr25519::Public>),
+		/// The reserve digest is recorded. \[block_number, root, leaf_count,
+		/// reporter\]
+		ReserveDigestRecorded(T::BlockNumber, T::Hash, u32, sr25519::Public),
+	}
+
+	/// The reporters allowed to submit the reserve digests.
+	///
+	/// Reporters: Vec<sr25519::Public>
+	#[pallet::storage]
+	#[pallet::getter(fn reporters)]
+	pub type Reporters<T: Config> = StorageValue<_, BoundedVec<sr25519::Public, T::MaxReporters>, ValueQuery>;
+
+	/// The recorded reserve digests of the blocks kept, with the reporter and
+	/// the signature.
+	///
+	/// ReserveDigests: map BlockNumber => Option<(ReserveDigest, sr25519::Public,
+	/// sr25519::Signature)>
+	#[pallet::storage]
+	#[pallet::getter(fn reserve_digests)]
+	pub type ReserveDigests<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		(
+			ReserveDigest<T::BlockNumber, T::Hash>,
+			sr25519::Public,
+			sr25519::Signature,
+		),
+		OptionQuery,
+	>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Remove the digest moved out of the history kept.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let depth = T::DigestHistoryDepth::get();
+			if now > depth {
+				ReserveDigests::<T>::remove(now - depth);
+			}
+			T::WeightInfo::on_initialize()
+		}
+
+		/// Runs after every block. Start offchain worker to compute the reserve
+		/// digest of the block and submit it by unsigned tx, if the node holds
+		/// the key of a reporter.
+		fn offchain_worker(now: T::BlockNumber) {
+			if !(now % T::ReportPeriod::get()).is_zero() {
+				return;
+			}
+
+			if let Err(e) = Self::_offchain_worker(now) {
+				log::info!(
+					target: "proof-of-reserve offchain worker",
+					"cannot run offchain worker at {:?}: {:?}",
+					now,
+					e,
+				);
+			}
+		}
+
+		fn integrity_test() {
+			assert!(!T::ReportPeriod::get().is_zero());
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the reporters allowed to submit the reserve digests.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `reporters`: the sr25519 public keys of the reporters.
+		#[pallet::weight((T::WeightInfo::set_reporters(reporters.len() as u32), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_reporters(origin: OriginFor<T>, reporters: Vec<sr25519::Public>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let bounded_reporters: BoundedVec<sr25519::Public, T::MaxReporters> =
+				reporters.clone().try_into().map_err(|_| Error::<T>::TooManyReporters)?;
+			Reporters::<T>::put(bounded_reporters);
+			Self::deposit_event(Event::ReportersUpdated(reporters));
+			Ok(())
+		}
+
+		/// Record the reserve digest signed by a reporter.
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `digest`: the reserve digest of the block.
+		/// - `reporter`: the public key of the reporter.
+		/// - `signature`: the signature of the encoded digest by the reporter.
+		#[pallet::weight(T::WeightInfo::submit_reserve_digest())]
+		#[transactional]
+		pub fn submit_reserve_digest(
+			origin: OriginFor<T>,
+			digest: ReserveDigest<T::BlockNumber, T::Hash>,
+			reporter: sr25519::Public,
+			signature: sr25519::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::check_digest(&digest, &reporter, &signature)?;
+
+			ReserveDigests::<T>::insert(digest.block_number, (digest, reporter, signature));
+			Self::deposit_event(Event::ReserveDigestRecorded(
+				digest.block_number,
+				digest.root,
+				digest.leaf_count,
+				reporter,
+			));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::submit_reserve_digest {
+					digest,
+					reporter,
+					signature,
+				} => {
+					match Self::check_digest(digest, reporter, signature) {
+						Ok(()) => {}
+						Err(Error::<T>::InvalidReporter) | Err(Error::<T>::InvalidSignature) => {
+							return InvalidTransaction::BadProof.into();
+						}
+						Err(_) => return InvalidTransaction::Stale.into(),
+					}
+
+					ValidTransaction::with_tag_prefix("ProofOfReserveOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides(digest.block_number)
+						.longevity(64_u64)
+						.propagate(true)
+						.build()
+				}
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn check_digest(
+		digest: &ReserveDigest<T::BlockNumber, T::Hash>,
+		reporter: &sr25519::Public,
+		signature: &sr25519::Signature,
+	) -> Result<(), Error<T>> {
+		let now = frame_system::Pallet::<T>::block_number();
+		ensure!(
+			digest.block_number <= now && now.saturating_sub(digest.block_number) < T::DigestHistoryDepth::get(),
+			Error::<T>::InvalidBlockNumber
+		);
+		ensure!(
+			!ReserveDigests::<T>::contains_key(digest.block_number),
+			Error::<T>::DigestAlreadyRecorded
+		);
+		ensure!(Self::reporters().contains(reporter), Error::<T>::InvalidReporter);
+		ensure!(
+			sp_io::crypto::sr25519_verify(signature, &digest.encode(), reporter),
+			Error::<T>::InvalidSignature
+		);
+		Ok(())
+	}
+
+	fn _offchain_worker(now: T::BlockNumber) -> Result<(), OffchainErr> {
+		// keep the leaves on every node running the offchain worker, so the proofs
+		// of the recorded digests are served without the historical state
+		let leaves = Self::reserve_leaves();
+		StorageValueRef::persistent(&Self::leaves_storage_key(now)).set(&leaves);
+		let depth = T::DigestHistoryDepth::get();
+		if now > depth {
+			// the leaves are only kept for the report blocks, the older expired ones
+			// are removed by the previous reports
+			let expired = now - depth;
+			let mut expired_leaves = StorageValueRef::persistent(&Self::leaves_storage_key(
+				expired.saturating_sub(expired % T::ReportPeriod::get()),
+			));
+			expired_leaves.clear();
+		}
+
+		// check if we are a potential validator
+		if !sp_io::offchain::is_validator() {
+			return Err(OffchainErr::NotValidator);
+		}
+
+		let reporters = Self::reporters();
+		let reporter = match sp_io::crypto::sr25519_public_keys(KEY_TYPE)
+			.into_iter()
+			.find(|key| reporters.contains(key))
+		{
+			Some(reporter) => reporter,
+			None => return Ok(()),
+		};
+
+		let digest = Self::digest_of(now, &leaves);
+		let signature =
+			sp_io::crypto::sr25519_sign(KEY_TYPE, &reporter, &digest.encode()).ok_or(OffchainErr::SubmitTransaction)?;
+		let call = Call::<T>::submit_reserve_digest {
+			digest,
+			reporter,
+			signature,
+		};
+		SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+			.map_err(|_| OffchainErr::SubmitTransaction)
+	}
+
+	/// Get the reserve leaves at current state, in the order of the merkle
+	/// tree.
+	pub fn reserve_leaves() -> Vec<ReserveLeaf<T::AccountId>> {
+		let mut leaves = vec![ReserveLeaf::Treasury {
+			surplus_pool: <T as loans::Config>::CDPTreasury::get_surplus_pool(),
+			debit_pool: <T as loans::Config>::CDPTreasury::get_debit_pool(),
+		}];
+
+		for currency_id in T::CollateralCurrencyIds::get() {
+			let Position { collateral, debit } = <loans::Pallet<T>>::total_positions(currency_id);
+			leaves.push(ReserveLeaf::Collateral {
+				currency_id,
+				treasury_collateral: <T as loans::Config>::CDPTreasury::get_total_collaterals(currency_id),
+				total_collateral: collateral,
+				total_debit: debit,
+			});
+			leaves.extend(loans::Positions::<T>::iter_prefix(currency_id).map(
+				|(who, Position { collateral, debit })| ReserveLeaf::Position {
+					currency_id,
+					who,
+					collateral,
+					debit,
+				},
+			));
+		}
+
+		leaves.extend(
+			loans::ExtraCollaterals::<T>::iter().map(|((currency_id, who), extra_currency_id, amount)| {
+				ReserveLeaf::ExtraCollateral {
+					currency_id,
+					who,
+					extra_currency_id,
+					amount,
+				}
+			}),
+		);
+
+		leaves
+	}
+
+	/// Get the persistent local storage key of the reserve leaves of
+	/// `block_number`.
+	pub fn leaves_storage_key(block_number: T::BlockNumber) -> Vec<u8> {
+		let mut key = OFFCHAIN_WORKER_RESERVE_LEAVES.to_vec();
+		key.extend(block_number.encode());
+		key
+	}
+
+	/// Get the reserve digest of `block_number` at current state.
+	pub fn reserve_digest(block_number: T::BlockNumber) -> ReserveDigest<T::BlockNumber, T::Hash> {
+		Self::digest_of(block_number, &Self::reserve_leaves())
+	}
+
+	fn digest_of(
+		block_number: T::BlockNumber,
+		leaves: &[ReserveLeaf<T::AccountId>],
+	) -> ReserveDigest<T::BlockNumber, T::Hash> {
+		let hashes = Self::leaf_hashes(leaves);
+		ReserveDigest {
+			block_number,
+			root: Self::merkle_root(hashes.clone()),
+			leaf_count: hashes.len() as u32,
+		}
+	}
+
+	/// Get the position leaf of `who` in the reserve leaves of a reported
+	/// block, with the merkle proof, the index of the leaf and the count of the
+	/// leaves.
+	#[allow(clippy::type_complexity)]
+	pub fn position_proof(
+		leaves: &[ReserveLeaf<T::AccountId>],
+		currency_id: CurrencyId,
+		who: &T::AccountId,
+	) -> Option<(ReserveLeaf<T::AccountId>, Vec<T::Hash>, u32, u32)> {
+		let index = leaves.iter().position(|leaf| {
+			matches!(leaf, ReserveLeaf::Position { currency_id: id, who: account, .. } if *id == currency_id && account == who)
+		})?;
+		let hashes = Self::leaf_hashes(leaves);
+		let proof = Self::merkle_proof(hashes.clone(), index);
+		Some((leaves[index].clone(), proof, index as u32, hashes.len() as u32))
+	}
+
+	/// Verify the merkle proof of `leaf` at `index` against `root` over
+	/// `leaf_count` leaves.
+	pub fn verify_proof(
+		root: T::Hash,
+		leaf: &ReserveLeaf<T::AccountId>,
+		proof: &[T::Hash],
+		index: u32,
+		leaf_count: u32,
+	) -> bool {
+		if index >= leaf_count {
+			return false;
+		}
+
+		let mut computed = Self::hash_leaf(leaf);
+		let mut proof = proof.iter();
+		let mut index = index;
+		let mut len = leaf_count;
+		while len > 1 {
+			let sibling = index ^ 1;
+			if sibling < len {
+				let node = match proof.next() {
+					Some(node) => node,
+					None => return false,
+				};
+				computed = if index % 2 == 0 {
+					Self::hash_node(&computed, node)
+				} else {
+					Self::hash_node(node, &computed)
+				};
+			}
+			index /= 2;
+			len = (len + 1) / 2;
+		}
+
+		proof.next().is_none() && computed == root
+	}
+
+	fn hash_leaf(leaf: &ReserveLeaf<T::AccountId>) -> T::Hash {
+		T::Hashing::hash_of(&(LEAF_PREFIX, leaf))
+	}
+
+	fn hash_node(left: &T::Hash, right: &T::Hash) -> T::Hash {
+		T::Hashing::hash_of(&(NODE_PREFIX, left, right))
+	}
+
+	fn leaf_hashes(leaves: &[ReserveLeaf<T::AccountId>]) -> Vec<T::Hash> {
+		leaves.iter().map(Self::hash_leaf).collect()
+	}
+
+	/// The pairs of the nodes are hashed into the upper layer, the last node of
+	/// the layer of odd length is promoted.
+	fn next_layer(layer: &[T::Hash]) -> Vec<T::Hash> {
+		layer
+			.chunks(2)
+			.map(|pair| {
+				if let [left, right] = pair {
+					Self::hash_node(left, right)
+				} else {
+					pair[0]
+				}
+			})
+			.collect()
+	}
+
+	fn merkle_root(mut layer: Vec<T::Hash>) -> T::Hash {
+		if layer.is_empty() {
+			return Default::default();
+		}
+		while layer.len() > 1 {
+			layer = Self::next_layer(&layer);
+		}
+		layer[0]
+	}
+
+	fn merkle_proof(mut layer: Vec<T::Hash>, mut index: usize) -> Vec<T::Hash> {
+		let mut proof = vec![];
+		while layer.len() > 1 {
+			if let Some(sibling) = layer.get(index ^ 1) {
+				proof.push(*sibling);
+			}
+			index /= 2;
+			layer = Self::next_layer(&layer);
+		}
+		proof
+	}
+}
@@ -0,0 +1,349 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the proof of reserve module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Call as MockCall, Event, *};
+use sp_core::{
+	offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+	Pair,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::traits::BadOrigin;
+use std::sync::Arc;
+
+fn reporter_pair() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[1u8; 32])
+}
+
+#[test]
+fn set_reporters_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let reporter = reporter_pair().public();
+
+		assert_noop!(
+			ProofOfReserveModule::set_reporters(Origin::signed(5), vec![reporter]),
+			BadOrigin
+		);
+		assert_noop!(
+			ProofOfReserveModule::set_reporters(Origin::signed(1), vec![reporter; 4]),
+			Error::<Runtime>::TooManyReporters
+		);
+
+		assert_ok!(ProofOfReserveModule::set_reporters(Origin::signed(1), vec![reporter]));
+		System::assert_last_event(Event::ProofOfReserveModule(crate::Event::ReportersUpdated(vec![
+			reporter,
+		])));
+		assert_eq!(ProofOfReserveModule::reporters().into_inner(), vec![reporter]);
+	});
+}
+
+#[test]
+fn position_proof_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 300));
+		assert_ok!(LoansModule::adjust_position(&BOB, BTC, 200, 400));
+		assert_ok!(LoansModule::adjust_position(&ALICE, DOT, 300, 500));
+
+		let digest = ProofOfReserveModule::reserve_digest(1);
+		let leaves = ProofOfReserveModule::reserve_leaves();
+		// the treasury leaf, 2 collateral leaves and 3 position leaves
+		assert_eq!(digest.leaf_count, 6);
+		assert_eq!(leaves.len(), 6);
+
+		for (currency_id, who, collateral, debit) in
+			[(BTC, ALICE, 100, 300), (BTC, BOB, 200, 400), (DOT, ALICE, 300, 500)]
+		{
+			let (leaf, proof, index, leaf_count) =
+				ProofOfReserveModule::position_proof(&leaves, currency_id, &who).unwrap();
+			assert_eq!(
+				leaf,
+				ReserveLeaf::Position {
+					currency_id,
+					who,
+					collateral,
+					debit
+				}
+			);
+			assert_eq!(leaf_count, digest.leaf_count);
+			assert!(ProofOfReserveModule::verify_proof(
+				digest.root,
+				&leaf,
+				&proof,
+				index,
+				leaf_count
+			));
+
+			// the tampered leaf is rejected
+			let tampered_leaf = ReserveLeaf::Position {
+				currency_id,
+				who,
+				collateral: collateral + 1,
+				debit,
+			};
+			assert!(!ProofOfReserveModule::verify_proof(
+				digest.root,
+				&tampered_leaf,
+				&proof,
+				index,
+				leaf_count
+			));
+		}
+
+		assert_eq!(ProofOfReserveModule::position_proof(&leaves, DOT, &BOB), None);
+
+		// the root changes with the positions, the proofs of the leaves kept still
+		// verify against the recorded digest
+		assert_ok!(LoansModule::adjust_position(&BOB, DOT, 10, 0));
+		assert_ne!(ProofOfReserveModule::reserve_digest(1).root, digest.root);
+		assert_eq!(ProofOfReserveModule::position_proof(&leaves, DOT, &BOB), None);
+		let (leaf, proof, index, leaf_count) = ProofOfReserveModule::position_proof(&leaves, BTC, &BOB).unwrap();
+		assert!(ProofOfReserveModule::verify_proof(
+			digest.root,
+			&leaf,
+			&proof,
+			index,
+			leaf_count
+		));
+	});
+}
+
+#[test]
+fn reserve_leaves_include_extra_collaterals() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 300));
+		assert_ok!(LoansModule::adjust_extra_collateral(&ALICE, BTC, DOT, 50));
+
+		assert_eq!(
+			ProofOfReserveModule::reserve_leaves(),
+			vec![
+				ReserveLeaf::Treasury {
+					surplus_pool: 0,
+					debit_pool: 0
+				},
+				ReserveLeaf::Collateral {
+					currency_id: BTC,
+					treasury_collateral: 0,
+					total_collateral: 100,
+					total_debit: 300
+				},
+				ReserveLeaf::Position {
+					currency_id: BTC,
+					who: ALICE,
+					collateral: 100,
+					debit: 300
+				},
+				ReserveLeaf::Collateral {
+					currency_id: DOT,
+					treasury_collateral: 0,
+					total_collateral: 50,
+					total_debit: 0
+				},
+				ReserveLeaf::ExtraCollateral {
+					currency_id: BTC,
+					who: ALICE,
+					extra_currency_id: DOT,
+					amount: 50
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn submit_reserve_digest_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(20);
+		let pair = reporter_pair();
+		let reporter = pair.public();
+		let digest = ProofOfReserveModule::reserve_digest(20);
+		let signature = pair.sign(&digest.encode());
+
+		assert_noop!(
+			ProofOfReserveModule::submit_reserve_digest(Origin::none(), digest, reporter, signature.clone()),
+			Error::<Runtime>::InvalidReporter
+		);
+		assert_ok!(ProofOfReserveModule::set_reporters(Origin::signed(1), vec![reporter]));
+
+		let other_signature = sr25519::Pair::from_seed(&[2u8; 32]).sign(&digest.encode());
+		assert_noop!(
+			ProofOfReserveModule::submit_reserve_digest(Origin::none(), digest, reporter, other_signature.clone()),
+			Error::<Runtime>::InvalidSignature
+		);
+		assert_eq!(
+			ProofOfReserveModule::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::submit_reserve_digest {
+					digest,
+					reporter,
+					signature: other_signature,
+				}
+			),
+			Err(InvalidTransaction::BadProof.into())
+		);
+
+		let future_digest = ProofOfReserveModule::reserve_digest(21);
+		assert_noop!(
+			ProofOfReserveModule::submit_reserve_digest(
+				Origin::none(),
+				future_digest,
+				reporter,
+				pair.sign(&future_digest.encode())
+			),
+			Error::<Runtime>::InvalidBlockNumber
+		);
+		assert_eq!(
+			ProofOfReserveModule::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::submit_reserve_digest {
+					digest: future_digest,
+					reporter,
+					signature: pair.sign(&future_digest.encode()),
+				}
+			),
+			Err(InvalidTransaction::Stale.into())
+		);
+
+		assert_ok!(ProofOfReserveModule::submit_reserve_digest(
+			Origin::none(),
+			digest,
+			reporter,
+			signature.clone()
+		));
+		System::assert_last_event(Event::ProofOfReserveModule(crate::Event::ReserveDigestRecorded(
+			20,
+			digest.root,
+			digest.leaf_count,
+			reporter,
+		)));
+		assert_eq!(
+			ProofOfReserveModule::reserve_digests(20),
+			Some((digest, reporter, signature.clone()))
+		);
+		assert_noop!(
+			ProofOfReserveModule::submit_reserve_digest(Origin::none(), digest, reporter, signature),
+			Error::<Runtime>::DigestAlreadyRecorded
+		);
+
+		// the digest out of the history kept is removed
+		ProofOfReserveModule::on_initialize(119);
+		assert!(ProofOfReserveModule::reserve_digests(20).is_some());
+		ProofOfReserveModule::on_initialize(120);
+		assert_eq!(ProofOfReserveModule::reserve_digests(20), None);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_reserve_digest() {
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let reporter = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).unwrap();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	ext.execute_with(|| {
+		System::set_block_number(10);
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 300));
+
+		// not a reporter
+		ProofOfReserveModule::offchain_worker(10);
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		assert_ok!(ProofOfReserveModule::set_reporters(Origin::signed(1), vec![reporter]));
+
+		// not the report period
+		ProofOfReserveModule::offchain_worker(11);
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		ProofOfReserveModule::offchain_worker(10);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		if let MockCall::ProofOfReserveModule(crate::Call::submit_reserve_digest {
+			digest,
+			reporter: reporter_call,
+			signature,
+		}) = tx.call
+		{
+			assert_eq!(digest, ProofOfReserveModule::reserve_digest(10));
+			assert_eq!(reporter_call, reporter);
+			assert_ok!(ProofOfReserveModule::submit_reserve_digest(
+				Origin::none(),
+				digest,
+				reporter_call,
+				signature
+			));
+		}
+		assert_eq!(
+			ProofOfReserveModule::reserve_digests(10).map(|(digest, _, _)| digest),
+			Some(ProofOfReserveModule::reserve_digest(10))
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_keeps_reserve_leaves() {
+	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+	let (pool, _pool_state) = testing::TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		let leaves_of = |block_number: BlockNumber| {
+			StorageValueRef::persistent(&ProofOfReserveModule::leaves_storage_key(block_number))
+				.get::<Vec<ReserveLeaf<AccountId>>>()
+				.unwrap()
+		};
+
+		System::set_block_number(10);
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 300));
+
+		// the leaves are kept without the key of a reporter
+		ProofOfReserveModule::offchain_worker(10);
+		let leaves = leaves_of(10).unwrap();
+		assert_eq!(leaves, ProofOfReserveModule::reserve_leaves());
+
+		// the proof from the leaves kept is served after the positions changed
+		assert_ok!(LoansModule::adjust_position(&ALICE, BTC, 100, 0));
+		let (leaf, proof, index, leaf_count) = ProofOfReserveModule::position_proof(&leaves, BTC, &ALICE).unwrap();
+		assert!(ProofOfReserveModule::verify_proof(
+			ProofOfReserveModule::digest_of(10, &leaves).root,
+			&leaf,
+			&proof,
+			index,
+			leaf_count
+		));
+
+		// the leaves out of the history kept are removed
+		ProofOfReserveModule::offchain_worker(100);
+		assert!(leaves_of(10).is_some());
+		ProofOfReserveModule::offchain_worker(110);
+		assert_eq!(leaves_of(10), None);
+		assert!(leaves_of(110).is_some());
+	});
+}
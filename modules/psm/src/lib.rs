@@ -0,0 +1,395 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # PSM Module
+//!
+//! ## Overview
+//!
+//! The peg stability module (PSM) swaps the stable currency 1:1 with the
+//! external stable currencies whitelisted by governance, such as the bridged
+//! USDC and USDT, which anchors the peg by arbitrage. Minting takes the
+//! external stable currency into the reserve of the module and issues the
+//! same value of stable currency, and redeeming burns the stable currency and
+//! pays the same value of external stable currency out of the reserve. The
+//! amounts are converted by the decimals of the currencies.
+//!
+//! The swaps are charged the fees of `FeeProvider` in stable currency, which
+//! go to the surplus pool of CDP treasury. The stable currency minted and
+//! burned for each external stable currency is capped per `CapPeriod` blocks
+//! by governance. The external stable currency removed from the whitelist
+//! can't be minted with anymore, but its reserve can still be redeemed
+//! without the caps.
+//!
+//! The swaps are stopped after emergency shutdown, and the reserve is
+//! refunded with the collaterals of CDPs in the final redemption, in
+//! proportion to the stable currency redeemed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId, RevenueSource};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{CDPTreasury, EmergencyShutdown, Erc20InfoMapping, PSMFeeProvider, Ratio, StableCurrencyReserve};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The caps of the stable currency swapped with an external stable currency
+/// in a period.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct SwapCaps {
+	/// The cap of the stable currency minted in a period.
+	pub mint_cap: Balance,
+	/// The cap of the stable currency burned in a period.
+	pub burn_cap: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to transfer the stable currencies
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// CDP treasury to issue and burn the stable currency, and to receive
+		/// the fees
+		type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The fee rates of minting and redeeming
+		type FeeProvider: PSMFeeProvider;
+
+		/// Mapping to get the decimals of currencies
+		type Erc20InfoMapping: Erc20InfoMapping;
+
+		/// The period in blocks of the swap caps, must be non-zero
+		#[pallet::constant]
+		type CapPeriod: Get<Self::BlockNumber>;
+
+		/// Emergency shutdown.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// The origin which may update the swap caps
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The PSM module id, keep the reserve of external stable currencies
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The currency is not a whitelisted external stable currency
+		InvalidCurrencyId,
+		/// The decimals of the currency is unknown
+		InvalidDecimals,
+		/// The amount is too small to swap
+		InvalidAmount,
+		/// The stable currency minted in the period exceeds the mint cap
+		ExceedMintCap,
+		/// The stable currency burned in the period exceeds the burn cap
+		ExceedBurnCap,
+		/// The reserve of the external stable currency is not enough
+		InsufficientReserve,
+		/// System has already been shutdown
+		AlreadyShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The stable currency is minted with an external stable currency.
+		/// \[who, currency_id, external_amount, stable_amount, fee\]
+		Minted(T::AccountId, CurrencyId, Balance, Balance, Balance),
+		/// The stable currency is redeemed for an external stable currency.
+		/// \[who, currency_id, external_amount, stable_amount, fee\]
+		Redeemed(T::AccountId, CurrencyId, Balance, Balance, Balance),
+		/// The swap caps of an external stable currency are updated, the
+		/// currency is removed from the whitelist if none. \[currency_id,
+		/// swap_caps\]
+		SwapCapsUpdated(CurrencyId, Option<SwapCaps>),
+	}
+
+	/// The whitelisted external stable currencies and their swap caps.
+	///
+	/// ExternalStableCurrencies: map CurrencyId => Option<SwapCaps>
+	#[pallet::storage]
+	#[pallet::getter(fn external_stable_currencies)]
+	pub type ExternalStableCurrencies<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, SwapCaps, OptionQuery>;
+
+	/// The stable currency (minted, burned) with the external stable
+	/// currencies in the current period.
+	///
+	/// PeriodSwapped: map CurrencyId => (Balance, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn period_swapped)]
+	pub type PeriodSwapped<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, (Balance, Balance), ValueQuery>;
+
+	/// The reserve of the external stable currencies backing the stable
+	/// currency minted, including the delisted ones.
+	///
+	/// Reserves: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn reserves)]
+	pub type Reserves<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Reset the swapped amounts at the beginning of a period.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if (now % T::CapPeriod::get()).is_zero() {
+				PeriodSwapped::<T>::remove_all(None);
+				T::WeightInfo::on_initialize_with_reset()
+			} else {
+				T::WeightInfo::on_initialize()
+			}
+		}
+
+		fn integrity_test() {
+			assert!(!T::CapPeriod::get().is_zero());
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Mint the stable currency with an external stable currency.
+		///
+		/// - `currency_id`: the external stable currency to swap.
+		/// - `amount`: the amount of the external stable currency to swap.
+		#[pallet::weight(<T as Config>::WeightInfo::mint())]
+		#[transactional]
+		pub fn mint(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			let caps = Self::external_stable_currencies(currency_id).ok_or(Error::<T>::InvalidCurrencyId)?;
+			let stable_amount = Self::to_stable_amount(currency_id, amount)?;
+			ensure!(!stable_amount.is_zero(), Error::<T>::InvalidAmount);
+
+			PeriodSwapped::<T>::try_mutate(currency_id, |(minted, _)| -> DispatchResult {
+				let new_minted = minted.checked_add(stable_amount).ok_or(ArithmeticError::Overflow)?;
+				ensure!(new_minted <= caps.mint_cap, Error::<T>::ExceedMintCap);
+				*minted = new_minted;
+				Ok(())
+			})?;
+
+			let fee = T::FeeProvider::mint_fee().saturating_mul_int(stable_amount);
+			T::Currency::transfer(currency_id, &who, &Self::account_id(), amount)?;
+			Reserves::<T>::try_mutate(currency_id, |reserve| -> DispatchResult {
+				*reserve = reserve.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+			T::CDPTreasury::issue_debit(&who, stable_amount, true)?;
+			Self::charge_fee(&who, fee)?;
+
+			Self::deposit_event(Event::Minted(
+				who,
+				currency_id,
+				amount,
+				stable_amount.saturating_sub(fee),
+				fee,
+			));
+			Ok(())
+		}
+
+		/// Redeem the stable currency for an external stable currency. The
+		/// stable currency less than a unit of the external stable currency is
+		/// not redeemed. The reserve of the currency removed from the
+		/// whitelist is redeemed without the burn cap.
+		///
+		/// - `currency_id`: the external stable currency to swap.
+		/// - `stable_amount`: the amount of the stable currency to swap,
+		///   including the fee.
+		#[pallet::weight(<T as Config>::WeightInfo::redeem())]
+		#[transactional]
+		pub fn redeem(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			#[pallet::compact] stable_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			let maybe_caps = Self::external_stable_currencies(currency_id);
+			ensure!(
+				maybe_caps.is_some() || Reserves::<T>::contains_key(currency_id),
+				Error::<T>::InvalidCurrencyId
+			);
+			let fee = T::FeeProvider::redeem_fee().saturating_mul_int(stable_amount);
+			let amount = Self::from_stable_amount(currency_id, stable_amount.saturating_sub(fee))?;
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			ensure!(Self::reserves(currency_id) >= amount, Error::<T>::InsufficientReserve);
+			// burn only the stable currency of the whole units paid out
+			let burn_amount = Self::to_stable_amount(currency_id, amount)?;
+
+			if let Some(caps) = maybe_caps {
+				PeriodSwapped::<T>::try_mutate(currency_id, |(_, burned)| -> DispatchResult {
+					let new_burned = burned.checked_add(burn_amount).ok_or(ArithmeticError::Overflow)?;
+					ensure!(new_burned <= caps.burn_cap, Error::<T>::ExceedBurnCap);
+					*burned = new_burned;
+					Ok(())
+				})?;
+			}
+
+			Self::charge_fee(&who, fee)?;
+			T::CDPTreasury::burn_debit(&who, burn_amount)?;
+			Self::withdraw_reserve(&who, currency_id, amount)?;
+
+			Self::deposit_event(Event::Redeemed(who, currency_id, amount, burn_amount, fee));
+			Ok(())
+		}
+
+		/// Update the swap caps of an external stable currency, whitelist the
+		/// currency if it's not, or remove it from the whitelist if none. The
+		/// reserve of the removed currency can still be redeemed.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: the external stable currency.
+		/// - `swap_caps`: the caps of the stable currency minted and burned in
+		///   a period.
+		#[pallet::weight((<T as Config>::WeightInfo::set_swap_caps(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_swap_caps(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			swap_caps: Option<SwapCaps>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				currency_id != T::GetStableCurrencyId::get(),
+				Error::<T>::InvalidCurrencyId
+			);
+			ensure!(
+				T::Erc20InfoMapping::decimals(currency_id).is_some(),
+				Error::<T>::InvalidDecimals
+			);
+
+			ExternalStableCurrencies::<T>::mutate_exists(currency_id, |maybe_caps| *maybe_caps = swap_caps);
+			Self::deposit_event(Event::SwapCapsUpdated(currency_id, swap_caps));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of PSM module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Pay out the reserve of the external stable currency, the entry is
+	/// removed when the reserve is used up.
+	fn withdraw_reserve(to: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Reserves::<T>::try_mutate_exists(currency_id, |maybe_reserve| -> DispatchResult {
+			let reserve = maybe_reserve
+				.unwrap_or_default()
+				.checked_sub(amount)
+				.ok_or(Error::<T>::InsufficientReserve)?;
+			*maybe_reserve = Some(reserve).filter(|reserve| !reserve.is_zero());
+			Ok(())
+		})?;
+		T::Currency::transfer(currency_id, &Self::account_id(), to, amount)
+	}
+
+	/// Transfer the fee to the surplus pool of CDP treasury.
+	fn charge_fee(who: &T::AccountId, fee: Balance) -> DispatchResult {
+		if !fee.is_zero() {
+			T::CDPTreasury::deposit_surplus(who, fee)?;
+			T::CDPTreasury::on_revenue(RevenueSource::PSMFee, fee);
+		}
+		Ok(())
+	}
+
+	/// The decimals of the stable currency minus the decimals of the external
+	/// stable currency.
+	fn decimals_diff(currency_id: CurrencyId) -> Result<i32, DispatchError> {
+		let stable_decimals =
+			T::Erc20InfoMapping::decimals(T::GetStableCurrencyId::get()).ok_or(Error::<T>::InvalidDecimals)?;
+		let decimals = T::Erc20InfoMapping::decimals(currency_id).ok_or(Error::<T>::InvalidDecimals)?;
+		Ok(i32::from(stable_decimals) - i32::from(decimals))
+	}
+
+	/// Convert the amount of the external stable currency to the stable
+	/// currency of the same value.
+	pub fn to_stable_amount(currency_id: CurrencyId, amount: Balance) -> Result<Balance, DispatchError> {
+		let diff = Self::decimals_diff(currency_id)?;
+		let unit = 10u128
+			.checked_pow(diff.unsigned_abs())
+			.ok_or(ArithmeticError::Overflow)?;
+		if diff >= 0 {
+			Ok(amount.checked_mul(unit).ok_or(ArithmeticError::Overflow)?)
+		} else {
+			Ok(amount / unit)
+		}
+	}
+
+	/// Convert the amount of the stable currency to the external stable
+	/// currency of the same value, rounded down.
+	pub fn from_stable_amount(currency_id: CurrencyId, stable_amount: Balance) -> Result<Balance, DispatchError> {
+		let diff = Self::decimals_diff(currency_id)?;
+		let unit = 10u128
+			.checked_pow(diff.unsigned_abs())
+			.ok_or(ArithmeticError::Overflow)?;
+		if diff >= 0 {
+			Ok(stable_amount / unit)
+		} else {
+			Ok(stable_amount.checked_mul(unit).ok_or(ArithmeticError::Overflow)?)
+		}
+	}
+}
+
+impl<T: Config> StableCurrencyReserve<T::AccountId, CurrencyId, Balance> for Pallet<T> {
+	fn reserve_currency_ids() -> Vec<CurrencyId> {
+		Reserves::<T>::iter_keys().collect()
+	}
+
+	fn refund_reserve(who: &T::AccountId, currency_id: CurrencyId, ratio: Ratio) -> Result<Balance, DispatchError> {
+		let refund_amount = ratio.saturating_mul_int(Self::reserves(currency_id));
+		if !refund_amount.is_zero() {
+			Self::withdraw_reserve(who, currency_id, refund_amount)?;
+		}
+		Ok(refund_amount)
+	}
+}
@@ -0,0 +1,206 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the PSM module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+use support::Rate;
+
+const SWAP_CAPS: SwapCaps = SwapCaps {
+	mint_cap: 1_000_000,
+	burn_cap: 1_000_000,
+};
+
+#[test]
+fn set_swap_caps_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PSMModule::set_swap_caps(Origin::signed(BOB), CASH, Some(SWAP_CAPS)),
+			BadOrigin
+		);
+		assert_noop!(
+			PSMModule::set_swap_caps(Origin::signed(ALICE), AUSD, Some(SWAP_CAPS)),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_noop!(
+			PSMModule::set_swap_caps(Origin::signed(ALICE), CurrencyId::ForeignAsset(0), Some(SWAP_CAPS)),
+			Error::<Runtime>::InvalidDecimals
+		);
+
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, Some(SWAP_CAPS)));
+		System::assert_last_event(Event::PSMModule(crate::Event::SwapCapsUpdated(CASH, Some(SWAP_CAPS))));
+		assert_eq!(PSMModule::external_stable_currencies(CASH), Some(SWAP_CAPS));
+
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, None));
+		System::assert_last_event(Event::PSMModule(crate::Event::SwapCapsUpdated(CASH, None)));
+		assert_eq!(PSMModule::external_stable_currencies(CASH), None);
+	});
+}
+
+#[test]
+fn mint_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			PSMModule::mint(Origin::signed(ALICE), CASH, 100),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, Some(SWAP_CAPS)));
+		set_fees(Rate::saturating_from_rational(1, 100), Rate::zero());
+
+		assert_noop!(
+			PSMModule::mint(Origin::signed(ALICE), DOT, 100),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_noop!(
+			PSMModule::mint(Origin::signed(ALICE), CASH, 0),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			PSMModule::mint(Origin::signed(ALICE), CASH, 101),
+			Error::<Runtime>::ExceedMintCap
+		);
+
+		// 1 CASH of 8 decimals is worth 10_000 AUSD of 12 decimals
+		assert_ok!(PSMModule::mint(Origin::signed(ALICE), CASH, 100));
+		System::assert_last_event(Event::PSMModule(crate::Event::Minted(
+			ALICE, CASH, 100, 990_000, 10_000,
+		)));
+		assert_eq!(Tokens::free_balance(CASH, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(CASH, &PSMModule::account_id()), 100);
+		assert_eq!(PSMModule::reserves(CASH), 100);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 990_000);
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 10_000);
+		assert_eq!(PSMModule::period_swapped(CASH), (1_000_000, 0));
+
+		assert_noop!(
+			PSMModule::mint(Origin::signed(BOB), CASH, 1),
+			Error::<Runtime>::ExceedMintCap
+		);
+
+		// the swapped amounts are reset at the beginning of a period
+		PSMModule::on_initialize(10);
+		assert_eq!(PSMModule::period_swapped(CASH), (0, 0));
+		assert_ok!(PSMModule::mint(Origin::signed(BOB), CASH, 1));
+	});
+}
+
+#[test]
+fn redeem_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, Some(SWAP_CAPS)));
+		assert_ok!(PSMModule::mint(Origin::signed(ALICE), CASH, 100));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1_000_000);
+
+		assert_noop!(
+			PSMModule::redeem(Origin::signed(ALICE), CASH, 1_010_000),
+			Error::<Runtime>::InsufficientReserve
+		);
+
+		set_fees(Rate::zero(), Rate::saturating_from_rational(1, 100));
+		assert_noop!(
+			PSMModule::redeem(Origin::signed(ALICE), CASH, 10_000),
+			Error::<Runtime>::InvalidAmount
+		);
+
+		// the AUSD less than a unit of CASH is not redeemed
+		assert_ok!(PSMModule::redeem(Origin::signed(ALICE), CASH, 505_000));
+		System::assert_last_event(Event::PSMModule(crate::Event::Redeemed(
+			ALICE, CASH, 49, 490_000, 5_050,
+		)));
+		assert_eq!(Tokens::free_balance(CASH, &ALICE), 949);
+		assert_eq!(Tokens::free_balance(CASH, &PSMModule::account_id()), 51);
+		assert_eq!(PSMModule::reserves(CASH), 51);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 504_950);
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 5_050);
+		assert_eq!(PSMModule::period_swapped(CASH), (1_000_000, 490_000));
+
+		assert_ok!(PSMModule::set_swap_caps(
+			Origin::signed(ALICE),
+			CASH,
+			Some(SwapCaps {
+				mint_cap: 1_000_000,
+				burn_cap: 500_000,
+			})
+		));
+		assert_ok!(PSMModule::redeem(Origin::signed(ALICE), CASH, 20_000));
+		assert_eq!(PSMModule::period_swapped(CASH), (1_000_000, 500_000));
+		assert_noop!(
+			PSMModule::redeem(Origin::signed(ALICE), CASH, 20_000),
+			Error::<Runtime>::ExceedBurnCap
+		);
+	});
+}
+
+#[test]
+fn redeem_delisted_reserve_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, Some(SWAP_CAPS)));
+		assert_ok!(PSMModule::mint(Origin::signed(ALICE), CASH, 100));
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, None));
+
+		// the delisted currency can't be minted with, but its reserve can be redeemed without caps
+		assert_noop!(
+			PSMModule::mint(Origin::signed(ALICE), CASH, 1),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_ok!(PSMModule::redeem(Origin::signed(ALICE), CASH, 1_000_000));
+		assert_eq!(Tokens::free_balance(CASH, &ALICE), 1000);
+		assert_eq!(PSMModule::reserves(CASH), 0);
+		assert!(!Reserves::<Runtime>::contains_key(CASH));
+		assert_eq!(PSMModule::period_swapped(CASH), (1_000_000, 0));
+
+		assert_noop!(
+			PSMModule::redeem(Origin::signed(ALICE), CASH, 10_000),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+	});
+}
+
+#[test]
+fn shutdown_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(PSMModule::set_swap_caps(Origin::signed(ALICE), CASH, Some(SWAP_CAPS)));
+		assert_ok!(PSMModule::mint(Origin::signed(ALICE), CASH, 100));
+		mock_shutdown();
+
+		assert_noop!(
+			PSMModule::mint(Origin::signed(BOB), CASH, 100),
+			Error::<Runtime>::AlreadyShutdown
+		);
+		assert_noop!(
+			PSMModule::redeem(Origin::signed(ALICE), CASH, 10_000),
+			Error::<Runtime>::AlreadyShutdown
+		);
+
+		// the reserve is refunded in the final redemption
+		assert_eq!(PSMModule::reserve_currency_ids(), vec![CASH]);
+		assert_eq!(
+			PSMModule::refund_reserve(&ALICE, CASH, Ratio::saturating_from_rational(1, 4)),
+			Ok(25)
+		);
+		assert_eq!(Tokens::free_balance(CASH, &ALICE), 925);
+		assert_eq!(PSMModule::reserves(CASH), 75);
+		assert_eq!(PSMModule::refund_reserve(&ALICE, CASH, Ratio::one()), Ok(75));
+		assert_eq!(PSMModule::reserve_currency_ids(), vec![]);
+	});
+}
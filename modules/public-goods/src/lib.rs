@@ -0,0 +1,369 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Public Goods Module
+//!
+//! ## Overview
+//!
+//! Public goods module funds public goods projects by quadratic funding
+//! rounds. Projects register to receive donations, and users donate stable
+//! currency to them directly. Rounds run one after another with a fixed
+//! duration, at the end of each round the matching pool is distributed to the
+//! projects donated in the round, in proportion to the square of the sum of
+//! square roots of the donations minus the donations, so the number of donors
+//! counts more than the amount of donations. Only the donations of accounts
+//! attested by the attestation registry are counted for matching, for
+//! sybil-resistance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_io::KillStorageResult;
+use sp_runtime::{
+	traits::{AccountIdConversion, IntegerSquareRoot, Saturating, UniqueSaturatedInto, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{AttestationRegistry, Ratio};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type ProjectId = u32;
+pub type RoundIndex = u32;
+
+/// The donations received by a project in a round
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct RoundProjectInfo {
+	/// Total donations, including the ones of accounts not attested
+	pub total_donations: Balance,
+	/// Total donations of attested accounts
+	pub attested_donations: Balance,
+	/// Sum of the square roots of the donations of each attested account
+	pub sqrt_donations_sum: Balance,
+}
+
+impl RoundProjectInfo {
+	/// The weight of the project when distributing the matching pool.
+	pub fn matching_weight(&self) -> Balance {
+		self.sqrt_donations_sum
+			.saturating_mul(self.sqrt_donations_sum)
+			.saturating_sub(self.attested_donations)
+	}
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency for donations and matching pool
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stablecoin currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// The public goods module id, keep the matching pool.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The length in blocks of a funding round.
+		#[pallet::constant]
+		type RoundDuration: Get<Self::BlockNumber>;
+
+		/// The maximum number of projects can be registered.
+		#[pallet::constant]
+		type MaxProjects: Get<u32>;
+
+		/// The maximum number of contributions of the finalized rounds
+		/// removed in a block.
+		#[pallet::constant]
+		type MaxContributionsCleanup: Get<u32>;
+
+		/// The attestation registry for sybil-resistance, only the donations of
+		/// attested accounts are counted for matching.
+		type AttestationRegistry: AttestationRegistry<Self::AccountId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The project does not exist
+		ProjectNotExists,
+		/// The number of projects exceeds the limit
+		TooManyProjects,
+		/// The amount is invalid
+		InvalidAmount,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A project registered. \[project_id, beneficiary\]
+		ProjectRegistered(ProjectId, T::AccountId),
+		/// Donated to a project. \[round, project_id, donor, amount, attested\]
+		Donated(RoundIndex, ProjectId, T::AccountId, Balance, bool),
+		/// The matching pool funded. \[who, amount\]
+		MatchingPoolFunded(T::AccountId, Balance),
+		/// Matching fund distributed to a project. \[round, project_id,
+		/// amount\]
+		MatchingFundDistributed(RoundIndex, ProjectId, Balance),
+		/// A funding round finalized. \[round, total_matching_fund\]
+		RoundFinalized(RoundIndex, Balance),
+	}
+
+	/// The id of the next registered project.
+	///
+	/// NextProjectId: ProjectId
+	#[pallet::storage]
+	#[pallet::getter(fn next_project_id)]
+	pub type NextProjectId<T: Config> = StorageValue<_, ProjectId, ValueQuery>;
+
+	/// The beneficiary of the registered projects.
+	///
+	/// Projects: map ProjectId => Option<AccountId>
+	#[pallet::storage]
+	#[pallet::getter(fn projects)]
+	pub type Projects<T: Config> = StorageMap<_, Twox64Concat, ProjectId, T::AccountId, OptionQuery>;
+
+	/// The donations received by the projects in the round.
+	///
+	/// RoundProjects: double_map RoundIndex, ProjectId => RoundProjectInfo
+	#[pallet::storage]
+	#[pallet::getter(fn round_projects)]
+	pub type RoundProjects<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, RoundIndex, Twox64Concat, ProjectId, RoundProjectInfo, ValueQuery>;
+
+	/// The donations of attested accounts to the projects in the round.
+	///
+	/// Contributions: double_map (RoundIndex, ProjectId), AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn contributions)]
+	pub type Contributions<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, (RoundIndex, ProjectId), Twox64Concat, T::AccountId, Balance, ValueQuery>;
+
+	/// The contributions of the projects in the finalized rounds waiting to
+	/// be removed, at most `MaxContributionsCleanup` contributions are
+	/// removed in a block.
+	///
+	/// ContributionsToCleanup: map (RoundIndex, ProjectId) => ()
+	#[pallet::storage]
+	#[pallet::getter(fn contributions_to_cleanup)]
+	pub type ContributionsToCleanup<T: Config> = StorageMap<_, Twox64Concat, (RoundIndex, ProjectId), (), OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Finalize the last round when a new round starts, and remove the
+		/// contributions of the finalized rounds in bounded batches.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let round_duration = T::RoundDuration::get();
+			let finalize_weight = if !now.is_zero() && !round_duration.is_zero() && (now % round_duration).is_zero() {
+				let round: RoundIndex = (now / round_duration).unique_saturated_into();
+				Self::finalize_round(round.saturating_sub(1))
+			} else {
+				0
+			};
+			finalize_weight.saturating_add(Self::cleanup_contributions())
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a project with the caller as the beneficiary.
+		#[pallet::weight(T::WeightInfo::register_project())]
+		#[transactional]
+		pub fn register_project(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let project_id = NextProjectId::<T>::try_mutate(|id| -> Result<ProjectId, DispatchError> {
+				let current_id = *id;
+				ensure!(current_id < T::MaxProjects::get(), Error::<T>::TooManyProjects);
+				*id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+				Ok(current_id)
+			})?;
+			Projects::<T>::insert(project_id, &who);
+
+			Self::deposit_event(Event::ProjectRegistered(project_id, who));
+			Ok(())
+		}
+
+		/// Donate stable currency to the beneficiary of the project, the
+		/// donation is counted for matching of current round if the caller is
+		/// attested.
+		///
+		/// - `project_id`: the project to donate.
+		/// - `amount`: the donation amount.
+		#[pallet::weight(T::WeightInfo::donate())]
+		#[transactional]
+		pub fn donate(
+			origin: OriginFor<T>,
+			#[pallet::compact] project_id: ProjectId,
+			#[pallet::compact] amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			let beneficiary = Self::projects(project_id).ok_or(Error::<T>::ProjectNotExists)?;
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &beneficiary, amount)?;
+
+			let round = Self::current_round();
+			let attested = T::AttestationRegistry::is_attested(&who);
+			RoundProjects::<T>::try_mutate(round, project_id, |info| -> DispatchResult {
+				info.total_donations = info.total_donations.saturating_add(amount);
+				if attested {
+					Contributions::<T>::try_mutate((round, project_id), &who, |contribution| -> DispatchResult {
+						let new_contribution = contribution.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+						info.attested_donations = info.attested_donations.saturating_add(amount);
+						info.sqrt_donations_sum = info
+							.sqrt_donations_sum
+							.saturating_add(new_contribution.integer_sqrt())
+							.saturating_sub(contribution.integer_sqrt());
+						*contribution = new_contribution;
+						Ok(())
+					})?;
+				}
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Donated(round, project_id, who, amount, attested));
+			Ok(())
+		}
+
+		/// Transfer stable currency to the matching pool, it will be
+		/// distributed at the end of current round.
+		///
+		/// - `amount`: the amount to fund.
+		#[pallet::weight(T::WeightInfo::fund_matching_pool())]
+		#[transactional]
+		pub fn fund_matching_pool(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::account_id(), amount)?;
+
+			Self::deposit_event(Event::MatchingPoolFunded(who, amount));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of public goods module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Get the stable currency amount of the matching pool.
+	pub fn matching_pool() -> Balance {
+		T::Currency::free_balance(T::GetStableCurrencyId::get(), &Self::account_id())
+	}
+
+	/// Get the index of current round.
+	pub fn current_round() -> RoundIndex {
+		let now = frame_system::Pallet::<T>::block_number();
+		let round_duration = T::RoundDuration::get();
+		if round_duration.is_zero() {
+			Zero::zero()
+		} else {
+			(now / round_duration).unique_saturated_into()
+		}
+	}
+
+	/// Distribute the matching pool to the projects donated in the round in
+	/// proportion to their matching weight. The matching pool is kept for
+	/// next round if no attested donation in the round.
+	fn finalize_round(round: RoundIndex) -> Weight {
+		let round_projects: Vec<(ProjectId, RoundProjectInfo)> = RoundProjects::<T>::drain_prefix(round).collect();
+		let projects_count = round_projects.len() as u32;
+		let total_weight = round_projects.iter().fold(Balance::zero(), |total, (_, info)| {
+			total.saturating_add(info.matching_weight())
+		});
+		let matching_pool = Self::matching_pool();
+		let mut total_matching_fund: Balance = Zero::zero();
+
+		for (project_id, info) in round_projects {
+			ContributionsToCleanup::<T>::insert((round, project_id), ());
+
+			let amount = Ratio::checked_from_rational(info.matching_weight(), total_weight)
+				.unwrap_or_default()
+				.saturating_mul_int(matching_pool);
+			if amount.is_zero() {
+				continue;
+			}
+			if let Some(beneficiary) = Self::projects(project_id) {
+				match T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), &beneficiary, amount) {
+					Ok(_) => {
+						total_matching_fund = total_matching_fund.saturating_add(amount);
+						Self::deposit_event(Event::MatchingFundDistributed(round, project_id, amount));
+					}
+					Err(e) => {
+						log::warn!(
+							target: "public-goods",
+							"finalize_round: failed to distribute matching fund {:?} to project {:?} of round {:?}: {:?}. \
+							This is unexpected but should be safe",
+							amount, project_id, round, e
+						);
+					}
+				}
+			}
+		}
+
+		Self::deposit_event(Event::RoundFinalized(round, total_matching_fund));
+		T::WeightInfo::on_initialize_with_finalize_round(projects_count)
+	}
+
+	/// Remove at most `MaxContributionsCleanup` contributions of the
+	/// finalized rounds.
+	fn cleanup_contributions() -> Weight {
+		let mut remaining = T::MaxContributionsCleanup::get();
+		let mut removed: u32 = 0;
+		let keys: Vec<(RoundIndex, ProjectId)> = ContributionsToCleanup::<T>::iter_keys()
+			.take(remaining as usize)
+			.collect();
+		for key in keys {
+			if remaining.is_zero() {
+				break;
+			}
+			let count = match Contributions::<T>::remove_prefix(key, Some(remaining)) {
+				KillStorageResult::AllRemoved(count) => {
+					ContributionsToCleanup::<T>::remove(key);
+					count
+				}
+				KillStorageResult::SomeRemaining(count) => count,
+			};
+			remaining = remaining.saturating_sub(count);
+			removed = removed.saturating_add(count);
+		}
+		T::WeightInfo::cleanup_contributions(removed)
+	}
+}
@@ -0,0 +1,192 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the public goods module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+
+#[test]
+fn register_project_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PublicGoodsModule::register_project(Origin::signed(ALICE)));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::ProjectRegistered(0, ALICE)));
+		assert_ok!(PublicGoodsModule::register_project(Origin::signed(BOB)));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::ProjectRegistered(1, BOB)));
+		assert_eq!(PublicGoodsModule::projects(0), Some(ALICE));
+		assert_eq!(PublicGoodsModule::projects(1), Some(BOB));
+		assert_eq!(PublicGoodsModule::next_project_id(), 2);
+
+		assert_noop!(
+			PublicGoodsModule::register_project(Origin::signed(CHARLIE)),
+			Error::<Runtime>::TooManyProjects
+		);
+	});
+}
+
+#[test]
+fn donate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PublicGoodsModule::register_project(Origin::signed(ALICE)));
+
+		assert_noop!(
+			PublicGoodsModule::donate(Origin::signed(CHARLIE), 1, 100),
+			Error::<Runtime>::ProjectNotExists
+		);
+		assert_noop!(
+			PublicGoodsModule::donate(Origin::signed(CHARLIE), 0, 0),
+			Error::<Runtime>::InvalidAmount
+		);
+
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(CHARLIE), 0, 44));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::Donated(0, 0, CHARLIE, 44, true)));
+		assert_eq!(
+			PublicGoodsModule::round_projects(0, 0),
+			RoundProjectInfo {
+				total_donations: 44,
+				attested_donations: 44,
+				sqrt_donations_sum: 6,
+			}
+		);
+
+		// the square root of the accumulated donation of the donor is counted
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(CHARLIE), 0, 56));
+		assert_eq!(PublicGoodsModule::contributions((0, 0), CHARLIE), 100);
+		assert_eq!(
+			PublicGoodsModule::round_projects(0, 0),
+			RoundProjectInfo {
+				total_donations: 100,
+				attested_donations: 100,
+				sqrt_donations_sum: 10,
+			}
+		);
+
+		// the donations of accounts not attested are not counted for matching
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(EVE), 0, 400));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::Donated(0, 0, EVE, 400, false)));
+		assert_eq!(PublicGoodsModule::contributions((0, 0), EVE), 0);
+		assert_eq!(
+			PublicGoodsModule::round_projects(0, 0),
+			RoundProjectInfo {
+				total_donations: 500,
+				attested_donations: 100,
+				sqrt_donations_sum: 10,
+			}
+		);
+		assert_eq!(PublicGoodsModule::round_projects(0, 0).matching_weight(), 0);
+
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 500);
+		assert_eq!(Tokens::free_balance(AUSD, &CHARLIE), 900);
+		assert_eq!(Tokens::free_balance(AUSD, &EVE), 600);
+	});
+}
+
+#[test]
+fn finalize_round_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(PublicGoodsModule::register_project(Origin::signed(ALICE)));
+		assert_ok!(PublicGoodsModule::register_project(Origin::signed(BOB)));
+
+		assert_noop!(
+			PublicGoodsModule::fund_matching_pool(Origin::signed(TREASURY), 0),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_ok!(PublicGoodsModule::fund_matching_pool(Origin::signed(TREASURY), 1000));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::MatchingPoolFunded(
+			TREASURY, 1000,
+		)));
+		assert_eq!(PublicGoodsModule::matching_pool(), 1000);
+
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(CHARLIE), 0, 100));
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(DAVE), 0, 100));
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(CHARLIE), 1, 25));
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(DAVE), 1, 25));
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(EVE), 1, 100));
+		assert_eq!(PublicGoodsModule::round_projects(0, 0).matching_weight(), 200);
+		assert_eq!(PublicGoodsModule::round_projects(0, 1).matching_weight(), 50);
+
+		assert_eq!(
+			PublicGoodsModule::on_initialize(9),
+			<() as WeightInfo>::cleanup_contributions(0)
+		);
+		assert_eq!(PublicGoodsModule::matching_pool(), 1000);
+
+		PublicGoodsModule::on_initialize(10);
+		System::assert_has_event(Event::PublicGoodsModule(crate::Event::MatchingFundDistributed(
+			0, 0, 800,
+		)));
+		System::assert_has_event(Event::PublicGoodsModule(crate::Event::MatchingFundDistributed(
+			0, 1, 200,
+		)));
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::RoundFinalized(0, 1000)));
+		assert_eq!(PublicGoodsModule::matching_pool(), 0);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 350);
+		assert_eq!(PublicGoodsModule::round_projects(0, 0), Default::default());
+		assert_eq!(PublicGoodsModule::contributions((0, 0), CHARLIE), 0);
+
+		// the matching pool is kept for next round if no attested donation
+		System::set_block_number(11);
+		assert_eq!(PublicGoodsModule::current_round(), 1);
+		assert_ok!(PublicGoodsModule::fund_matching_pool(Origin::signed(TREASURY), 500));
+		assert_ok!(PublicGoodsModule::donate(Origin::signed(EVE), 0, 100));
+		PublicGoodsModule::on_initialize(20);
+		System::assert_last_event(Event::PublicGoodsModule(crate::Event::RoundFinalized(1, 0)));
+		assert_eq!(PublicGoodsModule::matching_pool(), 500);
+	});
+}
+
+#[test]
+fn cleanup_contributions_work() {
+	let mut ext = ExtBuilder::default().build();
+	ext.execute_with(|| {
+		Contributions::<Runtime>::insert((0, 0), CHARLIE, 100);
+		Contributions::<Runtime>::insert((0, 0), DAVE, 100);
+		Contributions::<Runtime>::insert((0, 1), CHARLIE, 25);
+		Contributions::<Runtime>::insert((0, 1), DAVE, 25);
+		ContributionsToCleanup::<Runtime>::insert((0, 0), ());
+		ContributionsToCleanup::<Runtime>::insert((0, 1), ());
+	});
+	ext.commit_all().unwrap();
+
+	// at most `MaxContributionsCleanup` contributions are removed in a block
+	ext.execute_with(|| {
+		assert_eq!(
+			PublicGoodsModule::on_initialize(1),
+			<() as WeightInfo>::cleanup_contributions(3)
+		);
+		assert_eq!(Contributions::<Runtime>::iter().count(), 1);
+		assert_eq!(ContributionsToCleanup::<Runtime>::iter().count(), 1);
+	});
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		assert_eq!(
+			PublicGoodsModule::on_initialize(2),
+			<() as WeightInfo>::cleanup_contributions(1)
+		);
+		assert_eq!(Contributions::<Runtime>::iter().count(), 0);
+		assert_eq!(ContributionsToCleanup::<Runtime>::iter().count(), 0);
+	});
+}
@@ -0,0 +1,307 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Randomness Beacon Module
+//!
+//! ## Overview
+//!
+//! Randomness beacon module produces the randomness for on-chain lotteries
+//! by a commit-reveal scheme. Each round consists of a commit period and a
+//! reveal period. Participants commit the hash of their secret with the
+//! deposit reserved during the commit period, and reveal the secret during
+//! the reveal period to get the deposit back. At the end of the round, the
+//! revealed secrets are XORed and mixed with the previous seed and the
+//! `ExternalRandomness` of the finalizing block into the new seed, and the
+//! deposits of the unrevealed commitments are slashed.
+//!
+//! The `ExternalRandomness`, e.g. the relay chain randomness, is unknown
+//! to the participants until the round is finalized, so the seed is
+//! unpredictable even if no secret is revealed, and withholding a reveal
+//! can't steer the seed to a known outcome. The deposit of the n-th
+//! commitment of a round is `CommitDeposit` * n, so filling the round with
+//! many accounts locks quadratically more funds, and the late commitments
+//! withheld are slashed more heavily. Consumers must only settle the
+//! actions committed before the block at which the seed is finalized.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, Randomness, ReservableCurrency},
+	transactional,
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{Hash, Saturating};
+use sp_std::prelude::*;
+use support::RandomnessSource;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The secret revealed by the participant
+pub type Secret = [u8; 32];
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency to reserve the commit deposit
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The number of blocks of the commit period of a round
+		#[pallet::constant]
+		type CommitPeriod: Get<Self::BlockNumber>;
+
+		/// The number of blocks of the reveal period of a round
+		#[pallet::constant]
+		type RevealPeriod: Get<Self::BlockNumber>;
+
+		/// The deposit reserved for the first commitment of a round, the n-th
+		/// commitment reserves n times as much, slashed if not revealed
+		#[pallet::constant]
+		type CommitDeposit: Get<BalanceOf<Self>>;
+
+		/// The randomness unknown to the participants until the round is
+		/// finalized, mixed into the seed
+		type ExternalRandomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+		/// The max number of commitments in a round
+		#[pallet::constant]
+		type MaxParticipants: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The round is not in the commit period
+		NotInCommitPeriod,
+		/// The round is not in the reveal period
+		NotInRevealPeriod,
+		/// The account has already committed in the round
+		AlreadyCommitted,
+		/// The commitments of the round reach the limit
+		TooManyParticipants,
+		/// The commitment does not exist
+		CommitmentNotExists,
+		/// The commitment has already been revealed
+		AlreadyRevealed,
+		/// The secret does not match the commitment
+		InvalidSecret,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The commitment is submitted. \[who, round\]
+		Committed(T::AccountId, u32),
+		/// The secret is revealed. \[who, round\]
+		Revealed(T::AccountId, u32),
+		/// The deposit of the unrevealed commitment is slashed. \[who, round,
+		/// amount\]
+		CommitmentSlashed(T::AccountId, u32, BalanceOf<T>),
+		/// The round is finalized with the new seed. \[round, seed\]
+		RoundFinalized(u32, T::Hash),
+	}
+
+	/// The index of current round.
+	///
+	/// CurrentRound: u32
+	#[pallet::storage]
+	#[pallet::getter(fn current_round)]
+	pub type CurrentRound<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The block number at which current round started.
+	///
+	/// RoundStart: BlockNumber
+	#[pallet::storage]
+	#[pallet::getter(fn round_start)]
+	pub type RoundStart<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// The commitments of current round, whether they are revealed and the
+	/// deposits reserved.
+	///
+	/// Commitments: map AccountId => Option<(Hash, bool, Balance)>
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, (T::Hash, bool, BalanceOf<T>), OptionQuery>;
+
+	/// The number of commitments of current round.
+	///
+	/// CommitmentCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn commitment_count)]
+	pub type CommitmentCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The XOR of the secrets revealed in current round.
+	///
+	/// RoundEntropy: Secret
+	#[pallet::storage]
+	#[pallet::getter(fn round_entropy)]
+	pub type RoundEntropy<T: Config> = StorageValue<_, Secret, ValueQuery>;
+
+	/// The seed of the last finalized round and the block number at which it
+	/// was finalized.
+	///
+	/// RandomSeed: (Hash, BlockNumber)
+	#[pallet::storage]
+	#[pallet::getter(fn random_seed)]
+	pub type RandomSeed<T: Config> = StorageValue<_, (T::Hash, T::BlockNumber), ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Charge the weight of finalizing current round at the end of the
+		/// reveal period.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if Self::is_round_end(now) {
+				T::WeightInfo::on_initialize(Self::commitment_count())
+			} else {
+				0
+			}
+		}
+
+		/// Finalize current round and start the next round at the end of the
+		/// reveal period. It's done at the finalization of the block when the
+		/// `ExternalRandomness` of the block is available.
+		fn on_finalize(now: T::BlockNumber) {
+			if Self::is_round_end(now) {
+				Self::finalize_round(now);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Commit the hash of the secret to current round, the deposit of the
+		/// n-th commitment, `CommitDeposit` * n, is reserved until the secret
+		/// is revealed.
+		///
+		/// - `commitment`: the hash of the caller and the secret.
+		#[pallet::weight(T::WeightInfo::commit())]
+		#[transactional]
+		pub fn commit(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() < Self::round_start().saturating_add(T::CommitPeriod::get()),
+				Error::<T>::NotInCommitPeriod
+			);
+			ensure!(!Commitments::<T>::contains_key(&who), Error::<T>::AlreadyCommitted);
+			let count = Self::commitment_count();
+			ensure!(count < T::MaxParticipants::get(), Error::<T>::TooManyParticipants);
+
+			let deposit = T::CommitDeposit::get().saturating_mul(count.saturating_add(1).into());
+			T::Currency::reserve(&who, deposit)?;
+			Commitments::<T>::insert(&who, (commitment, false, deposit));
+			CommitmentCount::<T>::put(count.saturating_add(1));
+			Self::deposit_event(Event::Committed(who, Self::current_round()));
+			Ok(())
+		}
+
+		/// Reveal the secret committed to current round, and get back the
+		/// commit deposit.
+		///
+		/// - `secret`: the secret committed.
+		#[pallet::weight(T::WeightInfo::reveal())]
+		#[transactional]
+		pub fn reveal(origin: OriginFor<T>, secret: Secret) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= Self::round_start().saturating_add(T::CommitPeriod::get()),
+				Error::<T>::NotInRevealPeriod
+			);
+
+			let deposit =
+				Commitments::<T>::try_mutate(&who, |maybe_commitment| -> Result<BalanceOf<T>, DispatchError> {
+					let (commitment, revealed, deposit) =
+						maybe_commitment.as_mut().ok_or(Error::<T>::CommitmentNotExists)?;
+					ensure!(!*revealed, Error::<T>::AlreadyRevealed);
+					ensure!(
+						*commitment == Self::commitment_of(&who, &secret),
+						Error::<T>::InvalidSecret
+					);
+					*revealed = true;
+					Ok(*deposit)
+				})?;
+
+			T::Currency::unreserve(&who, deposit);
+			RoundEntropy::<T>::mutate(|entropy| {
+				entropy.iter_mut().zip(secret.iter()).for_each(|(a, b)| *a ^= b);
+			});
+			Self::deposit_event(Event::Revealed(who, Self::current_round()));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the commitment of the secret of `who`, including the account so
+	/// that others can't copy the commitment.
+	pub fn commitment_of(who: &T::AccountId, secret: &Secret) -> T::Hash {
+		T::Hashing::hash_of(&(who, secret))
+	}
+
+	fn is_round_end(now: T::BlockNumber) -> bool {
+		now >= Self::round_start()
+			.saturating_add(T::CommitPeriod::get())
+			.saturating_add(T::RevealPeriod::get())
+	}
+
+	fn finalize_round(now: T::BlockNumber) {
+		let round = Self::current_round();
+
+		// slash the deposits of the unrevealed commitments
+		for (who, (_, revealed, deposit)) in Commitments::<T>::drain() {
+			if !revealed {
+				let (_, remaining) = T::Currency::slash_reserved(&who, deposit);
+				Self::deposit_event(Event::CommitmentSlashed(who, round, deposit.saturating_sub(remaining)));
+			}
+		}
+		CommitmentCount::<T>::kill();
+
+		let (previous_seed, _) = Self::random_seed();
+		let (external_randomness, _) = T::ExternalRandomness::random(&round.encode());
+		let seed = T::Hashing::hash_of(&(previous_seed, RoundEntropy::<T>::take(), external_randomness, round));
+		RandomSeed::<T>::put((seed, now));
+		CurrentRound::<T>::put(round.saturating_add(1));
+		RoundStart::<T>::put(now);
+		Self::deposit_event(Event::RoundFinalized(round, seed));
+	}
+}
+
+impl<T: Config> RandomnessSource<T::Hash, T::BlockNumber> for Pallet<T> {
+	fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+		let (seed, finalized_at) = Self::random_seed();
+		(T::Hashing::hash_of(&(seed, subject)), finalized_at)
+	}
+}
@@ -0,0 +1,214 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the randomness beacon module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use mock::*;
+use sp_core::H256;
+
+const ALICE_SECRET: Secret = [1u8; 32];
+const BOB_SECRET: Secret = [2u8; 32];
+
+#[test]
+fn commit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let commitment = RandomnessBeaconModule::commitment_of(&ALICE, &ALICE_SECRET);
+
+		assert_ok!(RandomnessBeaconModule::commit(Origin::signed(ALICE), commitment));
+		System::assert_last_event(Event::RandomnessBeaconModule(crate::Event::Committed(ALICE, 0)));
+		assert_eq!(
+			RandomnessBeaconModule::commitments(ALICE),
+			Some((commitment, false, 100))
+		);
+		assert_eq!(RandomnessBeaconModule::commitment_count(), 1);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 100);
+
+		assert_noop!(
+			RandomnessBeaconModule::commit(Origin::signed(ALICE), commitment),
+			Error::<Runtime>::AlreadyCommitted
+		);
+		// the deposit grows with the commitments of the round
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(BOB),
+			RandomnessBeaconModule::commitment_of(&BOB, &BOB_SECRET)
+		));
+		assert_eq!(PalletBalances::reserved_balance(BOB), 200);
+		assert_noop!(
+			RandomnessBeaconModule::commit(Origin::signed(CHARLIE), commitment),
+			Error::<Runtime>::TooManyParticipants
+		);
+
+		System::set_block_number(10);
+		assert_noop!(
+			RandomnessBeaconModule::commit(Origin::signed(CHARLIE), commitment),
+			Error::<Runtime>::NotInCommitPeriod
+		);
+	});
+}
+
+#[test]
+fn reveal_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(ALICE),
+			RandomnessBeaconModule::commitment_of(&ALICE, &ALICE_SECRET)
+		));
+		// BOB copies the commitment of ALICE
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(BOB),
+			RandomnessBeaconModule::commitment_of(&ALICE, &ALICE_SECRET)
+		));
+		assert_noop!(
+			RandomnessBeaconModule::reveal(Origin::signed(ALICE), ALICE_SECRET),
+			Error::<Runtime>::NotInRevealPeriod
+		);
+
+		System::set_block_number(10);
+		assert_noop!(
+			RandomnessBeaconModule::reveal(Origin::signed(CHARLIE), ALICE_SECRET),
+			Error::<Runtime>::CommitmentNotExists
+		);
+		assert_noop!(
+			RandomnessBeaconModule::reveal(Origin::signed(ALICE), BOB_SECRET),
+			Error::<Runtime>::InvalidSecret
+		);
+		assert_noop!(
+			RandomnessBeaconModule::reveal(Origin::signed(BOB), ALICE_SECRET),
+			Error::<Runtime>::InvalidSecret
+		);
+
+		assert_ok!(RandomnessBeaconModule::reveal(Origin::signed(ALICE), ALICE_SECRET));
+		System::assert_last_event(Event::RandomnessBeaconModule(crate::Event::Revealed(ALICE, 0)));
+		assert_eq!(
+			RandomnessBeaconModule::commitments(ALICE),
+			Some((RandomnessBeaconModule::commitment_of(&ALICE, &ALICE_SECRET), true, 100))
+		);
+		assert_eq!(RandomnessBeaconModule::round_entropy(), ALICE_SECRET);
+		assert_eq!(PalletBalances::reserved_balance(ALICE), 0);
+
+		assert_noop!(
+			RandomnessBeaconModule::reveal(Origin::signed(ALICE), ALICE_SECRET),
+			Error::<Runtime>::AlreadyRevealed
+		);
+	});
+}
+
+#[test]
+fn finalize_round_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(ALICE),
+			RandomnessBeaconModule::commitment_of(&ALICE, &ALICE_SECRET)
+		));
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(BOB),
+			RandomnessBeaconModule::commitment_of(&BOB, &BOB_SECRET)
+		));
+
+		System::set_block_number(10);
+		assert_ok!(RandomnessBeaconModule::reveal(Origin::signed(ALICE), ALICE_SECRET));
+
+		assert_eq!(RandomnessBeaconModule::on_initialize(19), 0);
+		RandomnessBeaconModule::on_finalize(19);
+		assert_eq!(RandomnessBeaconModule::current_round(), 0);
+
+		assert_eq!(
+			RandomnessBeaconModule::on_initialize(20),
+			<() as WeightInfo>::on_initialize(2)
+		);
+		RandomnessBeaconModule::on_finalize(20);
+		let (external_randomness, _) = MockExternalRandomness::random(&0u32.encode());
+		let seed = <Runtime as frame_system::Config>::Hashing::hash_of(&(
+			H256::default(),
+			ALICE_SECRET,
+			external_randomness,
+			0u32,
+		));
+		System::assert_has_event(Event::RandomnessBeaconModule(crate::Event::CommitmentSlashed(
+			BOB, 0, 200,
+		)));
+		System::assert_last_event(Event::RandomnessBeaconModule(crate::Event::RoundFinalized(0, seed)));
+		assert_eq!(PalletBalances::free_balance(BOB), 800);
+		assert_eq!(PalletBalances::reserved_balance(BOB), 0);
+		assert_eq!(RandomnessBeaconModule::commitments(BOB), None);
+		assert_eq!(RandomnessBeaconModule::commitment_count(), 0);
+		assert_eq!(RandomnessBeaconModule::round_entropy(), [0u8; 32]);
+		assert_eq!(RandomnessBeaconModule::random_seed(), (seed, 20));
+		assert_eq!(RandomnessBeaconModule::current_round(), 1);
+		assert_eq!(RandomnessBeaconModule::round_start(), 20);
+
+		System::set_block_number(21);
+		assert_ok!(RandomnessBeaconModule::commit(
+			Origin::signed(BOB),
+			RandomnessBeaconModule::commitment_of(&BOB, &BOB_SECRET)
+		));
+		System::assert_last_event(Event::RandomnessBeaconModule(crate::Event::Committed(BOB, 1)));
+	});
+}
+
+#[test]
+fn random_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (before, finalized_at) = RandomnessBeaconModule::random(b"lottery");
+		assert_eq!(finalized_at, 0);
+
+		RandomnessBeaconModule::on_finalize(20);
+		let (seed, _) = RandomnessBeaconModule::random_seed();
+		let (random, finalized_at) = RandomnessBeaconModule::random(b"lottery");
+		assert_eq!(finalized_at, 20);
+		assert_ne!(random, before);
+		assert_eq!(
+			random,
+			<Runtime as frame_system::Config>::Hashing::hash_of(&(seed, b"lottery".as_ref()))
+		);
+		assert_ne!(RandomnessBeaconModule::random(b"raffle").0, random);
+	});
+}
+
+#[test]
+fn seed_unpredictable_without_reveals() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(20);
+		EXTERNAL_RANDOMNESS.with(|v| *v.borrow_mut() = H256::repeat_byte(1));
+		RandomnessBeaconModule::on_finalize(20);
+
+		// the seed isn't determined by the previous seed and the round alone
+		let (seed, _) = RandomnessBeaconModule::random_seed();
+		let (external_randomness, _) = MockExternalRandomness::random(&0u32.encode());
+		assert_eq!(
+			seed,
+			<Runtime as frame_system::Config>::Hashing::hash_of(&(
+				H256::default(),
+				[0u8; 32],
+				external_randomness,
+				0u32
+			))
+		);
+		assert_ne!(
+			seed,
+			<Runtime as frame_system::Config>::Hashing::hash_of(&(H256::default(), [0u8; 32], 0u32))
+		);
+	});
+}
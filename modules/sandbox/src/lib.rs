@@ -0,0 +1,267 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Sandbox Module
+//!
+//! ## Overview
+//!
+//! Sandbox module is for testnet only, it must never be included in a
+//! production runtime. It allows the sandbox origin to simulate mainnet
+//! incidents, so that the incident-response runbooks can be rehearsed end to
+//! end without hacking nodes:
+//! - fast-forward the staking eras.
+//! - force the price of a currency, which overrides the oracle by
+//!   `ForcedPriceProvider`.
+//! - trigger a liquidation wave of the unsafe CDPs of a collateral type.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use orml_traits::{DataFeeder, DataProvider, Happened};
+use primitives::CurrencyId;
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, prelude::*};
+use support::Price;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + cdp_engine::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may use the sandbox.
+		type SandboxOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Start a new staking era immediately
+		type OnFastForwardEra: Happened<Self::BlockNumber>;
+
+		/// Notify the price of a currency is forced, the memoized price of the
+		/// currency must be dropped
+		type OnPriceForced: Happened<CurrencyId>;
+
+		/// The maximum number of eras fast-forwarded in a call
+		#[pallet::constant]
+		type MaxFastForwardEras: Get<u32>;
+
+		/// The maximum number of CDPs liquidated in a liquidation wave
+		#[pallet::constant]
+		type MaxLiquidationWaveSize: Get<u32>;
+
+		/// The maximum number of CDPs scanned in a liquidation wave, the next
+		/// wave resumes from where it stopped
+		#[pallet::constant]
+		type MaxLiquidationWaveScan: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Exceed the maximum number of eras fast-forwarded in a call
+		TooManyEras,
+		/// Exceed the maximum number of CDPs liquidated in a liquidation wave
+		TooManyLiquidations,
+		/// The price is zero
+		InvalidPrice,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The eras are fast-forwarded. \[eras\]
+		ErasFastForwarded(u32),
+		/// The forced price of a currency updated. \[currency_id,
+		/// forced_price\]
+		PriceForced(CurrencyId, Option<Price>),
+		/// The liquidation wave is triggered. \[collateral_type,
+		/// liquidated_count\]
+		LiquidationWaveTriggered(CurrencyId, u32),
+	}
+
+	/// The forced prices overriding the oracle.
+	///
+	/// ForcedPrices: map CurrencyId => Option<Price>
+	#[pallet::storage]
+	#[pallet::getter(fn forced_prices)]
+	pub type ForcedPrices<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
+
+	/// The raw key of the last CDP scanned by the unfinished liquidation wave
+	/// of the collateral type.
+	///
+	/// LiquidationWaveStartKey: map CurrencyId => Option<Vec<u8>>
+	#[pallet::storage]
+	#[pallet::getter(fn liquidation_wave_start_key)]
+	pub type LiquidationWaveStartKey<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Vec<u8>, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Start `eras` new staking eras immediately.
+		///
+		/// The dispatch origin of this call must be `SandboxOrigin`.
+		///
+		/// - `eras`: the number of eras to fast-forward.
+		#[pallet::weight(<T as Config>::WeightInfo::fast_forward_eras(*eras))]
+		#[transactional]
+		pub fn fast_forward_eras(origin: OriginFor<T>, eras: u32) -> DispatchResult {
+			T::SandboxOrigin::ensure_origin(origin)?;
+			ensure!(eras <= T::MaxFastForwardEras::get(), Error::<T>::TooManyEras);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			for _ in 0..eras {
+				T::OnFastForwardEra::happened(&now);
+			}
+
+			Self::deposit_event(Event::ErasFastForwarded(eras));
+			Ok(())
+		}
+
+		/// Force the price of the currency, `None` means the oracle price is
+		/// used again.
+		///
+		/// The dispatch origin of this call must be `SandboxOrigin`.
+		///
+		/// - `currency_id`: the currency to force the price.
+		/// - `price`: the forced price.
+		#[pallet::weight(<T as Config>::WeightInfo::force_price())]
+		#[transactional]
+		pub fn force_price(origin: OriginFor<T>, currency_id: CurrencyId, price: Option<Price>) -> DispatchResult {
+			T::SandboxOrigin::ensure_origin(origin)?;
+			ensure!(price.map_or(true, |p| !p.is_zero()), Error::<T>::InvalidPrice);
+			ForcedPrices::<T>::mutate_exists(currency_id, |maybe_price| *maybe_price = price);
+			T::OnPriceForced::happened(&currency_id);
+			Self::deposit_event(Event::PriceForced(currency_id, price));
+			Ok(())
+		}
+
+		/// Liquidate the unsafe CDPs of the collateral type, at most
+		/// `max_count` CDPs. At most `MaxLiquidationWaveScan` CDPs are scanned,
+		/// the next wave resumes from where it stopped.
+		///
+		/// The dispatch origin of this call must be `SandboxOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `max_count`: the maximum number of CDPs to liquidate.
+		#[pallet::weight(<T as Config>::WeightInfo::trigger_liquidation_wave(*max_count)
+			.saturating_add(T::DbWeight::get().reads(T::MaxLiquidationWaveScan::get() as Weight)))]
+		#[transactional]
+		pub fn trigger_liquidation_wave(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			max_count: u32,
+		) -> DispatchResult {
+			T::SandboxOrigin::ensure_origin(origin)?;
+			ensure!(
+				max_count <= T::MaxLiquidationWaveSize::get(),
+				Error::<T>::TooManyLiquidations
+			);
+
+			let iterator = match Self::liquidation_wave_start_key(currency_id) {
+				Some(key) => <loans::Positions<T>>::iter_prefix_from(currency_id, key),
+				None => <loans::Positions<T>>::iter_prefix(currency_id),
+			};
+			let mut unsafe_cdps: Vec<T::AccountId> = vec![];
+			let mut scanned_count: u32 = 0;
+			let mut last_scanned = None;
+			let mut finished = true;
+			for (who, position) in iterator {
+				if scanned_count >= T::MaxLiquidationWaveScan::get() || unsafe_cdps.len() as u32 >= max_count {
+					finished = false;
+					break;
+				}
+				scanned_count += 1;
+				if matches!(
+					<cdp_engine::Pallet<T>>::check_cdp_status(currency_id, position.collateral, position.debit),
+					cdp_engine::CDPStatus::Unsafe
+				) {
+					unsafe_cdps.push(who.clone());
+				}
+				last_scanned = Some(who);
+			}
+
+			match (finished, last_scanned) {
+				(true, _) => LiquidationWaveStartKey::<T>::remove(currency_id),
+				(false, Some(who)) => LiquidationWaveStartKey::<T>::insert(
+					currency_id,
+					<loans::Positions<T>>::hashed_key_for(currency_id, who),
+				),
+				// nothing is scanned, resume from the same key
+				(false, None) => {}
+			}
+
+			let mut liquidated_count: u32 = 0;
+			for who in unsafe_cdps {
+				match Self::liquidate(who.clone(), currency_id) {
+					Ok(_) => liquidated_count += 1,
+					Err(e) => log::debug!(
+						target: "sandbox",
+						"trigger_liquidation_wave: liquidate CDP {:?} {:?} failed: {:?}",
+						who, currency_id, e
+					),
+				}
+			}
+
+			Self::deposit_event(Event::LiquidationWaveTriggered(currency_id, liquidated_count));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	#[transactional]
+	fn liquidate(who: T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+		<cdp_engine::Pallet<T>>::liquidate_unsafe_cdp(who, currency_id)?;
+		Ok(())
+	}
+}
+
+/// The price provider using the forced price of the sandbox if it's set,
+/// otherwise the price of `Source`.
+pub struct ForcedPriceProvider<T, Source>(PhantomData<(T, Source)>);
+impl<T: Config, Source: DataProvider<CurrencyId, Price>> DataProvider<CurrencyId, Price>
+	for ForcedPriceProvider<T, Source>
+{
+	fn get(currency_id: &CurrencyId) -> Option<Price> {
+		Pallet::<T>::forced_prices(currency_id).or_else(|| Source::get(currency_id))
+	}
+}
+
+impl<T: Config, Source: DataFeeder<CurrencyId, Price, T::AccountId>> DataFeeder<CurrencyId, Price, T::AccountId>
+	for ForcedPriceProvider<T, Source>
+{
+	fn feed_value(who: T::AccountId, currency_id: CurrencyId, price: Price) -> DispatchResult {
+		Source::feed_value(who, currency_id, price)
+	}
+}
@@ -0,0 +1,160 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the sandbox module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::Change;
+use sp_runtime::{traits::BadOrigin, FixedPointNumber};
+use support::{PriceProvider, Rate, Ratio};
+
+fn setup_collateral_params() {
+	assert_ok!(CDPEngineModule::set_collateral_params(
+		Origin::signed(1),
+		BTC,
+		Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+		Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+		Change::NewValue(10000),
+	));
+}
+
+#[test]
+fn fast_forward_eras_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(SandboxModule::fast_forward_eras(Origin::signed(5), 3), BadOrigin);
+		assert_noop!(
+			SandboxModule::fast_forward_eras(Origin::signed(1), 11),
+			Error::<Runtime>::TooManyEras
+		);
+
+		assert_ok!(SandboxModule::fast_forward_eras(Origin::signed(1), 3));
+		System::assert_last_event(Event::SandboxModule(crate::Event::ErasFastForwarded(3)));
+		assert_eq!(forced_eras(), 3);
+	});
+}
+
+#[test]
+fn force_price_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SandboxModule::force_price(Origin::signed(5), BTC, Some(Price::saturating_from_integer(2))),
+			BadOrigin
+		);
+		assert_noop!(
+			SandboxModule::force_price(Origin::signed(1), BTC, Some(Price::zero())),
+			Error::<Runtime>::InvalidPrice
+		);
+		assert_eq!(MockPriceSource::get_price(BTC), Some(Price::one()));
+
+		assert_ok!(SandboxModule::force_price(
+			Origin::signed(1),
+			BTC,
+			Some(Price::saturating_from_integer(2))
+		));
+		System::assert_last_event(Event::SandboxModule(crate::Event::PriceForced(
+			BTC,
+			Some(Price::saturating_from_integer(2)),
+		)));
+		assert_eq!(
+			SandboxModule::forced_prices(BTC),
+			Some(Price::saturating_from_integer(2))
+		);
+		assert_eq!(MockPriceSource::get_price(BTC), Some(Price::saturating_from_integer(2)));
+		assert_eq!(MockPriceSource::get_price(DOT), Some(Price::one()));
+		assert_eq!(price_forced_currencies(), vec![BTC]);
+
+		assert_ok!(SandboxModule::force_price(Origin::signed(1), BTC, None));
+		System::assert_last_event(Event::SandboxModule(crate::Event::PriceForced(BTC, None)));
+		assert_eq!(SandboxModule::forced_prices(BTC), None);
+		assert_eq!(MockPriceSource::get_price(BTC), Some(Price::one()));
+		assert_eq!(price_forced_currencies(), vec![BTC, BTC]);
+	});
+}
+
+#[test]
+fn trigger_liquidation_wave_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 20));
+
+		assert_noop!(
+			SandboxModule::trigger_liquidation_wave(Origin::signed(5), BTC, 10),
+			BadOrigin
+		);
+		assert_noop!(
+			SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 51),
+			Error::<Runtime>::TooManyLiquidations
+		);
+
+		// all CDPs are safe at the oracle price
+		assert_ok!(SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 10));
+		System::assert_last_event(Event::SandboxModule(crate::Event::LiquidationWaveTriggered(BTC, 0)));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+
+		// the CDP of ALICE becomes unsafe at the forced price
+		assert_ok!(SandboxModule::force_price(
+			Origin::signed(1),
+			BTC,
+			Some(Price::saturating_from_rational(1, 2))
+		));
+		assert_ok!(SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 0));
+		System::assert_last_event(Event::SandboxModule(crate::Event::LiquidationWaveTriggered(BTC, 0)));
+		assert_eq!(LoansModule::positions(BTC, ALICE).debit, 50);
+
+		assert_ok!(SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 10));
+		System::assert_last_event(Event::SandboxModule(crate::Event::LiquidationWaveTriggered(BTC, 1)));
+		assert_eq!(LoansModule::positions(BTC, ALICE), loans::Position::default());
+		assert_eq!(LoansModule::positions(BTC, BOB).debit, 20);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+	});
+}
+
+#[test]
+fn trigger_liquidation_wave_resume_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, BTC, 100, 50));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, BTC, 100, 50));
+		assert_ok!(CDPEngineModule::adjust_position(&CAROL, BTC, 100, 50));
+		assert_ok!(SandboxModule::force_price(
+			Origin::signed(1),
+			BTC,
+			Some(Price::saturating_from_rational(1, 2))
+		));
+
+		// at most 2 CDPs are scanned in a wave
+		assert_ok!(SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 10));
+		System::assert_last_event(Event::SandboxModule(crate::Event::LiquidationWaveTriggered(BTC, 2)));
+		assert!(SandboxModule::liquidation_wave_start_key(BTC).is_some());
+
+		// the next wave resumes from where it stopped
+		assert_ok!(SandboxModule::trigger_liquidation_wave(Origin::signed(1), BTC, 10));
+		System::assert_last_event(Event::SandboxModule(crate::Event::LiquidationWaveTriggered(BTC, 1)));
+		assert_eq!(SandboxModule::liquidation_wave_start_key(BTC), None);
+		assert_eq!(LoansModule::positions(BTC, ALICE), loans::Position::default());
+		assert_eq!(LoansModule::positions(BTC, BOB), loans::Position::default());
+		assert_eq!(LoansModule::positions(BTC, CAROL), loans::Position::default());
+	});
+}
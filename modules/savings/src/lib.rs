@@ -0,0 +1,262 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Savings Module
+//!
+//! ## Overview
+//!
+//! Savings module lets users deposit the stable currency and earn the savings
+//! rate set by governance. The deposits are tracked in shares of the savings
+//! pool, and the savings index, the stable currency amount of a share, grows
+//! by the savings rate at the beginning of each block. The interest is paid
+//! from the surplus pool of CDP treasury, and the interest of a block is
+//! capped by the surplus available after the debit pool is offset, so the
+//! savings never drain the surplus backing the bad debit. The deposits can be withdrawn at
+//! any time.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating, Zero},
+	FixedPointNumber,
+};
+use support::{CDPTreasury, ExchangeRate, Rate};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to move the stable currency
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// CDP treasury to pay the interest from the surplus pool
+		type CDPTreasury: CDPTreasury<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The origin which may update the savings rate
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The savings module id, keep the deposits and the interest paid
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultSavingsIndex() -> ExchangeRate {
+		ExchangeRate::one()
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The amount is too small to get any share
+		InvalidAmount,
+		/// The shares of the account are not enough
+		NotEnoughShares,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The stable currency is deposited. \[who, amount, shares\]
+		Deposited(T::AccountId, Balance, Balance),
+		/// The stable currency is withdrawn. \[who, amount, shares\]
+		Withdrawn(T::AccountId, Balance, Balance),
+		/// The savings rate per block is updated. \[savings_rate\]
+		SavingsRateUpdated(Rate),
+		/// The interest is paid from the surplus pool. \[amount,
+		/// new_savings_index\]
+		InterestAccrued(Balance, ExchangeRate),
+	}
+
+	/// The savings rate per block.
+	///
+	/// SavingsRate: Rate
+	#[pallet::storage]
+	#[pallet::getter(fn savings_rate)]
+	pub type SavingsRate<T: Config> = StorageValue<_, Rate, ValueQuery>;
+
+	/// The stable currency amount of a share, starts at one.
+	///
+	/// SavingsIndex: ExchangeRate
+	#[pallet::storage]
+	#[pallet::getter(fn savings_index)]
+	pub type SavingsIndex<T: Config> = StorageValue<_, ExchangeRate, ValueQuery, DefaultSavingsIndex>;
+
+	/// The shares of the accounts.
+	///
+	/// Shares: map AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn shares)]
+	pub type Shares<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Balance, ValueQuery>;
+
+	/// The total shares of the savings pool.
+	///
+	/// TotalShares: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_shares)]
+	pub type TotalShares<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Accrue the interest of the block.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			if Self::accrue_interest() {
+				T::WeightInfo::on_initialize_with_accrual()
+			} else {
+				T::WeightInfo::on_initialize()
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Deposit the stable currency into the savings pool.
+		///
+		/// - `amount`: the stable currency amount to deposit.
+		#[pallet::weight(<T as Config>::WeightInfo::deposit())]
+		#[transactional]
+		pub fn deposit(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let shares = Self::savings_index()
+				.reciprocal()
+				.map(|rate| rate.saturating_mul_int(amount))
+				.unwrap_or_default();
+			ensure!(!shares.is_zero(), Error::<T>::InvalidAmount);
+
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &who, &Self::account_id(), amount)?;
+			Shares::<T>::mutate(&who, |balance| *balance = balance.saturating_add(shares));
+			TotalShares::<T>::mutate(|total| *total = total.saturating_add(shares));
+			Self::deposit_event(Event::Deposited(who, amount, shares));
+			Ok(())
+		}
+
+		/// Withdraw the stable currency of the shares from the savings pool.
+		///
+		/// - `shares`: the shares to redeem.
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
+		#[transactional]
+		pub fn withdraw(origin: OriginFor<T>, #[pallet::compact] shares: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Shares::<T>::try_mutate_exists(&who, |maybe_shares| -> DispatchResult {
+				let balance = maybe_shares.take().unwrap_or_default();
+				ensure!(balance >= shares, Error::<T>::NotEnoughShares);
+				let remainder = balance.saturating_sub(shares);
+				*maybe_shares = if remainder.is_zero() { None } else { Some(remainder) };
+				Ok(())
+			})?;
+			TotalShares::<T>::mutate(|total| *total = total.saturating_sub(shares));
+
+			let amount = Self::savings_index().saturating_mul_int(shares);
+			T::Currency::transfer(T::GetStableCurrencyId::get(), &Self::account_id(), &who, amount)?;
+			Self::deposit_event(Event::Withdrawn(who, amount, shares));
+			Ok(())
+		}
+
+		/// Update the savings rate per block.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `savings_rate`: the savings rate per block.
+		#[pallet::weight((<T as Config>::WeightInfo::set_savings_rate(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_savings_rate(origin: OriginFor<T>, savings_rate: Rate) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			SavingsRate::<T>::put(savings_rate);
+			Self::deposit_event(Event::SavingsRateUpdated(savings_rate));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of savings module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Get the stable currency amount of the shares of `who`.
+	pub fn balance_of(who: &T::AccountId) -> Balance {
+		Self::savings_index().saturating_mul_int(Self::shares(who))
+	}
+
+	/// Pay the interest of the block from the surplus pool and grow the
+	/// savings index accordingly. Return true if any interest is paid.
+	fn accrue_interest() -> bool {
+		let total_shares = Self::total_shares();
+		let savings_rate = Self::savings_rate();
+		if total_shares.is_zero() || savings_rate.is_zero() {
+			return false;
+		}
+
+		let savings_index = Self::savings_index();
+		let available_surplus = T::CDPTreasury::get_surplus_pool().saturating_sub(T::CDPTreasury::get_debit_pool());
+		let interest = savings_index
+			.saturating_mul(savings_rate)
+			.saturating_mul_int(total_shares)
+			.min(available_surplus);
+		// round down the growth of the index, so that the interest paid always
+		// covers the shares
+		let index_growth = ExchangeRate::checked_from_rational(interest, total_shares).unwrap_or_default();
+		if index_growth.is_zero() {
+			return false;
+		}
+
+		match T::CDPTreasury::withdraw_surplus(&Self::account_id(), interest) {
+			Ok(_) => {
+				let new_savings_index = savings_index.saturating_add(index_growth);
+				SavingsIndex::<T>::put(new_savings_index);
+				Self::deposit_event(Event::InterestAccrued(interest, new_savings_index));
+				true
+			}
+			Err(e) => {
+				log::warn!(
+					target: "savings",
+					"withdraw_surplus: failed to pay the interest {:?}: {:?}. \
+					This is unexpected but should be safe",
+					interest, e
+				);
+				false
+			}
+		}
+	}
+}
@@ -0,0 +1,134 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the savings module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn set_savings_rate_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SavingsModule::set_savings_rate(Origin::signed(BOB), Rate::saturating_from_rational(1, 100)),
+			BadOrigin
+		);
+
+		assert_ok!(SavingsModule::set_savings_rate(
+			Origin::signed(ALICE),
+			Rate::saturating_from_rational(1, 100)
+		));
+		System::assert_last_event(Event::SavingsModule(crate::Event::SavingsRateUpdated(
+			Rate::saturating_from_rational(1, 100),
+		)));
+		assert_eq!(SavingsModule::savings_rate(), Rate::saturating_from_rational(1, 100));
+	});
+}
+
+#[test]
+fn deposit_and_withdraw_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SavingsModule::deposit(Origin::signed(ALICE), 0),
+			Error::<Runtime>::InvalidAmount
+		);
+
+		assert_ok!(SavingsModule::deposit(Origin::signed(ALICE), 100));
+		System::assert_last_event(Event::SavingsModule(crate::Event::Deposited(ALICE, 100, 100)));
+		assert_eq!(SavingsModule::shares(ALICE), 100);
+		assert_eq!(SavingsModule::total_shares(), 100);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(AUSD, &SavingsModule::account_id()), 100);
+
+		assert_noop!(
+			SavingsModule::withdraw(Origin::signed(ALICE), 101),
+			Error::<Runtime>::NotEnoughShares
+		);
+		assert_noop!(
+			SavingsModule::withdraw(Origin::signed(BOB), 1),
+			Error::<Runtime>::NotEnoughShares
+		);
+
+		assert_ok!(SavingsModule::withdraw(Origin::signed(ALICE), 40));
+		System::assert_last_event(Event::SavingsModule(crate::Event::Withdrawn(ALICE, 40, 40)));
+		assert_eq!(SavingsModule::shares(ALICE), 60);
+		assert_eq!(SavingsModule::total_shares(), 60);
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 940);
+		assert_eq!(Tokens::free_balance(AUSD, &SavingsModule::account_id()), 60);
+	});
+}
+
+#[test]
+fn accrue_interest_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SavingsModule::set_savings_rate(
+			Origin::signed(ALICE),
+			Rate::saturating_from_rational(1, 100)
+		));
+		assert_ok!(SavingsModule::deposit(Origin::signed(ALICE), 100));
+
+		// no surplus is left after the debit pool is offset
+		set_debit_pool(1000);
+		SavingsModule::on_initialize(2);
+		assert_eq!(SavingsModule::savings_index(), ExchangeRate::one());
+
+		set_debit_pool(0);
+		SavingsModule::on_initialize(3);
+		System::assert_last_event(Event::SavingsModule(crate::Event::InterestAccrued(
+			1,
+			ExchangeRate::saturating_from_rational(101, 100),
+		)));
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 999);
+		assert_eq!(SavingsModule::balance_of(&ALICE), 101);
+
+		// capped by the surplus left after the debit pool is offset
+		assert_ok!(SavingsModule::set_savings_rate(
+			Origin::signed(ALICE),
+			Rate::saturating_from_rational(1, 10)
+		));
+		set_debit_pool(998);
+		SavingsModule::on_initialize(4);
+		System::assert_last_event(Event::SavingsModule(crate::Event::InterestAccrued(
+			1,
+			ExchangeRate::saturating_from_rational(102, 100),
+		)));
+		assert_eq!(Tokens::free_balance(AUSD, &TREASURY), 998);
+
+		// the surplus leaving the treasury directly is not paid
+		set_debit_pool(0);
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			AUSD, &TREASURY, &BOB, 998
+		));
+		SavingsModule::on_initialize(5);
+		assert_eq!(
+			SavingsModule::savings_index(),
+			ExchangeRate::saturating_from_rational(102, 100)
+		);
+
+		// the interest is withdrawn with the deposit
+		assert_ok!(SavingsModule::withdraw(Origin::signed(ALICE), 100));
+		System::assert_last_event(Event::SavingsModule(crate::Event::Withdrawn(ALICE, 102, 100)));
+		assert_eq!(Tokens::free_balance(AUSD, &ALICE), 1002);
+		assert_eq!(Tokens::free_balance(AUSD, &SavingsModule::account_id()), 0);
+		assert_eq!(SavingsModule::total_shares(), 0);
+	});
+}
@@ -0,0 +1,149 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Stablecoin Metrics Module
+//!
+//! ## Overview
+//!
+//! Stablecoin metrics module records the velocity of stablecoin, the count and
+//! volume of stablecoin transfers in each block, for the analysis of the peg
+//! and adoption. Transfers from or to system accounts (module accounts) are
+//! internal flows of the protocol, and are excluded from the metrics. Only the
+//! metrics of the last `WindowSize` blocks are kept.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, traits::Contains};
+use frame_system::pallet_prelude::*;
+use primitives::{Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{One, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::OnTransfer;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The stablecoin transfer metrics of a block
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct VelocityStats {
+	/// The count of transfers
+	pub transfer_count: u32,
+	/// The total amount of transfers
+	pub volume: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The stable currency id
+		#[pallet::constant]
+		type GetStableCurrencyId: Get<CurrencyId>;
+
+		/// The system accounts whose transfers are excluded from the metrics
+		type SystemAccounts: Contains<Self::AccountId>;
+
+		/// The number of blocks to keep the metrics
+		#[pallet::constant]
+		type WindowSize: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The stablecoin transfer metrics of the blocks in the window.
+	///
+	/// BlockVelocity: map BlockNumber => VelocityStats
+	#[pallet::storage]
+	#[pallet::getter(fn block_velocity)]
+	pub type BlockVelocity<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, VelocityStats, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Remove the metrics of the block moved out of the window. The metrics
+		/// of the block are also written by the transfers not weighted by
+		/// their callers, pre-charge the DB read and write of them here.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let window_size: T::BlockNumber = T::WindowSize::get().into();
+			if now >= window_size {
+				BlockVelocity::<T>::remove(now - window_size);
+			}
+			T::WeightInfo::on_initialize().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the stablecoin transfer count and volume of each block of the last
+	/// `blocks` blocks, including current block, in ascending order of the
+	/// block number.
+	pub fn velocity_report(blocks: u32) -> Vec<(T::BlockNumber, u32, Balance)> {
+		let blocks = blocks.min(T::WindowSize::get());
+		if blocks.is_zero() {
+			return vec![];
+		}
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let mut block_number = now.saturating_sub(T::BlockNumber::from(blocks - 1));
+		let mut report = vec![];
+		while block_number <= now {
+			let stats = Self::block_velocity(block_number);
+			report.push((block_number, stats.transfer_count, stats.volume));
+			block_number = block_number.saturating_add(One::one());
+		}
+		report
+	}
+}
+
+impl<T: Config> OnTransfer<T::AccountId, CurrencyId, Balance> for Pallet<T> {
+	fn on_transfer(currency_id: CurrencyId, from: &T::AccountId, to: &T::AccountId, amount: Balance) {
+		if currency_id != T::GetStableCurrencyId::get()
+			|| amount.is_zero()
+			|| T::SystemAccounts::contains(from)
+			|| T::SystemAccounts::contains(to)
+		{
+			return;
+		}
+
+		BlockVelocity::<T>::mutate(frame_system::Pallet::<T>::block_number(), |stats| {
+			stats.transfer_count = stats.transfer_count.saturating_add(1);
+			stats.volume = stats.volume.saturating_add(amount);
+		});
+	}
+
+	fn on_transfer_weight() -> Weight {
+		T::WeightInfo::on_transfer()
+	}
+}
@@ -0,0 +1,88 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the stablecoin metrics module.
+
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+
+#[test]
+fn on_transfer_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &BOB, 100);
+		StablecoinMetricsModule::on_transfer(AUSD, &BOB, &ALICE, 50);
+		assert_eq!(
+			StablecoinMetricsModule::block_velocity(1),
+			VelocityStats {
+				transfer_count: 2,
+				volume: 150
+			}
+		);
+
+		// ignore other currencies, empty transfers and transfers of system accounts
+		StablecoinMetricsModule::on_transfer(ACA, &ALICE, &BOB, 100);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &BOB, 0);
+		StablecoinMetricsModule::on_transfer(AUSD, &SYSTEM, &BOB, 100);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &SYSTEM, 100);
+		assert_eq!(
+			StablecoinMetricsModule::block_velocity(1),
+			VelocityStats {
+				transfer_count: 2,
+				volume: 150
+			}
+		);
+		assert_eq!(
+			<StablecoinMetricsModule as OnTransfer<_, _, _>>::on_transfer_weight(),
+			<() as WeightInfo>::on_transfer()
+		);
+	});
+}
+
+#[test]
+fn velocity_report_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(StablecoinMetricsModule::velocity_report(0), vec![]);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &BOB, 100);
+
+		System::set_block_number(2);
+		StablecoinMetricsModule::on_initialize(2);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &BOB, 30);
+		StablecoinMetricsModule::on_transfer(AUSD, &ALICE, &BOB, 20);
+		assert_eq!(
+			StablecoinMetricsModule::velocity_report(5),
+			vec![(0, 0, 0), (1, 1, 100), (2, 2, 50)]
+		);
+
+		System::set_block_number(3);
+		StablecoinMetricsModule::on_initialize(3);
+		assert_eq!(StablecoinMetricsModule::velocity_report(2), vec![(2, 2, 50), (3, 0, 0)]);
+
+		// the metrics of the block moved out of the window are removed
+		System::set_block_number(4);
+		StablecoinMetricsModule::on_initialize(4);
+		assert_eq!(StablecoinMetricsModule::block_velocity(1), Default::default());
+		assert_eq!(
+			StablecoinMetricsModule::velocity_report(3),
+			vec![(2, 2, 50), (3, 0, 0), (4, 0, 0)]
+		);
+	});
+}
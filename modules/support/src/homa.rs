@@ -20,7 +20,7 @@ use super::*;
 use frame_support::{traits::Get, Parameter};
 use sp_runtime::{
 	traits::{MaybeDisplay, MaybeSerializeDeserialize, Member},
-	RuntimeDebug,
+	Permill, RuntimeDebug,
 };
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
@@ -94,3 +94,15 @@ pub trait HomaProtocol<AccountId, Balance, EraIndex> {
 	fn redeem_by_claim_unbonding(who: &AccountId, amount: Balance, target_era: EraIndex) -> DispatchResult;
 	fn withdraw_redemption(who: &AccountId) -> sp_std::result::Result<Balance, DispatchError>;
 }
+
+/// An abstraction of the liquid staking of Homa protocol, which mints liquid
+/// currency for staking currency and redeems it back.
+pub trait HomaManager<AccountId, Balance> {
+	/// Mint liquid currency by staking `amount` of staking currency.
+	fn mint(who: &AccountId, amount: Balance) -> DispatchResult;
+	/// Request to redeem `amount` of liquid currency, `fast_match_fee_rate`
+	/// is the extra fee rate rewarded to the minter who matches the request.
+	fn request_redeem(who: &AccountId, amount: Balance, fast_match_fee_rate: Permill) -> DispatchResult;
+	/// Get the exchange rate of staking currency to liquid currency.
+	fn get_exchange_rate() -> ExchangeRate;
+}
@@ -0,0 +1,288 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Hook registry with explicit ordering and weight budgets.
+//!
+//! Handlers plugged into a hook as a tuple are called in the order of the
+//! tuple, and nothing bounds the weight of each of them. `HookRegistry`
+//! instead collects the hooks registered by `OrderedHook`, and calls them in
+//! ascending order of the runtime configured `Order`. A hook whose weight
+//! exceeds its `WeightBudget` is deferred, the args are queued and the unused
+//! budget is carried over to the following executions of the registry, until
+//! the carried budget covers the queued executions. The registry refuses to be
+//! reentered by the hooks it's calling.
+
+use super::*;
+use codec::{Decode, Encode};
+use frame_support::{log, storage::unhashed, traits::Get};
+use orml_traits::OnDust;
+use sp_std::{any::type_name, marker::PhantomData};
+
+/// The hook which could be registered in `HookRegistry`.
+pub trait Hook<Args> {
+	/// The weight of executing the hook with `args`, it's checked against the
+	/// weight budget before the execution.
+	fn weight(args: &Args) -> Weight;
+	/// Execute the hook with `args`.
+	fn execute(args: &Args);
+}
+
+/// The hook collected by `HookRegistry`.
+pub struct HookEntry<Args> {
+	/// The execution order, the lower one is called earlier.
+	pub order: u32,
+	/// The max weight the hook is allowed to consume.
+	pub weight_budget: Weight,
+	pub weight: fn(&Args) -> Weight,
+	pub execute: fn(&Args),
+}
+
+/// A set of hooks to be collected by `HookRegistry`.
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+pub trait HookSet<Args> {
+	fn collect(entries: &mut Vec<HookEntry<Args>>);
+}
+
+/// Register the hook `H` with its execution `Order` and `WeightBudget`.
+pub struct OrderedHook<H, Order, WeightBudget>(PhantomData<(H, Order, WeightBudget)>);
+
+impl<Args, H, Order, WeightBudget> HookSet<Args> for OrderedHook<H, Order, WeightBudget>
+where
+	H: Hook<Args>,
+	Order: Get<u32>,
+	WeightBudget: Get<Weight>,
+{
+	fn collect(entries: &mut Vec<HookEntry<Args>>) {
+		entries.push(HookEntry {
+			order: Order::get(),
+			weight_budget: WeightBudget::get(),
+			weight: H::weight,
+			execute: H::execute,
+		});
+	}
+}
+
+/// Call the registered `Hooks` in ascending order.
+pub struct HookRegistry<Hooks>(PhantomData<Hooks>);
+
+impl<Hooks: 'static> HookRegistry<Hooks> {
+	fn guard_key() -> Vec<u8> {
+		let mut key = b":acala:hook_registry:".to_vec();
+		key.extend_from_slice(&sp_io::hashing::twox_128(type_name::<Hooks>().as_bytes()));
+		key
+	}
+
+	/// The key of the carried budget and the deferred args of the hook
+	/// registered at `index`.
+	fn deferred_key(index: u32) -> Vec<u8> {
+		let mut key = Self::guard_key();
+		key.extend_from_slice(b":deferred:");
+		key.extend_from_slice(&index.encode());
+		key
+	}
+
+	/// Get the carried budget and the deferred args of the hook registered at
+	/// `index`.
+	pub fn deferred<Args: Decode>(index: u32) -> (Weight, Vec<Args>) {
+		unhashed::get(&Self::deferred_key(index)).unwrap_or((0, Vec::new()))
+	}
+
+	/// Execute the hooks with `args`, return the consumed weight.
+	///
+	/// Hooks with the same order are called in the order they're registered.
+	/// The deferred executions of a hook are done before the current one.
+	pub fn execute<Args>(args: &Args) -> Weight
+	where
+		Args: Clone + Encode + Decode,
+		Hooks: HookSet<Args>,
+	{
+		let guard_key = Self::guard_key();
+		if unhashed::exists(&guard_key) {
+			log::warn!(
+				target: "hook-registry",
+				"execute: reentered {:?}, the nested execution is ignored",
+				type_name::<Hooks>()
+			);
+			return 0;
+		}
+		unhashed::put(&guard_key, &());
+
+		let mut entries = Vec::new();
+		Hooks::collect(&mut entries);
+		let mut entries = entries
+			.into_iter()
+			.enumerate()
+			.map(|(index, entry)| (index as u32, entry))
+			.collect::<Vec<_>>();
+		// stable sort keeps the registration order of hooks with the same order
+		entries.sort_by_key(|(_, entry)| entry.order);
+
+		let mut consumed_weight: Weight = 0;
+		for (index, entry) in entries {
+			let (carried_budget, mut pending) = Self::deferred::<Args>(index);
+			let mut budget = carried_budget.saturating_add(entry.weight_budget);
+			pending.push(args.clone());
+
+			// execute the deferred args first, in the order they're queued
+			let mut executed = 0;
+			for pending_args in pending.iter() {
+				let weight = (entry.weight)(pending_args);
+				if weight > budget {
+					break;
+				}
+				(entry.execute)(pending_args);
+				budget = budget.saturating_sub(weight);
+				consumed_weight = consumed_weight.saturating_add(weight);
+				executed += 1;
+			}
+			pending.drain(..executed);
+
+			if pending.is_empty() {
+				// the unused budget is only carried over for the deferred executions
+				unhashed::kill(&Self::deferred_key(index));
+			} else {
+				log::warn!(
+					target: "hook-registry",
+					"execute: {:?} executions of hook with order {:?} are deferred, carried budget {:?}",
+					pending.len(),
+					entry.order,
+					budget
+				);
+				unhashed::put(&Self::deferred_key(index), &(budget, pending));
+			}
+		}
+
+		unhashed::kill(&guard_key);
+		consumed_weight
+	}
+}
+
+impl<EraIndex: Clone + Encode + Decode, Hooks: HookSet<EraIndex> + 'static> OnNewEra<EraIndex> for HookRegistry<Hooks> {
+	fn on_new_era(era: EraIndex) {
+		Self::execute(&era);
+	}
+}
+
+impl<AccountId, CurrencyId, Balance, Hooks> OnDust<AccountId, CurrencyId, Balance> for HookRegistry<Hooks>
+where
+	AccountId: Clone + Encode + Decode,
+	CurrencyId: Clone + Encode + Decode,
+	Balance: Clone + Encode + Decode,
+	Hooks: HookSet<(AccountId, CurrencyId, Balance)> + 'static,
+{
+	fn on_dust(who: &AccountId, currency_id: CurrencyId, amount: Balance) {
+		Self::execute(&(who.clone(), currency_id, amount));
+	}
+}
+
+/// Adapt the `OnNewEra` handler `H` to `Hook`, which takes `W` weight.
+pub struct NewEraHook<H, W>(PhantomData<(H, W)>);
+
+impl<EraIndex: Clone, H: OnNewEra<EraIndex>, W: Get<Weight>> Hook<EraIndex> for NewEraHook<H, W> {
+	fn weight(_era: &EraIndex) -> Weight {
+		W::get()
+	}
+
+	fn execute(era: &EraIndex) {
+		H::on_new_era(era.clone());
+	}
+}
+
+/// Adapt the `OnDust` handler `H` to `Hook`, which takes `W` weight.
+pub struct DustHook<H, W>(PhantomData<(H, W)>);
+
+impl<AccountId, CurrencyId, Balance, H, W> Hook<(AccountId, CurrencyId, Balance)> for DustHook<H, W>
+where
+	CurrencyId: Clone,
+	Balance: Clone,
+	H: OnDust<AccountId, CurrencyId, Balance>,
+	W: Get<Weight>,
+{
+	fn weight(_args: &(AccountId, CurrencyId, Balance)) -> Weight {
+		W::get()
+	}
+
+	fn execute((who, currency_id, amount): &(AccountId, CurrencyId, Balance)) {
+		H::on_dust(who, currency_id.clone(), amount.clone());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::parameter_types;
+	use sp_std::cell::RefCell;
+
+	thread_local! {
+		static EXECUTED: RefCell<Vec<(u32, u32)>> = RefCell::new(Vec::new());
+	}
+
+	fn executed() -> Vec<(u32, u32)> {
+		EXECUTED.with(|v| v.borrow().clone())
+	}
+
+	/// Records the execution with its id, weighs the args.
+	pub struct MockHook<Id>(PhantomData<Id>);
+	impl<Id: Get<u32>> Hook<u32> for MockHook<Id> {
+		fn weight(args: &u32) -> Weight {
+			*args as Weight
+		}
+
+		fn execute(args: &u32) {
+			EXECUTED.with(|v| v.borrow_mut().push((Id::get(), *args)));
+		}
+	}
+
+	parameter_types! {
+		pub const IdA: u32 = 1;
+		pub const IdB: u32 = 2;
+		pub const OrderFirst: u32 = 0;
+		pub const OrderSecond: u32 = 1;
+		pub const Budget: Weight = 10;
+	}
+
+	type Registry = HookRegistry<(
+		OrderedHook<MockHook<IdB>, OrderSecond, Budget>,
+		OrderedHook<MockHook<IdA>, OrderFirst, Budget>,
+	)>;
+
+	#[test]
+	fn execute_in_order() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_eq!(Registry::execute(&5), 10);
+			assert_eq!(executed(), vec![(1, 5), (2, 5)]);
+		});
+	}
+
+	#[test]
+	fn carry_over_hooks_exceeding_budget() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			// exceeds the budget, deferred with the budget carried over
+			assert_eq!(Registry::execute(&15), 0);
+			assert_eq!(executed(), vec![]);
+			assert_eq!(Registry::deferred::<u32>(0), (10, vec![15]));
+			assert_eq!(Registry::deferred::<u32>(1), (10, vec![15]));
+
+			// the deferred executions are done first once the budget covers them
+			assert_eq!(Registry::execute(&3), 36);
+			assert_eq!(executed(), vec![(1, 15), (1, 3), (2, 15), (2, 3)]);
+			assert_eq!(Registry::deferred::<u32>(0), (0, vec![]));
+			assert_eq!(Registry::deferred::<u32>(1), (0, vec![]));
+		});
+	}
+}
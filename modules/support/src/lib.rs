@@ -24,16 +24,17 @@ use frame_support::pallet_prelude::{DispatchClass, Pays, Weight};
 use primitives::{
 	evm::{CallInfo, EvmAddress},
 	task::TaskResult,
-	CurrencyId,
+	CircuitBreakerId, CircuitBreakerReason, CurrencyId, RevenueSource, StableAssetPoolId,
 };
-use sp_core::H160;
+use sp_core::{H160, U256};
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, CheckedDiv, MaybeSerializeDeserialize},
+	traits::{AtLeast32BitUnsigned, CheckedDiv, MaybeSerializeDeserialize, SaturatedConversion},
 	transaction_validity::TransactionValidityError,
 	DispatchError, DispatchResult, FixedU128, RuntimeDebug,
 };
 use sp_std::{
 	cmp::{Eq, PartialEq},
+	convert::TryInto,
 	fmt::Debug,
 	prelude::*,
 };
@@ -41,11 +42,15 @@ use sp_std::{
 use xcm::latest::prelude::*;
 
 pub mod homa;
+pub mod hooks;
 pub mod mocks;
+pub mod swap;
 pub use homa::{
-	HomaProtocol, NomineesProvider, OnCommission, OnNewEra, PolkadotBridge, PolkadotBridgeCall, PolkadotBridgeState,
-	PolkadotBridgeType, PolkadotStakingLedger, PolkadotUnlockChunk,
+	HomaManager, HomaProtocol, NomineesProvider, OnCommission, OnNewEra, PolkadotBridge, PolkadotBridgeCall,
+	PolkadotBridgeState, PolkadotBridgeType, PolkadotStakingLedger, PolkadotUnlockChunk,
 };
+pub use hooks::{DustHook, Hook, HookEntry, HookRegistry, HookSet, NewEraHook, OrderedHook};
+pub use swap::{AggregatedSwap, AggregatedSwapAdapter, SwapPath};
 
 pub type Price = FixedU128;
 pub type ExchangeRate = FixedU128;
@@ -62,7 +67,36 @@ pub trait RiskManager<AccountId, CurrencyId, Balance, DebitBalance> {
 		check_required_ratio: bool,
 	) -> DispatchResult;
 
+	/// check the position of `who` including the extra collaterals backing it,
+	/// same as `check_position_valid` if the extra collaterals are not
+	/// supported.
+	fn check_vault_valid(
+		currency_id: CurrencyId,
+		_who: &AccountId,
+		collateral_balance: Balance,
+		debit_balance: DebitBalance,
+		check_required_ratio: bool,
+	) -> DispatchResult {
+		Self::check_position_valid(currency_id, collateral_balance, debit_balance, check_required_ratio)
+	}
+
 	fn check_debit_cap(currency_id: CurrencyId, total_debit_balance: DebitBalance) -> DispatchResult;
+
+	/// check the debit of the position of `who` against the cap per account,
+	/// no cap if it's not supported.
+	fn check_debit_per_account(
+		_currency_id: CurrencyId,
+		_who: &AccountId,
+		_debit_balance: DebitBalance,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	/// get the debt denomination of the collateral type, `None` if its debit
+	/// is denominated in the stable currency.
+	fn get_debt_denomination(_currency_id: CurrencyId) -> Option<CurrencyId> {
+		None
+	}
 }
 
 #[cfg(feature = "std")]
@@ -112,6 +146,13 @@ pub trait DEXManager<AccountId, CurrencyId, Balance> {
 
 	fn get_swap_supply_amount(path: &[CurrencyId], target_amount: Balance) -> Option<Balance>;
 
+	/// Whether users can swap through `path` instantly. The swaps through
+	/// `DEXManager` are always executed instantly, the entry points of users
+	/// should check it.
+	fn is_instant_execution(_path: &[CurrencyId]) -> bool {
+		true
+	}
+
 	fn swap_with_exact_supply(
 		who: &AccountId,
 		path: &[CurrencyId],
@@ -211,6 +252,114 @@ where
 	}
 }
 
+/// The DEX prices accumulated over blocks, the time-weighted average price
+/// over a period is the difference of the cumulative prices at both ends
+/// divided by the blocks elapsed.
+pub trait DEXPriceCumulative<CurrencyId, BlockNumber> {
+	/// Get the cumulative price of `currency_id_a` in `currency_id_b` up to
+	/// the current block, and the current block number. The price changed in
+	/// a block is only accumulated from the next block.
+	fn get_cumulative_price(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<(U256, BlockNumber)>;
+}
+
+impl<CurrencyId, BlockNumber> DEXPriceCumulative<CurrencyId, BlockNumber> for () {
+	fn get_cumulative_price(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<(U256, BlockNumber)> {
+		None
+	}
+}
+
+/// Get the time-weighted average price between two observations of
+/// `DEXPriceCumulative`, `None` if no block elapsed between them.
+pub fn average_cumulative_price<BlockNumber: AtLeast32BitUnsigned>(
+	(last_cumulative_price, last_observed): (U256, BlockNumber),
+	(cumulative_price, now): (U256, BlockNumber),
+) -> Option<Price> {
+	let elapsed = U256::from(now.checked_sub(&last_observed)?.saturated_into::<u128>());
+	let average_inner = cumulative_price
+		.saturating_sub(last_cumulative_price)
+		.checked_div(elapsed)?;
+	Some(Price::from_inner(TryInto::<u128>::try_into(average_inner).ok()?))
+}
+
+/// An abstraction of stable swap pools, for modules which move liquidity or
+/// trades between them and the constant product DEX.
+pub trait StableAssetPool<AccountId, CurrencyId, Balance> {
+	/// Get the currencies of the pool, ordered by their index in the pool.
+	fn get_pool_assets(pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>>;
+
+	/// Mint pool token by supplying `amounts` of the pool currencies,
+	/// return the minted pool token amount.
+	fn mint(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amounts: Vec<Balance>,
+		min_mint_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError>;
+
+	/// Redeem `amount` of pool token for all pool currencies in proportion,
+	/// return the redeemed amounts ordered by currency index.
+	fn redeem_proportion(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amount: Balance,
+		min_redeem_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError>;
+
+	/// Swap `dx` of the currency at index `i` to the currency at index `j`,
+	/// return the actual target amount.
+	fn swap(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		i: u32,
+		j: u32,
+		dx: Balance,
+		min_dy: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError>;
+
+	/// Get the target amount of swapping `dx` of the currency at index `i` to
+	/// the currency at index `j`, `None` if the swap is not possible.
+	fn get_swap_output_amount(pool_id: StableAssetPoolId, i: u32, j: u32, dx: Balance) -> Option<Balance>;
+}
+
+impl<AccountId, CurrencyId, Balance> StableAssetPool<AccountId, CurrencyId, Balance> for () {
+	fn get_pool_assets(_pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>> {
+		None
+	}
+
+	fn mint(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amounts: Vec<Balance>,
+		_min_mint_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Err(DispatchError::Other("unimplemented stable asset pool"))
+	}
+
+	fn redeem_proportion(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_amount: Balance,
+		_min_redeem_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError> {
+		Err(DispatchError::Other("unimplemented stable asset pool"))
+	}
+
+	fn swap(
+		_who: &AccountId,
+		_pool_id: StableAssetPoolId,
+		_i: u32,
+		_j: u32,
+		_dx: Balance,
+		_min_dy: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Err(DispatchError::Other("unimplemented stable asset pool"))
+	}
+
+	fn get_swap_output_amount(_pool_id: StableAssetPoolId, _i: u32, _j: u32, _dx: Balance) -> Option<Balance> {
+		None
+	}
+}
+
 /// An abstraction of cdp treasury for Honzon Protocol.
 pub trait CDPTreasury<AccountId> {
 	type Balance;
@@ -245,11 +394,35 @@ pub trait CDPTreasury<AccountId> {
 	/// deposit surplus(stable currency) to cdp treasury by `from`
 	fn deposit_surplus(from: &AccountId, surplus: Self::Balance) -> DispatchResult;
 
+	/// withdraw surplus(stable currency) of cdp treasury to `to`
+	fn withdraw_surplus(to: &AccountId, surplus: Self::Balance) -> DispatchResult;
+
 	/// deposit collateral assets to cdp treasury by `who`
 	fn deposit_collateral(from: &AccountId, currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult;
 
 	/// withdraw collateral assets of cdp treasury to `who`
 	fn withdraw_collateral(to: &AccountId, currency_id: Self::CurrencyId, amount: Self::Balance) -> DispatchResult;
+
+	/// record the protocol revenue from `source` into the accounting of
+	/// current period
+	fn on_revenue(_source: RevenueSource, _amount: Self::Balance) {}
+
+	/// record the surplus generated by the collateral `currency_id`
+	fn on_collateral_surplus(_currency_id: Self::CurrencyId, _amount: Self::Balance) {}
+
+	/// record the liquidation `penalty` of the collateral `currency_id`
+	/// included in the `target` of its collateral auctions or sales, it's
+	/// recorded as surplus when realized
+	fn on_unrealized_collateral_surplus(
+		_currency_id: Self::CurrencyId,
+		_penalty: Self::Balance,
+		_target: Self::Balance,
+	) {
+	}
+
+	/// realize the surplus of the collateral `currency_id` when a collateral
+	/// auction or sale of `target` ends with `proceeds` raised
+	fn on_collateral_proceeds(_currency_id: Self::CurrencyId, _target: Self::Balance, _proceeds: Self::Balance) {}
 }
 
 pub trait CDPTreasuryExtended<AccountId>: CDPTreasury<AccountId> {
@@ -269,6 +442,19 @@ pub trait CDPTreasuryExtended<AccountId>: CDPTreasury<AccountId> {
 		collateral_in_auction: bool,
 	) -> sp_std::result::Result<Self::Balance, DispatchError>;
 
+	/// the weight of swapping collateral by the swap path of `path_len`
+	/// currencies
+	fn swap_collateral_to_stable_weight(path_len: u32) -> Weight;
+
+	/// swap collateral which not in auction to at least `target_amount` of
+	/// stable currency by the aggregated swap paths through the stable asset
+	/// pools, return actual supply collateral amount
+	fn swap_collateral_to_stable_by_aggregated_paths(
+		currency_id: Self::CurrencyId,
+		max_supply_amount: Self::Balance,
+		target_amount: Self::Balance,
+	) -> sp_std::result::Result<Self::Balance, DispatchError>;
+
 	fn create_collateral_auctions(
 		currency_id: Self::CurrencyId,
 		amount: Self::Balance,
@@ -277,9 +463,122 @@ pub trait CDPTreasuryExtended<AccountId>: CDPTreasury<AccountId> {
 		splited: bool,
 	) -> sp_std::result::Result<u32, DispatchError>;
 
+	/// sell collateral by a sale whose price decays over time instead of
+	/// auctions, return the id of the created sale
+	fn create_decreasing_price_sale(
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+		refund_receiver: AccountId,
+	) -> sp_std::result::Result<u32, DispatchError>;
+
+	/// get the swap path from the collateral to stable currency, the direct
+	/// path is used if it's not configured
+	fn get_collateral_swap_path(currency_id: Self::CurrencyId) -> Vec<Self::CurrencyId>;
+
+	/// whether the collateral type is always swapped by DEX instead of
+	/// auctions
+	fn is_auction_exempt(currency_id: Self::CurrencyId) -> bool;
+
 	fn max_auction() -> u32;
 }
 
+/// The accounting of the system debit and surplus per debt denomination, the
+/// stable currency is always a debt denomination and the others are enabled
+/// by governance.
+pub trait DebtDenominations<AccountId>: CDPTreasury<AccountId> {
+	/// whether the currency is an enabled debt denomination
+	fn is_debt_denomination(denomination: Self::CurrencyId) -> bool;
+
+	/// get the surplus pool of the debt denomination
+	fn get_surplus_pool_of(denomination: Self::CurrencyId) -> Self::Balance;
+
+	/// get the debit pool of the debt denomination
+	fn get_debit_pool_of(denomination: Self::CurrencyId) -> Self::Balance;
+
+	/// increase the debit pool of the debt denomination
+	fn on_system_debit_of(denomination: Self::CurrencyId, amount: Self::Balance) -> DispatchResult;
+
+	/// increase the surplus pool of the debt denomination
+	fn on_system_surplus_of(denomination: Self::CurrencyId, amount: Self::Balance) -> DispatchResult;
+
+	/// issue debit in the debt denomination to `who`, the debit pool of the
+	/// denomination is increased if it's unbacked
+	fn issue_debit_of(
+		denomination: Self::CurrencyId,
+		who: &AccountId,
+		debit: Self::Balance,
+		backed: bool,
+	) -> DispatchResult;
+
+	/// burn debit in the debt denomination of `who`
+	fn burn_debit_of(denomination: Self::CurrencyId, who: &AccountId, debit: Self::Balance) -> DispatchResult;
+
+	/// record a collateral type denominated in the debt denomination, it
+	/// can't be disabled until no collateral type is denominated in it
+	fn inc_denomination_consumers(denomination: Self::CurrencyId) -> DispatchResult;
+
+	/// record a collateral type no longer denominated in the debt
+	/// denomination
+	fn dec_denomination_consumers(denomination: Self::CurrencyId);
+
+	/// sell collateral by a decreasing price sale paid in the debt
+	/// denomination, return the id of the created sale
+	fn create_decreasing_price_sale_of(
+		denomination: Self::CurrencyId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+		refund_receiver: AccountId,
+	) -> sp_std::result::Result<u32, DispatchError>;
+}
+
+/// Lend assets for the duration of a single transaction.
+pub trait FlashLoan<AccountId, CurrencyId, Balance> {
+	/// The fee to borrow `amount` of `currency_id`, `None` if the flash loan
+	/// of it is not enabled.
+	fn flash_loan_fee(currency_id: CurrencyId, amount: Balance) -> Option<Balance>;
+
+	/// Lend `amount` of `currency_id` to `who`, execute `f`, then take the
+	/// loan and the fee back from `who`. Fails if `f` fails or the repayment
+	/// is not enough.
+	fn flash_loan(
+		who: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		f: impl FnOnce() -> DispatchResult,
+	) -> DispatchResult;
+}
+
+/// The earning strategy the idle collateral of CDP treasury is deposited
+/// into, such as liquid staking.
+pub trait TreasuryYieldStrategy<AccountId, CurrencyId, Balance> {
+	/// Deposit `amount` of `currency_id` of `who` into the strategy.
+	fn deposit(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+
+	/// Withdraw `amount` of `currency_id` from the strategy back to `who`.
+	fn withdraw(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+
+	/// The amount of `currency_id` of `who` in the strategy, including the
+	/// accrued yield.
+	fn balance(who: &AccountId, currency_id: CurrencyId) -> Balance;
+}
+
+/// The no-op yield strategy, nothing is deposited into it.
+impl<AccountId, CurrencyId, Balance: Default> TreasuryYieldStrategy<AccountId, CurrencyId, Balance> for () {
+	fn deposit(_who: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn withdraw(_who: &AccountId, _currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn balance(_who: &AccountId, _currency_id: CurrencyId) -> Balance {
+		Default::default()
+	}
+}
+
 pub trait PriceProvider<CurrencyId> {
 	fn get_price(currency_id: CurrencyId) -> Option<Price>;
 	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
@@ -291,6 +590,12 @@ pub trait PriceProvider<CurrencyId> {
 	}
 }
 
+impl<CurrencyId> PriceProvider<CurrencyId> for () {
+	fn get_price(_currency_id: CurrencyId) -> Option<Price> {
+		None
+	}
+}
+
 pub trait LockablePrice<CurrencyId> {
 	fn lock_price(currency_id: CurrencyId) -> DispatchResult;
 	fn unlock_price(currency_id: CurrencyId) -> DispatchResult;
@@ -300,13 +605,64 @@ pub trait ExchangeRateProvider {
 	fn get_exchange_rate() -> ExchangeRate;
 }
 
+/// Provide the fee rates of swapping in peg stability module.
+pub trait PSMFeeProvider {
+	fn mint_fee() -> Rate;
+	fn redeem_fee() -> Rate;
+}
+
 pub trait EmergencyShutdown {
 	fn is_shutdown() -> bool;
 }
 
+impl EmergencyShutdown for () {
+	fn is_shutdown() -> bool {
+		false
+	}
+}
+
+/// The reserve of other currencies backing the stable currency besides the
+/// collaterals of CDPs, it's refunded with them in the final redemption of
+/// emergency shutdown.
+pub trait StableCurrencyReserve<AccountId, CurrencyId, Balance> {
+	/// get the currencies in the reserve
+	fn reserve_currency_ids() -> Vec<CurrencyId>;
+
+	/// refund the proportion of the reserve of the currency to `who`, return
+	/// the refunded amount
+	fn refund_reserve(who: &AccountId, currency_id: CurrencyId, ratio: Ratio) -> Result<Balance, DispatchError>;
+}
+
+impl<AccountId, CurrencyId, Balance: Default> StableCurrencyReserve<AccountId, CurrencyId, Balance> for () {
+	fn reserve_currency_ids() -> Vec<CurrencyId> {
+		vec![]
+	}
+
+	fn refund_reserve(_who: &AccountId, _currency_id: CurrencyId, _ratio: Ratio) -> Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+}
+
+/// Handler for the transfers of multi currencies.
+pub trait OnTransfer<AccountId, CurrencyId, Balance> {
+	fn on_transfer(currency_id: CurrencyId, from: &AccountId, to: &AccountId, amount: Balance);
+
+	/// The weight of handling a transfer, charged by the callers.
+	fn on_transfer_weight() -> Weight;
+}
+
+impl<AccountId, CurrencyId, Balance> OnTransfer<AccountId, CurrencyId, Balance> for () {
+	fn on_transfer(_currency_id: CurrencyId, _from: &AccountId, _to: &AccountId, _amount: Balance) {}
+
+	fn on_transfer_weight() -> Weight {
+		0
+	}
+}
+
 pub trait DEXIncentives<AccountId, CurrencyId, Balance> {
 	fn do_deposit_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult;
 	fn do_withdraw_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+	fn do_claim_dex_share_rewards(who: &AccountId, lp_currency_id: CurrencyId) -> DispatchResult;
 }
 
 #[cfg(feature = "std")]
@@ -318,6 +674,30 @@ impl<AccountId, CurrencyId, Balance> DEXIncentives<AccountId, CurrencyId, Balanc
 	fn do_withdraw_dex_share(_: &AccountId, _: CurrencyId, _: Balance) -> DispatchResult {
 		Ok(())
 	}
+
+	fn do_claim_dex_share_rewards(_: &AccountId, _: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
+}
+
+pub trait TokenIncentives<AccountId, CurrencyId, Balance> {
+	fn do_deposit_token(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+	fn do_withdraw_token(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+	fn do_claim_token_rewards(who: &AccountId, currency_id: CurrencyId) -> DispatchResult;
+}
+
+impl<AccountId, CurrencyId, Balance> TokenIncentives<AccountId, CurrencyId, Balance> for () {
+	fn do_deposit_token(_: &AccountId, _: CurrencyId, _: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn do_withdraw_token(_: &AccountId, _: CurrencyId, _: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn do_claim_token_rewards(_: &AccountId, _: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
 }
 
 /// Return true if the call of EVM precompile contract is allowed.
@@ -325,6 +705,18 @@ pub trait PrecompileCallerFilter {
 	fn is_allowed(caller: H160) -> bool;
 }
 
+/// Return true if the call to the EVM contract is paused.
+pub trait EvmCallPauseFilter {
+	/// `input` is the call data, starting with the function selector.
+	fn is_paused(address: H160, input: &[u8]) -> bool;
+}
+
+impl EvmCallPauseFilter for () {
+	fn is_paused(_: H160, _: &[u8]) -> bool {
+		false
+	}
+}
+
 /// An abstraction of EVM for EVMBridge
 pub trait EVM<AccountId> {
 	type Balance: AtLeast32BitUnsigned + Copy + MaybeSerializeDeserialize + Default;
@@ -617,6 +1009,20 @@ pub trait CallBuilder {
 	fn finalize_call_into_xcm_message(call: Self::RelayChainCall, extra_fee: Self::Balance, weight: Weight) -> Xcm<()>;
 }
 
+/// Attestation registry, which attests that accounts are owned by unique
+/// persons for sybil-resistance.
+pub trait AttestationRegistry<AccountId> {
+	/// Check whether `who` is attested.
+	fn is_attested(who: &AccountId) -> bool;
+}
+
+/// Without attestation registry, all accounts are treated as attested.
+impl<AccountId> AttestationRegistry<AccountId> for () {
+	fn is_attested(_who: &AccountId) -> bool {
+		true
+	}
+}
+
 /// Dispatchable tasks
 pub trait DispatchableTask {
 	fn dispatch(self, weight: Weight) -> TaskResult;
@@ -627,6 +1033,91 @@ pub trait IdleScheduler<Task> {
 	fn schedule(task: Task) -> DispatchResult;
 }
 
+/// The source of randomness for on-chain lotteries
+pub trait RandomnessSource<Output, BlockNumber> {
+	/// get the random output for `subject` and the block number since which
+	/// it's determinable, it must only be used to settle the actions
+	/// committed before that block
+	fn random(subject: &[u8]) -> (Output, BlockNumber);
+}
+
+/// Record the circuit breakers paused and unpaused
+pub trait CircuitBreakerRecorder {
+	/// the circuit breaker is paused for the reason
+	fn on_paused(id: CircuitBreakerId, reason: CircuitBreakerReason);
+	/// the circuit breaker is unpaused
+	fn on_unpaused(id: CircuitBreakerId);
+}
+
+impl CircuitBreakerRecorder for () {
+	fn on_paused(_id: CircuitBreakerId, _reason: CircuitBreakerReason) {}
+
+	fn on_unpaused(_id: CircuitBreakerId) {}
+}
+
+/// Unpause the circuit breaker by the pallet which owns it
+pub trait CircuitBreakerUnpause {
+	/// unpause the circuit breaker, return whether the circuit breaker is
+	/// owned by this handler
+	fn unpause(id: &CircuitBreakerId) -> bool;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl CircuitBreakerUnpause for Tuple {
+	fn unpause(id: &CircuitBreakerId) -> bool {
+		for_tuples!( #( if Tuple::unpause(id) { return true; } )* );
+		false
+	}
+}
+
+/// Pay the bounty to the caller who cleans up the terminal state
+pub trait PayCleanupBounty<AccountId> {
+	/// pay the bounty of `count` cleaned items to `who`, return the actual
+	/// paid bounty
+	fn pay_cleanup_bounty(who: &AccountId, count: u32) -> primitives::Balance;
+}
+
+/// Pay `BountyPerItem` of the native currency for each cleaned item from the
+/// treasury account, nothing is paid if the treasury can't afford it.
+pub struct TreasuryCleanupBounty<Currency, GetNativeCurrencyId, TreasuryAccount, BountyPerItem>(
+	sp_std::marker::PhantomData<(Currency, GetNativeCurrencyId, TreasuryAccount, BountyPerItem)>,
+);
+
+impl<AccountId, Currency, GetNativeCurrencyId, TreasuryAccount, BountyPerItem> PayCleanupBounty<AccountId>
+	for TreasuryCleanupBounty<Currency, GetNativeCurrencyId, TreasuryAccount, BountyPerItem>
+where
+	AccountId: Debug,
+	Currency: orml_traits::MultiCurrency<AccountId, CurrencyId = CurrencyId, Balance = primitives::Balance>,
+	GetNativeCurrencyId: frame_support::traits::Get<CurrencyId>,
+	TreasuryAccount: frame_support::traits::Get<AccountId>,
+	BountyPerItem: frame_support::traits::Get<primitives::Balance>,
+{
+	fn pay_cleanup_bounty(who: &AccountId, count: u32) -> primitives::Balance {
+		let bounty = BountyPerItem::get().saturating_mul(count.into());
+		match Currency::transfer(GetNativeCurrencyId::get(), &TreasuryAccount::get(), who, bounty) {
+			Ok(_) => bounty,
+			Err(e) => {
+				frame_support::log::warn!(
+					target: "cleanup-bounty",
+					"transfer: failed to pay cleanup bounty {:?} from {:?} to {:?}: {:?}. \
+					This is unexpected but should be safe",
+					bounty, TreasuryAccount::get(), who, e
+				);
+				0
+			}
+		}
+	}
+}
+
+/// The condition of a keeper job, checked before the call of the job is
+/// dispatched on behalf of the job owner
+pub trait KeeperCondition<AccountId> {
+	/// return true if the condition is met for the job owner `who`
+	fn is_met(&self, who: &AccountId) -> bool;
+	/// the weight of `is_met`
+	fn is_met_weight(&self) -> Weight;
+}
+
 #[cfg(feature = "std")]
 impl DispatchableTask for () {
 	fn dispatch(self, _weight: Weight) -> TaskResult {
@@ -16,14 +16,14 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{AddressMapping, CurrencyId, Erc20InfoMapping};
+use crate::{AddressMapping, CurrencyId, Erc20InfoMapping, RandomnessSource};
 use codec::Encode;
 use frame_support::pallet_prelude::DispatchResult;
 use primitives::{
 	currency::TokenInfo,
 	evm::{is_mirrored_tokens_address_prefix, EvmAddress, H160_POSITION_TOKEN},
 };
-use sp_core::{crypto::AccountId32, H160};
+use sp_core::{crypto::AccountId32, H160, H256};
 use sp_io::hashing::blake2_256;
 use sp_std::{
 	convert::{TryFrom, TryInto},
@@ -101,3 +101,13 @@ impl Erc20InfoMapping for MockErc20InfoMapping {
 		}
 	}
 }
+
+/// Deterministic randomness source for tests and simulations, the output is
+/// the hash of the subject.
+pub struct MockRandomnessSource;
+
+impl<BlockNumber: Default> RandomnessSource<H256, BlockNumber> for MockRandomnessSource {
+	fn random(subject: &[u8]) -> (H256, BlockNumber) {
+		(H256::from(blake2_256(subject)), Default::default())
+	}
+}
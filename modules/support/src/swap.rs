@@ -0,0 +1,147 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Swap aggregated over the constant product DEX and the stable asset pools.
+//!
+//! A swap is described by a sequence of `SwapPath`, each of them is a swap
+//! path of the DEX or a swap in a stable asset pool, and the target currency
+//! of a `SwapPath` must be the supply currency of the next one.
+
+use super::*;
+use frame_support::{ensure, transactional};
+use scale_info::TypeInfo;
+use sp_runtime::traits::Zero;
+use sp_std::marker::PhantomData;
+
+/// A step of the aggregated swap
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum SwapPath<CurrencyId> {
+	/// Swap by the DEX through the path
+	Dex(Vec<CurrencyId>),
+	/// Swap in the stable asset pool from the currency at index `i` to the
+	/// currency at index `j`. \[pool_id, i, j\]
+	Taiga(StableAssetPoolId, u32, u32),
+}
+
+/// An abstraction of the swap which routes through both the DEX and the stable
+/// asset pools.
+pub trait AggregatedSwap<AccountId, CurrencyId, Balance> {
+	/// Get the supply currency and the target currency of `paths`, `None` if
+	/// `paths` is empty or isn't continuous.
+	fn get_swap_currencies(paths: &[SwapPath<CurrencyId>]) -> Option<(CurrencyId, CurrencyId)>;
+
+	/// Get the target amount of swapping `supply_amount` through `paths`.
+	fn get_swap_target_amount(paths: &[SwapPath<CurrencyId>], supply_amount: Balance) -> Option<Balance>;
+
+	/// Swap `supply_amount` through `paths`, return the actual target amount.
+	fn swap_with_exact_supply(
+		who: &AccountId,
+		paths: &[SwapPath<CurrencyId>],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError>;
+}
+
+impl<AccountId, CurrencyId, Balance> AggregatedSwap<AccountId, CurrencyId, Balance> for () {
+	fn get_swap_currencies(_paths: &[SwapPath<CurrencyId>]) -> Option<(CurrencyId, CurrencyId)> {
+		None
+	}
+
+	fn get_swap_target_amount(_paths: &[SwapPath<CurrencyId>], _supply_amount: Balance) -> Option<Balance> {
+		None
+	}
+
+	fn swap_with_exact_supply(
+		_who: &AccountId,
+		_paths: &[SwapPath<CurrencyId>],
+		_supply_amount: Balance,
+		_min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		Err(DispatchError::Other("unimplemented aggregated swap"))
+	}
+}
+
+/// Implement `AggregatedSwap` by `DEX` and `StableAsset`. Only the final target
+/// amount is checked against the limit, the intermediate swaps are unlimited.
+pub struct AggregatedSwapAdapter<DEX, StableAsset>(PhantomData<(DEX, StableAsset)>);
+
+impl<AccountId, CurrencyId, Balance, DEX, StableAsset> AggregatedSwap<AccountId, CurrencyId, Balance>
+	for AggregatedSwapAdapter<DEX, StableAsset>
+where
+	CurrencyId: Copy + PartialEq,
+	Balance: Copy + PartialOrd + Zero,
+	DEX: DEXManager<AccountId, CurrencyId, Balance>,
+	StableAsset: StableAssetPool<AccountId, CurrencyId, Balance>,
+{
+	fn get_swap_currencies(paths: &[SwapPath<CurrencyId>]) -> Option<(CurrencyId, CurrencyId)> {
+		let mut currencies: Option<(CurrencyId, CurrencyId)> = None;
+		for path in paths {
+			let (supply_currency_id, target_currency_id) = match path {
+				SwapPath::Dex(dex_path) => {
+					if dex_path.len() < 2 {
+						return None;
+					}
+					(*dex_path.first()?, *dex_path.last()?)
+				}
+				SwapPath::Taiga(pool_id, i, j) => {
+					let assets = StableAsset::get_pool_assets(*pool_id)?;
+					(*assets.get(*i as usize)?, *assets.get(*j as usize)?)
+				}
+			};
+
+			currencies = match currencies {
+				None => Some((supply_currency_id, target_currency_id)),
+				Some((first_currency_id, last_currency_id)) if last_currency_id == supply_currency_id => {
+					Some((first_currency_id, target_currency_id))
+				}
+				_ => return None,
+			};
+		}
+		currencies
+	}
+
+	fn get_swap_target_amount(paths: &[SwapPath<CurrencyId>], supply_amount: Balance) -> Option<Balance> {
+		Self::get_swap_currencies(paths)?;
+		paths.iter().try_fold(supply_amount, |amount, path| match path {
+			SwapPath::Dex(dex_path) => DEX::get_swap_target_amount(dex_path, amount),
+			SwapPath::Taiga(pool_id, i, j) => StableAsset::get_swap_output_amount(*pool_id, *i, *j, amount),
+		})
+	}
+
+	#[transactional]
+	fn swap_with_exact_supply(
+		who: &AccountId,
+		paths: &[SwapPath<CurrencyId>],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		ensure!(
+			Self::get_swap_currencies(paths).is_some(),
+			DispatchError::Other("invalid aggregated swap paths")
+		);
+		let target_amount = paths.iter().try_fold(supply_amount, |amount, path| match path {
+			SwapPath::Dex(dex_path) => DEX::swap_with_exact_supply(who, dex_path, amount, Zero::zero()),
+			SwapPath::Taiga(pool_id, i, j) => StableAsset::swap(who, *pool_id, *i, *j, amount, Zero::zero()),
+		})?;
+		ensure!(
+			target_amount >= min_target_amount,
+			DispatchError::Other("aggregated swap target amount below the limit")
+		);
+		Ok(target_amount)
+	}
+}
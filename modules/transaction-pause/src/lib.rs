@@ -26,8 +26,13 @@ use frame_support::{
 	transactional,
 };
 use frame_system::pallet_prelude::*;
-use sp_runtime::DispatchResult;
+use primitives::{evm::EvmAddress, CircuitBreakerId, CircuitBreakerReason};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchResult,
+};
 use sp_std::{prelude::*, vec::Vec};
+use support::{CircuitBreakerRecorder, CircuitBreakerUnpause, EvmCallPauseFilter};
 
 mod mock;
 mod tests;
@@ -47,6 +52,30 @@ pub mod module {
 		/// The origin which may set filter.
 		type UpdateOrigin: EnsureOrigin<Self::Origin>;
 
+		/// The origin which may pause all the transactions of a pallet in
+		/// emergency.
+		type EmergencyOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The max number of blocks that an emergency pause lasts
+		#[pallet::constant]
+		type MaxEmergencyPauseDuration: Get<Self::BlockNumber>;
+
+		/// The names of the pallets that can't be paused in emergency, such as
+		/// the core and the governance pallets
+		type UnpausablePallets: Get<Vec<Vec<u8>>>;
+
+		/// The number of blocks after an emergency pause ends before the
+		/// pallet can be paused in emergency again
+		#[pallet::constant]
+		type EmergencyPauseCooldown: Get<Self::BlockNumber>;
+
+		/// The calls that are not paused by the emergency pause of their
+		/// pallet, such as the governance and the unsigned calls
+		type EmergencyPauseExemptCalls: Contains<<Self as frame_system::Config>::Call>;
+
+		/// The recorder of the paused and unpaused transactions
+		type CircuitBreakerRecorder: CircuitBreakerRecorder;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -57,6 +86,12 @@ pub mod module {
 		CannotPause,
 		/// invalid character encoding
 		InvalidCharacter,
+		/// The pause duration is zero or exceeds the max
+		InvalidPauseDuration,
+		/// The pallet is already paused in emergency
+		PalletAlreadyPaused,
+		/// The emergency pause of the pallet ended recently
+		PauseInCooldown,
 	}
 
 	#[pallet::event]
@@ -66,6 +101,16 @@ pub mod module {
 		TransactionPaused(Vec<u8>, Vec<u8>),
 		/// Unpaused transaction . \[pallet_name_bytes, function_name_bytes\]
 		TransactionUnpaused(Vec<u8>, Vec<u8>),
+		/// Paused all the transactions of the pallet in emergency.
+		/// \[pallet_name_bytes, expiry_block\]
+		PalletPaused(Vec<u8>, T::BlockNumber),
+		/// Unpaused the transactions of the pallet paused in emergency.
+		/// \[pallet_name_bytes\]
+		PalletUnpaused(Vec<u8>),
+		/// Paused the EVM call. \[contract, function_selector\]
+		EvmCallPaused(EvmAddress, Option<[u8; 4]>),
+		/// Unpaused the EVM call. \[contract, function_selector\]
+		EvmCallUnpaused(EvmAddress, Option<[u8; 4]>),
 	}
 
 	/// The paused transaction map
@@ -75,11 +120,52 @@ pub mod module {
 	#[pallet::getter(fn paused_transactions)]
 	pub type PausedTransactions<T: Config> = StorageMap<_, Twox64Concat, (Vec<u8>, Vec<u8>), (), OptionQuery>;
 
+	/// The pallets paused in emergency and the block number at which the
+	/// pause expires
+	///
+	/// PausedPallets: map PalletNameBytes => Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn paused_pallets)]
+	pub type PausedPallets<T: Config> = StorageMap<_, Twox64Concat, Vec<u8>, T::BlockNumber, OptionQuery>;
+
+	/// The block number before which the pallet can't be paused in
+	/// emergency again
+	///
+	/// PalletPauseCooldowns: map PalletNameBytes => Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn pallet_pause_cooldowns)]
+	pub type PalletPauseCooldowns<T: Config> = StorageMap<_, Twox64Concat, Vec<u8>, T::BlockNumber, OptionQuery>;
+
+	/// The paused EVM calls, all the functions of the contract are paused if
+	/// the function selector is `None`
+	///
+	/// PausedEvmCalls: map (EvmAddress, Option<FunctionSelector>) => Option<()>
+	#[pallet::storage]
+	#[pallet::getter(fn paused_evm_calls)]
+	pub type PausedEvmCalls<T: Config> = StorageMap<_, Twox64Concat, (EvmAddress, Option<[u8; 4]>), (), OptionQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Unpause the pallets whose emergency pause expires.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut paused_count: u32 = 0;
+			let mut expired_pallets: Vec<Vec<u8>> = vec![];
+			for (pallet_name, expiry) in PausedPallets::<T>::iter() {
+				paused_count = paused_count.saturating_add(1);
+				if expiry <= now {
+					expired_pallets.push(pallet_name);
+				}
+			}
+
+			for pallet_name in expired_pallets {
+				Self::do_unpause_pallet(pallet_name);
+			}
+			T::WeightInfo::on_initialize(paused_count)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -98,6 +184,10 @@ pub mod module {
 			PausedTransactions::<T>::mutate_exists((pallet_name.clone(), function_name.clone()), |maybe_paused| {
 				if maybe_paused.is_none() {
 					*maybe_paused = Some(());
+					T::CircuitBreakerRecorder::on_paused(
+						CircuitBreakerId::Transaction(pallet_name.clone(), function_name.clone()),
+						CircuitBreakerReason::Governance,
+					);
 					Self::deposit_event(Event::TransactionPaused(pallet_name, function_name));
 				}
 			});
@@ -112,14 +202,175 @@ pub mod module {
 			function_name: Vec<u8>,
 		) -> DispatchResult {
 			T::UpdateOrigin::ensure_origin(origin)?;
-			if PausedTransactions::<T>::take((&pallet_name, &function_name)).is_some() {
-				Self::deposit_event(Event::TransactionUnpaused(pallet_name, function_name));
-			};
+			Self::do_unpause_transaction(pallet_name, function_name);
+			Ok(())
+		}
+
+		/// Pause all the transactions of the pallet for `duration` blocks,
+		/// the pause expires automatically.
+		///
+		/// The dispatch origin of this call must be `EmergencyOrigin`.
+		///
+		/// - `pallet_name`: the name bytes of the pallet.
+		/// - `duration`: the number of blocks the pause lasts, at most
+		///   `MaxEmergencyPauseDuration`.
+		///
+		/// The calls in `EmergencyPauseExemptCalls` are not paused, and the
+		/// pallet can't be paused again until `EmergencyPauseCooldown`
+		/// blocks after the pause ends.
+		#[pallet::weight(T::WeightInfo::emergency_pause_pallet())]
+		#[transactional]
+		pub fn emergency_pause_pallet(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			T::EmergencyOrigin::ensure_origin(origin)?;
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxEmergencyPauseDuration::get(),
+				Error::<T>::InvalidPauseDuration
+			);
+
+			// not allowed to pause this pallet and the governance pallets
+			let pallet_name_string = sp_std::str::from_utf8(&pallet_name).map_err(|_| Error::<T>::InvalidCharacter)?;
+			ensure!(
+				pallet_name_string != <Self as PalletInfoAccess>::name()
+					&& !T::UnpausablePallets::get().contains(&pallet_name),
+				Error::<T>::CannotPause
+			);
+			ensure!(
+				!PausedPallets::<T>::contains_key(&pallet_name),
+				Error::<T>::PalletAlreadyPaused
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			if let Some(cooldown_end) = Self::pallet_pause_cooldowns(&pallet_name) {
+				ensure!(now >= cooldown_end, Error::<T>::PauseInCooldown);
+				PalletPauseCooldowns::<T>::remove(&pallet_name);
+			}
+
+			let expiry = now.saturating_add(duration);
+			PausedPallets::<T>::insert(&pallet_name, expiry);
+			T::CircuitBreakerRecorder::on_paused(
+				CircuitBreakerId::Pallet(pallet_name.clone()),
+				CircuitBreakerReason::Emergency,
+			);
+			Self::deposit_event(Event::PalletPaused(pallet_name, expiry));
+			Ok(())
+		}
+
+		/// Unpause the pallet paused in emergency before the pause expires.
+		///
+		/// The dispatch origin of this call must be `EmergencyOrigin` or
+		/// `UpdateOrigin`.
+		///
+		/// - `pallet_name`: the name bytes of the pallet.
+		#[pallet::weight(T::WeightInfo::emergency_unpause_pallet())]
+		#[transactional]
+		pub fn emergency_unpause_pallet(origin: OriginFor<T>, pallet_name: Vec<u8>) -> DispatchResult {
+			T::EmergencyOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(|origin| T::UpdateOrigin::ensure_origin(origin).map(|_| ()))?;
+			Self::do_unpause_pallet(pallet_name);
+			Ok(())
+		}
+
+		/// Pause the calls into the EVM contract, including the calls from
+		/// other contracts.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `contract`: the address of the contract or the precompile.
+		/// - `selector`: the function selector, all the functions of the
+		///   contract if `None`.
+		#[pallet::weight(T::WeightInfo::pause_evm_call())]
+		#[transactional]
+		pub fn pause_evm_call(origin: OriginFor<T>, contract: EvmAddress, selector: Option<[u8; 4]>) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			PausedEvmCalls::<T>::mutate_exists((contract, selector), |maybe_paused| {
+				if maybe_paused.is_none() {
+					*maybe_paused = Some(());
+					T::CircuitBreakerRecorder::on_paused(
+						CircuitBreakerId::EvmCall(contract, selector),
+						CircuitBreakerReason::Governance,
+					);
+					Self::deposit_event(Event::EvmCallPaused(contract, selector));
+				}
+			});
+			Ok(())
+		}
+
+		/// Unpause the calls into the EVM contract.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `contract`: the address of the contract or the precompile.
+		/// - `selector`: the function selector, all the functions of the
+		///   contract if `None`.
+		#[pallet::weight(T::WeightInfo::unpause_evm_call())]
+		#[transactional]
+		pub fn unpause_evm_call(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			selector: Option<[u8; 4]>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::do_unpause_evm_call(contract, selector);
 			Ok(())
 		}
 	}
 }
 
+impl<T: Config> Pallet<T> {
+	fn do_unpause_transaction(pallet_name: Vec<u8>, function_name: Vec<u8>) {
+		if PausedTransactions::<T>::take((&pallet_name, &function_name)).is_some() {
+			T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::Transaction(
+				pallet_name.clone(),
+				function_name.clone(),
+			));
+			Self::deposit_event(Event::TransactionUnpaused(pallet_name, function_name));
+		};
+	}
+
+	fn do_unpause_pallet(pallet_name: Vec<u8>) {
+		if PausedPallets::<T>::take(&pallet_name).is_some() {
+			let cooldown_end =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::EmergencyPauseCooldown::get());
+			PalletPauseCooldowns::<T>::insert(&pallet_name, cooldown_end);
+			T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::Pallet(pallet_name.clone()));
+			Self::deposit_event(Event::PalletUnpaused(pallet_name));
+		}
+	}
+
+	fn do_unpause_evm_call(contract: EvmAddress, selector: Option<[u8; 4]>) {
+		if PausedEvmCalls::<T>::take((contract, selector)).is_some() {
+			T::CircuitBreakerRecorder::on_unpaused(CircuitBreakerId::EvmCall(contract, selector));
+			Self::deposit_event(Event::EvmCallUnpaused(contract, selector));
+		}
+	}
+}
+
+impl<T: Config> CircuitBreakerUnpause for Pallet<T> {
+	fn unpause(id: &CircuitBreakerId) -> bool {
+		match id {
+			CircuitBreakerId::Transaction(pallet_name, function_name) => {
+				Self::do_unpause_transaction(pallet_name.clone(), function_name.clone());
+				true
+			}
+			CircuitBreakerId::Pallet(pallet_name) => {
+				Self::do_unpause_pallet(pallet_name.clone());
+				true
+			}
+			CircuitBreakerId::EvmCall(contract, selector) => {
+				Self::do_unpause_evm_call(*contract, *selector);
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
 pub struct PausedTransactionFilter<T>(sp_std::marker::PhantomData<T>);
 impl<T: Config> Contains<T::Call> for PausedTransactionFilter<T>
 where
@@ -131,5 +382,21 @@ where
 			pallet_name,
 		} = call.get_call_metadata();
 		PausedTransactions::<T>::contains_key((pallet_name.as_bytes(), function_name.as_bytes()))
+			|| (PausedPallets::<T>::contains_key(pallet_name.as_bytes())
+				&& !T::EmergencyPauseExemptCalls::contains(call))
+	}
+}
+
+impl<T: Config> EvmCallPauseFilter for Pallet<T> {
+	fn is_paused(address: EvmAddress, input: &[u8]) -> bool {
+		if PausedEvmCalls::<T>::contains_key((address, None::<[u8; 4]>)) {
+			return true;
+		}
+
+		input.get(0..4).map_or(false, |bytes| {
+			let mut selector = [0u8; 4];
+			selector.copy_from_slice(bytes);
+			PausedEvmCalls::<T>::contains_key((address, Some(selector)))
+		})
 	}
 }
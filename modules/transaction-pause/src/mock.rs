@@ -106,11 +106,31 @@ impl orml_tokens::Config for Runtime {
 
 ord_parameter_types! {
 	pub const One: AccountId = 1;
+	pub const Two: AccountId = 2;
+}
+
+parameter_types! {
+	pub const MaxEmergencyPauseDuration: u64 = 10;
+	pub UnpausablePallets: Vec<Vec<u8>> = vec![b"System".to_vec()];
+	pub const EmergencyPauseCooldown: u64 = 5;
+}
+
+pub struct EmergencyPauseExemptCalls;
+impl Contains<Call> for EmergencyPauseExemptCalls {
+	fn contains(call: &Call) -> bool {
+		matches!(call, Call::Balances(pallet_balances::Call::set_balance { .. }))
+	}
 }
 
 impl Config for Runtime {
 	type Event = Event;
 	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type EmergencyOrigin = EnsureSignedBy<Two, AccountId>;
+	type MaxEmergencyPauseDuration = MaxEmergencyPauseDuration;
+	type UnpausablePallets = UnpausablePallets;
+	type EmergencyPauseCooldown = EmergencyPauseCooldown;
+	type EmergencyPauseExemptCalls = EmergencyPauseExemptCalls;
+	type CircuitBreakerRecorder = ();
 	type WeightInfo = ();
 }
 
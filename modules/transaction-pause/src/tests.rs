@@ -32,6 +32,12 @@ const TOKENS_TRANSFER: &<Runtime as frame_system::Config>::Call = &mock::Call::T
 	currency_id: AUSD,
 	amount: 10,
 });
+const BALANCE_SET_BALANCE: &<Runtime as frame_system::Config>::Call =
+	&mock::Call::Balances(pallet_balances::Call::set_balance {
+		who: ALICE,
+		new_free: 10,
+		new_reserved: 0,
+	});
 
 #[test]
 fn pause_transaction_work() {
@@ -152,3 +158,195 @@ fn paused_transaction_filter_work() {
 		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
 	});
 }
+
+#[test]
+fn emergency_pause_pallet_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(1), b"Balances".to_vec(), 5),
+			BadOrigin
+		);
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"Balances".to_vec(), 0),
+			Error::<Runtime>::InvalidPauseDuration
+		);
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"Balances".to_vec(), 11),
+			Error::<Runtime>::InvalidPauseDuration
+		);
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"TransactionPause".to_vec(), 5),
+			Error::<Runtime>::CannotPause
+		);
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"System".to_vec(), 5),
+			Error::<Runtime>::CannotPause
+		);
+
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Balances".to_vec(),
+			5
+		));
+		System::assert_last_event(Event::TransactionPause(crate::Event::PalletPaused(
+			b"Balances".to_vec(),
+			6,
+		)));
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), Some(6));
+		assert!(PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
+		// the exempt calls are not paused
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_SET_BALANCE));
+
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"Balances".to_vec(), 5),
+			Error::<Runtime>::PalletAlreadyPaused
+		);
+
+		// the pause expires automatically
+		TransactionPause::on_initialize(5);
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), Some(6));
+		TransactionPause::on_initialize(6);
+		System::assert_last_event(Event::TransactionPause(crate::Event::PalletUnpaused(
+			b"Balances".to_vec(),
+		)));
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), None);
+		assert!(!PausedTransactionFilter::<Runtime>::contains(BALANCE_TRANSFER));
+	});
+}
+
+#[test]
+fn emergency_pause_pallet_cooldown_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Balances".to_vec(),
+			5
+		));
+		System::set_block_number(6);
+		TransactionPause::on_initialize(6);
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), None);
+		assert_eq!(TransactionPause::pallet_pause_cooldowns(b"Balances".to_vec()), Some(11));
+
+		// the pause can't be re-issued right after it expires
+		assert_noop!(
+			TransactionPause::emergency_pause_pallet(Origin::signed(2), b"Balances".to_vec(), 5),
+			Error::<Runtime>::PauseInCooldown
+		);
+
+		// the early unpause also starts the cooldown
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Tokens".to_vec(),
+			5
+		));
+		assert_ok!(TransactionPause::emergency_unpause_pallet(
+			Origin::signed(1),
+			b"Tokens".to_vec()
+		));
+		assert_eq!(TransactionPause::pallet_pause_cooldowns(b"Tokens".to_vec()), Some(11));
+
+		System::set_block_number(11);
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Balances".to_vec(),
+			5
+		));
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), Some(16));
+		assert_eq!(TransactionPause::pallet_pause_cooldowns(b"Balances".to_vec()), None);
+	});
+}
+
+#[test]
+fn emergency_unpause_pallet_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Balances".to_vec(),
+			5
+		));
+		assert_ok!(TransactionPause::emergency_pause_pallet(
+			Origin::signed(2),
+			b"Tokens".to_vec(),
+			5
+		));
+
+		assert_noop!(
+			TransactionPause::emergency_unpause_pallet(Origin::signed(5), b"Balances".to_vec()),
+			BadOrigin
+		);
+
+		// both the emergency origin and the update origin can unpause
+		assert_ok!(TransactionPause::emergency_unpause_pallet(
+			Origin::signed(2),
+			b"Balances".to_vec()
+		));
+		System::assert_last_event(Event::TransactionPause(crate::Event::PalletUnpaused(
+			b"Balances".to_vec(),
+		)));
+		assert_eq!(TransactionPause::paused_pallets(b"Balances".to_vec()), None);
+
+		assert_ok!(TransactionPause::emergency_unpause_pallet(
+			Origin::signed(1),
+			b"Tokens".to_vec()
+		));
+		assert_eq!(TransactionPause::paused_pallets(b"Tokens".to_vec()), None);
+		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
+	});
+}
+
+#[test]
+fn pause_and_unpause_evm_call_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = EvmAddress::from_low_u64_be(1024);
+		let transfer_selector = [0xa9, 0x05, 0x9c, 0xbb];
+
+		assert_noop!(
+			TransactionPause::pause_evm_call(Origin::signed(5), contract, None),
+			BadOrigin
+		);
+
+		assert_ok!(TransactionPause::pause_evm_call(
+			Origin::signed(1),
+			contract,
+			Some(transfer_selector)
+		));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmCallPaused(
+			contract,
+			Some(transfer_selector),
+		)));
+		assert_eq!(
+			TransactionPause::paused_evm_calls((contract, Some(transfer_selector))),
+			Some(())
+		);
+		assert!(TransactionPause::is_paused(contract, &[0xa9, 0x05, 0x9c, 0xbb, 0x01]));
+		assert!(!TransactionPause::is_paused(contract, &[0x09, 0x5e, 0xa7, 0xb3, 0x01]));
+		assert!(!TransactionPause::is_paused(contract, &[0xa9, 0x05]));
+		assert!(!TransactionPause::is_paused(
+			EvmAddress::from_low_u64_be(1025),
+			&[0xa9, 0x05, 0x9c, 0xbb]
+		));
+
+		// pause all the functions of the contract
+		assert_ok!(TransactionPause::pause_evm_call(Origin::signed(1), contract, None));
+		assert!(TransactionPause::is_paused(contract, &[0x09, 0x5e, 0xa7, 0xb3, 0x01]));
+		assert!(TransactionPause::is_paused(contract, &[]));
+
+		assert_noop!(
+			TransactionPause::unpause_evm_call(Origin::signed(5), contract, None),
+			BadOrigin
+		);
+		assert_ok!(TransactionPause::unpause_evm_call(Origin::signed(1), contract, None));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmCallUnpaused(contract, None)));
+		assert_eq!(TransactionPause::paused_evm_calls((contract, None::<[u8; 4]>)), None);
+		assert!(!TransactionPause::is_paused(contract, &[0x09, 0x5e, 0xa7, 0xb3, 0x01]));
+		assert!(TransactionPause::is_paused(contract, &[0xa9, 0x05, 0x9c, 0xbb, 0x01]));
+	});
+}
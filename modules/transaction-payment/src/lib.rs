@@ -22,12 +22,17 @@
 //!
 //! Transaction payment module is responsible for charge fee and tip in
 //! different currencies
+//!
+//! Governance can whitelist calls of specific accounts, such as the feeding
+//! of oracle operators, to be free of fees. At most `MaxFeeFreeTxPerBlock`
+//! fee-free transactions are included per block, and the rest of them are
+//! charged as normal.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
 use frame_support::{
-	dispatch::{DispatchResult, Dispatchable},
+	dispatch::{DispatchResult, Dispatchable, GetCallMetadata},
 	pallet_prelude::*,
 	traits::{
 		Currency, ExistenceRequirement, Imbalance, NamedReservableCurrency, OnUnbalanced, SameOrOther, WithdrawReasons,
@@ -270,6 +275,13 @@ pub mod module {
 		/// The price source to provider external market price.
 		type PriceSource: PriceProvider<CurrencyId>;
 
+		/// The max number of fee-free transactions in a block.
+		#[pallet::constant]
+		type MaxFeeFreeTxPerBlock: Get<u32>;
+
+		/// The origin which may update the fee-free calls.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -308,6 +320,22 @@ pub mod module {
 	pub type AlternativeFeeSwapPath<T: Config> =
 		StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<CurrencyId, T::TradingPathLimit>, OptionQuery>;
 
+	/// The calls of accounts which are free of fees.
+	///
+	/// FeeFreeCalls: double_map AccountId, (PalletName, FunctionName) => bool
+	#[pallet::storage]
+	#[pallet::getter(fn fee_free_calls)]
+	pub type FeeFreeCalls<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, (Vec<u8>, Vec<u8>), bool, ValueQuery>;
+
+	/// The number of fee-free transactions in current block, cleared on
+	/// block finalization.
+	///
+	/// FeeFreeTxCount: u32
+	#[pallet::storage]
+	#[pallet::getter(fn fee_free_tx_count)]
+	pub type FeeFreeTxCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -322,6 +350,7 @@ pub mod module {
 			NextFeeMultiplier::<T>::mutate(|fm| {
 				*fm = T::FeeMultiplierUpdate::convert(*fm);
 			});
+			FeeFreeTxCount::<T>::kill();
 		}
 
 		#[cfg(feature = "std")]
@@ -392,6 +421,32 @@ pub mod module {
 			}
 			Ok(())
 		}
+
+		/// Whitelist the call of the account to be free of fees, or remove
+		/// it from the whitelist.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `who`: the account sending the call.
+		/// - `pallet_name`: the pallet name of the call in the runtime.
+		/// - `function_name`: the function name of the call.
+		/// - `enabled`: whether the call is free of fees.
+		#[pallet::weight((<T as Config>::WeightInfo::set_fee_free_call(), DispatchClass::Operational))]
+		pub fn set_fee_free_call(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			pallet_name: Vec<u8>,
+			function_name: Vec<u8>,
+			enabled: bool,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if enabled {
+				FeeFreeCalls::<T>::insert(&who, (pallet_name, function_name), true);
+			} else {
+				FeeFreeCalls::<T>::remove(&who, (pallet_name, function_name));
+			}
+			Ok(())
+		}
 	}
 }
 
@@ -670,7 +725,7 @@ impl<T: Config + Send + Sync> sp_std::fmt::Debug for ChargeTransactionPayment<T>
 
 impl<T: Config + Send + Sync> ChargeTransactionPayment<T>
 where
-	<T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	<T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo> + GetCallMetadata,
 	PalletBalanceOf<T>: Send + Sync + FixedPointOperand,
 {
 	/// utility constructor. Used only in client/factory code.
@@ -678,6 +733,19 @@ where
 		Self(fee)
 	}
 
+	/// Whether the call of the account is whitelisted to be free of fees
+	/// and the fee-free transactions in the block don't reach the limit.
+	fn is_fee_free(who: &T::AccountId, call: &<T as frame_system::Config>::Call) -> bool {
+		let metadata = call.get_call_metadata();
+		FeeFreeCalls::<T>::get(
+			who,
+			(
+				metadata.pallet_name.as_bytes().to_vec(),
+				metadata.function_name.as_bytes().to_vec(),
+			),
+		) && FeeFreeTxCount::<T>::get() < T::MaxFeeFreeTxPerBlock::get()
+	}
+
 	fn withdraw_fee(
 		&self,
 		who: &T::AccountId,
@@ -740,7 +808,7 @@ where
 impl<T: Config + Send + Sync> SignedExtension for ChargeTransactionPayment<T>
 where
 	PalletBalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
-	<T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	<T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo> + GetCallMetadata,
 {
 	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
 	type AccountId = T::AccountId;
@@ -764,6 +832,10 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> TransactionValidity {
+		if Self::is_fee_free(who, call) {
+			return Ok(ValidTransaction::default());
+		}
+
 		let (fee, _) = self.withdraw_fee(who, call, info, len)?;
 		Ok(ValidTransaction {
 			priority: Self::get_priority(len, info, fee),
@@ -778,6 +850,11 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> Result<Self::Pre, TransactionValidityError> {
+		if Self::is_fee_free(who, call) {
+			FeeFreeTxCount::<T>::mutate(|count| *count = count.saturating_add(1));
+			return Ok((Zero::zero(), who.clone(), None, Zero::zero()));
+		}
+
 		let (fee, imbalance) = self.withdraw_fee(who, call, info, len)?;
 		Ok((self.0, who.clone(), imbalance, fee))
 	}
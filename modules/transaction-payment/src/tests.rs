@@ -30,7 +30,10 @@ use mock::{
 	TransactionPayment, ACA, ALICE, AUSD, BOB, CHARLIE, DOT, FEE_UNBALANCED_AMOUNT, TIP_UNBALANCED_AMOUNT,
 };
 use orml_traits::MultiCurrency;
-use sp_runtime::{testing::TestXt, traits::One};
+use sp_runtime::{
+	testing::TestXt,
+	traits::{BadOrigin, One},
+};
 use support::Price;
 
 const CALL: &<Runtime as frame_system::Config>::Call = &Call::Currencies(module_currencies::Call::transfer {
@@ -324,6 +327,85 @@ fn set_alternative_fee_swap_path_work() {
 	});
 }
 
+#[test]
+fn fee_free_calls_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let update_origin = Origin::signed(AccountId::new([0u8; 32]));
+		assert_noop!(
+			TransactionPayment::set_fee_free_call(
+				Origin::signed(ALICE),
+				BOB,
+				b"Currencies".to_vec(),
+				b"transfer_native_currency".to_vec(),
+				true
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			ChargeTransactionPayment::<Runtime>::from(0).validate(&BOB, CALL2, &INFO, 23),
+			TransactionValidityError::Invalid(InvalidTransaction::Payment)
+		);
+
+		assert_ok!(TransactionPayment::set_fee_free_call(
+			update_origin.clone(),
+			BOB,
+			b"Currencies".to_vec(),
+			b"transfer_native_currency".to_vec(),
+			true
+		));
+		assert!(TransactionPayment::fee_free_calls(
+			&BOB,
+			(b"Currencies".to_vec(), b"transfer_native_currency".to_vec())
+		));
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.validate(&BOB, CALL2, &INFO, 23)
+			.is_ok());
+		// other calls of the account are not free
+		assert_noop!(
+			ChargeTransactionPayment::<Runtime>::from(0).validate(&BOB, CALL, &INFO, 23),
+			TransactionValidityError::Invalid(InvalidTransaction::Payment)
+		);
+
+		// at most `MaxFeeFreeTxPerBlock` fee-free transactions in a block
+		for _ in 0..2 {
+			let pre = ChargeTransactionPayment::<Runtime>::from(0)
+				.pre_dispatch(&BOB, CALL2, &INFO, 23)
+				.unwrap();
+			assert_ok!(ChargeTransactionPayment::<Runtime>::post_dispatch(
+				pre,
+				&INFO,
+				&POST_INFO,
+				23,
+				&Ok(())
+			));
+		}
+		assert_eq!(TransactionPayment::fee_free_tx_count(), 2);
+		assert_eq!(FEE_UNBALANCED_AMOUNT.with(|a| *a.borrow()), 0);
+		assert_noop!(
+			ChargeTransactionPayment::<Runtime>::from(0).pre_dispatch(&BOB, CALL2, &INFO, 23),
+			TransactionValidityError::Invalid(InvalidTransaction::Payment)
+		);
+
+		TransactionPayment::on_finalize(1);
+		assert_eq!(TransactionPayment::fee_free_tx_count(), 0);
+		assert!(ChargeTransactionPayment::<Runtime>::from(0)
+			.pre_dispatch(&BOB, CALL2, &INFO, 23)
+			.is_ok());
+
+		assert_ok!(TransactionPayment::set_fee_free_call(
+			update_origin,
+			BOB,
+			b"Currencies".to_vec(),
+			b"transfer_native_currency".to_vec(),
+			false
+		));
+		assert_noop!(
+			ChargeTransactionPayment::<Runtime>::from(0).validate(&BOB, CALL2, &INFO, 23),
+			TransactionValidityError::Invalid(InvalidTransaction::Payment)
+		);
+	});
+}
+
 #[test]
 fn charge_fee_by_default_swap_path() {
 	ExtBuilder::default()
@@ -50,6 +50,7 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn set_alternative_fee_swap_path() -> Weight;
 	fn on_finalize() -> Weight;
+	fn set_fee_free_call() -> Weight;
 }
 
 /// Weights for module_transaction_payment using the Acala node and recommended hardware.
@@ -62,6 +63,10 @@ impl<T: frame_system::Config> WeightInfo for AcalaWeight<T> {
 	fn on_finalize() -> Weight {
 		(8_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_fee_free_call() -> Weight {
+		(6_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 }
@@ -75,6 +80,10 @@ impl WeightInfo for () {
 	fn on_finalize() -> Weight {
 		(8_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn set_fee_free_call() -> Weight {
+		(6_000_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 }
@@ -0,0 +1,601 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Vault Module
+//!
+//! ## Overview
+//!
+//! Vault module pools the deposits of an asset, either a DEX share or a
+//! token such as LDOT, and compounds the rewards of the pooled asset. Each
+//! vault is opened by governance and has its own account. The deposits are
+//! tracked in the share currency of the vault, which is minted on deposit and
+//! burned on withdrawal, and the asset amount of a share grows as the rewards
+//! are compounded.
+//!
+//! The DEX shares are staked to the incentive pool of the DEX share, and the
+//! tokens to the incentive pool of the token. At each harvest period, or when
+//! anyone calls `harvest`, the vault claims the incentive rewards, pays the
+//! performance fee of the rewards to the treasury and swaps the rest for the
+//! pair of the DEX share, or for the token. The DEX share vaults add liquidity
+//! with the pair and stake the new DEX shares, the token vaults stake the
+//! token. The swaps are limited by the max slippage against the oracle price,
+//! the liquidity is only added when the pool price is in the band of the
+//! oracle price, and the rewards which fail to be compounded are kept for the
+//! next harvest.
+//!
+//! The assets and the rewards of the vaults are tracked in storage, the
+//! currencies sent to the vault accounts are not counted. The shares and the
+//! assets are converted with a virtual offset, so that the share price can't
+//! be inflated by the first depositor.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{log, pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating, Zero},
+	FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{DEXIncentives, DEXManager, ExchangeRate, Price, PriceProvider, Rate, Ratio, TokenIncentives};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The virtual shares added to the total shares when converting between the
+/// assets and the shares.
+pub const VIRTUAL_SHARES: Balance = 1_000;
+/// The virtual assets added to the total assets when converting between the
+/// assets and the shares.
+pub const VIRTUAL_ASSETS: Balance = 1;
+
+/// The pooled asset of a vault.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct VaultInfo {
+	/// The currency of the vault shares.
+	pub share_currency_id: CurrencyId,
+	/// The rate of the rewards paid to the treasury as the performance fee.
+	pub performance_fee: Rate,
+	/// The asset amount pooled.
+	pub total_assets: Balance,
+	/// The total shares of the vault.
+	pub total_shares: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency to transfer the assets and the rewards, and to mint the
+		/// vault shares
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// DEX to swap the rewards and add liquidity
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The incentives to stake the DEX shares and claim the rewards
+		type DEXIncentives: DEXIncentives<Self::AccountId, CurrencyId, Balance>;
+
+		/// The incentives to stake the tokens and claim the rewards
+		type TokenIncentives: TokenIncentives<Self::AccountId, CurrencyId, Balance>;
+
+		/// The price source to limit the slippage of swaps
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The reward currencies compounded by the vaults
+		#[pallet::constant]
+		type RewardCurrencyIds: Get<Vec<CurrencyId>>;
+
+		/// The max slippage of the swaps against the oracle price
+		#[pallet::constant]
+		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
+
+		/// The account to receive the performance fees
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The period to harvest the vaults, must be non-zero
+		#[pallet::constant]
+		type HarvestPeriod: Get<Self::BlockNumber>;
+
+		/// The origin which may open the vaults and update the performance
+		/// fees
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The vault module id, the account of each vault is derived from it
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The vault of the asset is already opened
+		VaultAlreadyOpened,
+		/// The vault of the asset does not exist
+		VaultNotFound,
+		/// The performance fee is greater than one
+		InvalidPerformanceFee,
+		/// The share currency is a token, a DEX share, the asset, used by
+		/// another vault or already issued
+		InvalidShareCurrencyId,
+		/// The amount is too small to get any share
+		InvalidAmount,
+		/// The shares of the account are not enough
+		NotEnoughShares,
+		/// The oracle price is not available
+		InvalidFeedPrice,
+		/// The pool price is out of the band of the oracle price
+		PoolPriceOutOfBand,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A vault is opened. \[asset, share_currency_id, performance_fee\]
+		VaultOpened(CurrencyId, CurrencyId, Rate),
+		/// The performance fee of a vault is updated. \[asset,
+		/// performance_fee\]
+		PerformanceFeeUpdated(CurrencyId, Rate),
+		/// The asset is deposited. \[who, asset, amount, shares\]
+		Deposited(T::AccountId, CurrencyId, Balance, Balance),
+		/// The asset is withdrawn. \[who, asset, amount, shares\]
+		Withdrawn(T::AccountId, CurrencyId, Balance, Balance),
+		/// The performance fee of the rewards is paid to the treasury.
+		/// \[asset, reward_currency_id, fee\]
+		PerformanceFeePaid(CurrencyId, CurrencyId, Balance),
+		/// The rewards are compounded into the vault. \[asset,
+		/// compounded_amount\]
+		Harvested(CurrencyId, Balance),
+	}
+
+	/// The vaults of the assets.
+	///
+	/// Vaults: map CurrencyId => Option<VaultInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn vaults)]
+	pub type Vaults<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, VaultInfo, OptionQuery>;
+
+	/// The assets of the vaults of the share currencies.
+	///
+	/// ShareCurrencyVaults: map CurrencyId => Option<CurrencyId>
+	#[pallet::storage]
+	#[pallet::getter(fn share_currency_vaults)]
+	pub type ShareCurrencyVaults<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, CurrencyId, OptionQuery>;
+
+	/// The rewards claimed by the vaults, after the performance fee, which
+	/// are not swapped yet.
+	///
+	/// PendingRewards: double_map CurrencyId, CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn pending_rewards)]
+	pub type PendingRewards<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The amounts swapped from the rewards of the vaults which are not
+	/// compounded into the asset yet, such as the unused amount of the pair
+	/// when adding liquidity.
+	///
+	/// PendingCompounds: double_map CurrencyId, CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn pending_compounds)]
+	pub type PendingCompounds<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Harvest all the vaults at each harvest period.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if !(now % T::HarvestPeriod::get()).is_zero() {
+				return T::WeightInfo::on_initialize(0);
+			}
+
+			let assets: Vec<CurrencyId> = Vaults::<T>::iter_keys().collect();
+			for asset in assets.iter() {
+				if let Err(e) = Self::do_harvest(*asset) {
+					log::warn!(
+						target: "vault",
+						"do_harvest: failed to harvest the vault of {:?}: {:?}. \
+						This is unexpected but should be safe",
+						asset, e
+					);
+				}
+			}
+			T::WeightInfo::on_initialize(assets.len() as u32)
+		}
+
+		fn integrity_test() {
+			assert!(!T::HarvestPeriod::get().is_zero());
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Open the vault of the asset.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `asset`: the DEX share or the token pooled by the vault.
+		/// - `share_currency_id`: the currency of the vault shares, it must be
+		///   issued by the vault only.
+		/// - `performance_fee`: the rate of the rewards paid to the treasury.
+		#[pallet::weight((<T as Config>::WeightInfo::open_vault(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn open_vault(
+			origin: OriginFor<T>,
+			asset: CurrencyId,
+			share_currency_id: CurrencyId,
+			performance_fee: Rate,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(!Vaults::<T>::contains_key(asset), Error::<T>::VaultAlreadyOpened);
+			ensure!(performance_fee <= Rate::one(), Error::<T>::InvalidPerformanceFee);
+			ensure!(
+				share_currency_id != asset
+					&& !share_currency_id.is_token_currency_id()
+					&& !share_currency_id.is_dex_share_currency_id()
+					&& !ShareCurrencyVaults::<T>::contains_key(share_currency_id)
+					&& T::Currency::total_issuance(share_currency_id).is_zero(),
+				Error::<T>::InvalidShareCurrencyId
+			);
+
+			Vaults::<T>::insert(
+				asset,
+				VaultInfo {
+					share_currency_id,
+					performance_fee,
+					total_assets: Zero::zero(),
+					total_shares: Zero::zero(),
+				},
+			);
+			ShareCurrencyVaults::<T>::insert(share_currency_id, asset);
+			Self::deposit_event(Event::VaultOpened(asset, share_currency_id, performance_fee));
+			Ok(())
+		}
+
+		/// Update the performance fee of the vault.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `asset`: the asset of the vault.
+		/// - `performance_fee`: the rate of the rewards paid to the treasury.
+		#[pallet::weight((<T as Config>::WeightInfo::set_performance_fee(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_performance_fee(origin: OriginFor<T>, asset: CurrencyId, performance_fee: Rate) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(performance_fee <= Rate::one(), Error::<T>::InvalidPerformanceFee);
+			Vaults::<T>::try_mutate(asset, |maybe_vault| -> DispatchResult {
+				let vault = maybe_vault.as_mut().ok_or(Error::<T>::VaultNotFound)?;
+				vault.performance_fee = performance_fee;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::PerformanceFeeUpdated(asset, performance_fee));
+			Ok(())
+		}
+
+		/// Deposit the asset into the vault, and mint the vault shares.
+		///
+		/// - `asset`: the asset of the vault.
+		/// - `amount`: the asset amount to deposit.
+		#[pallet::weight(<T as Config>::WeightInfo::deposit())]
+		#[transactional]
+		pub fn deposit(origin: OriginFor<T>, asset: CurrencyId, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut vault = Self::vaults(asset).ok_or(Error::<T>::VaultNotFound)?;
+			let shares = Self::convert_to_shares(&vault, amount);
+			ensure!(!shares.is_zero(), Error::<T>::InvalidAmount);
+
+			let vault_account = Self::vault_account(asset);
+			T::Currency::transfer(asset, &who, &vault_account, amount)?;
+			if asset.is_dex_share_currency_id() {
+				T::DEXIncentives::do_deposit_dex_share(&vault_account, asset, amount)?;
+			} else {
+				T::TokenIncentives::do_deposit_token(&vault_account, asset, amount)?;
+			}
+			T::Currency::deposit(vault.share_currency_id, &who, shares)?;
+
+			vault.total_assets = vault.total_assets.saturating_add(amount);
+			vault.total_shares = vault.total_shares.saturating_add(shares);
+			Vaults::<T>::insert(asset, vault);
+			Self::deposit_event(Event::Deposited(who, asset, amount, shares));
+			Ok(())
+		}
+
+		/// Burn the vault shares, and withdraw the asset of them from the
+		/// vault.
+		///
+		/// - `asset`: the asset of the vault.
+		/// - `shares`: the shares to redeem.
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
+		#[transactional]
+		pub fn withdraw(origin: OriginFor<T>, asset: CurrencyId, #[pallet::compact] shares: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut vault = Self::vaults(asset).ok_or(Error::<T>::VaultNotFound)?;
+			ensure!(
+				T::Currency::free_balance(vault.share_currency_id, &who) >= shares,
+				Error::<T>::NotEnoughShares
+			);
+			let amount = Self::convert_to_assets(&vault, shares);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+			T::Currency::withdraw(vault.share_currency_id, &who, shares)?;
+			vault.total_assets = vault.total_assets.saturating_sub(amount);
+			vault.total_shares = vault.total_shares.saturating_sub(shares);
+			Vaults::<T>::insert(asset, vault);
+
+			let vault_account = Self::vault_account(asset);
+			if asset.is_dex_share_currency_id() {
+				T::DEXIncentives::do_withdraw_dex_share(&vault_account, asset, amount)?;
+			} else {
+				T::TokenIncentives::do_withdraw_token(&vault_account, asset, amount)?;
+			}
+			T::Currency::transfer(asset, &vault_account, &who, amount)?;
+			Self::deposit_event(Event::Withdrawn(who, asset, amount, shares));
+			Ok(())
+		}
+
+		/// Compound the rewards of the vault.
+		///
+		/// - `asset`: the asset of the vault.
+		#[pallet::weight(<T as Config>::WeightInfo::harvest())]
+		#[transactional]
+		pub fn harvest(origin: OriginFor<T>, asset: CurrencyId) -> DispatchResult {
+			ensure_signed(origin)?;
+			Self::do_harvest(asset)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the account of the vault of the asset.
+	pub fn vault_account(asset: CurrencyId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(asset)
+	}
+
+	/// Get the asset amount of the shares of `who`.
+	pub fn balance_of(asset: CurrencyId, who: &T::AccountId) -> Balance {
+		Self::vaults(asset).map_or(Zero::zero(), |vault| {
+			Self::convert_to_assets(&vault, T::Currency::free_balance(vault.share_currency_id, who))
+		})
+	}
+
+	/// Convert the asset amount to the shares, rounding down.
+	fn convert_to_shares(vault: &VaultInfo, amount: Balance) -> Balance {
+		ExchangeRate::checked_from_rational(
+			vault.total_shares.saturating_add(VIRTUAL_SHARES),
+			vault.total_assets.saturating_add(VIRTUAL_ASSETS),
+		)
+		.unwrap_or_default()
+		.saturating_mul_int(amount)
+	}
+
+	/// Convert the shares to the asset amount, rounding down.
+	fn convert_to_assets(vault: &VaultInfo, shares: Balance) -> Balance {
+		ExchangeRate::checked_from_rational(
+			vault.total_assets.saturating_add(VIRTUAL_ASSETS),
+			vault.total_shares.saturating_add(VIRTUAL_SHARES),
+		)
+		.unwrap_or_default()
+		.saturating_mul_int(shares)
+	}
+
+	/// Claim the rewards of the vault and compound them into the asset.
+	#[transactional]
+	fn do_harvest(asset: CurrencyId) -> DispatchResult {
+		let mut vault = Self::vaults(asset).ok_or(Error::<T>::VaultNotFound)?;
+		let vault_account = Self::vault_account(asset);
+		let reward_currency_ids = T::RewardCurrencyIds::get();
+
+		// the rewards are the balance changes of the claim, the currencies sent to
+		// the vault account by others are not compounded
+		let balances_before: Vec<Balance> = reward_currency_ids
+			.iter()
+			.map(|currency_id| T::Currency::free_balance(*currency_id, &vault_account))
+			.collect();
+		if asset.is_dex_share_currency_id() {
+			T::DEXIncentives::do_claim_dex_share_rewards(&vault_account, asset)?;
+		} else {
+			T::TokenIncentives::do_claim_token_rewards(&vault_account, asset)?;
+		}
+		for (reward_currency_id, balance_before) in reward_currency_ids.iter().zip(balances_before) {
+			let reward = T::Currency::free_balance(*reward_currency_id, &vault_account).saturating_sub(balance_before);
+			if reward.is_zero() {
+				continue;
+			}
+
+			let fee = vault.performance_fee.saturating_mul_int(reward);
+			if !fee.is_zero() {
+				T::Currency::transfer(*reward_currency_id, &vault_account, &T::TreasuryAccount::get(), fee)?;
+				Self::deposit_event(Event::PerformanceFeePaid(asset, *reward_currency_id, fee));
+			}
+			PendingRewards::<T>::mutate(asset, reward_currency_id, |pending| {
+				*pending = pending.saturating_add(reward.saturating_sub(fee))
+			});
+		}
+
+		let compounded_amount = if let Some((currency_id_a, currency_id_b)) = asset.split_dex_share_currency_id() {
+			Self::compound_rewards(asset, &reward_currency_ids, &[currency_id_a, currency_id_b]);
+			Self::add_liquidity(asset, currency_id_a, currency_id_b).unwrap_or_else(|e| {
+				log::warn!(
+					target: "vault",
+					"add_liquidity: failed to add liquidity for the vault of {:?}: {:?}. \
+					This is unexpected but should be safe",
+					asset, e
+				);
+				Zero::zero()
+			})
+		} else {
+			Self::compound_rewards(asset, &reward_currency_ids, &[asset]);
+			let amount = PendingCompounds::<T>::take(asset, asset);
+			if !amount.is_zero() {
+				T::TokenIncentives::do_deposit_token(&vault_account, asset, amount)?;
+			}
+			amount
+		};
+
+		vault.total_assets = vault.total_assets.saturating_add(compounded_amount);
+		Vaults::<T>::insert(asset, vault);
+		Self::deposit_event(Event::Harvested(asset, compounded_amount));
+		Ok(())
+	}
+
+	/// Swap the pending rewards of the vault equally for the targets. The
+	/// reward currencies which fail are kept for the next harvest.
+	fn compound_rewards(asset: CurrencyId, reward_currency_ids: &[CurrencyId], targets: &[CurrencyId]) {
+		for reward_currency_id in reward_currency_ids {
+			let reward = Self::pending_rewards(asset, reward_currency_id);
+			if reward.is_zero() {
+				continue;
+			}
+
+			if let Err(e) = Self::compound_reward(asset, *reward_currency_id, reward, targets) {
+				log::warn!(
+					target: "vault",
+					"compound_reward: failed to compound {:?} {:?} into the vault of {:?}: {:?}. \
+					This is unexpected but should be safe",
+					reward, reward_currency_id, asset, e
+				);
+			}
+		}
+	}
+
+	#[transactional]
+	fn compound_reward(
+		asset: CurrencyId,
+		reward_currency_id: CurrencyId,
+		reward: Balance,
+		targets: &[CurrencyId],
+	) -> DispatchResult {
+		let vault_account = Self::vault_account(asset);
+		let supply_amount = reward.checked_div(targets.len() as Balance).unwrap_or_default();
+		if supply_amount.is_zero() {
+			return Ok(());
+		}
+
+		for target_currency_id in targets {
+			let target_amount = if *target_currency_id == reward_currency_id {
+				supply_amount
+			} else {
+				let min_target_amount = T::PriceSource::get_relative_price(reward_currency_id, *target_currency_id)
+					.ok_or(Error::<T>::InvalidFeedPrice)?
+					.saturating_mul(Ratio::one().saturating_sub(T::MaxSwapSlippageCompareToOracle::get()))
+					.saturating_mul_int(supply_amount);
+				T::DEX::swap_with_exact_supply(
+					&vault_account,
+					&[reward_currency_id, *target_currency_id],
+					supply_amount,
+					min_target_amount,
+				)?
+			};
+			PendingCompounds::<T>::mutate(asset, target_currency_id, |pending| {
+				*pending = pending.saturating_add(target_amount)
+			});
+		}
+
+		let remainder = reward.saturating_sub(supply_amount.saturating_mul(targets.len() as Balance));
+		if remainder.is_zero() {
+			PendingRewards::<T>::remove(asset, reward_currency_id);
+		} else {
+			PendingRewards::<T>::insert(asset, reward_currency_id, remainder);
+		}
+		Ok(())
+	}
+
+	/// Add liquidity with the pending amounts of the pair and stake the DEX
+	/// shares, the unused amounts are kept for the next harvest. Returns the
+	/// increment of the DEX shares.
+	#[transactional]
+	fn add_liquidity(
+		asset: CurrencyId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let amount_a = Self::pending_compounds(asset, currency_id_a);
+		let amount_b = Self::pending_compounds(asset, currency_id_b);
+		if amount_a.is_zero() || amount_b.is_zero() {
+			return Ok(Zero::zero());
+		}
+
+		// the liquidity is added at the pool price, which must be in the band of the oracle price
+		let oracle_price =
+			T::PriceSource::get_relative_price(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidFeedPrice)?;
+		let (pool_a, pool_b) = T::DEX::get_liquidity_pool(currency_id_a, currency_id_b);
+		let pool_price = Price::checked_from_rational(pool_b, pool_a).ok_or(Error::<T>::PoolPriceOutOfBand)?;
+		let max_slippage = T::MaxSwapSlippageCompareToOracle::get();
+		ensure!(
+			pool_price
+				.max(oracle_price)
+				.saturating_sub(pool_price.min(oracle_price))
+				<= max_slippage.saturating_mul(oracle_price),
+			Error::<T>::PoolPriceOutOfBand
+		);
+
+		let total_shares = T::Currency::total_issuance(asset);
+		let expected_share_increment = Ratio::checked_from_rational(amount_a, pool_a)
+			.unwrap_or_default()
+			.saturating_mul_int(total_shares)
+			.min(
+				Ratio::checked_from_rational(amount_b, pool_b)
+					.unwrap_or_default()
+					.saturating_mul_int(total_shares),
+			);
+		let min_share_increment = Ratio::one()
+			.saturating_sub(max_slippage)
+			.saturating_mul_int(expected_share_increment);
+
+		let vault_account = Self::vault_account(asset);
+		let balance_a = T::Currency::free_balance(currency_id_a, &vault_account);
+		let balance_b = T::Currency::free_balance(currency_id_b, &vault_account);
+		let share_before = T::Currency::free_balance(asset, &vault_account);
+		T::DEX::add_liquidity(
+			&vault_account,
+			currency_id_a,
+			currency_id_b,
+			amount_a,
+			amount_b,
+			min_share_increment,
+			false,
+		)?;
+		let used_a = balance_a.saturating_sub(T::Currency::free_balance(currency_id_a, &vault_account));
+		let used_b = balance_b.saturating_sub(T::Currency::free_balance(currency_id_b, &vault_account));
+		let share_increment = T::Currency::free_balance(asset, &vault_account).saturating_sub(share_before);
+
+		PendingCompounds::<T>::insert(asset, currency_id_a, amount_a.saturating_sub(used_a));
+		PendingCompounds::<T>::insert(asset, currency_id_b, amount_b.saturating_sub(used_b));
+		if !share_increment.is_zero() {
+			T::DEXIncentives::do_deposit_dex_share(&vault_account, asset, share_increment)?;
+		}
+		Ok(share_increment)
+	}
+}
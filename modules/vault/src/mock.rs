@@ -0,0 +1,321 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the vault module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, traits::Nothing};
+use frame_system::EnsureSignedBy;
+use orml_traits::{parameter_type_with_key, MultiReservableCurrency};
+use primitives::{Amount, DexShare, TokenSymbol};
+use sp_core::{H160, H256};
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult};
+use sp_std::cell::RefCell;
+use support::Price;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const TREASURY: AccountId = 10;
+pub const DEX_POOL: AccountId = 20;
+pub const REWARD_SOURCE: AccountId = 30;
+pub const ACA: CurrencyId = CurrencyId::Token(TokenSymbol::ACA);
+pub const AUSD: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+pub const LDOT: CurrencyId = CurrencyId::Token(TokenSymbol::LDOT);
+pub const AUSD_DOT_LP: CurrencyId =
+	CurrencyId::DexShare(DexShare::Token(TokenSymbol::AUSD), DexShare::Token(TokenSymbol::DOT));
+pub const LP_VAULT_SHARE: CurrencyId = CurrencyId::ForeignAsset(0);
+pub const LDOT_VAULT_SHARE: CurrencyId = CurrencyId::ForeignAsset(1);
+
+mod vault {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = Nothing;
+}
+
+thread_local! {
+	static PENDING_REWARD: RefCell<Balance> = RefCell::new(0);
+}
+
+/// Set the ACA rewards paid at the next claim of the DEX share or the token
+/// rewards.
+pub fn set_pending_reward(amount: Balance) {
+	PENDING_REWARD.with(|v| *v.borrow_mut() = amount);
+}
+
+fn claim_pending_reward(who: &AccountId) -> DispatchResult {
+	let reward = PENDING_REWARD.with(|v| v.replace(0));
+	<Tokens as MultiCurrency<AccountId>>::transfer(ACA, &REWARD_SOURCE, who, reward)
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		match currency_id {
+			ACA | AUSD | DOT | LDOT => Some(Price::one()),
+			_ => None,
+		}
+	}
+}
+
+pub struct MockDEXIncentives;
+impl DEXIncentives<AccountId, CurrencyId, Balance> for MockDEXIncentives {
+	fn do_deposit_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::reserve(lp_currency_id, who, amount)
+	}
+
+	fn do_withdraw_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		let _ = Tokens::unreserve(lp_currency_id, who, amount);
+		Ok(())
+	}
+
+	fn do_claim_dex_share_rewards(who: &AccountId, _lp_currency_id: CurrencyId) -> DispatchResult {
+		claim_pending_reward(who)
+	}
+}
+
+pub struct MockTokenIncentives;
+impl TokenIncentives<AccountId, CurrencyId, Balance> for MockTokenIncentives {
+	fn do_deposit_token(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::reserve(currency_id, who, amount)
+	}
+
+	fn do_withdraw_token(who: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		let _ = Tokens::unreserve(currency_id, who, amount);
+		Ok(())
+	}
+
+	fn do_claim_token_rewards(who: &AccountId, _currency_id: CurrencyId) -> DispatchResult {
+		claim_pending_reward(who)
+	}
+}
+
+/// A DEX swaps all the currencies 1:1, and mints two DEX shares for each
+/// pair of currencies added. The liquidity pool is the balances of `DEX_POOL`.
+pub struct MockDEX;
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		(
+			Tokens::free_balance(currency_id_a, &DEX_POOL),
+			Tokens::free_balance(currency_id_b, &DEX_POOL),
+		)
+	}
+
+	fn get_liquidity_token_address(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<H160> {
+		unimplemented!()
+	}
+
+	fn get_swap_target_amount(_path: &[CurrencyId], supply_amount: Balance) -> Option<Balance> {
+		Some(supply_amount)
+	}
+
+	fn get_swap_supply_amount(_path: &[CurrencyId], _target_amount: Balance) -> Option<Balance> {
+		unimplemented!()
+	}
+
+	fn swap_with_exact_supply(
+		who: &AccountId,
+		path: &[CurrencyId],
+		supply_amount: Balance,
+		min_target_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let target_amount = Self::get_swap_target_amount(path, supply_amount).unwrap_or_default();
+		if target_amount < min_target_amount {
+			return Err(DispatchError::Other("slippage"));
+		}
+		<Tokens as MultiCurrency<AccountId>>::transfer(path[0], who, &DEX_POOL, supply_amount)?;
+		<Tokens as MultiCurrency<AccountId>>::transfer(path[path.len() - 1], &DEX_POOL, who, target_amount)?;
+		Ok(target_amount)
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn add_liquidity(
+		who: &AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		max_amount_a: Balance,
+		max_amount_b: Balance,
+		min_share_increment: Balance,
+		_stake_increment_share: bool,
+	) -> DispatchResult {
+		let amount = max_amount_a.min(max_amount_b);
+		let share_increment = amount.saturating_mul(2);
+		if share_increment < min_share_increment {
+			return Err(DispatchError::Other("unacceptable share increment"));
+		}
+		let lp_currency_id = CurrencyId::join_dex_share_currency_id(currency_id_a, currency_id_b).unwrap();
+		<Tokens as MultiCurrency<AccountId>>::transfer(currency_id_a, who, &DEX_POOL, amount)?;
+		<Tokens as MultiCurrency<AccountId>>::transfer(currency_id_b, who, &DEX_POOL, amount)?;
+		Tokens::deposit(lp_currency_id, who, share_increment)
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_remove_share: Balance,
+		_min_withdrawn_a: Balance,
+		_min_withdrawn_b: Balance,
+		_by_unstake: bool,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
+ord_parameter_types! {
+	pub const One: AccountId = ALICE;
+}
+
+parameter_types! {
+	pub RewardCurrencyIds: Vec<CurrencyId> = vec![ACA, AUSD, DOT];
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(5, 100);
+	pub const TreasuryAccount: AccountId = TREASURY;
+	pub const HarvestPeriod: BlockNumber = 10;
+	pub const VaultPalletId: PalletId = PalletId(*b"aca/vlts");
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type DEX = MockDEX;
+	type DEXIncentives = MockDEXIncentives;
+	type TokenIncentives = MockTokenIncentives;
+	type PriceSource = MockPriceSource;
+	type RewardCurrencyIds = RewardCurrencyIds;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type TreasuryAccount = TreasuryAccount;
+	type HarvestPeriod = HarvestPeriod;
+	type UpdateOrigin = EnsureSignedBy<One, AccountId>;
+	type PalletId = VaultPalletId;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		VaultModule: vault::{Pallet, Storage, Call, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, AUSD_DOT_LP, 1000),
+				(ALICE, LDOT, 1000),
+				(BOB, AUSD_DOT_LP, 1000),
+				(BOB, LDOT, 1000),
+				(REWARD_SOURCE, ACA, 1000),
+				(DEX_POOL, AUSD, 1000),
+				(DEX_POOL, DOT, 1000),
+				(DEX_POOL, LDOT, 1000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		set_pending_reward(0);
+
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
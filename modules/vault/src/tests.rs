@@ -0,0 +1,311 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the vault module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiReservableCurrency;
+use sp_runtime::traits::BadOrigin;
+
+fn performance_fee() -> Rate {
+	Rate::saturating_from_rational(10, 100)
+}
+
+#[test]
+fn open_vault_and_set_performance_fee_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			VaultModule::open_vault(Origin::signed(BOB), AUSD_DOT_LP, LP_VAULT_SHARE, performance_fee()),
+			BadOrigin
+		);
+		assert_noop!(
+			VaultModule::open_vault(
+				Origin::signed(ALICE),
+				AUSD_DOT_LP,
+				LP_VAULT_SHARE,
+				Rate::saturating_from_rational(101, 100)
+			),
+			Error::<Runtime>::InvalidPerformanceFee
+		);
+		assert_noop!(
+			VaultModule::open_vault(Origin::signed(ALICE), AUSD_DOT_LP, AUSD_DOT_LP, performance_fee()),
+			Error::<Runtime>::InvalidShareCurrencyId
+		);
+		assert_noop!(
+			VaultModule::open_vault(Origin::signed(ALICE), AUSD_DOT_LP, LDOT, performance_fee()),
+			Error::<Runtime>::InvalidShareCurrencyId
+		);
+		assert_noop!(
+			VaultModule::set_performance_fee(Origin::signed(ALICE), AUSD_DOT_LP, performance_fee()),
+			Error::<Runtime>::VaultNotFound
+		);
+
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			AUSD_DOT_LP,
+			LP_VAULT_SHARE,
+			performance_fee()
+		));
+		System::assert_last_event(Event::VaultModule(crate::Event::VaultOpened(
+			AUSD_DOT_LP,
+			LP_VAULT_SHARE,
+			performance_fee(),
+		)));
+		assert_eq!(
+			VaultModule::vaults(AUSD_DOT_LP),
+			Some(VaultInfo {
+				share_currency_id: LP_VAULT_SHARE,
+				performance_fee: performance_fee(),
+				total_assets: 0,
+				total_shares: 0,
+			})
+		);
+		assert_eq!(VaultModule::share_currency_vaults(LP_VAULT_SHARE), Some(AUSD_DOT_LP));
+		assert_noop!(
+			VaultModule::open_vault(Origin::signed(ALICE), AUSD_DOT_LP, LDOT_VAULT_SHARE, performance_fee()),
+			Error::<Runtime>::VaultAlreadyOpened
+		);
+		assert_noop!(
+			VaultModule::open_vault(Origin::signed(ALICE), LDOT, LP_VAULT_SHARE, performance_fee()),
+			Error::<Runtime>::InvalidShareCurrencyId
+		);
+
+		assert_ok!(VaultModule::set_performance_fee(
+			Origin::signed(ALICE),
+			AUSD_DOT_LP,
+			Rate::saturating_from_rational(20, 100)
+		));
+		System::assert_last_event(Event::VaultModule(crate::Event::PerformanceFeeUpdated(
+			AUSD_DOT_LP,
+			Rate::saturating_from_rational(20, 100),
+		)));
+		assert_eq!(
+			VaultModule::vaults(AUSD_DOT_LP).unwrap().performance_fee,
+			Rate::saturating_from_rational(20, 100)
+		);
+	});
+}
+
+#[test]
+fn deposit_and_withdraw_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			VaultModule::deposit(Origin::signed(ALICE), AUSD_DOT_LP, 100),
+			Error::<Runtime>::VaultNotFound
+		);
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			AUSD_DOT_LP,
+			LP_VAULT_SHARE,
+			performance_fee()
+		));
+		assert_noop!(
+			VaultModule::deposit(Origin::signed(ALICE), AUSD_DOT_LP, 0),
+			Error::<Runtime>::InvalidAmount
+		);
+
+		let vault_account = VaultModule::vault_account(AUSD_DOT_LP);
+		assert_ok!(VaultModule::deposit(Origin::signed(ALICE), AUSD_DOT_LP, 100));
+		System::assert_last_event(Event::VaultModule(crate::Event::Deposited(
+			ALICE,
+			AUSD_DOT_LP,
+			100,
+			100_000,
+		)));
+		assert_eq!(Tokens::free_balance(LP_VAULT_SHARE, &ALICE), 100_000);
+		assert_eq!(Tokens::free_balance(AUSD_DOT_LP, &ALICE), 900);
+		assert_eq!(Tokens::reserved_balance(AUSD_DOT_LP, &vault_account), 100);
+
+		// the vault shares are transferred as a token
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			LP_VAULT_SHARE,
+			&ALICE,
+			&BOB,
+			40_000
+		));
+		assert_eq!(VaultModule::balance_of(AUSD_DOT_LP, &BOB), 40);
+
+		assert_noop!(
+			VaultModule::withdraw(Origin::signed(BOB), AUSD_DOT_LP, 40_001),
+			Error::<Runtime>::NotEnoughShares
+		);
+		assert_ok!(VaultModule::withdraw(Origin::signed(BOB), AUSD_DOT_LP, 40_000));
+		System::assert_last_event(Event::VaultModule(crate::Event::Withdrawn(
+			BOB,
+			AUSD_DOT_LP,
+			40,
+			40_000,
+		)));
+		assert_eq!(Tokens::free_balance(LP_VAULT_SHARE, &BOB), 0);
+		assert_eq!(Tokens::total_issuance(LP_VAULT_SHARE), 60_000);
+		assert_eq!(Tokens::free_balance(AUSD_DOT_LP, &BOB), 1040);
+		assert_eq!(Tokens::reserved_balance(AUSD_DOT_LP, &vault_account), 60);
+		assert_eq!(
+			VaultModule::vaults(AUSD_DOT_LP),
+			Some(VaultInfo {
+				share_currency_id: LP_VAULT_SHARE,
+				performance_fee: performance_fee(),
+				total_assets: 60,
+				total_shares: 60_000,
+			})
+		);
+	});
+}
+
+#[test]
+fn donation_cannot_inflate_share_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			LDOT,
+			LDOT_VAULT_SHARE,
+			performance_fee()
+		));
+		assert_ok!(VaultModule::deposit(Origin::signed(ALICE), LDOT, 1));
+		assert_eq!(Tokens::free_balance(LDOT_VAULT_SHARE, &ALICE), 1_000);
+
+		// the donation to the vault account is not counted as the vault assets
+		let vault_account = VaultModule::vault_account(LDOT);
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			LDOT,
+			&ALICE,
+			&vault_account,
+			500
+		));
+		assert_eq!(VaultModule::vaults(LDOT).unwrap().total_assets, 1);
+
+		assert_ok!(VaultModule::deposit(Origin::signed(BOB), LDOT, 100));
+		assert_eq!(Tokens::free_balance(LDOT_VAULT_SHARE, &BOB), 100_000);
+		assert_ok!(VaultModule::withdraw(Origin::signed(BOB), LDOT, 100_000));
+		assert_eq!(Tokens::free_balance(LDOT, &BOB), 1000);
+	});
+}
+
+#[test]
+fn harvest_dex_share_vault_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			AUSD_DOT_LP,
+			LP_VAULT_SHARE,
+			performance_fee()
+		));
+		assert_ok!(VaultModule::deposit(Origin::signed(ALICE), AUSD_DOT_LP, 100));
+
+		// the donation to the vault account is not compounded
+		let vault_account = VaultModule::vault_account(AUSD_DOT_LP);
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			ACA,
+			&REWARD_SOURCE,
+			&vault_account,
+			50
+		));
+
+		set_pending_reward(100);
+		assert_ok!(VaultModule::harvest(Origin::signed(BOB), AUSD_DOT_LP));
+		System::assert_has_event(Event::VaultModule(crate::Event::PerformanceFeePaid(
+			AUSD_DOT_LP,
+			ACA,
+			10,
+		)));
+		System::assert_last_event(Event::VaultModule(crate::Event::Harvested(AUSD_DOT_LP, 90)));
+		assert_eq!(Tokens::free_balance(ACA, &TREASURY), 10);
+		assert_eq!(Tokens::free_balance(ACA, &vault_account), 50);
+		assert_eq!(VaultModule::pending_rewards(AUSD_DOT_LP, ACA), 0);
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, AUSD), 0);
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, DOT), 0);
+
+		assert_eq!(Tokens::reserved_balance(AUSD_DOT_LP, &vault_account), 190);
+		assert_eq!(VaultModule::vaults(AUSD_DOT_LP).unwrap().total_assets, 190);
+		assert_eq!(VaultModule::balance_of(AUSD_DOT_LP, &ALICE), 189);
+
+		assert_ok!(VaultModule::withdraw(Origin::signed(ALICE), AUSD_DOT_LP, 100_000));
+		assert_eq!(Tokens::free_balance(AUSD_DOT_LP, &ALICE), 1089);
+	});
+}
+
+#[test]
+fn harvest_dex_share_vault_keeps_pair_when_pool_price_out_of_band() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			AUSD_DOT_LP,
+			LP_VAULT_SHARE,
+			performance_fee()
+		));
+		assert_ok!(VaultModule::deposit(Origin::signed(ALICE), AUSD_DOT_LP, 100));
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			DOT, &DEX_POOL, &BOB, 200
+		));
+
+		set_pending_reward(100);
+		assert_ok!(VaultModule::harvest(Origin::signed(BOB), AUSD_DOT_LP));
+		System::assert_last_event(Event::VaultModule(crate::Event::Harvested(AUSD_DOT_LP, 0)));
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, AUSD), 45);
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, DOT), 45);
+		assert_eq!(VaultModule::vaults(AUSD_DOT_LP).unwrap().total_assets, 100);
+
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			DOT, &BOB, &DEX_POOL, 200
+		));
+		assert_ok!(VaultModule::harvest(Origin::signed(BOB), AUSD_DOT_LP));
+		System::assert_last_event(Event::VaultModule(crate::Event::Harvested(AUSD_DOT_LP, 90)));
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, AUSD), 0);
+		assert_eq!(VaultModule::pending_compounds(AUSD_DOT_LP, DOT), 0);
+		assert_eq!(VaultModule::vaults(AUSD_DOT_LP).unwrap().total_assets, 190);
+	});
+}
+
+#[test]
+fn harvest_token_vault_periodically() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(VaultModule::open_vault(
+			Origin::signed(ALICE),
+			LDOT,
+			LDOT_VAULT_SHARE,
+			performance_fee()
+		));
+		assert_ok!(VaultModule::deposit(Origin::signed(ALICE), LDOT, 100));
+
+		let vault_account = VaultModule::vault_account(LDOT);
+		assert_eq!(Tokens::reserved_balance(LDOT, &vault_account), 100);
+		assert_ok!(<Tokens as MultiCurrency<AccountId>>::transfer(
+			LDOT,
+			&DEX_POOL,
+			&vault_account,
+			20
+		));
+		set_pending_reward(50);
+
+		// not the harvest period
+		VaultModule::on_initialize(5);
+		assert_eq!(VaultModule::vaults(LDOT).unwrap().total_assets, 100);
+
+		VaultModule::on_initialize(10);
+		System::assert_last_event(Event::VaultModule(crate::Event::Harvested(LDOT, 45)));
+		assert_eq!(Tokens::free_balance(ACA, &TREASURY), 5);
+		assert_eq!(Tokens::free_balance(LDOT, &vault_account), 20);
+		assert_eq!(Tokens::reserved_balance(LDOT, &vault_account), 145);
+		assert_eq!(VaultModule::vaults(LDOT).unwrap().total_assets, 145);
+		assert_eq!(VaultModule::balance_of(LDOT, &ALICE), 144);
+	});
+}
@@ -0,0 +1,239 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Weight Metrics Module
+//!
+//! ## Overview
+//!
+//! Weight metrics module records the count of executed calls and the
+//! aggregate weight they consumed for each pallet of each day, so the
+//! operators can see which subsystems dominate the block weight and plan
+//! the capacity. The metrics are recorded by the `RecordWeightMetrics`
+//! signed extension after the dispatch of every extrinsic, including
+//! unsigned extrinsics and inherents. The storage access of the recording is
+//! registered to the block weight before dispatch. Only the metrics of the
+//! last `KeepDays` days are kept.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{
+	dispatch::GetCallMetadata,
+	pallet_prelude::*,
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_io::KillStorageResult;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, Dispatchable, PostDispatchInfoOf, Saturating, SignedExtension, UniqueSaturatedInto, Zero,
+	},
+	transaction_validity::TransactionValidityError,
+	RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The weight metrics of a pallet in a day
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct WeightStats {
+	/// The count of executed calls
+	pub call_count: u32,
+	/// The aggregate weight consumed by the calls
+	pub weight: Weight,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The number of blocks of a day
+		#[pallet::constant]
+		type BlocksPerDay: Get<Self::BlockNumber>;
+
+		/// The number of days to keep the metrics
+		#[pallet::constant]
+		type KeepDays: Get<u32>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The weight metrics of the pallets in the days kept.
+	///
+	/// PalletWeights: double_map DayIndex, PalletName => WeightStats
+	#[pallet::storage]
+	#[pallet::getter(fn pallet_weights)]
+	pub type PalletWeights<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Twox64Concat, Vec<u8>, WeightStats, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Remove the metrics of the day moved out of the kept days at the
+		/// beginning of a day
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let blocks_per_day = T::BlocksPerDay::get();
+			let keep_days = T::KeepDays::get();
+			if blocks_per_day.is_zero() || !(now % blocks_per_day).is_zero() || Self::day_index(now) < keep_days {
+				return 0;
+			}
+
+			let removed = match PalletWeights::<T>::remove_prefix(Self::day_index(now) - keep_days, None) {
+				KillStorageResult::AllRemoved(count) => count,
+				KillStorageResult::SomeRemaining(count) => count,
+			};
+			T::WeightInfo::on_initialize(removed)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the index of the day of the block.
+	pub fn day_index(block_number: T::BlockNumber) -> u32 {
+		let blocks_per_day = T::BlocksPerDay::get();
+		if blocks_per_day.is_zero() {
+			Zero::zero()
+		} else {
+			(block_number / blocks_per_day).unique_saturated_into()
+		}
+	}
+
+	/// Record the call of the pallet consumed `weight`.
+	pub fn record(pallet_name: &[u8], weight: Weight) {
+		let day = Self::day_index(frame_system::Pallet::<T>::block_number());
+		PalletWeights::<T>::mutate(day, pallet_name, |stats| {
+			stats.call_count = stats.call_count.saturating_add(1);
+			stats.weight = stats.weight.saturating_add(weight);
+		});
+	}
+
+	/// Get the call count and the aggregate weight of each pallet of each day
+	/// of the last `days` days, including today, in ascending order of the
+	/// day index.
+	pub fn weight_report(days: u32) -> Vec<(u32, Vec<u8>, u32, Weight)> {
+		let days = days.min(T::KeepDays::get());
+		if days.is_zero() {
+			return vec![];
+		}
+
+		let today = Self::day_index(frame_system::Pallet::<T>::block_number());
+		let mut report = vec![];
+		for day in today.saturating_sub(days - 1)..=today {
+			let mut stats = PalletWeights::<T>::iter_prefix(day).collect::<Vec<_>>();
+			stats.sort_by(|(a, _), (b, _)| a.cmp(b));
+			report.extend(
+				stats
+					.into_iter()
+					.map(|(pallet_name, stats)| (day, pallet_name, stats.call_count, stats.weight)),
+			);
+		}
+		report
+	}
+}
+
+/// Record the weight metrics of the pallet of the call after dispatch.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct RecordWeightMetrics<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for RecordWeightMetrics<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "RecordWeightMetrics")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> RecordWeightMetrics<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for RecordWeightMetrics<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for RecordWeightMetrics<T>
+where
+	<T as frame_system::Config>::Call: GetCallMetadata + Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "RecordWeightMetrics";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = Vec<u8>;
+
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn pre_dispatch(
+		self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Self::pre_dispatch_unsigned(call, info, len)
+	}
+
+	fn pre_dispatch_unsigned(
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		// charge the storage read and write of the recording in `post_dispatch`
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(T::WeightInfo::record(), info.class);
+		Ok(call.get_call_metadata().pallet_name.as_bytes().to_vec())
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		if !pre.is_empty() {
+			Pallet::<T>::record(&pre, post_info.calc_actual_weight(info));
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,118 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the weight metrics module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::assert_ok;
+use mock::*;
+
+#[test]
+fn record_weight_metrics_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let call = Call::System(frame_system::Call::remark { remark: vec![] });
+		let info = DispatchInfo {
+			weight: 100,
+			..Default::default()
+		};
+
+		let pre = RecordWeightMetrics::<Runtime>::new()
+			.pre_dispatch(&ALICE, &call, &info, 0)
+			.unwrap();
+		assert_eq!(pre, b"System".to_vec());
+		assert_eq!(System::block_weight().total(), <() as WeightInfo>::record());
+		let post_info = PostDispatchInfo {
+			actual_weight: Some(60),
+			pays_fee: Default::default(),
+		};
+		assert_ok!(RecordWeightMetrics::<Runtime>::post_dispatch(
+			pre,
+			&info,
+			&post_info,
+			0,
+			&Ok(())
+		));
+		assert_eq!(
+			WeightMetricsModule::pallet_weights(0, b"System".to_vec()),
+			WeightStats {
+				call_count: 1,
+				weight: 60
+			}
+		);
+
+		// unsigned extrinsics are recorded too
+		let pre = RecordWeightMetrics::<Runtime>::pre_dispatch_unsigned(&call, &info, 0).unwrap();
+		assert_eq!(System::block_weight().total(), 2 * <() as WeightInfo>::record());
+		assert_ok!(RecordWeightMetrics::<Runtime>::post_dispatch(
+			pre,
+			&info,
+			&Default::default(),
+			0,
+			&Ok(())
+		));
+		assert_eq!(
+			WeightMetricsModule::pallet_weights(0, b"System".to_vec()),
+			WeightStats {
+				call_count: 2,
+				weight: 160
+			}
+		);
+	});
+}
+
+#[test]
+fn weight_report_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(WeightMetricsModule::weight_report(0), vec![]);
+		WeightMetricsModule::record(b"System", 10);
+
+		System::set_block_number(10);
+		assert_eq!(WeightMetricsModule::on_initialize(10), 0);
+		WeightMetricsModule::record(b"System", 20);
+		WeightMetricsModule::record(b"Dex", 5);
+		assert_eq!(
+			WeightMetricsModule::weight_report(5),
+			vec![
+				(0, b"System".to_vec(), 1, 10),
+				(1, b"Dex".to_vec(), 1, 5),
+				(1, b"System".to_vec(), 1, 20)
+			]
+		);
+
+		// the metrics of the day moved out of the kept days are removed
+		System::set_block_number(20);
+		WeightMetricsModule::on_initialize(20);
+		assert_eq!(
+			WeightMetricsModule::pallet_weights(0, b"System".to_vec()),
+			Default::default()
+		);
+		WeightMetricsModule::record(b"Dex", 5);
+		assert_eq!(
+			WeightMetricsModule::weight_report(2),
+			vec![
+				(1, b"Dex".to_vec(), 1, 5),
+				(1, b"System".to_vec(), 1, 20),
+				(2, b"Dex".to_vec(), 1, 5)
+			]
+		);
+	});
+}
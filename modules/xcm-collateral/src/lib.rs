@@ -0,0 +1,336 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # XCM Collateral Module
+//!
+//! ## Overview
+//!
+//! XCM collateral module deposits the relay chain currency reserve
+//! transferred from the relay chain to the CDP of the user as collateral, so
+//! users could borrow against their relay chain funds in one reserve transfer.
+//!
+//! A user requests a deposit here, then reserve transfers the relay chain
+//! currency from the relay chain to the beneficiary location with the request
+//! id attached: `X2(AccountId32 { .. }, GeneralIndex(request_id))`. The
+//! relay chain currency is only credited when the reserve transfer arrives
+//! through `CollateralDepositTransactor`, after the XCM executor verified the
+//! relay chain as the reserve: it is deposited to the account first, then at
+//! most the requested amount is deposited to the CDP of the requester as
+//! collateral. If the request does not exist or belongs to another account,
+//! the relay chain currency is kept in the account. Requests not arrived in
+//! `ConfirmationTimeout` blocks expire.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use primitives::{Amount, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, marker::PhantomData};
+use support::EmergencyShutdown;
+use xcm::latest::prelude::*;
+use xcm_executor::{
+	traits::{Convert, TransactAsset},
+	Assets,
+};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub type RequestId = u32;
+
+/// The request to deposit relay chain currency reserve transferred from the
+/// relay chain as CDP collateral
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct DepositRequest<AccountId, BlockNumber> {
+	/// The owner of the CDP to deposit the collateral
+	pub who: AccountId,
+	/// The maximum amount of relay chain currency to deposit
+	pub amount: Balance,
+	/// The request expires at this block if the reserve transfer has not
+	/// arrived
+	pub expire_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + loans::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Relay chain currency id
+		#[pallet::constant]
+		type RelayChainCurrencyId: Get<CurrencyId>;
+
+		/// The number of blocks a deposit request expires after if the reserve
+		/// transfer has not arrived.
+		#[pallet::constant]
+		type ConfirmationTimeout: Get<Self::BlockNumber>;
+
+		/// Emergency shutdown.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The amount is invalid
+		InvalidAmount,
+		/// The deposit request does not exist
+		RequestNotFound,
+		/// The caller is not the owner of the deposit request
+		NoPermission,
+		/// The system has been shutdown
+		AlreadyShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Deposit requested. \[request_id, who, amount, expire_at\]
+		DepositRequested(RequestId, T::AccountId, Balance, T::BlockNumber),
+		/// The reserve transfer arrived and the collateral is deposited to the
+		/// CDP. \[request_id, who, amount\]
+		DepositConfirmed(RequestId, T::AccountId, Balance),
+		/// The reserve transfer arrived but failed to deposit as collateral,
+		/// the relay chain currency is kept in the account. \[request_id, who,
+		/// amount, error\]
+		DepositFailed(RequestId, T::AccountId, Balance, DispatchError),
+		/// Deposit request cancelled. \[request_id, who\]
+		DepositCancelled(RequestId, T::AccountId),
+		/// Deposit request expired. \[request_id, who\]
+		DepositExpired(RequestId, T::AccountId),
+	}
+
+	/// The id of the next deposit request.
+	///
+	/// NextRequestId: RequestId
+	#[pallet::storage]
+	#[pallet::getter(fn next_request_id)]
+	pub type NextRequestId<T: Config> = StorageValue<_, RequestId, ValueQuery>;
+
+	/// The deposit requests waiting for the reserve transfer.
+	///
+	/// DepositRequests: map RequestId => Option<DepositRequest>
+	#[pallet::storage]
+	#[pallet::getter(fn deposit_requests)]
+	pub type DepositRequests<T: Config> =
+		StorageMap<_, Twox64Concat, RequestId, DepositRequest<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// The deposit requests expire at the block.
+	///
+	/// RequestExpiries: double_map BlockNumber, RequestId => Option<()>
+	#[pallet::storage]
+	pub type RequestExpiries<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, RequestId, (), OptionQuery>;
+
+	/// The total amount of relay chain currency arrived by reserve transfers
+	/// and deposited as collateral.
+	///
+	/// TotalConfirmed: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_confirmed)]
+	pub type TotalConfirmed<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Remove the deposit requests expired at this block
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut count: u32 = 0;
+			for (request_id, _) in RequestExpiries::<T>::drain_prefix(now) {
+				if let Some(request) = DepositRequests::<T>::take(request_id) {
+					count = count.saturating_add(1);
+					Self::deposit_event(Event::DepositExpired(request_id, request.who));
+				}
+			}
+			<T as Config>::WeightInfo::on_initialize(count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Request to deposit relay chain currency which will be reserve
+		/// transferred from the relay chain as CDP collateral.
+		///
+		/// - `amount`: the maximum amount of relay chain currency to deposit.
+		#[pallet::weight(<T as Config>::WeightInfo::request_deposit())]
+		#[transactional]
+		pub fn request_deposit(origin: OriginFor<T>, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+			let request_id = NextRequestId::<T>::try_mutate(|id| -> Result<RequestId, DispatchError> {
+				let current_id = *id;
+				*id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+				Ok(current_id)
+			})?;
+			let expire_at = frame_system::Pallet::<T>::block_number().saturating_add(T::ConfirmationTimeout::get());
+			DepositRequests::<T>::insert(
+				request_id,
+				DepositRequest {
+					who: who.clone(),
+					amount,
+					expire_at,
+				},
+			);
+			RequestExpiries::<T>::insert(expire_at, request_id, ());
+
+			Self::deposit_event(Event::DepositRequested(request_id, who, amount, expire_at));
+			Ok(())
+		}
+
+		/// Cancel the deposit request of the caller waiting for the reserve
+		/// transfer.
+		///
+		/// - `request_id`: the deposit request to cancel.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_deposit())]
+		#[transactional]
+		pub fn cancel_deposit(origin: OriginFor<T>, #[pallet::compact] request_id: RequestId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let request = Self::deposit_requests(request_id).ok_or(Error::<T>::RequestNotFound)?;
+			ensure!(request.who == who, Error::<T>::NoPermission);
+			DepositRequests::<T>::remove(request_id);
+			RequestExpiries::<T>::remove(request.expire_at, request_id);
+
+			Self::deposit_event(Event::DepositCancelled(request_id, who));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Deposit at most the requested amount of the `amount` relay chain
+	/// currency arrived in the account of `who` to the CDP as collateral, the
+	/// relay chain currency is kept in the account if failed.
+	pub fn deposit_collateral(request_id: RequestId, who: &T::AccountId, amount: Balance) {
+		match Self::do_deposit_collateral(request_id, who, amount) {
+			Ok(deposited) => Self::deposit_event(Event::DepositConfirmed(request_id, who.clone(), deposited)),
+			Err(e) => Self::deposit_event(Event::DepositFailed(request_id, who.clone(), amount, e)),
+		}
+	}
+
+	#[transactional]
+	fn do_deposit_collateral(
+		request_id: RequestId,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> Result<Balance, DispatchError> {
+		ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+		let DepositRequest {
+			who: requester,
+			amount: requested,
+			expire_at,
+		} = DepositRequests::<T>::take(request_id).ok_or(Error::<T>::RequestNotFound)?;
+		ensure!(requester == *who, Error::<T>::NoPermission);
+		RequestExpiries::<T>::remove(expire_at, request_id);
+
+		let deposited = amount.min(requested);
+		ensure!(!deposited.is_zero(), Error::<T>::InvalidAmount);
+		let collateral_adjustment: Amount = deposited.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		<loans::Pallet<T>>::adjust_position(who, T::RelayChainCurrencyId::get(), collateral_adjustment, Zero::zero())?;
+		TotalConfirmed::<T>::mutate(|total| *total = total.saturating_add(deposited));
+		Ok(deposited)
+	}
+}
+
+/// Split the beneficiary with the deposit request id attached into the
+/// account location and the request id, `None` if no request id is attached.
+pub fn split_deposit_request(who: &MultiLocation) -> Option<(MultiLocation, RequestId)> {
+	match who {
+		MultiLocation {
+			parents: 0,
+			interior: X2(account @ AccountId32 { .. }, GeneralIndex(index)),
+		} => (*index)
+			.try_into()
+			.ok()
+			.map(|request_id| (MultiLocation::new(0, X1(account.clone())), request_id)),
+		_ => None,
+	}
+}
+
+/// The asset transactor depositing the relay chain currency reserve
+/// transferred to the beneficiary with the deposit request id attached as CDP
+/// collateral, other assets are handled by `Inner`.
+pub struct CollateralDepositTransactor<T, Inner, AccountIdConvert>(PhantomData<(T, Inner, AccountIdConvert)>);
+impl<T, Inner, AccountIdConvert> TransactAsset for CollateralDepositTransactor<T, Inner, AccountIdConvert>
+where
+	T: Config,
+	Inner: TransactAsset,
+	AccountIdConvert: Convert<MultiLocation, T::AccountId>,
+{
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset) -> XcmResult {
+		Inner::can_check_in(origin, what)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset) {
+		Inner::check_in(origin, what)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset) {
+		Inner::check_out(dest, what)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		let (account, request_id) = match split_deposit_request(who) {
+			Some(split) => split,
+			None => return Inner::deposit_asset(what, who),
+		};
+
+		// the reserve backed asset is issued to the account first, it's kept there
+		// if failed to deposit as collateral
+		Inner::deposit_asset(what, &account)?;
+
+		if let MultiAsset {
+			id: Concrete(location),
+			fun: Fungible(amount),
+		} = what
+		{
+			if *location == MultiLocation::parent() {
+				if let Ok(who) = AccountIdConvert::convert_ref(&account) {
+					Pallet::<T>::deposit_collateral(request_id, &who, *amount);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn withdraw_asset(what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		Inner::withdraw_asset(what, who)
+	}
+
+	fn transfer_asset(asset: &MultiAsset, from: &MultiLocation, to: &MultiLocation) -> Result<Assets, XcmError> {
+		Inner::transfer_asset(asset, from, to)
+	}
+}
@@ -0,0 +1,201 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the xcm collateral module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiCurrency;
+
+fn dot(amount: Balance) -> MultiAsset {
+	(MultiLocation::parent(), amount).into()
+}
+
+fn beneficiary_with_request(who: AccountId, request_id: RequestId) -> MultiLocation {
+	let mut location = account_location(who);
+	location.push_interior(GeneralIndex(request_id.into())).unwrap();
+	location
+}
+
+#[test]
+fn request_deposit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			XcmCollateralModule::request_deposit(Origin::signed(BOB), 0),
+			Error::<Runtime>::InvalidAmount
+		);
+
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(BOB), 500));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositRequested(
+			0, BOB, 500, 11,
+		)));
+		assert_eq!(
+			XcmCollateralModule::deposit_requests(0),
+			Some(DepositRequest {
+				who: BOB,
+				amount: 500,
+				expire_at: 11
+			})
+		);
+		assert_eq!(RequestExpiries::<Runtime>::get(11, 0), Some(()));
+		assert_eq!(XcmCollateralModule::next_request_id(), 1);
+
+		mock_shutdown();
+		assert_noop!(
+			XcmCollateralModule::request_deposit(Origin::signed(BOB), 500),
+			Error::<Runtime>::AlreadyShutdown
+		);
+	});
+}
+
+#[test]
+fn deposit_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(BOB), 500));
+
+		// the reserve transfer without request id is deposited to the account only
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(100),
+			&account_location(BOB)
+		));
+		assert_eq!(Currencies::free_balance(DOT, &BOB), 1100);
+		assert_eq!(LoansModule::positions(DOT, BOB).collateral, 0);
+
+		// the relay chain currency is kept in the account if the request does not
+		// exist or belongs to another account
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_request(BOB, 1)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositFailed(
+			1,
+			BOB,
+			100,
+			Error::<Runtime>::RequestNotFound.into(),
+		)));
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_request(ALICE, 0)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositFailed(
+			0,
+			ALICE,
+			100,
+			Error::<Runtime>::NoPermission.into(),
+		)));
+		assert_eq!(Currencies::free_balance(DOT, &BOB), 1200);
+		assert_eq!(Currencies::free_balance(DOT, &ALICE), 1100);
+		assert!(XcmCollateralModule::deposit_requests(0).is_some());
+
+		// at most the requested amount is deposited to the CDP
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(600),
+			&beneficiary_with_request(BOB, 0)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositConfirmed(0, BOB, 500)));
+		assert_eq!(XcmCollateralModule::deposit_requests(0), None);
+		assert_eq!(RequestExpiries::<Runtime>::get(11, 0), None);
+		assert_eq!(XcmCollateralModule::total_confirmed(), 500);
+		assert_eq!(Currencies::free_balance(DOT, &BOB), 1300);
+		assert_eq!(Currencies::free_balance(DOT, &LoansModule::account_id()), 500);
+		assert_eq!(LoansModule::positions(DOT, BOB).collateral, 500);
+		assert_eq!(LoansModule::positions(DOT, BOB).debit, 0);
+
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_request(BOB, 0)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositFailed(
+			0,
+			BOB,
+			100,
+			Error::<Runtime>::RequestNotFound.into(),
+		)));
+
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(BOB), 500));
+		mock_shutdown();
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_request(BOB, 1)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositFailed(
+			1,
+			BOB,
+			100,
+			Error::<Runtime>::AlreadyShutdown.into(),
+		)));
+		assert_eq!(LoansModule::positions(DOT, BOB).collateral, 500);
+	});
+}
+
+#[test]
+fn cancel_deposit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(BOB), 500));
+
+		assert_noop!(
+			XcmCollateralModule::cancel_deposit(Origin::signed(ALICE), 0),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(XcmCollateralModule::cancel_deposit(Origin::signed(BOB), 0));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositCancelled(0, BOB)));
+		assert_eq!(XcmCollateralModule::deposit_requests(0), None);
+		assert_eq!(RequestExpiries::<Runtime>::get(11, 0), None);
+		assert_noop!(
+			XcmCollateralModule::cancel_deposit(Origin::signed(BOB), 0),
+			Error::<Runtime>::RequestNotFound
+		);
+	});
+}
+
+#[test]
+fn deposit_request_expires_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(BOB), 500));
+		System::set_block_number(2);
+		assert_ok!(XcmCollateralModule::request_deposit(Origin::signed(ALICE), 300));
+
+		XcmCollateralModule::on_initialize(11);
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositExpired(0, BOB)));
+		assert_eq!(XcmCollateralModule::deposit_requests(0), None);
+		assert!(XcmCollateralModule::deposit_requests(1).is_some());
+
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(500),
+			&beneficiary_with_request(BOB, 0)
+		));
+		System::assert_last_event(Event::XcmCollateralModule(crate::Event::DepositFailed(
+			0,
+			BOB,
+			500,
+			Error::<Runtime>::RequestNotFound.into(),
+		)));
+		assert_ok!(MockCollateralDepositTransactor::deposit_asset(
+			&dot(300),
+			&beneficiary_with_request(ALICE, 1)
+		));
+		assert_eq!(LoansModule::positions(DOT, ALICE).collateral, 300);
+	});
+}
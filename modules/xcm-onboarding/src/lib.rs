@@ -0,0 +1,326 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # XCM Onboarding Module
+//!
+//! ## Overview
+//!
+//! XCM onboarding module allows users to open a CDP from the relay chain in
+//! one click. The relay chain currency is transferred by XCM to a beneficiary
+//! location with the encoded `OnboardingIntent` attached:
+//! `X2(AccountId32 { .. }, GeneralKey(intent))`. When the asset is
+//! deposited, `OnboardingTransactor` deposits it to the account, then
+//! deposits it as collateral to the CDP of the account and mints the
+//! requested value of stable currency in the same message processing.
+//!
+//! The account must authorize the onboarding in advance with the maximum
+//! value of stable currency to mint, the authorization is consumed by the
+//! onboarding. The minting is rejected unless the collateral ratio of the CDP
+//! stays above the required collateral ratio plus `SafetyMargin`. If the
+//! onboarding fails, the relay chain currency is kept in the account as a
+//! refund.
+//!
+//! The onboarding runs while the XCM executor deposits the asset, its weight
+//! is charged by the executor with `OnboardingWeigher`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use loans::Position;
+use primitives::{Amount, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, marker::PhantomData};
+use support::{EmergencyShutdown, PriceProvider, Ratio};
+use xcm::latest::prelude::*;
+use xcm_executor::{
+	traits::{Convert, TransactAsset, WeightBounds},
+	Assets,
+};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The onboarding intent attached to the beneficiary of the relay chain
+/// currency
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct OnboardingIntent {
+	/// The value of stable currency to mint against the deposited collateral
+	pub mint_value: Balance,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + cdp_engine::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Relay chain currency id
+		#[pallet::constant]
+		type RelayChainCurrencyId: Get<CurrencyId>;
+
+		/// The extra collateral ratio required above the required collateral
+		/// ratio of the collateral type when onboarding
+		#[pallet::constant]
+		type SafetyMargin: Get<Ratio>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The system has been shutdown
+		AlreadyShutdown,
+		/// The feed price is invalid
+		InvalidFeedPrice,
+		/// The collateral ratio is below the required collateral ratio plus
+		/// the safety margin
+		BelowSafetyMargin,
+		/// The account has not authorized the onboarding
+		NotAuthorized,
+		/// The mint value exceeds the authorized maximum mint value
+		ExceedAuthorizedMintValue,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The relay chain currency is deposited as collateral and the stable
+		/// currency is minted. \[who, collateral_amount, mint_value\]
+		Onboarded(T::AccountId, Balance, Balance),
+		/// The onboarding failed and the relay chain currency is kept in the
+		/// account. \[who, amount, error\]
+		OnboardingFailed(T::AccountId, Balance, DispatchError),
+		/// The onboarding is authorized. \[who, max_mint_value\]
+		OnboardingAuthorized(T::AccountId, Balance),
+		/// The onboarding authorization is revoked. \[who\]
+		OnboardingRevoked(T::AccountId),
+	}
+
+	/// The maximum value of stable currency the account authorized to mint by
+	/// the next onboarding.
+	///
+	/// OnboardingAuthorizations: map AccountId => Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn onboarding_authorizations)]
+	pub type OnboardingAuthorizations<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Balance, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Authorize the next onboarding to the caller, replacing the
+		/// existing authorization.
+		///
+		/// - `max_mint_value`: the maximum value of stable currency to mint.
+		#[pallet::weight(<T as Config>::WeightInfo::authorize_onboarding())]
+		#[transactional]
+		pub fn authorize_onboarding(
+			origin: OriginFor<T>,
+			#[pallet::compact] max_mint_value: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			OnboardingAuthorizations::<T>::insert(&who, max_mint_value);
+			Self::deposit_event(Event::OnboardingAuthorized(who, max_mint_value));
+			Ok(())
+		}
+
+		/// Revoke the onboarding authorization of the caller.
+		#[pallet::weight(<T as Config>::WeightInfo::revoke_onboarding())]
+		#[transactional]
+		pub fn revoke_onboarding(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			OnboardingAuthorizations::<T>::remove(&who);
+			Self::deposit_event(Event::OnboardingRevoked(who));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Deposit `amount` relay chain currency of `who` as collateral and mint
+	/// the stable currency of the intent, the relay chain currency is kept in
+	/// the account if failed.
+	pub fn onboard(who: &T::AccountId, amount: Balance, intent: OnboardingIntent) {
+		match Self::deposit_and_mint(who, amount, intent) {
+			Ok(_) => Self::deposit_event(Event::Onboarded(who.clone(), amount, intent.mint_value)),
+			Err(e) => Self::deposit_event(Event::OnboardingFailed(who.clone(), amount, e)),
+		}
+	}
+
+	#[transactional]
+	fn deposit_and_mint(who: &T::AccountId, amount: Balance, intent: OnboardingIntent) -> DispatchResult {
+		ensure!(
+			!<T as cdp_engine::Config>::EmergencyShutdown::is_shutdown(),
+			Error::<T>::AlreadyShutdown
+		);
+		let max_mint_value = OnboardingAuthorizations::<T>::take(who).ok_or(Error::<T>::NotAuthorized)?;
+		ensure!(
+			intent.mint_value <= max_mint_value,
+			Error::<T>::ExceedAuthorizedMintValue
+		);
+
+		let currency_id = T::RelayChainCurrencyId::get();
+		let debit = <cdp_engine::Pallet<T>>::get_debit_exchange_rate(currency_id)
+			.reciprocal()
+			.map(|rate| rate.saturating_mul_int(intent.mint_value))
+			.unwrap_or_default();
+		let collateral_adjustment: Amount = amount.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		let debit_adjustment: Amount = debit.try_into().map_err(|_| ArithmeticError::Overflow)?;
+		<cdp_engine::Pallet<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)?;
+
+		let Position { collateral, debit } = <loans::Pallet<T>>::positions(currency_id, who);
+		if !debit.is_zero() {
+			let price = <T as cdp_engine::Config>::PriceSource::get_relative_price(
+				currency_id,
+				<T as cdp_engine::Config>::GetStableCurrencyId::get(),
+			)
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let collateral_ratio =
+				<cdp_engine::Pallet<T>>::calculate_collateral_ratio(currency_id, collateral, debit, price);
+			let liquidation_ratio = <cdp_engine::Pallet<T>>::get_liquidation_ratio(currency_id);
+			let safe_ratio = <cdp_engine::Pallet<T>>::required_collateral_ratio(currency_id)
+				.map_or(liquidation_ratio, |ratio| ratio.max(liquidation_ratio))
+				.saturating_add(T::SafetyMargin::get());
+			ensure!(collateral_ratio >= safe_ratio, Error::<T>::BelowSafetyMargin);
+		}
+
+		Ok(())
+	}
+}
+
+/// Split the beneficiary with the onboarding intent attached into the
+/// account location and the intent, `None` if no intent is attached.
+pub fn split_onboarding_intent(who: &MultiLocation) -> Option<(MultiLocation, Option<OnboardingIntent>)> {
+	match who {
+		MultiLocation {
+			parents: 0,
+			interior: X2(account @ AccountId32 { .. }, GeneralKey(key)),
+		} => Some((
+			MultiLocation::new(0, X1(account.clone())),
+			OnboardingIntent::decode(&mut &key[..]).ok(),
+		)),
+		_ => None,
+	}
+}
+
+/// The asset transactor onboarding the relay chain currency deposited to the
+/// beneficiary with the onboarding intent attached, other assets are handled
+/// by `Inner`.
+pub struct OnboardingTransactor<T, Inner, AccountIdConvert>(PhantomData<(T, Inner, AccountIdConvert)>);
+impl<T, Inner, AccountIdConvert> TransactAsset for OnboardingTransactor<T, Inner, AccountIdConvert>
+where
+	T: Config,
+	Inner: TransactAsset,
+	AccountIdConvert: Convert<MultiLocation, T::AccountId>,
+{
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset) -> XcmResult {
+		Inner::can_check_in(origin, what)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset) {
+		Inner::check_in(origin, what)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset) {
+		Inner::check_out(dest, what)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		let (account, maybe_intent) = match split_onboarding_intent(who) {
+			Some(split) => split,
+			None => return Inner::deposit_asset(what, who),
+		};
+
+		// deposit to the account first, it's kept there if the onboarding fails
+		Inner::deposit_asset(what, &account)?;
+
+		if let (
+			Some(intent),
+			MultiAsset {
+				id: Concrete(location),
+				fun: Fungible(amount),
+			},
+		) = (maybe_intent, what)
+		{
+			if *location == MultiLocation::parent() {
+				if let Ok(who) = AccountIdConvert::convert_ref(&account) {
+					Pallet::<T>::onboard(&who, *amount, intent);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn withdraw_asset(what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		Inner::withdraw_asset(what, who)
+	}
+
+	fn transfer_asset(asset: &MultiAsset, from: &MultiLocation, to: &MultiLocation) -> Result<Assets, XcmError> {
+		Inner::transfer_asset(asset, from, to)
+	}
+}
+
+/// The weigher charging the onboarding of the relay chain currency deposited
+/// to the beneficiary with the onboarding intent attached, other weights are
+/// given by `Inner`.
+pub struct OnboardingWeigher<T, Inner>(PhantomData<(T, Inner)>);
+impl<T: Config, Inner: WeightBounds<C>, C> WeightBounds<C> for OnboardingWeigher<T, Inner> {
+	fn weight(message: &mut Xcm<C>) -> Result<Weight, ()> {
+		let onboarding_weight = onboarding_weight_of::<T, C>(message);
+		Inner::weight(message).map(|weight| weight.saturating_add(onboarding_weight))
+	}
+
+	fn instr_weight(instruction: &Instruction<C>) -> Result<Weight, ()> {
+		Inner::instr_weight(instruction)
+			.map(|weight| weight.saturating_add(onboarding_instr_weight_of::<T, C>(instruction)))
+	}
+}
+
+fn onboarding_weight_of<T: Config, C>(message: &Xcm<C>) -> Weight {
+	message.0.iter().fold(0, |weight, instruction| {
+		weight.saturating_add(onboarding_instr_weight_of::<T, C>(instruction))
+	})
+}
+
+fn onboarding_instr_weight_of<T: Config, C>(instruction: &Instruction<C>) -> Weight {
+	match instruction {
+		DepositAsset { beneficiary, .. } => match split_onboarding_intent(beneficiary) {
+			Some((_, Some(_))) => <T as Config>::WeightInfo::onboard(),
+			_ => 0,
+		},
+		SetErrorHandler(xcm) | SetAppendix(xcm) => onboarding_weight_of::<T, C>(xcm),
+		_ => 0,
+	}
+}
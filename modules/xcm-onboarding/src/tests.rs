@@ -0,0 +1,193 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the xcm onboarding module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::{Change, MultiCurrency};
+use sp_runtime::traits::BadOrigin;
+use support::Rate;
+
+fn setup_collateral_params() {
+	assert_ok!(CDPEngineModule::set_collateral_params(
+		Origin::signed(1),
+		DOT,
+		Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+		Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+		Change::NewValue(10000),
+	));
+}
+
+fn dot(amount: Balance) -> MultiAsset {
+	(MultiLocation::parent(), amount).into()
+}
+
+fn beneficiary_with_key(who: AccountId, key: Vec<u8>) -> MultiLocation {
+	let mut location = account_location(who);
+	location.push_interior(GeneralKey(key)).unwrap();
+	location
+}
+
+fn beneficiary_with_intent(who: AccountId, mint_value: Balance) -> MultiLocation {
+	beneficiary_with_key(who, OnboardingIntent { mint_value }.encode())
+}
+
+#[test]
+fn deposit_without_intent_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&account_location(BOB)
+		));
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 100);
+		assert_eq!(LoansModule::positions(DOT, BOB), loans::Position::default());
+
+		// the undecodable intent is ignored
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_key(BOB, vec![1])
+		));
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 200);
+		assert_eq!(LoansModule::positions(DOT, BOB), loans::Position::default());
+	});
+}
+
+#[test]
+fn authorize_and_revoke_onboarding_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(XcmOnboardingModule::authorize_onboarding(Origin::none(), 50), BadOrigin);
+		assert_ok!(XcmOnboardingModule::authorize_onboarding(Origin::signed(BOB), 50));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::OnboardingAuthorized(BOB, 50)));
+		assert_eq!(XcmOnboardingModule::onboarding_authorizations(BOB), Some(50));
+
+		assert_ok!(XcmOnboardingModule::revoke_onboarding(Origin::signed(BOB)));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::OnboardingRevoked(BOB)));
+		assert_eq!(XcmOnboardingModule::onboarding_authorizations(BOB), None);
+	});
+}
+
+#[test]
+fn deposit_and_mint_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(XcmOnboardingModule::authorize_onboarding(Origin::signed(BOB), 50));
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_intent(BOB, 50)
+		));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::Onboarded(BOB, 100, 50)));
+		assert_eq!(LoansModule::positions(DOT, BOB).collateral, 100);
+		assert_eq!(LoansModule::positions(DOT, BOB).debit, 50);
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 0);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 50);
+
+		// the authorization is consumed
+		assert_eq!(XcmOnboardingModule::onboarding_authorizations(BOB), None);
+	});
+}
+
+#[test]
+fn deposit_and_mint_without_authorization_refund() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_intent(BOB, 50)
+		));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::OnboardingFailed(
+			BOB,
+			100,
+			Error::<Runtime>::NotAuthorized.into(),
+		)));
+		assert_eq!(LoansModule::positions(DOT, BOB), loans::Position::default());
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 100);
+
+		assert_ok!(XcmOnboardingModule::authorize_onboarding(Origin::signed(BOB), 49));
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_intent(BOB, 50)
+		));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::OnboardingFailed(
+			BOB,
+			100,
+			Error::<Runtime>::ExceedAuthorizedMintValue.into(),
+		)));
+		assert_eq!(LoansModule::positions(DOT, BOB), loans::Position::default());
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 200);
+		assert_eq!(XcmOnboardingModule::onboarding_authorizations(BOB), Some(49));
+	});
+}
+
+#[test]
+fn deposit_and_mint_below_safety_margin_refund() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_params();
+		assert_ok!(XcmOnboardingModule::authorize_onboarding(Origin::signed(BOB), 51));
+		assert_ok!(MockOnboardingTransactor::deposit_asset(
+			&dot(100),
+			&beneficiary_with_intent(BOB, 51)
+		));
+		System::assert_last_event(Event::XcmOnboardingModule(crate::Event::OnboardingFailed(
+			BOB,
+			100,
+			Error::<Runtime>::BelowSafetyMargin.into(),
+		)));
+		assert_eq!(LoansModule::positions(DOT, BOB), loans::Position::default());
+		assert_eq!(Tokens::free_balance(DOT, &BOB), 100);
+		assert_eq!(Tokens::free_balance(AUSD, &BOB), 0);
+	});
+}
+
+#[test]
+fn onboarding_weigher_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let onboard_weight = <() as WeightInfo>::onboard();
+		let deposit = |beneficiary: MultiLocation| DepositAsset {
+			assets: All.into(),
+			max_assets: 1,
+			beneficiary,
+		};
+
+		let mut message = Xcm::<()>(vec![
+			ClearOrigin,
+			deposit(account_location(BOB)),
+			deposit(beneficiary_with_key(BOB, vec![1])),
+		]);
+		assert_eq!(OnboardingWeigher::<Runtime, MockWeigher>::weight(&mut message), Ok(3));
+
+		let mut message = Xcm::<()>(vec![
+			deposit(beneficiary_with_intent(BOB, 50)),
+			SetAppendix(Xcm(vec![deposit(beneficiary_with_intent(BOB, 50))])),
+		]);
+		assert_eq!(
+			OnboardingWeigher::<Runtime, MockWeigher>::weight(&mut message),
+			Ok(2 + 2 * onboard_weight)
+		);
+		assert_eq!(
+			OnboardingWeigher::<Runtime, MockWeigher>::instr_weight(&deposit(beneficiary_with_intent(BOB, 50))),
+			Ok(1 + onboard_weight)
+		);
+	});
+}
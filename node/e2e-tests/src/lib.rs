@@ -72,6 +72,7 @@ impl ChainInfo for NodeTemplateChainInfo {
 			frame_system::CheckWeight::<Self::Runtime>::new(),
 			module_transaction_payment::ChargeTransactionPayment::<Self::Runtime>::from(0),
 			module_evm::SetEvmOrigin::<Self::Runtime>::new(),
+			module_weight_metrics::RecordWeightMetrics::<Self::Runtime>::new(),
 		)
 	}
 }
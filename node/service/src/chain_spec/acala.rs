@@ -31,8 +31,9 @@ use acala_runtime::{
 	dollar, Balance, BalancesConfig, BlockNumber, CdpEngineConfig, CdpTreasuryConfig, CollatorSelectionConfig,
 	DexConfig, FinancialCouncilMembershipConfig, GeneralCouncilMembershipConfig, HomaCouncilMembershipConfig,
 	NativeTokenExistentialDeposit, OperatorMembershipAcalaConfig, OrmlNFTConfig, ParachainInfoConfig,
-	PolkadotXcmConfig, SS58Prefix, SessionConfig, SessionDuration, SessionKeys, SessionManagerConfig, SudoConfig,
-	SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, ACA, AUSD, DOT, LDOT,
+	ParameterCouncilMembershipConfig, PolkadotXcmConfig, SS58Prefix, SessionConfig, SessionDuration, SessionKeys,
+	SessionManagerConfig, SudoConfig, SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig,
+	ACA, AUSD, DOT, LDOT,
 };
 use runtime_common::TokenInfo;
 
@@ -249,6 +250,11 @@ fn acala_genesis(
 			members: vec![],
 			phantom: Default::default(),
 		},
+		parameter_council: Default::default(),
+		parameter_council_membership: ParameterCouncilMembershipConfig {
+			members: vec![],
+			phantom: Default::default(),
+		},
 		operator_membership_acala: OperatorMembershipAcalaConfig {
 			members: vec![],
 			phantom: Default::default(),
@@ -28,9 +28,9 @@ use crate::chain_spec::{get_account_id_from_seed, get_parachain_authority_keys_f
 use karura_runtime::{
 	dollar, Balance, BalancesConfig, BlockNumber, CdpEngineConfig, CdpTreasuryConfig, CollatorSelectionConfig,
 	DexConfig, FinancialCouncilMembershipConfig, GeneralCouncilMembershipConfig, HomaCouncilMembershipConfig,
-	OperatorMembershipAcalaConfig, OrmlNFTConfig, ParachainInfoConfig, PolkadotXcmConfig, SS58Prefix, SessionConfig,
-	SessionDuration, SessionKeys, SessionManagerConfig, SudoConfig, SystemConfig, TechnicalCommitteeMembershipConfig,
-	TokensConfig, VestingConfig, BNC, KAR, KSM, KUSD, LKSM, VSKSM,
+	OperatorMembershipAcalaConfig, OrmlNFTConfig, ParachainInfoConfig, ParameterCouncilMembershipConfig,
+	PolkadotXcmConfig, SS58Prefix, SessionConfig, SessionDuration, SessionKeys, SessionManagerConfig, SudoConfig,
+	SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, BNC, KAR, KSM, KUSD, LKSM, VSKSM,
 };
 use runtime_common::TokenInfo;
 
@@ -132,6 +132,11 @@ fn karura_genesis(
 			members: vec![],
 			phantom: Default::default(),
 		},
+		parameter_council: Default::default(),
+		parameter_council_membership: ParameterCouncilMembershipConfig {
+			members: vec![],
+			phantom: Default::default(),
+		},
 		operator_membership_acala: OperatorMembershipAcalaConfig {
 			members: vec![],
 			phantom: Default::default(),
@@ -239,9 +239,10 @@ fn testnet_genesis(
 		dollar, get_all_module_accounts, BalancesConfig, CdpEngineConfig, CdpTreasuryConfig, CollatorSelectionConfig,
 		DexConfig, EVMConfig, EnabledTradingPairs, FinancialCouncilMembershipConfig, GeneralCouncilMembershipConfig,
 		HomaCouncilMembershipConfig, IndicesConfig, NativeTokenExistentialDeposit, OperatorMembershipAcalaConfig,
-		OrmlNFTConfig, ParachainInfoConfig, PolkadotXcmConfig, RenVmBridgeConfig, SessionConfig, SessionDuration,
-		SessionKeys, SessionManagerConfig, StakingPoolConfig, StarportConfig, SudoConfig, SystemConfig,
-		TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, ACA, AUSD, DOT, LDOT, RENBTC,
+		OrmlNFTConfig, ParachainInfoConfig, ParameterCouncilMembershipConfig, PolkadotXcmConfig, RenVmBridgeConfig,
+		SessionConfig, SessionDuration, SessionKeys, SessionManagerConfig, StakingPoolConfig, StarportConfig,
+		SudoConfig, SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, ACA, AUSD, DOT,
+		LDOT, RENBTC,
 	};
 
 	let existential_deposit = NativeTokenExistentialDeposit::get();
@@ -307,6 +308,11 @@ fn testnet_genesis(
 			members: vec![root_key.clone()],
 			phantom: Default::default(),
 		},
+		parameter_council: Default::default(),
+		parameter_council_membership: ParameterCouncilMembershipConfig {
+			members: vec![root_key.clone()],
+			phantom: Default::default(),
+		},
 		operator_membership_acala: OperatorMembershipAcalaConfig {
 			members: vec![root_key.clone()],
 			phantom: Default::default(),
@@ -440,10 +446,10 @@ fn mandala_genesis(
 		cent, dollar, get_all_module_accounts, BalancesConfig, CdpEngineConfig, CdpTreasuryConfig,
 		CollatorSelectionConfig, DexConfig, EVMConfig, EnabledTradingPairs, FinancialCouncilMembershipConfig,
 		GeneralCouncilMembershipConfig, HomaCouncilMembershipConfig, IndicesConfig, NativeTokenExistentialDeposit,
-		OperatorMembershipAcalaConfig, OrmlNFTConfig, ParachainInfoConfig, PolkadotXcmConfig, RenVmBridgeConfig,
-		SessionConfig, SessionDuration, SessionKeys, SessionManagerConfig, StakingPoolConfig, StarportConfig,
-		SudoConfig, SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, ACA, AUSD, DOT,
-		LDOT, RENBTC,
+		OperatorMembershipAcalaConfig, OrmlNFTConfig, ParachainInfoConfig, ParameterCouncilMembershipConfig,
+		PolkadotXcmConfig, RenVmBridgeConfig, SessionConfig, SessionDuration, SessionKeys, SessionManagerConfig,
+		StakingPoolConfig, StarportConfig, SudoConfig, SystemConfig, TechnicalCommitteeMembershipConfig, TokensConfig,
+		VestingConfig, ACA, AUSD, DOT, LDOT, RENBTC,
 	};
 
 	let existential_deposit = NativeTokenExistentialDeposit::get();
@@ -509,6 +515,11 @@ fn mandala_genesis(
 			members: vec![root_key.clone()],
 			phantom: Default::default(),
 		},
+		parameter_council: Default::default(),
+		parameter_council_membership: ParameterCouncilMembershipConfig {
+			members: vec![root_key.clone()],
+			phantom: Default::default(),
+		},
 		operator_membership_acala: OperatorMembershipAcalaConfig {
 			members: endowed_accounts,
 			phantom: Default::default(),
@@ -100,6 +100,45 @@ pub struct EthereumTransactionMessage {
 	pub valid_until: BlockNumber,
 }
 
+/// Filter of the EVM logs. An empty `addresses` matches logs of any address,
+/// and a `None` or empty topic matches any topic at its position.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct LogFilter {
+	/// Addresses of the contracts which emitted the logs
+	pub addresses: Vec<EvmAddress>,
+	/// Accepted topics at each position
+	pub topics: Vec<Option<Vec<H256>>>,
+}
+
+impl LogFilter {
+	/// Check if the `log` matches the filter.
+	pub fn matches(&self, log: &Log) -> bool {
+		if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+			return false;
+		}
+
+		self.topics.iter().enumerate().all(|(position, topics)| match topics {
+			Some(topics) if !topics.is_empty() => {
+				log.topics.get(position).map_or(false, |topic| topics.contains(topic))
+			}
+			_ => true,
+		})
+	}
+}
+
+/// An EVM log emitted in a block.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BlockLog {
+	/// Index of the extrinsic which emitted the log, `None` if it's not
+	/// emitted by an extrinsic.
+	pub extrinsic_index: Option<u32>,
+	/// Index of the log among all EVM logs of the block
+	pub log_index: u32,
+	pub log: Log,
+}
+
 /// Ethereum precompiles
 /// 0 - 0x0000000000000000000000000000000000000400
 /// Acala precompiles
@@ -36,6 +36,7 @@ use sp_runtime::{
 use sp_std::prelude::*;
 
 pub use currency::{CurrencyId, DexShare, TokenSymbol};
+pub use nutsfinance_stable_asset::StableAssetPoolId;
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -169,6 +170,7 @@ pub enum ReserveIdentifier {
 	Honzon,
 	Nft,
 	TransactionPayment,
+	EvmStorageSponsor,
 
 	// always the last, indicate number of variants
 	Count,
@@ -177,3 +179,56 @@ pub enum ReserveIdentifier {
 pub type NFTBalance = u128;
 
 pub type CashYieldIndex = u128;
+
+/// The sources of protocol revenue.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord, MaxEncodedLen, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RevenueSource {
+	/// Interest accumulated on the debits of CDPs
+	StabilityFee,
+	/// Penalty charged on the liquidation of unsafe CDPs
+	LiquidationPenalty,
+	/// Fees of DEX swaps taken by the protocol
+	DexProtocolFee,
+	/// Fees of swaps in peg stability module
+	PSMFee,
+	/// Fees of flash loans lent by CDP treasury
+	FlashLoanFee,
+	/// Yield of the idle collateral of CDP treasury in yield strategies
+	YieldStrategy,
+}
+
+/// The circuit breakers pausing the functions of the protocol.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, PartialOrd, Ord, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CircuitBreakerId {
+	/// The transaction paused by transaction pause, identified by the pallet
+	/// name bytes and the function name bytes
+	Transaction(Vec<u8>, Vec<u8>),
+	/// The issuance of new debit of CDPs
+	DebitIssuance,
+	/// All the transactions of a pallet paused in emergency, identified by
+	/// the pallet name bytes
+	Pallet(Vec<u8>),
+	/// The EVM call paused by transaction pause, identified by the contract
+	/// address and the function selector, all the functions of the contract
+	/// if the selector is `None`
+	EvmCall(evm::EvmAddress, Option<[u8; 4]>),
+	/// The issuance of new debit of CDPs of the collateral type
+	CollateralMint(CurrencyId),
+	/// The collateral auctions of the collateral type, whose collateral is
+	/// swapped by DEX instead
+	CollateralAuction(CurrencyId),
+}
+
+/// The reasons of triggering the circuit breakers.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord, MaxEncodedLen, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CircuitBreakerReason {
+	/// Triggered by governance
+	Governance,
+	/// Triggered by the bad debt of CDP treasury exceeding the cap
+	BadDebtOverflow,
+	/// Triggered by the emergency origin, expires automatically
+	Emergency,
+}
@@ -18,11 +18,12 @@
 
 use super::*;
 use crate::evm::{
-	is_acala_precompile, is_system_contract, EvmAddress, PRECOMPILE_ADDRESS_START, PREDEPLOY_ADDRESS_START,
+	is_acala_precompile, is_system_contract, EvmAddress, LogFilter, PRECOMPILE_ADDRESS_START, PREDEPLOY_ADDRESS_START,
 	SYSTEM_CONTRACT_ADDRESS_PREFIX,
 };
 use frame_support::assert_ok;
-use sp_core::H160;
+use module_evm_utiltity::ethereum::Log;
+use sp_core::{H160, H256};
 use std::{
 	convert::{TryFrom, TryInto},
 	str::FromStr,
@@ -175,3 +176,45 @@ fn is_acala_precompile_works() {
 	assert!(!is_acala_precompile(PREDEPLOY_ADDRESS_START));
 	assert!(!is_acala_precompile([1u8; 20].into()));
 }
+
+#[test]
+fn log_filter_matches_works() {
+	let log = Log {
+		address: H160::from_low_u64_be(1),
+		topics: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+		data: vec![],
+	};
+
+	assert!(LogFilter::default().matches(&log));
+	assert!(LogFilter {
+		addresses: vec![H160::from_low_u64_be(2), H160::from_low_u64_be(1)],
+		topics: vec![],
+	}
+	.matches(&log));
+	assert!(!LogFilter {
+		addresses: vec![H160::from_low_u64_be(2)],
+		topics: vec![],
+	}
+	.matches(&log));
+
+	assert!(LogFilter {
+		addresses: vec![],
+		topics: vec![None, Some(vec![H256::from_low_u64_be(3), H256::from_low_u64_be(2)])],
+	}
+	.matches(&log));
+	assert!(LogFilter {
+		addresses: vec![],
+		topics: vec![Some(vec![]), Some(vec![H256::from_low_u64_be(2)])],
+	}
+	.matches(&log));
+	assert!(!LogFilter {
+		addresses: vec![],
+		topics: vec![Some(vec![H256::from_low_u64_be(2)])],
+	}
+	.matches(&log));
+	assert!(!LogFilter {
+		addresses: vec![],
+		topics: vec![None, None, Some(vec![H256::from_low_u64_be(3)])],
+	}
+	.matches(&log));
+}
@@ -60,6 +60,9 @@ pub mod incentives {
 pub mod prices {
 	include!("../../../mandala/src/benchmarking/prices.rs");
 }
+pub mod savings {
+	include!("../../../mandala/src/benchmarking/savings.rs");
+}
 pub mod transaction_pause {
 	include!("../../../mandala/src/benchmarking/transaction_pause.rs");
 }
@@ -17,16 +17,18 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	AcalaOracle, AccountId, Balance, Currencies, CurrencyId, MinimumCount, OperatorMembershipAcala, Price, Runtime,
+	AcalaOracle, AccountId, Balance, Currencies, CurrencyId, MinimumCount, OperatorMembershipAcala, Price, Rate, Ratio,
+	Runtime,
 };
 
 use frame_benchmarking::account;
 use frame_support::{assert_ok, traits::Contains};
 use frame_system::RawOrigin;
-use orml_traits::MultiCurrencyExtended;
+use orml_traits::{Change, MultiCurrencyExtended};
+use primitives::StableAssetPoolId;
 use sp_runtime::{
 	traits::{SaturatedConversion, StaticLookup},
-	DispatchResult,
+	DispatchError, DispatchResult,
 };
 use sp_std::prelude::*;
 
@@ -55,6 +57,42 @@ pub fn feed_price(prices: Vec<(CurrencyId, Price)>) -> DispatchResult {
 	Ok(())
 }
 
+pub fn set_collateral_params(
+	currency_id: CurrencyId,
+	interest_rate_per_sec: Change<Option<Rate>>,
+	liquidation_ratio: Change<Option<Ratio>>,
+	liquidation_penalty: Change<Option<Rate>>,
+	required_collateral_ratio: Change<Option<Ratio>>,
+	maximum_total_debit_value: Change<Balance>,
+) {
+	module_cdp_engine::CollateralParams::<Runtime>::mutate(currency_id, |params| {
+		if let Change::NewValue(update) = interest_rate_per_sec {
+			params.interest_rate_per_sec = update;
+		}
+		if let Change::NewValue(update) = liquidation_ratio {
+			params.liquidation_ratio = update;
+		}
+		if let Change::NewValue(update) = liquidation_penalty {
+			params.liquidation_penalty = update;
+		}
+		if let Change::NewValue(update) = required_collateral_ratio {
+			params.required_collateral_ratio = update;
+		}
+		if let Change::NewValue(val) = maximum_total_debit_value {
+			params.maximum_total_debit_value = val;
+		}
+	});
+}
+
+/// Stable swap is not enabled on this runtime, so there is no pool to create.
+pub fn create_stable_swap_pool(
+	_currency_id_a: CurrencyId,
+	_currency_id_b: CurrencyId,
+	_amount: Balance,
+) -> Result<Option<StableAssetPoolId>, DispatchError> {
+	Ok(None)
+}
+
 #[cfg(test)]
 pub mod tests {
 	pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -54,6 +54,10 @@ impl<T: frame_system::Config> module_transaction_payment::WeightInfo for WeightI
 	fn on_finalize() -> Weight {
 		(13_644_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_fee_free_call() -> Weight {
+		(6_000_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 }
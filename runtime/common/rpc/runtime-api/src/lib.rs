@@ -0,0 +1,89 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime API definition for the runtime configuration and the governance
+//! dry run.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::{Codec, Decode, Encode};
+use primitives::CurrencyId;
+use sp_runtime::{DispatchResult, FixedU128};
+use sp_std::prelude::*;
+
+/// The typed value of a configuration constant.
+#[derive(Eq, PartialEq, Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ConfigValue<Balance, BlockNumber> {
+	U32(u32),
+	Balance(Balance),
+	BlockNumber(BlockNumber),
+	/// Rate, ratio or exchange rate
+	FixedU128(FixedU128),
+	/// Fee ratio of (numerator, denominator)
+	Fraction(u32, u32),
+	CurrencyId(CurrencyId),
+	/// Module id, the module account is derived from it
+	PalletId([u8; 8]),
+}
+
+/// The outcome of a proposal dispatched against the current state, all the
+/// changes are discarded.
+#[derive(Eq, PartialEq, Encode, Decode, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ProposalDryRun<Event, RiskParams> {
+	/// The dispatch result of the proposal
+	pub result: DispatchResult,
+	/// The events emitted by the proposal
+	pub events: Vec<Event>,
+	/// The storage root before and after the proposal is dispatched, they are
+	/// equal if the proposal changes nothing
+	pub storage_root: (Vec<u8>, Vec<u8>),
+	/// The collateral types whose risk params are changed, with the risk
+	/// params before and after the proposal is dispatched, the changes
+	/// scheduled by the timelock are applied
+	pub risk_params: Vec<(CurrencyId, RiskParams, RiskParams)>,
+	/// The collateral types with the number of the unsafe CDPs before and
+	/// after the proposal is dispatched, under the risk params above
+	pub unsafe_cdps: Vec<(CurrencyId, u32, u32)>,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait ConfigApi<Balance, BlockNumber> where
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// The DeFi relevant configuration constants, keyed by
+		/// `<pallet name>.<constant name>`.
+		fn config_constants() -> Vec<(Vec<u8>, ConfigValue<Balance, BlockNumber>)>;
+	}
+
+	pub trait GovernanceApi<Call, Event, RiskParams> where
+		Call: Codec,
+		Event: Codec,
+		RiskParams: Codec,
+	{
+		/// Dispatch the proposal by the origin of the unanimous financial
+		/// council in a transaction that is always rolled back, so that the
+		/// councils can check its effect before voting.
+		fn dry_run_proposal(proposal: Call) -> ProposalDryRun<Event, RiskParams>;
+	}
+}
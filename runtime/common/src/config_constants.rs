@@ -0,0 +1,103 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use frame_support::traits::Get;
+use primitives::Balance;
+use runtime_common_rpc_runtime_api::ConfigValue;
+use sp_std::prelude::*;
+
+/// The DeFi relevant configuration constants of the runtime, keyed by
+/// `<pallet name>.<constant name>`, shared by the `ConfigApi` of all the
+/// runtimes.
+pub fn config_constants<Runtime>() -> Vec<(Vec<u8>, ConfigValue<Balance, Runtime::BlockNumber>)>
+where
+	Runtime:
+		module_auction_manager::Config + module_cdp_engine::Config + module_cdp_treasury::Config + module_dex::Config,
+{
+	let (exchange_fee_numerator, exchange_fee_denominator) = <Runtime as module_dex::Config>::GetExchangeFee::get();
+	vec![
+		(
+			b"AuctionManager.MinimumIncrementSize".to_vec(),
+			ConfigValue::FixedU128(<Runtime as module_auction_manager::Config>::MinimumIncrementSize::get()),
+		),
+		(
+			b"AuctionManager.AuctionTimeToClose".to_vec(),
+			ConfigValue::BlockNumber(<Runtime as module_auction_manager::Config>::AuctionTimeToClose::get()),
+		),
+		(
+			b"AuctionManager.AuctionDurationSoftCap".to_vec(),
+			ConfigValue::BlockNumber(<Runtime as module_auction_manager::Config>::AuctionDurationSoftCap::get()),
+		),
+		(
+			b"Loans.PalletId".to_vec(),
+			ConfigValue::PalletId(<Runtime as module_loans::Config>::PalletId::get().0),
+		),
+		(
+			b"CdpEngine.GetStableCurrencyId".to_vec(),
+			ConfigValue::CurrencyId(<Runtime as module_cdp_engine::Config>::GetStableCurrencyId::get()),
+		),
+		(
+			b"CdpEngine.DefaultLiquidationRatio".to_vec(),
+			ConfigValue::FixedU128(<Runtime as module_cdp_engine::Config>::DefaultLiquidationRatio::get()),
+		),
+		(
+			b"CdpEngine.DefaultDebitExchangeRate".to_vec(),
+			ConfigValue::FixedU128(<Runtime as module_cdp_engine::Config>::DefaultDebitExchangeRate::get()),
+		),
+		(
+			b"CdpEngine.DefaultLiquidationPenalty".to_vec(),
+			ConfigValue::FixedU128(<Runtime as module_cdp_engine::Config>::DefaultLiquidationPenalty::get()),
+		),
+		(
+			b"CdpEngine.MinimumDebitValue".to_vec(),
+			ConfigValue::Balance(<Runtime as module_cdp_engine::Config>::MinimumDebitValue::get()),
+		),
+		(
+			b"CdpEngine.MaxSwapSlippageCompareToOracle".to_vec(),
+			ConfigValue::FixedU128(<Runtime as module_cdp_engine::Config>::MaxSwapSlippageCompareToOracle::get()),
+		),
+		(
+			b"CdpTreasury.GetNativeCurrencyId".to_vec(),
+			ConfigValue::CurrencyId(<Runtime as module_cdp_treasury::Config>::GetNativeCurrencyId::get()),
+		),
+		(
+			b"CdpTreasury.MaxAuctionsCount".to_vec(),
+			ConfigValue::U32(<Runtime as module_cdp_treasury::Config>::MaxAuctionsCount::get()),
+		),
+		(
+			b"CdpTreasury.MaxAuctionsPerBlock".to_vec(),
+			ConfigValue::U32(<Runtime as module_cdp_treasury::Config>::MaxAuctionsPerBlock::get()),
+		),
+		(
+			b"CdpTreasury.PalletId".to_vec(),
+			ConfigValue::PalletId(<Runtime as module_cdp_treasury::Config>::PalletId::get().0),
+		),
+		(
+			b"Dex.GetExchangeFee".to_vec(),
+			ConfigValue::Fraction(exchange_fee_numerator, exchange_fee_denominator),
+		),
+		(
+			b"Dex.TradingPathLimit".to_vec(),
+			ConfigValue::U32(<Runtime as module_dex::Config>::TradingPathLimit::get()),
+		),
+		(
+			b"Dex.PalletId".to_vec(),
+			ConfigValue::PalletId(<Runtime as module_dex::Config>::PalletId::get().0),
+		),
+	]
+}
@@ -0,0 +1,189 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A builder populating a realistic full-system DeFi state, for the migration
+//! tests, benchmarks and fuzzing where the whole-runtime behavior is at
+//! stake, instead of the toy genesis of each pallet's mock.
+
+use codec::{Decode, Encode};
+use module_cdp_engine::{CollateralParams, RiskManagementParams};
+use module_support::{CDPTreasury, CDPTreasuryExtended, DEXManager};
+use orml_traits::MultiCurrency;
+use primitives::{Amount, Balance, CurrencyId};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash, TrailingZeroInput},
+	DispatchResult, FixedPointNumber,
+};
+use sp_std::marker::PhantomData;
+
+use crate::{Rate, Ratio};
+
+/// Get the account of the index generated by `FullStateBuilder`.
+pub fn full_state_account<AccountId: Decode>(index: u32) -> AccountId {
+	let entropy = BlakeTwo256::hash_of(&(b"acala/full-state", index).encode());
+	AccountId::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+		.expect("infinite length input; no invalid inputs for type; qed")
+}
+
+/// Populate the current externalities with the DEX pools of the native, relay
+/// chain and liquid currencies against the stable currency, `positions` CDPs
+/// of the relay chain currency, `auctions` collateral auctions and
+/// `incentive_shares` accounts staking the LP token of the relay chain
+/// currency. The oracle prices of the currencies must be fed before.
+pub struct FullStateBuilder<T> {
+	native_currency_id: CurrencyId,
+	stable_currency_id: CurrencyId,
+	relay_chain_currency_id: CurrencyId,
+	liquid_currency_id: CurrencyId,
+	dollar: fn(CurrencyId) -> Balance,
+	positions: u32,
+	auctions: u32,
+	incentive_shares: u32,
+	_marker: PhantomData<T>,
+}
+
+impl<T> FullStateBuilder<T>
+where
+	T: module_cdp_engine::Config + module_cdp_treasury::Config + module_dex::Config,
+{
+	pub fn new(
+		native_currency_id: CurrencyId,
+		stable_currency_id: CurrencyId,
+		relay_chain_currency_id: CurrencyId,
+		liquid_currency_id: CurrencyId,
+		dollar: fn(CurrencyId) -> Balance,
+	) -> Self {
+		Self {
+			native_currency_id,
+			stable_currency_id,
+			relay_chain_currency_id,
+			liquid_currency_id,
+			dollar,
+			positions: 2_000,
+			auctions: 24,
+			incentive_shares: 200,
+			_marker: PhantomData,
+		}
+	}
+
+	pub fn positions(mut self, positions: u32) -> Self {
+		self.positions = positions;
+		self
+	}
+
+	pub fn auctions(mut self, auctions: u32) -> Self {
+		self.auctions = auctions;
+		self
+	}
+
+	pub fn incentive_shares(mut self, incentive_shares: u32) -> Self {
+		self.incentive_shares = incentive_shares;
+		self
+	}
+
+	/// Populate the state, `funder` provides the liquidity of the DEX pools
+	/// and the collateral of the auctions.
+	pub fn populate(self, funder: &T::AccountId) -> DispatchResult {
+		let dollar = self.dollar;
+		let (native, stable, relay_chain, liquid) = (
+			self.native_currency_id,
+			self.stable_currency_id,
+			self.relay_chain_currency_id,
+			self.liquid_currency_id,
+		);
+
+		for currency_id in [native, stable, relay_chain, liquid] {
+			Self::deposit(currency_id, funder, 1_000_000_000 * dollar(currency_id))?;
+		}
+		for index in 0..self.positions.max(self.incentive_shares) {
+			let who = full_state_account::<T::AccountId>(index);
+			Self::deposit(native, &who, 10 * dollar(native))?;
+			Self::deposit(stable, &who, 1_000 * dollar(stable))?;
+			Self::deposit(relay_chain, &who, 1_000 * dollar(relay_chain))?;
+		}
+
+		// DEX pools of all enabled trading pairs
+		for currency_id in [native, relay_chain, liquid] {
+			<module_dex::Pallet<T> as DEXManager<_, _, _>>::add_liquidity(
+				funder,
+				currency_id,
+				stable,
+				1_000_000 * dollar(currency_id),
+				10_000_000 * dollar(stable),
+				0,
+				false,
+			)?;
+		}
+
+		// CDPs with debits of different sizes
+		CollateralParams::<T>::insert(
+			relay_chain,
+			RiskManagementParams {
+				maximum_total_debit_value: 1_000_000_000 * dollar(stable),
+				interest_rate_per_sec: Some(Rate::saturating_from_rational(1, 100_000_000)),
+				liquidation_ratio: Some(Ratio::saturating_from_rational(150, 100)),
+				liquidation_penalty: Some(Rate::saturating_from_rational(10, 100)),
+				required_collateral_ratio: Some(Ratio::saturating_from_rational(180, 100)),
+			},
+		);
+		for index in 0..self.positions {
+			let debit = (index % 10 + 1) as Balance * 400 * dollar(stable);
+			module_cdp_engine::Pallet::<T>::adjust_position(
+				&full_state_account(index),
+				relay_chain,
+				(500 * dollar(relay_chain)) as Amount,
+				debit as Amount,
+			)?;
+		}
+
+		// collateral auctions
+		for _ in 0..self.auctions {
+			<module_cdp_treasury::Pallet<T> as CDPTreasury<_>>::deposit_collateral(
+				funder,
+				relay_chain,
+				100 * dollar(relay_chain),
+			)?;
+			<module_cdp_treasury::Pallet<T> as CDPTreasuryExtended<_>>::create_collateral_auctions(
+				relay_chain,
+				100 * dollar(relay_chain),
+				800 * dollar(stable),
+				funder.clone(),
+				false,
+			)?;
+		}
+
+		// LP token staked in incentives
+		for index in 0..self.incentive_shares {
+			<module_dex::Pallet<T> as DEXManager<_, _, _>>::add_liquidity(
+				&full_state_account(index),
+				relay_chain,
+				stable,
+				10 * dollar(relay_chain),
+				100 * dollar(stable),
+				0,
+				true,
+			)?;
+		}
+
+		Ok(())
+	}
+
+	fn deposit(currency_id: CurrencyId, who: &T::AccountId, amount: Balance) -> DispatchResult {
+		<<T as module_cdp_treasury::Config>::Currency as MultiCurrency<_>>::deposit(currency_id, who, amount)
+	}
+}
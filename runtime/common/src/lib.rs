@@ -23,7 +23,7 @@
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	parameter_types,
-	traits::Contains,
+	traits::{Contains, Randomness},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, WEIGHT_PER_MILLIS},
 		DispatchClass, Weight,
@@ -39,19 +39,25 @@ use sp_core::{
 	H160,
 };
 use sp_runtime::{
-	traits::{BlockNumberProvider, Convert},
-	transaction_validity::TransactionPriority,
+	traits::{BlockNumberProvider, Convert, Hash},
+	transaction_validity::{TransactionLongevity, TransactionPriority},
 	Perbill,
 };
 use static_assertions::const_assert;
 
+mod config_constants;
+pub use config_constants::config_constants;
+
+#[cfg(any(feature = "std", feature = "runtime-benchmarks"))]
+pub mod full_state;
+
 mod homa;
 pub use homa::*;
 
 pub mod precompile;
 pub use precompile::{
-	AllPrecompiles, DexPrecompile, MultiCurrencyPrecompile, NFTPrecompile, OraclePrecompile, ScheduleCallPrecompile,
-	StateRentPrecompile,
+	AllPrecompiles, DexPrecompile, FlashLoanPrecompile, HomaPrecompile, MultiCurrencyPrecompile, NFTPrecompile,
+	OraclePrecompile, ScheduleCallPrecompile, StateRentPrecompile,
 };
 pub use primitives::{
 	currency::{TokenInfo, ACA, AUSD, BNC, DOT, KAR, KSM, KUSD, LDOT, LKSM, RENBTC, VSKSM},
@@ -67,6 +73,15 @@ parameter_types! {
 	pub const CdpEngineUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;      // 50%
 	pub const AuctionManagerUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 5; // 20%
 	pub const RenvmBridgeUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 10;   // 10%
+	pub const PricesUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 20;        // 5%
+	pub const ProofOfReserveUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 20; // 5%
+	pub const KeeperUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 50;         // 2%
+}
+
+// Longevity of unsigned transactions
+parameter_types! {
+	// The unsigned liquidations are revalidated and resubmitted by the offchain worker as the price moves
+	pub const CdpEngineUnsignedLongevity: TransactionLongevity = 16;
 }
 
 /// The call is allowed only if caller is a system contract.
@@ -86,6 +101,14 @@ impl Convert<u64, Weight> for GasToWeight {
 	}
 }
 
+/// Convert weight to gas, the inverse of `GasToWeight`
+pub struct WeightToGas;
+impl Convert<Weight, u64> for WeightToGas {
+	fn convert(a: Weight) -> u64 {
+		a as u64
+	}
+}
+
 // TODO: somehow estimate this value. Start from a conservative value.
 pub const AVERAGE_ON_INITIALIZE_RATIO: Perbill = Perbill::from_percent(10);
 /// The ratio that `Normal` extrinsics should occupy. Start from a conservative value.
@@ -168,16 +191,35 @@ impl<T: cumulus_pallet_parachain_system::Config> BlockNumberProvider for RelayCh
 	}
 }
 
+/// The randomness from the storage root of the relay parent, which the
+/// collators can only choose among the few recent relay blocks. It is only
+/// available after the validation data of the block is set.
+pub struct RelayChainRandomness<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: cumulus_pallet_parachain_system::Config> Randomness<T::Hash, T::BlockNumber> for RelayChainRandomness<T> {
+	fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+		let relay_parent_storage_root = cumulus_pallet_parachain_system::Pallet::<T>::validation_data()
+			.map(|d| d.relay_parent_storage_root)
+			.unwrap_or_default();
+		(
+			T::Hashing::hash_of(&(relay_parent_storage_root, subject)),
+			frame_system::Pallet::<T>::block_number(),
+		)
+	}
+}
+
 pub type GeneralCouncilInstance = pallet_collective::Instance1;
 pub type FinancialCouncilInstance = pallet_collective::Instance2;
 pub type HomaCouncilInstance = pallet_collective::Instance3;
 pub type TechnicalCommitteeInstance = pallet_collective::Instance4;
+pub type ParameterCouncilInstance = pallet_collective::Instance5;
 
 pub type GeneralCouncilMembershipInstance = pallet_membership::Instance1;
 pub type FinancialCouncilMembershipInstance = pallet_membership::Instance2;
 pub type HomaCouncilMembershipInstance = pallet_membership::Instance3;
 pub type TechnicalCommitteeMembershipInstance = pallet_membership::Instance4;
 pub type OperatorMembershipInstanceAcala = pallet_membership::Instance5;
+pub type ParameterCouncilMembershipInstance = pallet_membership::Instance6;
 
 // General Council
 pub type EnsureRootOrAllGeneralCouncil = EnsureOneOf<
@@ -306,6 +348,17 @@ pub type EnsureRootOrThreeFourthsTechnicalCommittee = EnsureOneOf<
 	pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, TechnicalCommitteeInstance>,
 >;
 
+// Parameter Council, its proposals are limited to the whitelisted parameter
+// updates by the call filter of the runtime
+pub type EnsureHalfParameterCouncil =
+	pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, ParameterCouncilInstance>;
+
+pub type EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil =
+	EnsureOneOf<AccountId, EnsureRootOrHalfFinancialCouncil, EnsureHalfParameterCouncil>;
+
+pub type EnsureRootOrThreeFourthsGeneralCouncilOrHalfParameterCouncil =
+	EnsureOneOf<AccountId, EnsureRootOrThreeFourthsGeneralCouncil, EnsureHalfParameterCouncil>;
+
 /// The type used to represent the kinds of proxying allowed.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub enum ProxyType {
@@ -344,4 +397,11 @@ mod tests {
 		min_blocked_addr[SYSTEM_CONTRACT_ADDRESS_PREFIX.len() - 1] = 1u8;
 		assert!(!SystemContractsFilter::is_allowed(min_blocked_addr.into()));
 	}
+
+	#[test]
+	fn weight_to_gas_is_inverse_of_gas_to_weight() {
+		for weight in [0, 1, 152_000_000, MAXIMUM_BLOCK_WEIGHT] {
+			assert_eq!(GasToWeight::convert(WeightToGas::convert(weight)), weight);
+		}
+	}
 }
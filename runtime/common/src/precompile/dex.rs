@@ -17,8 +17,9 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::input::{Input, InputT, Output};
-use crate::precompile::PrecompileOutput;
+use crate::precompile::{weight_to_cost, PrecompileOutput};
 use frame_support::log;
+use module_dex::WeightInfo as DexWeightInfo;
 use module_evm::{Context, ExitError, ExitSucceed, Precompile};
 use module_support::{AddressMapping as AddressMappingT, DEXManager, Erc20InfoMapping as Erc20InfoMappingT};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -35,8 +36,11 @@ use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
 /// - Get liquidity. Rest `input` bytes: `currency_id_a`, `currency_id_b`.
 /// - Swap with exact supply. Rest `input` bytes: `who`, `currency_id_a`, `currency_id_b`,
 ///   `supply_amount`, `min_target_amount`.
-pub struct DexPrecompile<AccountId, AddressMapping, Erc20InfoMapping, Dex>(
-	PhantomData<(AccountId, AddressMapping, Erc20InfoMapping, Dex)>,
+///
+/// The gas cost of the actions changing the state is converted from the weight
+/// of the native calls they map to, by `WeightInfo`.
+pub struct DexPrecompile<AccountId, AddressMapping, Erc20InfoMapping, Dex, WeightInfo>(
+	PhantomData<(AccountId, AddressMapping, Erc20InfoMapping, Dex, WeightInfo)>,
 );
 
 #[module_evm_utiltity_macro::generate_function_selector]
@@ -53,17 +57,18 @@ pub enum Action {
 	RemoveLiquidity = "removeLiquidity(address,address,address,uint256,uint256,uint256)",
 }
 
-impl<AccountId, AddressMapping, Erc20InfoMapping, Dex> Precompile
-	for DexPrecompile<AccountId, AddressMapping, Erc20InfoMapping, Dex>
+impl<AccountId, AddressMapping, Erc20InfoMapping, Dex, WeightInfo> Precompile
+	for DexPrecompile<AccountId, AddressMapping, Erc20InfoMapping, Dex, WeightInfo>
 where
 	AccountId: Debug + Clone,
 	AddressMapping: AddressMappingT<AccountId>,
 	Erc20InfoMapping: Erc20InfoMappingT,
 	Dex: DEXManager<AccountId, CurrencyId, Balance>,
+	WeightInfo: DexWeightInfo,
 {
 	fn execute(
 		input: &[u8],
-		_target_gas: Option<u64>,
+		target_gas: Option<u64>,
 		_context: &Context,
 	) -> result::Result<PrecompileOutput, ExitError> {
 		let input = Input::<Action, AccountId, AddressMapping, Erc20InfoMapping>::new(input);
@@ -172,6 +177,12 @@ where
 					who, path, supply_amount, min_target_amount
 				);
 
+				let cost = weight_to_cost(WeightInfo::swap_with_exact_supply(path_len), target_gas)?;
+				if !Dex::is_instant_execution(&path) {
+					return Err(ExitError::Other(
+						"Dex swap path must be in instant execution mode".into(),
+					));
+				}
 				let value =
 					Dex::swap_with_exact_supply(&who, &path, supply_amount, min_target_amount).map_err(|e| {
 						let err_msg: &str = e.into();
@@ -180,7 +191,7 @@ where
 
 				Ok(PrecompileOutput {
 					exit_status: ExitSucceed::Returned,
-					cost: 0,
+					cost,
 					output: Output::default().encode_u128(value),
 					logs: Default::default(),
 				})
@@ -201,6 +212,12 @@ where
 					who, path, target_amount, max_supply_amount
 				);
 
+				let cost = weight_to_cost(WeightInfo::swap_with_exact_target(path_len), target_gas)?;
+				if !Dex::is_instant_execution(&path) {
+					return Err(ExitError::Other(
+						"Dex swap path must be in instant execution mode".into(),
+					));
+				}
 				let value =
 					Dex::swap_with_exact_target(&who, &path, target_amount, max_supply_amount).map_err(|e| {
 						let err_msg: &str = e.into();
@@ -209,7 +226,7 @@ where
 
 				Ok(PrecompileOutput {
 					exit_status: ExitSucceed::Returned,
-					cost: 0,
+					cost,
 					output: Output::default().encode_u128(value),
 					logs: Default::default(),
 				})
@@ -228,6 +245,7 @@ where
 					who, currency_id_a, currency_id_b, max_amount_a, max_amount_b, min_share_increment,
 				);
 
+				let cost = weight_to_cost(WeightInfo::add_liquidity(), target_gas)?;
 				Dex::add_liquidity(
 					&who,
 					currency_id_a,
@@ -244,7 +262,7 @@ where
 
 				Ok(PrecompileOutput {
 					exit_status: ExitSucceed::Returned,
-					cost: 0,
+					cost,
 					output: vec![],
 					logs: Default::default(),
 				})
@@ -263,6 +281,7 @@ where
 					who, currency_id_a, currency_id_b, remove_share, min_withdrawn_a, min_withdrawn_b,
 				);
 
+				let cost = weight_to_cost(WeightInfo::remove_liquidity(), target_gas)?;
 				Dex::remove_liquidity(
 					&who,
 					currency_id_a,
@@ -279,7 +298,7 @@ where
 
 				Ok(PrecompileOutput {
 					exit_status: ExitSucceed::Returned,
-					cost: 0,
+					cost,
 					output: vec![],
 					logs: Default::default(),
 				})
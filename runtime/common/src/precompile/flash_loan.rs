@@ -0,0 +1,172 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::input::{Input, InputT, Output};
+use crate::precompile::{weight_to_cost, PrecompileOutput};
+use frame_support::{ensure, log};
+use module_cdp_treasury::WeightInfo as CDPTreasuryWeightInfo;
+use module_evm::{Context, ExitError, ExitReason, ExitSucceed, Precompile};
+use module_support::{
+	AddressMapping as AddressMappingT, Erc20InfoMapping as Erc20InfoMappingT, ExecutionMode, FlashLoan as FlashLoanT,
+	InvokeContext, EVM as EVMTrait,
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use primitives::{Balance, CurrencyId};
+use sp_core::{H256, U256};
+use sp_runtime::{traits::SaturatedConversion, DispatchError, RuntimeDebug};
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
+
+/// The gas limit of the callback to the borrower contract.
+const CALLBACK_GAS_LIMIT: u64 = 2_100_000;
+/// The storage limit of the callback to the borrower contract.
+const CALLBACK_STORAGE_LIMIT: u32 = 1_000;
+
+/// The `FlashLoan` impl precompile.
+///
+///
+/// `input` data starts with `action`.
+///
+/// Actions:
+/// - Get flash loan fee. Rest `input` bytes: `currency_id`, `amount`.
+/// - Flash loan. Rest `input` bytes: `who`, `currency_id`, `amount`. The loan is lent to `who`, then
+///   `onFlashLoan(address,uint256,uint256)` of contract `who` is called with `currency_id`, `amount`
+///   and `fee`. The loan and the fee are taken back from `who` after the callback.
+///
+/// The gas cost of flash loan is converted from the weight of the native call by `WeightInfo`, plus
+/// the gas used by the callback.
+pub struct FlashLoanPrecompile<AccountId, AddressMapping, Erc20InfoMapping, FlashLoan, EVM, WeightInfo>(
+	PhantomData<(AccountId, AddressMapping, Erc20InfoMapping, FlashLoan, EVM, WeightInfo)>,
+);
+
+#[module_evm_utiltity_macro::generate_function_selector]
+#[derive(RuntimeDebug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Action {
+	GetFlashLoanFee = "getFlashLoanFee(address,uint256)",
+	FlashLoan = "flashLoan(address,address,uint256)",
+}
+
+#[module_evm_utiltity_macro::generate_function_selector]
+#[derive(RuntimeDebug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Callback {
+	OnFlashLoan = "onFlashLoan(address,uint256,uint256)",
+}
+
+impl<AccountId, AddressMapping, Erc20InfoMapping, FlashLoan, EVM, WeightInfo> Precompile
+	for FlashLoanPrecompile<AccountId, AddressMapping, Erc20InfoMapping, FlashLoan, EVM, WeightInfo>
+where
+	AccountId: Debug + Clone,
+	AddressMapping: AddressMappingT<AccountId>,
+	Erc20InfoMapping: Erc20InfoMappingT,
+	FlashLoan: FlashLoanT<AccountId, CurrencyId, Balance>,
+	EVM: EVMTrait<AccountId>,
+	WeightInfo: CDPTreasuryWeightInfo,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> result::Result<PrecompileOutput, ExitError> {
+		let input = Input::<Action, AccountId, AddressMapping, Erc20InfoMapping>::new(input);
+
+		let action = input.action()?;
+
+		match action {
+			Action::GetFlashLoanFee => {
+				let currency_id = input.currency_id_at(1)?;
+				let amount = input.balance_at(2)?;
+
+				log::debug!(
+					target: "evm",
+					"flash_loan: get_flash_loan_fee currency_id: {:?}, amount: {:?}",
+					currency_id, amount
+				);
+
+				let fee = FlashLoan::flash_loan_fee(currency_id, amount)
+					.ok_or_else(|| ExitError::Other("FlashLoan get_flash_loan_fee failed".into()))?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u128(fee),
+					logs: Default::default(),
+				})
+			}
+			Action::FlashLoan => {
+				let who = input.account_id_at(1)?;
+				let receiver = input.evm_address_at(1)?;
+				let currency_address = input.evm_address_at(2)?;
+				let currency_id = input.currency_id_at(2)?;
+				let amount = input.balance_at(3)?;
+
+				log::debug!(
+					target: "evm",
+					"flash_loan: flash_loan who: {:?}, currency_id: {:?}, amount: {:?}",
+					who, currency_id, amount
+				);
+
+				let fee = FlashLoan::flash_loan_fee(currency_id, amount)
+					.ok_or_else(|| ExitError::Other("FlashLoanNotEnabled".into()))?;
+				let cost = weight_to_cost(WeightInfo::flash_loan(), target_gas)?;
+				let gas_limit = target_gas.map_or(CALLBACK_GAS_LIMIT, |gas| {
+					gas.saturating_sub(cost).min(CALLBACK_GAS_LIMIT)
+				});
+
+				// action + currency_id + amount + fee
+				let mut callback_input = Into::<u32>::into(Callback::OnFlashLoan).to_be_bytes().to_vec();
+				callback_input.extend_from_slice(H256::from(currency_address).as_bytes());
+				callback_input.extend_from_slice(H256::from_uint(&U256::from(amount)).as_bytes());
+				callback_input.extend_from_slice(H256::from_uint(&U256::from(fee)).as_bytes());
+
+				let mut used_gas: u64 = 0;
+				FlashLoan::flash_loan(&who, currency_id, amount, || {
+					let info = EVM::execute(
+						InvokeContext {
+							contract: receiver,
+							sender: context.address,
+							origin: context.caller,
+						},
+						callback_input,
+						Default::default(),
+						gas_limit,
+						CALLBACK_STORAGE_LIMIT,
+						ExecutionMode::Execute,
+					)?;
+					used_gas = info.used_gas.saturated_into();
+					ensure!(
+						matches!(info.exit_reason, ExitReason::Succeed(_)),
+						DispatchError::Other("FlashLoan callback failed")
+					);
+					Ok(())
+				})
+				.map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: cost.saturating_add(used_gas),
+					output: vec![],
+					logs: Default::default(),
+				})
+			}
+		}
+	}
+}
@@ -32,8 +32,9 @@ use module_evm::EvmTask;
 use module_support::DispatchableTask;
 use module_support::{
 	mocks::MockAddressMapping, AddressMapping as AddressMappingT, DEXIncentives, ExchangeRate, ExchangeRateProvider,
+	FlashLoan, HomaManager,
 };
-use orml_traits::{parameter_type_with_key, MultiReservableCurrency};
+use orml_traits::{parameter_type_with_key, MultiCurrency, MultiReservableCurrency};
 pub use primitives::{
 	define_combined_task, evm::EvmAddress, task::TaskResult, Amount, BlockNumber, CurrencyId, DexShare, Header, Nonce,
 	ReserveIdentifier, TokenSymbol, TradingPair,
@@ -42,7 +43,7 @@ use scale_info::TypeInfo;
 use sp_core::{crypto::AccountId32, H160, H256};
 use sp_runtime::{
 	traits::{BlakeTwo256, Convert, IdentityLookup, One as OneT},
-	DispatchResult, FixedPointNumber, FixedU128, Perbill,
+	DispatchError, DispatchResult, FixedPointNumber, FixedU128, Perbill, Permill,
 };
 use sp_std::{collections::btree_map::BTreeMap, convert::TryFrom, str::FromStr};
 
@@ -173,6 +174,7 @@ impl module_currencies::Config for Test {
 	type EVMBridge = EVMBridge;
 	type SweepOrigin = EnsureSignedBy<CouncilAccount, AccountId>;
 	type OnDust = ();
+	type OnTransfer = ();
 }
 
 impl module_evm_bridge::Config for Test {
@@ -242,6 +244,7 @@ parameter_types! {
 	pub const GetStableCurrencyId: CurrencyId = CurrencyId::Token(TokenSymbol::AUSD);
 	pub DefaultFeeSwapPathList: Vec<Vec<CurrencyId>> = vec![vec![CurrencyId::Token(TokenSymbol::AUSD), CurrencyId::Token(TokenSymbol::ACA)]];
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::one();
+	pub const MaxFeeFreeTxPerBlock: u32 = 20;
 }
 
 impl module_transaction_payment::Config for Test {
@@ -257,6 +260,8 @@ impl module_transaction_payment::Config for Test {
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type TradingPathLimit = TradingPathLimit;
 	type PriceSource = module_prices::RealTimePriceProvider<Test>;
+	type MaxFeeFreeTxPerBlock = MaxFeeFreeTxPerBlock;
+	type UpdateOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = ();
 }
 pub type ChargeTransactionPayment = module_transaction_payment::ChargeTransactionPayment<Test>;
@@ -341,6 +346,10 @@ impl DEXIncentives<AccountId, CurrencyId, Balance> for MockDEXIncentives {
 		let _ = Tokens::unreserve(lp_currency_id, who, amount);
 		Ok(())
 	}
+
+	fn do_claim_dex_share_rewards(_who: &AccountId, _lp_currency_id: CurrencyId) -> DispatchResult {
+		Ok(())
+	}
 }
 
 ord_parameter_types! {
@@ -350,6 +359,7 @@ ord_parameter_types! {
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (1, 100);
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 10;
 	pub const DEXPalletId: PalletId = PalletId(*b"aca/dexm");
 }
 
@@ -358,11 +368,15 @@ impl module_dex::Config for Test {
 	type Currency = Tokens;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = EvmErc20InfoMapping;
 	type WeightInfo = ();
 	type DEXIncentives = MockDEXIncentives;
 	type ListingOrigin = EnsureSignedBy<ListingOrigin, AccountId>;
+	type StableAsset = ();
+	type ProtocolFeeReceiver = TreasuryAccount;
+	type OnProtocolFee = ();
 }
 
 pub type AdaptedBasicCurrency = module_currencies::BasicCurrencyAdapter<Test, Balances, Amount, BlockNumber>;
@@ -391,7 +405,10 @@ pub type ScheduleCallPrecompile = crate::ScheduleCallPrecompile<
 	OriginCaller,
 	Test,
 >;
-pub type DexPrecompile = crate::DexPrecompile<AccountId, MockAddressMapping, EvmErc20InfoMapping, DexModule>;
+pub type DexPrecompile = crate::DexPrecompile<AccountId, MockAddressMapping, EvmErc20InfoMapping, DexModule, ()>;
+pub type HomaPrecompile = crate::HomaPrecompile<AccountId, MockAddressMapping, EvmErc20InfoMapping, MockHoma, ()>;
+pub type FlashLoanPrecompile =
+	crate::FlashLoanPrecompile<AccountId, MockAddressMapping, EvmErc20InfoMapping, MockFlashLoan, EVMModule, ()>;
 
 parameter_types! {
 	pub NetworkContractSource: H160 = alice_evm_addr();
@@ -424,12 +441,15 @@ impl module_evm::Config for Test {
 	type Event = Event;
 	type Precompiles = AllPrecompiles<
 		SystemContractsFilter,
+		(),
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		HomaPrecompile,
+		FlashLoanPrecompile,
 	>;
 	type ChainId = ChainId;
 	type GasToWeight = GasToWeight;
@@ -454,10 +474,56 @@ impl ExchangeRateProvider for MockLiquidStakingExchangeProvider {
 	}
 }
 
+pub struct MockHoma;
+impl HomaManager<AccountId, Balance> for MockHoma {
+	fn mint(who: &AccountId, amount: Balance) -> DispatchResult {
+		let liquid_amount = Self::get_exchange_rate()
+			.reciprocal()
+			.unwrap_or_default()
+			.saturating_mul_int(amount);
+		Tokens::withdraw(DOT, who, amount)?;
+		Tokens::deposit(LDOT, who, liquid_amount)
+	}
+
+	fn request_redeem(who: &AccountId, amount: Balance, _fast_match_fee_rate: Permill) -> DispatchResult {
+		Tokens::reserve(LDOT, who, amount)
+	}
+
+	fn get_exchange_rate() -> ExchangeRate {
+		MockLiquidStakingExchangeProvider::get_exchange_rate()
+	}
+}
+
+pub struct MockFlashLoan;
+impl FlashLoan<AccountId, CurrencyId, Balance> for MockFlashLoan {
+	fn flash_loan_fee(currency_id: CurrencyId, amount: Balance) -> Option<Balance> {
+		if currency_id == AUSD {
+			Some(amount / 100)
+		} else {
+			None
+		}
+	}
+
+	fn flash_loan(
+		who: &AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+		f: impl FnOnce() -> DispatchResult,
+	) -> DispatchResult {
+		let fee = Self::flash_loan_fee(currency_id, amount).ok_or(DispatchError::Other("FlashLoanNotEnabled"))?;
+		Tokens::deposit(currency_id, who, amount)?;
+		f()?;
+		Tokens::withdraw(currency_id, who, amount + fee)
+	}
+}
+
 parameter_types! {
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_rational(1, 1);
 	pub const GetStakingCurrencyId: CurrencyId = DOT;
 	pub const GetLiquidCurrencyId: CurrencyId = LDOT;
+	pub const PricesTwapWindow: BlockNumber = 10;
+	pub const PriceDeviationReportInterval: BlockNumber = 10;
+	pub const PricesUnsignedPriority: u64 = 1 << 20;
 }
 
 ord_parameter_types! {
@@ -476,9 +542,21 @@ impl module_prices::Config for Test {
 	type DEX = DexModule;
 	type Currency = Currencies;
 	type Erc20InfoMapping = EvmErc20InfoMapping;
+	type DEXPriceCumulative = DexModule;
+	type TwapWindow = PricesTwapWindow;
+	type DeviationReportInterval = PriceDeviationReportInterval;
+	type UnsignedPriority = PricesUnsignedPriority;
 	type WeightInfo = ();
 }
 
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
 pub const ALICE: AccountId = AccountId::new([1u8; 32]);
 pub const BOB: AccountId = AccountId::new([2u8; 32]);
 pub const EVA: AccountId = AccountId::new([5u8; 32]);
@@ -24,7 +24,8 @@
 mod mock;
 mod tests;
 
-use frame_support::log;
+use crate::WeightToGas;
+use frame_support::{log, weights::Weight};
 use module_evm::{
 	precompiles::{
 		ECRecover, ECRecoverPublicKey, EvmPrecompiles, Identity, Precompile, PrecompileSet, Ripemd160, Sha256,
@@ -33,12 +34,15 @@ use module_evm::{
 	runner::state::PrecompileOutput,
 	Context, ExitError,
 };
-use module_support::PrecompileCallerFilter as PrecompileCallerFilterT;
+use module_support::{EvmCallPauseFilter as EvmCallPauseFilterT, PrecompileCallerFilter as PrecompileCallerFilterT};
 use primitives::evm::{is_acala_precompile, PRECOMPILE_ADDRESS_START};
 use sp_core::H160;
+use sp_runtime::traits::Convert;
 use sp_std::marker::PhantomData;
 
 pub mod dex;
+pub mod flash_loan;
+pub mod homa;
 pub mod input;
 pub mod multicurrency;
 pub mod nft;
@@ -47,57 +51,86 @@ pub mod schedule_call;
 pub mod state_rent;
 
 pub use dex::DexPrecompile;
+pub use flash_loan::FlashLoanPrecompile;
+pub use homa::HomaPrecompile;
 pub use multicurrency::MultiCurrencyPrecompile;
 pub use nft::NFTPrecompile;
 pub use oracle::OraclePrecompile;
 pub use schedule_call::ScheduleCallPrecompile;
 pub use state_rent::StateRentPrecompile;
 
+/// Convert the weight of the native call a precompile action maps to into its
+/// gas cost, so the precompile can't be used to underpay for the native call.
+/// Fails if the cost exceeds the target gas.
+pub fn weight_to_cost(weight: Weight, target_gas: Option<u64>) -> Result<u64, ExitError> {
+	let cost = WeightToGas::convert(weight);
+	if target_gas.map_or(false, |target_gas| cost > target_gas) {
+		return Err(ExitError::OutOfGas);
+	}
+	Ok(cost)
+}
+
 pub struct AllPrecompiles<
 	PrecompileCallerFilter,
+	EvmCallPauseFilter,
 	MultiCurrencyPrecompile,
 	NFTPrecompile,
 	StateRentPrecompile,
 	OraclePrecompile,
 	ScheduleCallPrecompile,
 	DexPrecompile,
+	HomaPrecompile,
+	FlashLoanPrecompile,
 >(
 	PhantomData<(
 		PrecompileCallerFilter,
+		EvmCallPauseFilter,
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		HomaPrecompile,
+		FlashLoanPrecompile,
 	)>,
 );
 
 impl<
 		PrecompileCallerFilter,
+		EvmCallPauseFilter,
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		HomaPrecompile,
+		FlashLoanPrecompile,
 	> PrecompileSet
 	for AllPrecompiles<
 		PrecompileCallerFilter,
+		EvmCallPauseFilter,
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
-	> where
+		HomaPrecompile,
+		FlashLoanPrecompile,
+	>
+where
 	MultiCurrencyPrecompile: Precompile,
 	NFTPrecompile: Precompile,
 	StateRentPrecompile: Precompile,
 	OraclePrecompile: Precompile,
 	ScheduleCallPrecompile: Precompile,
 	PrecompileCallerFilter: PrecompileCallerFilterT,
+	EvmCallPauseFilter: EvmCallPauseFilterT,
 	DexPrecompile: Precompile,
+	HomaPrecompile: Precompile,
+	FlashLoanPrecompile: Precompile,
 {
 	#[allow(clippy::type_complexity)]
 	fn execute(
@@ -106,6 +139,14 @@ impl<
 		target_gas: Option<u64>,
 		context: &Context,
 	) -> Option<core::result::Result<PrecompileOutput, ExitError>> {
+		// all the calls go through the precompiles first, reject the paused calls
+		// to the contracts as well as to the precompiles. A rejected call consumes
+		// the gas of its frame, which pays for the check.
+		if EvmCallPauseFilter::is_paused(address, input) {
+			log::debug!(target: "evm", "Call paused, address: {:?}, input: {:?}", address, input);
+			return Some(Err(ExitError::Other("call paused".into())));
+		}
+
 		EvmPrecompiles::<ECRecover, Sha256, Ripemd160, Identity, ECRecoverPublicKey, Sha3FIPS256, Sha3FIPS512>::execute(
 			address, input, target_gas, context,
 		)
@@ -133,6 +174,10 @@ impl<
 				Some(ScheduleCallPrecompile::execute(input, target_gas, context))
 			} else if address == PRECOMPILE_ADDRESS_START | H160::from_low_u64_be(5) {
 				Some(DexPrecompile::execute(input, target_gas, context))
+			} else if address == PRECOMPILE_ADDRESS_START | H160::from_low_u64_be(6) {
+				Some(HomaPrecompile::execute(input, target_gas, context))
+			} else if address == PRECOMPILE_ADDRESS_START | H160::from_low_u64_be(7) {
+				Some(FlashLoanPrecompile::execute(input, target_gas, context))
 			} else {
 				None
 			};
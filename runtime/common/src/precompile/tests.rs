@@ -22,26 +22,43 @@ use super::*;
 use crate::precompile::{
 	mock::{
 		aca_evm_address, alice, alice_evm_addr, ausd_evm_address, bob, bob_evm_addr, erc20_address_not_exists,
-		get_task_id, lp_aca_ausd_evm_address, new_test_ext, renbtc_evm_address, run_to_block, Balances, DexModule,
-		DexPrecompile, Event as TestEvent, MultiCurrencyPrecompile, Oracle, OraclePrecompile, Origin, Price,
-		ScheduleCallPrecompile, System, Test, ALICE, AUSD, INITIAL_BALANCE, RENBTC,
+		get_task_id, lp_aca_ausd_evm_address, new_test_ext, renbtc_evm_address, run_to_block, Balances, Currencies,
+		DexModule, DexPrecompile, Event as TestEvent, FlashLoanPrecompile, HomaPrecompile, MultiCurrencyPrecompile,
+		Oracle, OraclePrecompile, Origin, Price, ScheduleCallPrecompile, System, Test, Tokens, ALICE, AUSD, DOT,
+		INITIAL_BALANCE, LDOT, RENBTC,
 	},
 	schedule_call::TaskInfo,
 };
+use crate::{GasToWeight, Weight, WeightToGas};
 use codec::Encode;
 use frame_support::{assert_noop, assert_ok};
 use hex_literal::hex;
 use module_evm::{Context, ExitError, ExitSucceed, Precompile};
 use module_support::AddressMapping;
-use orml_traits::DataFeeder;
+use orml_traits::{DataFeeder, MultiCurrency, MultiReservableCurrency};
 use primitives::{
 	evm::{PRECOMPILE_ADDRESS_START, PREDEPLOY_ADDRESS_START},
 	Balance,
 };
 use sp_core::{H160, U256};
-use sp_runtime::FixedPointNumber;
+use sp_runtime::{traits::Convert, FixedPointNumber};
 use std::str::FromStr;
 
+/// The max deviation in percent of the weight charged for the gas cost of a
+/// precompile action from the weight of the native call it maps to.
+const GAS_WEIGHT_TOLERANCE_PERCENT: u64 = 5;
+
+fn assert_gas_tracks_weight(cost: u64, weight: Weight) {
+	let charged_weight = GasToWeight::convert(cost);
+	let tolerance = weight * GAS_WEIGHT_TOLERANCE_PERCENT / 100;
+	assert!(
+		charged_weight + tolerance >= weight && charged_weight <= weight + tolerance,
+		"precompile charged weight {} does not track native weight {}",
+		charged_weight,
+		weight
+	);
+}
+
 pub struct DummyPrecompile;
 impl Precompile for DummyPrecompile {
 	fn execute(
@@ -60,6 +77,9 @@ impl Precompile for DummyPrecompile {
 
 pub type WithSystemContractFilter = AllPrecompiles<
 	crate::SystemContractsFilter,
+	(),
+	DummyPrecompile,
+	DummyPrecompile,
 	DummyPrecompile,
 	DummyPrecompile,
 	DummyPrecompile,
@@ -120,6 +140,66 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 	);
 }
 
+const PAUSED_CONTRACT: [u8; 20] = hex!("1000000000000000000000000000000000000001");
+
+// pauses the calls to the MultiCurrency precompile and the `transfer` of
+// `PAUSED_CONTRACT`
+pub struct MockEvmCallPauseFilter;
+impl module_support::EvmCallPauseFilter for MockEvmCallPauseFilter {
+	fn is_paused(address: H160, input: &[u8]) -> bool {
+		address == PRECOMPILE_ADDRESS_START
+			|| (address == PAUSED_CONTRACT.into() && input.starts_with(&hex!("a9059cbb")))
+	}
+}
+
+pub type WithEvmCallPauseFilter = AllPrecompiles<
+	crate::SystemContractsFilter,
+	MockEvmCallPauseFilter,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+	DummyPrecompile,
+>;
+
+#[test]
+fn evm_call_pause_filter_works() {
+	let context = Context {
+		address: Default::default(),
+		caller: PREDEPLOY_ADDRESS_START,
+		apparent_value: 0.into(),
+	};
+	let paused = Some(Err(ExitError::Other("call paused".into())));
+
+	// the paused precompile
+	assert_eq!(
+		WithEvmCallPauseFilter::execute(PRECOMPILE_ADDRESS_START, &[0u8; 1], None, &context),
+		paused
+	);
+	assert!(WithEvmCallPauseFilter::execute(
+		PRECOMPILE_ADDRESS_START | H160::from_low_u64_be(1),
+		&[0u8; 1],
+		None,
+		&context
+	)
+	.unwrap()
+	.is_ok());
+
+	// the paused function of the contract
+	let contract: H160 = PAUSED_CONTRACT.into();
+	assert_eq!(
+		WithEvmCallPauseFilter::execute(contract, &hex!("a9059cbb0000"), None, &context),
+		paused
+	);
+	assert!(WithEvmCallPauseFilter::execute(contract, &hex!("095ea7b30000"), None, &context).is_none());
+	assert!(WithEvmCallPauseFilter::execute(bob_evm_addr(), &hex!("a9059cbb0000"), None, &context).is_none());
+	// the calls that are not paused need no gas for the check
+	assert!(WithEvmCallPauseFilter::execute(bob_evm_addr(), &[], Some(2_300), &context).is_none());
+}
+
 #[test]
 fn multicurrency_precompile_should_work() {
 	new_test_ext().execute_with(|| {
@@ -833,7 +913,7 @@ fn dex_precompile_swap_with_exact_supply_should_work() {
 		let resp = DexPrecompile::execute(&input, None, &context).unwrap();
 		assert_eq!(resp.exit_status, ExitSucceed::Returned);
 		assert_eq!(resp.output, expected_output);
-		assert_eq!(resp.cost, 0);
+		assert_gas_tracks_weight(resp.cost, <() as module_dex::WeightInfo>::swap_with_exact_supply(2));
 	});
 }
 
@@ -884,10 +964,175 @@ fn dex_precompile_swap_with_exact_target_should_work() {
 		let resp = DexPrecompile::execute(&input, None, &context).unwrap();
 		assert_eq!(resp.exit_status, ExitSucceed::Returned);
 		assert_eq!(resp.output, expected_output);
+		assert_gas_tracks_weight(resp.cost, <() as module_dex::WeightInfo>::swap_with_exact_target(2));
+	});
+}
+
+#[test]
+fn homa_precompile_mint_and_request_redeem_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Currencies::update_balance(Origin::root(), alice(), DOT, 1_000));
+
+		let context = Context {
+			address: Default::default(),
+			caller: alice_evm_addr(),
+			apparent_value: Default::default(),
+		};
+
+		// action + who + amount
+		let mut input = [0u8; 3 * 32];
+		// action
+		input[0..4].copy_from_slice(&Into::<u32>::into(homa::Action::Mint).to_be_bytes());
+		// who
+		U256::from(alice_evm_addr().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		// amount
+		U256::from(100).to_big_endian(&mut input[4 + 1 * 32..4 + 2 * 32]);
+
+		let resp = HomaPrecompile::execute(&input, None, &context).unwrap();
+		assert_eq!(resp.exit_status, ExitSucceed::Returned);
+		assert_eq!(resp.output, [0u8; 0]);
+		assert_gas_tracks_weight(resp.cost, <() as module_homa_lite::WeightInfo>::mint());
+		assert_eq!(Tokens::free_balance(DOT, &alice()), 900);
+		assert_eq!(Tokens::free_balance(LDOT, &alice()), 200);
+
+		// action + who + amount + fast_match_fee_rate
+		let mut input = [0u8; 4 * 32];
+		// action
+		input[0..4].copy_from_slice(&Into::<u32>::into(homa::Action::RequestRedeem).to_be_bytes());
+		// who
+		U256::from(alice_evm_addr().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		// amount
+		U256::from(150).to_big_endian(&mut input[4 + 1 * 32..4 + 2 * 32]);
+		// fast_match_fee_rate
+		U256::from(10_000).to_big_endian(&mut input[4 + 2 * 32..4 + 3 * 32]);
+
+		let resp = HomaPrecompile::execute(&input, None, &context).unwrap();
+		assert_eq!(resp.exit_status, ExitSucceed::Returned);
+		assert_eq!(resp.output, [0u8; 0]);
+		assert_gas_tracks_weight(resp.cost, <() as module_homa_lite::WeightInfo>::request_redeem());
+		assert_eq!(Tokens::free_balance(LDOT, &alice()), 50);
+		assert_eq!(Tokens::reserved_balance(LDOT, &alice()), 150);
+
+		// insufficient liquid balance
+		assert_noop!(
+			HomaPrecompile::execute(&input, None, &context),
+			ExitError::Other("BalanceTooLow".into())
+		);
+	});
+}
+
+#[test]
+fn homa_precompile_get_exchange_rate_should_work() {
+	new_test_ext().execute_with(|| {
+		let context = Context {
+			address: Default::default(),
+			caller: alice_evm_addr(),
+			apparent_value: Default::default(),
+		};
+
+		// action
+		let mut input = [0u8; 4];
+		input[0..4].copy_from_slice(&Into::<u32>::into(homa::Action::GetExchangeRate).to_be_bytes());
+
+		// 0.5 with 18 decimals
+		let mut expected_output = [0u8; 32];
+		U256::from(500_000_000_000_000_000u128).to_big_endian(&mut expected_output[..32]);
+
+		let resp = HomaPrecompile::execute(&input, None, &context).unwrap();
+		assert_eq!(resp.exit_status, ExitSucceed::Returned);
+		assert_eq!(resp.output, expected_output);
 		assert_eq!(resp.cost, 0);
 	});
 }
 
+#[test]
+fn precompile_should_fail_if_gas_not_enough_for_native_weight() {
+	new_test_ext().execute_with(|| {
+		let context = Context {
+			address: Default::default(),
+			caller: alice_evm_addr(),
+			apparent_value: Default::default(),
+		};
+
+		// action + who + amount
+		let mut input = [0u8; 3 * 32];
+		// action
+		input[0..4].copy_from_slice(&Into::<u32>::into(homa::Action::Mint).to_be_bytes());
+		// who
+		U256::from(alice_evm_addr().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		// amount
+		U256::from(100).to_big_endian(&mut input[4 + 1 * 32..4 + 2 * 32]);
+
+		let target_gas = WeightToGas::convert(<() as module_homa_lite::WeightInfo>::mint()) - 1;
+		assert_noop!(
+			HomaPrecompile::execute(&input, Some(target_gas), &context),
+			ExitError::OutOfGas
+		);
+	});
+}
+
+#[test]
+fn flash_loan_precompile_get_flash_loan_fee_should_work() {
+	new_test_ext().execute_with(|| {
+		let context = Context {
+			address: Default::default(),
+			caller: alice_evm_addr(),
+			apparent_value: Default::default(),
+		};
+
+		// action + currency_id + amount
+		let mut input = [0u8; 3 * 32];
+		// action
+		input[0..4].copy_from_slice(&Into::<u32>::into(flash_loan::Action::GetFlashLoanFee).to_be_bytes());
+		// currency_id
+		U256::from(ausd_evm_address().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		// amount
+		U256::from(1_000).to_big_endian(&mut input[4 + 1 * 32..4 + 2 * 32]);
+
+		let mut expected_output = [0u8; 32];
+		U256::from(10).to_big_endian(&mut expected_output[..32]);
+
+		let resp = FlashLoanPrecompile::execute(&input, None, &context).unwrap();
+		assert_eq!(resp.exit_status, ExitSucceed::Returned);
+		assert_eq!(resp.output, expected_output);
+		assert_eq!(resp.cost, 0);
+
+		// flash loan is not enabled
+		U256::from(renbtc_evm_address().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		assert_noop!(
+			FlashLoanPrecompile::execute(&input, None, &context),
+			ExitError::Other("FlashLoan get_flash_loan_fee failed".into())
+		);
+	});
+}
+
+#[test]
+fn flash_loan_precompile_flash_loan_should_handle_disabled_currency() {
+	new_test_ext().execute_with(|| {
+		let context = Context {
+			address: Default::default(),
+			caller: alice_evm_addr(),
+			apparent_value: Default::default(),
+		};
+
+		// action + who + currency_id + amount
+		let mut input = [0u8; 4 * 32];
+		// action
+		input[0..4].copy_from_slice(&Into::<u32>::into(flash_loan::Action::FlashLoan).to_be_bytes());
+		// who
+		U256::from(alice_evm_addr().as_bytes()).to_big_endian(&mut input[4 + 0 * 32..4 + 1 * 32]);
+		// currency_id
+		U256::from(renbtc_evm_address().as_bytes()).to_big_endian(&mut input[4 + 1 * 32..4 + 2 * 32]);
+		// amount
+		U256::from(1_000).to_big_endian(&mut input[4 + 2 * 32..4 + 3 * 32]);
+
+		assert_noop!(
+			FlashLoanPrecompile::execute(&input, None, &context),
+			ExitError::Other("FlashLoanNotEnabled".into())
+		);
+	});
+}
+
 #[test]
 fn task_id_max_and_min() {
 	let task_id = TaskInfo {
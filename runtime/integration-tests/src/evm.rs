@@ -335,6 +335,59 @@ fn test_evm_module() {
 		});
 }
 
+#[test]
+fn evm_call_with_ordinary_gas_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), NATIVE_CURRENCY, 1_000 * dollar(NATIVE_CURRENCY)),
+			(
+				// evm alice
+				MockAddressMapping::get_account_id(&alice_evm_addr()),
+				NATIVE_CURRENCY,
+				1_000 * dollar(NATIVE_CURRENCY),
+			),
+		])
+		.build()
+		.execute_with(|| {
+			deploy_erc20_contracts();
+			let alice_account = MockAddressMapping::get_account_id(&alice_evm_addr());
+
+			// a plain call with the intrinsic gas only
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account.clone()),
+				bob_evm_addr(),
+				vec![],
+				0,
+				21_000,
+				100000
+			));
+			assert!(matches!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(Event::EVM(module_evm::Event::Executed(..)))
+			));
+
+			// transfer(bob, 100) with the gas of an ordinary token transfer
+			let mut input = hex_literal::hex!("a9059cbb").to_vec();
+			input.extend_from_slice(H256::from(bob_evm_addr()).as_bytes());
+			input.extend_from_slice(H256::from_low_u64_be(100).as_bytes());
+			assert_ok!(EVM::call(
+				Origin::signed(alice_account),
+				erc20_address_0(),
+				input,
+				0,
+				100_000,
+				100000
+			));
+
+			let invoke_context = module_support::InvokeContext {
+				contract: erc20_address_0(),
+				sender: alice_evm_addr(),
+				origin: alice_evm_addr(),
+			};
+			assert_eq!(EVMBridge::balance_of(invoke_context, bob_evm_addr()), Ok(100));
+		});
+}
+
 #[test]
 fn test_multicurrency_precompile_module() {
 	ExtBuilder::default()
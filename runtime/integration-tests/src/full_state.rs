@@ -0,0 +1,70 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests of the full-system DeFi state built by `FullStateBuilder`.
+
+use crate::setup::*;
+use module_incentives::PoolId;
+use runtime_common::full_state::{full_state_account, FullStateBuilder};
+
+#[test]
+fn full_state_builder_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		set_oracle_price(vec![
+			(RELAY_CHAIN_CURRENCY, Price::saturating_from_integer(10)),
+			(LIQUID_CURRENCY, Price::saturating_from_integer(8)),
+			(NATIVE_CURRENCY, Price::saturating_from_integer(1)),
+		]);
+		assert_ok!(FullStateBuilder::<Runtime>::new(
+			NATIVE_CURRENCY,
+			USD_CURRENCY,
+			RELAY_CHAIN_CURRENCY,
+			LIQUID_CURRENCY,
+			dollar,
+		)
+		.positions(1_000)
+		.auctions(12)
+		.incentive_shares(100)
+		.populate(&AccountId::from(ALICE)));
+
+		assert_eq!(
+			Loans::total_positions(RELAY_CHAIN_CURRENCY).collateral,
+			1_000 * 500 * dollar(RELAY_CHAIN_CURRENCY)
+		);
+		assert_eq!(
+			module_loans::Positions::<Runtime>::iter_prefix(RELAY_CHAIN_CURRENCY).count(),
+			1_000
+		);
+		assert_eq!(
+			module_auction_manager::CollateralAuctions::<Runtime>::iter().count(),
+			12
+		);
+		assert_eq!(
+			AuctionManager::total_collateral_in_auction(RELAY_CHAIN_CURRENCY),
+			12 * 100 * dollar(RELAY_CHAIN_CURRENCY)
+		);
+		assert!(!Dex::get_liquidity_pool(NATIVE_CURRENCY, USD_CURRENCY).0.is_zero());
+		assert!(!Dex::get_liquidity_pool(LIQUID_CURRENCY, USD_CURRENCY).0.is_zero());
+		assert!(!orml_rewards::Pallet::<Runtime>::shares_and_withdrawn_rewards(
+			PoolId::Dex(LPTOKEN),
+			full_state_account(99)
+		)
+		.0
+		.is_zero());
+	});
+}
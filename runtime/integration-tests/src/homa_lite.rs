@@ -650,10 +650,9 @@ mod karura_only_tests {
 			);
 			HomaLite::on_idle(5, 1_000_000_000);
 			assert_eq!(HomaLite::scheduled_unbond(), vec![]);
-			assert_eq!(
-				HomaLite::available_staking_balance(),
-				1000 * dollar(RELAY_CHAIN_CURRENCY)
-			);
+			// The withdrew staking currency is available once the relaychain reports the outcome.
+			assert_eq!(HomaLite::available_staking_balance(), 0);
+			assert!(HomaLite::pending_xcm_operations(0).is_some());
 		});
 
 		KusamaNet::execute_with(|| {
@@ -667,5 +666,14 @@ mod karura_only_tests {
 				1_001_999_400_000_000
 			);
 		});
+
+		Karura::execute_with(|| {
+			HomaLite::on_idle(5, 1_000_000_000);
+			assert_eq!(HomaLite::pending_xcm_operations(0), None);
+			assert_eq!(
+				HomaLite::available_staking_balance(),
+				1000 * dollar(RELAY_CHAIN_CURRENCY)
+			);
+		});
 	}
 }
@@ -125,15 +125,14 @@ fn liquidate_cdp() {
 				false,
 			));
 
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NewValue(Some(Rate::zero())),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(Some(Rate::saturating_from_rational(20, 100))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(1_000_000 * dollar(USD_CURRENCY)),
-			));
+			);
 
 			assert_ok!(CdpEngine::adjust_position(
 				&AccountId::from(ALICE),
@@ -168,15 +167,14 @@ fn liquidate_cdp() {
 			assert_eq!(CdpTreasury::debit_pool(), 0);
 			assert_eq!(AuctionManager::collateral_auctions(0), None);
 
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NoChange,
 				Change::NewValue(Some(Ratio::saturating_from_rational(400, 100))),
 				Change::NoChange,
 				Change::NewValue(Some(Ratio::saturating_from_rational(400, 100))),
 				Change::NoChange,
-			));
+			);
 
 			assert_ok!(CdpEngine::liquidate_unsafe_cdp(
 				AccountId::from(ALICE),
@@ -241,15 +239,14 @@ fn test_honzon_module() {
 		.execute_with(|| {
 			set_oracle_price(vec![(RELAY_CHAIN_CURRENCY, Price::saturating_from_rational(1, 1))]);
 
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
 				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 				Change::NewValue(10_000 * dollar(USD_CURRENCY)),
-			));
+			);
 			assert_ok!(CdpEngine::adjust_position(
 				&AccountId::from(ALICE),
 				RELAY_CHAIN_CURRENCY,
@@ -281,15 +278,14 @@ fn test_honzon_module() {
 				.is_ok(),
 				false
 			);
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NoChange,
 				Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
 				Change::NoChange,
 				Change::NoChange,
 				Change::NoChange,
-			));
+			);
 			assert_ok!(CdpEngine::liquidate(
 				Origin::none(),
 				RELAY_CHAIN_CURRENCY,
@@ -325,15 +321,14 @@ fn test_cdp_engine_module() {
 		])
 		.build()
 		.execute_with(|| {
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NewValue(Some(Rate::saturating_from_rational(1, 100000))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
 				Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
 				Change::NewValue(10_000 * dollar(USD_CURRENCY)),
-			));
+			);
 
 			let new_collateral_params = CdpEngine::collateral_params(RELAY_CHAIN_CURRENCY);
 
@@ -460,15 +455,14 @@ fn cdp_treasury_handles_honzon_surplus_correctly() {
 		.execute_with(|| {
 			System::set_block_number(1);
 			set_oracle_price(vec![(RELAY_CHAIN_CURRENCY, Price::saturating_from_rational(100, 1))]);
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NewValue(Some(Rate::saturating_from_rational(1, 10000))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(Some(Rate::saturating_from_rational(20, 100))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(1_000_000 * dollar(USD_CURRENCY)),
-			));
+			);
 			assert_ok!(Dex::add_liquidity(
 				Origin::signed(AccountId::from(BOB)),
 				RELAY_CHAIN_CURRENCY,
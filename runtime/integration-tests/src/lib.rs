@@ -43,6 +43,13 @@ mod dex;
 ))]
 mod evm;
 
+#[cfg(any(
+	feature = "with-mandala-runtime",
+	feature = "with-karura-runtime",
+	feature = "with-acala-runtime"
+))]
+mod full_state;
+
 #[cfg(any(
 	feature = "with-mandala-runtime",
 	feature = "with-karura-runtime",
@@ -113,15 +113,14 @@ fn proxy_permissions_correct() {
 			// runtimes have different minimum debit dust requirements
 			let min_debit: Balance = 100 * MinimumDebitValue::get();
 			set_oracle_price(vec![(RELAY_CHAIN_CURRENCY, Price::saturating_from_rational(100, 1))]);
-			assert_ok!(CdpEngine::set_collateral_params(
-				Origin::root(),
+			set_collateral_params(
 				RELAY_CHAIN_CURRENCY,
 				Change::NewValue(Some(Rate::saturating_from_rational(1, 10000))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(Some(Rate::saturating_from_rational(20, 100))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(200, 100))),
 				Change::NewValue(1_000_000 * dollar(USD_CURRENCY)),
-			));
+			);
 			assert_ok!(Dex::add_liquidity(
 				Origin::signed(AccountId::from(BOB)),
 				RELAY_CHAIN_CURRENCY,
@@ -310,6 +310,35 @@ impl ExtBuilder {
 	}
 }
 
+/// Set the risk management params of the collateral type, bypassing the
+/// timelock of the governance updates.
+pub fn set_collateral_params(
+	currency_id: CurrencyId,
+	interest_rate_per_sec: Change<Option<Rate>>,
+	liquidation_ratio: Change<Option<Ratio>>,
+	liquidation_penalty: Change<Option<Rate>>,
+	required_collateral_ratio: Change<Option<Ratio>>,
+	maximum_total_debit_value: Change<Balance>,
+) {
+	module_cdp_engine::CollateralParams::<Runtime>::mutate(currency_id, |params| {
+		if let Change::NewValue(update) = interest_rate_per_sec {
+			params.interest_rate_per_sec = update;
+		}
+		if let Change::NewValue(update) = liquidation_ratio {
+			params.liquidation_ratio = update;
+		}
+		if let Change::NewValue(update) = liquidation_penalty {
+			params.liquidation_penalty = update;
+		}
+		if let Change::NewValue(update) = required_collateral_ratio {
+			params.required_collateral_ratio = update;
+		}
+		if let Change::NewValue(val) = maximum_total_debit_value {
+			params.maximum_total_debit_value = val;
+		}
+	});
+}
+
 pub fn set_oracle_price(prices: Vec<(CurrencyId, Price)>) {
 	AcalaOracle::on_finalize(0);
 	assert_ok!(AcalaOracle::feed_values(
@@ -54,10 +54,12 @@ use sp_version::RuntimeVersion;
 use frame_system::{EnsureRoot, RawOrigin};
 use module_asset_registry::{EvmErc20InfoMapping, XcmForeignAssetIdMapping};
 use module_currencies::BasicCurrencyAdapter;
-use module_evm::{CallInfo, CreateInfo, EvmTask, Runner};
+use module_evm::{BlockLog, CallInfo, CreateInfo, EvmTask, LogFilter, Runner};
 use module_evm_accounts::EvmAddressMapping;
 use module_relaychain::RelayChainCallBuilder;
-use module_support::{DispatchableTask, ForeignAssetIdMapping};
+use module_support::{
+	AggregatedSwapAdapter, DispatchableTask, DustHook, ForeignAssetIdMapping, HookRegistry, OrderedHook,
+};
 use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
 use orml_traits::{
@@ -90,7 +92,7 @@ pub use frame_support::{
 		InstanceFilter, IsSubType, IsType, KeyOwnerProofSystem, LockIdentifier, Nothing, OnUnbalanced, Randomness,
 		SortedMembers, U128CurrencyToVote,
 	},
-	weights::{constants::RocksDbWeight, IdentityFee, Weight},
+	weights::{constants::RocksDbWeight, DispatchClass, GetDispatchInfo, IdentityFee, Weight},
 	PalletId, RuntimeDebug, StorageValue,
 };
 
@@ -104,16 +106,18 @@ pub use constants::{fee::*, parachains, time::*};
 pub use primitives::{
 	define_combined_task, evm::EstimateResourcesRequest, task::TaskResult, AccountId, AccountIndex, Address, Amount,
 	AuctionId, AuthoritysOriginId, Balance, BlockNumber, CurrencyId, DataProviderId, EraIndex, Hash, Moment, Nonce,
-	ReserveIdentifier, Share, Signature, TokenSymbol, TradingPair,
+	ReserveIdentifier, RevenueSource, Share, Signature, TokenSymbol, TradingPair,
 };
 pub use runtime_common::{
 	cent, dollar, microcent, millicent, EnsureRootOrAllGeneralCouncil, EnsureRootOrAllTechnicalCommittee,
-	EnsureRootOrHalfFinancialCouncil, EnsureRootOrHalfGeneralCouncil, EnsureRootOrHalfHomaCouncil,
-	EnsureRootOrOneGeneralCouncil, EnsureRootOrOneThirdsTechnicalCommittee, EnsureRootOrThreeFourthsGeneralCouncil,
-	EnsureRootOrTwoThirdsGeneralCouncil, EnsureRootOrTwoThirdsTechnicalCommittee, ExchangeRate,
-	FinancialCouncilInstance, FinancialCouncilMembershipInstance, GasToWeight, GeneralCouncilInstance,
-	GeneralCouncilMembershipInstance, HomaCouncilInstance, HomaCouncilMembershipInstance,
-	OperatorMembershipInstanceAcala, Price, ProxyType, Rate, Ratio, RelayChainBlockNumberProvider,
+	EnsureRootOrHalfFinancialCouncil, EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil,
+	EnsureRootOrHalfGeneralCouncil, EnsureRootOrHalfHomaCouncil, EnsureRootOrOneGeneralCouncil,
+	EnsureRootOrOneThirdsTechnicalCommittee, EnsureRootOrThreeFourthsGeneralCouncil,
+	EnsureRootOrThreeFourthsGeneralCouncilOrHalfParameterCouncil, EnsureRootOrTwoThirdsGeneralCouncil,
+	EnsureRootOrTwoThirdsTechnicalCommittee, ExchangeRate, FinancialCouncilInstance,
+	FinancialCouncilMembershipInstance, GasToWeight, GeneralCouncilInstance, GeneralCouncilMembershipInstance,
+	HomaCouncilInstance, HomaCouncilMembershipInstance, OperatorMembershipInstanceAcala, ParameterCouncilInstance,
+	ParameterCouncilMembershipInstance, Price, ProxyType, Rate, Ratio, RelayChainBlockNumberProvider,
 	RelayChainSubAccountId, RuntimeBlockLength, RuntimeBlockWeights, SystemContractsFilter, TechnicalCommitteeInstance,
 	TechnicalCommitteeMembershipInstance, TimeStampedPrice, BNC, KAR, KSM, KUSD, LKSM, RENBTC, VSKSM,
 };
@@ -163,6 +167,7 @@ parameter_types! {
 	// Treasury reserve
 	pub const TreasuryReservePalletId: PalletId = PalletId(*b"aca/reve");
 	pub const NftPalletId: PalletId = PalletId(*b"aca/aNFT");
+	pub const SavingsPalletId: PalletId = PalletId(*b"aca/savg");
 	// Vault all unrleased native token.
 	pub UnreleasedNativeVaultAccountId: AccountId = PalletId(*b"aca/urls").into_account();
 }
@@ -178,6 +183,7 @@ pub fn get_all_module_accounts() -> Vec<AccountId> {
 		IncentivesPalletId::get().into_account(),
 		TreasuryPalletId::get().into_account(),
 		TreasuryReservePalletId::get().into_account(),
+		SavingsPalletId::get().into_account(),
 		ZeroAccountId::get(),
 		UnreleasedNativeVaultAccountId::get(),
 	]
@@ -213,6 +219,13 @@ impl Contains<Call> for BaseCallFilter {
 			return false;
 		}
 
+		if let Call::ParameterCouncil(pallet_collective::Call::propose { proposal, .. })
+		| Call::ParameterCouncil(pallet_collective::Call::execute { proposal, .. }) = call
+		{
+			// the parameter council can only propose the whitelisted parameter updates
+			return ParameterCouncilCallFilter::contains(proposal);
+		}
+
 		if let Call::PolkadotXcm(xcm_method) = call {
 			match xcm_method {
 				pallet_xcm::Call::send { .. }
@@ -239,6 +252,33 @@ impl Contains<Call> for BaseCallFilter {
 	}
 }
 
+/// The calls the parameter council is allowed to dispatch: the risk params of
+/// cdp-engine, the auction params of cdp-treasury and the emission of
+/// incentives.
+pub struct ParameterCouncilCallFilter;
+impl Contains<Call> for ParameterCouncilCallFilter {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::CdpEngine(module_cdp_engine::Call::set_global_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_collateral_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::cancel_pending_risk_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_annual_interest_rate { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_interest_rate_curve { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_soft_debit_cap { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_minimum_debit_value { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_liquidation_grace { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_max_debit_per_account { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_size { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_value { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_collateral_sale_params { .. })
+				| Call::Incentives(module_incentives::Call::update_incentive_rewards { .. })
+				| Call::Incentives(module_incentives::Call::update_dex_saving_rewards { .. })
+				| Call::Incentives(module_incentives::Call::update_claim_reward_deduction_rates { .. })
+		)
+	}
+}
+
 impl frame_system::Config for Runtime {
 	type AccountId = AccountId;
 	type Call = Call;
@@ -313,6 +353,9 @@ parameter_types! {
 	pub const MaxInvulnerables: u32 = 10;
 	pub const KickPenaltySessionLength: u32 = 8;
 	pub const CollatorKickThreshold: Permill = Permill::from_percent(85);
+	pub CollatorLPBondCurrencyId: CurrencyId = TradingPair::from_currency_ids(KUSD, KAR).unwrap().dex_share_currency_id();
+	pub const CollatorLPBondHaircut: Permill = Permill::from_percent(20);
+	pub const CollatorMaxLPBondProportion: Permill = Permill::from_percent(50);
 }
 
 impl module_collator_selection::Config for Runtime {
@@ -326,6 +369,13 @@ impl module_collator_selection::Config for Runtime {
 	type MaxInvulnerables = MaxInvulnerables;
 	type KickPenaltySessionLength = KickPenaltySessionLength;
 	type CollatorKickThreshold = CollatorKickThreshold;
+	type MultiCurrency = Currencies;
+	type LPBondCurrencyId = CollatorLPBondCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type LPBondHaircut = CollatorLPBondHaircut;
+	type MaxLPBondProportion = CollatorMaxLPBondProportion;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type DEX = Dex;
 	type WeightInfo = weights::module_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -373,6 +423,7 @@ parameter_types! {
 	/// that combined with `AdjustmentVariable`, we can recover from the minimum.
 	/// See `multiplier_can_grow_from_zero`.
 	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000u128);
+	pub const MaxFeeFreeTxPerBlock: u32 = 20;
 }
 
 pub type SlowAdjustingFeeUpdate<R> =
@@ -503,6 +554,36 @@ impl pallet_membership::Config<TechnicalCommitteeMembershipInstance> for Runtime
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const ParameterCouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const ParameterCouncilMaxProposals: u32 = 20;
+	pub const ParameterCouncilMaxMembers: u32 = 30;
+}
+
+impl pallet_collective::Config<ParameterCouncilInstance> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = ParameterCouncilMotionDuration;
+	type MaxProposals = ParameterCouncilMaxProposals;
+	type MaxMembers = ParameterCouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+impl pallet_membership::Config<ParameterCouncilMembershipInstance> for Runtime {
+	type Event = Event;
+	type AddOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type RemoveOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type SwapOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type ResetOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type PrimeOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type MembershipInitialized = ParameterCouncil;
+	type MembershipChanged = ParameterCouncil;
+	type MaxMembers = ParameterCouncilMaxMembers;
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const OracleMaxMembers: u32 = 50;
 }
@@ -715,7 +796,7 @@ parameter_types! {
 type AcalaDataProvider = orml_oracle::Instance1;
 impl orml_oracle::Config<AcalaDataProvider> for Runtime {
 	type Event = Event;
-	type OnNewData = ();
+	type OnNewData = Prices;
 	type CombineData = orml_oracle::DefaultCombineData<Runtime, MinimumCount, ExpiresIn, AcalaDataProvider>;
 	type Time = Timestamp;
 	type OracleKey = CurrencyId;
@@ -794,6 +875,20 @@ parameter_types! {
 	pub KaruraTreasuryAccount: AccountId = TreasuryPalletId::get().into_account();
 }
 
+parameter_types! {
+	pub const TransferDustOrder: u32 = 0;
+	pub TransferDustWeight: Weight = RocksDbWeight::get().reads_writes(2, 2);
+	pub DustHookWeightBudget: Weight = RuntimeBlockWeights::get().max_block / 100;
+}
+
+pub type OnTokensDustHooks = HookRegistry<(
+	OrderedHook<
+		DustHook<orml_tokens::TransferDust<Runtime, KaruraTreasuryAccount>, TransferDustWeight>,
+		TransferDustOrder,
+		DustHookWeightBudget,
+	>,
+)>;
+
 impl orml_tokens::Config for Runtime {
 	type Event = Event;
 	type Balance = Balance;
@@ -801,13 +896,15 @@ impl orml_tokens::Config for Runtime {
 	type CurrencyId = CurrencyId;
 	type WeightInfo = weights::orml_tokens::WeightInfo<Runtime>;
 	type ExistentialDeposits = ExistentialDeposits;
-	type OnDust = orml_tokens::TransferDust<Runtime, KaruraTreasuryAccount>;
+	type OnDust = OnTokensDustHooks;
 	type MaxLocks = MaxLocks;
 	type DustRemovalWhitelist = DustRemovalWhitelist;
 }
 
 parameter_types! {
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_rational(1, 1);
+	pub const PricesTwapWindow: BlockNumber = 10 * MINUTES;
+	pub const PriceDeviationReportInterval: BlockNumber = HOURS;
 }
 
 impl module_prices::Config for Runtime {
@@ -822,6 +919,10 @@ impl module_prices::Config for Runtime {
 	type DEX = Dex;
 	type Currency = Currencies;
 	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
+	type DEXPriceCumulative = Dex;
+	type TwapWindow = PricesTwapWindow;
+	type DeviationReportInterval = PriceDeviationReportInterval;
+	type UnsignedPriority = runtime_common::PricesUnsignedPriority;
 	type WeightInfo = weights::module_prices::WeightInfo<Runtime>;
 }
 
@@ -842,6 +943,7 @@ impl module_currencies::Config for Runtime {
 	type EVMBridge = EVMBridge;
 	type SweepOrigin = EnsureRootOrOneGeneralCouncil;
 	type OnDust = module_currencies::TransferDust<Runtime, KaruraTreasuryAccount>;
+	type OnTransfer = ();
 }
 
 parameter_types! {
@@ -912,12 +1014,17 @@ parameter_types! {
 	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(2, 100);
 	pub const AuctionTimeToClose: BlockNumber = 15 * MINUTES;
 	pub const AuctionDurationSoftCap: BlockNumber = 2 * HOURS;
+	pub CleanupBounty: Balance = cent(KAR);
+	pub AuctionManagerMinimumSettlementWeight: Weight = RuntimeBlockWeights::get().max_block / 100;
 	pub DefaultSwapParitalPathList: Vec<Vec<CurrencyId>> = vec![
 		vec![KUSD],
 		vec![KSM, KUSD],
 	];
 }
 
+pub type TreasuryCleanupBounty =
+	module_support::TreasuryCleanupBounty<Currencies, GetNativeCurrencyId, KaruraTreasuryAccount, CleanupBounty>;
+
 impl module_auction_manager::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
@@ -931,7 +1038,11 @@ impl module_auction_manager::Config for Runtime {
 	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
 	type UnsignedPriority = runtime_common::AuctionManagerUnsignedPriority;
 	type EmergencyShutdown = EmergencyShutdown;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type CleanupBounty = TreasuryCleanupBounty;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumSettlementWeight = AuctionManagerMinimumSettlementWeight;
 	type WeightInfo = weights::module_auction_manager::WeightInfo<Runtime>;
 }
 
@@ -943,6 +1054,8 @@ impl module_loans::Config for Runtime {
 	type CDPTreasury = CdpTreasury;
 	type PalletId = LoansPalletId;
 	type OnUpdateLoan = module_incentives::OnUpdateLoan<Runtime>;
+	type OnUpdateDebit = module_cdp_engine::RecordDebitSnapshot<Runtime>;
+	type OnPositionUpdated = module_cdp_engine::OnPositionUpdated<Runtime>;
 }
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
@@ -1006,11 +1119,20 @@ where
 
 parameter_types! {
 	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![KSM, LKSM];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![];
 	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(150, 100);
 	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 10);
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(8, 100);
 	pub MinimumDebitValue: Balance = 20 * dollar(KUSD);
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(15, 100);
+	pub const DebitExchangeRateCheckpointInterval: BlockNumber = DAYS;
+	pub const MaxDebitExchangeRateCheckpoints: u32 = 366;
+	pub const InterestRateCurvePeriod: BlockNumber = DAYS;
+	pub StopLossExecutionFee: Balance = dollar(KUSD);
+	pub const RiskParamsUpdateDelay: BlockNumber = 2 * DAYS;
+	pub LiquidationKeeperBounty: Balance = 10 * dollar(KUSD);
+	pub LiquidationKeeperBountyShare: Ratio = Ratio::saturating_from_rational(5, 1000);
+	pub MaxLiquidationBountyPerBlock: Balance = 1000 * dollar(KUSD);
 }
 
 impl module_cdp_engine::Config for Runtime {
@@ -1023,26 +1145,52 @@ impl module_cdp_engine::Config for Runtime {
 	type MinimumDebitValue = MinimumDebitValue;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CdpTreasury;
-	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil;
+	type EmergencyOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type UnsignedPriority = runtime_common::CdpEngineUnsignedPriority;
+	type UnsignedLongevity = runtime_common::CdpEngineUnsignedLongevity;
 	type EmergencyShutdown = EmergencyShutdown;
 	type UnixTime = Timestamp;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type DEX = Dex;
+	type DEXPriceCumulative = Dex;
+	type DebitExchangeRateCheckpointInterval = DebitExchangeRateCheckpointInterval;
+	type MaxDebitExchangeRateCheckpoints = MaxDebitExchangeRateCheckpoints;
+	type InterestRateCurvePeriod = InterestRateCurvePeriod;
+	type OnLiquidation = ();
+	type StopLossExecutionFee = StopLossExecutionFee;
+	type CircuitBreakerRecorder = ();
+	type RiskParamsUpdateDelay = RiskParamsUpdateDelay;
+	type LiquidationKeeperBounty = LiquidationKeeperBounty;
+	type LiquidationKeeperBountyShare = LiquidationKeeperBountyShare;
+	type MaxLiquidationBountyPerBlock = MaxLiquidationBountyPerBlock;
 	type WeightInfo = weights::module_cdp_engine::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub DepositPerAuthorization: Balance = deposit(1, 64);
+	pub const MaxMergeLoans: u32 = 10;
 }
 
 impl module_honzon::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type DepositPerAuthorization = DepositPerAuthorization;
+	type MaxMergeLoans = MaxMergeLoans;
 	type WeightInfo = weights::module_honzon::WeightInfo<Runtime>;
 }
 
+impl module_savings::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = SavingsPalletId;
+	type WeightInfo = weights::module_savings::WeightInfo<Runtime>;
+}
+
 impl module_emergency_shutdown::Config for Runtime {
 	type Event = Event;
 	type CollateralCurrencyIds = CollateralCurrencyIds;
@@ -1050,12 +1198,15 @@ impl module_emergency_shutdown::Config for Runtime {
 	type CDPTreasury = CdpTreasury;
 	type AuctionManagerHandler = AuctionManager;
 	type ShutdownOrigin = EnsureRoot<AccountId>;
+	type StableCurrencyReserve = ();
 	type WeightInfo = weights::module_emergency_shutdown::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (3, 1000);	// 0.3%
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 100;
+	pub CdpTreasuryAccount: AccountId = CDPTreasuryPalletId::get().into_account();
 }
 
 impl module_dex::Config for Runtime {
@@ -1063,15 +1214,23 @@ impl module_dex::Config for Runtime {
 	type Currency = Currencies;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
 	type DEXIncentives = Incentives;
 	type WeightInfo = weights::module_dex::WeightInfo<Runtime>;
 	type ListingOrigin = EnsureRootOrHalfGeneralCouncil;
+	type StableAsset = ();
+	type ProtocolFeeReceiver = CdpTreasuryAccount;
+	type OnProtocolFee = CdpTreasury;
 }
 
 parameter_types! {
 	pub const MaxAuctionsCount: u32 = 50;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 7 * DAYS;
+	pub const OffsetPeriod: BlockNumber = MINUTES;
+	pub CdpTreasuryMinimumIdleWorkWeight: Weight = RuntimeBlockWeights::get().max_block / 100;
 	pub HonzonTreasuryAccount: AccountId = HonzonTreasuryPalletId::get().into_account();
 }
 
@@ -1079,18 +1238,81 @@ impl module_cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = AuctionManager;
-	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil;
 	type DEX = Dex;
+	type Swap = AggregatedSwapAdapter<Dex, ()>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = HonzonTreasuryAccount;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = module_cdp_engine::PauseDebitIssuance<Runtime>;
+	type CircuitBreakerRecorder = ();
+	type OnRevenue = ();
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumIdleWorkWeight = CdpTreasuryMinimumIdleWorkWeight;
+	type EmergencyShutdown = EmergencyShutdown;
 	type WeightInfo = weights::module_cdp_treasury::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxEmergencyPauseDuration: BlockNumber = DAYS;
+	pub UnpausablePallets: Vec<Vec<u8>> = vec![
+		b"System".to_vec(),
+		b"Timestamp".to_vec(),
+		b"ParachainSystem".to_vec(),
+		b"Sudo".to_vec(),
+		b"Scheduler".to_vec(),
+		b"Authority".to_vec(),
+		b"GeneralCouncil".to_vec(),
+		b"GeneralCouncilMembership".to_vec(),
+		b"FinancialCouncil".to_vec(),
+		b"FinancialCouncilMembership".to_vec(),
+		b"HomaCouncil".to_vec(),
+		b"HomaCouncilMembership".to_vec(),
+		b"TechnicalCommittee".to_vec(),
+		b"TechnicalCommitteeMembership".to_vec(),
+		b"ParameterCouncil".to_vec(),
+		b"ParameterCouncilMembership".to_vec(),
+		b"Democracy".to_vec(),
+	];
+	pub const EmergencyPauseCooldown: BlockNumber = DAYS;
+}
+
+pub struct EmergencyPauseExemptCalls;
+impl Contains<Call> for EmergencyPauseExemptCalls {
+	fn contains(call: &Call) -> bool {
+		// the governance calls are operational
+		call.get_dispatch_info().class == DispatchClass::Operational
+			|| matches!(
+				call,
+				Call::CdpEngine(module_cdp_engine::Call::liquidate { .. })
+					| Call::CdpEngine(module_cdp_engine::Call::settle { .. })
+					| Call::AuctionManager(module_auction_manager::Call::cancel { .. })
+			)
+	}
+}
+
 impl module_transaction_pause::Config for Runtime {
 	type Event = Event;
 	type UpdateOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type EmergencyOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
+	type MaxEmergencyPauseDuration = MaxEmergencyPauseDuration;
+	type UnpausablePallets = UnpausablePallets;
+	type EmergencyPauseCooldown = EmergencyPauseCooldown;
+	type EmergencyPauseExemptCalls = EmergencyPauseExemptCalls;
+	type CircuitBreakerRecorder = ();
 	type WeightInfo = weights::module_transaction_pause::WeightInfo<Runtime>;
 }
 
@@ -1131,6 +1353,8 @@ impl module_transaction_payment::Config for Runtime {
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type TradingPathLimit = TradingPathLimit;
 	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MaxFeeFreeTxPerBlock = MaxFeeFreeTxPerBlock;
+	type UpdateOrigin = EnsureRootOrHalfGeneralCouncil;
 	type WeightInfo = weights::module_transaction_payment::WeightInfo<Runtime>;
 }
 
@@ -1160,6 +1384,7 @@ impl orml_rewards::Config for Runtime {
 
 parameter_types! {
 	pub const AccumulatePeriod: BlockNumber = MINUTES;
+	pub const MaxAccumulatePoolsPerBlock: u32 = 100;
 }
 
 impl module_incentives::Config for Runtime {
@@ -1167,12 +1392,14 @@ impl module_incentives::Config for Runtime {
 	type RewardsSource = UnreleasedNativeVaultAccountId;
 	type StableCurrencyId = GetStableCurrencyId;
 	type AccumulatePeriod = AccumulatePeriod;
-	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncil;
+	type MaxAccumulatePoolsPerBlock = MaxAccumulatePoolsPerBlock;
+	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncilOrHalfParameterCouncil;
 	type CDPTreasury = CdpTreasury;
 	type Currency = Currencies;
 	type DEX = Dex;
 	type EmergencyShutdown = EmergencyShutdown;
 	type PalletId = IncentivesPalletId;
+	type CleanupBounty = TreasuryCleanupBounty;
 	type WeightInfo = weights::module_incentives::WeightInfo<Runtime>;
 }
 
@@ -1234,6 +1461,7 @@ impl InstanceFilter<Call> for ProxyType {
 						| Call::Democracy(..) | Call::GeneralCouncil(..)
 						| Call::FinancialCouncil(..)
 						| Call::HomaCouncil(..) | Call::TechnicalCommittee(..)
+						| Call::ParameterCouncil(..)
 						| Call::Treasury(..) | Call::Bounties(..)
 						| Call::Tips(..)
 				)
@@ -1319,8 +1547,28 @@ pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<
 	OriginCaller,
 	Runtime,
 >;
-pub type DexPrecompile =
-	runtime_common::DexPrecompile<AccountId, EvmAddressMapping<Runtime>, EvmErc20InfoMapping<Runtime>, Dex>;
+pub type DexPrecompile = runtime_common::DexPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	Dex,
+	weights::module_dex::WeightInfo<Runtime>,
+>;
+pub type HomaPrecompile = runtime_common::HomaPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	HomaLite,
+	weights::module_homa_lite::WeightInfo<Runtime>,
+>;
+pub type FlashLoanPrecompile = runtime_common::FlashLoanPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	CdpTreasury,
+	EVM,
+	weights::module_cdp_treasury::WeightInfo<Runtime>,
+>;
 
 impl module_evm::Config for Runtime {
 	type AddressMapping = EvmAddressMapping<Runtime>;
@@ -1331,12 +1579,15 @@ impl module_evm::Config for Runtime {
 	type Event = Event;
 	type Precompiles = runtime_common::AllPrecompiles<
 		SystemContractsFilter,
+		TransactionPause,
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		HomaPrecompile,
+		FlashLoanPrecompile,
 	>;
 	type ChainId = ChainId;
 	type GasToWeight = GasToWeight;
@@ -1610,6 +1861,9 @@ parameter_types! {
 	// Calculated from polkadot/xcm/xcm-builder: fn buy_weight
 	// We must charge higher than what Kusama required (533_333_300, obtained from integration test)
 	pub XcmUnbondFee: Balance = 60 * millicent(KSM);
+	pub const XcmRetryBaseDelay: BlockNumber = 10; // 1 minute in relaychain blocks
+	pub const MaxXcmRetryAttempts: u32 = 5;
+	pub const XcmResponseTimeout: BlockNumber = 10 * MINUTES;
 }
 impl module_homa_lite::Config for Runtime {
 	type Event = Event;
@@ -1635,6 +1889,9 @@ impl module_homa_lite::Config for Runtime {
 	type RelayChainUnbondingSlashingSpans = RelayChainUnbondingSlashingSpans;
 	type MaxScheduledUnbonds = MaxScheduledUnbonds;
 	type StakingUpdateFrequency = OneDay;
+	type XcmRetryBaseDelay = XcmRetryBaseDelay;
+	type MaxXcmRetryAttempts = MaxXcmRetryAttempts;
+	type XcmResponseTimeout = XcmResponseTimeout;
 }
 
 pub type LocalAssetTransactor = MultiCurrencyAdapter<
@@ -1858,6 +2115,8 @@ construct_runtime!(
 		TechnicalCommittee: pallet_collective::<Instance4>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 67,
 		TechnicalCommitteeMembership: pallet_membership::<Instance4>::{Pallet, Call, Storage, Event<T>, Config<T>} = 68,
 		Democracy: pallet_democracy::{Pallet, Call, Storage, Config<T>, Event<T>} = 69,
+		ParameterCouncil: pallet_collective::<Instance5>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 72,
+		ParameterCouncilMembership: pallet_membership::<Instance6>::{Pallet, Call, Storage, Event<T>, Config<T>} = 73,
 
 		// Oracle
 		//
@@ -1871,7 +2130,7 @@ construct_runtime!(
 		OrmlNFT: orml_nft::{Pallet, Storage, Config<T>} = 82,
 
 		// Karura Core
-		Prices: module_prices::{Pallet, Storage, Call, Event<T>} = 90,
+		Prices: module_prices::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 90,
 		Dex: module_dex::{Pallet, Storage, Call, Event<T>, Config<T>} = 91,
 
 		// Honzon
@@ -1881,6 +2140,7 @@ construct_runtime!(
 		CdpTreasury: module_cdp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 103,
 		CdpEngine: module_cdp_engine::{Pallet, Storage, Call, Event<T>, Config, ValidateUnsigned} = 104,
 		EmergencyShutdown: module_emergency_shutdown::{Pallet, Storage, Call, Event<T>} = 105,
+		Savings: module_savings::{Pallet, Storage, Call, Event<T>} = 106,
 
 		// Homa
 		HomaLite: module_homa_lite::{Pallet, Call, Storage, Event<T>} = 115,
@@ -2050,6 +2310,56 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_common_rpc_runtime_api::ConfigApi<Block, Balance, BlockNumber> for Runtime {
+		fn config_constants() -> Vec<(Vec<u8>, runtime_common_rpc_runtime_api::ConfigValue<Balance, BlockNumber>)> {
+			runtime_common::config_constants::<Runtime>()
+		}
+	}
+
+	impl module_cdp_engine_rpc_runtime_api::CdpEngineApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn debit_exchange_rate_checkpoints(currency_id: CurrencyId) -> Vec<(BlockNumber, ExchangeRate)> {
+			CdpEngine::debit_exchange_rate_checkpoints(currency_id).into_inner()
+		}
+
+		fn accrued_interest(currency_id: CurrencyId, who: AccountId, from: BlockNumber, to: BlockNumber) -> Option<Balance> {
+			CdpEngine::accrued_interest(currency_id, &who, from, to)
+		}
+	}
+
+	impl module_cdp_treasury_rpc_runtime_api::CdpTreasuryApi<Block, Balance> for Runtime {
+		fn revenue_report(periods: u32) -> Vec<(u32, Vec<(RevenueSource, Balance)>)> {
+			CdpTreasury::revenue_report(periods)
+		}
+
+		fn surplus_by_source() -> Vec<(CurrencyId, Balance)> {
+			CdpTreasury::surplus_report()
+		}
+
+		fn treasury_health() -> module_cdp_treasury_rpc_runtime_api::TreasuryHealth<Balance> {
+			module_cdp_treasury_rpc_runtime_api::TreasuryHealth {
+				surplus_pool: CdpTreasury::surplus_pool(),
+				debit_pool: CdpTreasury::debit_pool(),
+				collaterals: CdpTreasury::collateral_report(CollateralCurrencyIds::get()),
+				reserve_ratio: CdpTreasury::reserve_ratio(),
+			}
+		}
+	}
+
+	impl module_honzon_rpc_runtime_api::HonzonApi<Block, AccountId, Balance> for Runtime {
+		fn position_health(currency_id: CurrencyId, who: AccountId) -> Option<module_honzon_rpc_runtime_api::PositionHealth<Balance>> {
+			Honzon::position_health(currency_id, &who).map(
+				|(collateral_ratio, required_collateral_ratio, max_additional_debit_value, liquidation_price)| {
+					module_honzon_rpc_runtime_api::PositionHealth {
+						collateral_ratio,
+						required_collateral_ratio,
+						max_additional_debit_value,
+						liquidation_price,
+					}
+				},
+			)
+		}
+	}
+
 	impl module_evm_rpc_runtime_api::EVMRuntimeRPCApi<Block, Balance> for Runtime {
 		fn call(
 			from: H160,
@@ -2142,6 +2452,10 @@ impl_runtime_apis! {
 
 			request.ok_or(sp_runtime::DispatchError::Other("Invalid parameter extrinsic, not evm Call"))
 		}
+
+		fn block_logs(filter: LogFilter) -> Vec<BlockLog> {
+			EVM::block_logs(&filter)
+		}
 	}
 
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
@@ -2200,6 +2514,7 @@ impl_runtime_apis! {
 			orml_list_benchmark!(list, extra, module_evm_accounts, benchmarking::evm_accounts);
 			orml_list_benchmark!(list, extra, module_currencies, benchmarking::currencies);
 			orml_list_benchmark!(list, extra, module_session_manager, benchmarking::session_manager);
+			orml_list_benchmark!(list, extra, module_savings, benchmarking::savings);
 			orml_list_benchmark!(list, extra, orml_tokens, benchmarking::tokens);
 			orml_list_benchmark!(list, extra, orml_vesting, benchmarking::vesting);
 			orml_list_benchmark!(list, extra, orml_auction, benchmarking::auction);
@@ -2259,6 +2574,7 @@ impl_runtime_apis! {
 			orml_add_benchmark!(params, batches, module_evm_accounts, benchmarking::evm_accounts);
 			orml_add_benchmark!(params, batches, module_currencies, benchmarking::currencies);
 			orml_add_benchmark!(params, batches, module_session_manager, benchmarking::session_manager);
+			orml_add_benchmark!(params, batches, module_savings, benchmarking::savings);
 			orml_add_benchmark!(params, batches, orml_tokens, benchmarking::tokens);
 			orml_add_benchmark!(params, batches, orml_vesting, benchmarking::vesting);
 			orml_add_benchmark!(params, batches, orml_auction, benchmarking::auction);
@@ -2367,6 +2683,50 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn parameter_council_call_filter_works() {
+		let whitelisted_call = Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_size {
+			currency_id: KSM,
+			size: 100,
+		});
+		let other_call = Call::CdpTreasury(module_cdp_treasury::Call::burn_surplus { amount: 100 });
+
+		assert!(BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::propose {
+				threshold: 1,
+				proposal: Box::new(whitelisted_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(whitelisted_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(!BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::propose {
+				threshold: 1,
+				proposal: Box::new(other_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(!BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(other_call.clone()),
+				length_bound: 100,
+			}
+		)));
+
+		// the other collectives are not limited
+		assert!(BaseCallFilter::contains(&Call::FinancialCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(other_call),
+				length_bound: 100,
+			}
+		)));
+	}
+
 	#[test]
 	fn check_call_size() {
 		assert!(
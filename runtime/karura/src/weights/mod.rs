@@ -34,6 +34,7 @@ pub mod module_honzon;
 pub mod module_incentives;
 pub mod module_nft;
 pub mod module_prices;
+pub mod module_savings;
 pub mod module_session_manager;
 pub mod module_transaction_pause;
 pub mod module_transaction_payment;
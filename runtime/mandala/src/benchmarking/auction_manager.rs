@@ -18,20 +18,22 @@
 
 use crate::{
 	dollar, AccountId, AuctionId, AuctionManager, CdpTreasury, Currencies, CurrencyId, EmergencyShutdown,
-	GetStableCurrencyId, GetStakingCurrencyId, Price, Runtime,
+	GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, Price, Ratio, Runtime, TreasuryPalletId,
 };
 
-use super::utils::feed_price;
-use frame_benchmarking::account;
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
 use frame_system::RawOrigin;
+use module_auction_manager::AuctionCreationPrices;
 use module_support::{AuctionManager as AuctionManagerTrait, CDPTreasury};
 use orml_benchmarking::runtime_benchmarks;
-use orml_traits::MultiCurrency;
-use sp_runtime::FixedPointNumber;
-use sp_std::vec;
+use orml_traits::{AuctionHandler, MultiCurrency};
+use sp_runtime::{traits::AccountIdConversion, FixedPointNumber};
+use sp_std::prelude::*;
 
 const SEED: u32 = 0;
 
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
 const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
 const STAKING: CurrencyId = GetStakingCurrencyId::get();
 
@@ -62,6 +64,54 @@ runtime_benchmarks! {
 		// shutdown
 		EmergencyShutdown::emergency_shutdown(RawOrigin::Root.into())?;
 	}: cancel(RawOrigin::None, auction_id)
+
+	// `settle` an ended collateral auction, worst case:
+	// auction have been already bid
+	settle_collateral_auction {
+		let bidder: AccountId = account("bidder", 0, SEED);
+		let funder: AccountId = account("funder", 0, SEED);
+
+		// set balance
+		Currencies::deposit(STABLECOIN, &bidder, 80 * dollar(STABLECOIN))?;
+		Currencies::deposit(STAKING, &funder, dollar(STAKING))?;
+		CdpTreasury::deposit_collateral(&funder, STAKING, dollar(STAKING))?;
+
+		// feed price
+		feed_price(vec![(STAKING, Price::saturating_from_integer(120))])?;
+
+		// create collateral auction
+		AuctionManager::new_collateral_auction(&funder, STAKING, dollar(STAKING), 100 * dollar(STABLECOIN))?;
+		let auction_id: AuctionId = Default::default();
+
+		// bid collateral auction
+		let _ = AuctionManager::collateral_auction_bid_handler(1, auction_id, (bidder.clone(), 80 * dollar(STABLECOIN)), None);
+
+		// end collateral auction
+		AuctionManager::on_auction_ended(auction_id, Some((bidder, 80 * dollar(STABLECOIN))));
+	}: {
+		AuctionManager::settle_ended_auctions(u64::MAX);
+	}
+
+	set_reserve_price_ratio {
+	}: _(RawOrigin::Root, STAKING, Some(Ratio::saturating_from_rational(80, 100)))
+
+	// `close_auctions` removes the records of `n` finished auctions and pays the
+	// cleanup bounty
+	close_auctions {
+		let n in 1 .. 100;
+
+		let caller: AccountId = whitelisted_caller();
+		let treasury: AccountId = TreasuryPalletId::get().into_account();
+		set_balance(NATIVE, &treasury, 1_000 * dollar(NATIVE));
+		let mut ids: Vec<AuctionId> = vec![];
+		for i in 0 .. n {
+			AuctionCreationPrices::<Runtime>::insert(i, Price::saturating_from_integer(120));
+			ids.push(i);
+		}
+	}: _(RawOrigin::Signed(caller), ids)
+	verify {
+		assert!(AuctionManager::auction_creation_prices(0).is_none());
+	}
 }
 
 #[cfg(test)]
@@ -19,8 +19,8 @@
 use crate::{
 	dollar, AccountId, Address, Amount, Balance, CdpEngine, CdpTreasury, CollateralCurrencyIds, CurrencyId,
 	DefaultDebitExchangeRate, DefaultSwapParitalPathList, Dex, EmergencyShutdown, ExistentialDeposits,
-	GetLiquidCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, MaxAuctionsCount, MinimumDebitValue, Price, Rate,
-	Ratio, Runtime, Timestamp, MILLISECS_PER_BLOCK,
+	GetLiquidCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, Loans, MaxAuctionsCount, MinimumDebitValue, Price,
+	Rate, Ratio, Runtime, System, Timestamp, MILLISECS_PER_BLOCK,
 };
 
 use super::utils::{feed_price, set_balance};
@@ -28,6 +28,9 @@ use core::convert::TryInto;
 use frame_benchmarking::account;
 use frame_support::traits::OnInitialize;
 use frame_system::RawOrigin;
+use module_cdp_engine::{
+	InterestRateCurve, LiquidationGrace, LiquidationGraces, OracleFailurePolicy, RiskManagementParams, SoftDebitCap,
+};
 use module_support::DEXManager;
 use orml_benchmarking::runtime_benchmarks;
 use orml_traits::{Change, GetByKey};
@@ -99,15 +102,14 @@ runtime_benchmarks! {
 			// set balance
 			set_balance(currency_id, &owner, collateral_amount + ExistentialDeposits::get(&currency_id));
 
-			CdpEngine::set_collateral_params(
-				RawOrigin::Root.into(),
+			super::utils::set_collateral_params(
 				currency_id,
 				Change::NoChange,
 				Change::NewValue(Some(Ratio::saturating_from_rational(0, 100))),
 				Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 				Change::NewValue(Some(Ratio::saturating_from_rational(0, 100))),
 				Change::NewValue(min_debit_value * 100),
-			)?;
+			);
 
 			// adjust position
 			CdpEngine::adjust_position(&owner, currency_id, collateral_amount.try_into().unwrap(), min_debit_amount)?;
@@ -155,15 +157,14 @@ runtime_benchmarks! {
 		feed_price(vec![(STAKING, collateral_price)])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		super::utils::set_collateral_params(
 			STAKING,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(min_debit_value * 100),
-		)?;
+		);
 
 		let auction_size = collateral_amount / b as u128;
 		// adjust auction size so we hit MaxAuctionCount
@@ -172,15 +173,14 @@ runtime_benchmarks! {
 		CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
 
 		// modify liquidation rate to make the cdp unsafe
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		super::utils::set_collateral_params(
 			STAKING,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
 			Change::NoChange,
 			Change::NoChange,
 			Change::NoChange,
-		)?;
+		);
 	}: liquidate(RawOrigin::None, STAKING, owner_lookup)
 
 	// `liquidate` by dex
@@ -211,29 +211,27 @@ runtime_benchmarks! {
 		feed_price(vec![(STAKING, collateral_price)])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		super::utils::set_collateral_params(
 			LIQUID,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(debit_value * 100),
-		)?;
+		);
 
 		// adjust position
 		CdpEngine::adjust_position(&owner, LIQUID, (10 * collateral_amount).try_into().unwrap(), debit_amount)?;
 
 		// modify liquidation rate to make the cdp unsafe
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		super::utils::set_collateral_params(
 			LIQUID,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
 			Change::NoChange,
 			Change::NoChange,
 			Change::NoChange,
-		)?;
+		);
 	}: liquidate(RawOrigin::None, LIQUID, owner_lookup)
 	verify {
 		let (_, stable_amount) = Dex::get_liquidity_pool(STAKING, STABLECOIN);
@@ -246,6 +244,55 @@ runtime_benchmarks! {
 		assert!(stable_amount_mandala < 10_000 * dollar(STABLECOIN));
 	}
 
+	// `liquidate` records the deadline of the grace window
+	liquidate_in_grace_period {
+		let owner: AccountId = account("owner", 0, SEED);
+		let owner_lookup = AccountIdLookup::unlookup(owner.clone());
+		let min_debit_value = MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(STAKING);
+		let collateral_price = Price::one();		// 1 USD
+		let min_debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: Amount = min_debit_amount.unique_saturated_into();
+		let collateral_value = 2 * min_debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(STAKING, &owner, collateral_amount + ExistentialDeposits::get(&STAKING));
+
+		// feed price
+		feed_price(vec![(STAKING, collateral_price)])?;
+
+		// set risk params
+		super::utils::set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100),
+		);
+		LiquidationGraces::<Runtime>::insert(STAKING, LiquidationGrace {
+			grace_period: 10,
+			hard_liquidation_ratio: Ratio::saturating_from_rational(100, 100),
+		});
+
+		// adjust position
+		CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
+
+		// modify liquidation rate to make the cdp unsafe but above the hard liquidation ratio
+		super::utils::set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		);
+	}: liquidate(RawOrigin::None, STAKING, owner_lookup)
+	verify {
+		assert!(CdpEngine::unsafe_cdp_deadlines(STAKING, &owner).is_some());
+	}
+
 	settle {
 		let owner: AccountId = account("owner", 0, SEED);
 		let owner_lookup = AccountIdLookup::unlookup(owner.clone());
@@ -264,15 +311,14 @@ runtime_benchmarks! {
 		feed_price(vec![(STAKING, collateral_price)])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		super::utils::set_collateral_params(
 			STAKING,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(min_debit_value * 100),
-		)?;
+		);
 
 		// adjust position
 		CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
@@ -280,6 +326,239 @@ runtime_benchmarks! {
 		// shutdown
 		EmergencyShutdown::emergency_shutdown(RawOrigin::Root.into())?;
 	}: _(RawOrigin::None, STAKING, owner_lookup)
+
+	// `settle` the CDP which has debit but no collateral left
+	settle_without_collateral {
+		let owner: AccountId = account("owner", 0, SEED);
+		let owner_lookup = AccountIdLookup::unlookup(owner.clone());
+		let min_debit_value = MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(STAKING);
+		let collateral_price = Price::one();		// 1 USD
+		let min_debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: Amount = min_debit_amount.unique_saturated_into();
+		let collateral_value = 2 * min_debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+		let collateral_adjustment: Amount = collateral_amount.try_into().unwrap();
+
+		// set balance
+		set_balance(STAKING, &owner, collateral_amount + ExistentialDeposits::get(&STAKING));
+
+		// feed price
+		feed_price(vec![(STAKING, collateral_price)])?;
+
+		// set risk params
+		super::utils::set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100),
+		);
+
+		// adjust position
+		CdpEngine::adjust_position(&owner, STAKING, collateral_adjustment, min_debit_amount)?;
+
+		// drop the collateral record of the position, only the debit is left to settle
+		Loans::update_loan(&owner, STAKING, -collateral_adjustment, 0)?;
+
+		// shutdown
+		EmergencyShutdown::emergency_shutdown(RawOrigin::Root.into())?;
+	}: settle(RawOrigin::None, STAKING, owner_lookup)
+	verify {
+		assert_eq!(Loans::positions(STAKING, &owner).debit, 0);
+	}
+
+	set_collateral_params_expiry {
+		let expire_at = System::block_number() + 100;
+	}: _(RawOrigin::Root, STAKING, Some((expire_at, RiskManagementParams::default())))
+
+	set_debit_issuance_paused {
+	}: _(RawOrigin::Root, true)
+
+	set_collateral_mint_paused {
+	}: _(RawOrigin::Root, STAKING, true)
+
+	set_max_debit_per_account {
+	}: _(RawOrigin::Root, STAKING, Some(10_000 * dollar(STABLECOIN)))
+
+	set_max_system_leverage {
+	}: _(RawOrigin::Root, Some(Ratio::saturating_from_rational(10, 1)))
+
+	// the setters of the risk params merge the changes into the pending ones
+	set_annual_interest_rate {
+		CdpEngine::set_liquidation_grace(RawOrigin::Root.into(), STAKING, None)?;
+	}: _(RawOrigin::Root, STAKING, Some(Rate::saturating_from_rational(5, 100)))
+
+	set_interest_rate_curve {
+		CdpEngine::set_liquidation_grace(RawOrigin::Root.into(), STAKING, None)?;
+	}: _(RawOrigin::Root, STAKING, Some(InterestRateCurve {
+		base_rate: Rate::saturating_from_rational(1, 1000000),
+		slope: Rate::saturating_from_rational(1, 100000),
+	}))
+
+	set_soft_debit_cap {
+		CdpEngine::set_collateral_params(
+			RawOrigin::Root.into(),
+			STAKING,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(100_000 * dollar(STABLECOIN)),
+		)?;
+	}: _(RawOrigin::Root, STAKING, Some(SoftDebitCap {
+		soft_cap: 10_000 * dollar(STABLECOIN),
+		interest_rate_multiplier: Ratio::saturating_from_rational(2, 1),
+	}))
+
+	set_minimum_debit_value {
+		CdpEngine::set_liquidation_grace(RawOrigin::Root.into(), STAKING, None)?;
+	}: _(RawOrigin::Root, STAKING, Some(10 * dollar(STABLECOIN)))
+
+	set_liquidation_grace {
+		CdpEngine::set_minimum_debit_value(RawOrigin::Root.into(), STAKING, None)?;
+	}: _(RawOrigin::Root, STAKING, Some(LiquidationGrace {
+		grace_period: 10,
+		hard_liquidation_ratio: Ratio::saturating_from_rational(100, 100),
+	}))
+
+	cancel_pending_risk_params {
+		CdpEngine::set_minimum_debit_value(RawOrigin::Root.into(), STAKING, None)?;
+	}: _(RawOrigin::Root, STAKING)
+	verify {
+		assert!(CdpEngine::pending_risk_params(STAKING).is_none());
+	}
+
+	set_oracle_failure_policy {
+	}: _(RawOrigin::Root, STAKING, OracleFailurePolicy::DexTwap {
+		window: 100,
+		haircut: Ratio::saturating_from_rational(10, 100),
+	})
+
+	// mandala has no debt denomination other than the stable currency
+	set_debt_denomination {
+	}: _(RawOrigin::Root, STAKING, None)
+
+	place_stop_loss_order {
+		let owner: AccountId = account("owner", 0, SEED);
+		let min_debit_value = MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(STAKING);
+		let min_debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: Amount = min_debit_amount.unique_saturated_into();
+		let collateral_value = 2 * min_debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(STAKING, &owner, collateral_amount + ExistentialDeposits::get(&STAKING));
+
+		// feed price
+		feed_price(vec![(STAKING, Price::one())])?;
+
+		// set risk params
+		super::utils::set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100),
+		);
+
+		// adjust position
+		CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
+	}: _(RawOrigin::Signed(owner.clone()), STAKING, Price::saturating_from_rational(1, 2), collateral_amount)
+	verify {
+		assert!(CdpEngine::stop_loss_orders(STAKING, &owner).is_some());
+	}
+
+	cancel_stop_loss_order {
+		let owner: AccountId = account("owner", 0, SEED);
+		let min_debit_value = MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(STAKING);
+		let min_debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: Amount = min_debit_amount.unique_saturated_into();
+		let collateral_value = 2 * min_debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(STAKING, &owner, collateral_amount + ExistentialDeposits::get(&STAKING));
+
+		// feed price
+		feed_price(vec![(STAKING, Price::one())])?;
+
+		// set risk params
+		super::utils::set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100),
+		);
+
+		// adjust position and place the order
+		CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
+		CdpEngine::place_stop_loss_order(
+			RawOrigin::Signed(owner.clone()).into(),
+			STAKING,
+			Price::saturating_from_rational(1, 2),
+			collateral_amount,
+		)?;
+	}: _(RawOrigin::Signed(owner.clone()), STAKING)
+	verify {
+		assert!(CdpEngine::stop_loss_orders(STAKING, &owner).is_none());
+	}
+
+	// `execute_stop_loss` swaps the collateral of the order by dex
+	execute_stop_loss {
+		let owner: AccountId = account("owner", 0, SEED);
+		let owner_lookup = AccountIdLookup::unlookup(owner.clone());
+		let funder: AccountId = account("funder", 0, SEED);
+		let mut path: Vec<CurrencyId> = DefaultSwapParitalPathList::get().last().unwrap().clone();
+
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(LIQUID);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 2 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(LIQUID), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+		let collateral_price = Price::one();		// 1 USD
+
+		path.insert(0, LIQUID);
+		for i in 0..path.len() {
+			if i != 0 {
+				inject_liquidity(funder.clone(), path[i], path[i-1], 10_000 * dollar(path[i]), 10_000 * dollar(path[i-1]))?;
+			}
+		}
+
+		set_balance(LIQUID, &owner, (10 * collateral_amount) + ExistentialDeposits::get(&LIQUID));
+
+		// feed price
+		feed_price(vec![(STAKING, collateral_price)])?;
+
+		// set risk params
+		super::utils::set_collateral_params(
+			LIQUID,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// adjust position and place the order which is triggered at the current price
+		CdpEngine::adjust_position(&owner, LIQUID, (10 * collateral_amount).try_into().unwrap(), debit_amount)?;
+		CdpEngine::place_stop_loss_order(
+			RawOrigin::Signed(owner.clone()).into(),
+			LIQUID,
+			Price::saturating_from_integer(1_000),
+			10 * collateral_amount,
+		)?;
+	}: _(RawOrigin::None, LIQUID, owner_lookup)
+	verify {
+		assert!(CdpEngine::stop_loss_orders(LIQUID, &owner).is_none());
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,60 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{dollar, CdpInsuranceFund, CurrencyId, GetStableCurrencyId, InsuranceFundAccount, Ratio, Runtime};
+
+use super::utils::set_balance;
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_cdp_treasury::DebitPool;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_cdp_insurance_fund }
+
+	on_initialize {
+	}: {
+		CdpInsuranceFund::on_initialize(1);
+	}
+
+	// cover the bad debit of the CDP treasury with the fund
+	on_initialize_with_cover {
+		DebitPool::<Runtime>::put(100 * dollar(STABLECOIN));
+		set_balance(STABLECOIN, &InsuranceFundAccount::get(), 1_000 * dollar(STABLECOIN));
+	}: {
+		CdpInsuranceFund::on_initialize(1);
+	}
+
+	set_fee_share {
+	}: _(RawOrigin::Root, Ratio::saturating_from_rational(10, 100))
+
+	set_fund_cap {
+	}: _(RawOrigin::Root, 10_000 * dollar(STABLECOIN))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
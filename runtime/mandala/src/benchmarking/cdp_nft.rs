@@ -0,0 +1,111 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Amount, CdpEngine, CdpNft, CollateralCurrencyIds, CurrencyId, ExistentialDeposits,
+	GetStableCurrencyId, Honzon, Price, Rate, Ratio, Runtime,
+};
+
+use super::utils::{feed_price, set_balance, set_collateral_params};
+use core::convert::TryInto;
+use frame_benchmarking::whitelisted_caller;
+use frame_system::RawOrigin;
+use module_cdp_nft::TokenizedPositions;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::{Change, GetByKey};
+use sp_runtime::{
+	traits::{One, UniqueSaturatedInto},
+	DispatchResult, FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+fn open_position(who: &AccountId, currency_id: CurrencyId) -> DispatchResult {
+	let debit_value = 100 * dollar(STABLECOIN);
+	let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+	let debit_amount = debit_exchange_rate
+		.reciprocal()
+		.unwrap()
+		.saturating_mul_int(debit_value);
+	let debit_amount: Amount = debit_amount.unique_saturated_into();
+	let collateral_value = 10 * debit_value;
+	let collateral_amount =
+		Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+	// set balance
+	set_balance(
+		currency_id,
+		who,
+		collateral_amount + ExistentialDeposits::get(&currency_id),
+	);
+
+	// feed price
+	feed_price(vec![(currency_id, Price::one())])?;
+
+	// set risk params
+	set_collateral_params(
+		currency_id,
+		Change::NoChange,
+		Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+		Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+		Change::NewValue(debit_value * 100),
+	);
+
+	Honzon::adjust_loan(
+		RawOrigin::Signed(who.clone()).into(),
+		currency_id,
+		collateral_amount.try_into().unwrap(),
+		debit_amount,
+	)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_cdp_nft }
+
+	// worst: the class of the position tokens is created
+	tokenize_position {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		open_position(&caller, currency_id)?;
+	}: _(RawOrigin::Signed(caller), currency_id)
+	verify {
+		assert!(CdpNft::position_class_id().is_some());
+	}
+
+	redeem_position {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		open_position(&caller, currency_id)?;
+		CdpNft::tokenize_position(RawOrigin::Signed(caller.clone()).into(), currency_id)?;
+		let token_id = TokenizedPositions::<Runtime>::iter_keys().next().unwrap();
+	}: _(RawOrigin::Signed(caller), token_id)
+	verify {
+		assert!(CdpNft::tokenized_positions(token_id).is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
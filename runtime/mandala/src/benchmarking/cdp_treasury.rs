@@ -17,16 +17,83 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	dollar, CdpTreasury, Currencies, CurrencyId, GetStableCurrencyId, GetStakingCurrencyId, MaxAuctionsCount, Runtime,
+	dollar, AccountId, Balance, Call, CdpTreasury, CollateralCurrencyIds, Currencies, CurrencyId,
+	DebtDenominationCurrencyIds, Dex, GetLiquidCurrencyId, GetNativeCurrencyId, GetStableCurrencyId,
+	GetStakingCurrencyId, MaxAuctionsCount, MaxAuctionsPerBlock, Price, Rate, Ratio, Runtime, TradingPathLimit,
 };
 
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::weights::Weight;
 use frame_system::RawOrigin;
-use module_support::CDPTreasury;
+use module_cdp_treasury::{
+	DebitHandling, DecreasingPriceSaleParams, DenominationDebitPool, PendingCollateralAuction,
+	PendingCollateralAuctions, PendingCollateralAuctionsRange, SurplusHandling, TotalCollateralPendingAuction,
+};
+use module_support::{CDPTreasury, CDPTreasuryExtended, SwapPath};
 use orml_benchmarking::runtime_benchmarks;
 use orml_traits::MultiCurrency;
+use runtime_common::RENBTC;
+use sp_runtime::{traits::One, FixedPointNumber};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
 
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
 const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
 const STAKING: CurrencyId = GetStakingCurrencyId::get();
+const LIQUID: CurrencyId = GetLiquidCurrencyId::get();
+
+// the intermediate currencies of the swap paths from the collateral to the
+// stable currency
+const INTERMEDIATE_CURRENCY_LIST: [CurrencyId; 3] = [NATIVE, LIQUID, RENBTC];
+
+fn inject_liquidity(
+	maker: AccountId,
+	currency_id_a: CurrencyId,
+	currency_id_b: CurrencyId,
+	amount_a: Balance,
+	amount_b: Balance,
+) -> Result<(), &'static str> {
+	// set balance
+	set_balance(currency_id_a, &maker, amount_a);
+	set_balance(currency_id_b, &maker, amount_b);
+
+	let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), currency_id_a, currency_id_b);
+
+	Dex::add_liquidity(
+		RawOrigin::Signed(maker.clone()).into(),
+		currency_id_a,
+		currency_id_b,
+		amount_a,
+		amount_b,
+		Default::default(),
+		false,
+	)?;
+
+	Ok(())
+}
+
+// build the swap path of length `p` from the staking currency to the stable
+// currency, and inject liquidity for each trading pair of it
+fn setup_swap_path(p: u32) -> Result<Vec<CurrencyId>, &'static str> {
+	let maker: AccountId = account("maker", 0, SEED);
+
+	let mut path = vec![STAKING];
+	path.extend(INTERMEDIATE_CURRENCY_LIST.iter().take(p.saturating_sub(2) as usize));
+	path.push(STABLECOIN);
+	for pair in path.windows(2) {
+		inject_liquidity(
+			maker.clone(),
+			pair[0],
+			pair[1],
+			10_000 * dollar(pair[0]),
+			10_000 * dollar(pair[1]),
+		)?;
+	}
+
+	Ok(path)
+}
 
 runtime_benchmarks! {
 	{ Runtime, module_cdp_treasury }
@@ -46,6 +113,246 @@ runtime_benchmarks! {
 	extract_surplus_to_treasury {
 		CdpTreasury::on_system_surplus(1_000 * dollar(STABLECOIN))?;
 	}: _(RawOrigin::Root, 200 * dollar(STABLECOIN))
+
+	swap_exact_collateral_to_stable {
+		let p in 2 .. TradingPathLimit::get();
+
+		let path = setup_swap_path(p)?;
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), 1_000 * dollar(STAKING))?;
+	}: {
+		CdpTreasury::swap_exact_collateral_to_stable(STAKING, 100 * dollar(STAKING), 0, &path, false)?;
+	}
+	verify {
+		// would panic the benchmark anyways, must add new currencies to INTERMEDIATE_CURRENCY_LIST for benchmarking to work
+		assert!(TradingPathLimit::get() <= INTERMEDIATE_CURRENCY_LIST.len() as u32 + 2);
+	}
+
+	swap_collateral_to_exact_stable {
+		let p in 2 .. TradingPathLimit::get();
+
+		let path = setup_swap_path(p)?;
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), 1_000 * dollar(STAKING))?;
+	}: {
+		CdpTreasury::swap_collateral_to_exact_stable(STAKING, 100 * dollar(STAKING), 10 * dollar(STABLECOIN), &path, false)?;
+	}
+	verify {
+		// would panic the benchmark anyways, must add new currencies to INTERMEDIATE_CURRENCY_LIST for benchmarking to work
+		assert!(TradingPathLimit::get() <= INTERMEDIATE_CURRENCY_LIST.len() as u32 + 2);
+	}
+
+	dilute_to_cover_debit {
+		let maker: AccountId = account("maker", 0, SEED);
+		inject_liquidity(maker, NATIVE, STABLECOIN, 10_000 * dollar(NATIVE), 10_000 * dollar(STABLECOIN))?;
+		feed_price(vec![(NATIVE, Price::saturating_from_integer(1))])?;
+		CdpTreasury::set_debit_handling(RawOrigin::Root.into(), DebitHandling::Dilution)?;
+		CdpTreasury::set_dilution_params(RawOrigin::Root.into(), 100 * dollar(NATIVE), 100)?;
+		CdpTreasury::on_system_debit(1_000 * dollar(STABLECOIN))?;
+	}: {
+		CdpTreasury::dilute_to_cover_debit(1);
+	}
+	verify {
+		assert_eq!(CdpTreasury::native_diluted_in_period().1, 100 * dollar(NATIVE));
+	}
+
+	set_expected_collateral_auction_value {
+	}: _(RawOrigin::Root, STAKING, Some(1_000 * dollar(STABLECOIN)))
+
+	set_surplus_buffer_size {
+	}: _(RawOrigin::Root, 1_000 * dollar(STABLECOIN))
+
+	set_collateral_sale_params {
+	}: _(RawOrigin::Root, STAKING, Some(DecreasingPriceSaleParams {
+		start_price_ratio: Ratio::saturating_from_rational(120, 100),
+		decay_per_block: Ratio::saturating_from_rational(1, 100),
+		floor_price_ratio: Ratio::saturating_from_rational(50, 100),
+		min_take_amount: dollar(STABLECOIN),
+	}))
+
+	set_surplus_handling {
+	}: _(RawOrigin::Root, SurplusHandling::BuyBackBurn)
+
+	set_debit_handling {
+	}: _(RawOrigin::Root, DebitHandling::Dilution)
+
+	set_dilution_params {
+	}: _(RawOrigin::Root, 100 * dollar(NATIVE), 100)
+
+	set_flash_loan_fee_rate {
+	}: _(RawOrigin::Root, STABLECOIN, Some(Rate::saturating_from_rational(1, 1000)))
+
+	set_aggregated_swap_paths {
+		let path = setup_swap_path(TradingPathLimit::get())?;
+	}: _(RawOrigin::Root, STAKING, Some(vec![SwapPath::Dex(path)]))
+
+	set_collateral_swap_path {
+		let path = setup_swap_path(TradingPathLimit::get())?;
+	}: _(RawOrigin::Root, STAKING, Some(path))
+
+	set_surplus_auto_extract_threshold {
+	}: _(RawOrigin::Root, Some(1_000 * dollar(STABLECOIN)))
+
+	set_max_debit_pool {
+	}: _(RawOrigin::Root, Some(1_000_000 * dollar(STABLECOIN)))
+
+	// disabling the yield strategy withdraws the collateral deposited
+	set_yield_strategy_enabled {
+		CdpTreasury::set_yield_strategy_enabled(RawOrigin::Root.into(), STAKING, true)?;
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), 1_000 * dollar(STAKING))?;
+		CdpTreasury::manage_yield_strategies(Weight::MAX);
+	}: _(RawOrigin::Root, STAKING, false)
+
+	set_auction_exempt_collateral {
+	}: _(RawOrigin::Root, STAKING, Some(Ratio::saturating_from_rational(5, 100)))
+
+	// mandala has no debt denomination other than the stable currency
+	set_debt_denomination_enabled {
+	}: _(RawOrigin::Root, NATIVE, false)
+
+	burn_surplus {
+		CdpTreasury::on_system_surplus(1_000 * dollar(STABLECOIN))?;
+	}: _(RawOrigin::Root, 200 * dollar(STABLECOIN))
+
+	deposit_surplus_from {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(caller), 200 * dollar(STABLECOIN))
+
+	donate_collateral {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STAKING, &caller, 1_000 * dollar(STAKING));
+	}: _(RawOrigin::Signed(caller), STAKING, 200 * dollar(STAKING))
+
+	// the cost of the inner call is charged on top of the weight
+	flash_loan {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STABLECOIN, &caller, 10 * dollar(STABLECOIN));
+		CdpTreasury::on_system_surplus(1_000 * dollar(STABLECOIN))?;
+		CdpTreasury::set_flash_loan_fee_rate(RawOrigin::Root.into(), STABLECOIN, Some(Rate::saturating_from_rational(1, 1000)))?;
+		let call = Call::System(frame_system::Call::remark { remark: vec![] });
+	}: _(RawOrigin::Signed(caller), STABLECOIN, 1_000 * dollar(STABLECOIN), Box::new(call))
+
+	take_collateral_sale {
+		let caller: AccountId = whitelisted_caller();
+		let refund_receiver: AccountId = account("refund_receiver", 0, SEED);
+		feed_price(vec![(STAKING, Price::saturating_from_integer(1))])?;
+		CdpTreasury::set_collateral_sale_params(RawOrigin::Root.into(), STAKING, Some(DecreasingPriceSaleParams {
+			start_price_ratio: Ratio::saturating_from_rational(120, 100),
+			decay_per_block: Ratio::saturating_from_rational(1, 100),
+			floor_price_ratio: Ratio::saturating_from_rational(50, 100),
+			min_take_amount: dollar(STABLECOIN),
+		}))?;
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), 1_000 * dollar(STAKING))?;
+		// the take raises the target and refunds the collateral left
+		let sale_id = CdpTreasury::create_decreasing_price_sale(STAKING, 1_000 * dollar(STAKING), 1_000 * dollar(STABLECOIN), refund_receiver)?;
+		set_balance(STABLECOIN, &caller, 10_000 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(caller), sale_id, 1_000 * dollar(STAKING), Price::saturating_from_integer(2))
+	verify {
+		assert!(CdpTreasury::collateral_sales(sale_id).is_none());
+	}
+
+	// the cancelled sale is split to the collateral auctions
+	cancel_collateral_sale {
+		let refund_receiver: AccountId = account("refund_receiver", 0, SEED);
+		feed_price(vec![(STAKING, Price::saturating_from_integer(1))])?;
+		CdpTreasury::set_collateral_sale_params(RawOrigin::Root.into(), STAKING, Some(DecreasingPriceSaleParams {
+			start_price_ratio: Ratio::saturating_from_rational(120, 100),
+			decay_per_block: Ratio::saturating_from_rational(1, 100),
+			floor_price_ratio: Ratio::saturating_from_rational(50, 100),
+			min_take_amount: dollar(STABLECOIN),
+		}))?;
+		CdpTreasury::set_expected_collateral_auction_size(RawOrigin::Root.into(), STAKING, (1_000 * dollar(STAKING)) / MaxAuctionsCount::get() as u128)?;
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), 1_000 * dollar(STAKING))?;
+		let sale_id = CdpTreasury::create_decreasing_price_sale(STAKING, 1_000 * dollar(STAKING), 1_000 * dollar(STABLECOIN), refund_receiver)?;
+	}: _(RawOrigin::Root, sale_id)
+
+	handle_excess_surplus {
+		let maker: AccountId = account("maker", 0, SEED);
+		inject_liquidity(maker, NATIVE, STABLECOIN, 10_000 * dollar(NATIVE), 10_000 * dollar(STABLECOIN))?;
+		feed_price(vec![(NATIVE, Price::saturating_from_integer(1))])?;
+		CdpTreasury::set_surplus_handling(RawOrigin::Root.into(), SurplusHandling::BuyBackBurn)?;
+		CdpTreasury::on_system_surplus(100 * dollar(STABLECOIN))?;
+	}: {
+		CdpTreasury::handle_excess_surplus();
+	}
+	verify {
+		assert_eq!(CdpTreasury::surplus_pool(), 0);
+	}
+
+	auto_extract_surplus {
+		CdpTreasury::set_surplus_auto_extract_threshold(RawOrigin::Root.into(), Some(100 * dollar(STABLECOIN)))?;
+		CdpTreasury::on_system_surplus(1_000 * dollar(STABLECOIN))?;
+	}: {
+		CdpTreasury::auto_extract_surplus();
+	}
+	verify {
+		assert_eq!(CdpTreasury::surplus_pool(), 100 * dollar(STABLECOIN));
+	}
+
+	// the swap of each collateral type is charged by its swap path on top of
+	// the weight
+	dispose_collaterals {
+		let c in 1 .. CollateralCurrencyIds::get().len() as u32;
+
+		let maker: AccountId = account("maker", 0, SEED);
+		let currency_ids = CollateralCurrencyIds::get();
+		let mut feed_data: Vec<(CurrencyId, Price)> = vec![];
+		for currency_id in currency_ids.iter().take(c as usize) {
+			inject_liquidity(maker.clone(), *currency_id, STABLECOIN, 10_000 * dollar(*currency_id), 10_000 * dollar(STABLECOIN))?;
+			CdpTreasury::set_collateral_swap_path(RawOrigin::Root.into(), *currency_id, Some(vec![*currency_id, STABLECOIN]))?;
+			Currencies::deposit(*currency_id, &CdpTreasury::account_id(), 10 * dollar(*currency_id))?;
+			if *currency_id != LIQUID {
+				feed_data.push((*currency_id, Price::one()));
+			}
+		}
+		feed_price(feed_data)?;
+	}: {
+		CdpTreasury::dispose_collaterals(Weight::MAX);
+	}
+
+	create_pending_collateral_auctions {
+		let n in 1 .. MaxAuctionsPerBlock::get();
+
+		let refund_receiver: AccountId = account("refund_receiver", 0, SEED);
+		for i in 0 .. n {
+			PendingCollateralAuctions::<Runtime>::insert(i, PendingCollateralAuction {
+				refund_recipient: refund_receiver.clone(),
+				currency_id: STAKING,
+				amount: 10 * dollar(STAKING),
+				target: 10 * dollar(STABLECOIN),
+			});
+		}
+		PendingCollateralAuctionsRange::<Runtime>::put((0, n));
+		TotalCollateralPendingAuction::<Runtime>::insert(STAKING, n as u128 * 10 * dollar(STAKING));
+		Currencies::deposit(STAKING, &CdpTreasury::account_id(), n as u128 * 10 * dollar(STAKING))?;
+	}: {
+		CdpTreasury::create_pending_collateral_auctions();
+	}
+	verify {
+		assert_eq!(CdpTreasury::pending_collateral_auctions_range(), (n, n));
+	}
+
+	manage_yield_strategies {
+		let c in 1 .. CollateralCurrencyIds::get().len() as u32;
+
+		for currency_id in CollateralCurrencyIds::get().iter().take(c as usize) {
+			CdpTreasury::set_yield_strategy_enabled(RawOrigin::Root.into(), *currency_id, true)?;
+			Currencies::deposit(*currency_id, &CdpTreasury::account_id(), 10 * dollar(*currency_id))?;
+		}
+	}: {
+		CdpTreasury::manage_yield_strategies(Weight::MAX);
+	}
+
+	offset_denominations {
+		let d in 0 .. DebtDenominationCurrencyIds::get().len() as u32;
+
+		for denomination in DebtDenominationCurrencyIds::get().iter().take(d as usize) {
+			CdpTreasury::set_debt_denomination_enabled(RawOrigin::Root.into(), *denomination, true)?;
+			DenominationDebitPool::<Runtime>::insert(*denomination, 10 * dollar(*denomination));
+			Currencies::deposit(*denomination, &CdpTreasury::account_id(), 10 * dollar(*denomination))?;
+		}
+	}: {
+		CdpTreasury::offset_denominations();
+	}
 }
 
 #[cfg(test)]
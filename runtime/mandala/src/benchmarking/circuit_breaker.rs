@@ -0,0 +1,81 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CircuitBreaker, CircuitBreakerId, CircuitBreakerMaxAutoUnpauseChecksPerBlock, CircuitBreakerReason, CurrencyId,
+	GetStakingCurrencyId, Price, Runtime, System,
+};
+
+use super::utils::feed_price;
+use codec::Encode;
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_circuit_breaker::AutoUnpauseCondition;
+use module_support::CircuitBreakerRecorder;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::{traits::One, FixedPointNumber};
+use sp_std::prelude::*;
+
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+fn breaker_id(index: u32) -> CircuitBreakerId {
+	CircuitBreakerId::Pallet(index.encode())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_circuit_breaker }
+
+	// check the price band condition of `c` active breakers
+	on_initialize {
+		let c in 0 .. CircuitBreakerMaxAutoUnpauseChecksPerBlock::get();
+
+		feed_price(vec![(STAKING, Price::one())])?;
+		for i in 0 .. c {
+			CircuitBreaker::on_paused(breaker_id(i), CircuitBreakerReason::Governance);
+			CircuitBreaker::set_auto_unpause_condition(
+				RawOrigin::Root.into(),
+				breaker_id(i),
+				Some(AutoUnpauseCondition::PriceWithinBand {
+					currency_id: STAKING,
+					lower_bound: Price::saturating_from_rational(1, 2),
+					upper_bound: Price::saturating_from_integer(2),
+					blocks: 10,
+				}),
+			)?;
+		}
+	}: {
+		CircuitBreaker::on_initialize(System::block_number());
+	}
+
+	confirm_pause {
+		CircuitBreaker::on_paused(breaker_id(0), CircuitBreakerReason::Governance);
+	}: _(RawOrigin::Root, breaker_id(0))
+
+	set_auto_unpause_condition {
+		CircuitBreaker::on_paused(breaker_id(0), CircuitBreakerReason::Governance);
+	}: _(RawOrigin::Root, breaker_id(0), Some(AutoUnpauseCondition::ConfirmationAbsent { blocks: 100 }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
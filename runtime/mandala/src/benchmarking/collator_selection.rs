@@ -17,10 +17,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	AccountId, Balance, Balances, CollatorKickThreshold, CollatorSelection, Event, MaxCandidates, MaxInvulnerables,
-	MinCandidates, Runtime, Session, SessionDuration, SessionKeys, System,
+	dollar, AccountId, Balance, Balances, CollatorKickThreshold, CollatorLPBondCurrencyId, CollatorSelection,
+	Currencies, CurrencyId, Dex, Event, GetNativeCurrencyId, GetStableCurrencyId, MaxCandidates, MaxInvulnerables,
+	MinCandidates, Price, Runtime, Session, SessionDuration, SessionKeys, System,
 };
 
+use super::utils::{feed_price, set_balance};
 use frame_benchmarking::{account, whitelisted_caller};
 use frame_support::{
 	assert_ok,
@@ -30,12 +32,17 @@ use frame_support::{
 use frame_system::RawOrigin;
 use module_collator_selection::POINT_PER_BLOCK;
 use orml_benchmarking::{runtime_benchmarks, whitelist_account};
+use orml_traits::MultiCurrency;
 use pallet_authorship::EventHandler;
 use pallet_session::SessionManager;
+use sp_runtime::{traits::One, Permill};
 use sp_std::prelude::*;
 
 const SEED: u32 = 0;
 
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
 fn assert_last_event(generic_event: Event) {
 	System::assert_last_event(generic_event.into());
 }
@@ -61,6 +68,28 @@ fn register_candidates(count: u32) {
 	}
 }
 
+// add the liquidity of the LP bond currency for `who` and set the candidacy
+// bond above the value of the LP bond, return the LP amount.
+fn setup_lp_bond(who: &AccountId) -> Result<Balance, &'static str> {
+	module_collator_selection::CandidacyBond::<Runtime>::put(1_000 * dollar(NATIVE));
+	feed_price(vec![(NATIVE, Price::one())])?;
+
+	set_balance(NATIVE, who, 2_000 * dollar(NATIVE));
+	set_balance(STABLECOIN, who, 100 * dollar(STABLECOIN));
+	let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), STABLECOIN, NATIVE);
+	Dex::add_liquidity(
+		RawOrigin::Signed(who.clone()).into(),
+		STABLECOIN,
+		NATIVE,
+		100 * dollar(STABLECOIN),
+		100 * dollar(NATIVE),
+		Default::default(),
+		false,
+	)?;
+
+	Ok(Currencies::free_balance(CollatorLPBondCurrencyId::get(), who))
+}
+
 runtime_benchmarks! {
 	{ Runtime, module_collator_selection }
 
@@ -253,6 +282,35 @@ runtime_benchmarks! {
 	} verify {
 		assert!(module_collator_selection::Candidates::<Runtime>::get().len() == (c - removals) as usize);
 	}
+
+	// worse case is the same as `register_as_candidate`, with the LP bond reserved.
+	register_as_candidate_with_lp_bond {
+		// MinCandidates = 5, so begin with 5.
+		let c in 5 .. MaxCandidates::get();
+
+		let caller: AccountId = whitelisted_caller();
+		let lp_amount = setup_lp_bond(&caller)?;
+		module_collator_selection::DesiredCandidates::<Runtime>::put(c);
+		register_candidates(c-1);
+
+		Session::set_keys(RawOrigin::Signed(caller.clone()).into(), SessionKeys::default(), vec![]).unwrap();
+	}: _(RawOrigin::Signed(caller.clone()), lp_amount)
+	verify {
+		assert_eq!(module_collator_selection::LPBonds::<Runtime>::get(&caller), lp_amount);
+	}
+
+	// worse case is slashing the LP bond, which removes the slashed liquidity.
+	slash_candidate {
+		let caller: AccountId = whitelisted_caller();
+		let lp_amount = setup_lp_bond(&caller)?;
+		module_collator_selection::DesiredCandidates::<Runtime>::put(MaxCandidates::get());
+
+		Session::set_keys(RawOrigin::Signed(caller.clone()).into(), SessionKeys::default(), vec![]).unwrap();
+		CollatorSelection::register_as_candidate_with_lp_bond(RawOrigin::Signed(caller.clone()).into(), lp_amount)?;
+	}: _(RawOrigin::Root, caller.clone(), Permill::from_percent(50))
+	verify {
+		assert!(module_collator_selection::LPBonds::<Runtime>::get(&caller) < lp_amount);
+	}
 }
 
 #[cfg(test)]
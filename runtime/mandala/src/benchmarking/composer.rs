@@ -0,0 +1,144 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Balance, CollateralCurrencyIds, Currencies, CurrencyId, Dex, GetLiquidCurrencyId,
+	GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, Price, Rate, Ratio, Runtime, TradingPathLimit,
+};
+
+use super::utils::{feed_price, set_balance, set_collateral_params};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use module_composer::{Step, StepAmount};
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::{Change, MultiCurrency};
+use runtime_common::{BNC, RENBTC, VSKSM};
+use sp_runtime::{traits::One, FixedPointNumber};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const LIQUID: CurrencyId = GetLiquidCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+const CURRENCY_LIST: [CurrencyId; 7] = [NATIVE, STABLECOIN, LIQUID, STAKING, BNC, VSKSM, RENBTC];
+
+fn inject_liquidity(
+	maker: AccountId,
+	currency_id_a: CurrencyId,
+	currency_id_b: CurrencyId,
+	max_amount_a: Balance,
+	max_amount_b: Balance,
+) -> Result<(), &'static str> {
+	// set balance
+	set_balance(currency_id_a, &maker, max_amount_a);
+	set_balance(currency_id_b, &maker, max_amount_b);
+
+	let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), currency_id_a, currency_id_b);
+
+	Dex::add_liquidity(
+		RawOrigin::Signed(maker).into(),
+		currency_id_a,
+		currency_id_b,
+		max_amount_a,
+		max_amount_b,
+		Default::default(),
+		false,
+	)?;
+
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_composer }
+
+	// a swap step through the path of `n` currencies
+	swap {
+		let n in 2 .. TradingPathLimit::get() as u32;
+
+		let maker: AccountId = account("maker", 0, SEED);
+		let caller: AccountId = whitelisted_caller();
+
+		let mut path: Vec<CurrencyId> = vec![CURRENCY_LIST[0]];
+		for i in 1 .. n as usize {
+			inject_liquidity(maker.clone(), CURRENCY_LIST[i - 1], CURRENCY_LIST[i], 10_000 * dollar(CURRENCY_LIST[i - 1]), 10_000 * dollar(CURRENCY_LIST[i]))?;
+			path.push(CURRENCY_LIST[i]);
+		}
+
+		set_balance(path[0], &caller, 10_000 * dollar(path[0]));
+	}: compose(RawOrigin::Signed(caller), vec![Step::Swap {
+		path: path.clone(),
+		supply_amount: StepAmount::Exact(100 * dollar(path[0])),
+		min_target_amount: 0,
+	}])
+
+	add_liquidity {
+		let maker: AccountId = account("maker", 0, SEED);
+		let caller: AccountId = whitelisted_caller();
+		inject_liquidity(maker, STABLECOIN, NATIVE, 10_000 * dollar(STABLECOIN), 10_000 * dollar(NATIVE))?;
+
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+		set_balance(NATIVE, &caller, 1_000 * dollar(NATIVE));
+	}: compose(RawOrigin::Signed(caller), vec![Step::AddLiquidity {
+		currency_id_a: STABLECOIN,
+		currency_id_b: NATIVE,
+		max_amount_a: StepAmount::Exact(100 * dollar(STABLECOIN)),
+		max_amount_b: 100 * dollar(NATIVE),
+		min_share_increment: 0,
+	}])
+
+	stake_dex_share {
+		let caller: AccountId = whitelisted_caller();
+		inject_liquidity(caller.clone(), STABLECOIN, NATIVE, 100 * dollar(STABLECOIN), 100 * dollar(NATIVE))?;
+		let lp_currency_id = CurrencyId::join_dex_share_currency_id(STABLECOIN, NATIVE).unwrap();
+		let share = Currencies::free_balance(lp_currency_id, &caller);
+	}: compose(RawOrigin::Signed(caller), vec![Step::StakeDexShare {
+		lp_currency_id,
+		amount: StepAmount::Exact(share),
+	}])
+
+	deposit_collateral {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+
+		set_balance(currency_id, &caller, 1_000 * dollar(currency_id));
+		feed_price(vec![(currency_id, Price::one())])?;
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(10_000 * dollar(STABLECOIN)),
+		);
+	}: compose(RawOrigin::Signed(caller), vec![Step::DepositCollateral {
+		currency_id,
+		amount: StepAmount::Exact(100 * dollar(currency_id)),
+	}])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,106 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, Dca, DcaMinExecutionInterval, Dex, GetNativeCurrencyId, GetStableCurrencyId, Price,
+	Ratio, Runtime, System, TreasuryAccount,
+};
+
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::weights::Weight;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::{traits::One, FixedPointNumber};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+fn create_order(owner: &AccountId) -> Result<(), &'static str> {
+	set_balance(STABLECOIN, owner, 1_000 * dollar(STABLECOIN));
+	set_balance(NATIVE, owner, 1_000 * dollar(NATIVE));
+	Dca::create_order(
+		RawOrigin::Signed(owner.clone()).into(),
+		STABLECOIN,
+		NATIVE,
+		10 * dollar(STABLECOIN),
+		DcaMinExecutionInterval::get(),
+		100 * dollar(STABLECOIN),
+		Ratio::saturating_from_rational(10, 100),
+	)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_dca }
+
+	create_order {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+		set_balance(NATIVE, &caller, 1_000 * dollar(NATIVE));
+	}: _(RawOrigin::Signed(caller), STABLECOIN, NATIVE, 10 * dollar(STABLECOIN), DcaMinExecutionInterval::get(), 100 * dollar(STABLECOIN), Ratio::saturating_from_rational(10, 100))
+
+	cancel_order {
+		let caller: AccountId = whitelisted_caller();
+		create_order(&caller)?;
+	}: _(RawOrigin::Signed(caller), 0)
+
+	// the overhead of the idle hook without any due order
+	on_idle {
+	}: {
+		Dca::execute_due_orders(System::block_number(), Weight::MAX);
+	}
+
+	// execute a due order by swapping on DEX
+	execute_order {
+		let maker: AccountId = account("maker", 0, SEED);
+		let owner: AccountId = account("owner", 0, SEED);
+		set_balance(STABLECOIN, &maker, 10_000 * dollar(STABLECOIN));
+		set_balance(NATIVE, &maker, 10_000 * dollar(NATIVE));
+		let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), STABLECOIN, NATIVE);
+		Dex::add_liquidity(
+			RawOrigin::Signed(maker).into(),
+			STABLECOIN,
+			NATIVE,
+			10_000 * dollar(STABLECOIN),
+			10_000 * dollar(NATIVE),
+			Default::default(),
+			false,
+		)?;
+		feed_price(vec![(NATIVE, Price::one())])?;
+		set_balance(NATIVE, &TreasuryAccount::get(), dollar(NATIVE));
+		create_order(&owner)?;
+	}: {
+		Dca::execute_due_orders(System::block_number(), Weight::MAX);
+	}
+	verify {
+		assert!(Dca::orders(0).unwrap().remaining_budget < 100 * dollar(STABLECOIN));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,116 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Balance, CurrencyId, DebitBackstop, DebitBackstopMaxProviders, DebitBackstopMinimumCommitment,
+	DebitBackstopWindow, GetNativeCurrencyId, GetStableCurrencyId, Price, Runtime, System, TreasuryAccount,
+};
+
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_cdp_treasury::DebitPool;
+use module_debit_backstop::UncoveredSince;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::traits::One;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+fn commit(who: &AccountId) -> Result<(), &'static str> {
+	set_balance(STABLECOIN, who, 2 * DebitBackstopMinimumCommitment::get());
+	DebitBackstop::commit(
+		RawOrigin::Signed(who.clone()).into(),
+		DebitBackstopMinimumCommitment::get(),
+	)?;
+	Ok(())
+}
+
+// make the commitments accrue the rewards paid by the treasury
+fn accrue_rewards() -> Result<(), &'static str> {
+	DebitBackstop::set_reward_budget(RawOrigin::Root.into(), 1_000 * dollar(NATIVE))?;
+	set_balance(NATIVE, &TreasuryAccount::get(), 1_000 * dollar(NATIVE));
+	System::set_block_number(System::block_number() + 1_000);
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_debit_backstop }
+
+	// record the block from which the debit is uncovered
+	on_initialize {
+		DebitPool::<Runtime>::put(100 * dollar(STABLECOIN));
+	}: {
+		DebitBackstop::on_initialize(System::block_number());
+	}
+
+	// draw the commitments of `p` providers to cover the debit
+	on_initialize_with_draw {
+		let p in 1 .. DebitBackstopMaxProviders::get();
+
+		for i in 0 .. p {
+			let provider: AccountId = account("provider", i, SEED);
+			commit(&provider)?;
+		}
+		accrue_rewards()?;
+		feed_price(vec![(NATIVE, Price::one())])?;
+		DebitPool::<Runtime>::put(DebitBackstopMinimumCommitment::get() * p as Balance);
+		UncoveredSince::<Runtime>::put(System::block_number());
+		System::set_block_number(System::block_number() + DebitBackstopWindow::get());
+	}: {
+		DebitBackstop::on_initialize(System::block_number());
+	}
+	verify {
+		assert_eq!(DebitBackstop::providers_count(), 0);
+	}
+
+	// worst: top up the commitment with the rewards paid
+	commit {
+		let caller: AccountId = whitelisted_caller();
+		commit(&caller)?;
+		accrue_rewards()?;
+	}: _(RawOrigin::Signed(caller), DebitBackstopMinimumCommitment::get())
+
+	withdraw {
+		let caller: AccountId = whitelisted_caller();
+		commit(&caller)?;
+		accrue_rewards()?;
+	}: _(RawOrigin::Signed(caller))
+
+	claim_rewards {
+		let caller: AccountId = whitelisted_caller();
+		commit(&caller)?;
+		accrue_rewards()?;
+	}: _(RawOrigin::Signed(caller))
+
+	set_reward_budget {
+	}: _(RawOrigin::Root, 1_000 * dollar(NATIVE))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
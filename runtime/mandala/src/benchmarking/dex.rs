@@ -18,17 +18,23 @@
 
 use crate::{
 	dollar, AccountId, Balance, Currencies, CurrencyId, Dex, Event, GetLiquidCurrencyId, GetNativeCurrencyId,
-	GetStableCurrencyId, GetStakingCurrencyId, Runtime, System, TradingPathLimit,
+	GetStableCurrencyId, GetStakingCurrencyId, Incentives, MaxBatchSwapOrders, Ratio, Runtime, System,
+	TradingPathLimit,
 };
 
+use super::utils::create_stable_swap_pool;
 use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
 use frame_system::RawOrigin;
-use module_dex::TradingPairStatus;
+use module_dex::{ExecutionMode, PoolType, SwapLimits, TradingPairStatus};
 use orml_benchmarking::runtime_benchmarks;
 use orml_traits::{MultiCurrency, MultiCurrencyExtended};
 use primitives::TradingPair;
 use runtime_common::{BNC, RENBTC, VSKSM};
-use sp_runtime::traits::UniqueSaturatedInto;
+use sp_runtime::{
+	traits::{UniqueSaturatedInto, Zero},
+	FixedPointNumber,
+};
 use sp_std::prelude::*;
 
 const SEED: u32 = 0;
@@ -330,6 +336,122 @@ runtime_benchmarks! {
 		// would panic the benchmark anyways, must add new currencies to CURRENCY_LIST for benchmarking to work
 		assert!(TradingPathLimit::get() < CURRENCY_LIST.len() as u32);
 	}
+
+	set_provision_vesting_period {
+		let trading_pair = TradingPair::from_currency_ids(STABLECOIN, NATIVE).unwrap();
+		if let TradingPairStatus::Enabled = Dex::trading_pair_statuses(trading_pair) {
+			Dex::disable_trading_pair(RawOrigin::Root.into(), trading_pair.first(), trading_pair.second())?;
+		}
+		Dex::list_provisioning(
+			RawOrigin::Root.into(),
+			trading_pair.first(),
+			trading_pair.second(),
+			dollar(trading_pair.first()),
+			dollar(trading_pair.second()),
+			100 * dollar(trading_pair.first()),
+			1000 * dollar(trading_pair.second()),
+			0
+		)?;
+	}: _(RawOrigin::Root, trading_pair.first(), trading_pair.second(), 100)
+
+	// claim the vested part of the dex share in the middle of the vesting period
+	claim_vested_provision_shares {
+		let founder: AccountId = whitelisted_caller();
+		let trading_pair = TradingPair::from_currency_ids(STABLECOIN, NATIVE).unwrap();
+		if let TradingPairStatus::Enabled = Dex::trading_pair_statuses(trading_pair) {
+			Dex::disable_trading_pair(RawOrigin::Root.into(), trading_pair.first(), trading_pair.second())?;
+		}
+		Dex::list_provisioning(
+			RawOrigin::Root.into(),
+			trading_pair.first(),
+			trading_pair.second(),
+			dollar(trading_pair.first()),
+			dollar(trading_pair.second()),
+			10 * dollar(trading_pair.first()),
+			10 * dollar(trading_pair.second()),
+			0
+		)?;
+		Dex::set_provision_vesting_period(RawOrigin::Root.into(), trading_pair.first(), trading_pair.second(), 100)?;
+
+		// set balance
+		<Currencies as MultiCurrencyExtended<_>>::update_balance(trading_pair.first(), &founder, (100 * dollar(trading_pair.first())).unique_saturated_into())?;
+		<Currencies as MultiCurrencyExtended<_>>::update_balance(trading_pair.second(), &founder, (100 * dollar(trading_pair.second())).unique_saturated_into())?;
+
+		Dex::add_provision(
+			RawOrigin::Signed(founder.clone()).into(),
+			trading_pair.first(),
+			trading_pair.second(),
+			dollar(trading_pair.first()),
+			20 * dollar(trading_pair.second())
+		)?;
+		Dex::end_provisioning(
+			RawOrigin::Signed(founder.clone()).into(),
+			trading_pair.first(),
+			trading_pair.second(),
+		)?;
+		System::set_block_number(System::block_number() + 50);
+	}: _(RawOrigin::Signed(whitelisted_caller()), founder.clone(), trading_pair.first(), trading_pair.second())
+	verify {
+		assert!(!Currencies::free_balance(trading_pair.dex_share_currency_id(), &founder).is_zero());
+	}
+
+	// worst: migrate the staked constant product share to the stable swap pool
+	migrate_liquidity {
+		let maker: AccountId = whitelisted_caller();
+		let stable_swap_pool = create_stable_swap_pool(LIQUID, STABLECOIN, 10_000 * dollar(STABLECOIN))?;
+
+		inject_liquidity(maker.clone(), LIQUID, STABLECOIN, 100 * dollar(LIQUID), 100 * dollar(STABLECOIN), false)?;
+		let lp_currency_id = TradingPair::from_currency_ids(LIQUID, STABLECOIN).unwrap().dex_share_currency_id();
+		let share = Currencies::free_balance(lp_currency_id, &maker);
+		Incentives::deposit_dex_share(RawOrigin::Signed(maker.clone()).into(), lp_currency_id, share)?;
+	}: {
+		let result = Dex::migrate_liquidity(RawOrigin::Signed(maker).into(), LIQUID, STABLECOIN, PoolType::ConstantProduct, PoolType::StableSwap(stable_swap_pool.unwrap_or_default()), share, 0, true);
+		// without stable swap the migration is rolled back after the withdrawal, which is all it can cost
+		if stable_swap_pool.is_some() {
+			result?;
+		}
+	}
+
+	set_swap_limits {
+	}: _(RawOrigin::Root, STABLECOIN, NATIVE, Some(SwapLimits {
+		max_price_impact: Ratio::saturating_from_rational(5, 100),
+		max_volume_per_block: 10_000 * dollar(STABLECOIN),
+	}))
+
+	set_execution_mode {
+	}: _(RawOrigin::Root, STABLECOIN, NATIVE, ExecutionMode::BatchAuction { max_orders: MaxBatchSwapOrders::get() })
+
+	set_protocol_fee_rate {
+	}: _(RawOrigin::Root, Ratio::saturating_from_rational(1, 1000))
+
+	submit_batch_swap_order {
+		let maker: AccountId = account("maker", 0, SEED);
+		let taker: AccountId = whitelisted_caller();
+		inject_liquidity(maker, STABLECOIN, NATIVE, 10_000 * dollar(STABLECOIN), 10_000 * dollar(NATIVE), false)?;
+		Dex::set_execution_mode(RawOrigin::Root.into(), STABLECOIN, NATIVE, ExecutionMode::BatchAuction { max_orders: MaxBatchSwapOrders::get() })?;
+		<Currencies as MultiCurrencyExtended<_>>::update_balance(STABLECOIN, &taker, (100 * dollar(STABLECOIN)).unique_saturated_into())?;
+	}: _(RawOrigin::Signed(taker), STABLECOIN, NATIVE, 10 * dollar(STABLECOIN), 0)
+
+	// settle `n` orders of the batch auction of a trading pair in both directions
+	settle_batch_auction {
+		let n in 1 .. MaxBatchSwapOrders::get();
+
+		let maker: AccountId = account("maker", 0, SEED);
+		inject_liquidity(maker, STABLECOIN, NATIVE, 10_000 * dollar(STABLECOIN), 10_000 * dollar(NATIVE), false)?;
+		Dex::set_execution_mode(RawOrigin::Root.into(), STABLECOIN, NATIVE, ExecutionMode::BatchAuction { max_orders: MaxBatchSwapOrders::get() })?;
+		for i in 0 .. n {
+			let taker: AccountId = account("taker", i, SEED);
+			let (supply_currency_id, target_currency_id) = if i % 2 == 0 { (STABLECOIN, NATIVE) } else { (NATIVE, STABLECOIN) };
+			<Currencies as MultiCurrencyExtended<_>>::update_balance(supply_currency_id, &taker, (10 * dollar(supply_currency_id)).unique_saturated_into())?;
+			Dex::submit_batch_swap_order(RawOrigin::Signed(taker).into(), supply_currency_id, target_currency_id, 10 * dollar(supply_currency_id), 0)?;
+		}
+	}: {
+		Dex::on_initialize(System::block_number() + 1);
+	}
+	verify {
+		let trading_pair = TradingPair::from_currency_ids(STABLECOIN, NATIVE).unwrap();
+		assert!(Dex::batch_swap_orders(trading_pair).is_empty());
+	}
 }
 
 #[cfg(test)]
@@ -154,6 +154,54 @@ runtime_benchmarks! {
 
 		whitelist_account!(alice_account);
 	}: _(RawOrigin::Signed(alice_account_id()), contract)
+
+	register_storage_sponsor {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, 10_000)
+	verify {
+		assert!(EVM::storage_sponsors(contract).is_some());
+	}
+
+	// worst: the sponsor pool has to be released
+	unregister_storage_sponsor {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+		EVM::register_storage_sponsor(Origin::signed(alice_account_id()), contract, 10_000)?;
+		EVM::deposit_storage_sponsor_pool(Origin::signed(alice_account_id()), contract, 1_000 * dollar(NATIVE))?;
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract)
+	verify {
+		assert!(EVM::storage_sponsors(contract).is_none());
+	}
+
+	deposit_storage_sponsor_pool {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+		EVM::register_storage_sponsor(Origin::signed(alice_account_id()), contract, 10_000)?;
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, 1_000 * dollar(NATIVE))
+
+	withdraw_storage_sponsor_pool {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+		EVM::register_storage_sponsor(Origin::signed(alice_account_id()), contract, 10_000)?;
+		EVM::deposit_storage_sponsor_pool(Origin::signed(alice_account_id()), contract, 1_000 * dollar(NATIVE))?;
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, 1_000 * dollar(NATIVE))
 }
 
 #[cfg(test)]
@@ -19,11 +19,11 @@
 use crate::{
 	dollar, AccountId, Amount, Balance, CdpEngine, CollateralCurrencyIds, Currencies, CurrencyId,
 	DefaultSwapParitalPathList, DepositPerAuthorization, Dex, ExistentialDeposits, GetLiquidCurrencyId,
-	GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, Honzon, Price, Rate, Ratio, Runtime,
+	GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId, Honzon, MaxMergeLoans, Price, Rate, Ratio, Runtime,
 	TradingPathLimit,
 };
 
-use super::utils::{feed_price, set_balance};
+use super::utils::{feed_price, set_balance, set_collateral_params};
 use core::convert::TryInto;
 use frame_benchmarking::{account, whitelisted_caller};
 use frame_system::RawOrigin;
@@ -125,6 +125,15 @@ runtime_benchmarks! {
 		}
 	}: _(RawOrigin::Signed(caller))
 
+	set_delegation_limit {
+		let caller: AccountId = whitelisted_caller();
+		let to: AccountId = account("to", 0, SEED);
+		let to_lookup = AccountIdLookup::unlookup(to);
+
+		// set balance
+		set_balance(NATIVE, &caller, DepositPerAuthorization::get());
+	}: _(RawOrigin::Signed(caller), STAKING, to_lookup, dollar(STABLECOIN), dollar(STAKING))
+
 	// `adjust_loan`, best case:
 	// adjust both collateral and debit
 	adjust_loan {
@@ -145,17 +154,123 @@ runtime_benchmarks! {
 		feed_price(vec![(currency_id, collateral_price)])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		set_collateral_params(
 			currency_id,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(debit_value * 100),
-		)?;
+		);
 	}: _(RawOrigin::Signed(caller), currency_id, collateral_amount.try_into().unwrap(), debit_amount)
 
+	// `adjust_loan` only deposits the collateral to the CDP which has debit
+	adjust_loan_without_debit {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let collateral_price = Price::one();		// 1 USD
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &caller, 2 * collateral_amount + ExistentialDeposits::get(&currency_id));
+
+		// feed price
+		feed_price(vec![(currency_id, collateral_price)])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// initialize the CDP with debit
+		Honzon::adjust_loan(RawOrigin::Signed(caller.clone()).into(), currency_id, collateral_amount.try_into().unwrap(), debit_amount)?;
+	}: adjust_loan(RawOrigin::Signed(caller), currency_id, collateral_amount.try_into().unwrap(), 0)
+
+	adjust_loans {
+		let c in 1 .. CollateralCurrencyIds::get().len() as u32;
+		let caller: AccountId = whitelisted_caller();
+		let collateral_price = Price::one();		// 1 USD
+		let debit_value = 100 * dollar(STABLECOIN);
+		let mut adjustments: Vec<(CurrencyId, Amount, Amount)> = vec![];
+
+		for currency_id in CollateralCurrencyIds::get().into_iter().take(c as usize) {
+			let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+			let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+			let debit_amount: Amount = debit_amount.unique_saturated_into();
+			let collateral_value = 10 * debit_value;
+			let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+			// set balance
+			set_balance(currency_id, &caller, collateral_amount + ExistentialDeposits::get(&currency_id));
+
+			// feed price
+			feed_price(vec![(currency_id, collateral_price)])?;
+
+			// set risk params
+			set_collateral_params(
+				currency_id,
+				Change::NoChange,
+				Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+				Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+				Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+				Change::NewValue(debit_value * 100),
+			);
+
+			adjustments.push((currency_id, collateral_amount.try_into().unwrap(), debit_amount));
+		}
+	}: _(RawOrigin::Signed(caller), adjustments)
+
+	adjust_loan_on_behalf {
+		let caller: AccountId = whitelisted_caller();
+		let owner: AccountId = account("owner", 0, SEED);
+		let owner_lookup = AccountIdLookup::unlookup(owner.clone());
+		let caller_lookup = AccountIdLookup::unlookup(caller.clone());
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let collateral_price = Price::one();		// 1 USD
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &owner, collateral_amount + ExistentialDeposits::get(&currency_id));
+		set_balance(NATIVE, &owner, DepositPerAuthorization::get());
+
+		// feed price
+		feed_price(vec![(currency_id, collateral_price)])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// delegate caller with the delegation limit
+		Honzon::set_delegation_limit(
+			RawOrigin::Signed(owner).into(),
+			currency_id,
+			caller_lookup,
+			debit_amount.unique_saturated_into(),
+			0,
+		)?;
+	}: _(RawOrigin::Signed(caller), owner_lookup, currency_id, collateral_amount.try_into().unwrap(), debit_amount)
+
 	transfer_loan_from {
 		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
 		let sender: AccountId = account("sender", 0, SEED);
@@ -178,15 +293,14 @@ runtime_benchmarks! {
 		feed_price(vec![(currency_id, Price::one())])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		set_collateral_params(
 			currency_id,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(debit_value * 100),
-		)?;
+		);
 
 		// initialize sender's loan
 		Honzon::adjust_loan(
@@ -204,6 +318,105 @@ runtime_benchmarks! {
 		)?;
 	}: _(RawOrigin::Signed(receiver), currency_id, sender_lookup)
 
+	transfer_loan_to {
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let sender: AccountId = whitelisted_caller();
+		let receiver: AccountId = account("receiver", 0, SEED);
+		let receiver_lookup = AccountIdLookup::unlookup(receiver.clone());
+		let sender_lookup = AccountIdLookup::unlookup(sender.clone());
+
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &sender, collateral_amount + ExistentialDeposits::get(&currency_id));
+		set_balance(NATIVE, &receiver, DepositPerAuthorization::get());
+
+		// feed price
+		feed_price(vec![(currency_id, Price::one())])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// initialize sender's loan
+		Honzon::adjust_loan(
+			RawOrigin::Signed(sender.clone()).into(),
+			currency_id,
+			collateral_amount.try_into().unwrap(),
+			debit_amount,
+		)?;
+
+		// receiver authorizes sender
+		Honzon::authorize(
+			RawOrigin::Signed(receiver).into(),
+			currency_id,
+			sender_lookup,
+		)?;
+	}: _(RawOrigin::Signed(sender), currency_id, receiver_lookup)
+
+	merge_loans {
+		let c in 1 .. MaxMergeLoans::get();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let receiver: AccountId = whitelisted_caller();
+		let receiver_lookup = AccountIdLookup::unlookup(receiver.clone());
+
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// feed price
+		feed_price(vec![(currency_id, Price::one())])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100 * c as Balance),
+		);
+
+		let mut senders = vec![];
+		for i in 0 .. c {
+			let sender: AccountId = account("sender", i, SEED);
+
+			// set balance
+			set_balance(currency_id, &sender, collateral_amount + ExistentialDeposits::get(&currency_id));
+			set_balance(NATIVE, &sender, DepositPerAuthorization::get());
+
+			// initialize sender's loan
+			Honzon::adjust_loan(
+				RawOrigin::Signed(sender.clone()).into(),
+				currency_id,
+				collateral_amount.try_into().unwrap(),
+				debit_amount,
+			)?;
+
+			// authorize receiver
+			Honzon::authorize(
+				RawOrigin::Signed(sender.clone()).into(),
+				currency_id,
+				receiver_lookup.clone(),
+			)?;
+			senders.push(AccountIdLookup::unlookup(sender));
+		}
+	}: _(RawOrigin::Signed(receiver), currency_id, senders)
+
 	close_loan_has_debit_by_dex {
 		let u in 2 .. TradingPathLimit::get() as u32;
 		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
@@ -245,15 +458,14 @@ runtime_benchmarks! {
 		feed_price(vec![(currency_id, Price::one())])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		set_collateral_params(
 			currency_id,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(debit_value * 100),
-		)?;
+		);
 
 		// initialize sender's loan
 		Honzon::adjust_loan(
@@ -289,15 +501,14 @@ runtime_benchmarks! {
 		feed_price(vec![(STAKING, Price::one())])?;
 
 		// set risk params
-		CdpEngine::set_collateral_params(
-			RawOrigin::Root.into(),
+		set_collateral_params(
 			currency_id,
 			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(debit_value * 100),
-		)?;
+		);
 
 		// initialize sender's loan
 		Honzon::adjust_loan(
@@ -308,6 +519,149 @@ runtime_benchmarks! {
 		)?;
 
 	}: close_loan_has_debit_by_dex(RawOrigin::Signed(sender), currency_id, collateral_amount, None)
+
+	// issue the debit and swap it to the collateral of the CDP with debit
+	expand_position_collateral {
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let sender: AccountId = whitelisted_caller();
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &sender, collateral_amount + ExistentialDeposits::get(&currency_id));
+		let maker: AccountId = account("maker", 0, SEED);
+
+		// the collateral swap path is the direct trading pair with the stable currency
+		inject_liquidity(
+			maker.clone(),
+			currency_id,
+			STABLECOIN,
+			10_000 * dollar(currency_id),
+			10_000 * dollar(STABLECOIN),
+			false,
+		)?;
+
+		// feed price
+		feed_price(vec![(currency_id, Price::one())])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// initialize sender's loan
+		Honzon::adjust_loan(
+			RawOrigin::Signed(sender.clone()).into(),
+			currency_id,
+			collateral_amount.try_into().unwrap(),
+			debit_amount,
+		)?;
+	}: _(RawOrigin::Signed(sender), currency_id, debit_value, 0)
+
+	// swap part of the collateral to repay part of the debit of the CDP
+	shrink_position_debit {
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let sender: AccountId = whitelisted_caller();
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &sender, collateral_amount + ExistentialDeposits::get(&currency_id));
+		let maker: AccountId = account("maker", 0, SEED);
+
+		// the collateral swap path is the direct trading pair with the stable currency
+		inject_liquidity(
+			maker.clone(),
+			currency_id,
+			STABLECOIN,
+			10_000 * dollar(currency_id),
+			10_000 * dollar(STABLECOIN),
+			false,
+		)?;
+
+		// feed price
+		feed_price(vec![(currency_id, Price::one())])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// initialize sender's loan
+		Honzon::adjust_loan(
+			RawOrigin::Signed(sender.clone()).into(),
+			currency_id,
+			collateral_amount.try_into().unwrap(),
+			debit_amount,
+		)?;
+	}: _(RawOrigin::Signed(sender), currency_id, collateral_amount / 20, 0)
+
+	// `adjust_extra_collateral`, worst case:
+	// withdraw the extra collateral of the CDP with debit, which triggers the risk check
+	adjust_extra_collateral {
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let sender: AccountId = whitelisted_caller();
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_value = 10 * debit_value;
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+		// set balance
+		set_balance(currency_id, &sender, collateral_amount + ExistentialDeposits::get(&currency_id));
+		let extra_currency_id: CurrencyId = CollateralCurrencyIds::get()[1];
+		let extra_collateral_amount = Price::saturating_from_rational(dollar(extra_currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+		set_balance(extra_currency_id, &sender, extra_collateral_amount + ExistentialDeposits::get(&extra_currency_id));
+
+		// feed price
+		feed_price(vec![(currency_id, Price::one()), (extra_currency_id, Price::one())])?;
+
+		// set risk params
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+
+		// initialize sender's loan
+		Honzon::adjust_loan(
+			RawOrigin::Signed(sender.clone()).into(),
+			currency_id,
+			collateral_amount.try_into().unwrap(),
+			debit_amount,
+		)?;
+
+		// deposit the extra collateral
+		Honzon::adjust_extra_collateral(
+			RawOrigin::Signed(sender.clone()).into(),
+			currency_id,
+			extra_currency_id,
+			extra_collateral_amount.try_into().unwrap(),
+		)?;
+		let withdraw_amount: Amount = (extra_collateral_amount / 2).unique_saturated_into();
+	}: _(RawOrigin::Signed(sender), currency_id, extra_currency_id, -withdraw_amount)
 }
 
 #[cfg(test)]
@@ -17,8 +17,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	dollar, AccountId, AccumulatePeriod, CollateralCurrencyIds, Currencies, CurrencyId, GetNativeCurrencyId,
-	GetStableCurrencyId, GetStakingCurrencyId, Incentives, Rate, Rewards, Runtime, System,
+	dollar, AccountId, AccumulatePeriod, Balance, CollateralCurrencyIds, Currencies, CurrencyId, GetNativeCurrencyId,
+	GetStableCurrencyId, GetStakingCurrencyId, Incentives, Rate, Rewards, Runtime, System, TreasuryPalletId,
 };
 
 use super::utils::set_balance;
@@ -29,6 +29,7 @@ use module_incentives::PoolId;
 use orml_benchmarking::runtime_benchmarks;
 use orml_traits::MultiCurrency;
 use primitives::DexShare;
+use sp_runtime::traits::AccountIdConversion;
 use sp_std::prelude::*;
 
 const SEED: u32 = 0;
@@ -128,6 +129,27 @@ runtime_benchmarks! {
 			updates.push((PoolId::Loans(currency_id), Rate::default()));
 		}
 	}: _(RawOrigin::Root, updates)
+
+	// close `c` pools which have the unclaimed rewards to return
+	close_incentive_pools {
+		let c in 1 .. 100;
+		let caller: AccountId = whitelisted_caller();
+		let mut pool_ids = vec![];
+
+		for i in 0 .. c {
+			let pool_id = PoolId::Loans(CurrencyId::ForeignAsset(i as u16));
+			orml_rewards::PoolInfos::<Runtime>::mutate(&pool_id, |pool_info| {
+				pool_info.rewards.insert(NATIVE, (dollar(NATIVE), 0));
+			});
+			pool_ids.push(pool_id);
+		}
+		set_balance(NATIVE, &Incentives::account_id(), c as Balance * dollar(NATIVE));
+		let treasury: AccountId = TreasuryPalletId::get().into_account();
+		set_balance(NATIVE, &treasury, 1_000 * dollar(NATIVE));
+	}: _(RawOrigin::Signed(caller), pool_ids)
+	verify {
+		assert!(!orml_rewards::PoolInfos::<Runtime>::contains_key(PoolId::Loans(CurrencyId::ForeignAsset(0))));
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,93 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Call, CurrencyId, GetNativeCurrencyId, GetStakingCurrencyId, Keeper, KeeperJobCondition,
+	KeeperMinExecutionInterval, Runtime, System,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use module_incentives::PoolId;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+fn job_call() -> Box<Call> {
+	Box::new(Call::Incentives(module_incentives::Call::claim_rewards {
+		pool_id: PoolId::Loans(STAKING),
+	}))
+}
+
+fn register_job(owner: &AccountId) -> Result<(), &'static str> {
+	set_balance(NATIVE, owner, 1_000 * dollar(NATIVE));
+	Keeper::register_job(
+		RawOrigin::Signed(owner.clone()).into(),
+		KeeperJobCondition::Always,
+		job_call(),
+		KeeperMinExecutionInterval::get(),
+		10 * dollar(NATIVE),
+	)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_keeper }
+
+	register_job {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(NATIVE, &caller, 1_000 * dollar(NATIVE));
+	}: _(RawOrigin::Signed(caller), KeeperJobCondition::Always, job_call(), KeeperMinExecutionInterval::get(), 10 * dollar(NATIVE))
+
+	fund_job {
+		let caller: AccountId = whitelisted_caller();
+		let owner: AccountId = account("owner", 0, SEED);
+		register_job(&owner)?;
+		set_balance(NATIVE, &caller, 1_000 * dollar(NATIVE));
+	}: _(RawOrigin::Signed(caller), 0, 10 * dollar(NATIVE))
+
+	cancel_job {
+		let caller: AccountId = whitelisted_caller();
+		register_job(&caller)?;
+	}: _(RawOrigin::Signed(caller), 0)
+
+	// the overhead of the execution, the weights of the condition and the call are added
+	execute_job {
+		let keeper: AccountId = whitelisted_caller();
+		let owner: AccountId = account("owner", 0, SEED);
+		register_job(&owner)?;
+		set_balance(NATIVE, &keeper, dollar(NATIVE));
+	}: _(RawOrigin::Signed(keeper), 0)
+	verify {
+		assert!(Keeper::jobs(0).unwrap().next_execution > System::block_number());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,114 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Amount, Balance, CdpEngine, CollateralCurrencyIds, CurrencyId, GetStableCurrencyId, Honzon,
+	LiquidationInsurance, LiquidationInsuranceCoveragePeriod, Price, Rate, Ratio, Runtime, System,
+};
+
+use super::utils::{feed_price, set_balance, set_collateral_params};
+use core::convert::TryInto;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_liquidation_insurance::{CoverageParams, Policies, Policy, PolicyExpiries, TotalCoverage};
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::Change;
+use sp_runtime::{
+	traits::{One, UniqueSaturatedInto},
+	FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+fn coverage_params() -> CoverageParams {
+	CoverageParams {
+		premium_rate: Rate::saturating_from_rational(1, 100),
+		refund_ratio: Ratio::saturating_from_rational(50, 100),
+		max_total_coverage: 1_000_000 * dollar(STABLECOIN),
+		available: true,
+	}
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_liquidation_insurance }
+
+	// expire `c` policies
+	on_initialize {
+		let c in 0 .. 100;
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let expire_at = System::block_number() + LiquidationInsuranceCoveragePeriod::get();
+
+		for i in 0 .. c {
+			let who: AccountId = account("holder", i, SEED);
+			Policies::<Runtime>::insert(currency_id, &who, Policy {
+				coverage: 100 * dollar(STABLECOIN),
+				refund_ratio: Ratio::saturating_from_rational(50, 100),
+				expire_at,
+			});
+			PolicyExpiries::<Runtime>::insert(expire_at, (currency_id, who), ());
+		}
+		TotalCoverage::<Runtime>::insert(currency_id, 100 * dollar(STABLECOIN) * c as Balance);
+	}: {
+		LiquidationInsurance::on_initialize(expire_at);
+	}
+
+	set_coverage_params {
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+	}: _(RawOrigin::Root, currency_id, Some(coverage_params()))
+
+	// cover the position which has debit
+	buy_policy {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id: CurrencyId = CollateralCurrencyIds::get()[0];
+		let debit_value = 100 * dollar(STABLECOIN);
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(currency_id);
+		let debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(debit_value);
+		let debit_amount: Amount = debit_amount.unique_saturated_into();
+		let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(10 * debit_value);
+
+		set_balance(currency_id, &caller, 2 * collateral_amount);
+		set_balance(STABLECOIN, &caller, 100 * dollar(STABLECOIN));
+		feed_price(vec![(currency_id, Price::one())])?;
+		set_collateral_params(
+			currency_id,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(debit_value * 100),
+		);
+		Honzon::adjust_loan(RawOrigin::Signed(caller.clone()).into(), currency_id, collateral_amount.try_into().unwrap(), debit_amount)?;
+		LiquidationInsurance::set_coverage_params(RawOrigin::Root.into(), currency_id, Some(coverage_params()))?;
+	}: _(RawOrigin::Signed(caller.clone()), currency_id, 1_000 * dollar(STABLECOIN))
+	verify {
+		assert!(LiquidationInsurance::policies(currency_id, &caller).is_some());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
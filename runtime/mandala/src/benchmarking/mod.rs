@@ -26,9 +26,15 @@ pub mod utils;
 pub mod asset_registry;
 pub mod auction_manager;
 pub mod cdp_engine;
+pub mod cdp_insurance_fund;
+pub mod cdp_nft;
 pub mod cdp_treasury;
+pub mod circuit_breaker;
 pub mod collator_selection;
+pub mod composer;
 pub mod currencies;
+pub mod dca;
+pub mod debit_backstop;
 pub mod dex;
 pub mod emergency_shutdown;
 pub mod evm;
@@ -36,12 +42,28 @@ pub mod evm_accounts;
 pub mod homa;
 pub mod honzon;
 pub mod incentives;
+pub mod keeper;
+pub mod liquidation_insurance;
 pub mod nominees_election;
 pub mod nutsfinance_stable_asset;
+pub mod operations;
+pub mod peg_monitor;
+pub mod position_migration;
 pub mod prices;
+pub mod proof_of_reserve;
+pub mod psm;
+pub mod public_goods;
+pub mod randomness_beacon;
+pub mod sandbox;
+pub mod savings;
 pub mod session_manager;
+pub mod stablecoin_metrics;
 pub mod transaction_pause;
 pub mod transaction_payment;
+pub mod vault;
+pub mod weight_metrics;
+pub mod xcm_collateral;
+pub mod xcm_onboarding;
 
 // orml benchmarking
 pub mod auction;
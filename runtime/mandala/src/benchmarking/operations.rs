@@ -0,0 +1,104 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CdpTreasury, CurrencyId, Dex, GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId,
+	Operations, Price, Runtime, TreasuryAccount,
+};
+
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use module_operations::DexPositionRoll;
+use module_support::CDPTreasury;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::traits::One;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+fn set_operator(operator: &AccountId) -> Result<(), &'static str> {
+	Operations::set_operator(RawOrigin::Root.into(), Some(operator.clone()))?;
+	Operations::set_daily_value_limit(RawOrigin::Root.into(), 1_000_000 * dollar(STABLECOIN))?;
+	Ok(())
+}
+
+fn inject_liquidity(currency_id: CurrencyId) -> Result<(), &'static str> {
+	let maker: AccountId = account("maker", 0, SEED);
+	set_balance(currency_id, &maker, 10_000 * dollar(currency_id));
+	set_balance(STABLECOIN, &maker, 10_000 * dollar(STABLECOIN));
+	let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), currency_id, STABLECOIN);
+	Dex::add_liquidity(
+		RawOrigin::Signed(maker).into(),
+		currency_id,
+		STABLECOIN,
+		10_000 * dollar(currency_id),
+		10_000 * dollar(STABLECOIN),
+		Default::default(),
+		false,
+	)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_operations }
+
+	set_operator {
+		let operator: AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Root, Some(operator))
+
+	set_daily_value_limit {
+	}: _(RawOrigin::Root, 1_000_000 * dollar(STABLECOIN))
+
+	// swap the treasury collateral by the direct trading pair
+	swap_treasury_collateral {
+		let operator: AccountId = whitelisted_caller();
+		set_operator(&operator)?;
+		inject_liquidity(STAKING)?;
+		feed_price(vec![(STAKING, Price::one())])?;
+		set_balance(STAKING, &CdpTreasury::account_id(), 100 * dollar(STAKING));
+	}: _(RawOrigin::Signed(operator), STAKING, 10 * dollar(STAKING))
+
+	top_up_insurance_fund {
+		let operator: AccountId = whitelisted_caller();
+		set_operator(&operator)?;
+		CdpTreasury::on_system_surplus(1_000 * dollar(STABLECOIN))?;
+	}: _(RawOrigin::Signed(operator), 100 * dollar(STABLECOIN))
+
+	roll_treasury_dex_position {
+		let operator: AccountId = whitelisted_caller();
+		set_operator(&operator)?;
+		inject_liquidity(NATIVE)?;
+		feed_price(vec![(NATIVE, Price::one())])?;
+		set_balance(NATIVE, &TreasuryAccount::get(), 100 * dollar(NATIVE));
+		set_balance(STABLECOIN, &TreasuryAccount::get(), 100 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(operator), NATIVE, STABLECOIN, DexPositionRoll::Add(10 * dollar(NATIVE), 10 * dollar(STABLECOIN)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
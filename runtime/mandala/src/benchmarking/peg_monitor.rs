@@ -0,0 +1,81 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, CashCurrencyId, CurrencyId, GetStableCurrencyId, PegMonitor, PegMonitorTwapPeriod, Rate, Runtime,
+	TradingPair,
+};
+
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const CASH: CurrencyId = CashCurrencyId::get();
+
+fn setup_peg_pool() -> Result<(), &'static str> {
+	// the ED of the reference currency is not supported by `Tokens`, so the pool can not be
+	// funded by adding liquidity
+	module_dex::LiquidityPool::<Runtime>::insert(
+		TradingPair::from_currency_ids(STABLECOIN, CASH).unwrap(),
+		(10_000 * dollar(STABLECOIN), 10_000 * dollar(CASH)),
+	);
+	PegMonitor::set_fee_params(
+		RawOrigin::Root.into(),
+		Rate::saturating_from_rational(1, 1000),
+		Rate::saturating_from_rational(1, 100),
+		Rate::saturating_from_rational(1, 10),
+	)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_peg_monitor }
+
+	// accumulate the DEX price
+	on_initialize {
+		setup_peg_pool()?;
+	}: {
+		PegMonitor::on_initialize(1);
+	}
+
+	// accumulate the DEX price and adjust the PSM fees at the end of the TWAP period
+	on_initialize_with_adjustment {
+		setup_peg_pool()?;
+		PegMonitor::on_initialize(1);
+	}: {
+		PegMonitor::on_initialize(PegMonitorTwapPeriod::get());
+	}
+	verify {
+		assert!(PegMonitor::average_price().is_some());
+	}
+
+	set_fee_params {
+	}: _(RawOrigin::Root, Rate::saturating_from_rational(1, 1000), Rate::saturating_from_rational(1, 100), Rate::saturating_from_rational(1, 10))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
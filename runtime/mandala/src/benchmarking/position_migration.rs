@@ -0,0 +1,181 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Amount, Balance, CdpEngine, Currencies, CurrencyId, ExistentialDeposits, GetLiquidCurrencyId,
+	GetStableCurrencyId, GetStakingCurrencyId, Honzon, Loans, Origin, PositionMigration,
+	PositionMigrationAcknowledgementTimeout, Price, Rate, Ratio, Runtime, System,
+};
+
+use super::utils::{feed_price, open_hrmp_channel, set_balance, set_collateral_params};
+use core::convert::TryInto;
+use cumulus_primitives_core::ParaId;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use module_position_migration::{Exports, Imports, MigrationId, SiblingParachains};
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::{Change, GetByKey, MultiCurrency};
+use sp_runtime::{
+	traits::{One, UniqueSaturatedInto, Zero},
+	DispatchError, DispatchResult, FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+// the liquid currency is a collateral which is reserved on this parachain
+const LIQUID: CurrencyId = GetLiquidCurrencyId::get();
+const DEBIT_VALUE: Balance = 100;
+
+fn sibling() -> ParaId {
+	ParaId::from(2000)
+}
+
+fn sibling_origin() -> Origin {
+	Origin::from(cumulus_pallet_xcm::Origin::SiblingParachain(sibling()))
+}
+
+fn collateral_amount() -> Balance {
+	Price::saturating_from_rational(dollar(LIQUID), dollar(STABLECOIN))
+		.saturating_mul_int(10 * DEBIT_VALUE * dollar(STABLECOIN))
+}
+
+fn setup_sibling() {
+	SiblingParachains::<Runtime>::insert(sibling(), true);
+	open_hrmp_channel(sibling());
+}
+
+fn setup_collateral() -> DispatchResult {
+	feed_price(vec![(STAKING, Price::one())])?;
+	set_collateral_params(
+		LIQUID,
+		Change::NoChange,
+		Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+		Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+		Change::NewValue(100 * DEBIT_VALUE * dollar(STABLECOIN)),
+	);
+	Ok(())
+}
+
+fn open_position(who: &AccountId) -> DispatchResult {
+	setup_collateral()?;
+
+	let debit_amount: Amount = CdpEngine::get_debit_exchange_rate(LIQUID)
+		.reciprocal()
+		.unwrap()
+		.saturating_mul_int(DEBIT_VALUE * dollar(STABLECOIN))
+		.unique_saturated_into();
+	set_balance(LIQUID, who, collateral_amount() + ExistentialDeposits::get(&LIQUID));
+	Honzon::adjust_loan(
+		RawOrigin::Signed(who.clone()).into(),
+		LIQUID,
+		collateral_amount().try_into().unwrap(),
+		debit_amount,
+	)
+}
+
+fn export_position(who: &AccountId) -> Result<MigrationId, DispatchError> {
+	setup_sibling();
+	open_position(who)?;
+
+	let migration_id = PositionMigration::next_migration_id();
+	PositionMigration::export_position(RawOrigin::Signed(who.clone()).into(), LIQUID, sibling(), who.clone())?;
+	Ok(migration_id)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_position_migration }
+
+	export_position {
+		let caller: AccountId = whitelisted_caller();
+		let beneficiary: AccountId = account("beneficiary", 0, SEED);
+		setup_sibling();
+		open_position(&caller)?;
+		let migration_id = PositionMigration::next_migration_id();
+	}: _(RawOrigin::Signed(caller), LIQUID, sibling(), beneficiary)
+	verify {
+		assert!(PositionMigration::exports(migration_id).is_some());
+	}
+
+	import_position {
+		let beneficiary: AccountId = account("beneficiary", 0, SEED);
+		setup_sibling();
+	}: _(sibling_origin(), 0, beneficiary, LIQUID, collateral_amount(), DEBIT_VALUE * dollar(STABLECOIN))
+	verify {
+		assert!(PositionMigration::imports(sibling(), 0).is_some());
+	}
+
+	acknowledge_export {
+		let caller: AccountId = whitelisted_caller();
+		let migration_id = export_position(&caller)?;
+	}: _(sibling_origin(), migration_id)
+	verify {
+		assert!(!Exports::<Runtime>::contains_key(migration_id));
+	}
+
+	// worst: the position of the beneficiary is reopened
+	finalize_import {
+		let beneficiary: AccountId = account("beneficiary", 0, SEED);
+		setup_sibling();
+		setup_collateral()?;
+		PositionMigration::import_position(
+			sibling_origin(),
+			0,
+			beneficiary.clone(),
+			LIQUID,
+			collateral_amount(),
+			DEBIT_VALUE * dollar(STABLECOIN),
+		)?;
+		set_balance(
+			LIQUID,
+			&PositionMigration::import_account_id(sibling()),
+			collateral_amount() + ExistentialDeposits::get(&LIQUID),
+		);
+	}: _(sibling_origin(), 0)
+	verify {
+		assert!(!Imports::<Runtime>::contains_key(sibling(), 0));
+		assert!(!Loans::positions(LIQUID, &beneficiary).debit.is_zero());
+	}
+
+	cancel_export {
+		let caller: AccountId = whitelisted_caller();
+		let migration_id = export_position(&caller)?;
+		System::set_block_number(System::block_number() + PositionMigrationAcknowledgementTimeout::get() + 1);
+	}: _(RawOrigin::Signed(caller.clone()), migration_id)
+	verify {
+		assert!(!Exports::<Runtime>::contains_key(migration_id));
+		assert_eq!(Currencies::free_balance(LIQUID, &caller), collateral_amount() + ExistentialDeposits::get(&LIQUID));
+	}
+
+	set_sibling_parachain {
+	}: _(RawOrigin::Root, sibling(), true)
+	verify {
+		assert!(PositionMigration::sibling_parachains(sibling()));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -16,15 +16,38 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{CurrencyId, GetStableCurrencyId, Origin, Price, Prices, Runtime};
+use crate::{
+	dollar, AccountId, CurrencyId, Dex, GetLiquidCurrencyId, GetNativeCurrencyId, GetStableCurrencyId,
+	GetStakingCurrencyId, Origin, Price, PriceDeviationReportInterval, Prices, PricesTwapWindow, Ratio, Runtime,
+	System,
+};
 
-use super::utils::feed_price;
+use super::utils::{feed_price, set_balance};
+use frame_benchmarking::account;
+use frame_support::traits::OnInitialize;
 use frame_system::RawOrigin;
+use module_prices::DeviationReport;
 use orml_benchmarking::runtime_benchmarks;
-use sp_runtime::traits::One;
+use runtime_common::{BNC, RENBTC, VSKSM};
+use sp_core::U256;
+use sp_runtime::{
+	traits::{One, Zero},
+	FixedPointNumber,
+};
 use sp_std::vec;
 
+const SEED: u32 = 0;
+
 const STAKING: CurrencyId = GetStableCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const MONITORED_CURRENCY_LIST: [CurrencyId; 6] = [
+	GetNativeCurrencyId::get(),
+	GetLiquidCurrencyId::get(),
+	GetStakingCurrencyId::get(),
+	BNC,
+	VSKSM,
+	RENBTC,
+];
 
 runtime_benchmarks! {
 	{ Runtime, module_prices }
@@ -39,6 +62,50 @@ runtime_benchmarks! {
 		feed_price(vec![(STAKING, Price::one())])?;
 		Prices::lock_price(Origin::root(), STAKING)?;
 	}: _(RawOrigin::Root, STAKING)
+
+	set_deviation_threshold {
+		let currency_id = MONITORED_CURRENCY_LIST[0];
+	}: _(RawOrigin::Root, currency_id, Some(Ratio::saturating_from_rational(10, 100)))
+
+	// worst: the currency has been reported before
+	report_price_deviation {
+		let currency_id = MONITORED_CURRENCY_LIST[0];
+		Prices::set_deviation_threshold(Origin::root(), currency_id, Some(Ratio::saturating_from_rational(10, 100)))?;
+		feed_price(vec![(currency_id, Price::one())])?;
+		module_prices::DexTwapPrices::<Runtime>::insert(currency_id, Price::saturating_from_integer(2));
+		module_prices::LastDeviationReports::<Runtime>::insert(currency_id, DeviationReport {
+			block_number: Zero::zero(),
+			oracle_price: Price::one(),
+			dex_twap_price: Price::saturating_from_integer(2),
+		});
+		System::set_block_number(PriceDeviationReportInterval::get());
+	}: _(RawOrigin::None, currency_id, Price::one())
+
+	// derive the DEX TWAP prices of `n` monitored currencies at the end of the TWAP window
+	on_initialize {
+		let n in 0 .. MONITORED_CURRENCY_LIST.len() as u32;
+		let maker: AccountId = account("maker", 0, SEED);
+
+		for currency_id in MONITORED_CURRENCY_LIST.iter().take(n as usize) {
+			set_balance(*currency_id, &maker, 10_000 * dollar(*currency_id));
+			set_balance(STABLECOIN, &maker, 10_000 * dollar(STABLECOIN));
+			let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), *currency_id, STABLECOIN);
+			Dex::add_liquidity(
+				RawOrigin::Signed(maker.clone()).into(),
+				*currency_id,
+				STABLECOIN,
+				10_000 * dollar(*currency_id),
+				10_000 * dollar(STABLECOIN),
+				Default::default(),
+				false,
+			)?;
+			Prices::set_deviation_threshold(Origin::root(), *currency_id, Some(Ratio::saturating_from_rational(10, 100)))?;
+			module_prices::TwapObservations::<Runtime>::insert(currency_id, (U256::zero(), 0));
+		}
+		System::set_block_number(PricesTwapWindow::get());
+	}: {
+		Prices::on_initialize(System::block_number());
+	}
 }
 
 #[cfg(test)]
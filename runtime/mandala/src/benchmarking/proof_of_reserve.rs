@@ -0,0 +1,71 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{ProofOfReserve, ProofOfReserveDigestHistoryDepth, ProofOfReserveMaxReporters, Runtime, System};
+
+use codec::Encode;
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_proof_of_reserve::KEY_TYPE;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+runtime_benchmarks! {
+	{ Runtime, module_proof_of_reserve }
+
+	on_initialize {
+		let reporter = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+		ProofOfReserve::set_reporters(RawOrigin::Root.into(), vec![reporter])?;
+		System::set_block_number(1);
+		let digest = ProofOfReserve::reserve_digest(1);
+		let signature = sp_io::crypto::sr25519_sign(KEY_TYPE, &reporter, &digest.encode()).unwrap();
+		ProofOfReserve::submit_reserve_digest(RawOrigin::None.into(), digest, reporter, signature)?;
+	}: {
+		ProofOfReserve::on_initialize(1 + ProofOfReserveDigestHistoryDepth::get());
+	}
+
+	set_reporters {
+		let c in 0 .. ProofOfReserveMaxReporters::get();
+		let reporters = (0 .. c).map(|_| sp_io::crypto::sr25519_generate(KEY_TYPE, None)).collect::<Vec<_>>();
+	}: _(RawOrigin::Root, reporters)
+
+	submit_reserve_digest {
+		let reporter = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+		ProofOfReserve::set_reporters(RawOrigin::Root.into(), vec![reporter])?;
+		System::set_block_number(1);
+		let digest = ProofOfReserve::reserve_digest(1);
+		let signature = sp_io::crypto::sr25519_sign(KEY_TYPE, &reporter, &digest.encode()).unwrap();
+	}: _(RawOrigin::None, digest, reporter, signature)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+	use sp_keystore::{testing::KeyStore, KeystoreExt};
+	use std::sync::Arc;
+
+	fn new_test_ext_with_keystore() -> sp_io::TestExternalities {
+		let mut ext = new_test_ext();
+		ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+		ext
+	}
+
+	impl_benchmark_test_suite!(new_test_ext_with_keystore(),);
+}
@@ -0,0 +1,112 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Balance, CurrencyId, GetStableCurrencyId, PSMCapPeriod, PegMonitor, Rate, Runtime, PSM,
+};
+
+use super::utils::{register_foreign_asset, set_balance};
+use frame_benchmarking::whitelisted_caller;
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_psm::{PeriodSwapped, SwapCaps};
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::{DispatchError, FixedPointNumber};
+use sp_std::prelude::*;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+// the unit of the external stable currency with 6 decimals
+const UNIT: Balance = 1_000_000;
+
+fn setup_psm() -> Result<CurrencyId, DispatchError> {
+	let currency_id = register_foreign_asset(6);
+	PSM::set_swap_caps(
+		RawOrigin::Root.into(),
+		currency_id,
+		Some(SwapCaps {
+			mint_cap: 1_000_000 * dollar(STABLECOIN),
+			burn_cap: 1_000_000 * dollar(STABLECOIN),
+		}),
+	)?;
+	// worst: the fees are charged
+	PegMonitor::set_fee_params(
+		RawOrigin::Root.into(),
+		Rate::saturating_from_rational(1, 1000),
+		Rate::saturating_from_rational(1, 100),
+		Rate::saturating_from_rational(1, 10),
+	)?;
+	Ok(currency_id)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_psm }
+
+	mint {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id = setup_psm()?;
+		set_balance(currency_id, &caller, 1_000 * UNIT);
+	}: _(RawOrigin::Signed(caller), currency_id, 1_000 * UNIT)
+	verify {
+		assert_eq!(PSM::reserves(currency_id), 1_000 * UNIT);
+	}
+
+	redeem {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id = setup_psm()?;
+		set_balance(currency_id, &caller, 1_000 * UNIT);
+		PSM::mint(RawOrigin::Signed(caller.clone()).into(), currency_id, 1_000 * UNIT)?;
+	}: _(RawOrigin::Signed(caller), currency_id, 100 * dollar(STABLECOIN))
+	verify {
+		assert!(PSM::reserves(currency_id) < 1_000 * UNIT);
+	}
+
+	set_swap_caps {
+		let currency_id = register_foreign_asset(6);
+	}: _(RawOrigin::Root, currency_id, Some(SwapCaps { mint_cap: 1_000_000 * dollar(STABLECOIN), burn_cap: 1_000_000 * dollar(STABLECOIN) }))
+	verify {
+		assert!(PSM::external_stable_currencies(currency_id).is_some());
+	}
+
+	on_initialize {
+	}: {
+		PSM::on_initialize(1);
+	}
+
+	// the swapped amounts of the period are reset
+	on_initialize_with_reset {
+		let caller: AccountId = whitelisted_caller();
+		let currency_id = setup_psm()?;
+		set_balance(currency_id, &caller, 1_000 * UNIT);
+		PSM::mint(RawOrigin::Signed(caller).into(), currency_id, 1_000 * UNIT)?;
+	}: {
+		PSM::on_initialize(PSMCapPeriod::get());
+	}
+	verify {
+		assert!(!PeriodSwapped::<Runtime>::contains_key(currency_id));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,111 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, GetStableCurrencyId, MaxPublicGoodsContributionsCleanup, MaxPublicGoodsProjects,
+	PublicGoods, PublicGoodsRoundDuration, Runtime,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_public_goods::{Contributions, ContributionsToCleanup, RoundProjectInfo, RoundProjects};
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_public_goods }
+
+	register_project {
+		let caller: AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert!(PublicGoods::projects(0).is_some());
+	}
+
+	donate {
+		let caller: AccountId = whitelisted_caller();
+		let project: AccountId = account("project", 0, SEED);
+		PublicGoods::register_project(RawOrigin::Signed(project).into())?;
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(caller), 0, 100 * dollar(STABLECOIN))
+	verify {
+		assert_eq!(PublicGoods::round_projects(0, 0).total_donations, 100 * dollar(STABLECOIN));
+	}
+
+	fund_matching_pool {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(caller), 100 * dollar(STABLECOIN))
+	verify {
+		assert_eq!(PublicGoods::matching_pool(), 100 * dollar(STABLECOIN));
+	}
+
+	// finalize the round of `n` projects which all receive matching fund
+	on_initialize_with_finalize_round {
+		let n in 1 .. MaxPublicGoodsProjects::get();
+		let funder: AccountId = account("funder", 0, SEED);
+
+		for i in 0 .. n {
+			let project: AccountId = account("project", i, SEED);
+			PublicGoods::register_project(RawOrigin::Signed(project).into())?;
+			RoundProjects::<Runtime>::insert(0, i, RoundProjectInfo {
+				total_donations: 100 * dollar(STABLECOIN),
+				attested_donations: 100 * dollar(STABLECOIN),
+				sqrt_donations_sum: 100 * dollar(STABLECOIN),
+			});
+		}
+		set_balance(STABLECOIN, &funder, 1_000_000 * dollar(STABLECOIN));
+		PublicGoods::fund_matching_pool(RawOrigin::Signed(funder).into(), 1_000_000 * dollar(STABLECOIN))?;
+	}: {
+		PublicGoods::on_initialize(PublicGoodsRoundDuration::get());
+	}
+	verify {
+		assert_eq!(RoundProjects::<Runtime>::iter_prefix(0).count(), 0);
+	}
+
+	// remove `n` contributions of a finalized round
+	cleanup_contributions {
+		let n in 1 .. MaxPublicGoodsContributionsCleanup::get();
+
+		for i in 0 .. n {
+			let contributor: AccountId = account("contributor", i, SEED);
+			Contributions::<Runtime>::insert((0, 0), contributor, dollar(STABLECOIN));
+		}
+		ContributionsToCleanup::<Runtime>::insert((0, 0), ());
+	}: {
+		PublicGoods::on_initialize(1);
+	}
+	verify {
+		assert!(!ContributionsToCleanup::<Runtime>::contains_key((0, 0)));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
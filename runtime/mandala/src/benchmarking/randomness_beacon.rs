@@ -0,0 +1,91 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, GetNativeCurrencyId, RandomnessBeacon, RandomnessCommitPeriod,
+	RandomnessMaxParticipants, RandomnessRevealPeriod, Runtime, System,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::{OnFinalize, OnInitialize};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::DispatchResult;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+
+fn commit(who: &AccountId, secret: [u8; 32]) -> DispatchResult {
+	set_balance(NATIVE, who, 10_000 * dollar(NATIVE));
+	RandomnessBeacon::commit(
+		RawOrigin::Signed(who.clone()).into(),
+		RandomnessBeacon::commitment_of(who, &secret),
+	)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_randomness_beacon }
+
+	commit {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(NATIVE, &caller, 10_000 * dollar(NATIVE));
+		let commitment = RandomnessBeacon::commitment_of(&caller, &[1u8; 32]);
+	}: _(RawOrigin::Signed(caller.clone()), commitment)
+	verify {
+		assert!(RandomnessBeacon::commitments(&caller).is_some());
+	}
+
+	reveal {
+		let caller: AccountId = whitelisted_caller();
+		commit(&caller, [1u8; 32])?;
+		System::set_block_number(RandomnessCommitPeriod::get());
+	}: _(RawOrigin::Signed(caller.clone()), [1u8; 32])
+	verify {
+		assert_eq!(RandomnessBeacon::commitments(&caller).map(|(_, revealed, _)| revealed), Some(true));
+	}
+
+	// finalize the round of `c` commitments which are all slashed as unrevealed
+	on_initialize {
+		let c in 0 .. RandomnessMaxParticipants::get();
+
+		for i in 0 .. c {
+			let participant: AccountId = account("participant", i, SEED);
+			commit(&participant, [1u8; 32])?;
+		}
+		let round_end = RandomnessCommitPeriod::get() + RandomnessRevealPeriod::get();
+		System::set_block_number(round_end);
+	}: {
+		RandomnessBeacon::on_initialize(round_end);
+		RandomnessBeacon::on_finalize(round_end);
+	}
+	verify {
+		assert_eq!(RandomnessBeacon::current_round(), 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
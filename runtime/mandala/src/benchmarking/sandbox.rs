@@ -0,0 +1,105 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Amount, Balance, CdpEngine, CurrencyId, ExistentialDeposits, GetStableCurrencyId,
+	GetStakingCurrencyId, MinimumDebitValue, Price, Rate, Ratio, Runtime, Sandbox, SandboxMaxFastForwardEras,
+	SandboxMaxLiquidationWaveSize,
+};
+
+use super::utils::{feed_price, set_balance, set_collateral_params};
+use core::convert::TryInto;
+use frame_benchmarking::account;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::{Change, GetByKey};
+use sp_runtime::{
+	traits::{One, UniqueSaturatedInto},
+	FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_sandbox }
+
+	fast_forward_eras {
+		let c in 0 .. SandboxMaxFastForwardEras::get();
+	}: _(RawOrigin::Root, c)
+
+	force_price {
+	}: _(RawOrigin::Root, STAKING, Some(Price::one()))
+	verify {
+		assert_eq!(Sandbox::forced_prices(STAKING), Some(Price::one()));
+	}
+
+	// liquidate `c` unsafe CDPs
+	trigger_liquidation_wave {
+		let c in 1 .. SandboxMaxLiquidationWaveSize::get();
+
+		let min_debit_value = MinimumDebitValue::get();
+		let min_debit_amount: Amount = CdpEngine::get_debit_exchange_rate(STAKING)
+			.reciprocal()
+			.unwrap()
+			.saturating_mul_int(min_debit_value)
+			.unique_saturated_into();
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN))
+			.saturating_mul_int(2 * min_debit_value);
+
+		feed_price(vec![(STAKING, Price::one())])?;
+		set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(min_debit_value * 100 * c as Balance),
+		);
+		for i in 0 .. c {
+			let owner: AccountId = account("owner", i, SEED);
+			set_balance(STAKING, &owner, collateral_amount + ExistentialDeposits::get(&STAKING));
+			CdpEngine::adjust_position(&owner, STAKING, collateral_amount.try_into().unwrap(), min_debit_amount)?;
+		}
+
+		// modify liquidation rate to make the cdps unsafe
+		set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		);
+	}: _(RawOrigin::Root, STAKING, c)
+	verify {
+		assert!(Sandbox::liquidation_wave_start_key(STAKING).is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,88 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{dollar, AccountId, CdpTreasury, CurrencyId, ExchangeRate, GetStableCurrencyId, Rate, Runtime, Savings};
+
+use super::utils::set_balance;
+use frame_benchmarking::whitelisted_caller;
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::{traits::One, DispatchResult, FixedPointNumber};
+use sp_std::prelude::*;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+fn deposit(who: &AccountId) -> DispatchResult {
+	set_balance(STABLECOIN, who, 1_000 * dollar(STABLECOIN));
+	Savings::deposit(RawOrigin::Signed(who.clone()).into(), 1_000 * dollar(STABLECOIN))
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_savings }
+
+	deposit {
+		let caller: AccountId = whitelisted_caller();
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+	}: _(RawOrigin::Signed(caller.clone()), 1_000 * dollar(STABLECOIN))
+	verify {
+		assert_eq!(Savings::shares(&caller), 1_000 * dollar(STABLECOIN));
+	}
+
+	withdraw {
+		let caller: AccountId = whitelisted_caller();
+		deposit(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), 1_000 * dollar(STABLECOIN))
+	verify {
+		assert_eq!(Savings::shares(&caller), 0);
+	}
+
+	set_savings_rate {
+	}: _(RawOrigin::Root, Rate::saturating_from_rational(1, 1_000_000))
+	verify {
+		assert_eq!(Savings::savings_rate(), Rate::saturating_from_rational(1, 1_000_000));
+	}
+
+	// no interest is accrued
+	on_initialize {
+	}: {
+		Savings::on_initialize(1);
+	}
+
+	// the interest is paid from the surplus pool
+	on_initialize_with_accrual {
+		let caller: AccountId = whitelisted_caller();
+		deposit(&caller)?;
+		Savings::set_savings_rate(RawOrigin::Root.into(), Rate::saturating_from_rational(1, 1_000_000))?;
+		set_balance(STABLECOIN, &CdpTreasury::account_id(), 1_000 * dollar(STABLECOIN));
+	}: {
+		Savings::on_initialize(1);
+	}
+	verify {
+		assert!(Savings::savings_index() > ExchangeRate::one());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
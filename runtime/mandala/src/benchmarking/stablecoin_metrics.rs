@@ -0,0 +1,69 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, GetStableCurrencyId, Runtime, StablecoinMetrics, StablecoinMetricsWindowSize, System,
+};
+
+use frame_benchmarking::account;
+use frame_support::traits::OnInitialize;
+use module_stablecoin_metrics::{BlockVelocity, VelocityStats};
+use module_support::OnTransfer;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_stablecoin_metrics }
+
+	// the stats of the block out of the window are removed
+	on_initialize {
+		BlockVelocity::<Runtime>::insert(0, VelocityStats {
+			transfer_count: 1,
+			volume: dollar(STABLECOIN),
+		});
+	}: {
+		StablecoinMetrics::on_initialize(StablecoinMetricsWindowSize::get());
+	}
+	verify {
+		assert!(!BlockVelocity::<Runtime>::contains_key(0));
+	}
+
+	// the transfer is recorded
+	on_transfer {
+		let from: AccountId = account("from", 0, SEED);
+		let to: AccountId = account("to", 0, SEED);
+	}: {
+		<StablecoinMetrics as OnTransfer<_, _, _>>::on_transfer(STABLECOIN, &from, &to, dollar(STABLECOIN));
+	}
+	verify {
+		assert_eq!(StablecoinMetrics::block_velocity(System::block_number()).transfer_count, 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
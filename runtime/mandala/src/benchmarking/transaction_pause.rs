@@ -18,8 +18,11 @@
 
 use crate::{Origin, Runtime, TransactionPause};
 
+use frame_support::traits::OnInitialize;
 use frame_system::RawOrigin;
 use orml_benchmarking::runtime_benchmarks;
+use sp_core::H160;
+use sp_std::prelude::*;
 
 runtime_benchmarks! {
 	{ Runtime, module_transaction_pause }
@@ -30,6 +33,31 @@ runtime_benchmarks! {
 	unpause_transaction {
 		TransactionPause::pause_transaction(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec())?;
 	}: _(RawOrigin::Root, b"Balances".to_vec(), b"transfer".to_vec())
+
+	emergency_pause_pallet {
+	}: _(RawOrigin::Root, b"Balances".to_vec(), 100)
+
+	emergency_unpause_pallet {
+		TransactionPause::emergency_pause_pallet(Origin::root(), b"Balances".to_vec(), 100)?;
+	}: _(RawOrigin::Root, b"Balances".to_vec())
+
+	pause_evm_call {
+	}: _(RawOrigin::Root, H160::from_low_u64_be(1024), Some([0xa9, 0x05, 0x9c, 0xbb]))
+
+	unpause_evm_call {
+		TransactionPause::pause_evm_call(Origin::root(), H160::from_low_u64_be(1024), Some([0xa9, 0x05, 0x9c, 0xbb]))?;
+	}: _(RawOrigin::Root, H160::from_low_u64_be(1024), Some([0xa9, 0x05, 0x9c, 0xbb]))
+
+	on_initialize {
+		let c in 0 .. 50;
+		for i in 0 .. c {
+			let mut pallet_name = b"Pallet".to_vec();
+			pallet_name.extend_from_slice(&[b'0' + (i / 10) as u8, b'0' + (i % 10) as u8]);
+			TransactionPause::emergency_pause_pallet(Origin::root(), pallet_name, 1)?;
+		}
+	}: {
+		TransactionPause::on_initialize(2);
+	}
 }
 
 #[cfg(test)]
@@ -40,6 +40,13 @@ runtime_benchmarks! {
 	}: {
 		TransactionPayment::on_finalize(System::block_number());
 	}
+
+	set_fee_free_call {
+		let who: AccountId = whitelisted_caller();
+	}: _(RawOrigin::Root, who.clone(), b"Honzon".to_vec(), b"adjust_loan".to_vec(), true)
+	verify {
+		assert!(TransactionPayment::fee_free_calls(&who, (b"Honzon".to_vec(), b"adjust_loan".to_vec())));
+	}
 }
 
 #[cfg(test)]
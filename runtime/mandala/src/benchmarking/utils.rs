@@ -17,19 +17,32 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	AcalaOracle, AccountId, Balance, Currencies, CurrencyId, MinimumCount, OperatorMembershipAcala, Price, Runtime,
+	AcalaOracle, AccountId, AssetRegistry, Balance, Currencies, CurrencyId, MinimumCount, OperatorMembershipAcala,
+	Price, Rate, Ratio, Runtime, StableAsset,
 };
 
+use cumulus_pallet_parachain_system::relay_state_snapshot::MessagingStateSnapshot;
+use cumulus_primitives_core::{AbridgedHrmpChannel, ParaId};
 use frame_benchmarking::account;
 use frame_support::traits::tokens::fungibles;
-use frame_support::{assert_ok, traits::Contains};
+use frame_support::{
+	assert_ok,
+	storage::{storage_prefix, unhashed},
+	traits::Contains,
+};
 use frame_system::RawOrigin;
-use orml_traits::MultiCurrencyExtended;
+use module_asset_registry::AssetMetadata;
+use orml_traits::{Change, MultiCurrencyExtended};
+use primitives::StableAssetPoolId;
 use sp_runtime::{
 	traits::{SaturatedConversion, StaticLookup},
-	DispatchResult,
+	DispatchError, DispatchResult,
+};
+use sp_std::{boxed::Box, prelude::*};
+use xcm::{
+	v1::{Junction::Parachain, Junctions::X1, MultiLocation},
+	VersionedMultiLocation,
 };
-use sp_std::prelude::*;
 
 pub fn lookup_of_account(who: AccountId) -> <<Runtime as frame_system::Config>::Lookup as StaticLookup>::Source {
 	<Runtime as frame_system::Config>::Lookup::unlookup(who)
@@ -60,6 +73,33 @@ pub fn set_balance_fungibles(currency_id: CurrencyId, who: &AccountId, balance:
 	assert_ok!(<orml_tokens::Pallet<Runtime> as fungibles::Mutate<AccountId>>::mint_into(currency_id, who, balance));
 }
 
+/// Create a stable swap pool of `currency_id_a` and `currency_id_b` seeded with `amount` of each,
+/// returns `None` if the runtime has no stable swap.
+pub fn create_stable_swap_pool(
+	currency_id_a: CurrencyId,
+	currency_id_b: CurrencyId,
+	amount: Balance,
+) -> Result<Option<StableAssetPoolId>, DispatchError> {
+	let seeder: AccountId = account("stable_swap_seeder", 0, 0);
+	let fee_recipient: AccountId = account("stable_swap_fee", 0, 0);
+	let pool_id = StableAsset::pool_count();
+	StableAsset::create_pool(
+		RawOrigin::Root.into(),
+		CurrencyId::StableAssetPoolToken(pool_id),
+		vec![currency_id_a, currency_id_b],
+		vec![1u128, 1u128],
+		10000000u128,
+		20000000u128,
+		50000000u128,
+		100u128,
+		fee_recipient,
+	)?;
+	set_balance_fungibles(currency_id_a, &seeder, amount);
+	set_balance_fungibles(currency_id_b, &seeder, amount);
+	StableAsset::mint(RawOrigin::Signed(seeder).into(), pool_id, vec![amount, amount], 0u128)?;
+	Ok(Some(pool_id))
+}
+
 #[cfg(test)]
 pub mod tests {
 	pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -69,3 +109,74 @@ pub mod tests {
 			.into()
 	}
 }
+
+/// Set the risk management params of the collateral type, bypassing the
+/// timelock of the governance updates.
+pub fn set_collateral_params(
+	currency_id: CurrencyId,
+	interest_rate_per_sec: Change<Option<Rate>>,
+	liquidation_ratio: Change<Option<Ratio>>,
+	liquidation_penalty: Change<Option<Rate>>,
+	required_collateral_ratio: Change<Option<Ratio>>,
+	maximum_total_debit_value: Change<Balance>,
+) {
+	module_cdp_engine::CollateralParams::<Runtime>::mutate(currency_id, |params| {
+		if let Change::NewValue(update) = interest_rate_per_sec {
+			params.interest_rate_per_sec = update;
+		}
+		if let Change::NewValue(update) = liquidation_ratio {
+			params.liquidation_ratio = update;
+		}
+		if let Change::NewValue(update) = liquidation_penalty {
+			params.liquidation_penalty = update;
+		}
+		if let Change::NewValue(update) = required_collateral_ratio {
+			params.required_collateral_ratio = update;
+		}
+		if let Change::NewValue(val) = maximum_total_debit_value {
+			params.maximum_total_debit_value = val;
+		}
+	});
+}
+
+/// Open an outbound HRMP channel to the sibling parachain so that the XCM sent by the
+/// benchmarked calls can be queued by `XcmpQueue`.
+pub fn open_hrmp_channel(para_id: ParaId) {
+	// `RelevantMessagingState` is private to `cumulus_pallet_parachain_system`
+	let key = storage_prefix(b"ParachainSystem", b"RelevantMessagingState");
+	let channel = AbridgedHrmpChannel {
+		max_capacity: u32::MAX,
+		max_total_size: u32::MAX,
+		max_message_size: u32::MAX,
+		msg_count: 0,
+		total_size: 0,
+		mqc_head: None,
+	};
+	let mut state: MessagingStateSnapshot = unhashed::get(&key).unwrap_or(MessagingStateSnapshot {
+		dmq_mqc_head: Default::default(),
+		relay_dispatch_queue_size: (0, 0),
+		ingress_channels: vec![],
+		egress_channels: vec![],
+	});
+	if let Err(index) = state.egress_channels.binary_search_by_key(&para_id, |(id, _)| *id) {
+		state.egress_channels.insert(index, (para_id, channel));
+	}
+	unhashed::put(&key, &state);
+}
+
+/// Register a new foreign asset, which can be funded and has the metadata of the decimals.
+pub fn register_foreign_asset(decimals: u8) -> CurrencyId {
+	let foreign_asset_id = module_asset_registry::NextForeignAssetId::<Runtime>::get();
+	let location = VersionedMultiLocation::V1(MultiLocation::new(1, X1(Parachain(2000 + u32::from(foreign_asset_id)))));
+	assert_ok!(AssetRegistry::register_foreign_asset(
+		RawOrigin::Root.into(),
+		Box::new(location),
+		Box::new(AssetMetadata {
+			name: b"Foreign Asset".to_vec(),
+			symbol: b"FA".to_vec(),
+			decimals,
+			minimal_balance: 1,
+		}),
+	));
+	CurrencyId::ForeignAsset(foreign_asset_id)
+}
@@ -0,0 +1,201 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Currencies, CurrencyId, Dex, GetNativeCurrencyId, GetStableCurrencyId, GetStakingCurrencyId,
+	Incentives, Price, Rate, Runtime, TradingPair, Vault, VaultHarvestPeriod,
+};
+
+use super::utils::{feed_price, register_foreign_asset, set_balance};
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_incentives::PoolId;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use sp_runtime::{
+	traits::{One, Zero},
+	DispatchError, DispatchResult, FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const NATIVE: CurrencyId = GetNativeCurrencyId::get();
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+// the LP tokens of the vaults, the rewards of every vault are swapped to both of the paired
+// currencies
+const VAULT_CURRENCY_LIST: [CurrencyId; 2] = [STAKING, NATIVE];
+
+fn inject_liquidity(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> DispatchResult {
+	let maker: AccountId = account("maker", 0, SEED);
+	set_balance(currency_id_a, &maker, 1_000_000 * dollar(currency_id_a));
+	set_balance(currency_id_b, &maker, 1_000_000 * dollar(currency_id_b));
+	let _ = Dex::enable_trading_pair(RawOrigin::Root.into(), currency_id_a, currency_id_b);
+	Dex::add_liquidity(
+		RawOrigin::Signed(maker).into(),
+		currency_id_a,
+		currency_id_b,
+		1_000_000 * dollar(currency_id_a),
+		1_000_000 * dollar(currency_id_b),
+		Default::default(),
+		false,
+	)
+}
+
+fn setup_dex() -> DispatchResult {
+	feed_price(vec![(NATIVE, Price::one()), (STAKING, Price::one())])?;
+	inject_liquidity(STABLECOIN, NATIVE)?;
+	inject_liquidity(STABLECOIN, STAKING)?;
+	inject_liquidity(NATIVE, STAKING)
+}
+
+// open the vault of the LP token of the stable currency and `currency_id`, with a deposit
+// and the unclaimed rewards
+fn setup_vault(currency_id: CurrencyId) -> Result<CurrencyId, DispatchError> {
+	let depositor: AccountId = account("depositor", 0, SEED);
+	let lp_currency_id = TradingPair::from_currency_ids(STABLECOIN, currency_id)
+		.unwrap()
+		.dex_share_currency_id();
+	Vault::open_vault(
+		RawOrigin::Root.into(),
+		lp_currency_id,
+		register_foreign_asset(12),
+		Rate::saturating_from_rational(10, 100),
+	)?;
+
+	set_balance(STABLECOIN, &depositor, 1_000 * dollar(STABLECOIN));
+	set_balance(currency_id, &depositor, 1_000 * dollar(currency_id));
+	Dex::add_liquidity(
+		RawOrigin::Signed(depositor.clone()).into(),
+		STABLECOIN,
+		currency_id,
+		1_000 * dollar(STABLECOIN),
+		1_000 * dollar(currency_id),
+		Default::default(),
+		false,
+	)?;
+	let lp_amount = Currencies::free_balance(lp_currency_id, &depositor);
+	Vault::deposit(RawOrigin::Signed(depositor).into(), lp_currency_id, lp_amount)?;
+
+	orml_rewards::PoolInfos::<Runtime>::mutate(PoolId::Dex(lp_currency_id), |pool_info| {
+		pool_info.rewards.insert(NATIVE, (100 * dollar(NATIVE), 0));
+		pool_info.rewards.insert(STABLECOIN, (100 * dollar(STABLECOIN), 0));
+	});
+	set_balance(
+		NATIVE,
+		&Incentives::account_id(),
+		Currencies::free_balance(NATIVE, &Incentives::account_id()) + 100 * dollar(NATIVE),
+	);
+	set_balance(
+		STABLECOIN,
+		&Incentives::account_id(),
+		Currencies::free_balance(STABLECOIN, &Incentives::account_id()) + 100 * dollar(STABLECOIN),
+	);
+	Ok(lp_currency_id)
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_vault }
+
+	open_vault {
+		let lp_currency_id = TradingPair::from_currency_ids(STABLECOIN, STAKING).unwrap().dex_share_currency_id();
+		let share_currency_id = register_foreign_asset(12);
+	}: _(RawOrigin::Root, lp_currency_id, share_currency_id, Rate::saturating_from_rational(10, 100))
+	verify {
+		assert!(Vault::vaults(lp_currency_id).is_some());
+	}
+
+	set_performance_fee {
+		let lp_currency_id = TradingPair::from_currency_ids(STABLECOIN, STAKING).unwrap().dex_share_currency_id();
+		Vault::open_vault(
+			RawOrigin::Root.into(),
+			lp_currency_id,
+			register_foreign_asset(12),
+			Rate::saturating_from_rational(10, 100),
+		)?;
+	}: _(RawOrigin::Root, lp_currency_id, Rate::saturating_from_rational(20, 100))
+	verify {
+		assert_eq!(Vault::vaults(lp_currency_id).unwrap().performance_fee, Rate::saturating_from_rational(20, 100));
+	}
+
+	deposit {
+		let caller: AccountId = whitelisted_caller();
+		setup_dex()?;
+		let lp_currency_id = setup_vault(STAKING)?;
+		set_balance(STABLECOIN, &caller, 1_000 * dollar(STABLECOIN));
+		set_balance(STAKING, &caller, 1_000 * dollar(STAKING));
+		Dex::add_liquidity(
+			RawOrigin::Signed(caller.clone()).into(),
+			STABLECOIN,
+			STAKING,
+			1_000 * dollar(STABLECOIN),
+			1_000 * dollar(STAKING),
+			Default::default(),
+			false,
+		)?;
+		let lp_amount = Currencies::free_balance(lp_currency_id, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), lp_currency_id, lp_amount)
+	verify {
+		assert!(Currencies::free_balance(lp_currency_id, &caller).is_zero());
+	}
+
+	withdraw {
+		let depositor: AccountId = account("depositor", 0, SEED);
+		setup_dex()?;
+		let lp_currency_id = setup_vault(STAKING)?;
+		let share_currency_id = Vault::vaults(lp_currency_id).unwrap().share_currency_id;
+		let shares = Currencies::free_balance(share_currency_id, &depositor);
+	}: _(RawOrigin::Signed(depositor.clone()), lp_currency_id, shares)
+	verify {
+		assert!(Currencies::free_balance(share_currency_id, &depositor).is_zero());
+	}
+
+	// worst: the rewards are swapped to both of the currencies of the LP token and compounded
+	harvest {
+		let caller: AccountId = whitelisted_caller();
+		setup_dex()?;
+		let lp_currency_id = setup_vault(STAKING)?;
+		let total_assets = Vault::vaults(lp_currency_id).unwrap().total_assets;
+	}: _(RawOrigin::Signed(caller), lp_currency_id)
+	verify {
+		assert!(Vault::vaults(lp_currency_id).unwrap().total_assets > total_assets);
+	}
+
+	// harvest `c` vaults at the end of the harvest period
+	on_initialize {
+		let c in 0 .. VAULT_CURRENCY_LIST.len() as u32;
+		setup_dex()?;
+		for currency_id in VAULT_CURRENCY_LIST.iter().take(c as usize) {
+			setup_vault(*currency_id)?;
+		}
+	}: {
+		Vault::on_initialize(VaultHarvestPeriod::get());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
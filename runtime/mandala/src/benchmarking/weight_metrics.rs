@@ -0,0 +1,64 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{Runtime, System, WeightMetrics, WeightMetricsBlocksPerDay, WeightMetricsKeepDays};
+
+use frame_support::traits::OnInitialize;
+use module_weight_metrics::{PalletWeights, WeightStats};
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+runtime_benchmarks! {
+	{ Runtime, module_weight_metrics }
+
+	// remove the stats of `c` pallets of the expired day
+	on_initialize {
+		let c in 0 .. 100;
+		for i in 0 .. c {
+			PalletWeights::<Runtime>::insert(0, i.to_le_bytes().to_vec(), WeightStats {
+				call_count: 1,
+				weight: 1_000_000,
+			});
+		}
+		let now = WeightMetricsBlocksPerDay::get() * WeightMetricsKeepDays::get();
+	}: {
+		WeightMetrics::on_initialize(now);
+	}
+	verify {
+		assert_eq!(PalletWeights::<Runtime>::iter_prefix(0).count(), 0);
+	}
+
+	// record the weight of a dispatched call
+	record {
+		let pallet_name = b"Dex".to_vec();
+	}: {
+		WeightMetrics::record(&pallet_name, 1_000_000);
+	}
+	verify {
+		assert_eq!(WeightMetrics::pallet_weights(WeightMetrics::day_index(System::block_number()), pallet_name).call_count, 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,76 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, GetStakingCurrencyId, Runtime, System, XcmCollateral,
+	XcmCollateralConfirmationTimeout,
+};
+
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use module_xcm_collateral::RequestExpiries;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_xcm_collateral }
+
+	request_deposit {
+		let caller: AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), 100 * dollar(STAKING))
+	verify {
+		assert!(XcmCollateral::deposit_requests(0).is_some());
+	}
+
+	cancel_deposit {
+		let caller: AccountId = whitelisted_caller();
+		XcmCollateral::request_deposit(RawOrigin::Signed(caller.clone()).into(), 100 * dollar(STAKING))?;
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(XcmCollateral::deposit_requests(0).is_none());
+	}
+
+	// expire `n` deposit requests
+	on_initialize {
+		let n in 0 .. 100;
+		for i in 0 .. n {
+			let requester: AccountId = account("requester", i, SEED);
+			XcmCollateral::request_deposit(RawOrigin::Signed(requester).into(), 100 * dollar(STAKING))?;
+		}
+		let expire_at = System::block_number() + XcmCollateralConfirmationTimeout::get();
+	}: {
+		XcmCollateral::on_initialize(expire_at);
+	}
+	verify {
+		assert_eq!(RequestExpiries::<Runtime>::iter_prefix(expire_at).count(), 0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
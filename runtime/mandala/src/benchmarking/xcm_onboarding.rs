@@ -0,0 +1,88 @@
+// This file is part of Acala.
+
+// Copyright (C) 2020-2021 Acala Foundation.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, ExistentialDeposits, GetStableCurrencyId, GetStakingCurrencyId, Loans, Price, Rate,
+	Ratio, Runtime, XcmOnboarding,
+};
+
+use super::utils::{feed_price, set_balance, set_collateral_params};
+use frame_benchmarking::whitelisted_caller;
+use frame_system::RawOrigin;
+use module_xcm_onboarding::OnboardingIntent;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::{Change, GetByKey};
+use sp_runtime::{traits::One, FixedPointNumber};
+use sp_std::prelude::*;
+
+const STABLECOIN: CurrencyId = GetStableCurrencyId::get();
+const STAKING: CurrencyId = GetStakingCurrencyId::get();
+
+runtime_benchmarks! {
+	{ Runtime, module_xcm_onboarding }
+
+	authorize_onboarding {
+		let caller: AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller.clone()), 100 * dollar(STABLECOIN))
+	verify {
+		assert_eq!(XcmOnboarding::onboarding_authorizations(&caller), Some(100 * dollar(STABLECOIN)));
+	}
+
+	revoke_onboarding {
+		let caller: AccountId = whitelisted_caller();
+		XcmOnboarding::authorize_onboarding(RawOrigin::Signed(caller.clone()).into(), 100 * dollar(STABLECOIN))?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(XcmOnboarding::onboarding_authorizations(&caller).is_none());
+	}
+
+	// worst: the deposited asset is locked as collateral and the stable currency is minted
+	onboard {
+		let caller: AccountId = whitelisted_caller();
+		let mint_value = 100 * dollar(STABLECOIN);
+		let collateral_amount = Price::saturating_from_rational(dollar(STAKING), dollar(STABLECOIN))
+			.saturating_mul_int(10 * mint_value);
+
+		feed_price(vec![(STAKING, Price::one())])?;
+		set_collateral_params(
+			STAKING,
+			Change::NoChange,
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
+			Change::NewValue(100 * mint_value),
+		);
+		XcmOnboarding::authorize_onboarding(RawOrigin::Signed(caller.clone()).into(), mint_value)?;
+		// the asset is deposited to the account by the transactor before onboarding
+		set_balance(STAKING, &caller, collateral_amount + ExistentialDeposits::get(&STAKING));
+	}: {
+		XcmOnboarding::onboard(&caller, collateral_amount, OnboardingIntent { mint_value });
+	}
+	verify {
+		assert_eq!(Loans::positions(STAKING, &caller).collateral, collateral_amount);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
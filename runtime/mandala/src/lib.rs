@@ -43,7 +43,7 @@ pub use frame_support::{
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
-		DispatchClass, IdentityFee, Weight,
+		DispatchClass, GetDispatchInfo, IdentityFee, Weight,
 	},
 	PalletId, RuntimeDebug, StorageValue,
 };
@@ -51,10 +51,13 @@ use frame_system::{EnsureRoot, RawOrigin};
 use hex_literal::hex;
 use module_asset_registry::{EvmErc20InfoMapping, XcmForeignAssetIdMapping};
 use module_currencies::{BasicCurrencyAdapter, Currency};
-use module_evm::{CallInfo, CreateInfo, EvmTask, Runner};
+use module_evm::{BlockLog, CallInfo, CreateInfo, EvmTask, LogFilter, Runner};
 use module_evm_accounts::EvmAddressMapping;
 use module_relaychain::RelayChainCallBuilder;
-use module_support::{DispatchableTask, ExchangeRateProvider, ForeignAssetIdMapping};
+use module_support::{
+	AggregatedSwapAdapter, DispatchableTask, DustHook, ExchangeRateProvider, ForeignAssetIdMapping, HookRegistry,
+	NewEraHook, OrderedHook, StableAssetPool,
+};
 use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 use scale_info::TypeInfo;
 
@@ -76,7 +79,7 @@ use sp_runtime::{
 		AccountIdConversion, BadOrigin, BlakeTwo256, Block as BlockT, Convert, SaturatedConversion, StaticLookup,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, DispatchResult, FixedPointNumber,
+	ApplyExtrinsicResult, DispatchResult, FixedPointNumber, TransactionOutcome,
 };
 use sp_std::prelude::*;
 
@@ -111,18 +114,20 @@ pub use authority::AuthorityConfigImpl;
 pub use constants::{fee::*, time::*};
 pub use primitives::{
 	evm::EstimateResourcesRequest, AccountId, AccountIndex, Address, Amount, AuctionId, AuthoritysOriginId, Balance,
-	BlockNumber, CurrencyId, DataProviderId, EraIndex, Hash, Moment, Nonce, ReserveIdentifier, Share, Signature,
-	TokenSymbol, TradingPair,
+	BlockNumber, CircuitBreakerId, CircuitBreakerReason, CurrencyId, DataProviderId, EraIndex, Hash, Moment, Nonce,
+	ReserveIdentifier, RevenueSource, Share, Signature, StableAssetPoolId, TokenSymbol, TradingPair,
 };
 pub use runtime_common::{
 	cent, dollar, microcent, millicent, CurveFeeModel, EnsureRootOrAllGeneralCouncil,
-	EnsureRootOrAllTechnicalCommittee, EnsureRootOrHalfFinancialCouncil, EnsureRootOrHalfGeneralCouncil,
+	EnsureRootOrAllTechnicalCommittee, EnsureRootOrHalfFinancialCouncil,
+	EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil, EnsureRootOrHalfGeneralCouncil,
 	EnsureRootOrHalfHomaCouncil, EnsureRootOrOneGeneralCouncil, EnsureRootOrOneThirdsTechnicalCommittee,
-	EnsureRootOrThreeFourthsGeneralCouncil, EnsureRootOrTwoThirdsGeneralCouncil,
-	EnsureRootOrTwoThirdsTechnicalCommittee, ExchangeRate, FinancialCouncilInstance,
-	FinancialCouncilMembershipInstance, GasToWeight, GeneralCouncilInstance, GeneralCouncilMembershipInstance,
-	HomaCouncilInstance, HomaCouncilMembershipInstance, OffchainSolutionWeightLimit, OperatorMembershipInstanceAcala,
-	Price, ProxyType, Rate, Ratio, RelayChainBlockNumberProvider, RelayChainSubAccountId, RuntimeBlockLength,
+	EnsureRootOrThreeFourthsGeneralCouncil, EnsureRootOrThreeFourthsGeneralCouncilOrHalfParameterCouncil,
+	EnsureRootOrTwoThirdsGeneralCouncil, EnsureRootOrTwoThirdsTechnicalCommittee, ExchangeRate,
+	FinancialCouncilInstance, FinancialCouncilMembershipInstance, GasToWeight, GeneralCouncilInstance,
+	GeneralCouncilMembershipInstance, HomaCouncilInstance, HomaCouncilMembershipInstance, OffchainSolutionWeightLimit,
+	OperatorMembershipInstanceAcala, ParameterCouncilInstance, ParameterCouncilMembershipInstance, Price, ProxyType,
+	Rate, Ratio, RelayChainBlockNumberProvider, RelayChainRandomness, RelayChainSubAccountId, RuntimeBlockLength,
 	RuntimeBlockWeights, SystemContractsFilter, TechnicalCommitteeInstance, TechnicalCommitteeMembershipInstance,
 	TimeStampedPrice, ACA, AUSD, DOT, LDOT, RENBTC,
 };
@@ -177,6 +182,16 @@ parameter_types! {
 	pub const TreasuryReservePalletId: PalletId = PalletId(*b"aca/reve");
 	pub const PhragmenElectionPalletId: LockIdentifier = *b"aca/phre";
 	pub const NftPalletId: PalletId = PalletId(*b"aca/aNFT");
+	pub const CdpNftPalletId: PalletId = PalletId(*b"aca/cnft");
+	pub const DebitBackstopPalletId: PalletId = PalletId(*b"aca/dbbs");
+	pub const SavingsPalletId: PalletId = PalletId(*b"aca/savg");
+	pub const PSMPalletId: PalletId = PalletId(*b"aca/pegs");
+	pub const DcaPalletId: PalletId = PalletId(*b"aca/dcao");
+	pub const KeeperPalletId: PalletId = PalletId(*b"aca/kpjb");
+	pub const VaultPalletId: PalletId = PalletId(*b"aca/vult");
+	pub const PositionMigrationPalletId: PalletId = PalletId(*b"aca/psmg");
+	pub const LiquidationInsurancePalletId: PalletId = PalletId(*b"aca/lqin");
+	pub const PublicGoodsPalletId: PalletId = PalletId(*b"aca/pgfd");
 	pub const NomineesElectionId: LockIdentifier = *b"aca/nome";
 	pub UnreleasedNativeVaultAccountId: AccountId = PalletId(*b"aca/urls").into_account();
 	// Ecosystem modules
@@ -200,6 +215,14 @@ pub fn get_all_module_accounts() -> Vec<AccountId> {
 		ZeroAccountId::get(),
 		UnreleasedNativeVaultAccountId::get(),
 		StableAssetPalletId::get().into_account(),
+		PublicGoodsPalletId::get().into_account(),
+		DebitBackstopPalletId::get().into_account(),
+		SavingsPalletId::get().into_account(),
+		PSMPalletId::get().into_account(),
+		DcaPalletId::get().into_account(),
+		KeeperPalletId::get().into_account(),
+		PositionMigrationPalletId::get().into_account(),
+		LiquidationInsurancePalletId::get().into_account(),
 	]
 }
 
@@ -214,6 +237,41 @@ impl Contains<Call> for BaseCallFilter {
 	fn contains(call: &Call) -> bool {
 		!module_transaction_pause::PausedTransactionFilter::<Runtime>::contains(call)
 			&& !matches!(call, Call::Democracy(pallet_democracy::Call::propose { .. }),)
+			&& match call {
+				// the parameter council can only propose the whitelisted parameter updates
+				Call::ParameterCouncil(pallet_collective::Call::propose { proposal, .. })
+				| Call::ParameterCouncil(pallet_collective::Call::execute { proposal, .. }) => {
+					ParameterCouncilCallFilter::contains(proposal)
+				}
+				_ => true,
+			}
+	}
+}
+
+/// The calls the parameter council is allowed to dispatch: the risk params of
+/// cdp-engine, the auction params of cdp-treasury and the emission of
+/// incentives.
+pub struct ParameterCouncilCallFilter;
+impl Contains<Call> for ParameterCouncilCallFilter {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::CdpEngine(module_cdp_engine::Call::set_global_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_collateral_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::cancel_pending_risk_params { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_annual_interest_rate { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_interest_rate_curve { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_soft_debit_cap { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_minimum_debit_value { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_liquidation_grace { .. })
+				| Call::CdpEngine(module_cdp_engine::Call::set_max_debit_per_account { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_size { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_value { .. })
+				| Call::CdpTreasury(module_cdp_treasury::Call::set_collateral_sale_params { .. })
+				| Call::Incentives(module_incentives::Call::update_incentive_rewards { .. })
+				| Call::Incentives(module_incentives::Call::update_dex_saving_rewards { .. })
+				| Call::Incentives(module_incentives::Call::update_claim_reward_deduction_rates { .. })
+		)
 	}
 }
 
@@ -292,6 +350,9 @@ parameter_types! {
 	pub const MaxInvulnerables: u32 = 50;
 	pub const KickPenaltySessionLength: u32 = 8;
 	pub const CollatorKickThreshold: Permill = Permill::from_percent(50);
+	pub CollatorLPBondCurrencyId: CurrencyId = TradingPair::from_currency_ids(AUSD, ACA).unwrap().dex_share_currency_id();
+	pub const CollatorLPBondHaircut: Permill = Permill::from_percent(20);
+	pub const CollatorMaxLPBondProportion: Permill = Permill::from_percent(50);
 }
 
 impl module_collator_selection::Config for Runtime {
@@ -305,6 +366,13 @@ impl module_collator_selection::Config for Runtime {
 	type MaxInvulnerables = MaxInvulnerables;
 	type KickPenaltySessionLength = KickPenaltySessionLength;
 	type CollatorKickThreshold = CollatorKickThreshold;
+	type MultiCurrency = Currencies;
+	type LPBondCurrencyId = CollatorLPBondCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type LPBondHaircut = CollatorLPBondHaircut;
+	type MaxLPBondProportion = CollatorMaxLPBondProportion;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type DEX = Dex;
 	type WeightInfo = weights::module_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -357,6 +425,7 @@ parameter_types! {
 	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
 	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(1, 100_000);
 	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000_000u128);
+	pub const MaxFeeFreeTxPerBlock: u32 = 20;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -484,6 +553,36 @@ impl pallet_membership::Config<TechnicalCommitteeMembershipInstance> for Runtime
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const ParameterCouncilMotionDuration: BlockNumber = DAYS;
+	pub const ParameterCouncilMaxProposals: u32 = 100;
+	pub const ParameterCouncilMaxMembers: u32 = 30;
+}
+
+impl pallet_collective::Config<ParameterCouncilInstance> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = ParameterCouncilMotionDuration;
+	type MaxProposals = ParameterCouncilMaxProposals;
+	type MaxMembers = ParameterCouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+impl pallet_membership::Config<ParameterCouncilMembershipInstance> for Runtime {
+	type Event = Event;
+	type AddOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type RemoveOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type SwapOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type ResetOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type PrimeOrigin = EnsureRootOrTwoThirdsGeneralCouncil;
+	type MembershipInitialized = ParameterCouncil;
+	type MembershipChanged = ParameterCouncil;
+	type MaxMembers = ParameterCouncilMaxMembers;
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const OracleMaxMembers: u32 = 50;
 }
@@ -734,7 +833,7 @@ parameter_types! {
 type AcalaDataProvider = orml_oracle::Instance1;
 impl orml_oracle::Config<AcalaDataProvider> for Runtime {
 	type Event = Event;
-	type OnNewData = ();
+	type OnNewData = Prices;
 	type CombineData = orml_oracle::DefaultCombineData<Runtime, MinimumCount, ExpiresIn, AcalaDataProvider>;
 	type Time = Timestamp;
 	type OracleKey = CurrencyId;
@@ -813,6 +912,20 @@ parameter_types! {
 	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account();
 }
 
+parameter_types! {
+	pub const TransferDustOrder: u32 = 0;
+	pub TransferDustWeight: Weight = RocksDbWeight::get().reads_writes(2, 2);
+	pub DustHookWeightBudget: Weight = RuntimeBlockWeights::get().max_block / 100;
+}
+
+pub type OnTokensDustHooks = HookRegistry<(
+	OrderedHook<
+		DustHook<orml_tokens::TransferDust<Runtime, TreasuryAccount>, TransferDustWeight>,
+		TransferDustOrder,
+		DustHookWeightBudget,
+	>,
+)>;
+
 impl orml_tokens::Config for Runtime {
 	type Event = Event;
 	type Balance = Balance;
@@ -820,18 +933,20 @@ impl orml_tokens::Config for Runtime {
 	type CurrencyId = CurrencyId;
 	type WeightInfo = weights::orml_tokens::WeightInfo<Runtime>;
 	type ExistentialDeposits = ExistentialDeposits;
-	type OnDust = orml_tokens::TransferDust<Runtime, TreasuryAccount>;
+	type OnDust = OnTokensDustHooks;
 	type MaxLocks = MaxLocks;
 	type DustRemovalWhitelist = DustRemovalWhitelist;
 }
 
 parameter_types! {
 	pub StableCurrencyFixedPrice: Price = Price::saturating_from_rational(1, 1);
+	pub const PricesTwapWindow: BlockNumber = 10 * MINUTES;
+	pub const PriceDeviationReportInterval: BlockNumber = HOURS;
 }
 
 impl module_prices::Config for Runtime {
 	type Event = Event;
-	type Source = AggregatedDataProvider;
+	type Source = module_sandbox::ForcedPriceProvider<Runtime, AggregatedDataProvider>;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
 	type GetStakingCurrencyId = GetStakingCurrencyId;
@@ -841,6 +956,10 @@ impl module_prices::Config for Runtime {
 	type DEX = Dex;
 	type Currency = Currencies;
 	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
+	type DEXPriceCumulative = Dex;
+	type TwapWindow = PricesTwapWindow;
+	type DeviationReportInterval = PriceDeviationReportInterval;
+	type UnsignedPriority = runtime_common::PricesUnsignedPriority;
 	type WeightInfo = weights::module_prices::WeightInfo<Runtime>;
 }
 
@@ -866,6 +985,7 @@ impl module_currencies::Config for Runtime {
 	type EVMBridge = EVMBridge;
 	type SweepOrigin = EnsureRootOrOneGeneralCouncil;
 	type OnDust = module_currencies::TransferDust<Runtime, TreasuryAccount>;
+	type OnTransfer = StablecoinMetrics;
 }
 
 pub struct EnsureRootOrTreasury;
@@ -927,11 +1047,16 @@ parameter_types! {
 	pub MinimumIncrementSize: Rate = Rate::saturating_from_rational(2, 100);
 	pub const AuctionTimeToClose: BlockNumber = 15 * MINUTES;
 	pub const AuctionDurationSoftCap: BlockNumber = 2 * HOURS;
+	pub CleanupBounty: Balance = cent(ACA);
+	pub AuctionManagerMinimumSettlementWeight: Weight = RuntimeBlockWeights::get().max_block / 100;
 	pub DefaultSwapParitalPathList: Vec<Vec<CurrencyId>> = vec![
 		vec![GetStableCurrencyId::get()],
 	];
 }
 
+pub type TreasuryCleanupBounty =
+	module_support::TreasuryCleanupBounty<Currencies, GetNativeCurrencyId, TreasuryAccount, CleanupBounty>;
+
 impl module_auction_manager::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
@@ -945,7 +1070,11 @@ impl module_auction_manager::Config for Runtime {
 	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
 	type UnsignedPriority = runtime_common::AuctionManagerUnsignedPriority;
 	type EmergencyShutdown = EmergencyShutdown;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type CleanupBounty = TreasuryCleanupBounty;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumSettlementWeight = AuctionManagerMinimumSettlementWeight;
 	type WeightInfo = weights::module_auction_manager::WeightInfo<Runtime>;
 }
 
@@ -957,6 +1086,8 @@ impl module_loans::Config for Runtime {
 	type CDPTreasury = CdpTreasury;
 	type PalletId = LoansPalletId;
 	type OnUpdateLoan = module_incentives::OnUpdateLoan<Runtime>;
+	type OnUpdateDebit = module_cdp_engine::RecordDebitSnapshot<Runtime>;
+	type OnPositionUpdated = module_cdp_engine::OnPositionUpdated<Runtime>;
 }
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
@@ -992,6 +1123,7 @@ where
 			frame_system::CheckWeight::<Runtime>::new(),
 			module_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
 			module_evm::SetEvmOrigin::<Runtime>::new(),
+			module_weight_metrics::RecordWeightMetrics::<Runtime>::new(),
 		);
 		let raw_payload = SignedPayload::new(call, extra)
 			.map_err(|e| {
@@ -1020,11 +1152,20 @@ where
 
 parameter_types! {
 	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![DOT, LDOT, RENBTC];
+	pub DebtDenominationCurrencyIds: Vec<CurrencyId> = vec![];
 	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(110, 100);
 	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 10);
 	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(5, 100);
 	pub MinimumDebitValue: Balance = dollar(AUSD);
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(15, 100);
+	pub const DebitExchangeRateCheckpointInterval: BlockNumber = DAYS;
+	pub const MaxDebitExchangeRateCheckpoints: u32 = 366;
+	pub const InterestRateCurvePeriod: BlockNumber = DAYS;
+	pub StopLossExecutionFee: Balance = dollar(AUSD);
+	pub const RiskParamsUpdateDelay: BlockNumber = HOURS;
+	pub LiquidationKeeperBounty: Balance = 10 * dollar(AUSD);
+	pub LiquidationKeeperBountyShare: Ratio = Ratio::saturating_from_rational(5, 1000);
+	pub MaxLiquidationBountyPerBlock: Balance = 1000 * dollar(AUSD);
 }
 
 impl module_cdp_engine::Config for Runtime {
@@ -1037,26 +1178,177 @@ impl module_cdp_engine::Config for Runtime {
 	type MinimumDebitValue = MinimumDebitValue;
 	type GetStableCurrencyId = GetStableCurrencyId;
 	type CDPTreasury = CdpTreasury;
-	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil;
+	type EmergencyOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type UnsignedPriority = runtime_common::CdpEngineUnsignedPriority;
+	type UnsignedLongevity = runtime_common::CdpEngineUnsignedLongevity;
 	type EmergencyShutdown = EmergencyShutdown;
 	type UnixTime = Timestamp;
 	type DefaultSwapParitalPathList = DefaultSwapParitalPathList;
+	type DEX = Dex;
+	type DEXPriceCumulative = Dex;
+	type DebitExchangeRateCheckpointInterval = DebitExchangeRateCheckpointInterval;
+	type MaxDebitExchangeRateCheckpoints = MaxDebitExchangeRateCheckpoints;
+	type InterestRateCurvePeriod = InterestRateCurvePeriod;
+	type OnLiquidation = LiquidationInsurance;
+	type StopLossExecutionFee = StopLossExecutionFee;
+	type CircuitBreakerRecorder = CircuitBreaker;
+	type RiskParamsUpdateDelay = RiskParamsUpdateDelay;
+	type LiquidationKeeperBounty = LiquidationKeeperBounty;
+	type LiquidationKeeperBountyShare = LiquidationKeeperBountyShare;
+	type MaxLiquidationBountyPerBlock = MaxLiquidationBountyPerBlock;
 	type WeightInfo = weights::module_cdp_engine::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub DepositPerAuthorization: Balance = dollar(ACA);
+	pub const MaxMergeLoans: u32 = 10;
 }
 
 impl module_honzon::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type DepositPerAuthorization = DepositPerAuthorization;
+	type MaxMergeLoans = MaxMergeLoans;
 	type WeightInfo = weights::module_honzon::WeightInfo<Runtime>;
 }
 
+impl module_cdp_nft::Config for Runtime {
+	type Event = Event;
+	type PalletId = CdpNftPalletId;
+	type EmergencyShutdown = EmergencyShutdown;
+	type WeightInfo = weights::module_cdp_nft::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const DebitBackstopWindow: BlockNumber = HOURS;
+	pub DebitBackstopMinimumCommitment: Balance = 1_000 * dollar(AUSD);
+	pub const DebitBackstopMaxProviders: u32 = 50;
+	// 10% of the committed aUSD per year, paid in ACA
+	pub DebitBackstopRewardRate: Rate = Rate::saturating_from_rational(1, 10 * 365 * DAYS);
+}
+
+impl module_debit_backstop::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type BackstopWindow = DebitBackstopWindow;
+	type MinimumCommitment = DebitBackstopMinimumCommitment;
+	type MaxProviders = DebitBackstopMaxProviders;
+	type RewardRate = DebitBackstopRewardRate;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type TreasuryAccount = TreasuryAccount;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = DebitBackstopPalletId;
+	type WeightInfo = weights::module_debit_backstop::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub CdpTreasuryAccount: AccountId = CDPTreasuryPalletId::get().into_account();
+	pub InsuranceFundAccount: AccountId = LiquidationInsurancePalletId::get().into_account();
+}
+
+impl module_cdp_insurance_fund::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type InsuranceFundAccount = InsuranceFundAccount;
+	type WeightInfo = weights::module_cdp_insurance_fund::WeightInfo<Runtime>;
+}
+
+impl module_savings::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = SavingsPalletId;
+	type WeightInfo = weights::module_savings::WeightInfo<Runtime>;
+}
+
+pub struct EnsureSiblingParachain;
+impl EnsureOrigin<Origin> for EnsureSiblingParachain {
+	type Success = ParaId;
+
+	fn try_origin(o: Origin) -> Result<Self::Success, Origin> {
+		Into::<Result<cumulus_pallet_xcm::Origin, Origin>>::into(o).and_then(|o| match o {
+			cumulus_pallet_xcm::Origin::SiblingParachain(id) => Ok(id),
+			r => Err(Origin::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> Origin {
+		Origin::from(cumulus_pallet_xcm::Origin::SiblingParachain(Default::default()))
+	}
+}
+
+pub struct SiblingSovereignAccount;
+impl Convert<ParaId, AccountId> for SiblingSovereignAccount {
+	fn convert(id: ParaId) -> AccountId {
+		Sibling::from(id).into_account()
+	}
+}
+
+parameter_types! {
+	pub PositionMigrationTransactFee: MultiAsset = (MultiLocation::parent(), cent(DOT)).into();
+	pub const PositionMigrationAcknowledgementTimeout: BlockNumber = 2 * HOURS;
+	pub const PositionMigrationTransactWeight: Weight = WEIGHT_PER_SECOND / 10;
+}
+
+impl module_position_migration::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type Call = Call;
+	type XcmSender = XcmRouter;
+	type SiblingOrigin = EnsureSiblingParachain;
+	type SiblingSovereignAccount = SiblingSovereignAccount;
+	type SelfParaId = ParachainInfo;
+	type CurrencyIdConvert = CurrencyIdConvert;
+	type AccountIdToMultiLocation = AccountIdToMultiLocation;
+	type TransactFee = PositionMigrationTransactFee;
+	type AcknowledgementTimeout = PositionMigrationAcknowledgementTimeout;
+	type TransactWeight = PositionMigrationTransactWeight;
+	type PalletId = PositionMigrationPalletId;
+	type WeightInfo = weights::module_position_migration::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const LiquidationInsuranceCoveragePeriod: BlockNumber = 30 * DAYS;
+}
+
+impl module_liquidation_insurance::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CoveragePeriod = LiquidationInsuranceCoveragePeriod;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = LiquidationInsurancePalletId;
+	type WeightInfo = weights::module_liquidation_insurance::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const SandboxMaxFastForwardEras: u32 = 28;
+	pub const SandboxMaxLiquidationWaveSize: u32 = 100;
+	pub const SandboxMaxLiquidationWaveScan: u32 = 1000;
+}
+
+impl module_sandbox::Config for Runtime {
+	type Event = Event;
+	type SandboxOrigin = EnsureRoot<AccountId>;
+	type OnFastForwardEra = module_polkadot_bridge::ForceNewEra<Runtime>;
+	type OnPriceForced = Prices;
+	type MaxFastForwardEras = SandboxMaxFastForwardEras;
+	type MaxLiquidationWaveSize = SandboxMaxLiquidationWaveSize;
+	type MaxLiquidationWaveScan = SandboxMaxLiquidationWaveScan;
+	type WeightInfo = weights::module_sandbox::WeightInfo<Runtime>;
+}
+
 impl module_emergency_shutdown::Config for Runtime {
 	type Event = Event;
 	type CollateralCurrencyIds = CollateralCurrencyIds;
@@ -1064,12 +1356,47 @@ impl module_emergency_shutdown::Config for Runtime {
 	type CDPTreasury = CdpTreasury;
 	type AuctionManagerHandler = AuctionManager;
 	type ShutdownOrigin = EnsureRootOrHalfGeneralCouncil;
+	type StableCurrencyReserve = PSM;
 	type WeightInfo = weights::module_emergency_shutdown::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const PegMonitorTwapPeriod: BlockNumber = HOURS;
+}
+
+impl module_peg_monitor::Config for Runtime {
+	type Event = Event;
+	type DEX = Dex;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type ReferenceCurrencyId = CashCurrencyId;
+	type TwapPeriod = PegMonitorTwapPeriod;
+	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type WeightInfo = weights::module_peg_monitor::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const PSMCapPeriod: BlockNumber = DAYS;
+}
+
+impl module_psm::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type FeeProvider = PegMonitor;
+	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
+	type CapPeriod = PSMCapPeriod;
+	type EmergencyShutdown = EmergencyShutdown;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = PSMPalletId;
+	type WeightInfo = weights::module_psm::WeightInfo<Runtime>;
+}
+
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (1, 1000);	// 0.1%
 	pub const TradingPathLimit: u32 = 4;
+	pub const MaxBatchSwapOrders: u32 = 100;
 	pub EnabledTradingPairs: Vec<TradingPair> = vec![
 		TradingPair::from_currency_ids(AUSD, ACA).unwrap(),
 		TradingPair::from_currency_ids(AUSD, DOT).unwrap(),
@@ -1083,15 +1410,118 @@ impl module_dex::Config for Runtime {
 	type Currency = Currencies;
 	type GetExchangeFee = GetExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
+	type MaxBatchSwapOrders = MaxBatchSwapOrders;
 	type PalletId = DEXPalletId;
 	type Erc20InfoMapping = EvmErc20InfoMapping<Runtime>;
 	type DEXIncentives = Incentives;
 	type WeightInfo = weights::module_dex::WeightInfo<Runtime>;
 	type ListingOrigin = EnsureRootOrHalfGeneralCouncil;
+	type StableAsset = StableAssetAdapter;
+	type ProtocolFeeReceiver = CdpTreasuryAccount;
+	type OnProtocolFee = CdpTreasury;
+}
+
+parameter_types! {
+	pub DcaExecutionFee: Balance = 10 * cent(ACA);
+	pub const DcaMinExecutionInterval: BlockNumber = HOURS;
+	pub const DcaMaxOrdersPerAccount: u32 = 10;
+	pub const DcaMaxExecutionsPerBlock: u32 = 20;
+}
+
+impl module_dca::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type DEX = Dex;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type ExecutionFee = DcaExecutionFee;
+	type TreasuryAccount = TreasuryAccount;
+	type MinExecutionInterval = DcaMinExecutionInterval;
+	type MaxOrdersPerAccount = DcaMaxOrdersPerAccount;
+	type MaxExecutionsPerBlock = DcaMaxExecutionsPerBlock;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type PalletId = DcaPalletId;
+	type WeightInfo = weights::module_dca::WeightInfo<Runtime>;
+}
+
+/// The conditions of the keeper jobs.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum KeeperJobCondition {
+	/// Met at every execution interval.
+	Always,
+	/// Met when the collateral ratio of the CDP of the job owner is below the
+	/// ratio.
+	CollateralRatioBelow(CurrencyId, Ratio),
+}
+
+impl module_support::KeeperCondition<AccountId> for KeeperJobCondition {
+	fn is_met(&self, who: &AccountId) -> bool {
+		match self {
+			KeeperJobCondition::Always => true,
+			KeeperJobCondition::CollateralRatioBelow(currency_id, ratio) => {
+				let module_loans::Position { collateral, debit } = Loans::positions(currency_id, who);
+				if debit == 0 {
+					return false;
+				}
+				CdpEngine::get_collateral_price(*currency_id).map_or(false, |price| {
+					let collateral = CdpEngine::vault_collateral(*currency_id, who, collateral);
+					CdpEngine::calculate_collateral_ratio(*currency_id, collateral, debit, price) < *ratio
+				})
+			}
+		}
+	}
+
+	fn is_met_weight(&self) -> Weight {
+		match self {
+			KeeperJobCondition::Always => 0,
+			// the position, the price and the collateral params
+			KeeperJobCondition::CollateralRatioBelow(..) => RocksDbWeight::get().reads(8),
+		}
+	}
+}
+
+/// The calls allowed in the keeper jobs.
+pub struct KeeperCallFilter;
+impl Contains<Call> for KeeperCallFilter {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::Honzon(..) | Call::Incentives(..) | Call::Dex(..) | Call::Dca(..)
+		)
+	}
+}
+
+parameter_types! {
+	pub KeeperFee: Balance = 10 * cent(ACA);
+	pub const KeeperMinExecutionInterval: BlockNumber = 10;
+	pub KeeperMaxCallWeight: Weight = RuntimeBlockWeights::get().max_block / 50;
+	pub const KeeperMaxJobsPerAccount: u32 = 10;
+	pub KeeperMaxConditionWeight: Weight = RocksDbWeight::get().reads(8);
+}
+
+impl module_keeper::Config for Runtime {
+	type Event = Event;
+	type Condition = KeeperJobCondition;
+	type MaxConditionWeight = KeeperMaxConditionWeight;
+	type Call = Call;
+	type CallFilter = KeeperCallFilter;
+	type Currency = Currencies;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type KeeperFee = KeeperFee;
+	type MinExecutionInterval = KeeperMinExecutionInterval;
+	type MaxCallWeight = KeeperMaxCallWeight;
+	type MaxJobsPerAccount = KeeperMaxJobsPerAccount;
+	type PalletId = KeeperPalletId;
+	type UnsignedPriority = runtime_common::KeeperUnsignedPriority;
+	type WeightInfo = weights::module_keeper::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	pub const MaxAuctionsCount: u32 = 50;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const RevenuePeriod: BlockNumber = 7 * DAYS;
+	pub const OffsetPeriod: BlockNumber = MINUTES;
+	pub CdpTreasuryMinimumIdleWorkWeight: Weight = RuntimeBlockWeights::get().max_block / 100;
 	pub HonzonTreasuryAccount: AccountId = HonzonTreasuryPalletId::get().into_account();
 }
 
@@ -1099,18 +1529,81 @@ impl module_cdp_treasury::Config for Runtime {
 	type Event = Event;
 	type Currency = Currencies;
 	type GetStableCurrencyId = GetStableCurrencyId;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
 	type AuctionManagerHandler = AuctionManager;
-	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncilOrHalfParameterCouncil;
 	type DEX = Dex;
+	type Swap = AggregatedSwapAdapter<Dex, StableAssetAdapter>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DebtDenominationCurrencyIds = DebtDenominationCurrencyIds;
 	type MaxAuctionsCount = MaxAuctionsCount;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
 	type PalletId = CDPTreasuryPalletId;
+	type RevenuePeriod = RevenuePeriod;
+	type OffsetPeriod = OffsetPeriod;
 	type TreasuryAccount = HonzonTreasuryAccount;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type OnSurplusChanged = ();
+	type OnDebitChanged = ();
+	type OnBadDebtOverflow = module_cdp_engine::PauseDebitIssuance<Runtime>;
+	type CircuitBreakerRecorder = CircuitBreaker;
+	type OnRevenue = CdpInsuranceFund;
+	type YieldStrategy = ();
+	type Call = Call;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MinimumIdleWorkWeight = CdpTreasuryMinimumIdleWorkWeight;
+	type EmergencyShutdown = EmergencyShutdown;
 	type WeightInfo = weights::module_cdp_treasury::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const MaxEmergencyPauseDuration: BlockNumber = DAYS;
+	pub UnpausablePallets: Vec<Vec<u8>> = vec![
+		b"System".to_vec(),
+		b"Timestamp".to_vec(),
+		b"ParachainSystem".to_vec(),
+		b"Sudo".to_vec(),
+		b"Scheduler".to_vec(),
+		b"Authority".to_vec(),
+		b"GeneralCouncil".to_vec(),
+		b"GeneralCouncilMembership".to_vec(),
+		b"FinancialCouncil".to_vec(),
+		b"FinancialCouncilMembership".to_vec(),
+		b"HomaCouncil".to_vec(),
+		b"HomaCouncilMembership".to_vec(),
+		b"TechnicalCommittee".to_vec(),
+		b"TechnicalCommitteeMembership".to_vec(),
+		b"ParameterCouncil".to_vec(),
+		b"ParameterCouncilMembership".to_vec(),
+		b"Democracy".to_vec(),
+	];
+	pub const EmergencyPauseCooldown: BlockNumber = DAYS;
+}
+
+pub struct EmergencyPauseExemptCalls;
+impl Contains<Call> for EmergencyPauseExemptCalls {
+	fn contains(call: &Call) -> bool {
+		// the governance calls are operational
+		call.get_dispatch_info().class == DispatchClass::Operational
+			|| matches!(
+				call,
+				Call::CdpEngine(module_cdp_engine::Call::liquidate { .. })
+					| Call::CdpEngine(module_cdp_engine::Call::settle { .. })
+					| Call::AuctionManager(module_auction_manager::Call::cancel { .. })
+			)
+	}
+}
+
 impl module_transaction_pause::Config for Runtime {
 	type Event = Event;
 	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncil;
+	type EmergencyOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
+	type MaxEmergencyPauseDuration = MaxEmergencyPauseDuration;
+	type UnpausablePallets = UnpausablePallets;
+	type EmergencyPauseCooldown = EmergencyPauseCooldown;
+	type EmergencyPauseExemptCalls = EmergencyPauseExemptCalls;
+	type CircuitBreakerRecorder = CircuitBreaker;
 	type WeightInfo = weights::module_transaction_pause::WeightInfo<Runtime>;
 }
 
@@ -1153,6 +1646,8 @@ impl module_transaction_payment::Config for Runtime {
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type TradingPathLimit = TradingPathLimit;
 	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MaxFeeFreeTxPerBlock = MaxFeeFreeTxPerBlock;
+	type UpdateOrigin = EnsureRootOrHalfGeneralCouncil;
 	type WeightInfo = weights::module_transaction_payment::WeightInfo<Runtime>;
 }
 
@@ -1182,6 +1677,7 @@ impl orml_rewards::Config for Runtime {
 
 parameter_types! {
 	pub const AccumulatePeriod: BlockNumber = MINUTES;
+	pub const MaxAccumulatePoolsPerBlock: u32 = 100;
 }
 
 impl module_incentives::Config for Runtime {
@@ -1189,24 +1685,62 @@ impl module_incentives::Config for Runtime {
 	type RewardsSource = UnreleasedNativeVaultAccountId;
 	type StableCurrencyId = GetStableCurrencyId;
 	type AccumulatePeriod = AccumulatePeriod;
-	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncil;
+	type MaxAccumulatePoolsPerBlock = MaxAccumulatePoolsPerBlock;
+	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncilOrHalfParameterCouncil;
 	type CDPTreasury = CdpTreasury;
 	type Currency = Currencies;
 	type DEX = Dex;
 	type EmergencyShutdown = EmergencyShutdown;
 	type PalletId = IncentivesPalletId;
+	type CleanupBounty = TreasuryCleanupBounty;
 	type WeightInfo = weights::module_incentives::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub VaultRewardCurrencyIds: Vec<CurrencyId> = vec![ACA, AUSD];
+	pub const VaultHarvestPeriod: BlockNumber = HOURS;
+}
+
+impl module_vault::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type DEX = Dex;
+	type DEXIncentives = Incentives;
+	type TokenIncentives = Incentives;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type RewardCurrencyIds = VaultRewardCurrencyIds;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type TreasuryAccount = TreasuryAccount;
+	type HarvestPeriod = VaultHarvestPeriod;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = VaultPalletId;
+	type WeightInfo = weights::module_vault::WeightInfo<Runtime>;
+}
+
 parameter_types! {
 	pub const PolkadotBondingDuration: EraIndex = 7;
 	pub const EraLength: BlockNumber = DAYS;
 	pub const MaxUnbonding: u32 = 1000;
-}
+	pub const NomineesElectionNewEraOrder: u32 = 0;
+	pub const StakingPoolNewEraOrder: u32 = 1;
+	pub NomineesElectionNewEraWeight: Weight = RocksDbWeight::get().reads_writes(3, 2);
+	pub StakingPoolNewEraWeight: Weight = RocksDbWeight::get().writes(2);
+	pub NewEraHookWeightBudget: Weight = RuntimeBlockWeights::get().max_block / 20;
+}
+
+// Nominees must be rebalanced before the staking pool starts its rebalance of the new era.
+pub type OnNewEraHooks = HookRegistry<(
+	OrderedHook<
+		NewEraHook<NomineesElection, NomineesElectionNewEraWeight>,
+		NomineesElectionNewEraOrder,
+		NewEraHookWeightBudget,
+	>,
+	OrderedHook<NewEraHook<StakingPool, StakingPoolNewEraWeight>, StakingPoolNewEraOrder, NewEraHookWeightBudget>,
+)>;
 
 impl module_polkadot_bridge::Config for Runtime {
 	type DOTCurrency = Currency<Runtime, GetStakingCurrencyId>;
-	type OnNewEra = (NomineesElection, StakingPool);
+	type OnNewEra = OnNewEraHooks;
 	type BondingDuration = PolkadotBondingDuration;
 	type EraLength = EraLength;
 	type PolkadotAccountId = AccountId;
@@ -1268,6 +1802,9 @@ parameter_types! {
 	// Calculated from polkadot/xcm/xcm-builder: fn buy_weight
 	// This is a place holder value since XCM is not tested for Mandala yet.
 	pub XcmUnbondFee: Balance = 60 * millicent(DOT);
+	pub const XcmRetryBaseDelay: BlockNumber = 10; // 1 minute in relaychain blocks
+	pub const MaxXcmRetryAttempts: u32 = 5;
+	pub const XcmResponseTimeout: BlockNumber = 10 * MINUTES;
 }
 impl module_homa_lite::Config for Runtime {
 	type Event = Event;
@@ -1293,6 +1830,9 @@ impl module_homa_lite::Config for Runtime {
 	type RelayChainUnbondingSlashingSpans = RelayChainUnbondingSlashingSpans;
 	type MaxScheduledUnbonds = MaxScheduledUnbonds;
 	type StakingUpdateFrequency = OneDay;
+	type XcmRetryBaseDelay = XcmRetryBaseDelay;
+	type MaxXcmRetryAttempts = MaxXcmRetryAttempts;
+	type XcmResponseTimeout = XcmResponseTimeout;
 }
 
 parameter_types! {
@@ -1354,6 +1894,160 @@ impl module_nft::Config for Runtime {
 	type WeightInfo = weights::module_nft::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const PublicGoodsRoundDuration: BlockNumber = 14 * DAYS;
+	pub const MaxPublicGoodsProjects: u32 = 1000;
+	pub const MaxPublicGoodsContributionsCleanup: u32 = 500;
+}
+
+impl module_public_goods::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type PalletId = PublicGoodsPalletId;
+	type RoundDuration = PublicGoodsRoundDuration;
+	type MaxProjects = MaxPublicGoodsProjects;
+	type MaxContributionsCleanup = MaxPublicGoodsContributionsCleanup;
+	type AttestationRegistry = ();
+	type WeightInfo = weights::module_public_goods::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const XcmCollateralConfirmationTimeout: BlockNumber = 2 * HOURS;
+}
+
+impl module_xcm_collateral::Config for Runtime {
+	type Event = Event;
+	type RelayChainCurrencyId = GetStakingCurrencyId;
+	type ConfirmationTimeout = XcmCollateralConfirmationTimeout;
+	type EmergencyShutdown = EmergencyShutdown;
+	type WeightInfo = weights::module_xcm_collateral::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub XcmOnboardingSafetyMargin: Ratio = Ratio::saturating_from_rational(50, 100);
+}
+
+impl module_xcm_onboarding::Config for Runtime {
+	type Event = Event;
+	type RelayChainCurrencyId = GetStakingCurrencyId;
+	type SafetyMargin = XcmOnboardingSafetyMargin;
+	type WeightInfo = weights::module_xcm_onboarding::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const WeightMetricsBlocksPerDay: BlockNumber = DAYS;
+	pub const WeightMetricsKeepDays: u32 = 30;
+}
+
+impl module_weight_metrics::Config for Runtime {
+	type BlocksPerDay = WeightMetricsBlocksPerDay;
+	type KeepDays = WeightMetricsKeepDays;
+	type WeightInfo = weights::module_weight_metrics::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const RandomnessCommitPeriod: BlockNumber = 10 * MINUTES;
+	pub const RandomnessRevealPeriod: BlockNumber = 10 * MINUTES;
+	pub RandomnessCommitDeposit: Balance = 10 * dollar(ACA);
+	pub const RandomnessMaxParticipants: u32 = 100;
+}
+
+impl module_randomness_beacon::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type CommitPeriod = RandomnessCommitPeriod;
+	type RevealPeriod = RandomnessRevealPeriod;
+	type CommitDeposit = RandomnessCommitDeposit;
+	type ExternalRandomness = RelayChainRandomness<Runtime>;
+	type MaxParticipants = RandomnessMaxParticipants;
+	type WeightInfo = weights::module_randomness_beacon::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub OperationsMaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(5, 100);
+	pub const OperationsBlocksPerDay: BlockNumber = DAYS;
+}
+
+impl module_operations::Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type CDPTreasury = CdpTreasury;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MaxSwapSlippageCompareToOracle = OperationsMaxSwapSlippageCompareToOracle;
+	type InsuranceFundAccount = InsuranceFundAccount;
+	type Currency = Currencies;
+	type DEX = Dex;
+	type TreasuryAccount = TreasuryAccount;
+	type BlocksPerDay = OperationsBlocksPerDay;
+	type TradingPathLimit = TradingPathLimit;
+	type WeightInfo = weights::module_operations::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const CircuitBreakerMaxHistoryRecords: u32 = 100;
+	pub const CircuitBreakerMaxAutoUnpauseChecksPerBlock: u32 = 20;
+}
+
+impl module_circuit_breaker::Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureRootOrThreeFourthsGeneralCouncil;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type Unpause = (TransactionPause, CdpEngine, CdpTreasury);
+	type MaxHistoryRecords = CircuitBreakerMaxHistoryRecords;
+	type MaxAutoUnpauseChecksPerBlock = CircuitBreakerMaxAutoUnpauseChecksPerBlock;
+	type WeightInfo = weights::module_circuit_breaker::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const ProofOfReserveReportPeriod: BlockNumber = 10 * MINUTES;
+	pub const ProofOfReserveDigestHistoryDepth: BlockNumber = 7 * DAYS;
+	pub const ProofOfReserveMaxReporters: u32 = 10;
+}
+
+impl module_proof_of_reserve::Config for Runtime {
+	type Event = Event;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type ReportPeriod = ProofOfReserveReportPeriod;
+	type DigestHistoryDepth = ProofOfReserveDigestHistoryDepth;
+	type MaxReporters = ProofOfReserveMaxReporters;
+	type UnsignedPriority = runtime_common::ProofOfReserveUnsignedPriority;
+	type UpdateOrigin = EnsureRootOrHalfGeneralCouncil;
+	type WeightInfo = weights::module_proof_of_reserve::WeightInfo<Runtime>;
+}
+
+pub struct SystemAccounts;
+impl Contains<AccountId> for SystemAccounts {
+	fn contains(a: &AccountId) -> bool {
+		get_all_module_accounts().contains(a)
+	}
+}
+
+parameter_types! {
+	pub const StablecoinMetricsWindowSize: u32 = DAYS;
+}
+
+impl module_stablecoin_metrics::Config for Runtime {
+	type GetStableCurrencyId = GetStableCurrencyId;
+	type SystemAccounts = SystemAccounts;
+	type WindowSize = StablecoinMetricsWindowSize;
+	type WeightInfo = weights::module_stablecoin_metrics::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const ComposerMaxSteps: u32 = 5;
+}
+
+impl module_composer::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type DEX = Dex;
+	type DEXIncentives = Incentives;
+	type MaxSteps = ComposerMaxSteps;
+	type WeightInfo = weights::module_composer::WeightInfo<Runtime>;
+}
+
 parameter_types! {
 	pub MaxClassMetadata: u32 = 1024;
 	pub MaxTokenMetadata: u32 = 1024;
@@ -1396,6 +2090,7 @@ impl InstanceFilter<Call> for ProxyType {
 						| Call::GeneralCouncil(..)
 						| Call::FinancialCouncil(..)
 						| Call::HomaCouncil(..) | Call::TechnicalCommittee(..)
+						| Call::ParameterCouncil(..)
 						| Call::Treasury(..) | Call::Bounties(..)
 						| Call::Tips(..)
 				)
@@ -1530,8 +2225,28 @@ pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<
 	OriginCaller,
 	Runtime,
 >;
-pub type DexPrecompile =
-	runtime_common::DexPrecompile<AccountId, EvmAddressMapping<Runtime>, EvmErc20InfoMapping<Runtime>, Dex>;
+pub type DexPrecompile = runtime_common::DexPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	Dex,
+	weights::module_dex::WeightInfo<Runtime>,
+>;
+pub type HomaPrecompile = runtime_common::HomaPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	HomaLite,
+	weights::module_homa_lite::WeightInfo<Runtime>,
+>;
+pub type FlashLoanPrecompile = runtime_common::FlashLoanPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmErc20InfoMapping<Runtime>,
+	CdpTreasury,
+	EVM,
+	weights::module_cdp_treasury::WeightInfo<Runtime>,
+>;
 
 #[cfg(feature = "with-ethereum-compatibility")]
 static ISTANBUL_CONFIG: module_evm_utiltity::evm::Config = module_evm_utiltity::evm::Config::istanbul();
@@ -1545,12 +2260,15 @@ impl module_evm::Config for Runtime {
 	type Event = Event;
 	type Precompiles = runtime_common::AllPrecompiles<
 		SystemContractsFilter,
+		TransactionPause,
 		MultiCurrencyPrecompile,
 		NFTPrecompile,
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		HomaPrecompile,
+		FlashLoanPrecompile,
 	>;
 	type ChainId = ChainId;
 	type GasToWeight = GasToWeight;
@@ -1679,14 +2397,19 @@ impl xcm_executor::Config for XcmConfig {
 	type Call = Call;
 	type XcmSender = XcmRouter;
 	// How to withdraw and deposit an asset.
-	type AssetTransactor = LocalAssetTransactor;
+	type AssetTransactor = module_xcm_onboarding::OnboardingTransactor<
+		Runtime,
+		module_xcm_collateral::CollateralDepositTransactor<Runtime, LocalAssetTransactor, LocationToAccountId>,
+		LocationToAccountId,
+	>;
 	type OriginConverter = XcmOriginToCallOrigin;
 	type IsReserve = MultiNativeAsset;
 	// Teleporting is disabled.
 	type IsTeleporter = ();
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+	type Weigher =
+		module_xcm_onboarding::OnboardingWeigher<Runtime, FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>>;
 	// Only receiving DOT is handled, and all fees must be paid in DOT.
 	type Trader = FixedRateOfFungible<DotPerSecond, ToTreasury>;
 	type ResponseHandler = (); // Don't handle responses for now.
@@ -1914,6 +2637,72 @@ type RebaseTokens = orml_tokens::Combiner<
 	Tokens,
 >;
 
+/// Adapt the stable asset pallet to `StableAssetPool`, the amounts returned are
+/// measured by the balance changes of the caller.
+pub struct StableAssetAdapter;
+impl StableAssetPool<AccountId, CurrencyId, Balance> for StableAssetAdapter {
+	fn get_pool_assets(pool_id: StableAssetPoolId) -> Option<Vec<CurrencyId>> {
+		StableAsset::pools(pool_id).map(|pool_info| pool_info.assets)
+	}
+
+	fn mint(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amounts: Vec<Balance>,
+		min_mint_amount: Balance,
+	) -> Result<Balance, sp_runtime::DispatchError> {
+		let pool_asset = StableAsset::pools(pool_id)
+			.ok_or(sp_runtime::DispatchError::Other("stable asset pool not found"))?
+			.pool_asset;
+		let balance_before = Currencies::free_balance(pool_asset, who);
+		StableAsset::mint(Origin::signed(who.clone()), pool_id, amounts, min_mint_amount)?;
+		Ok(Currencies::free_balance(pool_asset, who).saturating_sub(balance_before))
+	}
+
+	fn redeem_proportion(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		amount: Balance,
+		min_redeem_amounts: Vec<Balance>,
+	) -> Result<Vec<Balance>, sp_runtime::DispatchError> {
+		let assets =
+			Self::get_pool_assets(pool_id).ok_or(sp_runtime::DispatchError::Other("stable asset pool not found"))?;
+		let balances_before: Vec<Balance> = assets
+			.iter()
+			.map(|currency_id| Currencies::free_balance(*currency_id, who))
+			.collect();
+		StableAsset::redeem_proportion(Origin::signed(who.clone()), pool_id, amount, min_redeem_amounts)?;
+		Ok(assets
+			.iter()
+			.zip(balances_before)
+			.map(|(currency_id, balance_before)| {
+				Currencies::free_balance(*currency_id, who).saturating_sub(balance_before)
+			})
+			.collect())
+	}
+
+	fn swap(
+		who: &AccountId,
+		pool_id: StableAssetPoolId,
+		i: u32,
+		j: u32,
+		dx: Balance,
+		min_dy: Balance,
+	) -> Result<Balance, sp_runtime::DispatchError> {
+		let target_currency_id = Self::get_pool_assets(pool_id)
+			.and_then(|assets| assets.get(j as usize).copied())
+			.ok_or(sp_runtime::DispatchError::Other("stable asset pool not found"))?;
+		let balance_before = Currencies::free_balance(target_currency_id, who);
+		StableAsset::swap(Origin::signed(who.clone()), pool_id, i, j, dx, min_dy)?;
+		Ok(Currencies::free_balance(target_currency_id, who).saturating_sub(balance_before))
+	}
+
+	fn get_swap_output_amount(pool_id: StableAssetPoolId, i: u32, j: u32, dx: Balance) -> Option<Balance> {
+		<StableAsset as nutsfinance_stable_asset::traits::StableAsset>::get_swap_output_amount(pool_id, i, j, dx)
+			.map(|swap_result| swap_result.dy)
+	}
+}
+
 impl nutsfinance_stable_asset::Config for Runtime {
 	type Event = Event;
 	type AssetId = CurrencyId;
@@ -2016,6 +2805,7 @@ pub type SignedExtra = (
 	frame_system::CheckWeight<Runtime>,
 	module_transaction_payment::ChargeTransactionPayment<Runtime>,
 	module_evm::SetEvmOrigin<Runtime>,
+	module_weight_metrics::RecordWeightMetrics<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = AcalaUncheckedExtrinsic<Call, SignedExtra, ConvertEthereumTx>;
@@ -2070,6 +2860,8 @@ construct_runtime! {
 		HomaCouncilMembership: pallet_membership::<Instance3>::{Pallet, Call, Storage, Event<T>, Config<T>} = 55,
 		TechnicalCommittee: pallet_collective::<Instance4>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 56,
 		TechnicalCommitteeMembership: pallet_membership::<Instance4>::{Pallet, Call, Storage, Event<T>, Config<T>} = 57,
+		ParameterCouncil: pallet_collective::<Instance5>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 58,
+		ParameterCouncilMembership: pallet_membership::<Instance6>::{Pallet, Call, Storage, Event<T>, Config<T>} = 59,
 
 		Authority: orml_authority::{Pallet, Call, Storage, Event<T>, Origin<T>} = 70,
 		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>} = 71,
@@ -2087,16 +2879,28 @@ construct_runtime! {
 		OrmlNFT: orml_nft::{Pallet, Storage, Config<T>} = 102,
 
 		// Acala Core
-		Prices: module_prices::{Pallet, Storage, Call, Event<T>} = 110,
+		Prices: module_prices::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 110,
 		Dex: module_dex::{Pallet, Storage, Call, Event<T>, Config<T>} = 111,
+		RandomnessBeacon: module_randomness_beacon::{Pallet, Storage, Call, Event<T>} = 112,
+		Operations: module_operations::{Pallet, Storage, Call, Event<T>} = 113,
+		CircuitBreaker: module_circuit_breaker::{Pallet, Storage, Call, Event<T>} = 114,
+		ProofOfReserve: module_proof_of_reserve::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 115,
+		Dca: module_dca::{Pallet, Storage, Call, Event<T>} = 116,
+		Keeper: module_keeper::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 117,
 
 		// Honzon
+		Savings: module_savings::{Pallet, Storage, Call, Event<T>} = 118,
+		CdpInsuranceFund: module_cdp_insurance_fund::{Pallet, Storage, Call, Event<T>} = 119,
 		AuctionManager: module_auction_manager::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 120,
 		Loans: module_loans::{Pallet, Storage, Call, Event<T>} = 121,
 		Honzon: module_honzon::{Pallet, Storage, Call, Event<T>} = 122,
 		CdpTreasury: module_cdp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 123,
 		CdpEngine: module_cdp_engine::{Pallet, Storage, Call, Event<T>, Config, ValidateUnsigned} = 124,
 		EmergencyShutdown: module_emergency_shutdown::{Pallet, Storage, Call, Event<T>} = 125,
+		PegMonitor: module_peg_monitor::{Pallet, Storage, Call, Event<T>} = 126,
+		CdpNft: module_cdp_nft::{Pallet, Storage, Call, Event<T>} = 127,
+		DebitBackstop: module_debit_backstop::{Pallet, Storage, Call, Event<T>} = 128,
+		PositionMigration: module_position_migration::{Pallet, Storage, Call, Event<T>} = 129,
 
 		// Homa
 		Homa: module_homa::{Pallet, Call} = 130,
@@ -2107,9 +2911,18 @@ construct_runtime! {
 		HomaLite: module_homa_lite::{Pallet, Call, Storage, Event<T>} = 135,
 
 		// Acala Other
+		PSM: module_psm::{Pallet, Storage, Call, Event<T>} = 138,
+		Vault: module_vault::{Pallet, Storage, Call, Event<T>} = 139,
 		Incentives: module_incentives::{Pallet, Storage, Call, Event<T>} = 140,
 		NFT: module_nft::{Pallet, Call, Event<T>} = 141,
 		AssetRegistry: module_asset_registry::{Pallet, Call, Storage, Event<T>} = 142,
+		PublicGoods: module_public_goods::{Pallet, Call, Storage, Event<T>} = 143,
+		XcmCollateral: module_xcm_collateral::{Pallet, Call, Storage, Event<T>} = 144,
+		StablecoinMetrics: module_stablecoin_metrics::{Pallet, Storage} = 145,
+		Composer: module_composer::{Pallet, Call, Event<T>} = 146,
+		LiquidationInsurance: module_liquidation_insurance::{Pallet, Storage, Call, Event<T>} = 147,
+		XcmOnboarding: module_xcm_onboarding::{Pallet, Storage, Call, Event<T>} = 148,
+		WeightMetrics: module_weight_metrics::{Pallet, Storage} = 149,
 
 		// Ecosystem modules
 		RenVmBridge: ecosystem_renvm_bridge::{Pallet, Call, Config, Storage, Event<T>, ValidateUnsigned} = 150,
@@ -2146,6 +2959,7 @@ construct_runtime! {
 		StableAsset: nutsfinance_stable_asset::{Pallet, Call, Storage, Event<T>} = 200,
 
 		// Dev
+		Sandbox: module_sandbox::{Pallet, Call, Storage, Event<T>} = 254,
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 255,
 	}
 }
@@ -2286,6 +3100,128 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_common_rpc_runtime_api::ConfigApi<Block, Balance, BlockNumber> for Runtime {
+		fn config_constants() -> Vec<(Vec<u8>, runtime_common_rpc_runtime_api::ConfigValue<Balance, BlockNumber>)> {
+			runtime_common::config_constants::<Runtime>()
+		}
+	}
+
+	impl runtime_common_rpc_runtime_api::GovernanceApi<Block, Call, Event, module_cdp_engine::RiskManagementParams>
+		for Runtime
+	{
+		fn dry_run_proposal(
+			proposal: Call,
+		) -> runtime_common_rpc_runtime_api::ProposalDryRun<Event, module_cdp_engine::RiskManagementParams> {
+			dry_run_proposal(proposal)
+		}
+	}
+
+	impl module_cdp_engine_rpc_runtime_api::CdpEngineApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn debit_exchange_rate_checkpoints(currency_id: CurrencyId) -> Vec<(BlockNumber, ExchangeRate)> {
+			CdpEngine::debit_exchange_rate_checkpoints(currency_id).into_inner()
+		}
+
+		fn accrued_interest(currency_id: CurrencyId, who: AccountId, from: BlockNumber, to: BlockNumber) -> Option<Balance> {
+			CdpEngine::accrued_interest(currency_id, &who, from, to)
+		}
+
+		fn curve_interest_rate_per_sec(currency_id: CurrencyId) -> Option<Rate> {
+			CdpEngine::curve_interest_rate_per_sec(currency_id)
+		}
+	}
+
+	impl module_cdp_treasury_rpc_runtime_api::CdpTreasuryApi<Block, Balance> for Runtime {
+		fn revenue_report(periods: u32) -> Vec<(u32, Vec<(RevenueSource, Balance)>)> {
+			CdpTreasury::revenue_report(periods)
+		}
+
+		fn surplus_by_source() -> Vec<(CurrencyId, Balance)> {
+			CdpTreasury::surplus_report()
+		}
+
+		fn treasury_health() -> module_cdp_treasury_rpc_runtime_api::TreasuryHealth<Balance> {
+			module_cdp_treasury_rpc_runtime_api::TreasuryHealth {
+				surplus_pool: CdpTreasury::surplus_pool(),
+				debit_pool: CdpTreasury::debit_pool(),
+				collaterals: CdpTreasury::collateral_report(CollateralCurrencyIds::get()),
+				reserve_ratio: CdpTreasury::reserve_ratio(),
+			}
+		}
+	}
+
+	impl module_honzon_rpc_runtime_api::HonzonApi<Block, AccountId, Balance> for Runtime {
+		fn position_health(currency_id: CurrencyId, who: AccountId) -> Option<module_honzon_rpc_runtime_api::PositionHealth<Balance>> {
+			Honzon::position_health(currency_id, &who).map(
+				|(collateral_ratio, required_collateral_ratio, max_additional_debit_value, liquidation_price)| {
+					module_honzon_rpc_runtime_api::PositionHealth {
+						collateral_ratio,
+						required_collateral_ratio,
+						max_additional_debit_value,
+						liquidation_price,
+					}
+				},
+			)
+		}
+	}
+
+	impl module_stablecoin_metrics_rpc_runtime_api::StablecoinMetricsApi<Block, BlockNumber, Balance> for Runtime {
+		fn velocity_report(blocks: u32) -> Vec<(BlockNumber, u32, Balance)> {
+			StablecoinMetrics::velocity_report(blocks)
+		}
+	}
+
+	impl module_weight_metrics_rpc_runtime_api::WeightMetricsApi<Block> for Runtime {
+		fn weight_report(days: u32) -> Vec<(u32, Vec<u8>, u32, u64)> {
+			WeightMetrics::weight_report(days)
+		}
+	}
+
+	impl module_incentives_rpc_runtime_api::IncentivesApi<Block> for Runtime {
+		fn accumulation_cursor() -> Option<Vec<u8>> {
+			Incentives::accumulation_cursor()
+		}
+	}
+
+	impl module_circuit_breaker_rpc_runtime_api::CircuitBreakerApi<Block, BlockNumber> for Runtime {
+		fn active_breakers() -> Vec<(CircuitBreakerId, CircuitBreakerReason, BlockNumber, bool)> {
+			module_circuit_breaker::ActiveBreakers::<Runtime>::iter()
+				.map(|(id, state)| (id, state.reason, state.paused_at, state.confirmed))
+				.collect()
+		}
+
+		fn breaker_history() -> Vec<(CircuitBreakerId, CircuitBreakerReason, BlockNumber, BlockNumber)> {
+			CircuitBreaker::breaker_history()
+				.into_iter()
+				.map(|record| (record.id, record.reason, record.paused_at, record.unpaused_at))
+				.collect()
+		}
+	}
+
+	impl module_proof_of_reserve_rpc_runtime_api::ProofOfReserveApi<Block, AccountId, BlockNumber, Hash> for Runtime {
+		fn reserve_root() -> (Hash, u32) {
+			let digest = ProofOfReserve::reserve_digest(System::block_number());
+			(digest.root, digest.leaf_count)
+		}
+
+		fn recorded_digest(block_number: BlockNumber) -> Option<(Hash, u32)> {
+			ProofOfReserve::reserve_digests(block_number).map(|(digest, _, _)| (digest.root, digest.leaf_count))
+		}
+
+		fn position_proof(
+			encoded_leaves: Vec<u8>,
+			currency_id: CurrencyId,
+			who: AccountId,
+		) -> Option<(Balance, Balance, Vec<Hash>, u32, u32)> {
+			let leaves = Vec::<module_proof_of_reserve::ReserveLeaf<AccountId>>::decode(&mut &encoded_leaves[..]).ok()?;
+			match ProofOfReserve::position_proof(&leaves, currency_id, &who)? {
+				(module_proof_of_reserve::ReserveLeaf::Position { collateral, debit, .. }, proof, index, leaf_count) => {
+					Some((collateral, debit, proof, index, leaf_count))
+				}
+				_ => None,
+			}
+		}
+	}
+
 	impl module_evm_rpc_runtime_api::EVMRuntimeRPCApi<Block, Balance> for Runtime {
 		fn call(
 			from: H160,
@@ -2378,6 +3314,10 @@ impl_runtime_apis! {
 
 			request.ok_or(sp_runtime::DispatchError::Other("Invalid parameter extrinsic, not evm Call"))
 		}
+
+		fn block_logs(filter: LogFilter) -> Vec<BlockLog> {
+			EVM::block_logs(&filter)
+		}
 	}
 
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
@@ -2434,10 +3374,32 @@ impl_runtime_apis! {
 			orml_list_benchmark!(list, extra, module_transaction_payment, benchmarking::transaction_payment);
 			orml_list_benchmark!(list, extra, module_incentives, benchmarking::incentives);
 			orml_list_benchmark!(list, extra, module_prices, benchmarking::prices);
+			orml_list_benchmark!(list, extra, module_proof_of_reserve, benchmarking::proof_of_reserve);
 			orml_list_benchmark!(list, extra, module_evm_accounts, benchmarking::evm_accounts);
 			orml_list_benchmark!(list, extra, module_homa, benchmarking::homa);
 			orml_list_benchmark!(list, extra, module_currencies, benchmarking::currencies);
 			orml_list_benchmark!(list, extra, module_session_manager, benchmarking::session_manager);
+			orml_list_benchmark!(list, extra, module_cdp_insurance_fund, benchmarking::cdp_insurance_fund);
+			orml_list_benchmark!(list, extra, module_cdp_nft, benchmarking::cdp_nft);
+			orml_list_benchmark!(list, extra, module_circuit_breaker, benchmarking::circuit_breaker);
+			orml_list_benchmark!(list, extra, module_composer, benchmarking::composer);
+			orml_list_benchmark!(list, extra, module_dca, benchmarking::dca);
+			orml_list_benchmark!(list, extra, module_debit_backstop, benchmarking::debit_backstop);
+			orml_list_benchmark!(list, extra, module_keeper, benchmarking::keeper);
+			orml_list_benchmark!(list, extra, module_liquidation_insurance, benchmarking::liquidation_insurance);
+			orml_list_benchmark!(list, extra, module_operations, benchmarking::operations);
+			orml_list_benchmark!(list, extra, module_peg_monitor, benchmarking::peg_monitor);
+			orml_list_benchmark!(list, extra, module_position_migration, benchmarking::position_migration);
+			orml_list_benchmark!(list, extra, module_psm, benchmarking::psm);
+			orml_list_benchmark!(list, extra, module_public_goods, benchmarking::public_goods);
+			orml_list_benchmark!(list, extra, module_randomness_beacon, benchmarking::randomness_beacon);
+			orml_list_benchmark!(list, extra, module_sandbox, benchmarking::sandbox);
+			orml_list_benchmark!(list, extra, module_savings, benchmarking::savings);
+			orml_list_benchmark!(list, extra, module_stablecoin_metrics, benchmarking::stablecoin_metrics);
+			orml_list_benchmark!(list, extra, module_vault, benchmarking::vault);
+			orml_list_benchmark!(list, extra, module_weight_metrics, benchmarking::weight_metrics);
+			orml_list_benchmark!(list, extra, module_xcm_collateral, benchmarking::xcm_collateral);
+			orml_list_benchmark!(list, extra, module_xcm_onboarding, benchmarking::xcm_onboarding);
 
 			orml_list_benchmark!(list, extra, orml_tokens, benchmarking::tokens);
 			orml_list_benchmark!(list, extra, orml_vesting, benchmarking::vesting);
@@ -2497,10 +3459,32 @@ impl_runtime_apis! {
 			orml_add_benchmark!(params, batches, module_transaction_payment, benchmarking::transaction_payment);
 			orml_add_benchmark!(params, batches, module_incentives, benchmarking::incentives);
 			orml_add_benchmark!(params, batches, module_prices, benchmarking::prices);
+			orml_add_benchmark!(params, batches, module_proof_of_reserve, benchmarking::proof_of_reserve);
 			orml_add_benchmark!(params, batches, module_evm_accounts, benchmarking::evm_accounts);
 			orml_add_benchmark!(params, batches, module_homa, benchmarking::homa);
 			orml_add_benchmark!(params, batches, module_currencies, benchmarking::currencies);
 			orml_add_benchmark!(params, batches, module_session_manager, benchmarking::session_manager);
+			orml_add_benchmark!(params, batches, module_cdp_insurance_fund, benchmarking::cdp_insurance_fund);
+			orml_add_benchmark!(params, batches, module_cdp_nft, benchmarking::cdp_nft);
+			orml_add_benchmark!(params, batches, module_circuit_breaker, benchmarking::circuit_breaker);
+			orml_add_benchmark!(params, batches, module_composer, benchmarking::composer);
+			orml_add_benchmark!(params, batches, module_dca, benchmarking::dca);
+			orml_add_benchmark!(params, batches, module_debit_backstop, benchmarking::debit_backstop);
+			orml_add_benchmark!(params, batches, module_keeper, benchmarking::keeper);
+			orml_add_benchmark!(params, batches, module_liquidation_insurance, benchmarking::liquidation_insurance);
+			orml_add_benchmark!(params, batches, module_operations, benchmarking::operations);
+			orml_add_benchmark!(params, batches, module_peg_monitor, benchmarking::peg_monitor);
+			orml_add_benchmark!(params, batches, module_position_migration, benchmarking::position_migration);
+			orml_add_benchmark!(params, batches, module_psm, benchmarking::psm);
+			orml_add_benchmark!(params, batches, module_public_goods, benchmarking::public_goods);
+			orml_add_benchmark!(params, batches, module_randomness_beacon, benchmarking::randomness_beacon);
+			orml_add_benchmark!(params, batches, module_sandbox, benchmarking::sandbox);
+			orml_add_benchmark!(params, batches, module_savings, benchmarking::savings);
+			orml_add_benchmark!(params, batches, module_stablecoin_metrics, benchmarking::stablecoin_metrics);
+			orml_add_benchmark!(params, batches, module_vault, benchmarking::vault);
+			orml_add_benchmark!(params, batches, module_weight_metrics, benchmarking::weight_metrics);
+			orml_add_benchmark!(params, batches, module_xcm_collateral, benchmarking::xcm_collateral);
+			orml_add_benchmark!(params, batches, module_xcm_onboarding, benchmarking::xcm_onboarding);
 
 			orml_add_benchmark!(params, batches, orml_tokens, benchmarking::tokens);
 			orml_add_benchmark!(params, batches, orml_vesting, benchmarking::vesting);
@@ -2546,10 +3530,80 @@ cumulus_pallet_parachain_system::register_validate_block!(
 	CheckInherents = CheckInherents,
 );
 
+/// Dispatch the proposal by the origin of the unanimous financial council in a
+/// transaction that is always rolled back, the call filter and the timelock of
+/// the risk params apply as they do to a real council proposal.
+pub fn dry_run_proposal(
+	proposal: Call,
+) -> runtime_common_rpc_runtime_api::ProposalDryRun<Event, module_cdp_engine::RiskManagementParams> {
+	use sp_runtime::traits::Dispatchable;
+
+	let collateral_currency_ids = CollateralCurrencyIds::get();
+	frame_support::storage::with_transaction(|| {
+		System::reset_events();
+		let risk_params_before: Vec<module_cdp_engine::RiskManagementParams> = collateral_currency_ids
+			.iter()
+			.map(|currency_id| CdpEngine::collateral_params(currency_id))
+			.collect();
+		let unsafe_cdps_before: Vec<u32> = collateral_currency_ids
+			.iter()
+			.map(|currency_id| CdpEngine::unsafe_cdp_count(*currency_id))
+			.collect();
+		let pending_risk_params_before: Vec<_> = collateral_currency_ids
+			.iter()
+			.map(|currency_id| CdpEngine::pending_risk_params(currency_id))
+			.collect();
+		let storage_root_before = sp_io::storage::root();
+
+		let members = (FinancialCouncil::members().len() as u32).max(1);
+		let origin: Origin =
+			pallet_collective::RawOrigin::<AccountId, FinancialCouncilInstance>::Members(members, members).into();
+		let result = proposal.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+
+		let events = System::events().into_iter().map(|record| record.event).collect();
+		let storage_root_after = sp_io::storage::root();
+
+		// the risk params scheduled by the proposal take effect after the timelock,
+		// apply them to show the params and the unsafe CDPs under them
+		for (currency_id, pending_before) in collateral_currency_ids.iter().zip(pending_risk_params_before) {
+			if CdpEngine::pending_risk_params(currency_id) != pending_before {
+				CdpEngine::apply_pending_risk_params_now(*currency_id);
+			}
+		}
+
+		let risk_params = collateral_currency_ids
+			.iter()
+			.zip(risk_params_before)
+			.filter_map(|(currency_id, before)| {
+				let after = CdpEngine::collateral_params(currency_id);
+				if before != after {
+					Some((*currency_id, before, after))
+				} else {
+					None
+				}
+			})
+			.collect();
+		let unsafe_cdps = collateral_currency_ids
+			.iter()
+			.zip(unsafe_cdps_before)
+			.map(|(currency_id, before)| (*currency_id, before, CdpEngine::unsafe_cdp_count(*currency_id)))
+			.collect();
+
+		TransactionOutcome::Rollback(runtime_common_rpc_runtime_api::ProposalDryRun {
+			result,
+			events,
+			storage_root: (storage_root_before, storage_root_after),
+			risk_params,
+			unsafe_cdps,
+		})
+	})
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use frame_system::offchain::CreateSignedTransaction;
+	use orml_traits::Change;
 
 	#[test]
 	fn validate_transaction_submitter_bounds() {
@@ -2585,6 +3639,98 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn parameter_council_call_filter_works() {
+		let whitelisted_call = Call::CdpTreasury(module_cdp_treasury::Call::set_expected_collateral_auction_size {
+			currency_id: DOT,
+			size: 100,
+		});
+		let other_call = Call::CdpTreasury(module_cdp_treasury::Call::burn_surplus { amount: 100 });
+
+		assert!(BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::propose {
+				threshold: 1,
+				proposal: Box::new(whitelisted_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(whitelisted_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(!BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::propose {
+				threshold: 1,
+				proposal: Box::new(other_call.clone()),
+				length_bound: 100,
+			}
+		)));
+		assert!(!BaseCallFilter::contains(&Call::ParameterCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(other_call.clone()),
+				length_bound: 100,
+			}
+		)));
+
+		// the other collectives are not limited
+		assert!(BaseCallFilter::contains(&Call::FinancialCouncil(
+			pallet_collective::Call::execute {
+				proposal: Box::new(other_call),
+				length_bound: 100,
+			}
+		)));
+	}
+
+	#[test]
+	fn dry_run_proposal_works() {
+		sp_io::TestExternalities::new_empty().execute_with(|| {
+			System::set_block_number(1);
+
+			// dispatched by the council, and rolled back
+			let dry_run = dry_run_proposal(Call::CdpEngine(module_cdp_engine::Call::set_max_debit_per_account {
+				currency_id: DOT,
+				max_debit_per_account: Some(100),
+			}));
+			assert_eq!(dry_run.result, Ok(()));
+			assert_eq!(
+				dry_run.events,
+				vec![Event::CdpEngine(module_cdp_engine::Event::MaxDebitPerAccountUpdated(
+					DOT,
+					Some(100)
+				))]
+			);
+			assert_ne!(dry_run.storage_root.0, dry_run.storage_root.1);
+			assert_eq!(CdpEngine::max_debit_per_account(DOT), None);
+			assert!(System::events().is_empty());
+
+			// the risk params scheduled by the timelock are shown as applied
+			let dry_run = dry_run_proposal(Call::CdpEngine(module_cdp_engine::Call::set_collateral_params {
+				currency_id: DOT,
+				interest_rate_per_sec: Change::NoChange,
+				liquidation_ratio: Change::NewValue(Some(Ratio::saturating_from_rational(2, 1))),
+				liquidation_penalty: Change::NoChange,
+				required_collateral_ratio: Change::NoChange,
+				maximum_total_debit_value: Change::NoChange,
+			}));
+			assert_eq!(dry_run.result, Ok(()));
+			let before = CdpEngine::collateral_params(DOT);
+			let after = module_cdp_engine::RiskManagementParams {
+				liquidation_ratio: Some(Ratio::saturating_from_rational(2, 1)),
+				..before.clone()
+			};
+			assert_eq!(dry_run.risk_params, vec![(DOT, before, after)]);
+			assert!(dry_run.unsafe_cdps.iter().any(|(currency_id, _, _)| *currency_id == DOT));
+			assert!(CdpEngine::pending_risk_params(DOT).is_none());
+			assert_eq!(CdpEngine::collateral_params(DOT).liquidation_ratio, None);
+
+			// the calls requiring root are rejected
+			let dry_run = dry_run_proposal(Call::System(frame_system::Call::set_heap_pages { pages: 1 }));
+			assert_eq!(dry_run.result, Err(sp_runtime::traits::BadOrigin.into()));
+		});
+	}
+
 	#[test]
 	fn check_call_size() {
 		assert!(
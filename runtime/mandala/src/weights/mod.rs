@@ -22,9 +22,15 @@
 pub mod module_asset_registry;
 pub mod module_auction_manager;
 pub mod module_cdp_engine;
+pub mod module_cdp_insurance_fund;
+pub mod module_cdp_nft;
 pub mod module_cdp_treasury;
+pub mod module_circuit_breaker;
 pub mod module_collator_selection;
+pub mod module_composer;
 pub mod module_currencies;
+pub mod module_dca;
+pub mod module_debit_backstop;
 pub mod module_dex;
 pub mod module_emergency_shutdown;
 pub mod module_evm;
@@ -33,12 +39,28 @@ pub mod module_homa;
 pub mod module_homa_lite;
 pub mod module_honzon;
 pub mod module_incentives;
+pub mod module_keeper;
+pub mod module_liquidation_insurance;
 pub mod module_nft;
 pub mod module_nominees_election;
+pub mod module_operations;
+pub mod module_peg_monitor;
+pub mod module_position_migration;
 pub mod module_prices;
+pub mod module_proof_of_reserve;
+pub mod module_psm;
+pub mod module_public_goods;
+pub mod module_randomness_beacon;
+pub mod module_sandbox;
+pub mod module_savings;
 pub mod module_session_manager;
+pub mod module_stablecoin_metrics;
 pub mod module_transaction_pause;
 pub mod module_transaction_payment;
+pub mod module_vault;
+pub mod module_weight_metrics;
+pub mod module_xcm_collateral;
+pub mod module_xcm_onboarding;
 
 pub mod orml_auction;
 pub mod orml_authority;